@@ -0,0 +1,134 @@
+//! 前端的模糊测试：任意输入依次经过词法分析、语法分析、语义分析与 IR 生成，出错时只应
+//! 报告诊断，不应 panic 或耗尽内存。
+//!
+//! 除了 libFuzzer 逐字节的变异，还以记号为单位插入、删除、替换、复制与交换。这样变异的
+//! 结果大多仍能通过词法分析，甚至语法分析，更容易触及语义分析与 IR 生成中的错误处理。
+
+#![no_main]
+
+use libfuzzer_sys::{fuzz_mutator, fuzz_target, fuzzer_mutate};
+
+/// 插入与替换时选用的记号
+#[rustfmt::skip]
+const VOCABULARY: &[&str] = &[
+  // 关键字
+  "asm", "break", "const", "continue", "else", "if", "int", "return", "void", "while",
+  // 标识符，含运行时库函数
+  "main", "x", "y", "a", "f", "getint", "putint", "putch", "getarray", "__builtin_expect",
+  // 字面量，含各种进制与边界值
+  "0", "1", "2", "-1", "010", "0x7fffffff", "2147483647", "2147483648", "4294967295",
+  "'a'", "'\\n'", "\"r\"", "\"=r\"", "\"%0\"",
+  // 标点
+  "(", ")", "{", "}", "[", "]", ";", ",", ":", "=", "==", "!=", "<", "<=", ">", ">=", "+", "-",
+  "*", "/", "%", "!", "&", "&&", "||",
+  // 注释与换行
+  "/*", "*/", "//", "\n",
+];
+
+/// 每次变异至多做这么多次记号操作
+const MAX_EDITS: usize = 4;
+
+fuzz_target!(|data: &[u8]| {
+  let source = String::from_utf8_lossy(data);
+  let _ = sysyc::diagnose(&source);
+  let _ = sysyc::compile_to_koopa(&source, &Default::default());
+});
+
+fuzz_mutator!(|data: &mut [u8], size: usize, max_size: usize, seed: u32| {
+  let mut rng = Rng(u64::from(seed) | 1 << 32);
+  // 一部分变异仍交给 libFuzzer，以便产生新的字符与记号
+  if rng.below(4) == 0 {
+    return fuzzer_mutate(data, size, max_size);
+  }
+  let Ok(text) = std::str::from_utf8(&data[..size]) else {
+    return fuzzer_mutate(data, size, max_size);
+  };
+  let mut tokens = split(text);
+  for _ in 0..1 + rng.below(MAX_EDITS) {
+    edit(&mut tokens, &mut rng);
+  }
+  let mutated = tokens.concat();
+  if mutated.len() > max_size {
+    return fuzzer_mutate(data, size, max_size);
+  }
+  data[..mutated.len()].copy_from_slice(mutated.as_bytes());
+  mutated.len()
+});
+
+/// xorshift 伪随机数，由 libFuzzer 给出的种子决定，使变异可以重现
+struct Rng(u64);
+
+impl Rng {
+  fn next(&mut self) -> u64 {
+    self.0 ^= self.0 << 13;
+    self.0 ^= self.0 >> 7;
+    self.0 ^= self.0 << 17;
+    self.0
+  }
+
+  /// `[0, n)` 中的随机数，`n` 为零时为零
+  fn below(&mut self, n: usize) -> usize {
+    match n {
+      0 => 0,
+      _ => (self.next() % n as u64) as usize,
+    }
+  }
+
+  fn word(&mut self) -> &'static str {
+    VOCABULARY[self.below(VOCABULARY.len())]
+  }
+}
+
+/// 粗略地切分记号：标识符与数字、空白、字符串字面量各成一段，其余字符单独成段。
+/// 各段拼接起来即是原文
+fn split(text: &str) -> Vec<&str> {
+  let mut tokens = vec![];
+  let mut rest = text;
+  while let Some(c) = rest.chars().next() {
+    let len = if c == '_' || c.is_ascii_alphanumeric() {
+      rest.find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+    } else if c.is_whitespace() {
+      rest.find(|c: char| !c.is_whitespace())
+    } else if c == '"' {
+      rest[1..].find('"').map(|i| i + 2)
+    } else {
+      Some(c.len_utf8())
+    };
+    let len = len.unwrap_or(rest.len());
+    tokens.push(&rest[..len]);
+    rest = &rest[len..];
+  }
+  tokens
+}
+
+/// 对记号序列做一次随机的修改；插入的记号前后补上空格，不与相邻的记号粘连
+fn edit(tokens: &mut Vec<&str>, rng: &mut Rng) {
+  let len = tokens.len();
+  match rng.below(5) {
+    0 => {
+      let pos = rng.below(len + 1);
+      let word = rng.word();
+      tokens.splice(pos..pos, [" ", word, " "]);
+    }
+    1 if len > 0 => {
+      tokens.remove(rng.below(len));
+    }
+    2 if len > 0 => {
+      let pos = rng.below(len);
+      tokens[pos] = rng.word();
+    }
+    // 复制一段记号，用于构造重复的声明、嵌套的括号与语句
+    3 if len > 0 => {
+      let start = rng.below(len);
+      let end = (start + 1 + rng.below(8)).min(len);
+      let pos = rng.below(len + 1);
+      let span = tokens[start..end].to_vec();
+      tokens.splice(pos..pos, span);
+    }
+    4 if len > 1 => {
+      let (i, j) = (rng.below(len), rng.below(len));
+      tokens.swap(i, j);
+    }
+    _ => {}
+  }
+}
@@ -0,0 +1,94 @@
+#ifndef SYSYC_H
+#define SYSYC_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// 编译成功
+#define SYSY_OK 0
+
+// 源程序有错误，缓冲区中是诊断，每行一个 JSON 对象（与 `--error-format=json` 相同）
+#define SYSY_COMPILE_ERROR 1
+
+// 参数有误（空指针、非 UTF-8、未知的选项等），缓冲区中是错误信息
+#define SYSY_INVALID_ARGUMENT 2
+
+// 编译器内部错误，缓冲区中是错误信息
+#define SYSY_INTERNAL_ERROR 3
+
+// 输出的种类
+typedef enum SysyOutput {
+  // Koopa IR 文本（`-koopa`）
+  SYSY_OUTPUT_KOOPA = 0,
+  // RISC-V 汇编（`-riscv`）
+  SYSY_OUTPUT_RISCV = 1,
+} SysyOutput;
+
+// 编译选项；字符串为空指针时取默认值，与命令行上同名的选项含义相同
+typedef struct SysyOptions {
+  enum SysyOutput output;
+  // `--target`，如 `riscv64-unknown-linux-gnu`
+  const char *target;
+  // `--march`，如 `rv32imc`
+  const char *march;
+  // `--mcpu`
+  const char *mcpu;
+  // `--passes`，以逗号分隔
+  const char *passes;
+  // `-fpic`
+  bool pic;
+  // `--const-pool`
+  bool const_pool;
+} SysyOptions;
+
+// 由本库分配的字符串，`data` 以 NUL 结尾，`len` 不含 NUL
+typedef struct SysyBuffer {
+  char *data;
+  size_t len;
+} SysyBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// 编译 `source`，结果写入 `out_buf`，返回 `SYSY_OK` 等状态码。失败时 `out_buf` 中是错误信息；
+// `out_buf` 为空指针时只返回状态码。
+//
+// # Safety
+//
+// `source` 须为以 NUL 结尾的字符串，`options` 须指向有效的 [`SysyOptions`]，其中的字符串同样以 NUL
+// 结尾或为空指针；`out_buf` 须为空指针或可写。
+int sysy_compile(const char *source, const struct SysyOptions *options, struct SysyBuffer *out_buf);
+
+// 释放 [`sysy_compile`] 写入的缓冲区，之后 `buf` 为空；对空的缓冲区不做任何事。
+//
+// # Safety
+//
+// `buf` 须为空指针，或指向由 [`sysy_compile`] 写入、尚未释放的缓冲区。
+void sysy_buffer_free(struct SysyBuffer *buf);
+
+// 分配 `len` 字节的线性内存，供 JavaScript 写入字符串等参数；以 [`sysy_dealloc`] 释放
+uint8_t *sysy_alloc(size_t len);
+
+// 释放 [`sysy_alloc`] 分配的内存
+//
+// # Safety
+//
+// `data` 须为 [`sysy_alloc`] 以同样的 `len` 分配、尚未释放的内存。
+void sysy_dealloc(uint8_t *data, size_t len);
+
+// 以 `mode` 编译 `source`，结果或诊断写入 `out_buf`，返回 `SYSY_OK`、`SYSY_COMPILE_ERROR` 等状态码
+//
+// # Safety
+//
+// `source` 与 `mode` 须为以 NUL 结尾的字符串；`out_buf` 须为空指针或可写。
+int sysy_playground_compile(const char *source, const char *mode, struct SysyBuffer *out_buf);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif // SYSYC_H
@@ -0,0 +1,51 @@
+// 在浏览器中运行编译器：加载 sysyc.wasm（见 README 中的“在线演示”），compile(source, mode) 返回输出的文本。
+// 编译失败时抛出 SysyError，其 diagnostics 是与 --error-format=json 相同的诊断对象。
+
+const SYSY_OK = 0;
+const SYSY_COMPILE_ERROR = 1;
+
+export class SysyError extends Error {
+  constructor(message, diagnostics) {
+    super(message);
+    this.diagnostics = diagnostics;
+  }
+}
+
+export async function load(url = new URL("sysyc.wasm", import.meta.url)) {
+  const { instance } = await WebAssembly.instantiateStreaming(fetch(url));
+  const wasm = instance.exports;
+  const encoder = new TextEncoder();
+  const decoder = new TextDecoder();
+
+  // 以 NUL 结尾的字符串，返回地址与分配的长度
+  function cString(text) {
+    const bytes = encoder.encode(text);
+    const ptr = wasm.sysy_alloc(bytes.length + 1);
+    new Uint8Array(wasm.memory.buffer, ptr, bytes.length + 1).set([...bytes, 0]);
+    return [ptr, bytes.length + 1];
+  }
+
+  function compile(source, mode) {
+    const [sourcePtr, sourceLen] = cString(source);
+    const [modePtr, modeLen] = cString(mode);
+    // SysyBuffer：data 与 len 各占 4 字节
+    const buf = wasm.sysy_alloc(8);
+    const status = wasm.sysy_playground_compile(sourcePtr, modePtr, buf);
+    const [data, len] = new Uint32Array(wasm.memory.buffer, buf, 2);
+    const text = decoder.decode(new Uint8Array(wasm.memory.buffer, data, len));
+    wasm.sysy_buffer_free(buf);
+    wasm.sysy_dealloc(buf, 8);
+    wasm.sysy_dealloc(sourcePtr, sourceLen);
+    wasm.sysy_dealloc(modePtr, modeLen);
+    if (status === SYSY_OK) {
+      return text;
+    }
+    if (status === SYSY_COMPILE_ERROR) {
+      const diagnostics = text.trim().split("\n").map((line) => JSON.parse(line));
+      throw new SysyError(diagnostics.map((d) => d.message).join("\n"), diagnostics);
+    }
+    throw new SysyError(text, []);
+  }
+
+  return { compile, modes: ["koopa", "riscv", "perf", "ast", "ast-json", "tokens"] };
+}
@@ -0,0 +1,214 @@
+//! 调用图：函数之间的直接调用关系。Koopa IR 中只有直接调用，因此调用图是精确的。
+//!
+//! 强连通分量按被调用者在前的顺序给出，自底向上处理各函数（如内联、判断函数是否有副作用）时
+//! 依次访问即可，同一分量中的函数即互相递归的函数。
+//!
+//! ```
+//! use sysyc::analysis::CallGraph;
+//!
+//! let source = "int fib(int n) { if (n < 2) return n; return fib(n - 1) + fib(n - 2); }
+//!               int main() { return fib(10); }";
+//! let ir = sysyc::compile_to_koopa(source, &Default::default())?;
+//! let graph = CallGraph::new(&ir);
+//! let func = |name| *ir.funcs().iter().find(|(_, data)| data.name() == name).unwrap().0;
+//! assert!(graph.is_recursive(func("@fib")));
+//! assert!(!graph.is_recursive(func("@main")));
+//! assert_eq!(graph.call_sites(func("@fib"), func("@fib")), 2);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use koopa::ir::{Function, Program, ValueKind};
+
+use super::dot;
+
+#[derive(Debug)]
+pub struct CallGraph {
+  /// 程序中的函数，按定义的顺序，含只有声明的运行时库函数
+  funcs: Vec<Function>,
+  index: HashMap<Function, usize>,
+  /// 各函数直接调用的函数及其调用点的个数，按第一次调用的顺序
+  callees: Vec<Vec<(usize, usize)>>,
+  /// 各函数的调用者，按定义的顺序
+  callers: Vec<Vec<usize>>,
+  /// 强连通分量，被调用者所在的分量在前
+  sccs: Vec<Vec<Function>>,
+  /// 各函数是否递归：直接调用自身，或与其他函数在同一个强连通分量中
+  recursive: Vec<bool>,
+}
+
+impl CallGraph {
+  pub fn new(program: &Program) -> Self {
+    let funcs = program.func_layout().to_vec();
+    let index: HashMap<_, _> = funcs.iter().enumerate().map(|(i, &f)| (f, i)).collect();
+    let mut callees = vec![vec![]; funcs.len()];
+    let mut callers = vec![vec![]; funcs.len()];
+    for (caller, &func) in funcs.iter().enumerate() {
+      let data = program.func(func);
+      let sites: &mut Vec<(usize, usize)> = &mut callees[caller];
+      for (_, node) in data.layout().bbs() {
+        for &inst in node.insts().keys() {
+          let ValueKind::Call(call) = data.dfg().value(inst).kind() else {
+            continue;
+          };
+          let callee = index[&call.callee()];
+          match sites.iter_mut().find(|(f, _)| *f == callee) {
+            Some((_, count)) => *count += 1,
+            None => sites.push((callee, 1)),
+          }
+        }
+      }
+      for &(callee, _) in sites.iter() {
+        callers[callee].push(caller);
+      }
+    }
+    let sccs = strongly_connected(&callees);
+    let mut recursive: Vec<_> = (0..funcs.len())
+      .map(|f| callees[f].iter().any(|&(callee, _)| callee == f))
+      .collect();
+    for scc in sccs.iter().filter(|scc| scc.len() > 1) {
+      for &f in scc {
+        recursive[f] = true;
+      }
+    }
+    let sccs = sccs
+      .into_iter()
+      .map(|scc| scc.into_iter().map(|f| funcs[f]).collect())
+      .collect();
+    Self {
+      funcs,
+      index,
+      callees,
+      callers,
+      sccs,
+      recursive,
+    }
+  }
+
+  /// 程序中的函数，按定义的顺序
+  pub fn functions(&self) -> &[Function] {
+    &self.funcs
+  }
+
+  /// `func` 直接调用的函数，按第一次调用的顺序，不重复
+  pub fn callees(&self, func: Function) -> impl Iterator<Item = Function> + '_ {
+    let callees = &self.callees[self.index[&func]];
+    callees.iter().map(|&(callee, _)| self.funcs[callee])
+  }
+
+  /// 直接调用 `func` 的函数，按定义的顺序，不重复
+  pub fn callers(&self, func: Function) -> impl Iterator<Item = Function> + '_ {
+    let callers = &self.callers[self.index[&func]];
+    callers.iter().map(|&caller| self.funcs[caller])
+  }
+
+  /// `caller` 中调用 `callee` 的指令数
+  pub fn call_sites(&self, caller: Function, callee: Function) -> usize {
+    let callee = self.index[&callee];
+    let sites = &self.callees[self.index[&caller]];
+    sites
+      .iter()
+      .find(|&&(f, _)| f == callee)
+      .map_or(0, |&(_, count)| count)
+  }
+
+  /// `func` 能否经由一串调用再调用到自身
+  pub fn is_recursive(&self, func: Function) -> bool {
+    self.recursive[self.index[&func]]
+  }
+
+  /// 强连通分量，被调用者所在的分量在前；分量中的函数按定义的顺序
+  pub fn bottom_up(&self) -> &[Vec<Function>] {
+    &self.sccs
+  }
+
+  /// Graphviz DOT 格式（`--emit=callgraph.dot`）：边上标有多于一处的调用点个数，递归的函数以
+  /// 红色标出，只有声明的运行时库函数以虚线标出、且只列出被调用的
+  pub fn to_dot(&self, program: &Program) -> String {
+    let name = |f: usize| {
+      let name = program.func(self.funcs[f]).name();
+      dot::quote(&name[1..])
+    };
+    let mut dot = String::from("digraph callgraph {\n  node [shape=box];\n");
+    for (f, &func) in self.funcs.iter().enumerate() {
+      let declared = program.func(func).layout().entry_bb().is_none();
+      let style = match (declared, self.recursive[f]) {
+        (true, _) if self.callers[f].is_empty() => continue,
+        (true, _) => " [style=dashed]",
+        (false, true) => " [color=red]",
+        (false, false) => "",
+      };
+      writeln!(dot, "  {}{};", name(f), style).unwrap();
+    }
+    for (caller, sites) in self.callees.iter().enumerate() {
+      for &(callee, count) in sites {
+        write!(dot, "  {} -> {}", name(caller), name(callee)).unwrap();
+        match count {
+          1 => dot.push_str(";\n"),
+          _ => writeln!(dot, " [label=\"{}\"];", count).unwrap(),
+        }
+      }
+    }
+    dot.push_str("}\n");
+    dot
+  }
+}
+
+/// Tarjan 算法求强连通分量，按被调用者在前的顺序；以显式的栈代替递归，调用链很长时不会
+/// 耗尽栈。各分量中的函数按编号排序
+fn strongly_connected(succs: &[Vec<(usize, usize)>]) -> Vec<Vec<usize>> {
+  let n = succs.len();
+  let mut order = vec![usize::MAX; n];
+  let mut low = vec![0; n];
+  let mut on_stack = vec![false; n];
+  let mut stack = vec![];
+  let mut sccs = vec![];
+  let mut next = 0;
+  for root in 0..n {
+    if order[root] != usize::MAX {
+      continue;
+    }
+    // 各项是正在访问的函数与下一条要访问的边
+    let mut work = vec![(root, 0)];
+    order[root] = next;
+    low[root] = next;
+    next += 1;
+    stack.push(root);
+    on_stack[root] = true;
+    while let Some(&mut (v, ref mut edge)) = work.last_mut() {
+      if let Some(&(w, _)) = succs[v].get(*edge) {
+        *edge += 1;
+        if order[w] == usize::MAX {
+          order[w] = next;
+          low[w] = next;
+          next += 1;
+          stack.push(w);
+          on_stack[w] = true;
+          work.push((w, 0));
+        } else if on_stack[w] {
+          low[v] = low[v].min(order[w]);
+        }
+        continue;
+      }
+      work.pop();
+      if let Some(&(u, _)) = work.last() {
+        low[u] = low[u].min(low[v]);
+      }
+      if low[v] == order[v] {
+        let mut scc = vec![];
+        while let Some(w) = stack.pop() {
+          on_stack[w] = false;
+          scc.push(w);
+          if w == v {
+            break;
+          }
+        }
+        scc.sort();
+        sccs.push(scc);
+      }
+    }
+  }
+  sccs
+}
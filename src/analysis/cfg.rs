@@ -0,0 +1,147 @@
+//! 控制流图：函数中基本块之间的转移关系，取自各块末尾的转移指令。
+
+use std::collections::HashMap;
+
+use koopa::ir::{BasicBlock, FunctionData, Program, ValueKind};
+
+use super::dot::{self, Cluster};
+use crate::Result;
+
+#[derive(Debug)]
+pub struct Cfg {
+  /// 各基本块，按 IR 中的顺序，第一个是入口
+  blocks: Vec<BasicBlock>,
+  index: HashMap<BasicBlock, usize>,
+  /// 各块的后继；条件转移时依次是条件成立与不成立时的目标
+  succs: Vec<Vec<usize>>,
+  /// 各块的前驱，每条边一项
+  preds: Vec<Vec<usize>>,
+}
+
+impl Cfg {
+  pub fn new(func: &FunctionData) -> Self {
+    let blocks: Vec<_> = func.layout().bbs().keys().copied().collect();
+    let index: HashMap<_, _> = blocks.iter().enumerate().map(|(i, &bb)| (bb, i)).collect();
+    let mut succs = vec![vec![]; blocks.len()];
+    let mut preds = vec![vec![]; blocks.len()];
+    for (i, (_, node)) in func.layout().bbs().iter().enumerate() {
+      let Some(&last) = node.insts().back_key() else {
+        continue;
+      };
+      let targets = match func.dfg().value(last).kind() {
+        ValueKind::Branch(branch) => vec![branch.true_bb(), branch.false_bb()],
+        ValueKind::Jump(jump) => vec![jump.target()],
+        _ => vec![],
+      };
+      for target in targets {
+        let target = index[&target];
+        succs[i].push(target);
+        preds[target].push(i);
+      }
+    }
+    Self {
+      blocks,
+      index,
+      succs,
+      preds,
+    }
+  }
+
+  /// 各基本块，按 IR 中的顺序
+  pub fn blocks(&self) -> &[BasicBlock] {
+    &self.blocks
+  }
+
+  /// 入口块；只有声明的函数没有基本块
+  pub fn entry(&self) -> Option<BasicBlock> {
+    self.blocks.first().copied()
+  }
+
+  /// `bb` 的后继；条件转移时依次是条件成立与不成立时的目标
+  pub fn succs(&self, bb: BasicBlock) -> impl Iterator<Item = BasicBlock> + '_ {
+    let succs = &self.succs[self.index[&bb]];
+    succs.iter().map(|&succ| self.blocks[succ])
+  }
+
+  /// 从入口可达的块的逆后序：除回边外，每条边的起点都在终点之前
+  pub fn reverse_postorder(&self) -> Vec<BasicBlock> {
+    let mut postorder = vec![];
+    if self.blocks.is_empty() {
+      return postorder;
+    }
+    let mut visited = vec![false; self.blocks.len()];
+    // 以显式的栈代替递归，各项是块与下一个要访问的后继
+    let mut stack = vec![(0, 0)];
+    visited[0] = true;
+    while let Some(&mut (block, ref mut next)) = stack.last_mut() {
+      if let Some(&succ) = self.succs[block].get(*next) {
+        *next += 1;
+        if !visited[succ] {
+          visited[succ] = true;
+          stack.push((succ, 0));
+        }
+      } else {
+        stack.pop();
+        postorder.push(self.blocks[block]);
+      }
+    }
+    postorder.reverse();
+    postorder
+  }
+
+  /// `bb` 的前驱，每条边一项
+  pub fn preds(&self, bb: BasicBlock) -> impl Iterator<Item = BasicBlock> + '_ {
+    let preds = &self.preds[self.index[&bb]];
+    preds.iter().map(|&pred| self.blocks[pred])
+  }
+}
+
+/// Koopa IR 的文本中各块的指令，键为函数名与块名（均带前缀）
+fn block_texts(text: &str) -> HashMap<(&str, &str), Vec<&str>> {
+  let mut blocks: HashMap<_, Vec<_>> = HashMap::new();
+  let mut func = "";
+  let mut block = None;
+  for line in text.lines() {
+    if let Some(rest) = line.strip_prefix("fun ") {
+      func = &rest[..rest.find('(').unwrap_or(rest.len())];
+    } else if line.starts_with('%') && line.ends_with(':') {
+      // 块参数也不计入块名
+      let end = line.find(['(', ':']).unwrap();
+      block = Some((func, &line[..end]));
+    } else if let (Some(key), Some(inst)) = (block, line.strip_prefix("  ")) {
+      blocks.entry(key).or_default().push(inst);
+    }
+  }
+  blocks
+}
+
+/// 各函数的控制流图（`--emit=cfg`），块以其中的指令为标签，条件转移的两条边标有 `T`/`F`
+pub fn cfg_dot(program: &Program) -> Result<String> {
+  let text = crate::koopa_text(program)?;
+  let texts = block_texts(&text);
+  let mut clusters = vec![];
+  for &func in program.func_layout() {
+    let data = program.func(func);
+    if data.layout().entry_bb().is_none() {
+      continue;
+    }
+    let cfg = Cfg::new(data);
+    let bb_name = |bb| data.dfg().bb(bb).name().as_deref().unwrap_or("%?");
+    let mut cluster = Cluster::new(&data.name()[1..]);
+    for &bb in &cfg.blocks {
+      let insts = texts.get(&(data.name(), bb_name(bb)));
+      cluster.block(bb_name(bb), insts.into_iter().flatten());
+    }
+    for (&bb, succs) in cfg.blocks.iter().zip(&cfg.succs) {
+      let labels = match succs.len() {
+        2 => [Some("T"), Some("F")],
+        _ => [None, None],
+      };
+      for (&succ, label) in succs.iter().zip(labels) {
+        cluster.edge(bb_name(bb), bb_name(cfg.blocks[succ]), label);
+      }
+    }
+    clusters.push(cluster);
+  }
+  Ok(dot::graph("cfg", clusters))
+}
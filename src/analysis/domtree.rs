@@ -0,0 +1,138 @@
+//! 支配树：块 `a` 支配块 `b`，即从入口到 `b` 的每条路径都经过 `a`；`b` 的直接支配者是除 `b`
+//! 自身以外离 `b` 最近的支配者。以 Cooper、Harvey 与 Kennedy 的迭代算法求得，从入口不可达的块
+//! 不在树中。
+//!
+//! ```
+//! use sysyc::analysis::{Cfg, DomTree};
+//!
+//! let source = "int main() { int i = 0; while (i < 10) { if (i % 2) i = i + 3; i = i + 1; } return i; }";
+//! let ir = sysyc::compile_to_koopa(source, &Default::default())?;
+//! let main = ir.func(*ir.func_layout().last().unwrap());
+//! let block = |name: &str| {
+//!   let bbs = main.dfg().bbs();
+//!   *bbs.iter().find(|(_, data)| data.name().as_deref() == Some(name)).unwrap().0
+//! };
+//! let tree = DomTree::new(&Cfg::new(main));
+//! // 循环的入口支配循环体与循环之后的块，条件语句的分支不支配其后的块
+//! assert!(tree.dominates(block("%bb_while_entry_1"), block("%bb_while_end_1")));
+//! assert!(!tree.dominates(block("%bb_if_true_2"), block("%bb_if_end_2")));
+//! assert_eq!(tree.idom(block("%bb_if_end_2")), Some(block("%bb_while_body_1")));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+
+use koopa::ir::{BasicBlock, Program};
+
+use super::dot::{self, Cluster};
+use super::Cfg;
+
+#[derive(Debug)]
+pub struct DomTree {
+  /// 各可达块的直接支配者，入口没有
+  idom: HashMap<BasicBlock, BasicBlock>,
+  /// 各可达块在支配树中的子节点，按逆后序
+  children: HashMap<BasicBlock, Vec<BasicBlock>>,
+  /// 可达块在逆后序中的位置
+  order: HashMap<BasicBlock, usize>,
+}
+
+impl DomTree {
+  pub fn new(cfg: &Cfg) -> Self {
+    let rpo = cfg.reverse_postorder();
+    let order: HashMap<_, _> = rpo.iter().enumerate().map(|(i, &bb)| (bb, i)).collect();
+    // 以逆后序中的位置表示块，入口的直接支配者暂记为自身
+    let mut idom: Vec<Option<usize>> = vec![None; rpo.len()];
+    if !rpo.is_empty() {
+      idom[0] = Some(0);
+    }
+    let intersect = |idom: &[Option<usize>], mut a: usize, mut b: usize| {
+      while a != b {
+        while a > b {
+          a = idom[a].unwrap();
+        }
+        while b > a {
+          b = idom[b].unwrap();
+        }
+      }
+      a
+    };
+    let mut changed = true;
+    while changed {
+      changed = false;
+      for (i, &bb) in rpo.iter().enumerate().skip(1) {
+        // 只考虑已经求得直接支配者的前驱；按逆后序，至少有一个
+        let preds = cfg.preds(bb).filter_map(|pred| order.get(&pred).copied());
+        let new = preds
+          .filter(|&pred| idom[pred].is_some())
+          .reduce(|a, b| intersect(&idom, a, b));
+        if new.is_some() && new != idom[i] {
+          idom[i] = new;
+          changed = true;
+        }
+      }
+    }
+    let mut tree = Self {
+      idom: HashMap::new(),
+      children: rpo.iter().map(|&bb| (bb, vec![])).collect(),
+      order,
+    };
+    for (i, &bb) in rpo.iter().enumerate().skip(1) {
+      let parent = rpo[idom[i].unwrap()];
+      tree.idom.insert(bb, parent);
+      tree.children.get_mut(&parent).unwrap().push(bb);
+    }
+    tree
+  }
+
+  /// `bb` 的直接支配者；入口与不可达的块没有
+  pub fn idom(&self, bb: BasicBlock) -> Option<BasicBlock> {
+    self.idom.get(&bb).copied()
+  }
+
+  /// `bb` 直接支配的块，按逆后序
+  pub fn children(&self, bb: BasicBlock) -> &[BasicBlock] {
+    self.children.get(&bb).map_or(&[], Vec::as_slice)
+  }
+
+  pub fn is_reachable(&self, bb: BasicBlock) -> bool {
+    self.order.contains_key(&bb)
+  }
+
+  /// `a` 是否支配 `b`；每个可达块都支配自身，不可达的块不支配、也不被支配
+  pub fn dominates(&self, a: BasicBlock, mut b: BasicBlock) -> bool {
+    if !self.is_reachable(a) || !self.is_reachable(b) {
+      return false;
+    }
+    // 支配者在逆后序中总在前
+    while self.order[&b] > self.order[&a] {
+      b = self.idom[&b];
+    }
+    a == b
+  }
+}
+
+/// 各函数的支配树（`--emit=domtree`），边从直接支配者指向被支配的块；不可达的块以虚线标出
+pub fn domtree_dot(program: &Program) -> String {
+  let mut clusters = vec![];
+  for &func in program.func_layout() {
+    let data = program.func(func);
+    if data.layout().entry_bb().is_none() {
+      continue;
+    }
+    let cfg = Cfg::new(data);
+    let tree = DomTree::new(&cfg);
+    let bb_name = |bb| data.dfg().bb(bb).name().as_deref().unwrap_or("%?");
+    let mut cluster = Cluster::new(&data.name()[1..]);
+    for &bb in cfg.blocks() {
+      cluster.node(bb_name(bb), !tree.is_reachable(bb));
+    }
+    for &bb in cfg.blocks() {
+      for &child in tree.children(bb) {
+        cluster.edge(bb_name(bb), bb_name(child), None);
+      }
+    }
+    clusters.push(cluster);
+  }
+  dot::graph("domtree", clusters)
+}
@@ -0,0 +1,79 @@
+//! 输出 Graphviz DOT 格式的辅助函数。
+
+/// 带引号的 DOT 字符串
+pub fn quote(text: &str) -> String {
+  format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 多行文本的标签：各行左对齐
+pub fn label<S: AsRef<str>>(lines: impl IntoIterator<Item = S>) -> String {
+  let mut label = String::new();
+  for line in lines {
+    label += &line.as_ref().replace('\\', "\\\\").replace('"', "\\\"");
+    label += "\\l";
+  }
+  format!("\"{}\"", label)
+}
+
+/// 一个函数中以块为节点的图，作为子图输出
+pub struct Cluster {
+  name: String,
+  body: String,
+}
+
+impl Cluster {
+  pub fn new(func: &str) -> Self {
+    Self {
+      name: func.into(),
+      body: String::new(),
+    }
+  }
+
+  fn id(&self, block: &str) -> String {
+    quote(&format!("{}:{}", self.name, block))
+  }
+
+  /// 以块名为标签的节点，`dashed` 时以虚线标出
+  pub fn node(&mut self, block: &str, dashed: bool) {
+    let style = if dashed { ", style=dashed" } else { "" };
+    self.body += &format!(
+      "    {} [label={}{}];\n",
+      self.id(block),
+      quote(block),
+      style
+    );
+  }
+
+  /// 以块名与各条指令为标签的节点
+  pub fn block<S: AsRef<str>>(&mut self, block: &str, insts: impl IntoIterator<Item = S>) {
+    let header = format!("{}:", block);
+    let insts = insts.into_iter().map(|inst| inst.as_ref().to_string());
+    let lines = std::iter::once(header).chain(insts);
+    self.body += &format!("    {} [label={}];\n", self.id(block), label(lines));
+  }
+
+  /// 从块 `from` 到块 `to` 的边，`label` 如条件转移的 `T`/`F`
+  pub fn edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+    let attr = label.map_or(String::new(), |label| format!(" [label={}]", quote(label)));
+    self.body += &format!("    {} -> {}{};\n", self.id(from), self.id(to), attr);
+  }
+}
+
+/// 以各函数为子图的有向图
+pub fn graph(name: &str, clusters: Vec<Cluster>) -> String {
+  let mut dot = format!(
+    "digraph {} {{\n  node [shape=box, fontname=\"monospace\"];\n",
+    name
+  );
+  for cluster in clusters {
+    dot += &format!(
+      "  subgraph {} {{\n",
+      quote(&format!("cluster_{}", cluster.name))
+    );
+    dot += &format!("    label={};\n", quote(&cluster.name));
+    dot += &cluster.body;
+    dot += "  }\n";
+  }
+  dot += "}\n";
+  dot
+}
@@ -0,0 +1,10 @@
+//! Koopa IR 上的分析，供优化与可视化使用；分析只读取 IR，不做修改。
+
+mod callgraph;
+mod cfg;
+mod domtree;
+pub(crate) mod dot;
+
+pub use callgraph::CallGraph;
+pub use cfg::{cfg_dot, Cfg};
+pub use domtree::{domtree_dot, DomTree};
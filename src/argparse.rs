@@ -1,17 +1,322 @@
 use std::env::Args;
 
+use crate::backend::riscv::{Cpu, Extensions, Os, Target, Xlen};
+use crate::frontend::Sanitizers;
+use crate::link::DEFAULT_LINKER;
+use crate::optimization::pass_names;
+use crate::{completions, config};
+
+pub const USAGE: &str = "\
+Usage: sysyc [run | run-qemu] [options] <input>... [-o <output>]
+       (an input of - reads the source from stdin)
+       sysyc test [--qemu] [options] <dir>
+       sysyc gen [--qemu] [--seed=<n>] [--count=<n>] [options] [<dir>]
+       sysyc bench [--baseline=<cc>] [--history=<file>] [options] <dir>
+       sysyc cov report [-o <output>] <input> <counts>...
+       sysyc completions bash|zsh|fish
+
+Modes (default: compile and link with libsysy):
+  -koopa, --emit=koopa   Output Koopa IR
+  -riscv                 Output RISC-V assembly
+  -perf                  Output optimized RISC-V assembly
+  -S, -c, -E             Stop after assembly / object / preprocessing, like gcc
+  --emit=llvm            Output LLVM IR
+  --emit=wasm, --emit=wat
+                         Output a WebAssembly module (binary / text)
+  --emit=c               Output C99 source
+  --emit=ast             Output the syntax tree as S-expressions
+  --emit=ast-json        Output the syntax tree as JSON
+  --emit=ast-dot         Output the syntax tree in Graphviz DOT
+  --emit=tokens          Output the tokens produced by the lexer
+  --emit=highlight-html  Output the source as syntax-highlighted HTML
+  --find-refs <name>     List the definition and references of each symbol named <name>
+                         as <file>:<line>:<column> lines
+  --emit=callgraph.dot   Output the call graph in Graphviz DOT
+  --emit=cfg             Output the control-flow graph of each function in Graphviz DOT
+  --emit=domtree         Output the dominator tree of each function in Graphviz DOT
+  run                    Interpret the Koopa IR directly
+  run-qemu               Link, run under an emulator and print the judge's format;
+                         a second input is used as stdin
+  test                   Run each .sy with a .out under <dir> (with .in as stdin),
+                         compare with the judge's format and print a summary
+  gen                    Generate random programs, compare the results of sysyc and a
+                         reference C compiler, and save mismatches under <dir>
+  bench                  Build the performance cases under <dir>, run them under the
+                         emulator and print the times reported by starttime/stoptime
+  cov report             Print <input> with the execution count of each line, summed
+                         over the <counts> files written by programs built with --coverage
+  completions <shell>    Print a bash, zsh or fish completion script
+
+Options:
+  -o <file>              Write output to <file> (- for stdout)
+  --qemu                 With test or gen: run RISC-V code under the emulator instead
+                         of interpreting the Koopa IR
+  --jit                  With run or test: compile the Koopa IR to x86-64 machine code
+                         in memory and run it instead of interpreting (x86-64 Linux only)
+  --seed=<n>             With gen: seed of the first program (default: from the clock);
+                         with -o, write that program to <file> instead of testing
+  --count=<n>            With gen: number of programs to test (default: 100)
+  --reference=<cc>       With gen: C compiler for the expected results (default: cc),
+                         or interpret (with --qemu) to check only the RISC-V backend
+  --baseline=<cc>        With bench: also time the cases built by this RISC-V C compiler
+                         at -O2, e.g. riscv32-unknown-linux-gnu-gcc
+  --history=<file>       With bench: compare with the times saved in <file> by the
+                         previous run, then save the new times
+  --ssh=<host>           With bench: copy the programs to <host> and run them there
+  --save-temps           Keep the intermediate .i, .koopa, .s and .o files of each input
+                         next to the output
+  --watch                Recompile whenever an input file changes
+  --no-config            Ignore sysy.toml in the working directory
+  -O0, -O1               Run the optimization passes on RISC-V output
+                         (default: -O1 with -perf and bench, -O0 otherwise)
+  --target=<triple>      riscv32, riscv64, or a triple such as riscv64-unknown-linux-gnu
+                         (riscv32-unknown-none-elf implies --freestanding)
+  --print-target         Print the target triple, ISA and ABI selected by the options
+  --march=<isa>          e.g. rv32im, rv32imc, rv64gc
+  --mcpu=<cpu>           generic, rocket, sifive-e31 or sifive-u74
+  -fpic, -fno-pic        Position independent code (globals via GOT)
+  -g                     Emit debug information (assembly output only)
+  --asm-comments         Annotate assembly with IR and source lines
+  --source-map           Write <output>.map (JSON) mapping assembly lines to the source
+                         lines and Koopa IR instructions they come from
+  --const-pool           Load large constants from a per-function pool
+  -fsanitize=<checks>    Check at run time and abort with the source line on failure
+                         (comma-separated): bounds (array subscripts), integer
+                         (division by zero and -2147483648 / -1)
+  -finstrument-functions Count calls to each function and report them on exit
+  --coverage             Count executions of each source line and write the counts to
+                         stderr on exit, to be read by cov report
+  --auto-timing          With -perf, -O1 or bench: call starttime at the start of main and
+                         stoptime before it returns, unless the program calls them
+  --rvc-report           Report the RVC compression ratio
+  --time-passes          Report the time spent in each compilation phase
+  --stats                Report per-function block, instruction and stack statistics
+  --verify               Check IR and assembly invariants after every pass
+  --verify-opt           With run-qemu, or test or gen with --qemu: also build and run
+                         the program at -O0 and fail if the outputs differ
+  --jobs=<n>             Run register allocation, legalization and scheduling of the
+                         functions on <n> threads; IR generation and instruction selection
+                         stay sequential (default: the number of CPUs; same output)
+  --error-format=<format>
+                         human (default), pretty (with the source lines) or json
+                         (one object per line)
+  -v, -vv                Print debug / trace messages of the compiler to stderr
+                         (per module with e.g. SYSYC_LOG=backend::regalloc=trace)
+  --passes=<passes>      Run these passes after codegen in order (comma-separated),
+                         instead of the default pipeline of the mode
+  --print-passes         List the passes available to --passes
+  --cfg-after=<pass>     With --emit=cfg: show the assembly after <pass> (codegen or a
+                         pass in the pipeline) instead of the Koopa IR
+  --dump-ir-before=<passes>, --dump-ir-after=<passes>
+                         Write the IR around passes (comma-separated or all:
+                         codegen, peephole, schedule, compress) to <input>.NN.<pass>.*
+  --print-changed        Print a unified diff of each function changed by a pass to stderr
+  --freestanding         Bare-metal program booted by OpenSBI
+  --extensions           Allow overloaded functions; their names are mangled as in C++
+  --linker=<path>        Linker (default: ld.lld)
+  -L<dir>                Additional library directory
+  --runner=<command>     Emulator for run-qemu (default: qemu-riscv32-static)
+  -h, --help             Print this message
+  --version              Print version
+";
+
+const PASSES_HELP: &str = "\
+Passes available to --passes, in the default order:
+  peephole    Remove redundant loads after stores (default with -perf)
+  schedule    Reorder instructions by the latencies of --mcpu (default with -perf)
+  compress    Replace instructions with RVC forms (default with the C extension)
+";
+
 #[derive(Debug, Clone, Copy)]
 pub enum Mode {
   Koopa,
   Riscv,
   Perf,
+  /// 输出 LLVM IR（`--emit=llvm`）
+  Llvm,
+  /// 输出 WebAssembly 二进制模块（`--emit=wasm`）
+  Wasm,
+  /// 输出 WebAssembly 文本格式（`--emit=wat`）
+  Wat,
+  /// 输出 C 源代码（`--emit=c`）
+  C,
+  /// 输出词法分析的结果（`--emit=tokens`）
+  Tokens,
+  /// 以 HTML 输出高亮的源代码（`--emit=highlight-html`）
+  HighlightHtml,
+  /// 列出符号的定义与引用之处（`--find-refs`）
+  FindRefs,
+  /// 以 DOT 格式输出调用图（`--emit=callgraph.dot`）
+  CallGraph,
+  /// 以 DOT 格式输出各函数的控制流图（`--emit=cfg`）
+  Cfg,
+  /// 以 DOT 格式输出各函数的支配树（`--emit=domtree`）
+  DomTree,
+  /// 输出语法树（`--emit=ast`）
+  Ast,
+  /// 以 JSON 输出语法树（`--emit=ast-json`）
+  AstJson,
+  /// 以 DOT 格式输出语法树（`--emit=ast-dot`）
+  AstDot,
+  /// 输出预处理后的源代码（`-E`）
+  Preprocess,
+  /// 解释执行 Koopa IR（`sysyc run`）
+  Run,
+  /// 编译、链接后在模拟器中运行（`sysyc run-qemu`），以评测的格式输出结果
+  RunQemu,
+  /// 运行目录下的测试用例（`sysyc test`）
+  Test,
+  /// 以随机生成的程序做差分测试（`sysyc gen`）
+  Gen,
+  /// 运行目录下的性能测试用例并计时（`sysyc bench`）
+  Bench,
+  /// 以 `--coverage` 的计数标注源代码的各行（`sysyc cov report`）
+  CovReport,
+  /// 未指定模式：编译并与 `libsysy` 链接为可执行文件
+  Link,
+}
+
+/// 编译错误的输出格式（`--error-format`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorFormat {
+  Human,
+  /// 连同出错处的源代码一起输出
+  Pretty,
+  /// 每个诊断输出一行 JSON
+  Json,
+}
+
+impl ErrorFormat {
+  pub const NAMES: &'static [&'static str] = &["human", "pretty", "json"];
+
+  fn parse(name: &str) -> Result<Self, String> {
+    match name {
+      "human" => Ok(Self::Human),
+      "pretty" => Ok(Self::Pretty),
+      "json" => Ok(Self::Json),
+      _ => Err(format!(
+        "unknown --error-format '{}': expect human, pretty or json",
+        name
+      )),
+    }
+  }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ParsedArgs {
   pub mode: Mode,
   pub input: Vec<String>,
   pub output: Option<String>,
+  pub target: Target,
+  pub cpu: Cpu,
+  /// 输出 RVC 压缩率报告
+  pub rvc_report: bool,
+  /// 输出各阶段的用时（`--time-passes`）
+  pub time_passes: bool,
+  /// 输出各函数的统计信息（`--stats`）
+  pub stats: bool,
+  /// 每一遍之后检查 IR 与汇编（`--verify`）
+  pub verify: bool,
+  /// `--verify-opt` 时作为对照的 -O0 的遍，其结果须与 `passes` 的相同
+  pub verify_opt: Option<Vec<String>>,
+  /// 寄存器分配等按函数并行的阶段所用的线程数（`--jobs`），0 即处理器的核数
+  pub jobs: usize,
+  pub error_format: ErrorFormat,
+  /// 输出内部跟踪信息的详细程度：`-v` 为 1，`-vv` 为 2
+  pub verbose: u8,
+  /// 输入文件改动后重新编译（`--watch`）
+  pub watch: bool,
+  /// 在输出所在目录保留各阶段的中间文件（`--save-temps`）
+  pub save_temps: bool,
+  /// `sysyc test`/`gen` 在模拟器中运行 RISC-V 代码，而非解释执行（`--qemu`）
+  pub qemu: bool,
+  /// `sysyc run`/`test` 即时编译为本机代码执行，而非解释执行（`--jit`）
+  pub jit: bool,
+  /// `sysyc gen` 的第一个程序的种子（`--seed`），未给出时取自时钟
+  pub seed: Option<u64>,
+  /// `sysyc gen` 生成的程序数（`--count`）
+  pub count: usize,
+  /// `sysyc gen` 的参考编译器命令，可带参数，或 `interpret`（`--reference`）
+  pub reference: String,
+  /// `sysyc bench` 对照的 C 编译器命令，可带参数（`--baseline`）
+  pub baseline: Option<String>,
+  /// `sysyc bench` 保存各用例用时的文件，下次运行时与之比较（`--history`）
+  pub history: Option<String>,
+  /// `sysyc bench` 经 ssh 在这台机器上运行程序（`--ssh`）
+  pub ssh: Option<String>,
+  /// 在汇编中以注释标注 IR 指令与源代码行
+  pub asm_comments: bool,
+  /// 生成调试信息（`-g`）
+  pub debug: bool,
+  /// 另以 JSON 输出汇编各行的来源（`--source-map`）
+  pub source_map: bool,
+  /// 输出 ELF 目标文件而非汇编文本
+  pub emit_obj: bool,
+  /// 以 gcc 的 `-S`/`-c` 指定输出：未给出 `-o` 时输出到与输入同名的 `.s`/`.o` 文件
+  pub driver: bool,
+  /// 生成位置无关代码（`-fpic`）
+  pub pic: bool,
+  /// 大常量改由常量池加载
+  pub const_pool: bool,
+  /// 插入的运行时检查（`-fsanitize=`）
+  pub sanitize: Sanitizers,
+  /// 为函数调用计数（`-finstrument-functions`）
+  pub instrument_functions: bool,
+  /// 统计各源代码行执行的次数（`--coverage`）
+  pub coverage: bool,
+  /// 自动在 `main` 的首尾调用 `starttime`、`stoptime`（`--auto-timing`）
+  pub auto_timing: bool,
+  /// 允许函数重载（`--extensions`）
+  pub extensions: bool,
+  pub linker: String,
+  /// 运行可执行文件的模拟器命令，可带参数（`--runner`）
+  pub runner: Option<String>,
+  /// 额外的库搜索路径（`-L`）
+  pub lib_dirs: Vec<String>,
+  /// 代码生成之后依次运行的遍：`--passes` 给出，或由模式与扩展决定
+  pub passes: Vec<String>,
+  /// `--emit=cfg` 输出这一遍之后汇编的控制流图，未给出时是 Koopa IR 的（`--cfg-after`）
+  pub cfg_after: Option<String>,
+  /// `--find-refs` 查找的名字
+  pub find_refs: Option<String>,
+  /// 在这些遍之前输出 IR
+  pub dump_ir_before: Vec<String>,
+  /// 在这些遍之后输出 IR
+  pub dump_ir_after: Vec<String>,
+  /// 每一遍之后向标准错误输出改变了的函数的差异（`--print-changed`）
+  pub print_changed: bool,
+}
+
+/// 解析以逗号分隔的遍名，`all` 即所有的遍
+fn parse_passes(list: &str, passes: &mut Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+  let names = pass_names();
+  for pass in list.split(',') {
+    match pass {
+      "all" => passes.extend(names.iter().map(|p| p.to_string())),
+      _ if names.contains(&pass) => passes.push(pass.into()),
+      _ => {
+        let expect = names.join(", ");
+        return Err(format!("unknown pass '{}': expect all, {}", pass, expect).into());
+      }
+    }
+  }
+  Ok(())
+}
+
+/// 解析 `--passes`：以逗号分隔，代码生成之后的遍可以任意排列、重复
+fn parse_pipeline(list: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+  let names = pass_names();
+  let passes = list.split(',').filter(|pass| !pass.is_empty());
+  passes
+    .map(|pass| match pass {
+      _ if names[1..].contains(&pass) => Ok(pass.into()),
+      _ => Err(format!("unknown pass '{}' (see --print-passes)", pass).into()),
+    })
+    .collect()
+}
+
+fn bits(xlen: Xlen) -> usize {
+  xlen.bytes() * 8
 }
 
 pub fn parse(mut args: Args) -> Result<ParsedArgs, Box<dyn std::error::Error>> {
@@ -20,8 +325,59 @@ pub fn parse(mut args: Args) -> Result<ParsedArgs, Box<dyn std::error::Error>> {
   let mut mode: Option<Mode> = None;
   let mut input: Vec<String> = vec![];
   let mut output: Option<String> = None;
+  let mut xlen = Xlen::Rv32;
+  let mut ext = Extensions::default();
+  // `--target` 与 `--march` 各自指定的寄存器宽度，两者须一致
+  let mut target_xlen = None;
+  let mut target_os = None;
+  let mut target_name = String::new();
+  let mut print_target = false;
+  let mut march_xlen = None;
+  let mut cpu = Cpu::default();
+  let mut rvc_report = false;
+  let mut time_passes = false;
+  let mut stats = false;
+  let mut verify = false;
+  let mut verify_opt = false;
+  let mut jobs = 0;
+  let mut error_format = ErrorFormat::Human;
+  let mut verbose = 0u8;
+  let mut watch = false;
+  let mut save_temps = false;
+  let mut qemu = false;
+  let mut jit = false;
+  let mut seed = None;
+  let mut count = None;
+  let mut reference = None;
+  let mut baseline = None;
+  let mut history = None;
+  let mut ssh = None;
+  let mut asm_comments = false;
+  let mut source_map = false;
+  let mut debug = false;
+  let mut emit_obj = false;
+  let mut emit_asm = false;
+  let mut pic = false;
+  let mut const_pool = false;
+  let mut sanitize = Sanitizers::default();
+  let mut instrument_functions = false;
+  let mut coverage = false;
+  let mut auto_timing = false;
+  let mut freestanding = false;
+  let mut extensions = false;
+  let mut linker = DEFAULT_LINKER.to_string();
+  let mut runner = None;
+  let mut lib_dirs = vec![];
+  let mut opt_level = None;
+  let mut passes = None;
+  let mut cfg_after = None;
+  let mut find_refs = None;
+  let mut dump_ir_before = vec![];
+  let mut dump_ir_after = vec![];
+  let mut print_changed = false;
 
   let mut pending_output = false;
+  let mut pending_find_refs = false;
   let mut set_mode = |m: Mode| -> Result<(), Box<dyn std::error::Error>> {
     if let Some(mode) = mode {
       Err(format!("duplicate mode: {:#?} and {:#?}", mode, m).into())
@@ -31,25 +387,432 @@ pub fn parse(mut args: Args) -> Result<ParsedArgs, Box<dyn std::error::Error>> {
     }
   };
 
-  for i in args {
+  // 子命令须在最前
+  let mut args = args.peekable();
+  if args.next_if(|i| i == "run").is_some() {
+    set_mode(Mode::Run)?;
+  } else if args.next_if(|i| i == "run-qemu").is_some() {
+    set_mode(Mode::RunQemu)?;
+  } else if args.next_if(|i| i == "test").is_some() {
+    set_mode(Mode::Test)?;
+  } else if args.next_if(|i| i == "gen").is_some() {
+    set_mode(Mode::Gen)?;
+  } else if args.next_if(|i| i == "bench").is_some() {
+    set_mode(Mode::Bench)?;
+  } else if args.next_if(|i| i == "cov").is_some() {
+    if args.next_if(|i| i == "report").is_none() {
+      return Err("expect cov report".into());
+    }
+    set_mode(Mode::CovReport)?;
+  } else if args.next_if(|i| i == "completions").is_some() {
+    let shell = args.next().unwrap_or_default();
+    print!("{}", completions::generate(&shell)?);
+    std::process::exit(0);
+  }
+  // 配置文件给出默认的选项，命令行上已设置的项不再取其值
+  let cli: Vec<_> = args.collect();
+  let config = match cli.iter().any(|i| i == "--no-config") {
+    true => vec![],
+    false => config::load()?,
+  };
+  let given: Vec<_> = cli.iter().filter_map(|i| config::option_key(i)).collect();
+  let config = config.into_iter().filter(|i| match config::option_key(i) {
+    Some(key) => !given.contains(&key),
+    None => true,
+  });
+  for i in config.chain(cli.iter().cloned()) {
     if pending_output {
       output = Some(i);
       pending_output = false;
-    } else if i.starts_with("-") {
+    } else if pending_find_refs {
+      find_refs = Some(i);
+      pending_find_refs = false;
+    } else if i.starts_with("-") && i != "-" {
       match i.as_str() {
-        "-koopa" => set_mode(Mode::Koopa)?,
+        "-h" | "--help" => {
+          print!("{}", USAGE);
+          std::process::exit(0);
+        }
+        "--print-passes" => {
+          print!("{}", PASSES_HELP);
+          std::process::exit(0);
+        }
+        "--version" => {
+          println!("sysyc {}", env!("CARGO_PKG_VERSION"));
+          std::process::exit(0);
+        }
+        "-koopa" | "--emit=koopa" => set_mode(Mode::Koopa)?,
         "-riscv" => set_mode(Mode::Riscv)?,
         "-perf" => set_mode(Mode::Perf)?,
+        "--emit=llvm" => set_mode(Mode::Llvm)?,
+        "--emit=wasm" => set_mode(Mode::Wasm)?,
+        "--emit=wat" => set_mode(Mode::Wat)?,
+        "--emit=c" => set_mode(Mode::C)?,
+        "--emit=ast" => set_mode(Mode::Ast)?,
+        "--emit=ast-json" => set_mode(Mode::AstJson)?,
+        "--emit=ast-dot" => set_mode(Mode::AstDot)?,
+        "--emit=tokens" => set_mode(Mode::Tokens)?,
+        "--emit=highlight-html" => set_mode(Mode::HighlightHtml)?,
+        "--emit=callgraph.dot" => set_mode(Mode::CallGraph)?,
+        "--emit=cfg" => set_mode(Mode::Cfg)?,
+        "--emit=domtree" => set_mode(Mode::DomTree)?,
+        "--find-refs" => {
+          set_mode(Mode::FindRefs)?;
+          pending_find_refs = true;
+        }
         "-o" => pending_output = true,
-        _ => return Err(format!("unknown option: {}", i).into()),
+        "-c" => emit_obj = true,
+        "-S" => emit_asm = true,
+        "-E" => set_mode(Mode::Preprocess)?,
+        "-g" => debug = true,
+        "-fpic" | "-fPIC" | "-fpie" | "-fPIE" => pic = true,
+        "-fno-pic" | "-fno-PIC" | "-fno-pie" | "-fno-PIE" => pic = false,
+        "--print-target" => print_target = true,
+        "--rvc-report" => rvc_report = true,
+        "--time-passes" => time_passes = true,
+        "--stats" => stats = true,
+        "--verify" => verify = true,
+        "--print-changed" => print_changed = true,
+        "--verify-opt" => verify_opt = true,
+        "-v" => verbose = verbose.saturating_add(1),
+        "-vv" => verbose = verbose.saturating_add(2),
+        "--watch" => watch = true,
+        "--save-temps" => save_temps = true,
+        "--qemu" => qemu = true,
+        "--jit" => jit = true,
+        "--no-config" => {}
+        "-O0" => opt_level = Some(0),
+        "-O1" => opt_level = Some(1),
+        "--asm-comments" => asm_comments = true,
+        "--source-map" => source_map = true,
+        "--const-pool" => const_pool = true,
+        "-finstrument-functions" => instrument_functions = true,
+        "--coverage" => coverage = true,
+        "--auto-timing" => auto_timing = true,
+        "--freestanding" => freestanding = true,
+        "--extensions" => extensions = true,
+        _ if i.starts_with("-o") => output = Some(i[2..].into()),
+        _ if i.starts_with("-fsanitize=") => sanitize.enable(&i["-fsanitize=".len()..])?,
+        _ if i.starts_with("--linker=") => linker = i["--linker=".len()..].into(),
+        _ if i.starts_with("--runner=") => runner = Some(i["--runner=".len()..].into()),
+        _ if i.starts_with("-L") && i.len() > 2 => lib_dirs.push(i[2..].into()),
+        _ if i.starts_with("--passes=") => passes = Some(parse_pipeline(&i["--passes=".len()..])?),
+        _ if i.starts_with("--cfg-after=") => {
+          let pass = &i["--cfg-after=".len()..];
+          if !pass_names().contains(&pass) {
+            let expect = pass_names().join(", ");
+            return Err(format!("unknown pass '{}': expect {}", pass, expect).into());
+          }
+          cfg_after = Some(pass.to_string());
+        }
+        _ if i.starts_with("--dump-ir-before=") => {
+          parse_passes(&i["--dump-ir-before=".len()..], &mut dump_ir_before)?;
+        }
+        _ if i.starts_with("--dump-ir-after=") => {
+          parse_passes(&i["--dump-ir-after=".len()..], &mut dump_ir_after)?;
+        }
+        _ if i.starts_with("--target=") => {
+          target_name = i["--target=".len()..].into();
+          let (xlen, os) = Target::parse_triple(&target_name)?;
+          target_xlen = Some(xlen);
+          target_os = os;
+        }
+        _ if i.starts_with("--mcpu=") => cpu = Cpu::parse(&i["--mcpu=".len()..])?,
+        _ if i.starts_with("--jobs=") => {
+          let n = &i["--jobs=".len()..];
+          jobs = match n.parse() {
+            Ok(n) if n > 0 => n,
+            _ => return Err(format!("invalid --jobs '{}': expect a positive integer", n).into()),
+          };
+        }
+        _ if i.starts_with("--seed=") => {
+          let n = &i["--seed=".len()..];
+          seed = Some(
+            n.parse()
+              .map_err(|_| format!("invalid --seed '{}': expect a non-negative integer", n))?,
+          );
+        }
+        _ if i.starts_with("--count=") => {
+          let n = &i["--count=".len()..];
+          count = match n.parse() {
+            Ok(n) if n > 0 => Some(n),
+            _ => return Err(format!("invalid --count '{}': expect a positive integer", n).into()),
+          };
+        }
+        _ if i.starts_with("--reference=") => reference = Some(i["--reference=".len()..].into()),
+        _ if i.starts_with("--baseline=") => baseline = Some(i["--baseline=".len()..].into()),
+        _ if i.starts_with("--history=") => history = Some(i["--history=".len()..].into()),
+        _ if i.starts_with("--ssh=") => ssh = Some(i["--ssh=".len()..].into()),
+        _ if i.starts_with("--error-format=") => {
+          error_format = ErrorFormat::parse(&i["--error-format=".len()..])?;
+        }
+        _ if i.starts_with("--march=") => {
+          let (march, march_ext) = Extensions::parse_march(&i["--march=".len()..])?;
+          march_xlen = Some(march);
+          ext = march_ext;
+        }
+        _ => return Err(format!("unknown option: {} (see --help)", i).into()),
       }
     } else {
       input.push(i);
     }
   }
-  let mode = mode.ok_or("missing mode")?;
-  if input.len() == 0 {
-    return Err("missing input".into());
+  if pending_output {
+    return Err("missing filename after -o".into());
+  }
+  if pending_find_refs {
+    return Err("missing name after --find-refs".into());
+  }
+  match (target_xlen, march_xlen) {
+    (Some(x), Some(march)) if x != march => {
+      let message = format!("--target={} conflicts with --march", target_name);
+      return Err(format!("{}=rv{}", message, bits(march)).into());
+    }
+    (Some(x), _) | (_, Some(x)) => xlen = x,
+    _ => {}
+  }
+  let os = match target_os {
+    Some(Os::Linux) if freestanding => {
+      return Err(format!("--freestanding conflicts with --target={}", target_name).into());
+    }
+    Some(os) => os,
+    None if freestanding => Os::None,
+    None => Os::Linux,
+  };
+  let target = Target::new(xlen, ext, os);
+  let freestanding = target.freestanding();
+  if print_target {
+    println!("triple: {}", target.triple());
+    println!("march:  rv{}{}", bits(xlen), ext);
+    println!("mabi:   {}", target.abi);
+    std::process::exit(0);
+  }
+  if rvc_report && !ext.c {
+    let hint = format!("e.g. --march=rv{}{}c", bits(xlen), ext);
+    return Err(format!("--rvc-report requires the C extension ({})", hint).into());
+  }
+  if freestanding && pic {
+    // 裸机上没有动态链接器填写 GOT
+    return Err("--freestanding cannot be combined with -fpic".into());
+  }
+  // 同 gcc：`-S` 优先于 `-c`
+  let driver = mode.is_none() && (emit_asm || emit_obj);
+  if emit_asm {
+    emit_obj = false;
+  }
+  let mode = match mode {
+    Some(mode) => mode,
+    None if driver => Mode::Riscv,
+    None => Mode::Link,
+  };
+  if qemu && !matches!(mode, Mode::Test | Mode::Gen) {
+    return Err("--qemu requires the test or gen subcommand".into());
+  }
+  if jit && !matches!(mode, Mode::Run | Mode::Test) {
+    return Err("--jit requires the run or test subcommand".into());
+  }
+  if jit && qemu {
+    return Err("--jit cannot be combined with --qemu".into());
+  }
+  if (seed.is_some() || count.is_some() || reference.is_some()) && !matches!(mode, Mode::Gen) {
+    return Err("--seed, --count and --reference require the gen subcommand".into());
+  }
+  let reference = reference.unwrap_or_else(|| "cc".into());
+  if (baseline.is_some() || history.is_some() || ssh.is_some()) && !matches!(mode, Mode::Bench) {
+    return Err("--baseline, --history and --ssh require the bench subcommand".into());
+  }
+  if reference == "interpret" && !qemu {
+    // 否则两边都是解释执行
+    return Err("--reference=interpret requires --qemu".into());
+  }
+  if cfg_after.is_some() && !matches!(mode, Mode::Cfg) {
+    return Err("--cfg-after requires --emit=cfg".into());
+  }
+  let riscv = matches!(
+    mode,
+    Mode::Riscv | Mode::Perf | Mode::Link | Mode::RunQemu | Mode::Bench
+  ) || qemu
+    || cfg_after.is_some();
+  if stats && !riscv {
+    // 统计的是 RISC-V 代码生成的结果
+    return Err("--stats requires RISC-V code generation".into());
+  }
+  let pipeline = driver || matches!(mode, Mode::Link | Mode::RunQemu);
+  if save_temps && !pipeline {
+    // 其余模式只输出一种结果，没有中间文件
+    return Err("--save-temps requires -S, -c, linking or run-qemu".into());
+  }
+  if passes.is_some() && !riscv {
+    return Err("--passes requires RISC-V code generation".into());
+  }
+  if print_changed && !riscv {
+    // 遍作用于汇编
+    return Err("--print-changed requires RISC-V code generation".into());
+  }
+  // 启用的插桩中的一项，用于报错
+  let instrument = [
+    (sanitize.any(), "-fsanitize"),
+    (instrument_functions, "-finstrument-functions"),
+    (coverage, "--coverage"),
+  ];
+  let instrument = instrument
+    .into_iter()
+    .find_map(|(on, flag)| on.then_some(flag));
+  let other_backend = || matches!(mode, Mode::Llvm | Mode::Wasm | Mode::Wat | Mode::C);
+  if let Some(flag) = instrument.filter(|_| other_backend()) {
+    // 诊断与报告所用的字符输出与终止只由 RISC-V 后端与解释器提供
+    return Err(format!("{} requires RISC-V code generation or run", flag).into());
+  }
+  if source_map && (!matches!(mode, Mode::Riscv | Mode::Perf) || emit_obj) {
+    return Err("--source-map requires assembly output (-S, -riscv or -perf)".into());
+  }
+  // 各优化级别默认的遍
+  let default_passes = |level: u8| {
+    let mut passes = vec![];
+    if level > 0 {
+      passes.extend(["peephole".to_string(), "schedule".to_string()]);
+    }
+    if ext.c {
+      passes.push("compress".to_string());
+    }
+    passes
+  };
+  let level = opt_level.unwrap_or(matches!(mode, Mode::Perf | Mode::Bench) as u8);
+  if auto_timing && level == 0 {
+    // 计时只对优化后的代码有意义
+    return Err("--auto-timing requires -perf, -O1 or bench".into());
+  }
+  let passes = passes.unwrap_or_else(|| default_passes(level));
+  let verify_opt = verify_opt.then(|| default_passes(0));
+  if verify_opt.is_some() && !(matches!(mode, Mode::RunQemu) || qemu) {
+    // 优化的遍作用于汇编，须运行 RISC-V 程序才能比较
+    return Err("--verify-opt requires run-qemu, or test or gen with --qemu".into());
+  }
+  if verify_opt.as_ref() == Some(&passes) {
+    return Err("--verify-opt requires optimization passes (-O1, -perf or --passes)".into());
+  }
+  if verify_opt.is_some() && matches!(mode, Mode::RunQemu) && input.len() < 2 {
+    // 程序要运行两次，不能都读终端的输入
+    return Err("--verify-opt with run-qemu expects the stdin file as the second input".into());
+  }
+  if let Some(pass) = cfg_after.as_ref().filter(|&pass| pass != "codegen") {
+    if !passes.contains(pass) {
+      return Err(
+        format!(
+          "--cfg-after: pass {} is not in the pipeline (see --passes)",
+          pass
+        )
+        .into(),
+      );
+    }
+  }
+  if passes.iter().any(|pass| pass == "compress") && !ext.c {
+    // 没有 C 扩展的处理器不能执行压缩指令
+    let hint = format!("e.g. --march=rv{}{}c", bits(xlen), ext);
+    return Err(format!("pass compress requires the C extension ({})", hint).into());
+  }
+  if watch && matches!(mode, Mode::Run) {
+    // 解释执行结束时即以程序的返回值退出
+    return Err("--watch cannot be combined with run".into());
+  }
+  if watch && input.iter().any(|input| input == "-") {
+    return Err("--watch cannot read from stdin".into());
+  }
+  if freestanding && (matches!(mode, Mode::RunQemu | Mode::Bench) || qemu) {
+    // 用户态模拟器不提供 SBI
+    return Err("run-qemu cannot be combined with --freestanding".into());
+  }
+  if matches!(mode, Mode::Test) && (input.len() != 1 || output.is_some()) {
+    return Err("test expects a single directory and no -o".into());
+  }
+  if matches!(mode, Mode::Bench) && (input.len() != 1 || output.is_some()) {
+    return Err("bench expects a single directory and no -o".into());
+  }
+  if matches!(mode, Mode::CovReport) && input.len() < 2 {
+    return Err("cov report expects a source file and at least one counts file".into());
+  }
+  if matches!(mode, Mode::Gen) && input.len() > 1 {
+    return Err("gen expects at most one directory".into());
+  }
+  if input.len() == 0 && !matches!(mode, Mode::Gen) {
+    return Err("missing input (see --help)".into());
+  }
+  let separate = input.len() > 1 && (driver || matches!(mode, Mode::Link));
+  if let Some(flag) = instrument.filter(|_| separate) {
+    // 各源文件分别编译，每个目标文件都会带上一份诊断与报告的例程
+    return Err(
+      format!(
+        "{} supports only a single input with -c, -S or linking",
+        flag
+      )
+      .into(),
+    );
+  }
+  if input.len() > 1 {
+    match mode {
+      // 第二个输入是标准输入
+      Mode::RunQemu => {}
+      Mode::Link if freestanding => {
+        // 每个源文件都会带上一份裸机的库函数
+        return Err("--freestanding supports only a single input".into());
+      }
+      Mode::Link => {}
+      _ if (driver || matches!(mode, Mode::Preprocess)) && output.is_some() => {
+        return Err("cannot specify -o with -c, -S or -E with multiple files".into());
+      }
+      _ if driver || matches!(mode, Mode::Preprocess) => {}
+      // 其余模式把各个源文件合并为一个程序；源代码行只记录第一个文件
+      _ if debug || asm_comments || source_map || coverage => {
+        let message = "-g, --asm-comments, --source-map and --coverage support only a single input";
+        return Err(message.into());
+      }
+      _ => {}
+    }
   }
-  Ok(ParsedArgs { mode, input, output })
+  Ok(ParsedArgs {
+    mode,
+    input,
+    output,
+    target,
+    cpu,
+    rvc_report,
+    time_passes,
+    stats,
+    verify,
+    verify_opt,
+    jobs,
+    error_format,
+    verbose,
+    watch,
+    save_temps,
+    qemu,
+    jit,
+    seed,
+    count: count.unwrap_or(100),
+    reference,
+    baseline,
+    history,
+    ssh,
+    asm_comments,
+    debug,
+    source_map,
+    emit_obj,
+    driver,
+    pic,
+    const_pool,
+    sanitize,
+    instrument_functions,
+    coverage,
+    auto_timing,
+    extensions,
+    linker,
+    runner,
+    lib_dirs,
+    passes,
+    cfg_after,
+    find_refs,
+    dump_ir_before,
+    dump_ir_after,
+    print_changed,
+  })
 }
@@ -1,100 +1,209 @@
+mod assembler;
+mod c;
+mod cfg;
+mod debug_info;
 mod error;
+mod freestanding;
 mod from_func;
+mod from_global;
 mod from_value;
+mod hosted;
+mod layout;
+mod legalize;
+mod llvm;
+mod mir;
+mod regalloc;
 pub mod riscv;
+mod soft_muldiv;
+mod source_map;
+mod trap;
+mod verify;
+pub mod wasm;
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::VecDeque;
 use std::io::BufWriter;
-use std::sync::RwLock;
 
 use koopa::back::KoopaGenerator;
-use koopa::ir::{Function, Program, Type, TypeKind, Value, ValueKind};
-use once_cell::sync::Lazy;
+use koopa::ir::{Type, ValueKind};
 
+pub use self::assembler::assemble;
+pub use self::c::generate_c;
+pub use self::cfg::cfg_dot;
 use self::error::LabelNotExistError;
-use self::riscv::Riscv;
+pub use self::llvm::generate_llvm;
 use self::riscv::directive::Directive;
+use self::riscv::{Cpu, Riscv, Target};
+pub use self::source_map::{extract as extract_source_map, AsmSourceMap, Origin};
+pub use self::verify::{verify_asm, verify_ir};
+use crate::frontend::{Ir, SourceMap};
+use crate::parallel;
+use crate::stats::Stats;
+use crate::timing::Timings;
 use crate::Result;
 
-static FUNC_NAMES: Lazy<RwLock<HashMap<Function, String>>> = Lazy::new(|| RwLock::default());
-static VAR_NAMES: Lazy<RwLock<HashMap<Value, String>>> = Lazy::new(|| RwLock::default());
+/// Koopa IR 文本的各行，依次用作各全局变量、函数、基本块与指令的汇编注释。每次代码生成各有
+/// 一份，按 IR 中的顺序逐行取出
+struct Listing(VecDeque<String>);
 
-static DEBUG_INFO: Lazy<RwLock<VecDeque<String>>> = Lazy::new(|| RwLock::default());
-
-fn flatten_initializer(ir: &Program, value: Value) -> Vec<i32> {
-  let mut insts = vec![];
-  match ir.borrow_value(value).kind() {
-    ValueKind::Integer(i) => {
-      let i = i.value();
-      insts.push(i);
-    }
-    ValueKind::Aggregate(agg) => {
-      for i in agg.elems() {
-        insts.extend(flatten_initializer(ir, *i));
-      }
-    }
-    _ => panic!("initializer not integer nor aggregate"),
-  }
-  insts
-}
-
-pub fn generate_riscv(ir: &Program) -> Result<Riscv> {
-  Type::set_ptr_size(4);
-  // Prepare debug info
-  {
+impl Listing {
+  fn new(ir: &Ir) -> Result<Self> {
     let buf = BufWriter::new(Vec::new());
     let mut gen = KoopaGenerator::new(buf);
     gen.generate_on(ir)?;
     let bytes = gen.writer().into_inner()?;
     let string = String::from_utf8(bytes)?;
-    for i in string.split("\n") {
-      DEBUG_INFO.write()?.push_back(i.into());
-    }
+    Ok(Self(string.split('\n').map(String::from).collect()))
+  }
+
+  /// 取出下一行；文本的行数与 IR 对不上时报错而不是静默地错位
+  fn next_line(&mut self) -> Result<String> {
+    let line = self.0.pop_front();
+    line.ok_or_else(|| "IR listing has fewer lines than the IR".into())
   }
+}
+
+/// 源代码信息，用于汇编注释与调试信息
+#[derive(Clone, Copy)]
+pub struct SourceInfo<'a> {
+  pub map: &'a SourceMap,
+  /// 源文件路径
+  pub file: &'a str,
+  /// 以注释标注对应的 IR 指令与源代码行
+  pub comments: bool,
+  /// 生成 `.loc` 与 DWARF 调试信息
+  pub debug: bool,
+  /// 记录各条 IR 指令的来源，供 `--source-map` 使用
+  pub origins: bool,
+}
+
+/// 目标指令集之外的代码生成选项
+#[derive(Clone, Copy)]
+pub struct Options {
+  /// 指令开销所依据的处理器（`--mcpu`）
+  pub cpu: Cpu,
+  /// 生成位置无关代码：全局变量的地址经由 GOT 取得
+  pub pic: bool,
+  /// 需要两条指令构造的常量改由各函数的常量池加载
+  pub const_pool: bool,
+  /// 每一遍之后检查 MIR 与汇编的不变量（`--verify`）
+  pub verify: bool,
+  /// 寄存器分配与合法化所用的线程数，0 即处理器的核数（见 [`parallel::map`]）
+  pub jobs: usize,
+}
+
+/// 生成 RISC-V 汇编。各阶段的用时记入 `timings`；给出 `stats` 时（`--stats`）在其中记录各函数的
+/// 栈帧与寄存器压力
+pub fn generate_riscv(
+  ir: &Ir,
+  target: Target,
+  options: Options,
+  source: Option<SourceInfo>,
+  timings: &Timings,
+  stats: Option<&mut Stats>,
+) -> Result<Riscv> {
+  Type::set_ptr_size(target.ptr_size());
+  let mut listing = timings.time("ir-listing", || Listing::new(ir))?;
+
+  let comments = source.is_some_and(|source| source.comments);
+  let debug = source.filter(|source| source.debug);
 
   let mut result = Riscv::new();
+  if let Some(source) = debug {
+    result.add_directive(Directive::File(source.file.into()));
+  }
+  if options.pic {
+    result.add_directive(Directive::Option("pic".into()));
+  }
+  if target.freestanding() {
+    result.extend(freestanding::generate_start());
+  }
   let mut has_global_alloc = false;
 
-  for (&v, vd) in ir.borrow_values().iter() {
-    if let ValueKind::GlobalAlloc(alloc) = vd.kind() {
+  // 按 IR 中的顺序（而非哈希表的顺序）输出全局变量，保证每次输出相同，且与注释一一对应
+  for &v in ir.inst_layout() {
+    let vd = ir.borrow_value(v);
+    if let ValueKind::GlobalAlloc(_) = vd.kind() {
       has_global_alloc = true;
-      result.add_comment(DEBUG_INFO.write()?.pop_front().unwrap());
+      let comment = listing.next_line()?;
+      if comments {
+        result.add_comment(comment);
+      }
       let name = vd
         .name()
         .clone()
         .ok_or(LabelNotExistError("alloc ???".into()))?;
       let name = name[1..].to_string();
-      result.add_directive(Directive::Data);
-      result.add_directive(Directive::Globl(name.clone()));
-      result.add_label(name.clone());
-      let init = alloc.init();
-      if matches!(ir.borrow_value(init).kind(), ValueKind::ZeroInit(_)) {
-        if let TypeKind::Pointer(base) = vd.ty().kind() {
-          result.add_directive(Directive::Zero(base.size() as i32));
-        } else {
-          panic!("global alloc do not have pointer type");
-        }
-      } else {
-        result.add_directive(Directive::Word(flatten_initializer(ir, init)));
-      }
+      result.extend(from_global::generate(ir, v, &name)?);
       result.add_empty();
-
-      VAR_NAMES.write()?.insert(v, name);
     }
   }
   if has_global_alloc {
-    DEBUG_INFO.write()?.pop_front().unwrap();
-  }
-
-  for (&f, fd) in ir.funcs() {
-    let func_name = &fd.name()[1..];
-    FUNC_NAMES.write()?.insert(f, func_name.into());
+    listing.next_line()?;
   }
 
+  // 指令选择须访问 IR，依次进行；其后各函数相互独立，并行地分配寄存器
+  let mut debug_funcs = vec![];
+  let mut mirs = vec![];
   for &func in ir.func_layout() {
-    let asm = from_func::generate(ir, func)?;
-    result.extend(asm);
+    let isel = || from_func::generate(ir, func, &mut listing, target, options, source);
+    let (mir, frame) = timings.time("isel", isel)?;
+    if options.verify {
+      verify::verify_mir(&mir, &ir.func(func).name()[1..])?;
+    }
+    // 不统计时不计算寄存器压力
+    let frame = frame.filter(|_| stats.is_some());
+    mirs.push((func, mir, frame));
+    if let Some(source) = debug {
+      if let Some(line) = source.map.func_line(func) {
+        let name = ir.func(func).name()[1..].to_string();
+        let attrs = &ir.annotations.func_attrs;
+        let in_text = attrs
+          .get(&func)
+          .and_then(|attrs| attrs.section.as_ref())
+          .is_none();
+        debug_funcs.push(debug_info::DebugFunc {
+          name,
+          line,
+          in_text,
+        });
+      }
+    }
+  }
+  // 错误（`Box<dyn Error>`）不能跨线程传递，以其文本代替
+  let funcs = parallel::map(options.jobs, mirs, |(func, mir, mut frame)| {
+    let allocate = || regalloc::allocate(mir, frame.as_mut());
+    let riscv = timings
+      .time("regalloc", allocate)
+      .map_err(|e| e.to_string())?;
+    if options.verify {
+      verify::verify_asm(&riscv, "regalloc", false).map_err(|e| e.to_string())?;
+    }
+    let riscv = timings.time("legalize", || legalize::legalize(riscv));
+    if options.verify {
+      verify::verify_asm(&riscv, "legalize", true).map_err(|e| e.to_string())?;
+    }
+    Ok::<_, String>((func, riscv, frame))
+  });
+  let mut stats = stats;
+  for func in funcs {
+    let (func, riscv, frame) = func?;
+    result.extend(riscv);
+    if let (Some(stats), Some(frame)) = (stats.as_deref_mut(), frame) {
+      stats.record(&ir.func(func).name()[1..], frame);
+    }
+  }
+  let helpers = soft_muldiv::generate_helpers(&result, target.xlen);
+  result.extend(helpers);
+  let traps = trap::generate_helpers(&result, target);
+  result.extend(traps);
+  let syscalls = hosted::generate_syscalls(&result, target);
+  result.extend(syscalls);
+  if target.freestanding() {
+    result.extend(freestanding::generate_sbi_calls());
   }
-  
+  if let Some(source) = debug {
+    result.extend(debug_info::generate(source.file, &debug_funcs, target.xlen));
+  }
+
   Ok(result)
 }
@@ -0,0 +1,655 @@
+//! 集成汇编器：将 [`Riscv`] 直接编码为可重定位的 ELF 目标文件，不依赖外部的 `as`。
+//!
+//! 伪指令的展开方式与 [`Inst`] 输出的汇编文本保持一致。
+
+mod elf;
+mod encode;
+
+use std::collections::{HashMap, HashSet};
+
+use self::elf::{ObjectFile, Reloc, Symbol, SymbolBind, SymbolType};
+use self::encode::*;
+use super::error::AssembleError;
+use super::riscv::directive::{self, Directive};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem, Xlen};
+use crate::Result;
+
+const SECTION_TEXT: usize = 0;
+const SECTION_DATA: usize = 1;
+const SECTION_BSS: usize = 2;
+const SECTION_RODATA: usize = 3;
+/// 其后是 `.section 名字,"ax"` 给出的其他代码段，按出现的顺序
+const SECTION_EXTRA: usize = 4;
+
+const R_RISCV_BRANCH: u32 = 16;
+const R_RISCV_JAL: u32 = 17;
+const R_RISCV_CALL_PLT: u32 = 19;
+const R_RISCV_GOT_HI20: u32 = 20;
+const R_RISCV_PCREL_HI20: u32 = 23;
+const R_RISCV_PCREL_LO12_I: u32 = 24;
+const R_RISCV_HI20: u32 = 26;
+const R_RISCV_LO12_I: u32 = 27;
+const R_RISCV_LO12_S: u32 = 28;
+
+/// 条件分支的可达范围（字节）
+const BRANCH_RANGE: std::ops::Range<i64> = -4096..4096;
+/// `jal` 的可达范围（字节）
+const JAL_RANGE: std::ops::Range<i64> = -(1 << 20)..(1 << 20);
+
+/// 编码后大小待定或需要重定位的指令片段
+#[derive(Debug)]
+enum Piece {
+  Word(u32),
+  Half(u16),
+  /// `beq`/`bne rs, zero, label`；超出范围时改写为反向分支跳过一条 `jal`
+  Branch {
+    eq: bool,
+    rs: Reg,
+    label: String,
+  },
+  /// `jal zero, label`
+  Jump(String),
+  /// `auipc rd, %pcrel_hi(label)` + `addi rd, rd, %pcrel_lo(...)`；
+  /// `got` 时改为经由 GOT：`auipc rd, %got_pcrel_hi(label)` + `lw`/`ld rd, %pcrel_lo(...)(rd)`
+  La {
+    rd: Reg,
+    label: String,
+    got: bool,
+  },
+  /// `auipc ra, 0` + `jalr ra, 0(ra)`，带 `R_RISCV_CALL_PLT`
+  Call(String),
+  /// 立即数为零的指令，带针对 `label` 绝对地址的重定位（`%hi`/`%lo`）
+  Abs {
+    word: u32,
+    label: String,
+    reloc: u32,
+  },
+}
+
+#[derive(Debug)]
+enum Entry {
+  Label(String),
+  Piece(Piece),
+  Bytes(Vec<u8>),
+  Align(u32),
+}
+
+/// `li rd, imm` 的展开
+fn lower_li(rd: Reg, imm: i32, xlen: Xlen) -> Vec<Piece> {
+  if (-2048..2048).contains(&imm) {
+    return vec![Piece::Word(i_type(OP_IMM, 0b000, rd, Reg::Zero, imm))];
+  }
+  let (hi, lo) = split_hi_lo(imm);
+  let mut pieces = vec![Piece::Word(u_type(OP_LUI, rd, hi))];
+  if lo != 0 {
+    let opcode = match xlen {
+      Xlen::Rv32 => OP_IMM,
+      Xlen::Rv64 => OP_IMM_32,
+    };
+    pieces.push(Piece::Word(i_type(opcode, 0b000, rd, rd, lo)));
+  }
+  pieces
+}
+
+fn lower_mem(load: bool, funct3: u32, reg: Reg, offset: i32, base: Reg) -> Vec<Piece> {
+  vec![Piece::Word(if load {
+    i_type(OP_LOAD, funct3, reg, base, offset)
+  } else {
+    s_type(OP_STORE, funct3, base, reg, offset)
+  })]
+}
+
+fn lower_imm(funct3: u32, rd: Reg, rs: Reg, imm: i32) -> Vec<Piece> {
+  vec![Piece::Word(i_type(OP_IMM, funct3, rd, rs, imm))]
+}
+
+fn abs(word: u32, label: &str, reloc: u32) -> Vec<Piece> {
+  vec![Piece::Abs {
+    word,
+    label: label.into(),
+    reloc,
+  }]
+}
+
+fn lower(inst: &Inst, xlen: Xlen, pic: bool) -> Vec<Piece> {
+  let op =
+    |funct3, funct7, rd, rs1, rs2| vec![Piece::Word(r_type(OP, funct3, funct7, rd, rs1, rs2))];
+  let op32 =
+    |funct3, funct7, rd, rs1, rs2| vec![Piece::Word(r_type(OP_32, funct3, funct7, rd, rs1, rs2))];
+  match inst {
+    Inst::Beqz(rs, label) => vec![Piece::Branch {
+      eq: true,
+      rs: *rs,
+      label: label.clone(),
+    }],
+    Inst::Bnez(rs, label) => vec![Piece::Branch {
+      eq: false,
+      rs: *rs,
+      label: label.clone(),
+    }],
+    Inst::J(label) => vec![Piece::Jump(label.clone())],
+    Inst::Call(label) => vec![Piece::Call(label.clone())],
+    Inst::Asm(..) => unreachable!("inline assembly is rejected before lowering"),
+    Inst::Ret => vec![Piece::Word(i_type(OP_JALR, 0b000, Reg::Zero, Reg::Ra, 0))],
+    Inst::Ecall => vec![Piece::Word(i_type(
+      OP_SYSTEM,
+      0b000,
+      Reg::Zero,
+      Reg::Zero,
+      0,
+    ))],
+    Inst::Lw(rd, offset, rs) => lower_mem(true, 0b010, *rd, *offset, *rs),
+    Inst::Ld(rd, offset, rs) => lower_mem(true, 0b011, *rd, *offset, *rs),
+    Inst::Sw(rs2, offset, rs1) => lower_mem(false, 0b010, *rs2, *offset, *rs1),
+    Inst::Sd(rs2, offset, rs1) => lower_mem(false, 0b011, *rs2, *offset, *rs1),
+    Inst::Add(rd, rs1, rs2) => op(0b000, 0, *rd, *rs1, *rs2),
+    Inst::Addw(rd, rs1, rs2) => op32(0b000, 0, *rd, *rs1, *rs2),
+    Inst::Addi(rd, rs, imm) => lower_imm(0b000, *rd, *rs, *imm),
+    Inst::Sub(rd, rs1, rs2) => op(0b000, 0x20, *rd, *rs1, *rs2),
+    Inst::Subw(rd, rs1, rs2) => op32(0b000, 0x20, *rd, *rs1, *rs2),
+    Inst::Slt(rd, rs1, rs2) => op(0b010, 0, *rd, *rs1, *rs2),
+    Inst::Sltu(rd, rs1, rs2) => op(0b011, 0, *rd, *rs1, *rs2),
+    Inst::Sgt(rd, rs1, rs2) => op(0b010, 0, *rd, *rs2, *rs1),
+    Inst::Seqz(rd, rs) => vec![Piece::Word(i_type(OP_IMM, 0b011, *rd, *rs, 1))],
+    Inst::Snez(rd, rs) => op(0b011, 0, *rd, Reg::Zero, *rs),
+    Inst::Xor(rd, rs1, rs2) => op(0b100, 0, *rd, *rs1, *rs2),
+    Inst::Xori(rd, rs, imm) => lower_imm(0b100, *rd, *rs, *imm),
+    Inst::Or(rd, rs1, rs2) => op(0b110, 0, *rd, *rs1, *rs2),
+    Inst::Ori(rd, rs, imm) => lower_imm(0b110, *rd, *rs, *imm),
+    Inst::And(rd, rs1, rs2) => op(0b111, 0, *rd, *rs1, *rs2),
+    Inst::Andi(rd, rs, imm) => lower_imm(0b111, *rd, *rs, *imm),
+    Inst::Sll(rd, rs1, rs2) => op(0b001, 0, *rd, *rs1, *rs2),
+    Inst::Slli(rd, rs, imm) => lower_imm(0b001, *rd, *rs, *imm),
+    Inst::Srl(rd, rs1, rs2) => op(0b101, 0, *rd, *rs1, *rs2),
+    Inst::Srli(rd, rs, imm) => lower_imm(0b101, *rd, *rs, *imm),
+    Inst::Sra(rd, rs1, rs2) => op(0b101, 0x20, *rd, *rs1, *rs2),
+    Inst::Mul(rd, rs1, rs2) => op(0b000, 1, *rd, *rs1, *rs2),
+    Inst::Mulw(rd, rs1, rs2) => op32(0b000, 1, *rd, *rs1, *rs2),
+    Inst::Div(rd, rs1, rs2) => op(0b100, 1, *rd, *rs1, *rs2),
+    Inst::Divw(rd, rs1, rs2) => op32(0b100, 1, *rd, *rs1, *rs2),
+    Inst::Rem(rd, rs1, rs2) => op(0b110, 1, *rd, *rs1, *rs2),
+    Inst::Remw(rd, rs1, rs2) => op32(0b110, 1, *rd, *rs1, *rs2),
+    Inst::SextW(rd, rs) => vec![Piece::Word(i_type(OP_IMM_32, 0b000, *rd, *rs, 0))],
+    Inst::Li(rd, imm) => lower_li(*rd, *imm, xlen),
+    Inst::La(rd, label) => vec![Piece::La {
+      rd: *rd,
+      label: label.clone(),
+      got: pic,
+    }],
+    Inst::Mv(rd, rs) => vec![Piece::Word(i_type(OP_IMM, 0b000, *rd, *rs, 0))],
+    Inst::Lui(rd, label) => abs(u_type(OP_LUI, *rd, 0), label, R_RISCV_HI20),
+    Inst::AddiLo(rd, rs, label) => abs(i_type(OP_IMM, 0b000, *rd, *rs, 0), label, R_RISCV_LO12_I),
+    Inst::LwLo(rd, label, rs) => abs(i_type(OP_LOAD, 0b010, *rd, *rs, 0), label, R_RISCV_LO12_I),
+    Inst::LdLo(rd, label, rs) => abs(i_type(OP_LOAD, 0b011, *rd, *rs, 0), label, R_RISCV_LO12_I),
+    Inst::SwLo(rs2, label, rs1) => abs(
+      s_type(OP_STORE, 0b010, *rs1, *rs2, 0),
+      label,
+      R_RISCV_LO12_S,
+    ),
+    Inst::SdLo(rs2, label, rs1) => abs(
+      s_type(OP_STORE, 0b011, *rs1, *rs2, 0),
+      label,
+      R_RISCV_LO12_S,
+    ),
+  }
+}
+
+/// 汇编过程中的全部状态
+struct Assembler {
+  xlen: Xlen,
+  rvc: bool,
+  /// `.option pic`：`la` 经由 GOT 取地址
+  pic: bool,
+  /// 各段的内容，下标为 `SECTION_*`
+  sections: Vec<Vec<Entry>>,
+  /// 其他代码段的名字，依次是第 `SECTION_EXTRA` 段及其后各段
+  code_sections: Vec<String>,
+  globals: HashSet<String>,
+  types: HashMap<String, directive::SymbolType>,
+  sizes: HashMap<String, usize>,
+  /// `.size label, end-label`：label -> end
+  size_to: HashMap<String, String>,
+  /// 需要展开为长跳转的分支：(段, 条目下标)
+  long_branches: HashSet<(usize, usize)>,
+  /// 标号定义位置：标号 -> (段, 偏移量)
+  labels: HashMap<String, (usize, u64)>,
+}
+
+impl Assembler {
+  fn new(xlen: Xlen) -> Self {
+    Self {
+      xlen,
+      rvc: false,
+      pic: false,
+      sections: (0..SECTION_EXTRA).map(|_| vec![]).collect(),
+      code_sections: vec![],
+      globals: HashSet::new(),
+      types: HashMap::new(),
+      sizes: HashMap::new(),
+      size_to: HashMap::new(),
+      long_branches: HashSet::new(),
+      labels: HashMap::new(),
+    }
+  }
+
+  /// 段中是否是代码
+  fn is_code(section: usize) -> bool {
+    section == SECTION_TEXT || section >= SECTION_EXTRA
+  }
+
+  /// `.section` 所指的段：名字后可有标志，如 `.text.hot,"ax",@progbits`；
+  /// 数据段只支持固定的几个，带有 `x` 标志的其他段是代码段
+  fn section(&mut self, spec: &str) -> Result<usize> {
+    let mut parts = spec.split(',');
+    let name = parts.next().unwrap_or_default().trim();
+    let flags = parts.next().unwrap_or_default().trim().trim_matches('"');
+    let section = match name {
+      ".text" => SECTION_TEXT,
+      ".data" => SECTION_DATA,
+      ".bss" => SECTION_BSS,
+      ".rodata" => SECTION_RODATA,
+      _ if flags.contains('x') => match self.code_sections.iter().position(|s| s == name) {
+        Some(index) => SECTION_EXTRA + index,
+        None => {
+          self.code_sections.push(name.into());
+          self.sections.push(vec![]);
+          self.sections.len() - 1
+        }
+      },
+      _ => return Err(AssembleError(format!("unsupported section {}", spec)).into()),
+    };
+    Ok(section)
+  }
+
+  fn collect(&mut self, riscv: &Riscv) -> Result<()> {
+    let mut section = SECTION_TEXT;
+    for item in &riscv.0 {
+      if let RiscvItem::Directive(Directive::Section(spec)) = item {
+        section = self.section(spec)?;
+        continue;
+      }
+      let entries = &mut self.sections[section];
+      match item {
+        RiscvItem::Label(label) => entries.push(Entry::Label(label.clone())),
+        RiscvItem::Inst(inst) => {
+          if !Self::is_code(section) {
+            return Err(
+              AssembleError(format!(
+                "instruction outside .text: {}",
+                inst.to_string().trim()
+              ))
+              .into(),
+            );
+          }
+          if let Inst::Asm(..) = inst {
+            return Err(
+              AssembleError("inline assembly is not supported by the built-in assembler".into())
+                .into(),
+            );
+          }
+          if !inst.imm_fits() {
+            let inst = inst.to_string();
+            return Err(AssembleError(format!("immediate out of range: {}", inst.trim())).into());
+          }
+          entries.extend(
+            lower(inst, self.xlen, self.pic)
+              .into_iter()
+              .map(Entry::Piece),
+          );
+        }
+        RiscvItem::Compressed(inst) => {
+          if !Self::is_code(section) {
+            return Err(
+              AssembleError(format!(
+                "instruction outside .text: {}",
+                inst.to_string().trim()
+              ))
+              .into(),
+            );
+          }
+          entries.push(Entry::Piece(Piece::Half(compressed(inst))));
+          self.rvc = true;
+        }
+        RiscvItem::Directive(directive) => match directive {
+          Directive::Text => section = SECTION_TEXT,
+          Directive::Data => section = SECTION_DATA,
+          Directive::Bss => section = SECTION_BSS,
+          // 切换到的段可能是新的代码段，在取得当前段之前处理
+          Directive::Section(_) => unreachable!("sections are switched above"),
+          Directive::Globl(label) => {
+            self.globals.insert(label.clone());
+          }
+          Directive::Local(label) => {
+            self.globals.remove(label);
+          }
+          Directive::Type(label, ty) => {
+            self.types.insert(label.clone(), *ty);
+          }
+          Directive::Align(pow) => entries.push(Entry::Align(*pow)),
+          Directive::Size(label, size) => {
+            self.sizes.insert(label.clone(), *size);
+          }
+          Directive::SizeTo(label, end) => {
+            self.size_to.insert(label.clone(), end.clone());
+          }
+          Directive::Zero(n) => entries.push(Entry::Bytes(vec![0; *n as usize])),
+          Directive::Word(words) => {
+            entries.push(Entry::Bytes(
+              words.iter().flat_map(|w| w.to_le_bytes()).collect(),
+            ));
+          }
+          Directive::Option(option) => match option.as_str() {
+            "rvc" => self.rvc = true,
+            "norvc" => {}
+            "pic" => self.pic = true,
+            "nopic" => self.pic = false,
+            _ => return Err(AssembleError(format!("unsupported .option {}", option)).into()),
+          },
+          // 来源标记在输出之前去除，照常汇编时忽略；volatile 的标记只约束优化
+          Directive::Origin(_) | Directive::Volatile => {}
+          Directive::File(_)
+          | Directive::Loc(_)
+          | Directive::Byte(_)
+          | Directive::Half(_)
+          | Directive::Uleb128(_)
+          | Directive::Asciz(_)
+          | Directive::Addr(..) => {
+            let directive = directive.to_string();
+            return Err(
+              AssembleError(format!("unsupported directive {}", directive.trim())).into(),
+            );
+          }
+        },
+        RiscvItem::Comment(_) | RiscvItem::Empty => {}
+      }
+    }
+    Ok(())
+  }
+
+  fn align_pad(&self, offset: u64, pow: u32) -> u64 {
+    let align = 1u64 << pow;
+    (align - offset % align) % align
+  }
+
+  fn piece_size(&self, section: usize, index: usize, piece: &Piece) -> u64 {
+    match piece {
+      Piece::Half(_) => 2,
+      Piece::Word(_) | Piece::Jump(_) | Piece::Abs { .. } => 4,
+      Piece::Branch { .. } if self.long_branches.contains(&(section, index)) => 8,
+      Piece::Branch { .. } => 4,
+      Piece::La { .. } | Piece::Call(_) => 8,
+    }
+  }
+
+  /// 计算所有标号的位置；返回各段的大小
+  fn layout(&mut self) -> Result<Vec<u64>> {
+    let mut sizes = vec![0; self.sections.len()];
+    let mut labels = HashMap::new();
+    for (section, entries) in self.sections.iter().enumerate() {
+      let mut offset = 0;
+      for (index, entry) in entries.iter().enumerate() {
+        match entry {
+          Entry::Label(label) => {
+            if labels.insert(label.clone(), (section, offset)).is_some() {
+              return Err(AssembleError(format!("duplicate label {}", label)).into());
+            }
+          }
+          Entry::Piece(piece) => offset += self.piece_size(section, index, piece),
+          Entry::Bytes(bytes) => offset += bytes.len() as u64,
+          Entry::Align(pow) => offset += self.align_pad(offset, *pow),
+        }
+      }
+      sizes[section] = offset;
+    }
+    self.labels = labels;
+    Ok(sizes)
+  }
+
+  /// 同段内标号相对于 `offset` 的距离；标号位于其他段或未定义时返回 `None`
+  fn distance(&self, section: usize, offset: u64, label: &str) -> Option<i64> {
+    match self.labels.get(label) {
+      Some(&(s, target)) if s == section => Some(target as i64 - offset as i64),
+      _ => None,
+    }
+  }
+
+  /// 反复布局，直到不再有分支需要展开
+  fn relax(&mut self) -> Result<Vec<u64>> {
+    loop {
+      let sizes = self.layout()?;
+      let mut grown = vec![];
+      for (section, entries) in self.sections.iter().enumerate() {
+        let mut offset = 0;
+        for (index, entry) in entries.iter().enumerate() {
+          match entry {
+            Entry::Piece(piece) => {
+              if let Piece::Branch { label, .. } = piece {
+                let out_of_range = self
+                  .distance(section, offset, label)
+                  .is_some_and(|d| !BRANCH_RANGE.contains(&d));
+                if out_of_range && !self.long_branches.contains(&(section, index)) {
+                  grown.push((section, index));
+                }
+              }
+              offset += self.piece_size(section, index, piece);
+            }
+            Entry::Bytes(bytes) => offset += bytes.len() as u64,
+            Entry::Align(pow) => offset += self.align_pad(offset, *pow),
+            Entry::Label(_) => {}
+          }
+        }
+      }
+      if grown.is_empty() {
+        return Ok(sizes);
+      }
+      self.long_branches.extend(grown);
+    }
+  }
+
+  fn emit(&self, sizes: Vec<u64>) -> Result<ObjectFile> {
+    let mut object = ObjectFile::new(self.xlen, self.rvc, self.code_sections.clone());
+    let mut pcrel_labels = vec![];
+    for (section, entries) in self.sections.iter().enumerate() {
+      let mut data = Vec::with_capacity(sizes[section] as usize);
+      let push = |data: &mut Vec<u8>, word: u32| data.extend(word.to_le_bytes());
+      for (index, entry) in entries.iter().enumerate() {
+        let offset = data.len() as u64;
+        match entry {
+          Entry::Label(_) => {}
+          Entry::Bytes(bytes) => data.extend(bytes),
+          Entry::Align(pow) => {
+            let pad = self.align_pad(offset, *pow) as usize;
+            if Self::is_code(section) {
+              // 以 nop 填充
+              let mut rest = pad;
+              if rest % 4 == 2 {
+                data.extend(0x0001u16.to_le_bytes());
+                rest -= 2;
+              }
+              for _ in 0..rest / 4 {
+                push(&mut data, i_type(OP_IMM, 0b000, Reg::Zero, Reg::Zero, 0));
+              }
+            } else {
+              data.extend(vec![0; pad]);
+            }
+          }
+          Entry::Piece(piece) => match piece {
+            Piece::Word(word) => push(&mut data, *word),
+            Piece::Half(half) => data.extend(half.to_le_bytes()),
+            Piece::Branch { eq, rs, label } => {
+              let funct3 = if *eq { 0b000 } else { 0b001 };
+              if self.long_branches.contains(&(section, index)) {
+                // bne/beq rs, zero, 8; jal zero, label
+                push(&mut data, b_type(funct3 ^ 1, *rs, Reg::Zero, 8));
+                let word = self.jump(&mut object, section, offset + 4, label)?;
+                push(&mut data, word);
+              } else {
+                let imm = match self.distance(section, offset, label) {
+                  Some(d) => d as i32,
+                  None => {
+                    let reloc = Reloc::new(section, offset, label, R_RISCV_BRANCH);
+                    object.relocs.push(reloc);
+                    0
+                  }
+                };
+                push(&mut data, b_type(funct3, *rs, Reg::Zero, imm));
+              }
+            }
+            Piece::Jump(label) => {
+              let word = self.jump(&mut object, section, offset, label)?;
+              push(&mut data, word);
+            }
+            Piece::La { rd, label, got } => {
+              let pcrel = format!(".Lpcrel_hi{}", pcrel_labels.len());
+              pcrel_labels.push((pcrel.clone(), section, offset));
+              let hi = if *got {
+                R_RISCV_GOT_HI20
+              } else {
+                R_RISCV_PCREL_HI20
+              };
+              object.relocs.push(Reloc::new(section, offset, label, hi));
+              let lo = Reloc::new(section, offset + 4, &pcrel, R_RISCV_PCREL_LO12_I);
+              object.relocs.push(lo);
+              push(&mut data, u_type(OP_AUIPC, *rd, 0));
+              push(
+                &mut data,
+                if *got {
+                  let funct3 = match self.xlen {
+                    Xlen::Rv32 => 0b010,
+                    Xlen::Rv64 => 0b011,
+                  };
+                  i_type(OP_LOAD, funct3, *rd, *rd, 0)
+                } else {
+                  i_type(OP_IMM, 0b000, *rd, *rd, 0)
+                },
+              );
+            }
+            Piece::Abs { word, label, reloc } => {
+              object
+                .relocs
+                .push(Reloc::new(section, offset, label, *reloc));
+              push(&mut data, *word);
+            }
+            Piece::Call(label) => {
+              // 同段内的局部标号可直接解析；全局符号留给链接器
+              let (hi, lo) = match self.distance(section, offset, label) {
+                Some(d) if !self.globals.contains(label) => split_hi_lo(d as i32),
+                _ => {
+                  let reloc = Reloc::new(section, offset, label, R_RISCV_CALL_PLT);
+                  object.relocs.push(reloc);
+                  (0, 0)
+                }
+              };
+              push(&mut data, u_type(OP_AUIPC, Reg::Ra, hi));
+              push(&mut data, i_type(OP_JALR, 0b000, Reg::Ra, Reg::Ra, lo));
+            }
+          },
+        }
+      }
+      if section == SECTION_BSS {
+        object.bss_size = data.len() as u64;
+      } else {
+        object.sections[section] = data;
+      }
+    }
+    self.symbols(&mut object, pcrel_labels);
+    Ok(object)
+  }
+
+  /// `jal zero, label`
+  fn jump(&self, object: &mut ObjectFile, section: usize, offset: u64, label: &str) -> Result<u32> {
+    let imm = match self.distance(section, offset, label) {
+      Some(d) if JAL_RANGE.contains(&d) => d as i32,
+      Some(_) => return Err(AssembleError(format!("jump target {} out of range", label)).into()),
+      None => {
+        object
+          .relocs
+          .push(Reloc::new(section, offset, label, R_RISCV_JAL));
+        0
+      }
+    };
+    Ok(j_type(Reg::Zero, imm))
+  }
+
+  fn symbols(&self, object: &mut ObjectFile, pcrel_labels: Vec<(String, usize, u64)>) {
+    // `.L` 开头的标号只在汇编时使用，除非被重定位引用，不进入符号表
+    let referenced: HashSet<_> = object
+      .relocs
+      .iter()
+      .map(|reloc| reloc.symbol.as_str())
+      .collect();
+    let mut defined: Vec<_> = self
+      .labels
+      .iter()
+      .filter(|(name, _)| !name.starts_with(".L") || referenced.contains(name.as_str()))
+      .collect();
+    defined.sort_by_key(|(name, &(section, offset))| (section, offset, name.as_str()));
+    let symbol = |name: &str, section: usize, offset: u64| {
+      let global = self.globals.contains(name);
+      let ty = match self.types.get(name) {
+        Some(directive::SymbolType::Function) => SymbolType::Func,
+        Some(directive::SymbolType::Object) => SymbolType::Object,
+        None => SymbolType::NoType,
+      };
+      let size = match self.size_to.get(name).and_then(|end| self.labels.get(end)) {
+        Some(&(_, end)) => end - offset,
+        None => self.sizes.get(name).copied().unwrap_or(0) as u64,
+      };
+      Symbol {
+        name: name.into(),
+        value: offset,
+        size,
+        bind: if global {
+          SymbolBind::Global
+        } else {
+          SymbolBind::Local
+        },
+        ty,
+        section: Some(section),
+      }
+    };
+    for (name, &(section, offset)) in &defined {
+      if !self.globals.contains(name.as_str()) {
+        object.symbols.push(symbol(name, section, offset));
+      }
+    }
+    for (name, section, offset) in pcrel_labels {
+      object.symbols.push(symbol(&name, section, offset));
+    }
+    for (name, &(section, offset)) in &defined {
+      if self.globals.contains(name.as_str()) {
+        object.symbols.push(symbol(name, section, offset));
+      }
+    }
+    let mut undefined: Vec<_> = object
+      .relocs
+      .iter()
+      .map(|reloc| reloc.symbol.clone())
+      .filter(|name| !self.labels.contains_key(name) && !name.starts_with(".Lpcrel_hi"))
+      .collect::<HashSet<_>>()
+      .into_iter()
+      .collect();
+    undefined.sort();
+    for name in undefined {
+      object.symbols.push(Symbol {
+        name,
+        value: 0,
+        size: 0,
+        bind: SymbolBind::Global,
+        ty: SymbolType::NoType,
+        section: None,
+      });
+    }
+  }
+}
+
+/// 将汇编程序编码为 ELF 可重定位目标文件
+pub fn assemble(riscv: &Riscv, xlen: Xlen) -> Result<Vec<u8>> {
+  let mut assembler = Assembler::new(xlen);
+  assembler.collect(riscv)?;
+  let sizes = assembler.relax()?;
+  let object = assembler.emit(sizes)?;
+  Ok(object.to_bytes())
+}
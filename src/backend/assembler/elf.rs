@@ -0,0 +1,356 @@
+// https://refspecs.linuxfoundation.org/elf/gabi4+/contents.html
+// https://github.com/riscv-non-isa/riscv-elf-psabi-doc
+
+use std::collections::HashMap;
+
+use super::{SECTION_BSS, SECTION_DATA, SECTION_EXTRA, SECTION_RODATA, SECTION_TEXT};
+use crate::backend::riscv::Xlen;
+
+const EM_RISCV: u16 = 243;
+const ET_REL: u16 = 1;
+const EF_RISCV_RVC: u32 = 0x1;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_SYMTAB: u32 = 2;
+const SHT_STRTAB: u32 = 3;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+const SHF_INFO_LINK: u64 = 0x40;
+
+/// 节头表中各节的下标；有其他代码段时，每个代码段另有一个节及其重定位表，依次后移
+const SHNDX_SYMTAB: u32 = 6;
+const SHNDX_STRTAB: u32 = 7;
+const SHNDX_SHSTRTAB: u32 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolBind {
+  Local = 0,
+  Global = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolType {
+  NoType = 0,
+  Object = 1,
+  Func = 2,
+}
+
+#[derive(Debug)]
+pub struct Symbol {
+  pub name: String,
+  pub value: u64,
+  pub size: u64,
+  pub bind: SymbolBind,
+  pub ty: SymbolType,
+  /// 所在段；`None` 表示未定义
+  pub section: Option<usize>,
+}
+
+/// 代码段中的一处重定位
+#[derive(Debug)]
+pub struct Reloc {
+  /// 所在段
+  pub section: usize,
+  pub offset: u64,
+  pub symbol: String,
+  pub kind: u32,
+}
+
+impl Reloc {
+  pub fn new(section: usize, offset: u64, symbol: &str, kind: u32) -> Self {
+    Self {
+      section,
+      offset,
+      symbol: symbol.into(),
+      kind,
+    }
+  }
+}
+
+pub struct ObjectFile {
+  xlen: Xlen,
+  rvc: bool,
+  /// 各段内容，下标为 `SECTION_*`；`.bss` 只记录大小
+  pub sections: Vec<Vec<u8>>,
+  /// 其他代码段的名字，依次是第 `SECTION_EXTRA` 段及其后各段
+  code_sections: Vec<String>,
+  pub bss_size: u64,
+  pub symbols: Vec<Symbol>,
+  pub relocs: Vec<Reloc>,
+}
+
+/// 按 ELF 类别（32/64 位）写出小端序数据
+struct Writer {
+  buf: Vec<u8>,
+  is64: bool,
+}
+
+impl Writer {
+  fn u8(&mut self, v: u8) {
+    self.buf.push(v);
+  }
+  fn u16(&mut self, v: u16) {
+    self.buf.extend(v.to_le_bytes());
+  }
+  fn u32(&mut self, v: u32) {
+    self.buf.extend(v.to_le_bytes());
+  }
+  /// `ElfN_Addr`、`ElfN_Off` 等随类别变化宽度的字段
+  fn word(&mut self, v: u64) {
+    if self.is64 {
+      self.buf.extend(v.to_le_bytes());
+    } else {
+      self.buf.extend((v as u32).to_le_bytes());
+    }
+  }
+  fn align(&mut self, align: usize) {
+    let len = self.buf.len().next_multiple_of(align);
+    self.buf.resize(len, 0);
+  }
+}
+
+/// 字符串表
+struct StrTab(Vec<u8>);
+
+impl StrTab {
+  fn new() -> Self {
+    Self(vec![0])
+  }
+  fn add(&mut self, s: &str) -> u32 {
+    let index = self.0.len() as u32;
+    self.0.extend(s.as_bytes());
+    self.0.push(0);
+    index
+  }
+}
+
+struct SectionHeader {
+  name: u32,
+  ty: u32,
+  flags: u64,
+  offset: u64,
+  size: u64,
+  link: u32,
+  info: u32,
+  align: u64,
+  entsize: u64,
+}
+
+impl ObjectFile {
+  pub fn new(xlen: Xlen, rvc: bool, code_sections: Vec<String>) -> Self {
+    Self {
+      xlen,
+      rvc,
+      sections: vec![vec![]; SECTION_EXTRA + code_sections.len()],
+      code_sections,
+      bss_size: 0,
+      symbols: vec![],
+      relocs: vec![],
+    }
+  }
+
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let is64 = self.xlen == Xlen::Rv64;
+    let word = self.xlen.bytes();
+    let (ehsize, shentsize, symentsize, relaentsize) = if is64 {
+      (64, 64, 24, 24)
+    } else {
+      (52, 40, 16, 12)
+    };
+
+    let mut shstrtab = StrTab::new();
+    let mut strtab = StrTab::new();
+    let mut w = Writer {
+      buf: vec![0; ehsize],
+      is64,
+    };
+    let mut headers = vec![];
+
+    // 各数据段
+    let text_align = if self.rvc { 2 } else { 4 };
+    let code = SHF_ALLOC | SHF_EXECINSTR;
+    let mut sections = vec![
+      (".text", SECTION_TEXT, code, text_align),
+      (".data", SECTION_DATA, SHF_ALLOC | SHF_WRITE, word),
+      (".bss", SECTION_BSS, SHF_ALLOC | SHF_WRITE, word),
+      (".rodata", SECTION_RODATA, SHF_ALLOC, word),
+    ];
+    for (i, name) in self.code_sections.iter().enumerate() {
+      sections.push((name, SECTION_EXTRA + i, code, text_align));
+    }
+    for (name, index, flags, align) in sections {
+      w.align(align);
+      let offset = w.buf.len() as u64;
+      let (ty, size) = if index == SECTION_BSS {
+        (SHT_NOBITS, self.bss_size)
+      } else {
+        w.buf.extend(&self.sections[index]);
+        (SHT_PROGBITS, self.sections[index].len() as u64)
+      };
+      headers.push(SectionHeader {
+        name: shstrtab.add(name),
+        ty,
+        flags,
+        offset,
+        size,
+        link: 0,
+        info: 0,
+        align: align as u64,
+        entsize: 0,
+      });
+    }
+
+    // 符号表：第 0 项为空符号，局部符号须排在全局符号之前
+    let mut indices = HashMap::new();
+    let mut symtab = Writer { buf: vec![], is64 };
+    let mut first_global = None;
+    let null = Symbol {
+      name: String::new(),
+      value: 0,
+      size: 0,
+      bind: SymbolBind::Local,
+      ty: SymbolType::NoType,
+      section: None,
+    };
+    for (index, symbol) in std::iter::once(&null).chain(&self.symbols).enumerate() {
+      if symbol.bind == SymbolBind::Global && first_global.is_none() {
+        first_global = Some(index);
+      }
+      let name = if symbol.name.is_empty() {
+        0
+      } else {
+        strtab.add(&symbol.name)
+      };
+      indices.insert(symbol.name.as_str(), index as u64);
+      let info = (symbol.bind as u8) << 4 | symbol.ty as u8;
+      let shndx = symbol.section.map_or(0, |s| s as u16 + 1);
+      symtab.u32(name);
+      if is64 {
+        symtab.u8(info);
+        symtab.u8(0);
+        symtab.u16(shndx);
+        symtab.word(symbol.value);
+        symtab.word(symbol.size);
+      } else {
+        symtab.word(symbol.value);
+        symtab.word(symbol.size);
+        symtab.u8(info);
+        symtab.u8(0);
+        symtab.u16(shndx);
+      }
+    }
+    let first_global = first_global.unwrap_or(self.symbols.len() + 1);
+
+    // 各代码段的重定位表：.rela.text 以及其他代码段的
+    let extra = 2 * self.code_sections.len() as u32;
+    let code_sections =
+      std::iter::once(".text").chain(self.code_sections.iter().map(String::as_str));
+    let code_indices = std::iter::once(SECTION_TEXT).chain(SECTION_EXTRA..);
+    let mut tables = vec![];
+    for (name, section) in code_sections.zip(code_indices) {
+      let mut rela = Writer { buf: vec![], is64 };
+      for reloc in self.relocs.iter().filter(|reloc| reloc.section == section) {
+        let symbol = indices[reloc.symbol.as_str()];
+        rela.word(reloc.offset);
+        if is64 {
+          rela.word(symbol << 32 | reloc.kind as u64);
+        } else {
+          rela.word(symbol << 8 | reloc.kind as u64);
+        }
+        rela.word(0);
+      }
+      tables.push((
+        format!(".rela{}", name),
+        SHT_RELA,
+        SHF_INFO_LINK,
+        rela.buf,
+        SHNDX_SYMTAB + extra,
+        section as u32 + 1,
+        relaentsize,
+      ));
+    }
+    tables.push((
+      ".symtab".into(),
+      SHT_SYMTAB,
+      0,
+      symtab.buf,
+      SHNDX_STRTAB + extra,
+      first_global as u32,
+      symentsize,
+    ));
+    tables.push((".strtab".into(), SHT_STRTAB, 0, strtab.0, 0, 0, 0));
+    for (name, ty, flags, data, link, info, entsize) in tables {
+      w.align(word);
+      headers.push(SectionHeader {
+        name: shstrtab.add(&name),
+        ty,
+        flags,
+        offset: w.buf.len() as u64,
+        size: data.len() as u64,
+        link,
+        info,
+        align: if entsize == 0 { 1 } else { word as u64 },
+        entsize: entsize as u64,
+      });
+      w.buf.extend(data);
+    }
+    let name = shstrtab.add(".shstrtab");
+    headers.push(SectionHeader {
+      name,
+      ty: SHT_STRTAB,
+      flags: 0,
+      offset: w.buf.len() as u64,
+      size: shstrtab.0.len() as u64,
+      link: 0,
+      info: 0,
+      align: 1,
+      entsize: 0,
+    });
+    w.buf.extend(&shstrtab.0);
+    debug_assert_eq!(headers.len() as u32, SHNDX_SHSTRTAB + extra);
+
+    // 节头表
+    w.align(word);
+    let shoff = w.buf.len() as u64;
+    w.buf.extend(vec![0; shentsize]);
+    for h in &headers {
+      w.u32(h.name);
+      w.u32(h.ty);
+      w.word(h.flags);
+      w.word(0);
+      w.word(h.offset);
+      w.word(h.size);
+      w.u32(h.link);
+      w.u32(h.info);
+      w.word(h.align);
+      w.word(h.entsize);
+    }
+
+    // ELF 头
+    let mut header = Writer { buf: vec![], is64 };
+    header.buf.extend(b"\x7fELF");
+    header.u8(if is64 { 2 } else { 1 });
+    header.u8(1);
+    header.u8(1);
+    header.buf.extend([0; 9]);
+    header.u16(ET_REL);
+    header.u16(EM_RISCV);
+    header.u32(1);
+    header.word(0);
+    header.word(0);
+    header.word(shoff);
+    header.u32(if self.rvc { EF_RISCV_RVC } else { 0 });
+    header.u16(ehsize as u16);
+    header.u16(0);
+    header.u16(0);
+    header.u16(shentsize as u16);
+    header.u16(headers.len() as u16 + 1);
+    header.u16((SHNDX_SHSTRTAB + extra) as u16);
+    w.buf[..ehsize].copy_from_slice(&header.buf);
+    w.buf
+  }
+}
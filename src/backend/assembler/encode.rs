@@ -0,0 +1,184 @@
+// https://github.com/riscv/riscv-isa-manual/releases （第 2、16、24 章）
+
+use crate::backend::riscv::compressed::CInst;
+use crate::backend::riscv::reg::Reg;
+
+pub const OP_LOAD: u32 = 0x03;
+pub const OP_IMM: u32 = 0x13;
+pub const OP_AUIPC: u32 = 0x17;
+pub const OP_IMM_32: u32 = 0x1b;
+pub const OP_STORE: u32 = 0x23;
+pub const OP: u32 = 0x33;
+pub const OP_LUI: u32 = 0x37;
+pub const OP_32: u32 = 0x3b;
+pub const OP_BRANCH: u32 = 0x63;
+pub const OP_JALR: u32 = 0x67;
+pub const OP_JAL: u32 = 0x6f;
+pub const OP_SYSTEM: u32 = 0x73;
+
+fn reg(r: Reg) -> u32 {
+  r as u32
+}
+
+/// x8 ~ x15 在压缩指令中的 3 位编码
+fn creg(r: Reg) -> u32 {
+  (r as u32 - 8) & 0b111
+}
+
+/// 取 `value` 的第 `hi` 至 `lo` 位
+fn bits(value: i32, hi: u32, lo: u32) -> u32 {
+  ((value as u32) >> lo) & ((1 << (hi - lo + 1)) - 1)
+}
+
+pub fn r_type(opcode: u32, funct3: u32, funct7: u32, rd: Reg, rs1: Reg, rs2: Reg) -> u32 {
+  funct7 << 25 | reg(rs2) << 20 | reg(rs1) << 15 | funct3 << 12 | reg(rd) << 7 | opcode
+}
+
+pub fn i_type(opcode: u32, funct3: u32, rd: Reg, rs1: Reg, imm: i32) -> u32 {
+  bits(imm, 11, 0) << 20 | reg(rs1) << 15 | funct3 << 12 | reg(rd) << 7 | opcode
+}
+
+pub fn s_type(opcode: u32, funct3: u32, rs1: Reg, rs2: Reg, imm: i32) -> u32 {
+  bits(imm, 11, 5) << 25
+    | reg(rs2) << 20
+    | reg(rs1) << 15
+    | funct3 << 12
+    | bits(imm, 4, 0) << 7
+    | opcode
+}
+
+pub fn b_type(funct3: u32, rs1: Reg, rs2: Reg, imm: i32) -> u32 {
+  bits(imm, 12, 12) << 31
+    | bits(imm, 10, 5) << 25
+    | reg(rs2) << 20
+    | reg(rs1) << 15
+    | funct3 << 12
+    | bits(imm, 4, 1) << 8
+    | bits(imm, 11, 11) << 7
+    | OP_BRANCH
+}
+
+pub fn u_type(opcode: u32, rd: Reg, imm20: i32) -> u32 {
+  bits(imm20, 19, 0) << 12 | reg(rd) << 7 | opcode
+}
+
+pub fn j_type(rd: Reg, imm: i32) -> u32 {
+  bits(imm, 20, 20) << 31
+    | bits(imm, 10, 1) << 21
+    | bits(imm, 11, 11) << 20
+    | bits(imm, 19, 12) << 12
+    | reg(rd) << 7
+    | OP_JAL
+}
+
+/// 按 `li` 的展开方式拆分立即数：高 20 位（供 `lui`）与低 12 位（有符号）
+pub fn split_hi_lo(imm: i32) -> (i32, i32) {
+  let lo = (imm << 20) >> 20;
+  let hi = (imm.wrapping_sub(lo) >> 12) & 0xfffff;
+  (hi, lo)
+}
+
+/// CA 格式：`funct6 rd'/rs1' funct2 rs2' op`
+fn ca(funct6: u32, funct2: u32, rd: Reg, rs2: Reg) -> u32 {
+  funct6 << 10 | creg(rd) << 7 | funct2 << 5 | creg(rs2) << 2 | 0b01
+}
+
+pub fn compressed(inst: &CInst) -> u16 {
+  let c = |funct3: u32, op: u32| funct3 << 13 | op;
+  let word = match *inst {
+    CInst::Addi4spn(rd, imm) => {
+      c(0b000, 0b00)
+        | bits(imm, 5, 4) << 11
+        | bits(imm, 9, 6) << 7
+        | bits(imm, 2, 2) << 6
+        | bits(imm, 3, 3) << 5
+        | creg(rd) << 2
+    }
+    CInst::Lw(rd, imm, rs1) => {
+      c(0b010, 0b00)
+        | bits(imm, 5, 3) << 10
+        | creg(rs1) << 7
+        | bits(imm, 2, 2) << 6
+        | bits(imm, 6, 6) << 5
+        | creg(rd) << 2
+    }
+    CInst::Ld(rd, imm, rs1) => {
+      c(0b011, 0b00) | bits(imm, 5, 3) << 10 | creg(rs1) << 7 | bits(imm, 7, 6) << 5 | creg(rd) << 2
+    }
+    CInst::Sw(rs2, imm, rs1) => {
+      c(0b110, 0b00)
+        | bits(imm, 5, 3) << 10
+        | creg(rs1) << 7
+        | bits(imm, 2, 2) << 6
+        | bits(imm, 6, 6) << 5
+        | creg(rs2) << 2
+    }
+    CInst::Sd(rs2, imm, rs1) => {
+      c(0b111, 0b00)
+        | bits(imm, 5, 3) << 10
+        | creg(rs1) << 7
+        | bits(imm, 7, 6) << 5
+        | creg(rs2) << 2
+    }
+    CInst::Addi(rd, imm) => {
+      c(0b000, 0b01) | bits(imm, 5, 5) << 12 | reg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Addiw(rd, imm) => {
+      c(0b001, 0b01) | bits(imm, 5, 5) << 12 | reg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Li(rd, imm) => {
+      c(0b010, 0b01) | bits(imm, 5, 5) << 12 | reg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Lui(rd, imm) => {
+      c(0b011, 0b01) | bits(imm, 5, 5) << 12 | reg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Addi16sp(imm) => {
+      c(0b011, 0b01)
+        | bits(imm, 9, 9) << 12
+        | reg(Reg::Sp) << 7
+        | bits(imm, 4, 4) << 6
+        | bits(imm, 6, 6) << 5
+        | bits(imm, 8, 7) << 3
+        | bits(imm, 5, 5) << 2
+    }
+    CInst::Srli(rd, imm) => {
+      c(0b100, 0b01) | bits(imm, 5, 5) << 12 | creg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Andi(rd, imm) => {
+      c(0b100, 0b01) | bits(imm, 5, 5) << 12 | 0b10 << 10 | creg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Sub(rd, rs2) => ca(0b100011, 0b00, rd, rs2),
+    CInst::Xor(rd, rs2) => ca(0b100011, 0b01, rd, rs2),
+    CInst::Or(rd, rs2) => ca(0b100011, 0b10, rd, rs2),
+    CInst::And(rd, rs2) => ca(0b100011, 0b11, rd, rs2),
+    CInst::Subw(rd, rs2) => ca(0b100111, 0b00, rd, rs2),
+    CInst::Addw(rd, rs2) => ca(0b100111, 0b01, rd, rs2),
+    CInst::Slli(rd, imm) => {
+      c(0b000, 0b10) | bits(imm, 5, 5) << 12 | reg(rd) << 7 | bits(imm, 4, 0) << 2
+    }
+    CInst::Lwsp(rd, imm) => {
+      c(0b010, 0b10)
+        | bits(imm, 5, 5) << 12
+        | reg(rd) << 7
+        | bits(imm, 4, 2) << 4
+        | bits(imm, 7, 6) << 2
+    }
+    CInst::Ldsp(rd, imm) => {
+      c(0b011, 0b10)
+        | bits(imm, 5, 5) << 12
+        | reg(rd) << 7
+        | bits(imm, 4, 3) << 5
+        | bits(imm, 8, 6) << 2
+    }
+    CInst::Jr(rs1) => c(0b100, 0b10) | reg(rs1) << 7,
+    CInst::Mv(rd, rs2) => c(0b100, 0b10) | reg(rd) << 7 | reg(rs2) << 2,
+    CInst::Add(rd, rs2) => c(0b100, 0b10) | 1 << 12 | reg(rd) << 7 | reg(rs2) << 2,
+    CInst::Swsp(rs2, imm) => {
+      c(0b110, 0b10) | bits(imm, 5, 2) << 9 | bits(imm, 7, 6) << 7 | reg(rs2) << 2
+    }
+    CInst::Sdsp(rs2, imm) => {
+      c(0b111, 0b10) | bits(imm, 5, 3) << 10 | bits(imm, 8, 6) << 7 | reg(rs2) << 2
+    }
+  };
+  word as u16
+}
@@ -0,0 +1,445 @@
+//! C 源代码输出（`--emit=c`），便于与 gcc/clang 的结果对照、独立于 RISC-V 后端检验前端。
+//!
+//! 由 Koopa IR 逐条翻译为 C99：每条指令的结果是一个局部变量，`alloc` 是局部变量的地址，
+//! 基本块是标号，以 `goto` 跳转。算术运算按 RISC-V 的语义进行：加减乘与左移回绕，
+//! 除数为 0 或溢出时的结果同 `div`/`rem` 指令，不依赖 C 中未定义的行为。对 `volatile` 变量的
+//! 读写经由指向 volatile 的指针进行。
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+
+use koopa::ir::{BasicBlock, BinaryOp, FunctionData, Program, Type, TypeKind, Value, ValueKind};
+
+use super::error::UnimplementedError;
+use crate::frontend::{FuncAttrs, Ir};
+use crate::Result;
+
+/// SysY 中不是关键字的 C99 关键字，用作名字时加上后缀 `_`
+#[rustfmt::skip]
+const C_KEYWORDS: &[&str] = &[
+  "auto", "case", "char", "default", "do", "double", "enum", "extern", "float", "for", "goto",
+  "inline", "long", "register", "restrict", "short", "signed", "sizeof", "static", "struct",
+  "switch", "typedef", "union", "unsigned", "volatile",
+];
+
+const DIV_HELPER: &str = "__sysy_div";
+const REM_HELPER: &str = "__sysy_rem";
+
+/// 除法与取余，与 RISC-V 的 `div`/`rem` 一致
+const HELPERS: &str = "\
+static inline int __sysy_div(int a, int b) {
+  if (b == 0) return -1;
+  if (b == -1) return (int)(0u - (unsigned)a);
+  return a / b;
+}
+
+static inline int __sysy_rem(int a, int b) {
+  if (b == 0) return a;
+  if (b == -1) return 0;
+  return a % b;
+}
+";
+
+/// IR 中的全局名字（去掉 `@`/`%`）对应的 C 名字
+fn c_name(name: &str) -> String {
+  let name = &name[1..];
+  if C_KEYWORDS.contains(&name) {
+    format!("{}_", name)
+  } else {
+    name.into()
+  }
+}
+
+/// 以 `ty` 类型声明 `declarator`
+fn declare(ty: &Type, declarator: &str) -> String {
+  match ty.kind() {
+    TypeKind::Int32 => format!("int {}", declarator).trim_end().into(),
+    TypeKind::Unit => format!("void {}", declarator).trim_end().into(),
+    TypeKind::Array(base, len) => declare(base, &format!("{}[{}]", declarator, len)),
+    TypeKind::Pointer(base) => match base.kind() {
+      TypeKind::Array(..) => declare(base, &format!("(*{})", declarator)),
+      _ => declare(base, &format!("*{}", declarator)),
+    },
+    TypeKind::Function(..) => unreachable!("function values are never operands"),
+  }
+}
+
+fn int_literal(value: i32) -> String {
+  // `-2147483648` 在 C 中是对 `2147483648` 取负，而后者不是 `int`
+  if value == i32::MIN {
+    "(-2147483647 - 1)".into()
+  } else {
+    value.to_string()
+  }
+}
+
+/// 全局变量的初始值，全零时为 `None`
+fn initializer(ir: &Program, value: Value) -> Option<String> {
+  let vd = ir.borrow_value(value);
+  match vd.kind() {
+    ValueKind::Integer(i) if i.value() != 0 => Some(int_literal(i.value())),
+    ValueKind::Aggregate(agg) => {
+      let mut elems: Vec<_> = agg
+        .elems()
+        .iter()
+        .map(|&e| (e, initializer(ir, e)))
+        .collect();
+      // 末尾的零值元素省略，由 C 补为零
+      while elems.last().is_some_and(|(_, init)| init.is_none()) {
+        elems.pop();
+      }
+      if elems.is_empty() {
+        return None;
+      }
+      let elems = elems.into_iter().map(|(e, init)| {
+        init.unwrap_or_else(|| match ir.borrow_value(e).ty().kind() {
+          TypeKind::Array(..) => "{0}".into(),
+          _ => "0".into(),
+        })
+      });
+      Some(format!("{{{}}}", elems.collect::<Vec<_>>().join(", ")))
+    }
+    _ => None,
+  }
+}
+
+/// 函数原型；`params` 为各参数的名字，为空时只给出类型
+fn prototype(fd: &FunctionData, params: &[String]) -> String {
+  let (param_tys, ret) = match fd.ty().kind() {
+    TypeKind::Function(params, ret) => (params, ret),
+    _ => unreachable!("function should have function type"),
+  };
+  let params: Vec<_> = if param_tys.is_empty() {
+    vec!["void".into()]
+  } else {
+    let names = params
+      .iter()
+      .map(String::as_str)
+      .chain(std::iter::repeat(""));
+    param_tys
+      .iter()
+      .zip(names)
+      .map(|(ty, name)| declare(ty, name))
+      .collect()
+  };
+  declare(
+    ret,
+    &format!("{}({})", c_name(fd.name()), params.join(", ")),
+  )
+}
+
+/// 函数的属性，写在原型之前，如 `__attribute__((noinline)) `；没有属性时为空
+fn c_attrs(attrs: &FuncAttrs) -> String {
+  let flags = [
+    (attrs.noinline, "noinline"),
+    (attrs.always_inline, "always_inline"),
+    (attrs.noreturn, "noreturn"),
+  ];
+  let mut list: Vec<_> = flags
+    .into_iter()
+    .filter(|&(enabled, _)| enabled)
+    .map(|(_, name)| name.to_string())
+    .collect();
+  if let Some(section) = &attrs.section {
+    list.push(format!("section({:?})", section));
+  }
+  match list.is_empty() {
+    true => String::new(),
+    false => format!("__attribute__(({})) ", list.join(", ")),
+  }
+}
+
+/// 把局部的聚合初始值展开为字
+fn flatten(fd: &FunctionData, value: Value, words: &mut Vec<String>) {
+  let vd = fd.dfg().value(value);
+  match vd.kind() {
+    ValueKind::Integer(i) => words.push(int_literal(i.value())),
+    ValueKind::Aggregate(agg) => {
+      for &elem in agg.elems() {
+        flatten(fd, elem, words);
+      }
+    }
+    _ => words.extend(std::iter::repeat_n("0".to_string(), vd.ty().size() / 4)),
+  }
+}
+
+struct FuncContext<'a> {
+  ir: &'a Ir,
+  fd: &'a FunctionData,
+  /// 局部名字的前缀，使其不与全局的名字相同
+  prefix: &'a str,
+  /// 各指令按出现顺序的序号
+  ids: HashMap<Value, usize>,
+  bbs: HashMap<BasicBlock, usize>,
+  body: String,
+}
+
+impl FuncContext<'_> {
+  fn value_kind(&self, value: Value) -> ValueKind {
+    self.fd.dfg().value(value).kind().clone()
+  }
+
+  fn temp(&self, value: Value) -> String {
+    format!("{}v{}", self.prefix, self.ids[&value])
+  }
+
+  fn storage(&self, value: Value) -> String {
+    format!("{}s{}", self.prefix, self.ids[&value])
+  }
+
+  fn param(&self, index: usize) -> String {
+    format!("{}a{}", self.prefix, index)
+  }
+
+  fn label(&self, bb: BasicBlock) -> String {
+    format!("bb{}", self.bbs[&bb])
+  }
+
+  fn expr(&self, value: Value) -> Result<String> {
+    if value.is_global() {
+      let name = self.ir.borrow_value(value).name().clone().unwrap();
+      return Ok(format!("(&{})", c_name(&name)));
+    }
+    Ok(match self.value_kind(value) {
+      ValueKind::Integer(i) => int_literal(i.value()),
+      ValueKind::ZeroInit(_) | ValueKind::Undef(_) => "0".into(),
+      ValueKind::FuncArgRef(arg) => self.param(arg.index()),
+      ValueKind::Alloc(_) => format!("(&{})", self.storage(value)),
+      ValueKind::BlockArgRef(arg) => return Err(UnimplementedError(Box::new(arg)).into()),
+      _ => self.temp(value),
+    })
+  }
+
+  /// 以 `ptr` 为地址读写 `ty` 类型的值；访问 volatile 变量时转换为指向 volatile 的指针
+  fn access(&self, ptr: Value, ty: &Type) -> Result<String> {
+    let expr = self.expr(ptr)?;
+    Ok(match self.ir.annotations.is_volatile(self.fd.dfg(), ptr) {
+      true => format!("(({}){})", declare(ty, "volatile *"), expr),
+      false => expr,
+    })
+  }
+
+  fn stmt(&mut self, stmt: String) {
+    writeln!(self.body, "  {};", stmt).unwrap();
+  }
+
+  /// 以 `expr` 定义 `value`；结果未被使用时只保留函数调用
+  fn define(&mut self, value: Value, expr: String) {
+    if !self.fd.dfg().value(value).used_by().is_empty() {
+      self.stmt(format!("{} = {}", self.temp(value), expr));
+    } else if let ValueKind::Call(_) = self.value_kind(value) {
+      self.stmt(expr);
+    }
+  }
+
+  fn generate_inst(&mut self, value: Value) -> Result<()> {
+    match self.value_kind(value) {
+      ValueKind::Alloc(_) => {}
+      ValueKind::Load(load) => {
+        let ty = self.fd.dfg().value(value).ty().clone();
+        let expr = format!("*{}", self.access(load.src(), &ty)?);
+        self.define(value, expr);
+      }
+      ValueKind::Store(store) => match self.value_kind(store.value()) {
+        ValueKind::ZeroInit(_) => {
+          let dest = self.expr(store.dest())?;
+          let ptr = match self.ir.annotations.is_volatile(self.fd.dfg(), store.dest()) {
+            true => "int volatile *",
+            false => "int *",
+          };
+          // 逐字清零，而不是把每个字展开为一条语句；`store` 没有结果，以其名字为循环变量
+          let words = self.fd.dfg().value(store.value()).ty().size() / 4;
+          let i = self.temp(value);
+          let stmt = format!(
+            "for (int {i} = 0; {i} < {}; {i}++) (({}){})[{i}] = 0",
+            words, ptr, dest
+          );
+          self.stmt(stmt);
+        }
+        ValueKind::Aggregate(_) => {
+          let dest = self.expr(store.dest())?;
+          let ptr = match self.ir.annotations.is_volatile(self.fd.dfg(), store.dest()) {
+            true => "int volatile *",
+            false => "int *",
+          };
+          let mut words = vec![];
+          flatten(self.fd, store.value(), &mut words);
+          for (i, word) in words.into_iter().enumerate() {
+            self.stmt(format!("(({}){})[{}] = {}", ptr, dest, i, word));
+          }
+        }
+        _ => {
+          let ty = self.fd.dfg().value(store.value()).ty().clone();
+          let dest = self.access(store.dest(), &ty)?;
+          let stmt = format!("*{} = {}", dest, self.expr(store.value())?);
+          self.stmt(stmt);
+        }
+      },
+      ValueKind::GetPtr(ptr) => {
+        let expr = format!("{} + {}", self.expr(ptr.src())?, self.expr(ptr.index())?);
+        self.define(value, expr);
+      }
+      ValueKind::GetElemPtr(ptr) => {
+        let expr = format!("&(*{})[{}]", self.expr(ptr.src())?, self.expr(ptr.index())?);
+        self.define(value, expr);
+      }
+      ValueKind::Binary(binary) => {
+        let (l, r) = (self.expr(binary.lhs())?, self.expr(binary.rhs())?);
+        let wrapping = |op: &str| format!("(int)((unsigned){} {} (unsigned){})", l, op, r);
+        let expr = match binary.op() {
+          BinaryOp::NotEq => format!("{} != {}", l, r),
+          BinaryOp::Eq => format!("{} == {}", l, r),
+          BinaryOp::Gt => format!("{} > {}", l, r),
+          BinaryOp::Lt => format!("{} < {}", l, r),
+          BinaryOp::Ge => format!("{} >= {}", l, r),
+          BinaryOp::Le => format!("{} <= {}", l, r),
+          BinaryOp::Add => wrapping("+"),
+          BinaryOp::Sub => wrapping("-"),
+          BinaryOp::Mul => wrapping("*"),
+          BinaryOp::Div => format!("{}({}, {})", DIV_HELPER, l, r),
+          BinaryOp::Mod => format!("{}({}, {})", REM_HELPER, l, r),
+          BinaryOp::And => format!("{} & {}", l, r),
+          BinaryOp::Or => format!("{} | {}", l, r),
+          BinaryOp::Xor => format!("{} ^ {}", l, r),
+          BinaryOp::Shl => format!("(int)((unsigned){} << ({} & 31))", l, r),
+          BinaryOp::Shr => format!("(int)((unsigned){} >> ({} & 31))", l, r),
+          // 负数右移在 gcc/clang 中都是算术右移
+          BinaryOp::Sar => format!("{} >> ({} & 31)", l, r),
+        };
+        self.define(value, expr);
+      }
+      ValueKind::Call(call) => {
+        let args = call.args().iter().map(|&arg| self.expr(arg));
+        let args = args.collect::<Result<Vec<_>>>()?;
+        let callee = c_name(self.ir.func(call.callee()).name());
+        let expr = format!("{}({})", callee, args.join(", "));
+        self.define(value, expr);
+      }
+      ValueKind::Branch(branch) => {
+        let cond = self.expr(branch.cond())?;
+        let (t, f) = (self.label(branch.true_bb()), self.label(branch.false_bb()));
+        self.stmt(format!("if ({}) goto {}; else goto {}", cond, t, f));
+      }
+      ValueKind::Jump(jump) => {
+        let stmt = format!("goto {}", self.label(jump.target()));
+        self.stmt(stmt);
+      }
+      ValueKind::Return(ret) => match ret.value() {
+        Some(value) => {
+          let stmt = format!("return {}", self.expr(value)?);
+          self.stmt(stmt);
+        }
+        None => self.stmt("return".into()),
+      },
+      x => return Err(UnimplementedError(Box::new(x)).into()),
+    }
+    Ok(())
+  }
+}
+
+fn generate_func(ir: &Ir, fd: &FunctionData, prefix: &str) -> Result<String> {
+  let bbs = fd.layout().bbs();
+  let mut context = FuncContext {
+    ir,
+    fd,
+    prefix,
+    ids: HashMap::new(),
+    bbs: bbs.keys().enumerate().map(|(i, &bb)| (bb, i)).collect(),
+    body: String::new(),
+  };
+  let params: Vec<_> = (0..fd.params().len()).map(|i| context.param(i)).collect();
+  let mut text = format!("{} {{\n", prototype(fd, &params));
+
+  // 局部变量都声明在函数开头，`goto` 不会跳过它们的声明
+  let mut targets = HashSet::new();
+  for (_, node) in bbs {
+    for &v in node.insts().keys() {
+      context.ids.insert(v, context.ids.len());
+      let vd = fd.dfg().value(v);
+      match vd.kind() {
+        ValueKind::Alloc(_) => {
+          let base = match vd.ty().kind() {
+            TypeKind::Pointer(base) => base,
+            _ => panic!("alloc should have pointer type"),
+          };
+          writeln!(text, "  {};", declare(base, &context.storage(v)))?;
+        }
+        _ if !vd.used_by().is_empty() => {
+          writeln!(text, "  {};", declare(vd.ty(), &context.temp(v)))?;
+        }
+        ValueKind::Branch(branch) => targets.extend([branch.true_bb(), branch.false_bb()]),
+        ValueKind::Jump(jump) => {
+          targets.insert(jump.target());
+        }
+        _ => {}
+      }
+    }
+  }
+
+  for (&bb, node) in bbs {
+    if targets.contains(&bb) {
+      writeln!(context.body, "{}:", context.label(bb))?;
+    }
+    for &v in node.insts().keys() {
+      context.generate_inst(v)?;
+    }
+  }
+  text += &context.body;
+  text += "}\n";
+  Ok(text)
+}
+
+pub fn generate_c(ir: &Ir, mut output: impl Write) -> Result<()> {
+  if !ir.annotations.inline_asm.is_empty() {
+    return Err("inline assembly cannot be translated to C".into());
+  }
+
+  // 全局的名字形如局部名字时，局部名字加上前缀 `_` 以示区别
+  let mut names: Vec<_> = ir.funcs().values().map(|fd| c_name(fd.name())).collect();
+  for &v in ir.inst_layout() {
+    names.push(c_name(ir.borrow_value(v).name().as_ref().unwrap()));
+  }
+  let mut prefix = String::new();
+  let is_local = |name: &str, prefix: &str| {
+    name.strip_prefix(prefix).is_some_and(|rest| {
+      let digits = rest.get(1..).unwrap_or("");
+      rest.starts_with(['v', 's', 'a'])
+        && !digits.is_empty()
+        && digits.bytes().all(|b| b.is_ascii_digit())
+    })
+  };
+  while names.iter().any(|name| is_local(name, &prefix)) {
+    prefix.push('_');
+  }
+
+  let mut text = String::from(HELPERS);
+  text += "\n";
+  let attrs = &ir.annotations.func_attrs;
+  for &f in ir.func_layout() {
+    let attrs = attrs.get(&f).map(c_attrs).unwrap_or_default();
+    writeln!(text, "{}{};", attrs, prototype(ir.func(f), &[]))?;
+  }
+  text += "\n";
+  for &v in ir.inst_layout() {
+    let vd = ir.borrow_value(v);
+    let (base, init) = match (vd.ty().kind(), vd.kind()) {
+      (TypeKind::Pointer(base), ValueKind::GlobalAlloc(alloc)) => (base, alloc.init()),
+      _ => unreachable!("global instruction should be alloc"),
+    };
+    let decl = declare(base, &c_name(vd.name().as_ref().unwrap()));
+    match initializer(ir, init) {
+      Some(init) => writeln!(text, "{} = {};", decl, init)?,
+      None => writeln!(text, "{};", decl)?,
+    }
+  }
+  for &f in ir.func_layout() {
+    let fd = ir.func(f);
+    if fd.layout().entry_bb().is_some() {
+      text += "\n";
+      text += &generate_func(ir, fd, &prefix)?;
+    }
+  }
+  output.write_all(text.as_bytes())?;
+  Ok(())
+}
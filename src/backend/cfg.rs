@@ -0,0 +1,115 @@
+//! 汇编的控制流图（`--emit=cfg --cfg-after=<pass>`）：各函数按标号分块，边取自分支、跳转与
+//! 顺序执行，用以查看某一遍之后块的排布。
+
+use crate::analysis::dot::{self, Cluster};
+
+use super::from_func::func_end_label;
+use super::riscv::compressed::CInst;
+use super::riscv::directive::{Directive, SymbolType};
+use super::riscv::inst::Inst;
+use super::riscv::{Riscv, RiscvItem};
+
+/// 一个块：标号、各条指令的文本，以及转移的目标与边的标签
+struct Block {
+  label: String,
+  insts: Vec<String>,
+  edges: Vec<(String, Option<&'static str>)>,
+  /// 能否顺序执行到下一块
+  falls_through: bool,
+}
+
+impl Block {
+  fn new(label: &str) -> Self {
+    Self {
+      label: label.into(),
+      insts: vec![],
+      edges: vec![],
+      falls_through: true,
+    }
+  }
+}
+
+/// 正在分块的函数
+struct Func {
+  cluster: Cluster,
+  end_label: String,
+  blocks: Vec<Block>,
+}
+
+impl Func {
+  fn new(name: &str) -> Self {
+    Self {
+      cluster: Cluster::new(name),
+      end_label: func_end_label(name),
+      blocks: vec![],
+    }
+  }
+
+  /// 把各个块加入子图，顺序执行的边连到下一块
+  fn finish(mut self) -> Cluster {
+    for (i, block) in self.blocks.iter().enumerate() {
+      self.cluster.block(&block.label, &block.insts);
+      for (target, label) in &block.edges {
+        self.cluster.edge(&block.label, target, *label);
+      }
+      let next = self.blocks.get(i + 1).filter(|_| block.falls_through);
+      if let Some(next) = next {
+        // 条件分支之后的顺序执行即条件不成立
+        let conditional = block.edges.last().is_some_and(|(_, label)| label.is_some());
+        self
+          .cluster
+          .edge(&block.label, &next.label, conditional.then_some("F"));
+      }
+    }
+    self.cluster
+  }
+}
+
+/// 各函数的控制流图；块以标号开始，到函数的结束标号为止，其后的常量池等数据不计入
+pub fn cfg_dot(riscv: &Riscv) -> String {
+  let mut clusters = vec![];
+  let mut current: Option<Func> = None;
+  for item in &riscv.0 {
+    let block = current.as_mut().and_then(|func| func.blocks.last_mut());
+    match item {
+      RiscvItem::Directive(Directive::Type(name, SymbolType::Function)) => {
+        clusters.extend(current.take().map(Func::finish));
+        current = Some(Func::new(name));
+      }
+      RiscvItem::Label(label) => match &mut current {
+        Some(func) if *label == func.end_label => clusters.extend(current.take().map(Func::finish)),
+        Some(func) => func.blocks.push(Block::new(label)),
+        None => {}
+      },
+      RiscvItem::Inst(inst) => {
+        let Some(block) = block else {
+          continue;
+        };
+        block.insts.push(inst.to_string().trim().to_string());
+        match inst {
+          Inst::Beqz(_, target) | Inst::Bnez(_, target) => {
+            block.edges.push((target.clone(), Some("T")));
+          }
+          Inst::J(target) => {
+            block.edges.push((target.clone(), None));
+            block.falls_through = false;
+          }
+          Inst::Ret => block.falls_through = false,
+          _ => {}
+        }
+      }
+      RiscvItem::Compressed(inst) => {
+        let Some(block) = block else {
+          continue;
+        };
+        block.insts.push(inst.to_string().trim().to_string());
+        if matches!(inst, CInst::Jr(_)) {
+          block.falls_through = false;
+        }
+      }
+      _ => {}
+    }
+  }
+  clusters.extend(current.map(Func::finish));
+  dot::graph("cfg", clusters)
+}
@@ -0,0 +1,149 @@
+//! DWARF 4 调试信息（`-g`）。
+//!
+//! 行号表由汇编器根据 `.loc` 生成；这里只生成 `.debug_abbrev` 与 `.debug_info`：一个编译单元，
+//! 以及每个函数的地址范围与帧基址（`sp`）。
+
+#[cfg(feature = "host")]
+use std::env;
+
+use super::from_func::func_end_label;
+use super::riscv::directive::Directive;
+use super::riscv::{Riscv, Xlen};
+
+// https://dwarfstd.org/doc/DWARF4.pdf （第 7.5 节）
+const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+const DW_TAG_SUBPROGRAM: u64 = 0x2e;
+const DW_AT_NAME: u64 = 0x03;
+const DW_AT_STMT_LIST: u64 = 0x10;
+const DW_AT_LOW_PC: u64 = 0x11;
+const DW_AT_HIGH_PC: u64 = 0x12;
+const DW_AT_LANGUAGE: u64 = 0x13;
+const DW_AT_COMP_DIR: u64 = 0x1b;
+const DW_AT_PRODUCER: u64 = 0x25;
+const DW_AT_DECL_FILE: u64 = 0x3a;
+const DW_AT_DECL_LINE: u64 = 0x3b;
+const DW_AT_EXTERNAL: u64 = 0x3f;
+const DW_AT_FRAME_BASE: u64 = 0x40;
+const DW_FORM_ADDR: u64 = 0x01;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA4: u64 = 0x06;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_UDATA: u64 = 0x0f;
+const DW_FORM_SEC_OFFSET: u64 = 0x17;
+const DW_FORM_EXPRLOC: u64 = 0x18;
+const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+const DW_LANG_C99: u16 = 0x0c;
+const DW_OP_REG2: u8 = 0x52;
+
+const ABBREV_CU: u64 = 1;
+const ABBREV_SUBPROGRAM: u64 = 2;
+
+/// 需要生成调试信息的函数
+pub struct DebugFunc {
+  pub name: String,
+  pub line: usize,
+  /// 是否在 `.text` 中，而不是 `section` 属性指定的节
+  pub in_text: bool,
+}
+
+fn abbrev(r: &mut Riscv, code: u64, tag: u64, children: bool, attrs: &[(u64, u64)]) {
+  r.add_directive(Directive::Uleb128(code));
+  r.add_directive(Directive::Uleb128(tag));
+  r.add_directive(Directive::Byte(children as u8));
+  for &(attr, form) in attrs {
+    r.add_directive(Directive::Uleb128(attr));
+    r.add_directive(Directive::Uleb128(form));
+  }
+  r.add_directive(Directive::Byte(0));
+  r.add_directive(Directive::Byte(0));
+}
+
+/// `file` 为源文件路径；`funcs` 按代码中的顺序排列
+pub fn generate(file: &str, funcs: &[DebugFunc], xlen: Xlen) -> Riscv {
+  let word = xlen.bytes();
+  let mut r = Riscv::new();
+  if funcs.is_empty() {
+    return r;
+  }
+
+  r.add_directive(Directive::Section(".debug_abbrev".into()));
+  r.add_label(".Ldebug_abbrev_start".into());
+  #[rustfmt::skip]
+  abbrev(&mut r, ABBREV_CU, DW_TAG_COMPILE_UNIT, true, &[
+    (DW_AT_PRODUCER, DW_FORM_STRING),
+    (DW_AT_LANGUAGE, DW_FORM_DATA2),
+    (DW_AT_NAME, DW_FORM_STRING),
+    (DW_AT_STMT_LIST, DW_FORM_SEC_OFFSET),
+    (DW_AT_COMP_DIR, DW_FORM_STRING),
+    (DW_AT_LOW_PC, DW_FORM_ADDR),
+    (DW_AT_HIGH_PC, DW_FORM_DATA4),
+  ]);
+  #[rustfmt::skip]
+  abbrev(&mut r, ABBREV_SUBPROGRAM, DW_TAG_SUBPROGRAM, false, &[
+    (DW_AT_LOW_PC, DW_FORM_ADDR),
+    (DW_AT_HIGH_PC, DW_FORM_DATA4),
+    (DW_AT_FRAME_BASE, DW_FORM_EXPRLOC),
+    (DW_AT_NAME, DW_FORM_STRING),
+    (DW_AT_DECL_FILE, DW_FORM_DATA1),
+    (DW_AT_DECL_LINE, DW_FORM_UDATA),
+    (DW_AT_EXTERNAL, DW_FORM_FLAG_PRESENT),
+  ]);
+  r.add_directive(Directive::Byte(0));
+
+  // 浏览器中没有当前目录
+  #[cfg(feature = "host")]
+  let comp_dir = env::current_dir()
+    .map(|dir| dir.display().to_string())
+    .unwrap_or_default();
+  #[cfg(not(feature = "host"))]
+  let comp_dir = String::new();
+  // 编译单元的地址范围须在同一节中，只含 `.text` 中的函数；都不在其中时只含第一个函数
+  let text: Vec<_> = funcs.iter().filter(|func| func.in_text).collect();
+  let (first, last) = match (text.first(), text.last()) {
+    (Some(first), Some(last)) => (&first.name, &last.name),
+    _ => (&funcs[0].name, &funcs[0].name),
+  };
+  let last = func_end_label(last);
+
+  r.add_directive(Directive::Section(".debug_info".into()));
+  r.add_directive(Directive::Addr(
+    4,
+    ".Ldebug_info_end-.Ldebug_info_start".into(),
+  ));
+  r.add_label(".Ldebug_info_start".into());
+  r.add_directive(Directive::Half(4));
+  r.add_directive(Directive::Addr(4, ".Ldebug_abbrev_start".into()));
+  r.add_directive(Directive::Byte(word as u8));
+
+  r.add_directive(Directive::Uleb128(ABBREV_CU));
+  r.add_directive(Directive::Asciz("sysyc".into()));
+  r.add_directive(Directive::Half(DW_LANG_C99));
+  r.add_directive(Directive::Asciz(file.into()));
+  r.add_directive(Directive::Addr(4, ".Ldebug_line_start".into()));
+  r.add_directive(Directive::Asciz(comp_dir));
+  r.add_directive(Directive::Addr(word, first.clone()));
+  r.add_directive(Directive::Addr(4, format!("{}-{}", last, first)));
+
+  for func in funcs {
+    r.add_directive(Directive::Uleb128(ABBREV_SUBPROGRAM));
+    r.add_directive(Directive::Addr(word, func.name.clone()));
+    r.add_directive(Directive::Addr(
+      4,
+      format!("{}-{}", func_end_label(&func.name), func.name),
+    ));
+    // 帧基址：栈指针，序言之后即不再变化
+    r.add_directive(Directive::Uleb128(1));
+    r.add_directive(Directive::Byte(DW_OP_REG2));
+    r.add_directive(Directive::Asciz(func.name.clone()));
+    r.add_directive(Directive::Byte(1));
+    r.add_directive(Directive::Uleb128(func.line as u64));
+  }
+  r.add_directive(Directive::Byte(0));
+  r.add_label(".Ldebug_info_end".into());
+
+  // 内容由汇编器根据 `.loc` 填充
+  r.add_directive(Directive::Section(".debug_line".into()));
+  r.add_label(".Ldebug_line_start".into());
+  r
+}
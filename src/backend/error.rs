@@ -21,3 +21,55 @@ impl fmt::Display for LabelNotExistError {
     write!(f, "cannot find corresponding label for '{}'", self.0)
   }
 }
+
+#[derive(Debug)]
+pub struct AssembleError(pub String);
+
+impl Error for AssembleError {}
+
+impl fmt::Display for AssembleError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "assembler: {}", self.0)
+  }
+}
+
+#[derive(Debug)]
+pub struct RegAllocError(pub String);
+
+impl Error for RegAllocError {}
+
+impl fmt::Display for RegAllocError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "register allocation: {}", self.0)
+  }
+}
+
+#[derive(Debug)]
+pub struct WasmError(pub String);
+
+impl Error for WasmError {}
+
+impl fmt::Display for WasmError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "wasm: {}", self.0)
+  }
+}
+
+#[derive(Debug)]
+pub struct VerifyError {
+  pub pass: String,
+  pub func: String,
+  pub message: String,
+}
+
+impl Error for VerifyError {}
+
+impl fmt::Display for VerifyError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "verify: after {} in function {}: {}",
+      self.pass, self.func, self.message
+    )
+  }
+}
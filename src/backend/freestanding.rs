@@ -0,0 +1,88 @@
+//! 裸机运行（`--freestanding`）所需的入口与 SBI 调用例程。
+//!
+//! 程序由 OpenSBI 等 SBI 实现以 S 模式引导（如 `qemu-system-riscv32 -machine virt -kernel`）：
+//! - `_start` 位于 `.text` 开头，设置栈指针后调用 `main`，返回后经由 SBI 关机；
+//! - 字符 I/O 使用旧版控制台扩展（EID 0x01、0x02），其余库函数由前端以 SysY 实现；
+//! - 关机使用系统重置扩展（SRST），`main` 返回非零时以系统故障为原因；SBI 不支持时退回旧版关机。
+//!
+//! `.bss`（包括栈）由加载器清零，QEMU 加载 ELF 时即是如此。
+
+use super::from_func::func_end_label;
+use super::riscv::directive::{Directive, SymbolType};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::Riscv;
+use super::soft_muldiv::{begin_helper, end_helper};
+
+pub const SBI_GETCHAR: &str = "__sysy_sbi_getchar";
+pub const SBI_PUTCHAR: &str = "__sysy_sbi_putchar";
+
+const START: &str = "_start";
+const STACK_TOP: &str = ".L__sysy_stack_top";
+const STACK_SIZE: i32 = 1 << 20;
+
+// https://github.com/riscv-non-isa/riscv-sbi-doc
+pub const EID_LEGACY_PUTCHAR: i32 = 0x01;
+const EID_LEGACY_GETCHAR: i32 = 0x02;
+const EID_LEGACY_SHUTDOWN: i32 = 0x08;
+const EID_SRST: i32 = 0x53525354;
+const SRST_SHUTDOWN: i32 = 0;
+
+/// 入口 `_start` 与栈空间，应排在所有代码之前
+pub fn generate_start() -> Riscv {
+  let mut r = Riscv::new();
+  r.add_directive(Directive::Text);
+  r.add_directive(Directive::Globl(START.into()));
+  r.add_directive(Directive::Type(START.into(), SymbolType::Function));
+  r.add_label(START.into());
+  r.add_inst(Inst::La(Reg::Sp, STACK_TOP.into()));
+  r.add_inst(Inst::Call("main".into()));
+  r.add_inst(Inst::Snez(Reg::A1, Reg::A0));
+  add_shutdown(&mut r, START);
+  let end_label = func_end_label(START);
+  r.add_label(end_label.clone());
+  r.add_directive(Directive::SizeTo(START.into(), end_label));
+  r.add_empty();
+
+  r.add_directive(Directive::Bss);
+  r.add_directive(Directive::Align(4));
+  r.add_directive(Directive::Zero(STACK_SIZE));
+  r.add_label(STACK_TOP.into());
+  r.add_empty();
+  r
+}
+
+/// 以 `a1` 为原因（0 为无，1 为系统故障）关机，不再返回；`name` 是所在的例程
+pub fn add_shutdown(r: &mut Riscv, name: &str) {
+  r.add_inst(Inst::Li(Reg::A0, SRST_SHUTDOWN));
+  r.add_inst(Inst::Li(Reg::A6, 0));
+  r.add_inst(Inst::Li(Reg::A7, EID_SRST));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Li(Reg::A7, EID_LEGACY_SHUTDOWN));
+  r.add_inst(Inst::Ecall);
+  let halt = format!(".L{}_halt", name);
+  r.add_label(halt.clone());
+  r.add_inst(Inst::J(halt));
+}
+
+/// `putch`/`getch` 所用的 SBI 调用，遵循标准调用约定
+pub fn generate_sbi_calls() -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, SBI_PUTCHAR);
+  r.add_inst(Inst::Li(Reg::A7, EID_LEGACY_PUTCHAR));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, SBI_PUTCHAR);
+
+  // 没有输入时返回 -1，此时继续等待
+  let retry = format!(".L{}_retry", SBI_GETCHAR);
+  begin_helper(&mut r, SBI_GETCHAR);
+  r.add_label(retry.clone());
+  r.add_inst(Inst::Li(Reg::A7, EID_LEGACY_GETCHAR));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Slt(Reg::T0, Reg::A0, Reg::Zero));
+  r.add_inst(Inst::Bnez(Reg::T0, retry));
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, SBI_GETCHAR);
+  r
+}
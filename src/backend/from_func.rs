@@ -1,17 +1,42 @@
 use std::cmp;
 use std::collections::HashMap;
+use std::iter;
+use std::ops::Range;
 
 use koopa::ir::dfg::DataFlowGraph;
-use koopa::ir::{BasicBlock, Function, Program, Type, TypeKind, Value, ValueKind};
+use koopa::ir::{BasicBlock, BinaryOp, Function, Type, TypeKind, Value, ValueKind};
+use log::debug;
 
 use super::error::LabelNotExistError;
 use super::from_value;
-use super::riscv::Riscv;
-use super::riscv::directive::Directive;
+use super::layout;
+use super::mir::{Mir, VReg};
+use super::riscv::directive::{Directive, SymbolType};
 use super::riscv::{inst::Inst, reg::Reg};
-use super::{DEBUG_INFO, VAR_NAMES};
+use super::riscv::{Extensions, Target, Xlen};
+use super::{Listing, Options, SourceInfo};
+use crate::frontend::Ir;
+use crate::stats::Frame;
 use crate::Result;
 
+/// 常量池的最大项数，使池内偏移量可直接作为 `lw` 的立即数
+const MAX_POOL_SIZE: usize = 512;
+
+/// 同时缓存的寄存器数。单条 IR 指令至多同时占用 4 个临时寄存器，
+/// 再缓存 2 个恰好用尽可分配的 6 个寄存器
+const MAX_CACHED: usize = 2;
+
+/// 寄存器中缓存的内容
+#[derive(Clone, PartialEq)]
+enum Cached {
+  /// 标号的完整地址
+  Addr(String),
+  /// 标号地址的 `%hi` 部分
+  Hi(String),
+  /// IR Value 的值，与其在栈上的副本一致
+  Value(Value),
+}
+
 static CALL_REGS: [Reg; 8] = [
   Reg::A0,
   Reg::A1,
@@ -23,6 +48,47 @@ static CALL_REGS: [Reg; 8] = [
   Reg::A7,
 ];
 
+/// 类型在栈上的对齐要求
+fn align_of(ty: &Type) -> i32 {
+  match ty.kind() {
+    TypeKind::Array(base, _) => align_of(base),
+    TypeKind::Unit => 1,
+    _ => ty.size() as i32,
+  }
+}
+
+fn align_to(offset: i32, align: i32) -> i32 {
+  (offset + align - 1) / align * align
+}
+
+/// 生存期为 `lifetime` 的数组在栈上的位置：`base` 之上不与 `placed` 中同时存活的数组重叠的最低
+/// 位置。`placed` 是已分配的各数组的生存期与所占的范围
+fn place(
+  placed: &[(Range<u32>, Range<i32>)],
+  lifetime: &Range<u32>,
+  base: i32,
+  align: i32,
+  size: i32,
+) -> i32 {
+  let live: Vec<_> = placed
+    .iter()
+    .filter(|(other, _)| other.start < lifetime.end && lifetime.start < other.end)
+    .map(|(_, slot)| slot)
+    .collect();
+  let free = |&offset: &i32| {
+    let end = offset + size;
+    live
+      .iter()
+      .all(|slot| end <= slot.start || slot.end <= offset)
+  };
+  let candidates = iter::once(base).chain(live.iter().map(|slot| slot.end));
+  candidates
+    .map(|offset| align_to(offset, align))
+    .filter(free)
+    .min()
+    .unwrap()
+}
+
 pub struct GenerateContext<'a> {
   /// 局部变量（Alloc）到内存位置的映射
   pub locals: HashMap<Value, i32>,
@@ -37,24 +103,63 @@ pub struct GenerateContext<'a> {
   pub labels: HashMap<BasicBlock, String>,
 
   /// 已生成指令序列
-  pub insts: Riscv,
+  pub insts: Mir,
+  /// 下一个虚拟寄存器的编号
+  next_vreg: u32,
 
   /// IR 数据
-  pub program: &'a Program,
+  pub program: &'a Ir,
   pub func: Function,
+
+  /// 目标寄存器宽度
+  pub xlen: Xlen,
+  /// 目标支持的扩展
+  pub ext: Extensions,
+
+  /// 代码生成选项
+  pub options: Options,
+  /// 常量池（`--const-pool`）中的常量
+  const_pool: Option<Vec<i32>>,
+  /// 自上一条控制流指令以来寄存器中已有的地址与值，最近使用的在末尾
+  cached: Vec<(Cached, VReg)>,
+  /// 排布中紧随当前块之后的块，转移到它时直接落下
+  pub next_bb: Option<BasicBlock>,
+
+  /// 需要生成汇编注释或调试信息时的源代码信息，以及最近一次标注的行号
+  pub source: Option<SourceInfo<'a>>,
+  pub source_line: usize,
+
+  /// 保存到栈上的计算结果的个数与从栈上重新加载的次数（`--stats`）
+  spills: usize,
+  reloads: usize,
 }
 
 impl<'a> GenerateContext<'a> {
-  fn from(prog: &'a Program, func: Function) -> Result<Self> {
-    // 分配局部变量空间
+  fn from(
+    prog: &'a Ir,
+    func: Function,
+    xlen: Xlen,
+    ext: Extensions,
+    options: Options,
+    source: Option<SourceInfo<'a>>,
+  ) -> Result<Self> {
+    // 分配局部变量空间。生存期已知的数组（见 [`crate::frontend::Annotations::lifetimes`]）
+    // 放在其余的之上，生存期不相交的共用空间
     let mut locals = HashMap::new();
     let mut local_size = 0;
+    let mut scoped = vec![];
+    let lifetimes = &prog.annotations.lifetimes;
     let bbs = prog.func(func).layout().bbs();
     for (_, node) in bbs {
       for &v in node.insts().keys() {
         if let ValueKind::Alloc(_) = prog.func(func).dfg().value(v).kind() {
-          locals.insert(v, local_size);
           if let TypeKind::Pointer(base) = prog.func(func).dfg().value(v).ty().kind() {
+            if let Some(lifetime) = lifetimes.get(&v) {
+              scoped.push((v, lifetime.clone(), base.clone()));
+              continue;
+            }
+            local_size = align_to(local_size, align_of(base));
+            locals.insert(v, local_size);
             local_size += base.size() as i32;
           } else {
             panic!("alloc do not have pointer type");
@@ -62,36 +167,70 @@ impl<'a> GenerateContext<'a> {
         }
       }
     }
+    let mut placed = vec![];
+    for (v, lifetime, base) in scoped {
+      let size = base.size() as i32;
+      let offset = place(&placed, &lifetime, local_size, align_of(&base), size);
+      locals.insert(v, offset);
+      placed.push((lifetime, offset..offset + size));
+    }
+    local_size = placed
+      .iter()
+      .map(|(_, slot)| slot.end)
+      .fold(local_size, cmp::max);
 
     // 分配计算结果空间
     let mut temps = HashMap::new();
     let mut temp_size = local_size;
     for (_, node) in bbs {
       for &v in node.insts().keys() {
+        let ty = prog.func(func).dfg().value(v).ty();
+        temp_size = align_to(temp_size, align_of(ty));
         temps.insert(v, temp_size);
-        temp_size += prog.func(func).dfg().value(v).ty().size() as i32;
+        temp_size += ty.size() as i32;
       }
     }
 
+    let inline_asm = &prog.annotations.inline_asm;
     let calls: Vec<_> = prog
       .func(func)
       .dfg()
       .values()
       .iter()
       .filter_map(|(_, vd)| match vd.kind() {
-        ValueKind::Call(func) => Some(func.args().len() as i32),
+        // 内联汇编不是真正的调用，不需要保存 `ra` 与传参空间
+        ValueKind::Call(func) if !inline_asm.contains_key(&func.callee()) => {
+          Some(func.args().len() as i32)
+        }
         _ => None,
       })
       .collect();
 
-    let size_s = temp_size;
-    let size_r = if calls.len() > 0 { 4 } else { 0 };
+    // 没有 M 扩展时，乘除法需要调用辅助例程
+    let has_soft_muldiv = !ext.m
+      && prog
+        .func(func)
+        .dfg()
+        .values()
+        .iter()
+        .any(|(_, vd)| match vd.kind() {
+          ValueKind::Binary(b) => matches!(b.op(), BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod),
+          _ => false,
+        });
+
+    let word = xlen.bytes() as i32;
+    let size_s = align_to(temp_size, word);
+    let size_r = if calls.len() > 0 || has_soft_muldiv {
+      word
+    } else {
+      0
+    };
     let size_a = calls
       .iter()
       .map(|len| cmp::max(len - 8, 0))
       .max()
       .unwrap_or(0)
-      * 4;
+      * word;
 
     let size = (size_s + size_r + size_a + 15) & !15;
 
@@ -102,15 +241,32 @@ impl<'a> GenerateContext<'a> {
       size_r,
       frame_size: size,
       labels: HashMap::new(),
-      insts: Riscv::new(),
+      insts: Mir::new(),
+      next_vreg: 0,
       program: prog,
       func,
+      xlen,
+      ext,
+      options,
+      const_pool: options.const_pool.then(Vec::new),
+      cached: vec![],
+      next_bb: None,
+      source,
+      source_line: 0,
+      spills: 0,
+      reloads: 0,
     };
 
     // PROLOGUE
-    this.push_inst(Inst::Addi(Reg::Sp, Reg::Sp, -size));
+    let sp = Reg::Sp.into();
+    this.push_inst(Inst::Addi(sp, sp, -size));
     if size_r != 0 {
-      this.push_inst(Inst::Sw(Reg::Ra, this.frame_size - 4, Reg::Sp));
+      this.push_inst(Inst::store(
+        xlen.bytes(),
+        Reg::Ra.into(),
+        this.frame_size - size_r,
+        sp,
+      ));
     }
 
     Ok(this)
@@ -132,35 +288,163 @@ impl<'a> GenerateContext<'a> {
     self.program.borrow_value(value).ty().clone()
   }
 
+  /// 保存该 Value 所需的访存宽度
+  pub fn width_of(&self, value: Value) -> usize {
+    self.value_type(value).size()
+  }
+
   pub fn is_global_value(&self, v: Value) -> Result<Option<String>> {
     if self.dfg().values().get(&v).is_some() {
       Ok(None)
     } else {
-      let var = VAR_NAMES
-        .read()?
-        .get(&v)
-        .cloned()
-        .ok_or("global variable not found".to_string())?;
-      Ok(Some(var))
+      let values = self.program.borrow_values();
+      let name = values.get(&v).and_then(|vd| vd.name().clone());
+      let name = name.ok_or("global variable not found".to_string())?;
+      Ok(Some(name[1..].into()))
     }
   }
 
-  pub fn push_inst(&mut self, inst: Inst) {
+  pub fn push_inst(&mut self, inst: Inst<VReg>) {
+    // 缓存不跨越控制流：调用会破坏寄存器，基本块入口处也未必已经计算
+    if inst.is_control() {
+      self.cached.clear();
+    }
     self.insts.add_inst(inst);
   }
 
+  /// 分配一个新的虚拟寄存器
+  pub fn new_vreg(&mut self) -> VReg {
+    self.next_vreg += 1;
+    VReg::Virt(self.next_vreg - 1)
+  }
+
+  fn pool_label(&self) -> String {
+    format!(".LCPI_{}", &self.program.func(self.func).name()[1..])
+  }
+
+  /// 常量在池中的偏移；未启用常量池、一条 `li` 即可得到或池已满时返回 `None`
+  fn pool_offset(&mut self, imm: i32) -> Option<i32> {
+    let pool = self.const_pool.as_mut()?;
+    if (-2048..2048).contains(&imm) || imm & 0xfff == 0 {
+      return None;
+    }
+    let index = match pool.iter().position(|&x| x == imm) {
+      Some(index) => index,
+      None if pool.len() < MAX_POOL_SIZE => {
+        pool.push(imm);
+        pool.len() - 1
+      }
+      None => return None,
+    };
+    Some(index as i32 * 4)
+  }
+
+  fn is_cached(&self, content: &Cached) -> bool {
+    self.cached.iter().any(|(c, _)| c == content)
+  }
+
+  fn lookup_cached(&mut self, content: &Cached) -> Option<VReg> {
+    let index = self.cached.iter().position(|(c, _)| c == content)?;
+    let entry = self.cached.remove(index);
+    let reg = entry.1;
+    self.cached.push(entry);
+    Some(reg)
+  }
+
+  /// 记录寄存器的内容；只缓存虚拟寄存器，物理寄存器随时可能被改写
+  fn cache(&mut self, content: Cached, reg: VReg) {
+    if let VReg::Phys(_) = reg {
+      return;
+    }
+    if self.cached.len() == MAX_CACHED {
+      self.cached.remove(0);
+    }
+    self.cached.push((content, reg));
+  }
+
+  /// 存有 `%hi(label)` 的寄存器，仅用于非位置无关代码
+  fn global_hi(&mut self, label: &str) -> VReg {
+    if let Some(reg) = self.lookup_cached(&Cached::Hi(label.into())) {
+      return reg;
+    }
+    let reg = self.new_vreg();
+    self.push_inst(Inst::Lui(reg, label.into()));
+    self.cache(Cached::Hi(label.into()), reg);
+    reg
+  }
+
+  /// 存有标号 `label` 地址的寄存器
+  pub fn global_addr(&mut self, label: &str) -> VReg {
+    if let Some(reg) = self.lookup_cached(&Cached::Addr(label.into())) {
+      return reg;
+    }
+    let reg = self.new_vreg();
+    if self.options.pic {
+      self.push_inst(Inst::La(reg, label.into()));
+    } else {
+      let hi = self.global_hi(label);
+      self.push_inst(Inst::AddiLo(reg, hi, label.into()));
+    }
+    self.cache(Cached::Addr(label.into()), reg);
+    reg
+  }
+
+  /// 读取全局变量；非位置无关代码中把 `%lo` 折叠进访存指令
+  pub fn load_global(&mut self, width: usize, rd: VReg, label: &str) {
+    if self.options.pic || self.is_cached(&Cached::Addr(label.into())) {
+      let addr = self.global_addr(label);
+      self.push_inst(Inst::load(width, rd, 0, addr));
+    } else {
+      let hi = self.global_hi(label);
+      self.push_inst(Inst::load_lo(width, rd, label.into(), hi));
+    }
+  }
+
+  /// 写入全局变量，方式同 [`Self::load_global`]
+  pub fn store_global(&mut self, width: usize, rs: VReg, label: &str) {
+    if self.options.pic || self.is_cached(&Cached::Addr(label.into())) {
+      let addr = self.global_addr(label);
+      self.push_inst(Inst::store(width, rs, 0, addr));
+    } else {
+      let hi = self.global_hi(label);
+      self.push_inst(Inst::store_lo(width, rs, label.into(), hi));
+    }
+  }
+
+  /// 常量池的数据；常量池未启用或为空时不生成
+  fn generate_pool(&self) -> Mir {
+    let mut result = Mir::new();
+    match &self.const_pool {
+      Some(pool) if !pool.is_empty() => {
+        result.add_directive(Directive::Section(".rodata".into()));
+        result.add_directive(Directive::Align(2));
+        result.add_label(self.pool_label());
+        result.add_directive(Directive::Word(pool.clone()));
+      }
+      _ => {}
+    }
+    result
+  }
+
   pub fn set_args(&mut self, args: &[Value]) -> Result<()> {
     for (&arg, &reg) in args.iter().zip(CALL_REGS.iter()) {
-      let mut rd = reg;
+      let mut rd = reg.into();
       self.load_value_to_reg(arg, &mut rd)?;
-      if rd != reg {
-        self.push_inst(Inst::Add(reg, Reg::Zero, rd));
+      if rd != VReg::Phys(reg) {
+        self.push_inst(Inst::Add(reg.into(), Reg::Zero.into(), rd));
       }
     }
     if args.len() > 8 {
+      let word = self.xlen.bytes() as i32;
       for (i, &arg) in args[8..].iter().enumerate() {
-        self.load_value_to_reg(arg, &mut Reg::T0)?;
-        self.push_inst(Inst::Sw(Reg::T0, i as i32 * 4, Reg::Sp));
+        let mut rd = self.new_vreg();
+        self.load_value_to_reg(arg, &mut rd)?;
+        self.push_inst(Inst::store(
+          self.width_of(arg),
+          rd,
+          i as i32 * word,
+          Reg::Sp.into(),
+        ));
       }
     }
     Ok(())
@@ -168,11 +452,18 @@ impl<'a> GenerateContext<'a> {
 
   pub fn generate_epilogue(&mut self) {
     // EPILOGUE
+    let sp = Reg::Sp.into();
     if self.size_r != 0 {
-      self.push_inst(Inst::Lw(Reg::Ra, self.frame_size - 4, Reg::Sp));
+      let word = self.xlen.bytes();
+      self.push_inst(Inst::load(
+        word,
+        Reg::Ra.into(),
+        self.frame_size - self.size_r,
+        sp,
+      ));
     }
 
-    self.push_inst(Inst::Addi(Reg::Sp, Reg::Sp, self.frame_size));
+    self.push_inst(Inst::Addi(sp, sp, self.frame_size));
     self.push_inst(Inst::Ret);
   }
 
@@ -200,80 +491,166 @@ impl<'a> GenerateContext<'a> {
   }
 
   /// 将 Value 加载到寄存器；必要时修改目标寄存器
-  pub fn load_value_to_reg(&mut self, value: Value, reg: &mut Reg) -> Result<()> {
+  pub fn load_value_to_reg(&mut self, value: Value, reg: &mut VReg) -> Result<()> {
     let kind = self.dfg().value(value).kind();
     if let ValueKind::Integer(integer) = kind {
       // Alloc a register for storing a integer.
       let integer = integer.value();
-      if integer != 0 {
+      if let Some(offset) = self.pool_offset(integer) {
+        // Large constant, load it from the pool.
+        let base = self.global_addr(&self.pool_label());
+        self.push_inst(Inst::Lw(*reg, offset, base));
+      } else if integer != 0 {
         // For non-zero value, use a temp register, then `li` the immediate into it.
         self.push_inst(Inst::Li(*reg, integer));
       } else {
         // For zero, use `zero` register.
-        *reg = Reg::Zero;
+        *reg = Reg::Zero.into();
       }
     } else if let ValueKind::FuncArgRef(arg_ref) = kind {
       // Load function param, from reg or stack.
       let i = arg_ref.index();
       if i < 8 {
-        *reg = CALL_REGS[i];
+        *reg = CALL_REGS[i].into();
       } else {
-        let offset = self.frame_size + (i - 8) as i32 * 4;
-        self.push_inst(Inst::Lw(*reg, offset, Reg::Sp));
+        let offset = self.frame_size + (i - 8) as i32 * self.xlen.bytes() as i32;
+        self.push_inst(Inst::load(
+          self.width_of(value),
+          *reg,
+          offset,
+          Reg::Sp.into(),
+        ));
       }
+    } else if let Some(cached) = self.lookup_cached(&Cached::Value(value)) {
+      // Loaded or computed earlier in this block, reuse the register.
+      *reg = cached;
     } else {
       // Load local variable from stack.
       let offset = self.get_offset(value)?;
-      self.push_inst(Inst::Lw(*reg, offset, Reg::Sp));
+      self.push_inst(Inst::load(
+        self.width_of(value),
+        *reg,
+        offset,
+        Reg::Sp.into(),
+      ));
+      self.cache(Cached::Value(value), *reg);
+      self.reloads += 1;
     }
     Ok(())
   }
 
   /// 将 Value 保存到寄存器
-  pub fn save_value_from_reg(&mut self, value: Value, reg: Reg) -> Result<()> {
+  pub fn save_value_from_reg(&mut self, value: Value, reg: VReg) -> Result<()> {
     let offset = self.get_offset(value)?;
-    self.push_inst(Inst::Sw(reg, offset, Reg::Sp));
+    self.push_inst(Inst::store(
+      self.width_of(value),
+      reg,
+      offset,
+      Reg::Sp.into(),
+    ));
+    self.cache(Cached::Value(value), reg);
+    self.spills += 1;
     Ok(())
   }
 }
 
-pub fn generate(program: &Program, func: Function) -> Result<Riscv> {
+/// 函数代码结束处的标号，用于计算函数大小
+pub fn func_end_label(name: &str) -> String {
+  format!(".Lfunc_end_{}", name)
+}
+
+/// 为函数做指令选择，生成使用虚拟寄存器的 MIR，以及 `--stats` 所用的栈帧统计（只有声明的
+/// 函数没有）
+pub fn generate(
+  program: &Ir,
+  func: Function,
+  listing: &mut Listing,
+  target: Target,
+  options: Options,
+  source: Option<SourceInfo>,
+) -> Result<(Mir, Option<Frame>)> {
   let func_data = program.func(func);
   let func_name = &func_data.name()[1..];
 
-  let mut result = Riscv::new();
+  let mut result = Mir::new();
   if func_data.layout().entry_bb().is_none() {
     // Function declaration, skip.
-    DEBUG_INFO.write()?.pop_front();
-    DEBUG_INFO.write()?.pop_front();
-    return Ok(result);
+    listing.next_line()?;
+    listing.next_line()?;
+    return Ok((result, None));
   }
+  debug!("generating {}", func_name);
 
-  result.add_comment(DEBUG_INFO.write()?.pop_front().unwrap());
+  let comments = source.is_some_and(|source| source.comments);
+  let comment = listing.next_line()?;
+  if comments {
+    result.add_comment(comment);
+  }
+  // 各函数以 `.text` 开头（按函数运行的遍以此划分），`section` 属性指定的节在其后切换
   result.add_directive(Directive::Text);
+  let attrs = program.annotations.func_attrs.get(&func);
+  if let Some(section) = attrs.and_then(|attrs| attrs.section.as_ref()) {
+    result.add_directive(Directive::Section(format!("{},\"ax\",@progbits", section)));
+  }
   result.add_directive(Directive::Globl(func_name.into()));
+  result.add_directive(Directive::Type(func_name.into(), SymbolType::Function));
   result.add_label(func_name.into());
-  let mut context = GenerateContext::from(program, func)?;
+  // 序言归属于函数定义所在的行
+  if let Some(source) = source.filter(|source| source.debug) {
+    if let Some(line) = source.map.func_line(func) {
+      result.add_directive(Directive::Loc(line));
+    }
+  }
+  let mut context = GenerateContext::from(program, func, target.xlen, target.ext, options, source)?;
 
   // Generate map from BB to label
   for (&bb, _) in func_data.layout().bbs() {
     let bb_name = &func_data.dfg().bb(bb).name().clone().unwrap()[1..];
-    let label_name = format!("{}_{}", func_name, bb_name);
+    let label_name = format!(".L{}_{}", func_name, bb_name);
     context.labels.insert(bb, label_name.clone());
   }
 
+  // 注释按 IR 中的顺序给出，排布前先按块取出
+  let mut bb_comments = HashMap::new();
   for (&bb, node) in func_data.layout().bbs() {
+    let comment = listing.next_line()?;
+    let inst_comments = node.insts().keys().map(|_| listing.next_line());
+    let inst_comments = inst_comments.collect::<Result<Vec<_>>>()?;
+    listing.next_line()?;
+    bb_comments.insert(bb, (comment, inst_comments));
+  }
+  listing.next_line()?;
+
+  // `--stats` 据此给出寄存器压力最大处的源代码行
+  let mut lines = vec![];
+  let order = layout::layout(func_data, &program.annotations.branch_hints);
+  for (i, &bb) in order.iter().enumerate() {
     let label = context.get_label(bb)?;
-    context.insts.add_comment(DEBUG_INFO.write()?.pop_front().unwrap());
+    let (comment, inst_comments) = bb_comments.remove(&bb).unwrap();
+    if comments {
+      context.insts.add_comment(comment);
+    }
     context.insts.add_label(label);
-    for &i in node.insts().keys() {
-      from_value::generate(i, &mut context)?;
+    // 块可能从别处跳转而来：不沿用上一块的缓存与行号
+    context.cached.clear();
+    context.source_line = 0;
+    context.next_bb = order.get(i + 1).copied();
+    let node = func_data.layout().bbs().node(&bb).unwrap();
+    for (&inst, comment) in node.insts().keys().zip(inst_comments) {
+      if let Some((line, _)) = source.and_then(|source| source.map.line_of(inst)) {
+        lines.push((result.0.len() + context.insts.0.len(), line));
+      }
+      from_value::generate(inst, comment, &mut context)?;
     }
     result.append(&mut context.insts);
-    DEBUG_INFO.write()?.pop_front();
   }
-  DEBUG_INFO.write()?.pop_front();
+  let end_label = func_end_label(func_name);
+  result.add_label(end_label.clone());
+  result.add_directive(Directive::SizeTo(func_name.into(), end_label));
+  result.append(&mut context.generate_pool());
   result.add_empty();
+  let counts = (context.spills, context.reloads);
+  let frame = Frame::new(counts, context.frame_size, lines);
 
-  Ok(result)
+  Ok((result, Some(frame)))
 }
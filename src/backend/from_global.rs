@@ -0,0 +1,102 @@
+use koopa::ir::{Program, Type, TypeKind, Value, ValueKind};
+
+use super::riscv::directive::{Directive, SymbolType};
+use super::riscv::Riscv;
+use crate::Result;
+
+/// 连续零值达到该数量（字）时改用 `.zero` 输出
+const MIN_ZERO_RUN: usize = 4;
+
+/// 全局变量所在的段
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+  /// 全零初始化
+  Bss,
+  /// 有非零初始值。SysY 的全局常量在使用处折叠，不生成对象；这里的全局变量都可能被修改
+  /// （导出的符号也可能在别的目标文件中被写入），因此不放入 `.rodata`
+  Data,
+}
+
+/// 把初始值展开为 `(字, 连续的个数)`，相邻的零值合并为一段，`zeroinit` 不逐字展开
+fn flatten_initializer(ir: &Program, value: Value, runs: &mut Vec<(i32, usize)>) {
+  let (word, count) = match ir.borrow_value(value).kind() {
+    ValueKind::Integer(i) => (i.value(), 1),
+    ValueKind::Aggregate(agg) => {
+      for i in agg.elems() {
+        flatten_initializer(ir, *i, runs);
+      }
+      return;
+    }
+    ValueKind::ZeroInit(_) => (0, ir.borrow_value(value).ty().size() / 4),
+    _ => panic!("initializer not integer nor aggregate"),
+  };
+  match runs.last_mut() {
+    Some((0, zeros)) if word == 0 => *zeros += count,
+    _ => runs.push((word, count)),
+  }
+}
+
+/// 对齐要求（以 2 为底的对数，即 `.align` 的参数）
+fn align_log2(ty: &Type) -> u32 {
+  match ty.kind() {
+    TypeKind::Array(base, _) => align_log2(base),
+    _ => ty.size().trailing_zeros(),
+  }
+}
+
+/// 数据段内容：非零部分用 `.word`，较长的零值连续段用 `.zero`
+fn data_directives(runs: &[(i32, usize)]) -> Vec<Directive> {
+  let mut result = vec![];
+  let mut words = vec![];
+  for &(word, count) in runs {
+    if word == 0 && count >= MIN_ZERO_RUN {
+      if !words.is_empty() {
+        result.push(Directive::Word(std::mem::take(&mut words)));
+      }
+      result.push(Directive::Zero(count as i32 * 4));
+    } else {
+      words.extend(std::iter::repeat_n(word, count));
+    }
+  }
+  if !words.is_empty() {
+    result.push(Directive::Word(words));
+  }
+  result
+}
+
+/// 生成全局变量 `value`（名为 `name`）的数据定义
+pub fn generate(ir: &Program, value: Value, name: &str) -> Result<Riscv> {
+  let vd = ir.borrow_value(value);
+  let (init, base) = match (vd.kind(), vd.ty().kind()) {
+    (ValueKind::GlobalAlloc(alloc), TypeKind::Pointer(base)) => (alloc.init(), base.clone()),
+    _ => panic!("global alloc do not have pointer type"),
+  };
+  let size = base.size();
+  let mut data = vec![];
+  flatten_initializer(ir, init, &mut data);
+  let section = match data.iter().all(|&(word, _)| word == 0) {
+    true => Section::Bss,
+    false => Section::Data,
+  };
+  let align = align_log2(&base);
+
+  let mut result = Riscv::new();
+  result.add_directive(match section {
+    Section::Bss => Directive::Bss,
+    Section::Data => Directive::Data,
+  });
+  result.add_directive(Directive::Globl(name.into()));
+  result.add_directive(Directive::Type(name.into(), SymbolType::Object));
+  result.add_directive(Directive::Align(align));
+  result.add_label(name.into());
+  match section {
+    Section::Data => {
+      for directive in data_directives(&data) {
+        result.add_directive(directive);
+      }
+    }
+    Section::Bss => result.add_directive(Directive::Zero(size as i32)),
+  }
+  result.add_directive(Directive::Size(name.into(), size));
+  Ok(result)
+}
@@ -1,24 +1,73 @@
+use std::sync::Arc;
+
 use koopa::ir::{BinaryOp, TypeKind, Value, ValueKind};
 
+use super::error::*;
 use super::from_func::GenerateContext;
+use super::mir::VReg;
+use super::riscv::directive::Directive;
 use super::riscv::inst::Inst;
 use super::riscv::reg::Reg;
-use super::{error::*, DEBUG_INFO, FUNC_NAMES};
+use super::riscv::Xlen;
+use super::soft_muldiv::{self, DIVMOD, MEMZERO, MUL};
+use super::source_map::Origin;
 use crate::Result;
 
-pub fn generate(value: Value, context: &mut GenerateContext) -> Result<()> {
-  context
-    .insts
-    .add_comment(DEBUG_INFO.write()?.pop_front().unwrap());
+/// 清零内存时逐字写入的最多字数，更长时调用辅助例程
+const MAX_INLINE_ZERO_WORDS: usize = 8;
+
+/// `comment` 为该指令的 IR 文本，用于汇编注释
+pub fn generate(value: Value, comment: String, context: &mut GenerateContext) -> Result<()> {
+  if let Some(source) = context.source {
+    // 进入新的源代码行时，先标注该行
+    if let Some((line, text)) = source.map.line_of(value) {
+      if line != context.source_line {
+        context.source_line = line;
+        if source.debug {
+          context.insts.add_directive(Directive::Loc(line));
+        }
+        if source.comments {
+          context
+            .insts
+            .add_comment(format!("{}  (line {})", text, line));
+        }
+      }
+    }
+    if source.origins {
+      let line = source.map.line_of(value).map(|(line, _)| line);
+      let origin = Origin {
+        line,
+        koopa: comment.trim().into(),
+      };
+      context
+        .insts
+        .add_directive(Directive::Origin(Arc::new(origin)));
+    }
+    if source.comments {
+      context.insts.add_comment(comment);
+    }
+  }
+  let ptr = match context.value_kind(value) {
+    ValueKind::Load(load) => Some(load.src()),
+    ValueKind::Store(store) => Some(store.dest()),
+    _ => None,
+  };
+  if ptr.is_some_and(|ptr| context.program.annotations.is_volatile(context.dfg(), ptr)) {
+    context.insts.add_directive(Directive::Volatile);
+  }
   match context.value_kind(value) {
     ValueKind::Binary(binary) => {
+      if let Some(rd) = generate_const_mul(context, binary.op(), binary.lhs(), binary.rhs())? {
+        context.save_value_from_reg(value, rd)?;
+        return Ok(());
+      }
       let lhs = binary.lhs();
-      let mut rs1 = Reg::T0;
+      let mut rs1 = context.new_vreg();
       context.load_value_to_reg(lhs, &mut rs1)?;
       let rhs = binary.rhs();
-      let mut rs2 = Reg::T1;
+      let mut rs2 = context.new_vreg();
       context.load_value_to_reg(rhs, &mut rs2)?;
-      let rd = Reg::T2;
+      let mut rd = context.new_vreg();
       match binary.op() {
         BinaryOp::And => {
           context.push_inst(Inst::And(rd, rs1, rs2));
@@ -48,92 +97,123 @@ pub fn generate(value: Value, context: &mut GenerateContext) -> Result<()> {
           context.push_inst(Inst::Slt(rd, rs1, rs2));
           context.push_inst(Inst::Seqz(rd, rd));
         }
-        BinaryOp::Add => {
-          context.push_inst(Inst::Add(rd, rs1, rs2));
-        }
-        BinaryOp::Sub => {
-          context.push_inst(Inst::Sub(rd, rs1, rs2));
-        }
-        BinaryOp::Mul => {
-          context.push_inst(Inst::Mul(rd, rs1, rs2));
-        }
-        BinaryOp::Div => {
-          context.push_inst(Inst::Div(rd, rs1, rs2));
-        }
-        BinaryOp::Mod => {
-          context.push_inst(Inst::Rem(rd, rs1, rs2));
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod if !context.ext.m => {
+          rd = generate_soft_muldiv(context, binary.op(), rs1, rs2);
         }
+        // RV64 下使用 *w 指令，结果保持 32 位符号扩展
+        BinaryOp::Add => context.push_inst(match context.xlen {
+          Xlen::Rv32 => Inst::Add(rd, rs1, rs2),
+          Xlen::Rv64 => Inst::Addw(rd, rs1, rs2),
+        }),
+        BinaryOp::Sub => context.push_inst(match context.xlen {
+          Xlen::Rv32 => Inst::Sub(rd, rs1, rs2),
+          Xlen::Rv64 => Inst::Subw(rd, rs1, rs2),
+        }),
+        BinaryOp::Mul => context.push_inst(match context.xlen {
+          Xlen::Rv32 => Inst::Mul(rd, rs1, rs2),
+          Xlen::Rv64 => Inst::Mulw(rd, rs1, rs2),
+        }),
+        BinaryOp::Div => context.push_inst(match context.xlen {
+          Xlen::Rv32 => Inst::Div(rd, rs1, rs2),
+          Xlen::Rv64 => Inst::Divw(rd, rs1, rs2),
+        }),
+        BinaryOp::Mod => context.push_inst(match context.xlen {
+          Xlen::Rv32 => Inst::Rem(rd, rs1, rs2),
+          Xlen::Rv64 => Inst::Remw(rd, rs1, rs2),
+        }),
         x => return Err(UnimplementedError(Box::from(x)).into()),
       }
       context.save_value_from_reg(value, rd)?;
     }
     ValueKind::Return(ret) => {
       if let Some(retval) = ret.value() {
-        let mut rs = Reg::A0;
+        let mut rs = Reg::A0.into();
         context.load_value_to_reg(retval, &mut rs)?;
-        if rs != Reg::A0 {
-          context.push_inst(Inst::Mv(Reg::A0, rs));
+        if rs != VReg::Phys(Reg::A0) {
+          context.push_inst(Inst::Mv(Reg::A0.into(), rs));
         }
       }
       context.generate_epilogue();
     }
     ValueKind::Alloc(_) => {
       let offset = context.get_local(value);
-      context.push_inst(Inst::Addi(Reg::T2, Reg::Sp, offset));
-      context.save_value_from_reg(value, Reg::T2)?;
+      let rd = context.new_vreg();
+      context.push_inst(Inst::Addi(rd, Reg::Sp.into(), offset));
+      context.save_value_from_reg(value, rd)?;
+    }
+    ValueKind::Store(store)
+      if matches!(context.value_kind(store.value()), ValueKind::ZeroInit(_)) =>
+    {
+      let size = context.dfg().value(store.value()).ty().size();
+      generate_zero_fill(context, store.dest(), size)?;
     }
     ValueKind::Store(store) => {
       let value = store.value();
-      let mut rs = Reg::T0;
-      let mut rd = Reg::T1;
+      let mut rs = context.new_vreg();
       let dest = store.dest();
+      let width = context.width_of(value);
       context.load_value_to_reg(value, &mut rs)?;
       if let Some(var) = context.is_global_value(dest)? {
-        context.push_inst(Inst::La(rd, var));
+        context.store_global(width, rs, &var);
       } else {
+        let mut rd = context.new_vreg();
         context.load_value_to_reg(dest, &mut rd)?;
+        context.push_inst(Inst::store(width, rs, 0, rd));
       }
-      context.push_inst(Inst::Sw(rs, 0, rd));
     }
     ValueKind::Load(load) => {
-      let rd = Reg::T2;
-      let mut rs = Reg::T0;
+      let rd = context.new_vreg();
       let src = load.src();
+      let width = context.width_of(value);
       if let Some(var) = context.is_global_value(src)? {
-        context.push_inst(Inst::La(rs, var));
+        context.load_global(width, rd, &var);
       } else {
+        let mut rs = context.new_vreg();
         context.load_value_to_reg(src, &mut rs)?;
+        context.push_inst(Inst::load(width, rd, 0, rs));
       }
-      context.push_inst(Inst::Lw(rd, 0, rs));
       context.save_value_from_reg(value, rd)?;
     }
     ValueKind::Branch(branch) => {
       let cond = branch.cond();
-      let mut rd = Reg::T0;
+      let mut rd = context.new_vreg();
       context.load_value_to_reg(cond, &mut rd)?;
       let true_bb = branch.true_bb();
       let true_label = context.get_label(true_bb)?;
-      context.push_inst(Inst::Bnez(rd, true_label));
       let false_bb = branch.false_bb();
       let false_label = context.get_label(false_bb)?;
-      context.push_inst(Inst::J(false_label));
+      // 能落下的一侧省去跳转
+      if context.next_bb == Some(true_bb) {
+        context.push_inst(Inst::Beqz(rd, false_label));
+      } else {
+        context.push_inst(Inst::Bnez(rd, true_label));
+        if context.next_bb != Some(false_bb) {
+          context.push_inst(Inst::J(false_label));
+        }
+      }
     }
     ValueKind::Jump(jump) => {
       let bb = jump.target();
-      let label = context.get_label(bb)?;
-      context.push_inst(Inst::J(label));
+      if context.next_bb != Some(bb) {
+        let label = context.get_label(bb)?;
+        context.push_inst(Inst::J(label));
+      }
     }
     ValueKind::Call(func) => {
       let args = func.args();
+      let inline_asm = &context.program.annotations.inline_asm;
+      if let Some(template) = inline_asm.get(&func.callee()).cloned() {
+        return generate_inline_asm(context, value, template, args);
+      }
       context.set_args(args)?;
-      let callee = FUNC_NAMES
-        .read()?
+      let funcs = context.program.funcs();
+      let callee = funcs
         .get(&func.callee())
-        .cloned()
-        .ok_or(LabelNotExistError("global function ??".into()))?;
+        .map(|fd| fd.name()[1..].to_string());
+      let callee = callee.ok_or(LabelNotExistError("global function ??".into()))?;
       context.push_inst(Inst::Call(callee));
       if context.value_type(value).is_i32() {
-        context.save_value_from_reg(value, Reg::A0)?;
+        context.save_value_from_reg(value, Reg::A0.into())?;
       }
     }
     ValueKind::GetElemPtr(elem_ptr) => {
@@ -163,33 +243,159 @@ pub fn generate(value: Value, context: &mut GenerateContext) -> Result<()> {
   Ok(())
 }
 
+/// 内联汇编：输入操作数装入寄存器，输出操作数由模板写入新的寄存器
+fn generate_inline_asm(
+  context: &mut GenerateContext,
+  value: Value,
+  template: String,
+  args: &[Value],
+) -> Result<()> {
+  let mut inputs = vec![];
+  for &arg in args {
+    let mut reg = context.new_vreg();
+    context.load_value_to_reg(arg, &mut reg)?;
+    inputs.push(reg);
+  }
+  let output = (!context.value_type(value).is_unit()).then(|| context.new_vreg());
+  let outputs: Vec<_> = output.into_iter().collect();
+  context.push_inst(Inst::Asm(template, outputs, inputs));
+  if let Some(output) = output {
+    context.save_value_from_reg(value, output)?;
+  }
+  Ok(())
+}
+
+/// 常数乘法展开为移位加：没有 M 扩展时以此省去辅助例程的调用，有 M 扩展时仅在展开后的
+/// 指令数不超过 `mul` 的延迟（见 [`Cpu::max_mul_terms`]）时展开。返回结果所在寄存器，
+/// 不展开时返回 `None`
+///
+/// [`Cpu::max_mul_terms`]: super::riscv::Cpu::max_mul_terms
+fn generate_const_mul(
+  context: &mut GenerateContext,
+  op: BinaryOp,
+  lhs: Value,
+  rhs: Value,
+) -> Result<Option<VReg>> {
+  if !matches!(op, BinaryOp::Mul) {
+    return Ok(None);
+  }
+  let imm = |v: Value| match context.value_kind(v) {
+    ValueKind::Integer(i) => Some(i.value()),
+    _ => None,
+  };
+  let (var, imm) = match (imm(lhs), imm(rhs)) {
+    (_, Some(imm)) => (lhs, imm),
+    (Some(imm), _) => (rhs, imm),
+    _ => return Ok(None),
+  };
+  if (imm as u32).count_ones() > max_mul_terms(context) {
+    return Ok(None);
+  }
+  let mut rs = context.new_vreg();
+  context.load_value_to_reg(var, &mut rs)?;
+  let rd = context.new_vreg();
+  let scratch = context.new_vreg();
+  for inst in soft_muldiv::expand_mul(rd, rs, imm, scratch) {
+    context.push_inst(inst);
+  }
+  if context.xlen == Xlen::Rv64 {
+    context.push_inst(Inst::SextW(rd, rd));
+  }
+  Ok(Some(rd))
+}
+
+/// 常数乘法展开为移位加时允许的最多项数
+fn max_mul_terms(context: &GenerateContext) -> u32 {
+  if context.ext.m {
+    context.options.cpu.max_mul_terms()
+  } else {
+    soft_muldiv::MAX_INLINE_TERMS
+  }
+}
+
+/// 没有 M 扩展时，调用辅助例程计算乘除法。返回结果所在寄存器
+fn generate_soft_muldiv(context: &mut GenerateContext, op: BinaryOp, rs1: VReg, rs2: VReg) -> VReg {
+  context.push_inst(Inst::Mv(Reg::T0.into(), rs1));
+  context.push_inst(Inst::Mv(Reg::T1.into(), rs2));
+  match op {
+    BinaryOp::Mul => {
+      context.push_inst(Inst::Call(MUL.into()));
+      Reg::T2.into()
+    }
+    BinaryOp::Div => {
+      context.push_inst(Inst::Call(DIVMOD.into()));
+      Reg::T2.into()
+    }
+    _ => {
+      context.push_inst(Inst::Call(DIVMOD.into()));
+      Reg::T3.into()
+    }
+  }
+}
+
+/// 把 `dest` 处的 `size` 字节清零：不长时逐字写入 `zero`，否则调用辅助例程
+fn generate_zero_fill(context: &mut GenerateContext, dest: Value, size: usize) -> Result<()> {
+  let base = if let Some(var) = context.is_global_value(dest)? {
+    context.global_addr(&var)
+  } else {
+    let mut rd = context.new_vreg();
+    context.load_value_to_reg(dest, &mut rd)?;
+    rd
+  };
+  let words = size / 4;
+  if words <= MAX_INLINE_ZERO_WORDS {
+    for i in 0..words {
+      context.push_inst(Inst::Sw(Reg::Zero.into(), i as i32 * 4, base));
+    }
+  } else {
+    context.push_inst(Inst::Mv(Reg::T0.into(), base));
+    context.push_inst(Inst::Li(Reg::T1.into(), size as i32));
+    context.push_inst(Inst::Call(MEMZERO.into()));
+  }
+  Ok(())
+}
+
 fn generate_get_ptr(
   context: &mut GenerateContext,
   base: Value,
   index: Value,
   step: i32,
-) -> Result<Reg> {
-  let mut base_reg = Reg::T0;
-  if let Some(var) = context.is_global_value(base)? {
-    context.push_inst(Inst::La(base_reg, var));
+) -> Result<VReg> {
+  let base_reg = if let Some(var) = context.is_global_value(base)? {
+    context.global_addr(&var)
   } else {
+    let mut base_reg = context.new_vreg();
     context.load_value_to_reg(base, &mut base_reg)?;
-  }
+    base_reg
+  };
 
-  let mut index_reg = Reg::T1;
+  let mut index_reg = context.new_vreg();
   context.load_value_to_reg(index, &mut index_reg)?;
-  if index_reg != Reg::Zero {
-    let step = step as i32;
-    if (step & (step - 1)) == 0 {
-      // Power of 2
-      let pow = i32::trailing_zeros(step) as i32;
-      context.push_inst(Inst::Slli(Reg::T1, index_reg, pow));
-    } else {
-      context.push_inst(Inst::Li(Reg::T2, step as i32));
-      context.push_inst(Inst::Mul(Reg::T1, index_reg, Reg::T2));
-    }
-
-    context.push_inst(Inst::Add(base_reg, base_reg, Reg::T1));
+  if index_reg == VReg::Phys(Reg::Zero) {
+    return Ok(base_reg);
   }
-  Ok(base_reg)
+  let offset = if (step & (step - 1)) == 0 {
+    // Power of 2
+    let pow = i32::trailing_zeros(step) as i32;
+    let offset = context.new_vreg();
+    context.push_inst(Inst::Slli(offset, index_reg, pow));
+    offset
+  } else if context.ext.m && (step as u32).count_ones() > max_mul_terms(context) {
+    let step_reg = context.new_vreg();
+    let offset = context.new_vreg();
+    context.push_inst(Inst::Li(step_reg, step));
+    context.push_inst(Inst::Mul(offset, index_reg, step_reg));
+    offset
+  } else {
+    // 指针运算不在辅助例程的调用约定之内，没有 M 扩展时总是直接展开
+    let offset = context.new_vreg();
+    let scratch = context.new_vreg();
+    for inst in soft_muldiv::expand_mul(offset, index_reg, step, scratch) {
+      context.push_inst(inst);
+    }
+    offset
+  };
+  let result = context.new_vreg();
+  context.push_inst(Inst::Add(result, base_reg, offset));
+  Ok(result)
 }
@@ -0,0 +1,101 @@
+//! 内置运行时库（见 `frontend/hosted.sy`）所用的 Linux 系统调用例程，遵循标准调用约定。
+//! 与 C 库的同名函数不同，参数中没有的文件描述符与时钟由例程填上。
+
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem, Target, Xlen};
+use super::soft_muldiv::{begin_helper, end_helper};
+
+const READ: &str = "__sysy_read";
+const WRITE: &str = "__sysy_write";
+const CLOCK: &str = "__sysy_clock";
+const EXIT: &str = "__sysy_exit";
+
+// https://github.com/torvalds/linux/blob/master/include/uapi/asm-generic/unistd.h
+const SYS_READ: i32 = 63;
+const SYS_WRITE: i32 = 64;
+const SYS_EXIT_GROUP: i32 = 94;
+const SYS_CLOCK_GETTIME: i32 = 113;
+/// RV32 上只有 64 位 `time_t` 的系统调用
+const SYS_CLOCK_GETTIME64: i32 = 403;
+const CLOCK_MONOTONIC: i32 = 1;
+const STDIN: i32 = 0;
+
+/// `__sysy_read(buf, len)`：从标准输入读入
+fn generate_read() -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, READ);
+  r.add_inst(Inst::Mv(Reg::A2, Reg::A1));
+  r.add_inst(Inst::Mv(Reg::A1, Reg::A0));
+  r.add_inst(Inst::Li(Reg::A0, STDIN));
+  r.add_inst(Inst::Li(Reg::A7, SYS_READ));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, READ);
+  r
+}
+
+/// `__sysy_write(fd, buf, len)`
+fn generate_write() -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, WRITE);
+  r.add_inst(Inst::Li(Reg::A7, SYS_WRITE));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, WRITE);
+  r
+}
+
+/// `__sysy_clock(ts)`：单调时钟的当前时间，写入 `ts` 所指的 `timespec`
+fn generate_clock(xlen: Xlen) -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, CLOCK);
+  let number = match xlen {
+    Xlen::Rv32 => SYS_CLOCK_GETTIME64,
+    Xlen::Rv64 => SYS_CLOCK_GETTIME,
+  };
+  r.add_inst(Inst::Mv(Reg::A1, Reg::A0));
+  r.add_inst(Inst::Li(Reg::A0, CLOCK_MONOTONIC));
+  r.add_inst(Inst::Li(Reg::A7, number));
+  r.add_inst(Inst::Ecall);
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, CLOCK);
+  r
+}
+
+/// `__sysy_exit(code)`：结束整个进程，不再返回
+fn generate_exit() -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, EXIT);
+  r.add_inst(Inst::Li(Reg::A7, SYS_EXIT_GROUP));
+  r.add_inst(Inst::Ecall);
+  end_helper(&mut r, EXIT);
+  r
+}
+
+/// 为程序中调用到的例程生成代码；裸机运行时没有这些系统调用
+pub fn generate_syscalls(program: &Riscv, target: Target) -> Riscv {
+  let calls = |name: &str| {
+    program
+      .0
+      .iter()
+      .any(|item| matches!(item, RiscvItem::Inst(Inst::Call(callee)) if callee == name))
+  };
+  let mut result = Riscv::new();
+  if target.freestanding() {
+    return result;
+  }
+  if calls(READ) {
+    result.extend(generate_read());
+  }
+  if calls(WRITE) {
+    result.extend(generate_write());
+  }
+  if calls(CLOCK) {
+    result.extend(generate_clock(target.xlen));
+  }
+  if calls(EXIT) {
+    result.extend(generate_exit());
+  }
+  result
+}
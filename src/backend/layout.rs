@@ -0,0 +1,241 @@
+//! 基本块排布：让可能执行的路径尽量顺序落下，省去热路径上的无条件跳转。
+//!
+//! - 以静态分支预测选出紧接在每个块之后的块：`__builtin_expect` 给出的提示优先，
+//!   其次回边与留在循环内的一侧可能发生，直接返回的一侧不太可能发生，其余取条件为真的一侧；
+//! - 一条链走到尽头时，优先接上同一循环内尚未排布的块，使循环体连续、出口排在循环之后；
+//! - 只能经由不太可能发生的提前返回或提示为不太可能发生的分支到达的冷块排在函数末尾，
+//!   不占据热代码之间的指令缓存；
+//! - 最后把以条件分支退出的循环头旋转到循环末尾，每次迭代只执行一条向回的分支。
+
+use std::collections::HashMap;
+
+use koopa::ir::{BasicBlock, FunctionData, Value, ValueKind};
+
+struct Loop {
+  header: usize,
+  /// 各块是否属于该循环
+  body: Vec<bool>,
+  size: usize,
+}
+
+/// 以块在 IR 中的序号表示的控制流图
+struct Cfg {
+  succs: Vec<Vec<usize>>,
+  preds: Vec<Vec<usize>>,
+  /// 以 `ret` 结束的块
+  returns: Vec<bool>,
+  /// 以条件分支结束的块中，`__builtin_expect` 提示是否可能转移到真分支
+  hints: Vec<Option<bool>>,
+  reachable: Vec<bool>,
+  /// 由外到内排列
+  loops: Vec<Loop>,
+}
+
+impl Cfg {
+  fn new(func: &FunctionData, branch_hints: &HashMap<Value, bool>) -> Self {
+    let bbs: Vec<_> = func.layout().bbs().keys().copied().collect();
+    let index: HashMap<BasicBlock, usize> =
+      bbs.iter().enumerate().map(|(i, &bb)| (bb, i)).collect();
+    let n = bbs.len();
+    let mut succs = vec![vec![]; n];
+    let mut returns = vec![false; n];
+    let mut hints = vec![None; n];
+    for (i, node) in func.layout().bbs().nodes().enumerate() {
+      let last = node.insts().back_key().copied();
+      match last.map(|v| func.dfg().value(v).kind()) {
+        Some(ValueKind::Branch(branch)) => {
+          succs[i] = vec![index[&branch.true_bb()], index[&branch.false_bb()]];
+          hints[i] = branch_hints.get(&last.unwrap()).copied();
+        }
+        Some(ValueKind::Jump(jump)) => succs[i] = vec![index[&jump.target()]],
+        Some(ValueKind::Return(_)) => returns[i] = true,
+        _ => {}
+      }
+    }
+
+    // 深度优先搜索找出回边：指向搜索栈上的块
+    let mut reachable = vec![false; n];
+    let mut on_stack = vec![false; n];
+    let mut latches: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut stack = vec![(0, 0)];
+    reachable[0] = true;
+    on_stack[0] = true;
+    while let Some(&mut (b, ref mut next)) = stack.last_mut() {
+      if let Some(&s) = succs[b].get(*next) {
+        *next += 1;
+        if on_stack[s] {
+          latches.entry(s).or_default().push(b);
+        } else if !reachable[s] {
+          reachable[s] = true;
+          on_stack[s] = true;
+          stack.push((s, 0));
+        }
+      } else {
+        on_stack[b] = false;
+        stack.pop();
+      }
+    }
+
+    // 自然循环：不经过循环头即可到达回边起点的块
+    let mut preds = vec![vec![]; n];
+    for (b, ss) in succs.iter().enumerate() {
+      for &s in ss {
+        preds[s].push(b);
+      }
+    }
+    let mut loops: Vec<_> = latches
+      .into_iter()
+      .map(|(header, latches)| {
+        let mut body = vec![false; n];
+        body[header] = true;
+        let mut work = latches;
+        while let Some(b) = work.pop() {
+          if !body[b] {
+            body[b] = true;
+            work.extend(preds[b].iter().copied().filter(|&p| reachable[p]));
+          }
+        }
+        let size = body.iter().filter(|&&b| b).count();
+        Loop { header, body, size }
+      })
+      .collect();
+    loops.sort_by_key(|l| (usize::MAX - l.size, l.header));
+
+    Self {
+      succs,
+      preds,
+      returns,
+      hints,
+      reachable,
+      loops,
+    }
+  }
+
+  /// 包含 `b` 的最内层循环
+  fn innermost(&self, b: usize) -> Option<&Loop> {
+    self.loops.iter().rev().find(|l| l.body[b])
+  }
+
+  /// 从 `b` 转移到 `s` 时不离开 `b` 所在的循环
+  fn stays(&self, b: usize, s: usize) -> bool {
+    self.innermost(b).is_none_or(|l| l.body[s])
+  }
+
+  fn is_back_edge(&self, b: usize, s: usize) -> bool {
+    self.loops.iter().any(|l| l.header == s && l.body[b])
+  }
+
+  /// `b` 最可能的后继
+  fn likely(&self, b: usize) -> Option<usize> {
+    match self.succs[b][..] {
+      [s] => Some(s),
+      [t, f] => {
+        if let Some(hint) = self.hints[b] {
+          return Some(if hint { t } else { f });
+        }
+        let prefer = |p: &dyn Fn(usize) -> bool| match (p(t), p(f)) {
+          (true, false) => Some(t),
+          (false, true) => Some(f),
+          _ => None,
+        };
+        prefer(&|s| self.is_back_edge(b, s))
+          .or_else(|| prefer(&|s| self.stays(b, s)))
+          .or_else(|| prefer(&|s| !self.returns[s]))
+          .or(Some(t))
+      }
+      _ => None,
+    }
+  }
+
+  /// 冷块：只能经由冷边到达的块。冷边是条件分支中提示为不太可能发生的一侧，
+  /// 或未经提示、不太可能发生且直接返回的一侧（但不包括循环头的正常出口），以及冷块的出边
+  fn cold(&self) -> Vec<bool> {
+    let n = self.succs.len();
+    let is_header = |b: usize| self.loops.iter().any(|l| l.header == b);
+    let cold_edge = |cold: &[bool], p: usize, s: usize| {
+      let unlikely = self.succs[p].len() == 2 && self.likely(p) != Some(s);
+      cold[p]
+        || (unlikely && self.hints[p].is_some())
+        || (unlikely && self.returns[s] && !is_header(p))
+    };
+    let mut cold = vec![false; n];
+    loop {
+      let mut changed = false;
+      for b in 1..n {
+        let preds = self.preds[b].iter().filter(|&&p| self.reachable[p]);
+        if !cold[b] && self.reachable[b] && preds.clone().all(|&p| cold_edge(&cold, p, b)) {
+          cold[b] = true;
+          changed = true;
+        }
+      }
+      if !changed {
+        return cold;
+      }
+    }
+  }
+}
+
+/// 函数中基本块的排布顺序，入口块总在最前；`branch_hints` 是 `__builtin_expect` 给出的分支预测
+pub fn layout(func: &FunctionData, branch_hints: &HashMap<Value, bool>) -> Vec<BasicBlock> {
+  let bbs: Vec<_> = func.layout().bbs().keys().copied().collect();
+  if bbs.is_empty() {
+    return bbs;
+  }
+  let cfg = Cfg::new(func, branch_hints);
+  let n = bbs.len();
+  let cold = cfg.cold();
+
+  let mut placed = vec![false; n];
+  let mut order = vec![];
+  let mut cur = 0;
+  loop {
+    placed[cur] = true;
+    order.push(cur);
+    // 热块全部排布之后才开始排布冷块
+    let hot_done = (0..n).all(|b| placed[b] || cold[b] || !cfg.reachable[b]);
+    let unplaced = |&b: &usize| !placed[b] && (hot_done || !cold[b]);
+    let next = cfg
+      .likely(cur)
+      .filter(unplaced)
+      .or_else(|| {
+        cfg.succs[cur]
+          .iter()
+          .copied()
+          .filter(unplaced)
+          .find(|&s| cfg.stays(cur, s))
+      })
+      .or_else(|| {
+        // 由内到外，接上同一循环内尚未排布的块
+        let loops = cfg.loops.iter().rev().filter(|l| l.body[cur]);
+        loops
+          .filter_map(|l| (0..n).filter(unplaced).find(|&b| l.body[b]))
+          .next()
+      })
+      .or_else(|| (0..n).filter(unplaced).find(|&b| cfg.reachable[b]))
+      .or_else(|| (0..n).find(unplaced));
+    match next {
+      Some(next) => cur = next,
+      None => break,
+    }
+  }
+
+  // 旋转循环：循环头以条件分支退出、末尾的块无条件跳回循环头时，把循环头移到末尾，
+  // 使最后一个块直接落入循环头，循环头的条件分支跳回循环体开头
+  for l in cfg.loops.iter().rev() {
+    let pos = order.iter().position(|&b| b == l.header).unwrap();
+    let len = order[pos..].iter().take_while(|&&b| l.body[b]).count();
+    if pos == 0 || len != l.size || len < 2 {
+      continue;
+    }
+    let last = order[pos + len - 1];
+    let exits = match cfg.succs[l.header][..] {
+      [t, f] => (t == order[pos + 1] && !l.body[f]) || (f == order[pos + 1] && !l.body[t]),
+      _ => false,
+    };
+    if exits && cfg.succs[last] == [l.header] {
+      order[pos..pos + len].rotate_left(1);
+    }
+  }
+
+  order.into_iter().map(|i| bbs[i]).collect()
+}
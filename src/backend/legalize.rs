@@ -0,0 +1,152 @@
+//! 展开立即数（偏移量）超出 12 位的指令，此后的每条指令都可直接编码。
+//!
+//! 栈帧超过 2 KiB 时，`sp` 的调整与栈上访存的偏移量都会越界：
+//! - `addi sp, sp, imm` 尽量拆为两条 `addi`，且中间值保持 16 字节对齐；
+//! - 其余情形经由保留的 `t6` 物化立即数，改用寄存器形式或以 `t6` 为基址访存。
+//!
+//! 基本块排布可能让条件分支的目标远在 ±4 KiB 之外，这时改为以相反的条件跳过一条 `j`。
+//! 汇编器（如 `llvm-mc`）并不自动展开这样的分支。
+
+use std::collections::HashMap;
+
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem};
+
+/// 拆分 `sp` 调整时每步的最大幅度
+const SP_STEP_DOWN: i32 = -2048;
+const SP_STEP_UP: i32 = 2032;
+
+fn legalize_inst(inst: Inst, result: &mut Riscv) {
+  if inst.imm_fits() {
+    result.add_inst(inst);
+    return;
+  }
+  let t6 = Reg::T6;
+  match inst {
+    Inst::Addi(Reg::Sp, Reg::Sp, imm) if (2 * SP_STEP_DOWN..0).contains(&imm) => {
+      result.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, SP_STEP_DOWN));
+      result.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, imm - SP_STEP_DOWN));
+    }
+    Inst::Addi(Reg::Sp, Reg::Sp, imm) if (2048..SP_STEP_UP + 2048).contains(&imm) => {
+      result.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, SP_STEP_UP));
+      result.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, imm - SP_STEP_UP));
+    }
+    Inst::Lw(rd, offset, rs) | Inst::Ld(rd, offset, rs) => {
+      result.add_inst(Inst::Li(t6, offset));
+      result.add_inst(Inst::Add(t6, t6, rs));
+      let width = if let Inst::Ld(..) = inst { 8 } else { 4 };
+      result.add_inst(Inst::load(width, rd, 0, t6));
+    }
+    Inst::Sw(rs2, offset, rs1) | Inst::Sd(rs2, offset, rs1) => {
+      result.add_inst(Inst::Li(t6, offset));
+      result.add_inst(Inst::Add(t6, t6, rs1));
+      let width = if let Inst::Sd(..) = inst { 8 } else { 4 };
+      result.add_inst(Inst::store(width, rs2, 0, t6));
+    }
+    Inst::Addi(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::Add(rd, rs, t6));
+    }
+    Inst::Xori(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::Xor(rd, rs, t6));
+    }
+    Inst::Ori(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::Or(rd, rs, t6));
+    }
+    Inst::Andi(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::And(rd, rs, t6));
+    }
+    Inst::Slli(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::Sll(rd, rs, t6));
+    }
+    Inst::Srli(rd, rs, imm) => {
+      result.add_inst(Inst::Li(t6, imm));
+      result.add_inst(Inst::Srl(rd, rs, t6));
+    }
+    _ => unreachable!("{} has no immediate to legalize", inst),
+  }
+}
+
+/// 指令展开后的最大长度，压缩只会使其变短
+fn max_size(inst: &Inst) -> i64 {
+  match inst {
+    Inst::Li(_, imm) if !(-2048..=2047).contains(imm) => 8,
+    Inst::La(..) | Inst::Call(_) => 8,
+    // 按每行一条至多展开为两条指令的伪指令估计
+    Inst::Asm(template, ..) => 8 * template.lines().count() as i64,
+    _ => 4,
+  }
+}
+
+fn split_far_branches(riscv: Riscv) -> Riscv {
+  let mut items = riscv.0;
+  let mut count = 0;
+  loop {
+    let far: Vec<_> = {
+      let mut offsets = Vec::with_capacity(items.len());
+      let mut labels = HashMap::new();
+      let mut offset = 0;
+      for item in &items {
+        offsets.push(offset);
+        match item {
+          RiscvItem::Label(label) => {
+            labels.insert(label.as_str(), offset);
+          }
+          RiscvItem::Inst(inst) => offset += max_size(inst),
+          _ => {}
+        }
+      }
+      let in_range = |i: usize, label: &str| {
+        labels
+          .get(label)
+          .is_none_or(|&target| (-4096..=4094).contains(&(target - offsets[i])))
+      };
+      (0..items.len())
+        .filter(|&i| match &items[i] {
+          RiscvItem::Inst(Inst::Beqz(_, label) | Inst::Bnez(_, label)) => !in_range(i, label),
+          _ => false,
+        })
+        .collect()
+    };
+    if far.is_empty() {
+      return Riscv(items);
+    }
+    // 从后向前替换，前面的下标保持不变
+    for &i in far.iter().rev() {
+      let (rs, target, eq) = match &items[i] {
+        RiscvItem::Inst(Inst::Beqz(rs, label)) => (*rs, label.clone(), true),
+        RiscvItem::Inst(Inst::Bnez(rs, label)) => (*rs, label.clone(), false),
+        _ => unreachable!(),
+      };
+      let skip = format!("{}_far{}", target, count);
+      count += 1;
+      let inverted = if eq {
+        Inst::Bnez(rs, skip.clone())
+      } else {
+        Inst::Beqz(rs, skip.clone())
+      };
+      let replacement = [
+        RiscvItem::Inst(inverted),
+        RiscvItem::Inst(Inst::J(target)),
+        RiscvItem::Label(skip),
+      ];
+      items.splice(i..i + 1, replacement);
+    }
+  }
+}
+
+pub fn legalize(riscv: Riscv) -> Riscv {
+  let mut result = Riscv::new();
+  for item in riscv.0 {
+    match item {
+      RiscvItem::Inst(inst) => legalize_inst(inst, &mut result),
+      item => result.0.push(item),
+    }
+  }
+  split_far_branches(result)
+}
@@ -0,0 +1,615 @@
+//! LLVM IR 文本输出（`--emit=llvm`）。
+//!
+//! 由 Koopa IR 逐条翻译，不指定目标三元组，可交给 `llc -mtriple=...` 生成任意架构的代码；
+//! 指针是 LLVM 14 及以前的有类型指针。每条指令的结果是一个 SSA 值，基本块一一对应：
+//!
+//! - 所有 `alloc` 都在函数的入口分配。IR 中块作用域的局部变量、`&&`/`||` 的结果等的 `alloc`
+//!   在循环体中，原样输出的 `alloca` 每执行一次都使栈增长；
+//! - 算术运算按 RISC-V 的语义进行：加减乘回绕，移位量取低 5 位，除数为 0 或溢出时的结果同
+//!   `div`/`rem` 指令，与 C 与 WebAssembly 后端一致；
+//! - 内联汇编（对 `@__sysy_asm_N` 的调用）直接输出为 `asm sideeffect` 的调用。输出操作数
+//!   不与输入共用寄存器，且不与访存重排，与 RISC-V 后端一致；
+//! - 函数的属性（`__attribute__`）加在其定义与声明的参数表之后，访问 `volatile` 变量的
+//!   `load`/`store` 标为 `volatile`；
+//! - 数组的整体清零与初始值中连续的零以 `llvm.memset` 写入，而不是逐字写入。全局变量的初始值中
+//!   连续的零同样以 `zeroinitializer` 给出，这样的变量定义为由各段组成的结构体，输出不随数组的
+//!   大小增长。
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+
+use koopa::ir::{BasicBlock, BinaryOp, FunctionData, Program, Type, TypeKind, Value, ValueKind};
+
+use super::error::UnimplementedError;
+use crate::frontend::{FuncAttrs, Ir};
+use crate::Result;
+
+const MEMSET: &str = "llvm.memset.p0i8.i64";
+const DIV_HELPER: &str = "__sysy_div";
+const REM_HELPER: &str = "__sysy_rem";
+
+/// 连续零值达到该数量（字）时以 `llvm.memset` 或 `zeroinitializer` 给出
+const MIN_ZERO_RUN: usize = 8;
+
+/// 除法与取余，与 RISC-V 的 `div`/`rem` 一致：除数为 0 或 -1 时不执行 `sdiv`/`srem`
+const DIV_HELPERS: &str = "\
+define internal i32 @__sysy_div(i32 %a, i32 %b) alwaysinline {
+  %zero = icmp eq i32 %b, 0
+  %minus_one = icmp eq i32 %b, -1
+  %special = or i1 %zero, %minus_one
+  %divisor = select i1 %special, i32 1, i32 %b
+  %quotient = sdiv i32 %a, %divisor
+  %negated = sub i32 0, %a
+  %nonzero = select i1 %minus_one, i32 %negated, i32 %quotient
+  %result = select i1 %zero, i32 -1, i32 %nonzero
+  ret i32 %result
+}
+
+define internal i32 @__sysy_rem(i32 %a, i32 %b) alwaysinline {
+  %zero = icmp eq i32 %b, 0
+  %minus_one = icmp eq i32 %b, -1
+  %special = or i1 %zero, %minus_one
+  %divisor = select i1 %special, i32 1, i32 %b
+  %remainder = srem i32 %a, %divisor
+  %nonzero = select i1 %minus_one, i32 0, i32 %remainder
+  %result = select i1 %zero, i32 %a, i32 %nonzero
+  ret i32 %result
+}
+";
+
+fn llvm_type(ty: &Type) -> String {
+  match ty.kind() {
+    TypeKind::Int32 => "i32".into(),
+    TypeKind::Unit => "void".into(),
+    TypeKind::Array(base, len) => format!("[{} x {}]", len, llvm_type(base)),
+    TypeKind::Pointer(base) => format!("{}*", llvm_type(base)),
+    TypeKind::Function(..) => unreachable!("function values are never operands"),
+  }
+}
+
+/// 指针类型所指的类型
+fn pointee(ty: &Type) -> &Type {
+  match ty.kind() {
+    TypeKind::Pointer(base) => base,
+    _ => panic!("expected a pointer type, found {}", ty),
+  }
+}
+
+/// 函数的参数与返回值类型
+fn signature(fd: &FunctionData) -> (&[Type], &Type) {
+  match fd.ty().kind() {
+    TypeKind::Function(params, ret) => (params, ret),
+    _ => unreachable!("function should have function type"),
+  }
+}
+
+/// 把模板改写为 LLVM 的语法（操作数写作 `$N`），并转义为 LLVM 字符串
+fn llvm_asm_template(template: &str) -> String {
+  let mut text = String::new();
+  let mut chars = template.chars().peekable();
+  while let Some(c) = chars.next() {
+    match c {
+      '%' if chars.next_if_eq(&'%').is_some() => text.push('%'),
+      '%' => text.push('$'),
+      '$' => text.push_str("$$"),
+      '"' | '\\' => text += &format!("\\{:02X}", c as u32),
+      '\n' => text.push_str("\\0A"),
+      c => text.push(c),
+    }
+  }
+  text
+}
+
+/// 写在参数表之后的函数属性与所在的节；没有属性时为空
+fn llvm_func_attrs(attrs: &FuncAttrs) -> String {
+  let flags = [
+    (attrs.noinline, "noinline"),
+    (attrs.always_inline, "alwaysinline"),
+    (attrs.noreturn, "noreturn"),
+  ];
+  let mut text = String::new();
+  for (_, name) in flags.into_iter().filter(|&(enabled, _)| enabled) {
+    text += " ";
+    text += name;
+  }
+  if let Some(section) = &attrs.section {
+    let section = section.replace('\\', "\\5C").replace('"', "\\22");
+    text += &format!(" section \"{}\"", section);
+  }
+  text
+}
+
+/// 把初始值展开为 `(字, 连续的个数)`，相邻的零值合并为一段，`zeroinit` 不逐字展开。`value`
+/// 给出值的种类与类型，以便同样用于全局与局部的值
+fn flatten(value: &dyn Fn(Value) -> (ValueKind, Type), v: Value, runs: &mut Vec<(i32, usize)>) {
+  let (kind, ty) = value(v);
+  let (word, count) = match kind {
+    ValueKind::Integer(i) => (i.value(), 1),
+    ValueKind::Aggregate(agg) => {
+      for &elem in agg.elems() {
+        flatten(value, elem, runs);
+      }
+      return;
+    }
+    _ => (0, ty.size() / 4),
+  };
+  match runs.last_mut() {
+    Some((0, zeros)) if word == 0 => *zeros += count,
+    _ => runs.push((word, count)),
+  }
+}
+
+/// 全局变量的常量初始值，按其类型嵌套，全零的部分为 `zeroinitializer`
+fn constant(ir: &Program, value: Value) -> String {
+  let vd = ir.borrow_value(value);
+  match vd.kind() {
+    ValueKind::Integer(i) => i.value().to_string(),
+    ValueKind::Aggregate(agg) => {
+      let elems: Vec<_> = agg
+        .elems()
+        .iter()
+        .map(|&e| {
+          let ty = llvm_type(ir.borrow_value(e).ty());
+          format!("{} {}", ty, constant(ir, e))
+        })
+        .collect();
+      format!("[{}]", elems.join(", "))
+    }
+    ValueKind::Undef(_) => "undef".into(),
+    _ => "zeroinitializer".into(),
+  }
+}
+
+/// 由 `(字, 个数)` 的各段组成的结构体：长的零值段为 `zeroinitializer`，其余的字合为数组。
+/// 返回结构体的类型与初始值
+fn sparse_constant(runs: &[(i32, usize)]) -> (String, String) {
+  let mut fields: Vec<(usize, String)> = vec![];
+  let mut words: Vec<String> = vec![];
+  let flush = |words: &mut Vec<String>, fields: &mut Vec<(usize, String)>| {
+    if !words.is_empty() {
+      let elems: Vec<_> = words.iter().map(|w| format!("i32 {}", w)).collect();
+      fields.push((words.len(), format!("[{}]", elems.join(", "))));
+      words.clear();
+    }
+  };
+  for &(word, count) in runs {
+    if word == 0 && count >= MIN_ZERO_RUN {
+      flush(&mut words, &mut fields);
+      fields.push((count, "zeroinitializer".into()));
+    } else {
+      words.extend(std::iter::repeat_n(word.to_string(), count));
+    }
+  }
+  flush(&mut words, &mut fields);
+  let types: Vec<_> = fields
+    .iter()
+    .map(|(len, _)| format!("[{} x i32]", len))
+    .collect();
+  let values: Vec<_> = types
+    .iter()
+    .zip(&fields)
+    .map(|(ty, (_, value))| format!("{} {}", ty, value))
+    .collect();
+  (
+    format!("<{{ {} }}>", types.join(", ")),
+    format!("<{{ {} }}>", values.join(", ")),
+  )
+}
+
+/// 输出一个全局变量，返回代码中引用它的操作数
+fn generate_global(ir: &Program, value: Value, text: &mut String) -> Result<String> {
+  let vd = ir.borrow_value(value);
+  let (base, init) = match (vd.ty().kind(), vd.kind()) {
+    (TypeKind::Pointer(base), ValueKind::GlobalAlloc(alloc)) => (base, alloc.init()),
+    _ => unreachable!("global instruction should be alloc"),
+  };
+  let name = &vd.name().as_ref().unwrap()[1..];
+  let ty = llvm_type(base);
+  let mut runs = vec![];
+  let global = |v| {
+    let vd = ir.borrow_value(v);
+    (vd.kind().clone(), vd.ty().clone())
+  };
+  flatten(&global, init, &mut runs);
+  let zero_runs = runs.iter().filter(|&&(w, n)| w == 0 && n >= MIN_ZERO_RUN);
+  if runs.len() == 1 || zero_runs.count() == 0 {
+    writeln!(text, "@{} = global {} {}", name, ty, constant(ir, init))?;
+    return Ok(format!("@{}", name));
+  }
+  let (struct_ty, struct_init) = sparse_constant(&runs);
+  writeln!(text, "%{}.layout = type {}", name, struct_ty)?;
+  writeln!(text, "@{} = global %{}.layout {}", name, name, struct_init)?;
+  Ok(format!("bitcast (%{}.layout* @{} to {}*)", name, name, ty))
+}
+
+struct FuncContext<'a> {
+  ir: &'a Ir,
+  fd: &'a FunctionData,
+  /// 全局变量在代码中的操作数
+  globals: &'a HashMap<Value, String>,
+  /// 各指令按出现顺序的序号
+  ids: HashMap<Value, usize>,
+  bbs: HashMap<BasicBlock, usize>,
+  body: String,
+  /// 是否用到了 `llvm.memset` 与除法的辅助函数
+  uses_memset: bool,
+  uses_div: bool,
+}
+
+impl FuncContext<'_> {
+  fn value_kind(&self, value: Value) -> ValueKind {
+    self.fd.dfg().value(value).kind().clone()
+  }
+
+  fn ty(&self, value: Value) -> Type {
+    match value.is_global() {
+      true => self.ir.borrow_value(value).ty().clone(),
+      false => self.fd.dfg().value(value).ty().clone(),
+    }
+  }
+
+  fn temp(&self, value: Value) -> String {
+    format!("%v{}", self.ids[&value])
+  }
+
+  fn label(&self, bb: BasicBlock) -> String {
+    format!("bb{}", self.bbs[&bb])
+  }
+
+  fn operand(&self, value: Value) -> Result<String> {
+    if value.is_global() {
+      return Ok(self.globals[&value].clone());
+    }
+    Ok(match self.value_kind(value) {
+      ValueKind::Integer(i) => i.value().to_string(),
+      ValueKind::ZeroInit(_) => "zeroinitializer".into(),
+      ValueKind::Undef(_) => "undef".into(),
+      ValueKind::FuncArgRef(arg) => format!("%a{}", arg.index()),
+      ValueKind::Alloc(_) => format!("%s{}", self.ids[&value]),
+      ValueKind::BlockArgRef(arg) => return Err(UnimplementedError(Box::new(arg)).into()),
+      _ => self.temp(value),
+    })
+  }
+
+  /// 带类型的操作数，如 `i32 %v3`
+  fn typed(&self, value: Value) -> Result<String> {
+    Ok(format!(
+      "{} {}",
+      llvm_type(&self.ty(value)),
+      self.operand(value)?
+    ))
+  }
+
+  fn inst(&mut self, inst: String) {
+    writeln!(self.body, "  {}", inst).unwrap();
+  }
+
+  /// 写入数组的初始值：连续的零以 `llvm.memset` 写入，其余逐字写入
+  fn store_aggregate(&mut self, store: Value, value: Value, dest: Value) -> Result<()> {
+    let volatile = self.ir.annotations.is_volatile(self.fd.dfg(), dest);
+    let base = llvm_type(pointee(&self.ty(dest)));
+    let ptr = self.operand(dest)?;
+    let mut runs = vec![];
+    let local = |v| {
+      let vd = self.fd.dfg().value(v);
+      (vd.kind().clone(), vd.ty().clone())
+    };
+    flatten(&local, value, &mut runs);
+    let is_zeros = |&(word, count): &(i32, usize)| word == 0 && count >= MIN_ZERO_RUN;
+    // `store` 没有结果，以其名字为各个地址的前缀；地址分别按字节与按字转换一次
+    let name = self.temp(store);
+    let (bytes, words) = (format!("{}.bytes", name), format!("{}.words", name));
+    if runs.iter().any(is_zeros) {
+      self.inst(format!("{} = bitcast {}* {} to i8*", bytes, base, ptr));
+    }
+    if !runs.iter().all(is_zeros) {
+      self.inst(format!("{} = bitcast {}* {} to i32*", words, base, ptr));
+    }
+    let store_inst = if volatile { "store volatile" } else { "store" };
+    let mut offset = 0;
+    for run in runs {
+      let (word, count) = run;
+      if is_zeros(&run) {
+        self.uses_memset = true;
+        let start = format!("{}.{}", name, offset);
+        let gep = format!("getelementptr i8, i8* {}, i64 {}", bytes, offset * 4);
+        self.inst(format!("{} = {}", start, gep));
+        self.inst(format!(
+          "call void @{}(i8* {}, i8 0, i64 {}, i1 {})",
+          MEMSET,
+          start,
+          count * 4,
+          volatile
+        ));
+      } else {
+        for i in offset..offset + count {
+          let elem = format!("{}.{}", name, i);
+          let gep = format!("getelementptr i32, i32* {}, i64 {}", words, i);
+          self.inst(format!("{} = {}", elem, gep));
+          self.inst(format!("{} i32 {}, i32* {}", store_inst, word, elem));
+        }
+      }
+      offset += count;
+    }
+    Ok(())
+  }
+
+  fn generate_inst(&mut self, value: Value) -> Result<()> {
+    match self.value_kind(value) {
+      ValueKind::Alloc(_) => {}
+      ValueKind::Load(load) => {
+        let ty = llvm_type(&self.ty(value));
+        let volatile = match self.ir.annotations.is_volatile(self.fd.dfg(), load.src()) {
+          true => "volatile ",
+          false => "",
+        };
+        let inst = format!(
+          "{} = load {}{}, {}",
+          self.temp(value),
+          volatile,
+          ty,
+          self.typed(load.src())?
+        );
+        self.inst(inst);
+      }
+      ValueKind::Store(store) => match self.value_kind(store.value()) {
+        ValueKind::ZeroInit(_) | ValueKind::Aggregate(_) => {
+          self.store_aggregate(value, store.value(), store.dest())?
+        }
+        _ => {
+          let volatile = match self.ir.annotations.is_volatile(self.fd.dfg(), store.dest()) {
+            true => " volatile",
+            false => "",
+          };
+          let inst = format!(
+            "store{} {}, {}",
+            volatile,
+            self.typed(store.value())?,
+            self.typed(store.dest())?
+          );
+          self.inst(inst);
+        }
+      },
+      ValueKind::GetPtr(ptr) => {
+        let base = llvm_type(pointee(&self.ty(ptr.src())));
+        let inst = format!(
+          "{} = getelementptr {}, {}, {}",
+          self.temp(value),
+          base,
+          self.typed(ptr.src())?,
+          self.typed(ptr.index())?
+        );
+        self.inst(inst);
+      }
+      ValueKind::GetElemPtr(ptr) => {
+        let base = llvm_type(pointee(&self.ty(ptr.src())));
+        let inst = format!(
+          "{} = getelementptr {}, {}, i32 0, {}",
+          self.temp(value),
+          base,
+          self.typed(ptr.src())?,
+          self.typed(ptr.index())?
+        );
+        self.inst(inst);
+      }
+      ValueKind::Binary(binary) => self.binary(value, binary.op(), binary.lhs(), binary.rhs())?,
+      ValueKind::Call(call) => {
+        let args = call.args().iter().map(|&arg| self.typed(arg));
+        let args = args.collect::<Result<Vec<_>>>()?;
+        let callee = self.ir.func(call.callee());
+        let (params, ret) = signature(callee);
+        let ret_ty = llvm_type(ret);
+        let target = match self.ir.annotations.inline_asm.get(&call.callee()) {
+          Some(template) => {
+            let output = (!ret.is_unit()).then_some("=&r");
+            let constraints: Vec<_> = output
+              .into_iter()
+              .chain(params.iter().map(|_| "r"))
+              .chain(["~{memory}"])
+              .collect();
+            format!(
+              "asm sideeffect \"{}\", \"{}\"",
+              llvm_asm_template(template),
+              constraints.join(",")
+            )
+          }
+          None => callee.name().into(),
+        };
+        let call = format!("call {} {}({})", ret_ty, target, args.join(", "));
+        match ret.is_unit() {
+          true => self.inst(call),
+          false => self.inst(format!("{} = {}", self.temp(value), call)),
+        }
+      }
+      ValueKind::Branch(branch) => {
+        let cond = self.temp(value);
+        let inst = format!("{}.cond = icmp ne {}, 0", cond, self.typed(branch.cond())?);
+        self.inst(inst);
+        let (t, f) = (self.label(branch.true_bb()), self.label(branch.false_bb()));
+        self.inst(format!("br i1 {}.cond, label %{}, label %{}", cond, t, f));
+      }
+      ValueKind::Jump(jump) => {
+        let inst = format!("br label %{}", self.label(jump.target()));
+        self.inst(inst);
+      }
+      ValueKind::Return(ret) => match ret.value() {
+        Some(value) => {
+          let inst = format!("ret {}", self.typed(value)?);
+          self.inst(inst);
+        }
+        None => self.inst("ret void".into()),
+      },
+      x => return Err(UnimplementedError(Box::new(x)).into()),
+    }
+    Ok(())
+  }
+
+  fn binary(&mut self, value: Value, op: BinaryOp, lhs: Value, rhs: Value) -> Result<()> {
+    let result = self.temp(value);
+    let (l, r) = (self.operand(lhs)?, self.operand(rhs)?);
+    let constant = match self.value_kind(rhs) {
+      ValueKind::Integer(i) if !rhs.is_global() => Some(i.value()),
+      _ => None,
+    };
+    let pred = match op {
+      BinaryOp::NotEq => Some("ne"),
+      BinaryOp::Eq => Some("eq"),
+      BinaryOp::Gt => Some("sgt"),
+      BinaryOp::Lt => Some("slt"),
+      BinaryOp::Ge => Some("sge"),
+      BinaryOp::Le => Some("sle"),
+      _ => None,
+    };
+    if let Some(pred) = pred {
+      self.inst(format!("{}.cmp = icmp {} i32 {}, {}", result, pred, l, r));
+      self.inst(format!("{} = zext i1 {}.cmp to i32", result, result));
+      return Ok(());
+    }
+    let inst = match op {
+      BinaryOp::Add => "add",
+      BinaryOp::Sub => "sub",
+      BinaryOp::Mul => "mul",
+      BinaryOp::And => "and",
+      BinaryOp::Or => "or",
+      BinaryOp::Xor => "xor",
+      // 除数是 0 与 -1 以外的常数时不会溢出，直接相除
+      BinaryOp::Div if constant.is_some_and(|c| c != 0 && c != -1) => "sdiv",
+      BinaryOp::Mod if constant.is_some_and(|c| c != 0 && c != -1) => "srem",
+      BinaryOp::Div | BinaryOp::Mod => {
+        self.uses_div = true;
+        let helper = match op {
+          BinaryOp::Div => DIV_HELPER,
+          _ => REM_HELPER,
+        };
+        self.inst(format!(
+          "{} = call i32 @{}(i32 {}, i32 {})",
+          result, helper, l, r
+        ));
+        return Ok(());
+      }
+      BinaryOp::Shl | BinaryOp::Shr | BinaryOp::Sar => {
+        let inst = match op {
+          BinaryOp::Shl => "shl",
+          BinaryOp::Shr => "lshr",
+          _ => "ashr",
+        };
+        // 移位量不小于 32 时 LLVM 的结果未定义，RISC-V 只取其低 5 位
+        let amount = match constant {
+          Some(c) => (c & 31).to_string(),
+          None => {
+            self.inst(format!("{}.amount = and i32 {}, 31", result, r));
+            format!("{}.amount", result)
+          }
+        };
+        self.inst(format!("{} = {} i32 {}, {}", result, inst, l, amount));
+        return Ok(());
+      }
+      _ => unreachable!("comparisons are handled above"),
+    };
+    self.inst(format!("{} = {} i32 {}, {}", result, inst, l, r));
+    Ok(())
+  }
+}
+
+/// 函数的定义，或没有函数体时的声明；内联汇编的声明不输出
+fn generate_func(
+  ir: &Ir,
+  fd: &FunctionData,
+  attrs: &str,
+  globals: &HashMap<Value, String>,
+  helpers: &mut (bool, bool),
+) -> Result<String> {
+  let (param_tys, ret) = signature(fd);
+  let ret = llvm_type(ret);
+  let name = fd.name();
+  let bbs = fd.layout().bbs();
+  if fd.layout().entry_bb().is_none() {
+    let params: Vec<_> = param_tys.iter().map(llvm_type).collect();
+    return Ok(format!(
+      "declare {} {}({}){}\n",
+      ret,
+      name,
+      params.join(", "),
+      attrs
+    ));
+  }
+  let mut context = FuncContext {
+    ir,
+    fd,
+    globals,
+    ids: HashMap::new(),
+    bbs: bbs.keys().enumerate().map(|(i, &bb)| (bb, i)).collect(),
+    body: String::new(),
+    uses_memset: false,
+    uses_div: false,
+  };
+  let params: Vec<_> = param_tys
+    .iter()
+    .enumerate()
+    .map(|(i, ty)| format!("{} %a{}", llvm_type(ty), i))
+    .collect();
+  let mut text = format!(
+    "define {} {}({}){} {{\n",
+    ret,
+    name,
+    params.join(", "),
+    attrs
+  );
+
+  // 所有的 `alloca` 都在入口块中，其后跳转到 IR 的第一个基本块
+  text += "entry:\n";
+  for (_, node) in bbs {
+    for &v in node.insts().keys() {
+      context.ids.insert(v, context.ids.len());
+      if let ValueKind::Alloc(_) = fd.dfg().value(v).kind() {
+        let ty = llvm_type(pointee(fd.dfg().value(v).ty()));
+        writeln!(text, "  %s{} = alloca {}", context.ids[&v], ty)?;
+      }
+    }
+  }
+  writeln!(text, "  br label %bb0")?;
+
+  for (&bb, node) in bbs {
+    writeln!(context.body, "\n{}:", context.label(bb))?;
+    for &v in node.insts().keys() {
+      context.generate_inst(v)?;
+    }
+  }
+  text += &context.body;
+  text += "}\n";
+  helpers.0 |= context.uses_memset;
+  helpers.1 |= context.uses_div;
+  Ok(text)
+}
+
+pub fn generate_llvm(ir: &Ir, mut output: impl Write) -> Result<()> {
+  let mut text = String::new();
+  let mut globals = HashMap::new();
+  for &v in ir.inst_layout() {
+    let operand = generate_global(ir, v, &mut text)?;
+    globals.insert(v, operand);
+  }
+  // (是否用到了 `llvm.memset`，是否用到了除法的辅助函数)
+  let mut helpers = (false, false);
+  for &f in ir.func_layout() {
+    if ir.annotations.inline_asm.contains_key(&f) {
+      continue;
+    }
+    let attrs = ir.annotations.func_attrs.get(&f);
+    let attrs = attrs.map(llvm_func_attrs).unwrap_or_default();
+    if !text.is_empty() {
+      text += "\n";
+    }
+    text += &generate_func(ir, ir.func(f), &attrs, &globals, &mut helpers)?;
+  }
+  if helpers.1 {
+    text += "\n";
+    text += DIV_HELPERS;
+  }
+  if helpers.0 {
+    writeln!(text, "\ndeclare void @{}(i8*, i8, i64, i1)", MEMSET)?;
+  }
+  output.write_all(text.as_bytes())?;
+  Ok(())
+}
@@ -0,0 +1,35 @@
+//! 机器中间表示（MIR）：操作数可以是虚拟寄存器的 RISC-V 指令序列。
+//!
+//! 指令选择（`from_func`、`from_value`）生成 MIR，寄存器分配（`regalloc`）将其改写为只含物理
+//! 寄存器的 [`Riscv`]；其后的窥孔优化、指令调度、压缩与输出都在后者上进行。
+
+use std::fmt;
+
+use super::riscv::reg::Reg;
+use super::riscv::Riscv;
+
+/// MIR 中的寄存器操作数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VReg {
+  /// 由调用约定等决定的物理寄存器，如 `sp`、`zero`、参数寄存器
+  Phys(Reg),
+  /// 尚未分配的虚拟寄存器
+  Virt(u32),
+}
+
+impl From<Reg> for VReg {
+  fn from(reg: Reg) -> Self {
+    VReg::Phys(reg)
+  }
+}
+
+impl fmt::Display for VReg {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      VReg::Phys(reg) => write!(f, "{}", reg),
+      VReg::Virt(n) => write!(f, "%v{}", n),
+    }
+  }
+}
+
+pub type Mir = Riscv<VReg>;
@@ -0,0 +1,174 @@
+//! 寄存器分配：为 MIR 中的虚拟寄存器指派物理寄存器。
+//!
+//! 指令选择保证虚拟寄存器只在一个基本块之内存活，且不跨越函数调用，因此在线性指令序列上
+//! 做一次线性扫描即可，不需要溢出到栈上，也不需要拆分生存区间。
+
+use std::cmp;
+use std::collections::HashMap;
+
+use log::trace;
+
+use super::error::RegAllocError;
+use super::mir::{Mir, VReg};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem};
+use super::soft_muldiv::{DIVMOD, MEMZERO, MUL};
+use crate::stats::Frame;
+use crate::Result;
+
+/// 可供分配的寄存器；`t6` 留给大立即数（偏移量）的展开
+const POOL: [Reg; 6] = [Reg::T0, Reg::T1, Reg::T2, Reg::T3, Reg::T4, Reg::T5];
+
+/// 以位集合表示的 `POOL` 子集
+type RegSet = u8;
+
+fn bit(reg: Reg) -> RegSet {
+  POOL.iter().position(|&r| r == reg).map_or(0, |i| 1 << i)
+}
+
+fn phys_set(regs: Vec<VReg>) -> RegSet {
+  regs.into_iter().fold(0, |set, reg| match reg {
+    VReg::Phys(reg) => set | bit(reg),
+    VReg::Virt(_) => set,
+  })
+}
+
+/// 指令写入（或破坏）的物理寄存器
+fn clobbers(inst: &Inst<VReg>) -> RegSet {
+  match inst {
+    // 临时寄存器均为调用者保存
+    Inst::Call(_) => !0,
+    _ => phys_set(inst.defs()),
+  }
+}
+
+/// 指令读取的物理寄存器
+fn reads(inst: &Inst<VReg>) -> RegSet {
+  match inst {
+    // 辅助例程经由 `t0`、`t1` 传参
+    Inst::Call(callee) if [MUL, DIVMOD, MEMZERO].contains(&callee.as_str()) => {
+      bit(Reg::T0) | bit(Reg::T1)
+    }
+    _ => phys_set(inst.uses()),
+  }
+}
+
+/// `--stats`：每条指令之后同时占用的可分配寄存器，即跨过该指令的虚拟寄存器与仍保存着有用值
+/// 的物理寄存器；记录其最大值与首次出现的位置
+fn record_pressure(
+  frame: &mut Frame,
+  insts: &[Option<&Inst<VReg>>],
+  live_after: &[RegSet],
+  intervals: &[(u32, (usize, usize))],
+) {
+  // 跨过每条指令的虚拟寄存器个数的差分
+  let mut delta = vec![0isize; insts.len() + 1];
+  for &(_, (start, end)) in intervals {
+    delta[start] += 1;
+    delta[end] -= 1;
+  }
+  let (mut max, mut index, mut virt) = (0, 0, 0);
+  for (i, inst) in insts.iter().enumerate() {
+    virt += delta[i];
+    if inst.is_some() {
+      // 与之重叠的虚拟寄存器不会分配到这些物理寄存器
+      let pressure = virt as usize + live_after[i].count_ones() as usize;
+      if pressure > max {
+        (max, index) = (pressure, i);
+      }
+    }
+  }
+  frame.set_pressure(max, index);
+}
+
+/// 分配一个函数的寄存器；给出 `frame` 时在其中记录寄存器压力
+pub fn allocate(mir: Mir, frame: Option<&mut Frame>) -> Result<Riscv> {
+  let insts: Vec<_> = mir
+    .0
+    .iter()
+    .map(|item| match item {
+      RiscvItem::Inst(inst) => Some(inst),
+      _ => None,
+    })
+    .collect();
+
+  // 每条指令之后仍保存着有用值的物理寄存器
+  let mut live_after = vec![0; insts.len()];
+  let mut live = 0;
+  for (i, inst) in insts.iter().enumerate().rev() {
+    if let Some(inst) = inst {
+      live_after[i] = live;
+      live = (live & !clobbers(inst)) | reads(inst);
+    }
+  }
+
+  // 虚拟寄存器的生存区间：[首次定义, 最后一次使用]
+  let mut intervals: HashMap<u32, (usize, usize)> = HashMap::new();
+  for (i, inst) in insts.iter().enumerate() {
+    if let Some(inst) = inst {
+      for reg in inst.defs() {
+        if let VReg::Virt(v) = reg {
+          intervals.entry(v).or_insert((i, i));
+        }
+      }
+      for reg in inst.uses() {
+        if let VReg::Virt(v) = reg {
+          let interval = intervals
+            .get_mut(&v)
+            .ok_or_else(|| RegAllocError(format!("%v{} used before definition", v)))?;
+          interval.1 = i;
+        }
+      }
+    }
+  }
+  let mut intervals: Vec<_> = intervals.into_iter().collect();
+  intervals.sort_by_key(|&(_, (start, _))| start);
+  if let Some(frame) = frame {
+    record_pressure(frame, &insts, &live_after, &intervals);
+  }
+
+  // 线性扫描；区间在同一条指令处一端结束、一端开始时可以共用寄存器，
+  // 但内联汇编可能在读完输入之前写入输出，两者不能共用
+  let mut assigned = HashMap::new();
+  let mut active: Vec<(usize, Reg)> = vec![];
+  for (v, (start, end)) in intervals {
+    let asm = matches!(insts[start], Some(Inst::Asm(..)));
+    active.retain(|&(active_end, _)| active_end > start || (asm && active_end == start));
+    let mut busy = active.iter().fold(0, |set, &(_, reg)| set | bit(reg));
+    for i in start..cmp::max(end, start + 1) {
+      if let Some(inst) = insts[i] {
+        busy |= live_after[i] | clobbers(inst);
+      }
+    }
+    let reg = *POOL
+      .iter()
+      .find(|&&reg| busy & bit(reg) == 0)
+      .ok_or_else(|| RegAllocError(format!("no register available for %v{}", v)))?;
+    trace!("%v{} [{}, {}] -> {}", v, start, end, reg);
+    active.push((end, reg));
+    assigned.insert(v, reg);
+  }
+
+  let mut result = Riscv::new();
+  for item in mir.0 {
+    match item {
+      RiscvItem::Inst(inst) => {
+        let inst = inst.map_regs(|reg| match reg {
+          VReg::Phys(reg) => reg,
+          VReg::Virt(v) => assigned[&v],
+        });
+        // 分配后成为自身到自身的复制，直接删去
+        if !matches!(inst, Inst::Mv(rd, rs) if rd == rs) {
+          result.add_inst(inst);
+        }
+      }
+      RiscvItem::Label(label) => result.add_label(label),
+      RiscvItem::Compressed(inst) => result.0.push(RiscvItem::Compressed(inst)),
+      RiscvItem::Comment(comment) => result.add_comment(comment),
+      RiscvItem::Directive(directive) => result.add_directive(directive),
+      RiscvItem::Empty => result.add_empty(),
+    }
+  }
+  Ok(result)
+}
@@ -0,0 +1,218 @@
+// https://github.com/riscv/riscv-isa-manual/releases （第 16 章 "C" 扩展）
+
+use std::fmt;
+
+use super::inst::Inst;
+use super::reg::Reg;
+use super::Xlen;
+
+/// 压缩指令。仅收录本编译器会生成的指令的压缩形式。
+#[derive(Debug, PartialEq, Clone)]
+pub enum CInst {
+  /// `c.lwsp rd, uimm(sp)`：`uimm` 为 4 的倍数，范围 [0, 252]
+  Lwsp(Reg, i32),
+  /// `c.swsp rs2, uimm(sp)`：`uimm` 为 4 的倍数，范围 [0, 252]
+  Swsp(Reg, i32),
+  /// `c.ldsp rd, uimm(sp)`：`uimm` 为 8 的倍数，范围 [0, 504]
+  Ldsp(Reg, i32),
+  /// `c.sdsp rs2, uimm(sp)`：`uimm` 为 8 的倍数，范围 [0, 504]
+  Sdsp(Reg, i32),
+  /// `c.lw rd', uimm(rs1')`：`uimm` 为 4 的倍数，范围 [0, 124]
+  Lw(Reg, i32, Reg),
+  /// `c.sw rs2', uimm(rs1')`：`uimm` 为 4 的倍数，范围 [0, 124]
+  Sw(Reg, i32, Reg),
+  /// `c.ld rd', uimm(rs1')`：`uimm` 为 8 的倍数，范围 [0, 248]
+  Ld(Reg, i32, Reg),
+  /// `c.sd rs2', uimm(rs1')`：`uimm` 为 8 的倍数，范围 [0, 248]
+  Sd(Reg, i32, Reg),
+  /// `c.li rd, imm6`
+  Li(Reg, i32),
+  /// `c.lui rd, nzimm6`：`rd` 不为 `zero` 与 `sp`，`nzimm6` 是载入高 20 位的值，范围
+  /// [-32, 31] 且不为 0；汇编中写作 20 位的无符号数
+  Lui(Reg, i32),
+  /// `c.addi rd, nzimm6`
+  Addi(Reg, i32),
+  /// `c.addiw rd, imm6`（RV64）
+  Addiw(Reg, i32),
+  /// `c.addi16sp sp, nzimm`：`nzimm` 为 16 的倍数，范围 [-512, 496]
+  Addi16sp(i32),
+  /// `c.addi4spn rd', sp, nzuimm`：`nzuimm` 为 4 的倍数，范围 (0, 1020]
+  Addi4spn(Reg, i32),
+  /// `c.slli rd, shamt`
+  Slli(Reg, i32),
+  /// `c.srli rd', shamt`
+  Srli(Reg, i32),
+  /// `c.andi rd', imm6`
+  Andi(Reg, i32),
+  /// `c.mv rd, rs2`
+  Mv(Reg, Reg),
+  /// `c.add rd, rs2`
+  Add(Reg, Reg),
+  /// `c.sub rd', rs2'`
+  Sub(Reg, Reg),
+  /// `c.xor rd', rs2'`
+  Xor(Reg, Reg),
+  /// `c.or rd', rs2'`
+  Or(Reg, Reg),
+  /// `c.and rd', rs2'`
+  And(Reg, Reg),
+  /// `c.addw rd', rs2'`（RV64）
+  Addw(Reg, Reg),
+  /// `c.subw rd', rs2'`（RV64）
+  Subw(Reg, Reg),
+  /// `c.jr rs1`
+  Jr(Reg),
+}
+
+/// 压缩指令中 3 位寄存器字段可表示的寄存器（x8 ~ x15）
+fn is_compact(reg: Reg) -> bool {
+  matches!(
+    reg,
+    Reg::Fp | Reg::S1 | Reg::A0 | Reg::A1 | Reg::A2 | Reg::A3 | Reg::A4 | Reg::A5
+  )
+}
+
+fn is_imm6(imm: i32) -> bool {
+  (-32..32).contains(&imm)
+}
+
+fn is_scaled(imm: i32, scale: i32, max: i32) -> bool {
+  imm % scale == 0 && (0..=max).contains(&imm)
+}
+
+/// 尝试将指令替换为等价的压缩指令
+pub fn compress(inst: &Inst, xlen: Xlen) -> Option<CInst> {
+  let rv64 = xlen == Xlen::Rv64;
+  let c = match *inst {
+    Inst::Lw(rd, imm, Reg::Sp) if rd != Reg::Zero && is_scaled(imm, 4, 252) => CInst::Lwsp(rd, imm),
+    Inst::Sw(rs2, imm, Reg::Sp) if is_scaled(imm, 4, 252) => CInst::Swsp(rs2, imm),
+    Inst::Ld(rd, imm, Reg::Sp) if rv64 && rd != Reg::Zero && is_scaled(imm, 8, 504) => {
+      CInst::Ldsp(rd, imm)
+    }
+    Inst::Sd(rs2, imm, Reg::Sp) if rv64 && is_scaled(imm, 8, 504) => CInst::Sdsp(rs2, imm),
+    Inst::Lw(rd, imm, rs1) if is_compact(rd) && is_compact(rs1) && is_scaled(imm, 4, 124) => {
+      CInst::Lw(rd, imm, rs1)
+    }
+    Inst::Sw(rs2, imm, rs1) if is_compact(rs2) && is_compact(rs1) && is_scaled(imm, 4, 124) => {
+      CInst::Sw(rs2, imm, rs1)
+    }
+    Inst::Ld(rd, imm, rs1)
+      if rv64 && is_compact(rd) && is_compact(rs1) && is_scaled(imm, 8, 248) =>
+    {
+      CInst::Ld(rd, imm, rs1)
+    }
+    Inst::Sd(rs2, imm, rs1)
+      if rv64 && is_compact(rs2) && is_compact(rs1) && is_scaled(imm, 8, 248) =>
+    {
+      CInst::Sd(rs2, imm, rs1)
+    }
+    Inst::Li(rd, imm) if rd != Reg::Zero && is_imm6(imm) => CInst::Li(rd, imm),
+    Inst::Li(rd, imm)
+      if rd != Reg::Zero && rd != Reg::Sp && imm & 0xfff == 0 && is_imm6(imm >> 12) =>
+    {
+      CInst::Lui(rd, imm >> 12)
+    }
+    Inst::Addi(rd, Reg::Zero, imm) if rd != Reg::Zero && is_imm6(imm) => CInst::Li(rd, imm),
+    Inst::Mv(rd, Reg::Zero) if rd != Reg::Zero => CInst::Li(rd, 0),
+    // 结果即 `rs` 的运算
+    Inst::Slli(rd, rs, 0)
+    | Inst::Srli(rd, rs, 0)
+    | Inst::Ori(rd, rs, 0)
+    | Inst::Xori(rd, rs, 0)
+    | Inst::Or(rd, rs, Reg::Zero)
+    | Inst::Or(rd, Reg::Zero, rs)
+    | Inst::Xor(rd, rs, Reg::Zero)
+    | Inst::Xor(rd, Reg::Zero, rs)
+    | Inst::Sub(rd, rs, Reg::Zero)
+      if rd != Reg::Zero && rs != Reg::Zero =>
+    {
+      CInst::Mv(rd, rs)
+    }
+    Inst::Addi(Reg::Sp, Reg::Sp, imm)
+      if imm != 0 && imm % 16 == 0 && (-512..=496).contains(&imm) =>
+    {
+      CInst::Addi16sp(imm)
+    }
+    Inst::Addi(rd, Reg::Sp, imm) if is_compact(rd) && imm != 0 && is_scaled(imm, 4, 1020) => {
+      CInst::Addi4spn(rd, imm)
+    }
+    Inst::Addi(rd, rs, 0) if rd != Reg::Zero && rs != Reg::Zero => CInst::Mv(rd, rs),
+    Inst::Addi(rd, rs, imm) if rd == rs && rd != Reg::Zero && is_imm6(imm) => CInst::Addi(rd, imm),
+    Inst::Slli(rd, rs, imm)
+      if rd == rs && rd != Reg::Zero && imm > 0 && imm < xlen.bytes() as i32 * 8 =>
+    {
+      CInst::Slli(rd, imm)
+    }
+    Inst::Srli(rd, rs, imm)
+      if rd == rs && is_compact(rd) && imm > 0 && imm < xlen.bytes() as i32 * 8 =>
+    {
+      CInst::Srli(rd, imm)
+    }
+    Inst::Andi(rd, rs, imm) if rd == rs && is_compact(rd) && is_imm6(imm) => CInst::Andi(rd, imm),
+    Inst::SextW(rd, rs) if rv64 && rd == rs && rd != Reg::Zero => CInst::Addiw(rd, 0),
+    Inst::Mv(rd, rs) if rd != Reg::Zero && rs != Reg::Zero => CInst::Mv(rd, rs),
+    Inst::Add(rd, Reg::Zero, rs) | Inst::Add(rd, rs, Reg::Zero)
+      if rd != Reg::Zero && rs != Reg::Zero =>
+    {
+      CInst::Mv(rd, rs)
+    }
+    Inst::Add(rd, rs1, rs2) if rd == rs1 && rd != Reg::Zero && rs2 != Reg::Zero => {
+      CInst::Add(rd, rs2)
+    }
+    Inst::Add(rd, rs1, rs2) if rd == rs2 && rd != Reg::Zero && rs1 != Reg::Zero => {
+      CInst::Add(rd, rs1)
+    }
+    Inst::Sub(rd, rs1, rs2) if rd == rs1 && is_compact(rd) && is_compact(rs2) => {
+      CInst::Sub(rd, rs2)
+    }
+    Inst::Xor(rd, rs1, rs2) if rd == rs1 && is_compact(rd) && is_compact(rs2) => {
+      CInst::Xor(rd, rs2)
+    }
+    Inst::Or(rd, rs1, rs2) if rd == rs1 && is_compact(rd) && is_compact(rs2) => CInst::Or(rd, rs2),
+    Inst::And(rd, rs1, rs2) if rd == rs1 && is_compact(rd) && is_compact(rs2) => {
+      CInst::And(rd, rs2)
+    }
+    Inst::Addw(rd, rs1, rs2) if rv64 && rd == rs1 && is_compact(rd) && is_compact(rs2) => {
+      CInst::Addw(rd, rs2)
+    }
+    Inst::Subw(rd, rs1, rs2) if rv64 && rd == rs1 && is_compact(rd) && is_compact(rs2) => {
+      CInst::Subw(rd, rs2)
+    }
+    Inst::Ret => CInst::Jr(Reg::Ra),
+    _ => return None,
+  };
+  Some(c)
+}
+
+impl fmt::Display for CInst {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      CInst::Lwsp(rd, imm) => write!(f, "  c.lwsp {}, {}(sp)", rd, imm),
+      CInst::Swsp(rs2, imm) => write!(f, "  c.swsp {}, {}(sp)", rs2, imm),
+      CInst::Ldsp(rd, imm) => write!(f, "  c.ldsp {}, {}(sp)", rd, imm),
+      CInst::Sdsp(rs2, imm) => write!(f, "  c.sdsp {}, {}(sp)", rs2, imm),
+      CInst::Lw(rd, imm, rs1) => write!(f, "  c.lw {}, {}({})", rd, imm, rs1),
+      CInst::Sw(rs2, imm, rs1) => write!(f, "  c.sw {}, {}({})", rs2, imm, rs1),
+      CInst::Ld(rd, imm, rs1) => write!(f, "  c.ld {}, {}({})", rd, imm, rs1),
+      CInst::Sd(rs2, imm, rs1) => write!(f, "  c.sd {}, {}({})", rs2, imm, rs1),
+      CInst::Li(rd, imm) => write!(f, "  c.li {}, {}", rd, imm),
+      CInst::Lui(rd, imm) => write!(f, "  c.lui {}, {}", rd, imm & 0xfffff),
+      CInst::Addi(rd, imm) => write!(f, "  c.addi {}, {}", rd, imm),
+      CInst::Addiw(rd, imm) => write!(f, "  c.addiw {}, {}", rd, imm),
+      CInst::Addi16sp(imm) => write!(f, "  c.addi16sp sp, {}", imm),
+      CInst::Addi4spn(rd, imm) => write!(f, "  c.addi4spn {}, sp, {}", rd, imm),
+      CInst::Slli(rd, imm) => write!(f, "  c.slli {}, {}", rd, imm),
+      CInst::Srli(rd, imm) => write!(f, "  c.srli {}, {}", rd, imm),
+      CInst::Andi(rd, imm) => write!(f, "  c.andi {}, {}", rd, imm),
+      CInst::Mv(rd, rs2) => write!(f, "  c.mv {}, {}", rd, rs2),
+      CInst::Add(rd, rs2) => write!(f, "  c.add {}, {}", rd, rs2),
+      CInst::Sub(rd, rs2) => write!(f, "  c.sub {}, {}", rd, rs2),
+      CInst::Xor(rd, rs2) => write!(f, "  c.xor {}, {}", rd, rs2),
+      CInst::Or(rd, rs2) => write!(f, "  c.or {}, {}", rd, rs2),
+      CInst::And(rd, rs2) => write!(f, "  c.and {}, {}", rd, rs2),
+      CInst::Addw(rd, rs2) => write!(f, "  c.addw {}, {}", rd, rs2),
+      CInst::Subw(rd, rs2) => write!(f, "  c.subw {}, {}", rd, rs2),
+      CInst::Jr(rs1) => write!(f, "  c.jr {}", rs1),
+    }
+  }
+}
@@ -1,12 +1,46 @@
 use std::fmt;
+use std::sync::Arc;
+
+use crate::backend::Origin;
+
+/// 符号的类型（`.type`）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolType {
+  Function,
+  Object,
+}
 
 #[derive(Debug, Clone)]
 pub enum Directive {
   Text,
   Globl(String),
+  /// 仅在本目标文件内可见的符号
+  Local(String),
+  Type(String, SymbolType),
   Data,
+  Bss,
+  Section(String),
+  Align(u32),
+  Size(String, usize),
+  /// 符号的大小为从符号到另一标号的距离，如函数到其结束标号
+  SizeTo(String, String),
   Zero(i32),
   Word(Vec<i32>),
+  Option(String),
+  /// 源文件（编号固定为 1），供 `.loc` 引用
+  File(String),
+  /// 之后的指令来源于源文件的某行
+  Loc(usize),
+  /// 之后的指令来源于某条 IR 指令（`--source-map`），输出之前去除
+  Origin(Arc<Origin>),
+  /// 之后的访存访问 volatile 变量：窥孔优化与指令调度不跨过这一标记合并或重排访存
+  Volatile,
+  Byte(u8),
+  Half(u16),
+  Uleb128(u64),
+  Asciz(String),
+  /// 占若干字节（4 或 8）的符号表达式，如 `main`、`.Lend-main`
+  Addr(usize, String),
 }
 
 impl fmt::Display for Directive {
@@ -14,12 +48,31 @@ impl fmt::Display for Directive {
     let str = match self {
       Directive::Text => "  .text".into(),
       Directive::Globl(label) => format!("  .globl {}", label),
+      Directive::Local(label) => format!("  .local {}", label),
+      Directive::Type(label, SymbolType::Function) => format!("  .type {}, @function", label),
+      Directive::Type(label, SymbolType::Object) => format!("  .type {}, @object", label),
       Directive::Data => "  .data".into(),
+      Directive::Bss => "  .bss".into(),
+      Directive::Section(name) => format!("  .section {}", name),
+      Directive::Align(pow) => format!("  .align {}", pow),
+      Directive::Size(label, size) => format!("  .size {}, {}", label, size),
+      Directive::SizeTo(label, end) => format!("  .size {}, {}-{}", label, end, label),
       Directive::Zero(number) => format!("  .zero {}", number),
       Directive::Word(data) => {
         let data: Vec<_> = data.iter().map(i32::to_string).collect();
         format!("  .word {}", data.join(", "))
       }
+      Directive::Option(option) => format!("  .option {}", option),
+      Directive::File(name) => format!("  .file 1 {:?}", name),
+      Directive::Loc(line) => format!("  .loc 1 {} 0", line),
+      Directive::Origin(origin) => format!("  # origin {}", origin.koopa),
+      Directive::Volatile => "  # volatile".into(),
+      Directive::Byte(byte) => format!("  .byte {}", byte),
+      Directive::Half(half) => format!("  .half {}", half),
+      Directive::Uleb128(value) => format!("  .uleb128 {}", value),
+      Directive::Asciz(string) => format!("  .asciz {:?}", string),
+      Directive::Addr(4, expr) => format!("  .word {}", expr),
+      Directive::Addr(_, expr) => format!("  .quad {}", expr),
     };
     write!(f, "{}", str)
   }
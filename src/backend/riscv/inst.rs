@@ -6,16 +6,16 @@ use super::reg::Reg;
 
 #[allow(dead_code)]
 #[derive(Debug, PartialEq, Clone)]
-pub enum Inst {
+pub enum Inst<R = Reg> {
   /// 伪指令
   /// - 汇编格式：`beqz rs, label`
   /// - 行为：如果 `rs` 寄存器的值等于 0，则转移到目标 `label`
-  Beqz(Reg, String),
+  Beqz(R, String),
 
   /// 伪指令
   /// - 汇编格式：`bnez rs, label`
   /// - 行为：如果 `rs` 寄存器的值不等于 0，则转移到目标 `label`
-  Bnez(Reg, String),
+  Bnez(R, String),
 
   /// 伪指令
   /// - 汇编格式：`j label`
@@ -27,172 +27,506 @@ pub enum Inst {
   /// - 行为：将后一条指令的地址存入 `ra` 寄存器，并无条件转移到目标 `label`
   Call(String),
 
+  /// 内联汇编
+  /// - 汇编格式：模板原样输出，其中 `%N` 替换为第 N 个操作数（先输出后输入）所在的寄存器，
+  ///   `%%` 替换为 `%`
+  /// - 行为：由模板决定。除输出操作数外不得改写任何寄存器；输出操作数不与输入操作数共用寄存器
+  Asm(String, Vec<R>, Vec<R>),
+
   /// 伪指令
   /// - 汇编格式：`ret`
   /// - 行为：无条件转移到 `ra` 寄存器中保存的地址处
   Ret,
 
+  /// 指令
+  /// - 汇编格式：`ecall`
+  /// - 行为：向执行环境（如 SBI）发出请求，参数经由 `a0`、`a1`、`a6`、`a7` 传递，结果存入 `a0`、`a1`
+  Ecall,
+
   /// 指令
   /// - 汇编格式：`lw rs, imm12(rd)`
   /// - 行为：计算 `rd` 寄存器的值与 `imm12` 相加的结果作为访存地址，从内存中读取 32-bit 的数据，存入 `rs` 寄存器
-  Lw(Reg, i32, Reg),
+  Lw(R, i32, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`ld rs, imm12(rd)`
+  /// - 行为：计算 `rd` 寄存器的值与 `imm12` 相加的结果作为访存地址，从内存中读取 64-bit 的数据，存入 `rs` 寄存器
+  Ld(R, i32, R),
 
   /// 指令
   /// - 汇编格式：`sw rs2, imm12(rs1)`
   /// - 行为：计算 `rs1` 寄存器的值与 `imm12` 相加的结果作为访存地址，将 `rs2` 寄存器的值 (32-bit) 存入内存
-  Sw(Reg, i32, Reg),
+  Sw(R, i32, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`sd rs2, imm12(rs1)`
+  /// - 行为：计算 `rs1` 寄存器的值与 `imm12` 相加的结果作为访存地址，将 `rs2` 寄存器的值 (64-bit) 存入内存
+  Sd(R, i32, R),
 
   /// 指令
   /// - 汇编格式：`add rd, rs1, rs2`
   /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器相加的值，存入 `rd` 寄存器
-  Add(Reg, Reg, Reg),
+  Add(R, R, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`addw rd, rs1, rs2`
+  /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器低 32 位相加的值，符号扩展后存入 `rd` 寄存器
+  Addw(R, R, R),
 
   /// 指令
   /// - 汇编格式：`addi rd, rs1, imm12`
   /// - 行为：计算 `rs1` 寄存器和 `imm12` 相加的值，存入 `rd` 寄存器
-  Addi(Reg, Reg, i32),
+  Addi(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`sub rd, rs1, rs2`
   /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器相减的值，存入 `rd` 寄存器
-  Sub(Reg, Reg, Reg),
+  Sub(R, R, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`subw rd, rs1, rs2`
+  /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器低 32 位相减的值，符号扩展后存入 `rd` 寄存器
+  Subw(R, R, R),
 
   /// 指令
   /// - 汇编格式：`slt rd, rs1, rs2`
   /// - 行为：如果 `rs1` 寄存器小于 `rs2` 寄存器，则将 1 写入 `rd` 寄存器，否则写入 0
-  Slt(Reg, Reg, Reg),
+  Slt(R, R, R),
+
+  /// 指令
+  /// - 汇编格式：`sltu rd, rs1, rs2`
+  /// - 行为：如果 `rs1` 寄存器作为无符号数小于 `rs2` 寄存器，则将 1 写入 `rd` 寄存器，否则写入 0
+  Sltu(R, R, R),
 
   /// 伪指令
   /// - 汇编格式：`sgt rd, rs1, rs2`
   /// - 行为：如果 `rs1` 寄存器大于 `rs2` 寄存器，则将 1 写入 `rd` 寄存器，否则写入 0
-  Sgt(Reg, Reg, Reg),
+  Sgt(R, R, R),
 
   /// 伪指令
   /// - 汇编格式：`seqz rd, rs`
   /// - 行为：如果 `rs` 寄存器等于 0，则将 1 写入 `rd` 寄存器，否则写入 0
-  Seqz(Reg, Reg),
+  Seqz(R, R),
 
   /// 伪指令
   /// - 汇编格式：`snez rd, rs`
   /// - 行为：如果 `rs` 寄存器不等于 0，则将 1 写入 `rd` 寄存器，否则写入 0
-  Snez(Reg, Reg),
+  Snez(R, R),
 
   /// 指令
   /// - 汇编格式：`xor rd, rs1, rs2`
   /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器按位异或的值，存入 `rd` 寄存器
-  Xor(Reg, Reg, Reg),
+  Xor(R, R, R),
 
   /// 指令
   /// - 汇编格式：`xori rd, rs1, imm12`
   /// - 行为：计算 `rs1` 寄存器和 `imm12` 按位异或的值，存入 `rd` 寄存器
-  Xori(Reg, Reg, i32),
+  Xori(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`or rd, rs1, rs2`
   /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器按位或的值，存入 `rd` 寄存器
-  Or(Reg, Reg, Reg),
+  Or(R, R, R),
 
   /// 指令
   /// - 汇编格式：`ori rd, rs1, imm12`
   /// - 行为：计算 `rs1` 寄存器和 `imm12` 按位或的值，存入 `rd` 寄存器
-  Ori(Reg, Reg, i32),
+  Ori(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`and rd, rs1, rs2`
   /// - 行为：计算 `rs1` 寄存器和 `rs2` 寄存器按位与的值，存入 `rd` 寄存器
-  And(Reg, Reg, Reg),
+  And(R, R, R),
 
   /// 指令
   /// - 汇编格式：`andi rd, rs1, imm12`
   /// - 行为：计算 `rs1` 寄存器和 `imm12` 按位与的值，存入 `rd` 寄存器
-  Andi(Reg, Reg, i32),
+  Andi(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`sll rd, rs1, rs2`
   /// - 行为：对寄存器 `rs1` 进行逻辑左移运算，移位的位数为 `rs2` 寄存器的值，结果存入 `rd` 寄存器
-  Sll(Reg, Reg, Reg),
+  Sll(R, R, R),
 
   /// 指令
   /// - 汇编格式：`slli rd, rs1, imm12`
   /// - 行为：对寄存器 `rs1` 进行逻辑左移运算，移位的位数为 `imm12`，结果存入 `rd` 寄存器
-  Slli(Reg, Reg, i32),
+  Slli(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`srl rd, rs1, rs2`
   /// - 行为：对寄存器 `rs1` 进行逻辑右移运算，移位的位数为 `rs2` 寄存器的值，结果存入 `rd` 寄存器
-  Srl(Reg, Reg, Reg),
+  Srl(R, R, R),
+
+  /// 指令
+  /// - 汇编格式：`srli rd, rs1, imm12`
+  /// - 行为：对寄存器 `rs1` 进行逻辑右移运算，移位的位数为 `imm12`，结果存入 `rd` 寄存器
+  Srli(R, R, i32),
 
   /// 指令
   /// - 汇编格式：`sra rd, rs1, rs2`
   /// - 行为：对寄存器 `rs1` 进行算数右移运算，移位的位数为 `rs2` 寄存器的值，结果存入 `rd` 寄存器
-  Sra(Reg, Reg, Reg),
+  Sra(R, R, R),
 
   /// 指令
   /// - 汇编格式：`mul rd, rs1, rs2`
   /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 相乘的值，存入 `rd` 寄存器
-  Mul(Reg, Reg, Reg),
+  Mul(R, R, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`mulw rd, rs1, rs2`
+  /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 低 32 位相乘的值，符号扩展后存入 `rd` 寄存器
+  Mulw(R, R, R),
 
   /// 指令
   /// - 汇编格式：`div rd, rs1, rs2`
   /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 相除以的值，存入 `rd` 寄存器
-  Div(Reg, Reg, Reg),
+  Div(R, R, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`divw rd, rs1, rs2`
+  /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 低 32 位相除的值，符号扩展后存入 `rd` 寄存器
+  Divw(R, R, R),
 
   /// 指令
   /// - 汇编格式：`rem rd, rs1, rs2`
   /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 相取余的值，存入 `rd` 寄存器
-  Rem(Reg, Reg, Reg),
+  Rem(R, R, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`remw rd, rs1, rs2`
+  /// - 行为：计算寄存器 `rs1` 和寄存器 `rs2` 低 32 位相取余的值，符号扩展后存入 `rd` 寄存器
+  Remw(R, R, R),
+
+  /// 伪指令（RV64）
+  /// - 汇编格式：`sext.w rd, rs`
+  /// - 行为：将寄存器 `rs` 的低 32 位符号扩展后存入 `rd` 寄存器
+  SextW(R, R),
 
   /// 伪指令
   /// - 汇编格式：`li rd, imm`
   /// - 行为：将立即数 `imm` 加载到寄存器 `rd` 中
-  Li(Reg, i32),
+  Li(R, i32),
 
   /// 伪指令
   /// - 汇编格式：`la rd, label`
   /// - 行为：将标号 `label` 的绝对地址加载到寄存器 `rd` 中
-  La(Reg, String),
+  La(R, String),
 
   /// 伪指令
   /// - 汇编格式：`mv rd, rs`
   /// - 行为：将寄存器 `rs` 的值复制到寄存器 `rd`
-  Mv(Reg, Reg),
+  Mv(R, R),
+
+  /// 指令
+  /// - 汇编格式：`lui rd, %hi(label)`
+  /// - 行为：将标号 `label` 绝对地址的高 20 位（已计入低 12 位的符号扩展）加载到寄存器 `rd` 中
+  Lui(R, String),
+
+  /// 指令
+  /// - 汇编格式：`addi rd, rs, %lo(label)`
+  /// - 行为：`rs` 为 `%hi(label)` 时，将标号 `label` 的绝对地址存入 `rd` 寄存器
+  AddiLo(R, R, String),
+
+  /// 指令
+  /// - 汇编格式：`lw rd, %lo(label)(rs)`
+  /// - 行为：`rs` 为 `%hi(label)` 时，从标号 `label` 处读取 32-bit 的数据，存入 `rd` 寄存器
+  LwLo(R, String, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`ld rd, %lo(label)(rs)`
+  /// - 行为：`rs` 为 `%hi(label)` 时，从标号 `label` 处读取 64-bit 的数据，存入 `rd` 寄存器
+  LdLo(R, String, R),
+
+  /// 指令
+  /// - 汇编格式：`sw rs2, %lo(label)(rs1)`
+  /// - 行为：`rs1` 为 `%hi(label)` 时，将 `rs2` 寄存器的值 (32-bit) 存入标号 `label` 处
+  SwLo(R, String, R),
+
+  /// 指令（RV64）
+  /// - 汇编格式：`sd rs2, %lo(label)(rs1)`
+  /// - 行为：`rs1` 为 `%hi(label)` 时，将 `rs2` 寄存器的值 (64-bit) 存入标号 `label` 处
+  SdLo(R, String, R),
 }
 
-fn fmt_reg2(name: &str, reg1: Reg, reg2: Reg) -> String {
-  format!("  {} {}, {}", name, reg1, reg2)
+/// 访存指令的地址信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MemAccess<R = Reg> {
+  pub store: bool,
+  pub base: R,
+  pub offset: i32,
+  pub width: usize,
+  /// 偏移量为 `%lo(label)`，链接时才能确定
+  pub lo: bool,
 }
 
-fn fmt_reg3(name: &str, reg1: Reg, reg2: Reg, reg3: Reg) -> String {
-  format!("  {} {}, {}, {}", name, reg1, reg2, reg3)
+fn is_imm12(imm: i32) -> bool {
+  (-2048..2048).contains(&imm)
 }
 
-fn fmt_reg2_offset(name: &str, reg1: Reg, reg2: Reg, offset: i32) -> String {
-  if offset < -2048 || offset > 2047 {
-    format!(
-      "  li t6, {}\n  add t6, t6, {}\n  {} {}, 0(t6)",
-      offset, reg2, name, reg1
-    )
-  } else {
-    format!("  {} {}, {}({})", name, reg1, offset, reg2)
+impl<R: Copy + From<Reg>> Inst<R> {
+  /// 按访存宽度（字节）选择 `lw` 或 `ld`
+  pub fn load(width: usize, rd: R, offset: i32, rs: R) -> Self {
+    match width {
+      8 => Inst::Ld(rd, offset, rs),
+      _ => Inst::Lw(rd, offset, rs),
+    }
+  }
+
+  /// 按访存宽度（字节）选择 `sw` 或 `sd`
+  pub fn store(width: usize, rs2: R, offset: i32, rs1: R) -> Self {
+    match width {
+      8 => Inst::Sd(rs2, offset, rs1),
+      _ => Inst::Sw(rs2, offset, rs1),
+    }
   }
-}
 
-fn fmt_reg2_imm(name: &str, reg1: Reg, reg2: Reg, imm: i32) -> String {
-  if imm < -2048 || imm > 2047 {
-    let len = name.len();
-    format!(
-      "  li t6, {}\n  {} {}, {}, t6",
-      imm,
-      &name[..len - 1],
-      reg1,
-      reg2
+  /// 按访存宽度（字节）选择 `lw` 或 `ld`，以 `%lo(label)` 为偏移量
+  pub fn load_lo(width: usize, rd: R, label: String, rs: R) -> Self {
+    match width {
+      8 => Inst::LdLo(rd, label, rs),
+      _ => Inst::LwLo(rd, label, rs),
+    }
+  }
+
+  /// 按访存宽度（字节）选择 `sw` 或 `sd`，以 `%lo(label)` 为偏移量
+  pub fn store_lo(width: usize, rs2: R, label: String, rs1: R) -> Self {
+    match width {
+      8 => Inst::SdLo(rs2, label, rs1),
+      _ => Inst::SwLo(rs2, label, rs1),
+    }
+  }
+
+  /// 指令写入的寄存器
+  pub fn defs(&self) -> Vec<R> {
+    match self {
+      Inst::Beqz(..) | Inst::Bnez(..) | Inst::J(_) | Inst::Ret => vec![],
+      Inst::Call(_) => vec![Reg::Ra.into()],
+      Inst::Ecall => vec![Reg::A0.into(), Reg::A1.into()],
+      Inst::Asm(_, outputs, _) => outputs.clone(),
+      Inst::Sw(..) | Inst::Sd(..) | Inst::SwLo(..) | Inst::SdLo(..) => vec![],
+      Inst::Lw(rd, ..)
+      | Inst::Ld(rd, ..)
+      | Inst::LwLo(rd, ..)
+      | Inst::LdLo(rd, ..)
+      | Inst::Add(rd, ..)
+      | Inst::Addw(rd, ..)
+      | Inst::Addi(rd, ..)
+      | Inst::Sub(rd, ..)
+      | Inst::Subw(rd, ..)
+      | Inst::Slt(rd, ..)
+      | Inst::Sltu(rd, ..)
+      | Inst::Sgt(rd, ..)
+      | Inst::Seqz(rd, _)
+      | Inst::Snez(rd, _)
+      | Inst::Xor(rd, ..)
+      | Inst::Xori(rd, ..)
+      | Inst::Or(rd, ..)
+      | Inst::Ori(rd, ..)
+      | Inst::And(rd, ..)
+      | Inst::Andi(rd, ..)
+      | Inst::Sll(rd, ..)
+      | Inst::Slli(rd, ..)
+      | Inst::Srl(rd, ..)
+      | Inst::Srli(rd, ..)
+      | Inst::Sra(rd, ..)
+      | Inst::Mul(rd, ..)
+      | Inst::Mulw(rd, ..)
+      | Inst::Div(rd, ..)
+      | Inst::Divw(rd, ..)
+      | Inst::Rem(rd, ..)
+      | Inst::Remw(rd, ..)
+      | Inst::SextW(rd, _)
+      | Inst::Li(rd, _)
+      | Inst::La(rd, _)
+      | Inst::Lui(rd, _)
+      | Inst::AddiLo(rd, ..)
+      | Inst::Mv(rd, _) => vec![*rd],
+    }
+  }
+
+  /// 指令读取的寄存器
+  pub fn uses(&self) -> Vec<R> {
+    match self {
+      Inst::J(_) | Inst::Call(_) | Inst::Li(..) | Inst::La(..) | Inst::Lui(..) => vec![],
+      Inst::Ret => vec![Reg::Ra.into()],
+      Inst::Ecall => vec![
+        Reg::A0.into(),
+        Reg::A1.into(),
+        Reg::A6.into(),
+        Reg::A7.into(),
+      ],
+      Inst::Asm(_, _, inputs) => inputs.clone(),
+      Inst::Beqz(rs, _) | Inst::Bnez(rs, _) => vec![*rs],
+      Inst::Lw(_, _, rs) | Inst::Ld(_, _, rs) => vec![*rs],
+      Inst::LwLo(_, _, rs) | Inst::LdLo(_, _, rs) | Inst::AddiLo(_, rs, _) => vec![*rs],
+      Inst::Sw(rs2, _, rs1) | Inst::Sd(rs2, _, rs1) => vec![*rs2, *rs1],
+      Inst::SwLo(rs2, _, rs1) | Inst::SdLo(rs2, _, rs1) => vec![*rs2, *rs1],
+      Inst::Addi(_, rs, _)
+      | Inst::Xori(_, rs, _)
+      | Inst::Ori(_, rs, _)
+      | Inst::Andi(_, rs, _)
+      | Inst::Slli(_, rs, _)
+      | Inst::Srli(_, rs, _)
+      | Inst::Seqz(_, rs)
+      | Inst::Snez(_, rs)
+      | Inst::SextW(_, rs)
+      | Inst::Mv(_, rs) => vec![*rs],
+      Inst::Add(_, rs1, rs2)
+      | Inst::Addw(_, rs1, rs2)
+      | Inst::Sub(_, rs1, rs2)
+      | Inst::Subw(_, rs1, rs2)
+      | Inst::Slt(_, rs1, rs2)
+      | Inst::Sltu(_, rs1, rs2)
+      | Inst::Sgt(_, rs1, rs2)
+      | Inst::Xor(_, rs1, rs2)
+      | Inst::Or(_, rs1, rs2)
+      | Inst::And(_, rs1, rs2)
+      | Inst::Sll(_, rs1, rs2)
+      | Inst::Srl(_, rs1, rs2)
+      | Inst::Sra(_, rs1, rs2)
+      | Inst::Mul(_, rs1, rs2)
+      | Inst::Mulw(_, rs1, rs2)
+      | Inst::Div(_, rs1, rs2)
+      | Inst::Divw(_, rs1, rs2)
+      | Inst::Rem(_, rs1, rs2)
+      | Inst::Remw(_, rs1, rs2) => vec![*rs1, *rs2],
+    }
+  }
+
+  /// 访存指令的地址；非访存指令返回 `None`
+  pub fn mem(&self) -> Option<MemAccess<R>> {
+    let (store, base, offset, width, lo) = match *self {
+      Inst::Lw(_, offset, rs) => (false, rs, offset, 4, false),
+      Inst::Ld(_, offset, rs) => (false, rs, offset, 8, false),
+      Inst::Sw(_, offset, rs1) => (true, rs1, offset, 4, false),
+      Inst::Sd(_, offset, rs1) => (true, rs1, offset, 8, false),
+      Inst::LwLo(_, _, rs) => (false, rs, 0, 4, true),
+      Inst::LdLo(_, _, rs) => (false, rs, 0, 8, true),
+      Inst::SwLo(_, _, rs1) => (true, rs1, 0, 4, true),
+      Inst::SdLo(_, _, rs1) => (true, rs1, 0, 8, true),
+      _ => return None,
+    };
+    Some(MemAccess {
+      store,
+      base,
+      offset,
+      width,
+      lo,
+    })
+  }
+
+  /// 立即数（偏移量）能否直接编码；不能时需经 `legalize` 展开
+  pub fn imm_fits(&self) -> bool {
+    match *self {
+      Inst::Lw(_, imm, _)
+      | Inst::Ld(_, imm, _)
+      | Inst::Sw(_, imm, _)
+      | Inst::Sd(_, imm, _)
+      | Inst::Addi(_, _, imm)
+      | Inst::Xori(_, _, imm)
+      | Inst::Ori(_, _, imm)
+      | Inst::Andi(_, _, imm)
+      | Inst::Slli(_, _, imm)
+      | Inst::Srli(_, _, imm) => is_imm12(imm),
+      _ => true,
+    }
+  }
+
+  /// 是否改变控制流（分支、跳转、调用、返回、`ecall`）。内联汇编可能含有任意指令，也视为控制流指令
+  pub fn is_control(&self) -> bool {
+    matches!(
+      self,
+      Inst::Beqz(..)
+        | Inst::Bnez(..)
+        | Inst::J(_)
+        | Inst::Call(_)
+        | Inst::Asm(..)
+        | Inst::Ret
+        | Inst::Ecall
     )
-  } else {
-    format!("  {} {}, {}, {}", name, reg1, reg2, imm)
+  }
+
+  /// 逐个替换寄存器操作数，如寄存器分配时将虚拟寄存器换为物理寄存器
+  pub fn map_regs<S>(self, mut f: impl FnMut(R) -> S) -> Inst<S> {
+    match self {
+      Inst::Beqz(rs, label) => Inst::Beqz(f(rs), label),
+      Inst::Bnez(rs, label) => Inst::Bnez(f(rs), label),
+      Inst::J(label) => Inst::J(label),
+      Inst::Call(label) => Inst::Call(label),
+      Inst::Asm(template, outputs, inputs) => Inst::Asm(
+        template,
+        outputs.into_iter().map(&mut f).collect(),
+        inputs.into_iter().map(&mut f).collect(),
+      ),
+      Inst::Ret => Inst::Ret,
+      Inst::Ecall => Inst::Ecall,
+      Inst::Lw(r1, offset, r2) => Inst::Lw(f(r1), offset, f(r2)),
+      Inst::Ld(r1, offset, r2) => Inst::Ld(f(r1), offset, f(r2)),
+      Inst::Sw(r1, offset, r2) => Inst::Sw(f(r1), offset, f(r2)),
+      Inst::Sd(r1, offset, r2) => Inst::Sd(f(r1), offset, f(r2)),
+      Inst::Add(rd, rs1, rs2) => Inst::Add(f(rd), f(rs1), f(rs2)),
+      Inst::Addw(rd, rs1, rs2) => Inst::Addw(f(rd), f(rs1), f(rs2)),
+      Inst::Sub(rd, rs1, rs2) => Inst::Sub(f(rd), f(rs1), f(rs2)),
+      Inst::Subw(rd, rs1, rs2) => Inst::Subw(f(rd), f(rs1), f(rs2)),
+      Inst::Slt(rd, rs1, rs2) => Inst::Slt(f(rd), f(rs1), f(rs2)),
+      Inst::Sltu(rd, rs1, rs2) => Inst::Sltu(f(rd), f(rs1), f(rs2)),
+      Inst::Sgt(rd, rs1, rs2) => Inst::Sgt(f(rd), f(rs1), f(rs2)),
+      Inst::Xor(rd, rs1, rs2) => Inst::Xor(f(rd), f(rs1), f(rs2)),
+      Inst::Or(rd, rs1, rs2) => Inst::Or(f(rd), f(rs1), f(rs2)),
+      Inst::And(rd, rs1, rs2) => Inst::And(f(rd), f(rs1), f(rs2)),
+      Inst::Sll(rd, rs1, rs2) => Inst::Sll(f(rd), f(rs1), f(rs2)),
+      Inst::Srl(rd, rs1, rs2) => Inst::Srl(f(rd), f(rs1), f(rs2)),
+      Inst::Sra(rd, rs1, rs2) => Inst::Sra(f(rd), f(rs1), f(rs2)),
+      Inst::Mul(rd, rs1, rs2) => Inst::Mul(f(rd), f(rs1), f(rs2)),
+      Inst::Mulw(rd, rs1, rs2) => Inst::Mulw(f(rd), f(rs1), f(rs2)),
+      Inst::Div(rd, rs1, rs2) => Inst::Div(f(rd), f(rs1), f(rs2)),
+      Inst::Divw(rd, rs1, rs2) => Inst::Divw(f(rd), f(rs1), f(rs2)),
+      Inst::Rem(rd, rs1, rs2) => Inst::Rem(f(rd), f(rs1), f(rs2)),
+      Inst::Remw(rd, rs1, rs2) => Inst::Remw(f(rd), f(rs1), f(rs2)),
+      Inst::Addi(rd, rs, imm) => Inst::Addi(f(rd), f(rs), imm),
+      Inst::Xori(rd, rs, imm) => Inst::Xori(f(rd), f(rs), imm),
+      Inst::Ori(rd, rs, imm) => Inst::Ori(f(rd), f(rs), imm),
+      Inst::Andi(rd, rs, imm) => Inst::Andi(f(rd), f(rs), imm),
+      Inst::Slli(rd, rs, imm) => Inst::Slli(f(rd), f(rs), imm),
+      Inst::Srli(rd, rs, imm) => Inst::Srli(f(rd), f(rs), imm),
+      Inst::Seqz(rd, rs) => Inst::Seqz(f(rd), f(rs)),
+      Inst::Snez(rd, rs) => Inst::Snez(f(rd), f(rs)),
+      Inst::SextW(rd, rs) => Inst::SextW(f(rd), f(rs)),
+      Inst::Mv(rd, rs) => Inst::Mv(f(rd), f(rs)),
+      Inst::Li(rd, imm) => Inst::Li(f(rd), imm),
+      Inst::La(rd, label) => Inst::La(f(rd), label),
+      Inst::Lui(rd, label) => Inst::Lui(f(rd), label),
+      Inst::AddiLo(rd, rs, label) => Inst::AddiLo(f(rd), f(rs), label),
+      Inst::LwLo(rd, label, rs) => Inst::LwLo(f(rd), label, f(rs)),
+      Inst::LdLo(rd, label, rs) => Inst::LdLo(f(rd), label, f(rs)),
+      Inst::SwLo(rs2, label, rs1) => Inst::SwLo(f(rs2), label, f(rs1)),
+      Inst::SdLo(rs2, label, rs1) => Inst::SdLo(f(rs2), label, f(rs1)),
+    }
   }
 }
 
-fn fmt_reg_label(name: &str, reg: Reg, label: &String) -> String {
+fn fmt_reg2<R: fmt::Display>(name: &str, reg1: R, reg2: R) -> String {
+  format!("  {} {}, {}", name, reg1, reg2)
+}
+
+fn fmt_reg3<R: fmt::Display>(name: &str, reg1: R, reg2: R, reg3: R) -> String {
+  format!("  {} {}, {}, {}", name, reg1, reg2, reg3)
+}
+
+fn fmt_reg2_offset<R: fmt::Display>(name: &str, reg1: R, reg2: R, offset: i32) -> String {
+  format!("  {} {}, {}({})", name, reg1, offset, reg2)
+}
+
+fn fmt_reg2_lo<R: fmt::Display>(name: &str, reg1: R, reg2: R, label: &str) -> String {
+  format!("  {} {}, %lo({})({})", name, reg1, label, reg2)
+}
+
+fn fmt_reg2_imm<R: fmt::Display>(name: &str, reg1: R, reg2: R, imm: i32) -> String {
+  format!("  {} {}, {}, {}", name, reg1, reg2, imm)
+}
+
+fn fmt_reg_label<R: fmt::Display>(name: &str, reg: R, label: &String) -> String {
   format!("  {} {}, {}", name, reg, label)
 }
 
@@ -200,24 +534,59 @@ fn fmt_label(name: &str, label: &String) -> String {
   format!("  {} {}", name, label)
 }
 
-fn fmt_reg_imm(name: &str, reg: Reg, imm: i32) -> String {
+fn fmt_reg_imm<R: fmt::Display>(name: &str, reg: R, imm: i32) -> String {
   format!("  {} {}, {}", name, reg, imm)
 }
 
-impl fmt::Display for Inst {
+/// 替换内联汇编模板中的操作数，每行缩进输出
+fn fmt_asm<R: fmt::Display>(template: &str, operands: &[R]) -> String {
+  let mut text = String::new();
+  let mut chars = template.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != '%' || chars.next_if_eq(&'%').is_some() {
+      text.push(c);
+      continue;
+    }
+    let mut index = String::new();
+    while let Some(d) = chars.next_if(char::is_ascii_digit) {
+      index.push(d);
+    }
+    match index.parse::<usize>().ok().and_then(|i| operands.get(i)) {
+      Some(reg) => text += &reg.to_string(),
+      None => text += &format!("%{}", index),
+    }
+  }
+  let lines: Vec<_> = text
+    .lines()
+    .map(|line| format!("  {}", line.trim()))
+    .collect();
+  lines.join("\n")
+}
+
+impl<R: Copy + fmt::Display> fmt::Display for Inst<R> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let inst = match self {
       Inst::Beqz(rs, label) => fmt_reg_label("beqz", *rs, label),
       Inst::Bnez(rs, label) => fmt_reg_label("bnez", *rs, label),
       Inst::J(label) => fmt_label("j", label),
       Inst::Call(label) => fmt_label("call", label),
+      Inst::Asm(template, outputs, inputs) => {
+        let operands: Vec<_> = outputs.iter().chain(inputs).copied().collect();
+        fmt_asm(template, &operands)
+      }
       Inst::Ret => "  ret".into(),
+      Inst::Ecall => "  ecall".into(),
       Inst::Lw(rd, offset, rs) => fmt_reg2_offset("lw", *rd, *rs, *offset),
+      Inst::Ld(rd, offset, rs) => fmt_reg2_offset("ld", *rd, *rs, *offset),
       Inst::Sw(rd, offset, rs) => fmt_reg2_offset("sw", *rd, *rs, *offset),
+      Inst::Sd(rd, offset, rs) => fmt_reg2_offset("sd", *rd, *rs, *offset),
       Inst::Add(rd, rs1, rs2) => fmt_reg3("add", *rd, *rs1, *rs2),
+      Inst::Addw(rd, rs1, rs2) => fmt_reg3("addw", *rd, *rs1, *rs2),
       Inst::Addi(rd, rs, imm) => fmt_reg2_imm("addi", *rd, *rs, *imm),
       Inst::Sub(rd, rs1, rs2) => fmt_reg3("sub", *rd, *rs1, *rs2),
+      Inst::Subw(rd, rs1, rs2) => fmt_reg3("subw", *rd, *rs1, *rs2),
       Inst::Slt(rd, rs1, rs2) => fmt_reg3("slt", *rd, *rs1, *rs2),
+      Inst::Sltu(rd, rs1, rs2) => fmt_reg3("sltu", *rd, *rs1, *rs2),
       Inst::Sgt(rd, rs1, rs2) => fmt_reg3("sgt", *rd, *rs1, *rs2),
       Inst::Seqz(rd, rs) => fmt_reg2("seqz", *rd, *rs),
       Inst::Snez(rd, rs) => fmt_reg2("snez", *rd, *rs),
@@ -230,12 +599,23 @@ impl fmt::Display for Inst {
       Inst::Sll(rd, rs1, rs2) => fmt_reg3("sll", *rd, *rs1, *rs2),
       Inst::Slli(rd, rs1, imm) => fmt_reg2_imm("slli", *rd, *rs1, *imm),
       Inst::Srl(rd, rs1, rs2) => fmt_reg3("srl", *rd, *rs1, *rs2),
+      Inst::Srli(rd, rs1, imm) => fmt_reg2_imm("srli", *rd, *rs1, *imm),
       Inst::Sra(rd, rs1, rs2) => fmt_reg3("sra", *rd, *rs1, *rs2),
       Inst::Mul(rd, rs1, rs2) => fmt_reg3("mul", *rd, *rs1, *rs2),
+      Inst::Mulw(rd, rs1, rs2) => fmt_reg3("mulw", *rd, *rs1, *rs2),
       Inst::Div(rd, rs1, rs2) => fmt_reg3("div", *rd, *rs1, *rs2),
+      Inst::Divw(rd, rs1, rs2) => fmt_reg3("divw", *rd, *rs1, *rs2),
       Inst::Rem(rd, rs1, rs2) => fmt_reg3("rem", *rd, *rs1, *rs2),
+      Inst::Remw(rd, rs1, rs2) => fmt_reg3("remw", *rd, *rs1, *rs2),
+      Inst::SextW(rd, rs) => fmt_reg2("sext.w", *rd, *rs),
       Inst::Li(rd, imm) => fmt_reg_imm("li", *rd, *imm),
       Inst::La(rd, label) => fmt_reg_label("la", *rd, label),
+      Inst::Lui(rd, label) => format!("  lui {}, %hi({})", rd, label),
+      Inst::AddiLo(rd, rs, label) => format!("  addi {}, {}, %lo({})", rd, rs, label),
+      Inst::LwLo(rd, label, rs) => fmt_reg2_lo("lw", *rd, *rs, label),
+      Inst::LdLo(rd, label, rs) => fmt_reg2_lo("ld", *rd, *rs, label),
+      Inst::SwLo(rs2, label, rs1) => fmt_reg2_lo("sw", *rs2, *rs1, label),
+      Inst::SdLo(rs2, label, rs1) => fmt_reg2_lo("sd", *rs2, *rs1, label),
       Inst::Mv(rd, rs) => fmt_reg2("mv", *rd, *rs),
     };
     write!(f, "{}", inst)
@@ -1,23 +1,318 @@
+pub mod compressed;
+pub mod directive;
 pub mod inst;
 pub mod reg;
-pub mod directive;
 
 use std::fmt;
 
-use self::{inst::Inst, directive::Directive};
+use self::{compressed::CInst, directive::Directive, inst::Inst, reg::Reg};
+
+/// 目标架构的寄存器宽度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Xlen {
+  /// RV32，ilp32 调用约定
+  Rv32,
+  /// RV64，lp64 调用约定
+  Rv64,
+}
+
+impl Xlen {
+  /// 寄存器（以及指针）的字节数
+  pub fn bytes(&self) -> usize {
+    match self {
+      Xlen::Rv32 => 4,
+      Xlen::Rv64 => 8,
+    }
+  }
+}
+
+/// 目标支持的标准扩展
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extensions {
+  /// M：整数乘除法
+  pub m: bool,
+  /// A：原子操作
+  pub a: bool,
+  /// F：单精度浮点
+  pub f: bool,
+  /// D：双精度浮点
+  pub d: bool,
+  /// C：压缩指令
+  pub c: bool,
+}
+
+impl Default for Extensions {
+  /// 课程评测环境为 rv32im
+  fn default() -> Self {
+    Self {
+      m: true,
+      a: false,
+      f: false,
+      d: false,
+      c: false,
+    }
+  }
+}
+
+impl fmt::Display for Extensions {
+  /// 规范顺序的扩展字母，如 `imc`
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "i")?;
+    let letters = [
+      (self.m, 'm'),
+      (self.a, 'a'),
+      (self.f, 'f'),
+      (self.d, 'd'),
+      (self.c, 'c'),
+    ];
+    for (enabled, letter) in letters {
+      if enabled {
+        write!(f, "{}", letter)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Extensions {
+  /// 解析形如 `rv32imc`、`rv64gc` 的 `-march` 字符串
+  pub fn parse_march(march: &str) -> Result<(Xlen, Extensions), String> {
+    let march = march.to_ascii_lowercase();
+    let (xlen, rest) = if let Some(rest) = march.strip_prefix("rv32") {
+      (Xlen::Rv32, rest)
+    } else if let Some(rest) = march.strip_prefix("rv64") {
+      (Xlen::Rv64, rest)
+    } else {
+      return Err(format!("invalid -march '{}': expect rv32 or rv64", march));
+    };
+    // 以 `_` 分隔的多字母扩展（如 `_zicsr`）不影响指令选择，忽略
+    let single = rest.split('_').next().unwrap_or("");
+    let mut chars = single.chars();
+    match chars.next() {
+      Some('i') | Some('g') => {}
+      _ => {
+        return Err(format!(
+          "invalid -march '{}': base ISA must be i or g",
+          march
+        ))
+      }
+    }
+    // G 即 IMAFD
+    let g = single.starts_with('g');
+    let mut ext = Extensions {
+      m: g,
+      a: g,
+      f: g,
+      d: g,
+      c: false,
+    };
+    for ch in chars {
+      match ch {
+        'm' => ext.m = true,
+        'a' => ext.a = true,
+        'f' => ext.f = true,
+        'd' => ext.d = true,
+        'c' => ext.c = true,
+        _ => {
+          return Err(format!(
+            "invalid -march '{}': unknown extension '{}'",
+            march, ch
+          ))
+        }
+      }
+    }
+    if ext.d && !ext.f {
+      return Err(format!(
+        "invalid -march '{}': extension 'd' requires 'f'",
+        march
+      ));
+    }
+    Ok((xlen, ext))
+  }
+}
+
+/// 整数调用约定。生成的代码不使用浮点寄存器，即使有 F/D 扩展也按软浮点传参；
+/// IR 中没有浮点类型，ilp32f/ilp32d 等硬浮点约定尚未实现
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Abi {
+  Ilp32,
+  Lp64,
+}
+
+/// 程序的运行环境
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Os {
+  /// Linux 用户态，与 `libsysy` 链接
+  Linux,
+  /// 裸机，由 OpenSBI 引导
+  None,
+}
+
+/// 目标平台：`--target` 给出架构与运行环境，`--march` 给出扩展
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+  pub xlen: Xlen,
+  pub abi: Abi,
+  pub ext: Extensions,
+  pub os: Os,
+}
+
+impl Default for Target {
+  /// 课程评测环境：riscv32 Linux，rv32im
+  fn default() -> Self {
+    Self::new(Xlen::Rv32, Extensions::default(), Os::Linux)
+  }
+}
+
+impl Target {
+  pub fn new(xlen: Xlen, ext: Extensions, os: Os) -> Self {
+    let abi = match xlen {
+      Xlen::Rv32 => Abi::Ilp32,
+      Xlen::Rv64 => Abi::Lp64,
+    };
+    Self { xlen, abi, ext, os }
+  }
+
+  /// 解析 `--target`：`riscv32`、`riscv64`，或形如 `riscv32-unknown-linux-gnu`、
+  /// `riscv64-unknown-none-elf` 的三元组。运行环境省略或为 `elf` 时视为 Linux，只有 `none` 是裸机；
+  /// 返回的运行环境仅在三元组中写明时为 `Some`
+  pub fn parse_triple(triple: &str) -> Result<(Xlen, Option<Os>), String> {
+    let mut parts = triple.split('-');
+    let xlen = match parts.next() {
+      Some("riscv32") => Xlen::Rv32,
+      Some("riscv64") => Xlen::Rv64,
+      _ => {
+        return Err(format!(
+          "invalid --target '{}': expect riscv32 or riscv64",
+          triple
+        ))
+      }
+    };
+    let parts: Vec<_> = parts.collect();
+    let os = match parts.as_slice() {
+      [] => None,
+      // 供应商之后是系统，可能还有环境（`gnu`、`elf`）
+      [_vendor, os, ..] => match *os {
+        "linux" | "elf" => Some(Os::Linux),
+        "none" => Some(Os::None),
+        _ => {
+          return Err(format!(
+            "invalid --target '{}': unknown system '{}'",
+            triple, os
+          ))
+        }
+      },
+      _ => {
+        return Err(format!(
+          "invalid --target '{}': expect <arch>-<vendor>-<sys>",
+          triple
+        ))
+      }
+    };
+    Ok((xlen, os))
+  }
+
+  /// 规范的三元组，如 `riscv32-unknown-linux-gnu`
+  pub fn triple(&self) -> String {
+    let os = match self.os {
+      Os::Linux => "linux-gnu",
+      Os::None => "none-elf",
+    };
+    format!("riscv{}-unknown-{}", self.xlen.bytes() * 8, os)
+  }
+
+  /// 指针的字节数，即 Koopa IR 中指针类型的大小
+  pub fn ptr_size(&self) -> usize {
+    self.xlen.bytes()
+  }
+
+  /// 裸机运行：程序自带 `_start` 入口，库函数以 SysY 实现或经由 SBI 调用
+  pub fn freestanding(&self) -> bool {
+    self.os == Os::None
+  }
+}
+
+impl fmt::Display for Abi {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Abi::Ilp32 => write!(f, "ilp32"),
+      Abi::Lp64 => write!(f, "lp64"),
+    }
+  }
+}
+
+/// 目标处理器（`--mcpu`），决定指令调度与常数乘法展开所依据的开销
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Cpu {
+  #[default]
+  Generic,
+  Rocket,
+  SifiveE31,
+  SifiveU74,
+}
+
+impl Cpu {
+  /// `--mcpu` 可取的名字
+  pub const NAMES: &'static [&'static str] = &["generic", "rocket", "sifive-e31", "sifive-u74"];
+
+  pub fn parse(name: &str) -> Result<Cpu, String> {
+    match name {
+      "generic" => Ok(Cpu::Generic),
+      "rocket" => Ok(Cpu::Rocket),
+      "sifive-e31" => Ok(Cpu::SifiveE31),
+      "sifive-u74" => Ok(Cpu::SifiveU74),
+      _ => Err(format!(
+        "unknown -mcpu '{}': expect generic, rocket, sifive-e31 or sifive-u74",
+        name
+      )),
+    }
+  }
+
+  /// 指令结果可被后续指令使用前的周期数
+  pub fn latency(&self, inst: &Inst) -> u32 {
+    // (访存, 乘法, 除法)
+    let (load, mul, div) = match self {
+      Cpu::Generic => (2, 3, 20),
+      Cpu::Rocket => (3, 4, 33),
+      Cpu::SifiveE31 => (2, 2, 33),
+      Cpu::SifiveU74 => (3, 3, 20),
+    };
+    match inst {
+      Inst::Lw(..) | Inst::Ld(..) | Inst::LwLo(..) | Inst::LdLo(..) => load,
+      Inst::Mul(..) | Inst::Mulw(..) => mul,
+      Inst::Div(..) | Inst::Divw(..) | Inst::Rem(..) | Inst::Remw(..) => div,
+      _ => 1,
+    }
+  }
+
+  /// 有 M 扩展时常数乘法展开为移位加的最多项数：展开后的 `2 * 项数 - 1` 条单周期指令
+  /// 不超过 `mul` 的延迟
+  pub fn max_mul_terms(&self) -> u32 {
+    self
+      .latency(&Inst::Mul(Reg::Zero, Reg::Zero, Reg::Zero))
+      .div_ceil(2)
+  }
+}
 
 #[derive(Debug, Clone)]
-pub enum RiscvItem {
+pub enum RiscvItem<R = Reg> {
   Label(String),
-  Inst(Inst),
+  Inst(Inst<R>),
+  Compressed(CInst),
   Comment(String),
   Directive(Directive),
   Empty,
 }
 
-pub struct Riscv(pub Vec<RiscvItem>);
+pub struct Riscv<R = Reg>(pub Vec<RiscvItem<R>>);
+
+impl<R> Default for Riscv<R> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
 
-impl Riscv {
+impl<R> Riscv<R> {
   pub fn new() -> Self {
     Self(Vec::new())
   }
@@ -25,7 +320,7 @@ impl Riscv {
   pub fn add_label(&mut self, label: String) {
     self.0.push(RiscvItem::Label(label));
   }
-  pub fn add_inst(&mut self, inst: Inst) {
+  pub fn add_inst(&mut self, inst: Inst<R>) {
     self.0.push(RiscvItem::Inst(inst));
   }
   pub fn add_comment(&mut self, comment: String) {
@@ -38,19 +333,20 @@ impl Riscv {
     self.0.push(RiscvItem::Empty);
   }
 
-  pub fn extend(&mut self, other: Riscv) {
+  pub fn extend(&mut self, other: Riscv<R>) {
     self.0.extend(other.0);
   }
-  pub fn append(&mut self, other: &mut Riscv) {
+  pub fn append(&mut self, other: &mut Riscv<R>) {
     self.0.append(&mut other.0);
   }
 }
 
-impl fmt::Display for RiscvItem {
+impl<R: Copy + fmt::Display> fmt::Display for RiscvItem<R> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     let str = match self {
       RiscvItem::Label(label) => format!("{}:", label),
       RiscvItem::Inst(inst) => inst.to_string(),
+      RiscvItem::Compressed(inst) => inst.to_string(),
       RiscvItem::Comment(comment) => format!("# {}", comment),
       RiscvItem::Directive(directive) => directive.to_string(),
       RiscvItem::Empty => "".into(),
@@ -59,7 +355,7 @@ impl fmt::Display for RiscvItem {
   }
 }
 
-impl fmt::Display for Riscv {
+impl<R: Copy + fmt::Display> fmt::Display for Riscv<R> {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     for item in &self.0 {
       write!(f, "{}\n", item)?;
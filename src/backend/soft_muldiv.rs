@@ -0,0 +1,187 @@
+//! 无 M 扩展时的软件乘除法，以及清零内存的辅助例程。
+//!
+//! 辅助例程使用私有的调用约定：操作数放在 `t0`、`t1`，商/积放在 `t2`，余数放在 `t3`；
+//! 除 `t0` ~ `t6` 与 `ra` 外不修改其他寄存器。
+
+use super::from_func::func_end_label;
+use super::riscv::directive::{Directive, SymbolType};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem, Xlen};
+
+pub const MUL: &str = "__sysy_mul";
+pub const DIVMOD: &str = "__sysy_divmod";
+pub const MEMZERO: &str = "__sysy_memzero";
+
+/// 不调用辅助例程、内联展开常数乘法时允许的最多项数
+pub const MAX_INLINE_TERMS: u32 = 4;
+
+/// 把 `rs * imm` 展开为移位加序列（`imm` 的每个 1 位一项，共 `2 * 项数 - 1` 条指令），
+/// 结果存入 `rd`；`rd` 与 `rs` 不能是同一寄存器。
+///
+/// 运算按 XLEN 位进行，RV64 下整数乘法的结果需由调用者再做符号扩展
+pub fn expand_mul<R: Copy + From<Reg>>(rd: R, rs: R, imm: i32, scratch: R) -> Vec<Inst<R>> {
+  let bits = imm as u32;
+  let mut insts = vec![];
+  for k in (0..32).filter(|k| bits & (1 << k) != 0) {
+    if insts.is_empty() {
+      insts.push(Inst::Slli(rd, rs, k));
+    } else {
+      insts.push(Inst::Slli(scratch, rs, k));
+      insts.push(Inst::Add(rd, rd, scratch));
+    }
+  }
+  if insts.is_empty() {
+    insts.push(Inst::Mv(rd, Reg::Zero.into()));
+  }
+  insts
+}
+
+/// 辅助例程只在本目标文件内可见，各目标文件各自带有一份
+pub fn begin_helper(r: &mut Riscv, name: &str) {
+  r.add_directive(Directive::Text);
+  r.add_directive(Directive::Local(name.into()));
+  r.add_directive(Directive::Type(name.into(), SymbolType::Function));
+  r.add_label(name.into());
+}
+
+pub fn end_helper(r: &mut Riscv, name: &str) {
+  let end_label = func_end_label(name);
+  r.add_label(end_label.clone());
+  r.add_directive(Directive::SizeTo(name.into(), end_label));
+  r.add_empty();
+}
+
+fn branch_if_nonneg(insts: &mut Riscv, rs: Reg, label: &str) {
+  insts.add_inst(Inst::Slt(Reg::T6, rs, Reg::Zero));
+  insts.add_inst(Inst::Beqz(Reg::T6, label.into()));
+}
+
+/// `t2 = t0 * t1`，使用 `t3` ~ `t5`
+fn generate_mul(xlen: Xlen) -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, MUL);
+  r.add_inst(Inst::Li(Reg::T2, 0));
+  r.add_inst(Inst::Mv(Reg::T3, Reg::T0));
+  r.add_inst(Inst::Mv(Reg::T4, Reg::T1));
+  r.add_label(format!(".L{}_loop", MUL));
+  r.add_inst(Inst::Beqz(Reg::T4, format!(".L{}_end", MUL)));
+  r.add_inst(Inst::Andi(Reg::T5, Reg::T4, 1));
+  r.add_inst(Inst::Beqz(Reg::T5, format!(".L{}_skip", MUL)));
+  r.add_inst(Inst::Add(Reg::T2, Reg::T2, Reg::T3));
+  r.add_label(format!(".L{}_skip", MUL));
+  r.add_inst(Inst::Slli(Reg::T3, Reg::T3, 1));
+  r.add_inst(Inst::Srli(Reg::T4, Reg::T4, 1));
+  r.add_inst(Inst::J(format!(".L{}_loop", MUL)));
+  r.add_label(format!(".L{}_end", MUL));
+  if xlen == Xlen::Rv64 {
+    r.add_inst(Inst::SextW(Reg::T2, Reg::T2));
+  }
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, MUL);
+  r
+}
+
+/// `t2 = t0 / t1, t3 = t0 % t1`（向零取整），使用 `t4` ~ `t6` 并临时借用 `s1`
+///
+/// 除数为 0 时与 M 扩展的行为一致：商为 -1，余数为被除数。
+fn generate_divmod(xlen: Xlen) -> Riscv {
+  let word = xlen.bytes();
+  let label = |s: &str| format!(".L{}_{}", DIVMOD, s);
+  let mut r = Riscv::new();
+  begin_helper(&mut r, DIVMOD);
+  r.add_inst(Inst::Bnez(Reg::T1, label("start")));
+  r.add_inst(Inst::Li(Reg::T2, -1));
+  r.add_inst(Inst::Mv(Reg::T3, Reg::T0));
+  r.add_inst(Inst::Ret);
+
+  r.add_label(label("start"));
+  r.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, -16));
+  r.add_inst(Inst::store(word, Reg::S1, 0, Reg::Sp));
+  // t4: 商的符号；t5: 余数的符号
+  r.add_inst(Inst::Xor(Reg::T4, Reg::T0, Reg::T1));
+  r.add_inst(Inst::Mv(Reg::T5, Reg::T0));
+  // t3 = |t0|，t1 = |t1|
+  r.add_inst(Inst::Mv(Reg::T3, Reg::T0));
+  branch_if_nonneg(&mut r, Reg::T0, &label("abs_b"));
+  r.add_inst(Inst::Sub(Reg::T3, Reg::Zero, Reg::T0));
+  r.add_label(label("abs_b"));
+  branch_if_nonneg(&mut r, Reg::T1, &label("init"));
+  r.add_inst(Inst::Sub(Reg::T1, Reg::Zero, Reg::T1));
+
+  // 逐位试商：s1 为部分余数，t0 为当前位
+  r.add_label(label("init"));
+  r.add_inst(Inst::Li(Reg::T2, 0));
+  r.add_inst(Inst::Li(Reg::S1, 0));
+  r.add_inst(Inst::Li(Reg::T0, 31));
+  r.add_label(label("loop"));
+  r.add_inst(Inst::Slli(Reg::S1, Reg::S1, 1));
+  r.add_inst(Inst::Srl(Reg::T6, Reg::T3, Reg::T0));
+  r.add_inst(Inst::Andi(Reg::T6, Reg::T6, 1));
+  r.add_inst(Inst::Or(Reg::S1, Reg::S1, Reg::T6));
+  r.add_inst(Inst::Sltu(Reg::T6, Reg::S1, Reg::T1));
+  r.add_inst(Inst::Bnez(Reg::T6, label("next")));
+  r.add_inst(Inst::Sub(Reg::S1, Reg::S1, Reg::T1));
+  r.add_inst(Inst::Li(Reg::T6, 1));
+  r.add_inst(Inst::Sll(Reg::T6, Reg::T6, Reg::T0));
+  r.add_inst(Inst::Or(Reg::T2, Reg::T2, Reg::T6));
+  r.add_label(label("next"));
+  r.add_inst(Inst::Addi(Reg::T0, Reg::T0, -1));
+  r.add_inst(Inst::Slt(Reg::T6, Reg::T0, Reg::Zero));
+  r.add_inst(Inst::Beqz(Reg::T6, label("loop")));
+
+  // 恢复符号
+  branch_if_nonneg(&mut r, Reg::T4, &label("rem"));
+  r.add_inst(Inst::Sub(Reg::T2, Reg::Zero, Reg::T2));
+  r.add_label(label("rem"));
+  r.add_inst(Inst::Mv(Reg::T3, Reg::S1));
+  branch_if_nonneg(&mut r, Reg::T5, &label("end"));
+  r.add_inst(Inst::Sub(Reg::T3, Reg::Zero, Reg::T3));
+  r.add_label(label("end"));
+  if xlen == Xlen::Rv64 {
+    r.add_inst(Inst::SextW(Reg::T2, Reg::T2));
+  }
+  r.add_inst(Inst::load(word, Reg::S1, 0, Reg::Sp));
+  r.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, 16));
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, DIVMOD);
+  r
+}
+
+/// 清零 `t0` 起的 `t1` 字节（4 的倍数）
+fn generate_memzero() -> Riscv {
+  let label = |s: &str| format!(".L{}_{}", MEMZERO, s);
+  let mut r = Riscv::new();
+  begin_helper(&mut r, MEMZERO);
+  r.add_label(label("loop"));
+  r.add_inst(Inst::Beqz(Reg::T1, label("end")));
+  r.add_inst(Inst::Sw(Reg::Zero, 0, Reg::T0));
+  r.add_inst(Inst::Addi(Reg::T0, Reg::T0, 4));
+  r.add_inst(Inst::Addi(Reg::T1, Reg::T1, -4));
+  r.add_inst(Inst::J(label("loop")));
+  r.add_label(label("end"));
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, MEMZERO);
+  r
+}
+
+/// 为程序中调用到的辅助例程生成代码
+pub fn generate_helpers(program: &Riscv, xlen: Xlen) -> Riscv {
+  let calls = |name: &str| {
+    program
+      .0
+      .iter()
+      .any(|item| matches!(item, RiscvItem::Inst(Inst::Call(callee)) if callee == name))
+  };
+  let mut result = Riscv::new();
+  if calls(MUL) {
+    result.extend(generate_mul(xlen));
+  }
+  if calls(DIVMOD) {
+    result.extend(generate_divmod(xlen));
+  }
+  if calls(MEMZERO) {
+    result.extend(generate_memzero());
+  }
+  result
+}
@@ -0,0 +1,75 @@
+//! `--source-map`：输出的汇编各行与源代码行、Koopa IR 指令的对应，以 JSON 写入与汇编并列的
+//! `.map` 文件，供演练场等界面对照高亮。
+//!
+//! 指令选择在每条 IR 指令生成的代码之前插入 `Directive::Origin`，其中是该指令的来源；寄存器
+//! 分配与其后的各遍把它当作普通的伪指令保留（指令调度重排时随指令移动），输出之前由 [`extract`]
+//! 去除，同时得到每一行指令的来源。
+
+use super::riscv::directive::Directive;
+use super::riscv::{Riscv, RiscvItem};
+use crate::serialize::{Json, SCHEMA_VERSION};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Origin {
+  /// 源代码行号；前端生成的指令（如函数末尾补上的返回）没有
+  pub line: Option<usize>,
+  /// Koopa IR 指令的文本
+  pub koopa: String,
+}
+
+/// 汇编各行的来源
+#[derive(Debug, Clone, Default)]
+pub struct AsmSourceMap {
+  /// 汇编中的行号（从 1 开始）与其来源，只含来源已知的指令行，按行号排列
+  pub lines: Vec<(usize, Origin)>,
+}
+
+impl AsmSourceMap {
+  /// `{"version":1,"file":源文件,"lines":[{"asm":汇编行号,"line":源代码行号,"koopa":IR 指令}...]}`，
+  /// 没有源代码行号的 `line` 为 `null`
+  pub fn to_json(&self, file: &str) -> Json {
+    let lines = self.lines.iter().map(|(asm, origin)| {
+      let line = origin
+        .line
+        .map_or(Json::Null, |line| Json::Number(line as i64));
+      Json::Object(vec![
+        ("asm".into(), Json::Number(*asm as i64)),
+        ("line".into(), line),
+        ("koopa".into(), Json::String(origin.koopa.clone())),
+      ])
+    });
+    Json::Object(vec![
+      ("version".into(), Json::Number(SCHEMA_VERSION as i64)),
+      ("file".into(), Json::String(file.into())),
+      ("lines".into(), Json::Array(lines.collect())),
+    ])
+  }
+}
+
+/// 去除汇编中的来源标记，返回去除后的汇编与其中各行的来源。每条指令的来源是同一标号之后、
+/// 其前最近的标记
+pub fn extract(riscv: Riscv) -> (Riscv, AsmSourceMap) {
+  let mut map = AsmSourceMap::default();
+  let mut result = Riscv::new();
+  let mut origin = None;
+  // 下一项所在的行号
+  let mut line = 1;
+  for item in riscv.0 {
+    match item {
+      RiscvItem::Directive(Directive::Origin(marker)) => {
+        origin = Some(marker);
+        continue;
+      }
+      // 标号之后（如函数的序言）到下一个标记之前的指令来源未知
+      RiscvItem::Label(_) => origin = None,
+      _ => {}
+    }
+    if let (RiscvItem::Inst(_) | RiscvItem::Compressed(_), Some(origin)) = (&item, &origin) {
+      map.lines.push((line, Origin::clone(origin)));
+    }
+    // 内联汇编等可能占多行
+    line += item.to_string().matches('\n').count() + 1;
+    result.0.push(item);
+  }
+  (result, map)
+}
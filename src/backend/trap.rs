@@ -0,0 +1,71 @@
+//! 运行时检查（`-fsanitize=`）的诊断例程所用的字符输出与终止例程，遵循标准调用约定：
+//! - 在 Linux 上以 `write` 系统调用写入标准错误，再调用 C 库的 `exit`，使标准输出中尚在缓冲的
+//!   内容也能写出；
+//! - 裸机运行时经由 SBI 输出字符，以系统故障为原因关机。
+
+use super::freestanding::{self, EID_LEGACY_PUTCHAR};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem, Target};
+use super::soft_muldiv::{begin_helper, end_helper};
+
+pub const TRAP_PUTCHAR: &str = "__sysy_trap_putchar";
+pub const TRAP_EXIT: &str = "__sysy_trap_exit";
+
+const STDERR: i32 = 2;
+const SYS_WRITE: i32 = 64;
+
+/// 向标准错误输出字符 `a0`
+fn generate_putchar(target: Target) -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, TRAP_PUTCHAR);
+  if target.freestanding() {
+    r.add_inst(Inst::Li(Reg::A7, EID_LEGACY_PUTCHAR));
+    r.add_inst(Inst::Ecall);
+  } else {
+    // 字符写在栈上，小端序下其地址即是该字节
+    r.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, -16));
+    r.add_inst(Inst::Sw(Reg::A0, 0, Reg::Sp));
+    r.add_inst(Inst::Li(Reg::A0, STDERR));
+    r.add_inst(Inst::Mv(Reg::A1, Reg::Sp));
+    r.add_inst(Inst::Li(Reg::A2, 1));
+    r.add_inst(Inst::Li(Reg::A7, SYS_WRITE));
+    r.add_inst(Inst::Ecall);
+    r.add_inst(Inst::Addi(Reg::Sp, Reg::Sp, 16));
+  }
+  r.add_inst(Inst::Ret);
+  end_helper(&mut r, TRAP_PUTCHAR);
+  r
+}
+
+/// 以 `a0` 为退出码终止程序
+fn generate_exit(target: Target) -> Riscv {
+  let mut r = Riscv::new();
+  begin_helper(&mut r, TRAP_EXIT);
+  if target.freestanding() {
+    r.add_inst(Inst::Li(Reg::A1, 1));
+    freestanding::add_shutdown(&mut r, TRAP_EXIT);
+  } else {
+    r.add_inst(Inst::Call("exit".into()));
+  }
+  end_helper(&mut r, TRAP_EXIT);
+  r
+}
+
+/// 为程序中调用到的例程生成代码
+pub fn generate_helpers(program: &Riscv, target: Target) -> Riscv {
+  let calls = |name: &str| {
+    program
+      .0
+      .iter()
+      .any(|item| matches!(item, RiscvItem::Inst(Inst::Call(callee)) if callee == name))
+  };
+  let mut result = Riscv::new();
+  if calls(TRAP_PUTCHAR) {
+    result.extend(generate_putchar(target));
+  }
+  if calls(TRAP_EXIT) {
+    result.extend(generate_exit(target));
+  }
+  result
+}
@@ -0,0 +1,270 @@
+//! `--verify`：在每一遍之后检查 IR 与汇编的不变量，出错时指明是哪一遍、哪个函数。
+//!
+//! - Koopa IR：基本块以且仅以一条转移指令结尾，操作数已定义，转移的参数个数与各指令的类型相符；
+//! - MIR：虚拟寄存器先定义后使用，且只在一个基本块内、不跨越函数调用存活（寄存器分配的前提）；
+//! - 汇编：转移的目标标签存在，`legalize` 之后每条指令的立即数都可直接编码。
+
+use std::collections::HashSet;
+use std::fmt;
+
+use koopa::ir::dfg::DataFlowGraph;
+use koopa::ir::{FunctionData, Program, Type, TypeKind, Value, ValueKind};
+
+use super::error::VerifyError;
+use super::mir::{Mir, VReg};
+use super::riscv::directive::{Directive, SymbolType};
+use super::riscv::inst::Inst;
+use super::riscv::reg::Reg;
+use super::riscv::{Riscv, RiscvItem};
+use crate::Result;
+
+/// 遍 `irgen` 之后的 Koopa IR
+pub fn verify_ir(ir: &Program) -> Result<()> {
+  for &func in ir.func_layout() {
+    let data = ir.func(func);
+    if data.layout().entry_bb().is_some() {
+      verify_func(ir, data).map_err(|message| VerifyError {
+        pass: "irgen".into(),
+        func: data.name()[1..].into(),
+        message,
+      })?;
+    }
+  }
+  Ok(())
+}
+
+/// 操作数的类型；不存在时为 `None`
+fn value_ty(ir: &Program, dfg: &DataFlowGraph, value: Value) -> Option<Type> {
+  if value.is_global() {
+    let values = ir.borrow_values();
+    values.get(&value).map(|vd| vd.ty().clone())
+  } else {
+    dfg.values().get(&value).map(|vd| vd.ty().clone())
+  }
+}
+
+fn pointer_base(ty: &Type) -> Option<&Type> {
+  match ty.kind() {
+    TypeKind::Pointer(base) => Some(base),
+    _ => None,
+  }
+}
+
+fn verify_func(ir: &Program, data: &FunctionData) -> std::result::Result<(), String> {
+  let dfg = data.dfg();
+  let ret_ty = match data.ty().kind() {
+    TypeKind::Function(_, ret) => ret.clone(),
+    _ => return Err("function has a non-function type".into()),
+  };
+  let name = |value: Value| match dfg.values().get(&value).and_then(|vd| vd.name().clone()) {
+    Some(name) => name,
+    None => format!("{:?}", value),
+  };
+  for (&bb, node) in data.layout().bbs() {
+    let bb_name = dfg.bb(bb).name().clone().unwrap_or_default();
+    let insts: Vec<_> = node.insts().keys().copied().collect();
+    if insts.is_empty() {
+      return Err(format!("basic block {} is empty", bb_name));
+    }
+    let mut defined = HashSet::new();
+    for (i, &inst) in insts.iter().enumerate() {
+      let kind = dfg.value(inst).kind();
+      let ty = dfg.value(inst).ty();
+      let terminator = matches!(
+        kind,
+        ValueKind::Branch(_) | ValueKind::Jump(_) | ValueKind::Return(_)
+      );
+      if terminator != (i + 1 == insts.len()) {
+        return Err(format!(
+          "basic block {} does not end with exactly one terminator",
+          bb_name
+        ));
+      }
+
+      let mut operands = vec![];
+      for value in kind.value_uses() {
+        let ty = value_ty(ir, dfg, value)
+          .ok_or_else(|| format!("instruction {} uses an undefined value", name(inst)))?;
+        // 同一块中的指令须先定义后使用
+        if !value.is_global() && dfg.value(value).kind().is_local_inst() {
+          let parent = data.layout().parent_bb(value);
+          if parent.is_none() || (parent == Some(bb) && !defined.contains(&value)) {
+            return Err(format!(
+              "{} is used by {} before its definition",
+              name(value),
+              name(inst)
+            ));
+          }
+        }
+        operands.push(ty);
+      }
+      for target in kind.bb_uses() {
+        if data.layout().bbs().node(&target).is_none() {
+          return Err(format!(
+            "{} jumps to a block outside the function",
+            name(inst)
+          ));
+        }
+      }
+
+      let i32 = Type::get_i32();
+      let mismatch = || format!("type mismatch in {}", name(inst));
+      match kind {
+        ValueKind::Load(_) if pointer_base(&operands[0]) != Some(ty) => return Err(mismatch()),
+        ValueKind::Store(_) if pointer_base(&operands[1]) != Some(&operands[0]) => {
+          return Err(mismatch());
+        }
+        ValueKind::Binary(_) | ValueKind::GetPtr(_) | ValueKind::GetElemPtr(_) => {
+          let valid = match kind {
+            ValueKind::Binary(_) => operands[0] == i32 && *ty == i32,
+            ValueKind::GetPtr(_) => pointer_base(&operands[0]).is_some(),
+            _ => pointer_base(&operands[0])
+              .is_some_and(|base| matches!(base.kind(), TypeKind::Array(..))),
+          };
+          if !valid || operands[1] != i32 {
+            return Err(mismatch());
+          }
+        }
+        ValueKind::Branch(branch) => {
+          if operands[0] != i32 {
+            return Err(mismatch());
+          }
+          let targets = [
+            (branch.true_bb(), branch.true_args()),
+            (branch.false_bb(), branch.false_args()),
+          ];
+          for (target, args) in targets {
+            if dfg.bb(target).params().len() != args.len() {
+              return Err(format!(
+                "{} passes a wrong number of block arguments",
+                name(inst)
+              ));
+            }
+          }
+        }
+        ValueKind::Jump(jump) if dfg.bb(jump.target()).params().len() != jump.args().len() => {
+          return Err(format!(
+            "{} passes a wrong number of block arguments",
+            name(inst)
+          ));
+        }
+        ValueKind::Call(call) => {
+          let callee = ir
+            .funcs()
+            .get(&call.callee())
+            .ok_or_else(|| format!("{} calls a function that does not exist", name(inst)))?;
+          let valid = match callee.ty().kind() {
+            TypeKind::Function(params, ret) => *params == operands && ret == ty,
+            _ => false,
+          };
+          if !valid {
+            return Err(format!(
+              "{} does not match the type of {}",
+              name(inst),
+              callee.name()
+            ));
+          }
+        }
+        ValueKind::Return(_) => {
+          let valid = match operands.first() {
+            Some(ty) => *ty == ret_ty,
+            None => ret_ty.is_unit(),
+          };
+          if !valid {
+            return Err(format!(
+              "{} does not match the return type {}",
+              name(inst),
+              ret_ty
+            ));
+          }
+        }
+        _ => {}
+      }
+      defined.insert(inst);
+    }
+  }
+  Ok(())
+}
+
+/// 指令选择之后的 MIR
+pub fn verify_mir(mir: &Mir, func: &str) -> Result<()> {
+  let error = |message| VerifyError {
+    pass: "isel".into(),
+    func: func.into(),
+    message,
+  };
+  // 本块中自上一次调用以来定义的虚拟寄存器
+  let mut defined = HashSet::new();
+  for item in &mir.0 {
+    match item {
+      RiscvItem::Label(_) => defined.clear(),
+      RiscvItem::Inst(inst) => {
+        for reg in inst.uses() {
+          if let VReg::Virt(v) = reg {
+            if !defined.contains(&v) {
+              let message = format!(
+                "%v{} is not defined in this block before '{}'",
+                v,
+                inst.to_string().trim()
+              );
+              return Err(error(message).into());
+            }
+          }
+        }
+        if let Inst::Call(_) = inst {
+          defined.clear();
+        }
+        for reg in inst.defs() {
+          if let VReg::Virt(v) = reg {
+            defined.insert(v);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+  verify_asm(mir, "isel", false)
+}
+
+/// 遍 `pass` 之后的汇编。`legalized` 时要求立即数都可直接编码
+pub fn verify_asm<R>(riscv: &Riscv<R>, pass: &str, legalized: bool) -> Result<()>
+where
+  R: Copy + From<Reg> + fmt::Display,
+{
+  let labels: HashSet<_> = riscv
+    .0
+    .iter()
+    .filter_map(|item| match item {
+      RiscvItem::Label(label) => Some(label.as_str()),
+      _ => None,
+    })
+    .collect();
+  let mut func = String::new();
+  for item in &riscv.0 {
+    let inst = match item {
+      RiscvItem::Directive(Directive::Type(name, SymbolType::Function)) => {
+        func = name.clone();
+        continue;
+      }
+      RiscvItem::Inst(inst) => inst,
+      _ => continue,
+    };
+    let text = inst.to_string();
+    let text = text.trim();
+    let message = match inst {
+      Inst::Beqz(_, label) | Inst::Bnez(_, label) | Inst::J(label)
+        if !labels.contains(label.as_str()) =>
+      {
+        format!("'{}' branches to an undefined label", text)
+      }
+      _ if legalized && !inst.imm_fits() => format!("immediate of '{}' is out of range", text),
+      _ => continue,
+    };
+    Err(VerifyError {
+      pass: pass.into(),
+      func: func.clone(),
+      message,
+    })?;
+  }
+  Ok(())
+}
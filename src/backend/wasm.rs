@@ -0,0 +1,623 @@
+//! WebAssembly 后端（`--emit=wasm`/`--emit=wat`）。
+//!
+//! - IR 指令的计算结果与函数参数都是 wasm 局部变量；`alloc` 分配在线性内存中的栈帧里，
+//!   栈指针是唯一的全局变量；
+//! - 全局变量从地址 [`DATA_BASE`] 起排布在线性内存中，非零初始值以数据段给出，其后是栈；
+//! - 局部数组的整体清零（`store zeroinit`）用 `memory.fill`；
+//! - 运行时库函数从 `env` 模块导入，导出 `main` 与 `memory`；
+//! - 基本块在一个循环中以 `br_table` 分派：块结束时写入下一个块的序号并跳回循环头，
+//!   目标恰为下一个块时直接落入；
+//! - 除法按 RISC-V 的语义处理除数为 0 与溢出（wasm 的 `i32.div_s` 在这些情形下陷入）。
+
+mod encode;
+mod text;
+
+use std::collections::HashMap;
+
+use koopa::ir::{BasicBlock, BinaryOp, Function, FunctionData, Type, TypeKind};
+use koopa::ir::{Value, ValueKind};
+
+use super::error::{UnimplementedError, WasmError};
+use crate::frontend::Ir;
+use crate::Result;
+
+/// 导入运行时库函数的模块名
+pub const IMPORT_MODULE: &str = "env";
+/// 全局变量的起始地址，不使用空指针附近的内存
+pub const DATA_BASE: u32 = 1024;
+const STACK_SIZE: u32 = 8 << 20;
+const PAGE_SIZE: u32 = 65536;
+/// 栈指针在全局变量中的下标
+const SP: u32 = 0;
+
+/// 连续零值达到该数量（字）时，局部变量以 `memory.fill` 清零，全局变量的数据段在此断开
+const MIN_ZERO_RUN: usize = 4;
+
+const DIV_HELPER: &str = "__sysy_div";
+const REM_HELPER: &str = "__sysy_rem";
+
+/// 整数运算，操作数与结果均为 `i32`
+#[derive(Debug, Clone, Copy)]
+pub enum Numeric {
+  Eqz,
+  Eq,
+  Ne,
+  LtS,
+  GtS,
+  LeS,
+  GeS,
+  Add,
+  Sub,
+  Mul,
+  DivS,
+  RemS,
+  And,
+  Or,
+  Xor,
+  Shl,
+  ShrS,
+  ShrU,
+}
+
+/// 指令；分支目标以相对深度给出，函数以下标给出
+#[derive(Debug, Clone)]
+pub enum Inst {
+  Unreachable,
+  Block,
+  Loop,
+  If,
+  End,
+  Br(u32),
+  BrIf(u32),
+  BrTable(Vec<u32>, u32),
+  Return,
+  Call(u32),
+  Select,
+  LocalGet(u32),
+  LocalSet(u32),
+  GlobalGet(u32),
+  GlobalSet(u32),
+  /// `i32.load`，参数为偏移量
+  Load(u32),
+  /// `i32.store`，参数为偏移量
+  Store(u32),
+  /// `memory.fill`：以地址、字节的值与字节数为操作数
+  MemoryFill,
+  Const(i32),
+  Numeric(Numeric),
+}
+
+/// 函数签名：参数与返回值（若有）均为 `i32`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Signature {
+  pub params: usize,
+  pub result: bool,
+}
+
+pub struct Import {
+  pub name: String,
+  pub sig: Signature,
+}
+
+pub struct Func {
+  pub name: String,
+  pub sig: Signature,
+  /// 参数之外的局部变量个数
+  pub locals: u32,
+  pub body: Vec<Inst>,
+  pub export: bool,
+}
+
+/// 数据段：线性内存中的初始值
+pub struct Data {
+  pub addr: u32,
+  pub bytes: Vec<u8>,
+}
+
+pub struct Module {
+  /// 函数下标依次为导入函数与 `funcs`
+  pub imports: Vec<Import>,
+  pub funcs: Vec<Func>,
+  /// 线性内存的页数，栈顶即内存末尾
+  pub pages: u32,
+  pub data: Vec<Data>,
+}
+
+impl Module {
+  pub fn stack_top(&self) -> u32 {
+    self.pages * PAGE_SIZE
+  }
+
+  /// 下标为 `index` 的函数名
+  pub fn func_name(&self, index: u32) -> &str {
+    let index = index as usize;
+    match self.imports.get(index) {
+      Some(import) => &import.name,
+      None => &self.funcs[index - self.imports.len()].name,
+    }
+  }
+}
+
+fn signature(fd: &FunctionData) -> Signature {
+  match fd.ty().kind() {
+    TypeKind::Function(params, ret) => Signature {
+      params: params.len(),
+      result: !ret.is_unit(),
+    },
+    _ => unreachable!("function should have function type"),
+  }
+}
+
+/// 把初始值展开为 `(字, 连续的个数)`，相邻的零值合并为一段，`zeroinit` 不逐字展开。`value`
+/// 给出值的种类与类型，以便同样用于全局与局部的值
+fn flatten(value: &dyn Fn(Value) -> (ValueKind, Type), v: Value, runs: &mut Vec<(i32, usize)>) {
+  let (kind, ty) = value(v);
+  let (word, count) = match kind {
+    ValueKind::Integer(i) => (i.value(), 1),
+    ValueKind::Aggregate(agg) => {
+      for &elem in agg.elems() {
+        flatten(value, elem, runs);
+      }
+      return;
+    }
+    _ => (0, ty.size() / 4),
+  };
+  match runs.last_mut() {
+    Some((0, zeros)) if word == 0 => *zeros += count,
+    _ => runs.push((word, count)),
+  }
+}
+
+/// 以地址 `addr` 起的 `words` 为一个数据段，不含末尾的零值
+fn push_data(data: &mut Vec<Data>, addr: u32, words: &[i32]) {
+  let len = words.len() - words.iter().rev().take_while(|&&w| w == 0).count();
+  if len > 0 {
+    let bytes = words[..len].iter().flat_map(|w| w.to_le_bytes()).collect();
+    data.push(Data { addr, bytes });
+  }
+}
+
+/// 除法与取余的辅助函数：除数为 0 时商为 -1、余数为被除数；除数为 -1 时商为被除数取反
+/// （溢出时回绕）、余数为 0
+fn generate_div_helper(name: &str, op: Numeric) -> Func {
+  let (a, b) = (Inst::LocalGet(0), Inst::LocalGet(1));
+  let (on_zero, on_minus_one) = match op {
+    Numeric::DivS => (
+      vec![Inst::Const(-1)],
+      vec![Inst::Const(0), a.clone(), Inst::Numeric(Numeric::Sub)],
+    ),
+    _ => (vec![a.clone()], vec![Inst::Const(0)]),
+  };
+  let mut body = vec![b.clone(), Inst::Numeric(Numeric::Eqz), Inst::If];
+  body.extend(on_zero);
+  body.extend([Inst::Return, Inst::End]);
+  body.extend([
+    b.clone(),
+    Inst::Const(-1),
+    Inst::Numeric(Numeric::Eq),
+    Inst::If,
+  ]);
+  body.extend(on_minus_one);
+  body.extend([Inst::Return, Inst::End]);
+  body.extend([a, b, Inst::Numeric(op)]);
+  Func {
+    name: name.into(),
+    sig: Signature {
+      params: 2,
+      result: true,
+    },
+    locals: 0,
+    body,
+    export: false,
+  }
+}
+
+struct FuncContext<'a> {
+  fd: &'a FunctionData,
+  funcs: &'a HashMap<Function, u32>,
+  helpers: &'a HashMap<&'static str, u32>,
+  globals: &'a HashMap<Value, u32>,
+  bb_index: HashMap<BasicBlock, u32>,
+  /// 各指令结果所在的局部变量
+  locals: HashMap<Value, u32>,
+  /// 保存下一个基本块序号的局部变量
+  next: u32,
+  /// 保存栈帧基址的局部变量
+  fp: u32,
+  frame_size: u32,
+  /// 各 `alloc` 在栈帧中的偏移量
+  allocs: HashMap<Value, u32>,
+  body: Vec<Inst>,
+}
+
+impl<'a> FuncContext<'a> {
+  fn push(&mut self, inst: Inst) {
+    self.body.push(inst);
+  }
+
+  fn numeric(&mut self, op: Numeric) {
+    self.push(Inst::Numeric(op));
+  }
+
+  fn value_kind(&self, value: Value) -> ValueKind {
+    self.fd.dfg().value(value).kind().clone()
+  }
+
+  fn pointee_size(&self, value: Value) -> usize {
+    match self.fd.dfg().value(value).ty().kind() {
+      TypeKind::Pointer(base) => base.size(),
+      _ => panic!("value should have pointer type"),
+    }
+  }
+
+  /// 把值压入操作数栈
+  fn load_value(&mut self, value: Value) -> Result<()> {
+    if value.is_global() {
+      let addr = self.globals[&value];
+      self.push(Inst::Const(addr as i32));
+      return Ok(());
+    }
+    let inst = match self.value_kind(value) {
+      ValueKind::Integer(i) => Inst::Const(i.value()),
+      ValueKind::ZeroInit(_) | ValueKind::Undef(_) => Inst::Const(0),
+      ValueKind::FuncArgRef(arg) => Inst::LocalGet(arg.index() as u32),
+      ValueKind::BlockArgRef(arg) => return Err(UnimplementedError(Box::new(arg)).into()),
+      _ => Inst::LocalGet(self.locals[&value]),
+    };
+    self.push(inst);
+    Ok(())
+  }
+
+  fn save_value(&mut self, value: Value) {
+    self.push(Inst::LocalSet(self.locals[&value]));
+  }
+
+  /// `base` 加上 `index` 个 `step` 字节
+  fn generate_get_ptr(&mut self, base: Value, index: Value, step: usize) -> Result<()> {
+    self.load_value(base)?;
+    match self.value_kind(index) {
+      ValueKind::Integer(i) if i.value() == 0 => return Ok(()),
+      ValueKind::Integer(i) => self.push(Inst::Const(i.value().wrapping_mul(step as i32))),
+      _ => {
+        self.load_value(index)?;
+        self.push(Inst::Const(step as i32));
+        self.numeric(Numeric::Mul);
+      }
+    }
+    self.numeric(Numeric::Add);
+    Ok(())
+  }
+
+  fn generate_epilogue(&mut self) {
+    if self.frame_size > 0 {
+      self.push(Inst::LocalGet(self.fp));
+      self.push(Inst::Const(self.frame_size as i32));
+      self.numeric(Numeric::Add);
+      self.push(Inst::GlobalSet(SP));
+    }
+  }
+
+  /// 生成第 `pos` 个基本块中的指令；`depth` 为分派循环的相对深度
+  fn generate_inst(&mut self, value: Value, pos: u32, depth: u32) -> Result<()> {
+    let fallthrough = pos + 1;
+    match self.value_kind(value) {
+      ValueKind::Alloc(_) => {
+        self.push(Inst::LocalGet(self.fp));
+        let offset = self.allocs[&value];
+        if offset > 0 {
+          self.push(Inst::Const(offset as i32));
+          self.numeric(Numeric::Add);
+        }
+        self.save_value(value);
+      }
+      ValueKind::Load(load) => {
+        self.load_value(load.src())?;
+        self.push(Inst::Load(0));
+        self.save_value(value);
+      }
+      ValueKind::Store(store) => match self.value_kind(store.value()) {
+        ValueKind::Aggregate(_) | ValueKind::ZeroInit(_) => {
+          let dfg = self.fd.dfg();
+          let local = |v: Value| (dfg.value(v).kind().clone(), dfg.value(v).ty().clone());
+          let mut runs = vec![];
+          flatten(&local, store.value(), &mut runs);
+          let mut offset = 0;
+          for (word, count) in runs {
+            let bytes = 4 * count as u32;
+            if word == 0 && count >= MIN_ZERO_RUN {
+              self.load_value(store.dest())?;
+              if offset > 0 {
+                self.push(Inst::Const(offset as i32));
+                self.numeric(Numeric::Add);
+              }
+              self.push(Inst::Const(0));
+              self.push(Inst::Const(bytes as i32));
+              self.push(Inst::MemoryFill);
+            } else {
+              for i in 0..count as u32 {
+                self.load_value(store.dest())?;
+                self.push(Inst::Const(word));
+                self.push(Inst::Store(offset + 4 * i));
+              }
+            }
+            offset += bytes;
+          }
+        }
+        _ => {
+          self.load_value(store.dest())?;
+          self.load_value(store.value())?;
+          self.push(Inst::Store(0));
+        }
+      },
+      ValueKind::GetPtr(ptr) => {
+        let step = self.pointee_size(value);
+        self.generate_get_ptr(ptr.src(), ptr.index(), step)?;
+        self.save_value(value);
+      }
+      ValueKind::GetElemPtr(elem_ptr) => {
+        let step = self.pointee_size(value);
+        self.generate_get_ptr(elem_ptr.src(), elem_ptr.index(), step)?;
+        self.save_value(value);
+      }
+      ValueKind::Binary(binary) => {
+        self.load_value(binary.lhs())?;
+        self.load_value(binary.rhs())?;
+        // 除数为 0、-1 以外的常数时不会陷入
+        let safe = match self.value_kind(binary.rhs()) {
+          ValueKind::Integer(i) => i.value() != 0 && i.value() != -1,
+          _ => false,
+        };
+        let op = match binary.op() {
+          BinaryOp::NotEq => Numeric::Ne,
+          BinaryOp::Eq => Numeric::Eq,
+          BinaryOp::Gt => Numeric::GtS,
+          BinaryOp::Lt => Numeric::LtS,
+          BinaryOp::Ge => Numeric::GeS,
+          BinaryOp::Le => Numeric::LeS,
+          BinaryOp::Add => Numeric::Add,
+          BinaryOp::Sub => Numeric::Sub,
+          BinaryOp::Mul => Numeric::Mul,
+          BinaryOp::Div if safe => Numeric::DivS,
+          BinaryOp::Mod if safe => Numeric::RemS,
+          BinaryOp::Div => {
+            self.push(Inst::Call(self.helpers[DIV_HELPER]));
+            self.save_value(value);
+            return Ok(());
+          }
+          BinaryOp::Mod => {
+            self.push(Inst::Call(self.helpers[REM_HELPER]));
+            self.save_value(value);
+            return Ok(());
+          }
+          BinaryOp::And => Numeric::And,
+          BinaryOp::Or => Numeric::Or,
+          BinaryOp::Xor => Numeric::Xor,
+          BinaryOp::Shl => Numeric::Shl,
+          BinaryOp::Shr => Numeric::ShrU,
+          BinaryOp::Sar => Numeric::ShrS,
+        };
+        self.numeric(op);
+        self.save_value(value);
+      }
+      ValueKind::Call(call) => {
+        for &arg in call.args() {
+          self.load_value(arg)?;
+        }
+        self.push(Inst::Call(self.funcs[&call.callee()]));
+        if !self.fd.dfg().value(value).ty().is_unit() {
+          self.save_value(value);
+        }
+      }
+      ValueKind::Jump(jump) => {
+        let target = self.bb_index[&jump.target()];
+        if target != fallthrough {
+          self.push(Inst::Const(target as i32));
+          self.push(Inst::LocalSet(self.next));
+          self.push(Inst::Br(depth));
+        }
+      }
+      ValueKind::Branch(branch) => {
+        let t = self.bb_index[&branch.true_bb()];
+        let f = self.bb_index[&branch.false_bb()];
+        if f == fallthrough || t == fallthrough {
+          // 只在跳走时才用到 `next`，因此可以先写入
+          let (target, negate) = if f == fallthrough {
+            (t, false)
+          } else {
+            (f, true)
+          };
+          self.push(Inst::Const(target as i32));
+          self.push(Inst::LocalSet(self.next));
+          self.load_value(branch.cond())?;
+          if negate {
+            self.numeric(Numeric::Eqz);
+          }
+          self.push(Inst::BrIf(depth));
+        } else {
+          self.push(Inst::Const(t as i32));
+          self.push(Inst::Const(f as i32));
+          self.load_value(branch.cond())?;
+          self.push(Inst::Select);
+          self.push(Inst::LocalSet(self.next));
+          self.push(Inst::Br(depth));
+        }
+      }
+      ValueKind::Return(ret) => {
+        if let Some(value) = ret.value() {
+          self.load_value(value)?;
+        }
+        self.generate_epilogue();
+        self.push(Inst::Return);
+      }
+      x => return Err(UnimplementedError(Box::new(x)).into()),
+    }
+    Ok(())
+  }
+}
+
+fn generate_func(
+  fd: &FunctionData,
+  funcs: &HashMap<Function, u32>,
+  helpers: &HashMap<&'static str, u32>,
+  globals: &HashMap<Value, u32>,
+) -> Result<Func> {
+  let sig = signature(fd);
+  let bbs: Vec<_> = fd.layout().bbs().iter().collect();
+  let bb_index = bbs
+    .iter()
+    .enumerate()
+    .map(|(i, (&bb, _))| (bb, i as u32))
+    .collect();
+
+  let next = sig.params as u32;
+  let fp = next + 1;
+  let mut locals = HashMap::new();
+  let mut allocs = HashMap::new();
+  let mut frame_size = 0;
+  for (_, node) in &bbs {
+    for &v in node.insts().keys() {
+      let vd = fd.dfg().value(v);
+      if let ValueKind::Alloc(_) = vd.kind() {
+        allocs.insert(v, frame_size);
+        let size = match vd.ty().kind() {
+          TypeKind::Pointer(base) => base.size() as u32,
+          _ => panic!("alloc should have pointer type"),
+        };
+        frame_size += (size + 3) & !3;
+      }
+      if !vd.ty().is_unit() {
+        locals.insert(v, fp + 1 + locals.len() as u32);
+      }
+    }
+  }
+  let frame_size = (frame_size + 15) & !15;
+
+  let mut context = FuncContext {
+    fd,
+    funcs,
+    helpers,
+    globals,
+    bb_index,
+    next,
+    fp,
+    frame_size,
+    allocs,
+    locals,
+    body: vec![],
+  };
+  if frame_size > 0 {
+    context.push(Inst::GlobalGet(SP));
+    context.push(Inst::Const(frame_size as i32));
+    context.numeric(Numeric::Sub);
+    context.push(Inst::LocalSet(fp));
+    context.push(Inst::LocalGet(fp));
+    context.push(Inst::GlobalSet(SP));
+  }
+
+  // loop { block { ... block { br_table } 块 0 } 块 1 ... } 块 n-1 }
+  let n = bbs.len() as u32;
+  context.push(Inst::Loop);
+  for _ in 0..n {
+    context.push(Inst::Block);
+  }
+  context.push(Inst::LocalGet(next));
+  context.push(Inst::BrTable((0..n).collect(), n - 1));
+  for (pos, (_, node)) in bbs.iter().enumerate() {
+    let pos = pos as u32;
+    context.push(Inst::End);
+    for &v in node.insts().keys() {
+      context.generate_inst(v, pos, n - 1 - pos)?;
+    }
+  }
+  context.push(Inst::End);
+  context.push(Inst::Unreachable);
+
+  Ok(Func {
+    name: fd.name()[1..].into(),
+    sig,
+    locals: fp + 1 + context.locals.len() as u32 - sig.params as u32,
+    body: context.body,
+    export: fd.name() == "@main",
+  })
+}
+
+pub fn generate(ir: &Ir) -> Result<Module> {
+  if !ir.annotations.inline_asm.is_empty() {
+    let message = "inline assembly is not supported by the WebAssembly backend";
+    return Err(WasmError(message.into()).into());
+  }
+  Type::set_ptr_size(4);
+
+  // 全局变量
+  let mut globals = HashMap::new();
+  let mut data = vec![];
+  let mut addr = DATA_BASE;
+  for &v in ir.inst_layout() {
+    let vd = ir.borrow_value(v);
+    let init = match vd.kind() {
+      ValueKind::GlobalAlloc(alloc) => alloc.init(),
+      _ => unreachable!("global instruction should be alloc"),
+    };
+    let size = match vd.ty().kind() {
+      TypeKind::Pointer(base) => base.size() as u32,
+      _ => panic!("alloc should have pointer type"),
+    };
+    globals.insert(v, addr);
+    let global = |v: Value| {
+      let vd = ir.borrow_value(v);
+      (vd.kind().clone(), vd.ty().clone())
+    };
+    let mut runs = vec![];
+    flatten(&global, init, &mut runs);
+    // 线性内存初始为零，较长的零值连续段不放入数据段
+    let mut start = addr;
+    let mut words: Vec<i32> = vec![];
+    for (word, count) in runs {
+      if word == 0 && count >= MIN_ZERO_RUN {
+        push_data(&mut data, start, &words);
+        start += 4 * (words.len() + count) as u32;
+        words.clear();
+      } else {
+        words.extend(std::iter::repeat_n(word, count));
+      }
+    }
+    push_data(&mut data, start, &words);
+    addr += (size + 3) & !3;
+  }
+  let pages = (addr + STACK_SIZE).div_ceil(PAGE_SIZE);
+
+  // 函数下标：先是导入的声明，然后是定义与辅助函数
+  let (decls, defs): (Vec<Function>, Vec<Function>) = ir
+    .func_layout()
+    .iter()
+    .partition(|&&f| ir.func(f).layout().entry_bb().is_none());
+  let imports: Vec<_> = decls
+    .iter()
+    .map(|&f| Import {
+      name: ir.func(f).name()[1..].into(),
+      sig: signature(ir.func(f)),
+    })
+    .collect();
+  let funcs: HashMap<_, _> = decls
+    .iter()
+    .chain(&defs)
+    .enumerate()
+    .map(|(i, &f)| (f, i as u32))
+    .collect();
+  let helper_base = funcs.len() as u32;
+  let helpers = HashMap::from([(DIV_HELPER, helper_base), (REM_HELPER, helper_base + 1)]);
+
+  let mut module_funcs = vec![];
+  for &f in &defs {
+    module_funcs.push(generate_func(ir.func(f), &funcs, &helpers, &globals)?);
+  }
+  module_funcs.push(generate_div_helper(DIV_HELPER, Numeric::DivS));
+  module_funcs.push(generate_div_helper(REM_HELPER, Numeric::RemS));
+
+  Ok(Module {
+    imports,
+    funcs: module_funcs,
+    pages,
+    data,
+  })
+}
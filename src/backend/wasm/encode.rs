@@ -0,0 +1,247 @@
+// https://webassembly.github.io/spec/core/binary/index.html
+
+use std::collections::HashMap;
+
+use super::{Inst, Module, Numeric, Signature, IMPORT_MODULE};
+
+const SECTION_TYPE: u8 = 1;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_FUNCTION: u8 = 3;
+const SECTION_MEMORY: u8 = 5;
+const SECTION_GLOBAL: u8 = 6;
+const SECTION_EXPORT: u8 = 7;
+const SECTION_CODE: u8 = 10;
+const SECTION_DATA: u8 = 11;
+
+const TYPE_I32: u8 = 0x7f;
+const TYPE_FUNC: u8 = 0x60;
+const BLOCK_EMPTY: u8 = 0x40;
+const EXTERN_FUNC: u8 = 0x00;
+const EXTERN_MEMORY: u8 = 0x02;
+const OP_END: u8 = 0x0b;
+const OP_I32_CONST: u8 = 0x41;
+/// `i32.load`/`i32.store` 的对齐（2 的幂次）
+const ALIGN_WORD: u32 = 2;
+
+fn uleb(out: &mut Vec<u8>, mut value: u32) {
+  loop {
+    let byte = (value & 0x7f) as u8;
+    value >>= 7;
+    if value == 0 {
+      out.push(byte);
+      return;
+    }
+    out.push(byte | 0x80);
+  }
+}
+
+fn sleb(out: &mut Vec<u8>, mut value: i32) {
+  loop {
+    let byte = (value & 0x7f) as u8;
+    value >>= 7;
+    if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+      out.push(byte);
+      return;
+    }
+    out.push(byte | 0x80);
+  }
+}
+
+fn name(out: &mut Vec<u8>, name: &str) {
+  uleb(out, name.len() as u32);
+  out.extend(name.as_bytes());
+}
+
+fn section(out: &mut Vec<u8>, id: u8, count: usize, content: Vec<u8>) {
+  out.push(id);
+  let mut body = vec![];
+  uleb(&mut body, count as u32);
+  body.extend(content);
+  uleb(out, body.len() as u32);
+  out.extend(body);
+}
+
+fn numeric_opcode(op: Numeric) -> u8 {
+  match op {
+    Numeric::Eqz => 0x45,
+    Numeric::Eq => 0x46,
+    Numeric::Ne => 0x47,
+    Numeric::LtS => 0x48,
+    Numeric::GtS => 0x4a,
+    Numeric::LeS => 0x4c,
+    Numeric::GeS => 0x4e,
+    Numeric::Add => 0x6a,
+    Numeric::Sub => 0x6b,
+    Numeric::Mul => 0x6c,
+    Numeric::DivS => 0x6d,
+    Numeric::RemS => 0x6f,
+    Numeric::And => 0x71,
+    Numeric::Or => 0x72,
+    Numeric::Xor => 0x73,
+    Numeric::Shl => 0x74,
+    Numeric::ShrS => 0x75,
+    Numeric::ShrU => 0x76,
+  }
+}
+
+fn inst(out: &mut Vec<u8>, inst: &Inst) {
+  match inst {
+    Inst::Unreachable => out.push(0x00),
+    Inst::Block => out.extend([0x02, BLOCK_EMPTY]),
+    Inst::Loop => out.extend([0x03, BLOCK_EMPTY]),
+    Inst::If => out.extend([0x04, BLOCK_EMPTY]),
+    Inst::End => out.push(OP_END),
+    Inst::Br(depth) => {
+      out.push(0x0c);
+      uleb(out, *depth);
+    }
+    Inst::BrIf(depth) => {
+      out.push(0x0d);
+      uleb(out, *depth);
+    }
+    Inst::BrTable(targets, default) => {
+      out.push(0x0e);
+      uleb(out, targets.len() as u32);
+      for &target in targets {
+        uleb(out, target);
+      }
+      uleb(out, *default);
+    }
+    Inst::Return => out.push(0x0f),
+    Inst::Call(func) => {
+      out.push(0x10);
+      uleb(out, *func);
+    }
+    Inst::Select => out.push(0x1b),
+    Inst::LocalGet(local) => {
+      out.push(0x20);
+      uleb(out, *local);
+    }
+    Inst::LocalSet(local) => {
+      out.push(0x21);
+      uleb(out, *local);
+    }
+    Inst::GlobalGet(global) => {
+      out.push(0x23);
+      uleb(out, *global);
+    }
+    Inst::GlobalSet(global) => {
+      out.push(0x24);
+      uleb(out, *global);
+    }
+    Inst::Load(offset) | Inst::Store(offset) => {
+      out.push(if let Inst::Load(_) = inst { 0x28 } else { 0x36 });
+      uleb(out, ALIGN_WORD);
+      uleb(out, *offset);
+    }
+    Inst::MemoryFill => out.extend([0xfc, 0x0b, 0x00]),
+    Inst::Const(value) => {
+      out.push(OP_I32_CONST);
+      sleb(out, *value);
+    }
+    Inst::Numeric(op) => out.push(numeric_opcode(*op)),
+  }
+}
+
+impl Module {
+  /// 编码为二进制格式（`.wasm`）
+  pub fn encode(&self) -> Vec<u8> {
+    let mut out = b"\0asm".to_vec();
+    out.extend(1u32.to_le_bytes());
+
+    let sigs = self
+      .imports
+      .iter()
+      .map(|i| i.sig)
+      .chain(self.funcs.iter().map(|f| f.sig));
+    let mut types: Vec<Signature> = vec![];
+    let mut type_index = HashMap::new();
+    for sig in sigs {
+      type_index.entry(sig).or_insert_with(|| {
+        types.push(sig);
+        types.len() as u32 - 1
+      });
+    }
+    let mut content = vec![];
+    for sig in &types {
+      content.push(TYPE_FUNC);
+      uleb(&mut content, sig.params as u32);
+      content.extend(std::iter::repeat_n(TYPE_I32, sig.params));
+      uleb(&mut content, sig.result as u32);
+      if sig.result {
+        content.push(TYPE_I32);
+      }
+    }
+    section(&mut out, SECTION_TYPE, types.len(), content);
+
+    let mut content = vec![];
+    for import in &self.imports {
+      name(&mut content, IMPORT_MODULE);
+      name(&mut content, &import.name);
+      content.push(EXTERN_FUNC);
+      uleb(&mut content, type_index[&import.sig]);
+    }
+    section(&mut out, SECTION_IMPORT, self.imports.len(), content);
+
+    let mut content = vec![];
+    for func in &self.funcs {
+      uleb(&mut content, type_index[&func.sig]);
+    }
+    section(&mut out, SECTION_FUNCTION, self.funcs.len(), content);
+
+    // 只有下限
+    let mut content = vec![0x00];
+    uleb(&mut content, self.pages);
+    section(&mut out, SECTION_MEMORY, 1, content);
+
+    let mut content = vec![TYPE_I32, 0x01, OP_I32_CONST];
+    sleb(&mut content, self.stack_top() as i32);
+    content.push(OP_END);
+    section(&mut out, SECTION_GLOBAL, 1, content);
+
+    let mut content = vec![];
+    name(&mut content, "memory");
+    content.extend([EXTERN_MEMORY, 0]);
+    let mut count = 1;
+    for (i, func) in self.funcs.iter().enumerate() {
+      if func.export {
+        name(&mut content, &func.name);
+        content.push(EXTERN_FUNC);
+        uleb(&mut content, (self.imports.len() + i) as u32);
+        count += 1;
+      }
+    }
+    section(&mut out, SECTION_EXPORT, count, content);
+
+    let mut content = vec![];
+    for func in &self.funcs {
+      let mut body = vec![];
+      if func.locals > 0 {
+        uleb(&mut body, 1);
+        uleb(&mut body, func.locals);
+        body.push(TYPE_I32);
+      } else {
+        uleb(&mut body, 0);
+      }
+      for i in &func.body {
+        inst(&mut body, i);
+      }
+      body.push(OP_END);
+      uleb(&mut content, body.len() as u32);
+      content.extend(body);
+    }
+    section(&mut out, SECTION_CODE, self.funcs.len(), content);
+
+    let mut content = vec![];
+    for data in &self.data {
+      content.push(0x00);
+      content.push(OP_I32_CONST);
+      sleb(&mut content, data.addr as i32);
+      content.push(OP_END);
+      uleb(&mut content, data.bytes.len() as u32);
+      content.extend(&data.bytes);
+    }
+    section(&mut out, SECTION_DATA, self.data.len(), content);
+    out
+  }
+}
@@ -0,0 +1,127 @@
+// https://webassembly.github.io/spec/core/text/index.html
+
+use std::fmt;
+
+use super::{Inst, Module, Numeric, Signature, IMPORT_MODULE};
+
+fn numeric_name(op: Numeric) -> &'static str {
+  match op {
+    Numeric::Eqz => "i32.eqz",
+    Numeric::Eq => "i32.eq",
+    Numeric::Ne => "i32.ne",
+    Numeric::LtS => "i32.lt_s",
+    Numeric::GtS => "i32.gt_s",
+    Numeric::LeS => "i32.le_s",
+    Numeric::GeS => "i32.ge_s",
+    Numeric::Add => "i32.add",
+    Numeric::Sub => "i32.sub",
+    Numeric::Mul => "i32.mul",
+    Numeric::DivS => "i32.div_s",
+    Numeric::RemS => "i32.rem_s",
+    Numeric::And => "i32.and",
+    Numeric::Or => "i32.or",
+    Numeric::Xor => "i32.xor",
+    Numeric::Shl => "i32.shl",
+    Numeric::ShrS => "i32.shr_s",
+    Numeric::ShrU => "i32.shr_u",
+  }
+}
+
+fn signature(sig: Signature) -> String {
+  let mut text = String::new();
+  if sig.params > 0 {
+    text += " (param";
+    text += &" i32".repeat(sig.params);
+    text += ")";
+  }
+  if sig.result {
+    text += " (result i32)";
+  }
+  text
+}
+
+fn memarg(offset: u32) -> String {
+  if offset == 0 {
+    String::new()
+  } else {
+    format!(" offset={}", offset)
+  }
+}
+
+impl Module {
+  fn fmt_inst(&self, inst: &Inst) -> String {
+    match inst {
+      Inst::Unreachable => "unreachable".into(),
+      Inst::Block => "block".into(),
+      Inst::Loop => "loop".into(),
+      Inst::If => "if".into(),
+      Inst::End => "end".into(),
+      Inst::Br(depth) => format!("br {}", depth),
+      Inst::BrIf(depth) => format!("br_if {}", depth),
+      Inst::BrTable(targets, default) => {
+        let targets: Vec<_> = targets.iter().map(|t| t.to_string()).collect();
+        format!("br_table {} {}", targets.join(" "), default)
+      }
+      Inst::Return => "return".into(),
+      Inst::Call(func) => format!("call ${}", self.func_name(*func)),
+      Inst::Select => "select".into(),
+      Inst::LocalGet(local) => format!("local.get {}", local),
+      Inst::LocalSet(local) => format!("local.set {}", local),
+      Inst::GlobalGet(_) => "global.get $sp".into(),
+      Inst::GlobalSet(_) => "global.set $sp".into(),
+      Inst::Load(offset) => format!("i32.load{}", memarg(*offset)),
+      Inst::Store(offset) => format!("i32.store{}", memarg(*offset)),
+      Inst::MemoryFill => "memory.fill".into(),
+      Inst::Const(value) => format!("i32.const {}", value),
+      Inst::Numeric(op) => numeric_name(*op).into(),
+    }
+  }
+}
+
+/// 文本格式（`.wat`）
+impl fmt::Display for Module {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    writeln!(f, "(module")?;
+    for import in &self.imports {
+      let sig = signature(import.sig);
+      let (module, name) = (IMPORT_MODULE, &import.name);
+      writeln!(
+        f,
+        "  (import \"{}\" \"{}\" (func ${}{}))",
+        module, name, name, sig
+      )?;
+    }
+    writeln!(f, "  (memory (export \"memory\") {})", self.pages)?;
+    writeln!(
+      f,
+      "  (global $sp (mut i32) (i32.const {}))",
+      self.stack_top()
+    )?;
+    for data in &self.data {
+      let bytes: String = data.bytes.iter().map(|b| format!("\\{:02x}", b)).collect();
+      writeln!(f, "  (data (i32.const {}) \"{}\")", data.addr, bytes)?;
+    }
+    for func in &self.funcs {
+      write!(f, "  (func ${}", func.name)?;
+      if func.export {
+        write!(f, " (export \"{}\")", func.name)?;
+      }
+      writeln!(f, "{}", signature(func.sig))?;
+      if func.locals > 0 {
+        writeln!(f, "    (local{})", " i32".repeat(func.locals as usize))?;
+      }
+      let mut depth = 2;
+      for inst in &func.body {
+        if let Inst::End = inst {
+          depth -= 1;
+        }
+        writeln!(f, "{}{}", "  ".repeat(depth), self.fmt_inst(inst))?;
+        if let Inst::Block | Inst::Loop | Inst::If = inst {
+          depth += 1;
+        }
+      }
+      writeln!(f, "  )")?;
+    }
+    writeln!(f, ")")
+  }
+}
@@ -0,0 +1,298 @@
+//! `sysyc bench <dir>`：性能测试，即课程公开测例中性能测试的部分。
+//!
+//! 每个有 `.out` 的 `.sy` 文件编译、链接后在模拟器中运行（`--ssh` 时复制到另一台机器上运行），
+//! 输出须与 `.out` 相同；用时取运行时库在程序退出时报告的 `starttime`/`stoptime` 之间的总时间，
+//! 没有调用时是整个运行的时间。`--baseline` 另以该 C 编译器在 -O2 下编译同一程序作为对照，
+//! `--history` 则与前一次运行保存的用时比较，随后保存本次的用时。
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use crate::argparse::ParsedArgs;
+use crate::{compile_object, link, testsuite, Result, Timings};
+
+/// 作为 C 程序编译时，在程序之前给出运行时库函数的声明，与 `libsysy` 的 `sylib.h` 相同
+const PRELUDE: &str = "\
+int getint(void);
+int getch(void);
+int getarray(int a[]);
+void putint(int n);
+void putch(int c);
+void putarray(int n, int a[]);
+void starttime(void);
+void stoptime(void);
+";
+
+/// `--history` 文件的第一行
+const HISTORY_HEADER: &str = "# sysyc bench: microseconds per case";
+
+/// 运行时库在程序退出时向标准错误输出总的用时，如 `TOTAL: 0H-0M-1S-234567us`，返回微秒数
+fn parse_total(stderr: &str) -> Option<u64> {
+  let line = stderr
+    .lines()
+    .rev()
+    .find_map(|line| line.trim().strip_prefix("TOTAL: "))?;
+  let parts: Vec<_> = line.split('-').collect();
+  let units = [
+    ("H", 3_600_000_000),
+    ("M", 60_000_000),
+    ("S", 1_000_000),
+    ("us", 1),
+  ];
+  if parts.len() != units.len() {
+    return None;
+  }
+  let mut us = 0;
+  for (part, (unit, scale)) in parts.into_iter().zip(units) {
+    let n: u64 = part.strip_suffix(unit)?.parse().ok()?;
+    us += n * scale;
+  }
+  Some(us)
+}
+
+/// 运行命令，失败时返回错误
+fn command(program: &str, args: &[&str]) -> Result<()> {
+  let status = Command::new(program)
+    .args(args)
+    .status()
+    .map_err(|e| format!("cannot run '{}': {}", program, e))?;
+  match status.success() {
+    true => Ok(()),
+    false => Err(format!("'{} {}' failed: {}", program, args.join(" "), status).into()),
+  }
+}
+
+/// 以 `stdin` 为输入运行可执行文件 `exe`，返回评测格式的输出与用时（微秒）
+fn execute(args: &ParsedArgs, exe: &str, stdin: &str) -> Result<(Vec<u8>, u64)> {
+  let start = Instant::now();
+  let (stdout, stderr, code) = link::run_with_stderr(exe, Some(stdin), args)?;
+  // 没有调用 `starttime`/`stoptime` 时含模拟器的启动
+  let wall = start.elapsed().as_micros() as u64;
+  let us = parse_total(&String::from_utf8_lossy(&stderr)).unwrap_or(wall);
+  Ok((link::judge_output(stdout, code), us))
+}
+
+/// `--ssh`：复制到远端的 `/tmp` 下，经 ssh 运行后删除；标准输入由 ssh 转发
+fn execute_remote(args: &ParsedArgs, host: &str, exe: &str, stdin: &str) -> Result<(Vec<u8>, u64)> {
+  let name = Path::new(exe).file_name().unwrap().to_string_lossy();
+  let remote = format!("/tmp/{}", name);
+  command("scp", &["-q", exe, &format!("{}:{}", host, remote)])?;
+  let args = ParsedArgs {
+    runner: Some(format!("ssh {}", host)),
+    ..args.clone()
+  };
+  let result = execute(&args, &remote, stdin);
+  command("ssh", &[host, "rm", "-f", &remote])?;
+  result
+}
+
+/// 以 C 编译器 `cc` 在 -O2 下编译 `source`，与 `libsysy` 链接为 `exe`
+fn build_baseline(args: &ParsedArgs, cc: &str, source: &str, exe: &str) -> Result<()> {
+  let mut words = cc.split_whitespace();
+  let program = words.next().ok_or("empty baseline compiler command")?;
+  let mut command = Command::new(program);
+  // 命令中的选项在 `-O2` 之后，可以覆盖之
+  command.arg("-O2").args(words);
+  command.args(["-w", "-x", "c", "-", "-o", exe]);
+  for dir in &args.lib_dirs {
+    command.arg(format!("-L{}", dir));
+  }
+  if let Some(dir) = link::default_lib_dir(args.target.xlen) {
+    command.arg("-L").arg(dir);
+  }
+  let mut child = command
+    .arg("-lsysy")
+    .stdin(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("cannot run baseline compiler '{}': {}", program, e))?;
+  let mut stdin = child.stdin.take().unwrap();
+  stdin.write_all(PRELUDE.as_bytes())?;
+  stdin.write_all(source.as_bytes())?;
+  drop(stdin);
+  let status = child.wait()?;
+  match status.success() {
+    true => Ok(()),
+    false => Err(format!("baseline compiler '{}' failed: {}", program, status).into()),
+  }
+}
+
+/// 编译并运行一个用例，检查输出，返回用时（微秒）；`baseline` 时以该 C 编译器编译
+fn time_case(
+  args: &ParsedArgs,
+  case: &Path,
+  baseline: Option<&str>,
+  timings: &Timings,
+) -> Result<u64> {
+  let exe = env::temp_dir().join(format!("sysyc-bench-{}", std::process::id()));
+  let exe = exe.to_string_lossy();
+  match baseline {
+    Some(cc) => build_baseline(args, cc, &fs::read_to_string(case)?, &exe)?,
+    None => {
+      let object = compile_object(args, &case.to_string_lossy(), timings)?;
+      link::link(&[object], &exe, args, timings)?;
+    }
+  }
+  // 没有 `.in` 时也不继承终端的输入
+  let input = case.with_extension("in");
+  let stdin = match input.exists() {
+    true => input.to_string_lossy().into_owned(),
+    false => "/dev/null".into(),
+  };
+  let result = match &args.ssh {
+    Some(host) => execute_remote(args, host, &exe, &stdin),
+    None => execute(args, &exe, &stdin),
+  };
+  fs::remove_file(exe.as_ref())?;
+  let (output, us) = result?;
+  let expected = fs::read(case.with_extension("out"))?;
+  testsuite::compare(&expected, &output).map_err(|e| format!("wrong output: {}", e))?;
+  Ok(us)
+}
+
+/// 读入 `--history` 文件：每行是用例名与微秒数，以制表符分隔
+fn load_history(path: &str) -> Result<BTreeMap<String, u64>> {
+  let mut history = BTreeMap::new();
+  for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let entry = line
+      .split_once('\t')
+      .and_then(|(name, us)| Some((name, us.parse().ok()?)));
+    let Some((name, us)) = entry else {
+      return Err(format!("{}:{}: expect a case name and microseconds", path, i + 1).into());
+    };
+    history.insert(name.to_string(), us);
+  }
+  Ok(history)
+}
+
+fn save_history(path: &str, history: &BTreeMap<String, u64>) -> Result<()> {
+  let mut text = format!("{}\n", HISTORY_HEADER);
+  for (name, us) in history {
+    text += &format!("{}\t{}\n", name, us);
+  }
+  Ok(fs::write(path, text)?)
+}
+
+fn ms(us: Option<u64>) -> String {
+  us.map_or("-".into(), |us| format!("{:.3}", us as f64 / 1000.0))
+}
+
+fn ratio(ratio: Option<f64>) -> String {
+  ratio.map_or("-".into(), |r| format!("{:.2}x", r))
+}
+
+/// 与前一次相比的变化，如 `+3.2%`
+fn change(ratio: Option<f64>) -> String {
+  ratio.map_or("-".into(), |r| format!("{:+.1}%", (r - 1.0) * 100.0))
+}
+
+/// 用时之比的几何平均
+fn geomean(ratios: &[f64]) -> Option<f64> {
+  let sum: f64 = ratios.iter().map(|r| r.ln()).sum();
+  (!ratios.is_empty()).then(|| (sum / ratios.len() as f64).exp())
+}
+
+fn print_row(name: &str, cells: [String; 5]) {
+  let [sysyc, baseline, ratio, previous, change] = cells;
+  println!(
+    "{:<40} {:>12} {:>12} {:>8} {:>12} {:>8}",
+    name, sysyc, baseline, ratio, previous, change
+  );
+}
+
+/// 运行 `args.input[0]` 下的所有用例，逐个输出用时及与对照、前一次运行之比，最后是几何平均
+/// 与汇总；有用例失败时返回错误
+pub fn run(args: &ParsedArgs, timings: &Timings) -> Result<()> {
+  let dir = Path::new(&args.input[0]);
+  if !dir.is_dir() {
+    return Err(format!("bench expects a directory, found {}", dir.display()).into());
+  }
+  let mut cases = vec![];
+  testsuite::collect(dir, &mut cases)?;
+  if cases.is_empty() {
+    return Err(format!("no test cases (.sy with .out) found in {}", dir.display()).into());
+  }
+  cases.sort();
+  let mut history = match &args.history {
+    Some(path) if Path::new(path).exists() => load_history(path)?,
+    _ => BTreeMap::new(),
+  };
+
+  let header = ["sysyc (ms)", "baseline", "ratio", "previous", "change"];
+  print_row("case", header.map(String::from));
+  let start = Instant::now();
+  let mut failed = vec![];
+  let (mut ratios, mut changes) = (vec![], vec![]);
+  for case in &cases {
+    let name = case.strip_prefix(dir).unwrap_or(case).with_extension("");
+    let name = name.display().to_string();
+    let us = match time_case(args, case, None, timings) {
+      Ok(us) => us,
+      Err(e) => {
+        println!("FAIL {}", name);
+        // 编译错误可能有多行
+        println!("     {}", e.to_string().replace('\n', "\n     "));
+        failed.push(name);
+        continue;
+      }
+    };
+    // 对照出错是环境的问题，不再继续
+    let baseline = match &args.baseline {
+      Some(cc) => Some(
+        time_case(args, case, Some(cc), timings)
+          .map_err(|e| format!("baseline: {}: {}", name, e))?,
+      ),
+      None => None,
+    };
+    let to_baseline = baseline.map(|baseline| us as f64 / baseline.max(1) as f64);
+    let previous = history.insert(name.clone(), us);
+    let to_previous = previous.map(|previous| us as f64 / previous.max(1) as f64);
+    ratios.extend(to_baseline);
+    changes.extend(to_previous);
+    let cells = [
+      ms(Some(us)),
+      ms(baseline),
+      ratio(to_baseline),
+      ms(previous),
+      change(to_previous),
+    ];
+    print_row(&name, cells);
+  }
+  let (to_baseline, to_previous) = (geomean(&ratios), geomean(&changes));
+  if to_baseline.is_some() || to_previous.is_some() {
+    let empty = String::new;
+    let cells = [
+      empty(),
+      empty(),
+      ratio(to_baseline),
+      empty(),
+      change(to_previous),
+    ];
+    print_row("geomean", cells);
+  }
+  if let Some(path) = &args.history {
+    // 本次失败的用例保留前一次的用时
+    save_history(path, &history)?;
+  }
+  let passed = cases.len() - failed.len();
+  let elapsed = start.elapsed().as_secs_f64();
+  println!(
+    "\n{} passed, {} failed ({} cases) in {:.3} s",
+    passed,
+    failed.len(),
+    cases.len(),
+    elapsed
+  );
+  if failed.is_empty() {
+    Ok(())
+  } else {
+    Err(format!("failed: {}", failed.join(", ")).into())
+  }
+}
@@ -0,0 +1,366 @@
+//! 编辑器中打开的一个源文件：每次改动后重新做语法与语义分析，记录各处名字所指的符号，供悬停、
+//! 跳转到定义、查找引用与大纲查询。位置在协议中是行号与以 UTF-16 编码单元计的列号，在此换算为
+//! 字节偏移。
+
+use sysyc::diagnostics::{Diagnostic, Severity};
+use sysyc::frontend::ast::*;
+use sysyc::frontend::highlight::{highlight, TokenClass};
+use sysyc::frontend::query::{Reference, SymbolIndex};
+use sysyc::frontend::sema::{SymbolId, SymbolInfo, SymbolKind};
+use sysyc::frontend::{analyze_all, parse, reparse, SysyType, TypedCompUnit};
+use sysyc::serialize::Json;
+
+/// 大纲中的一项：函数的子项是其参数与局部变量
+struct Outline {
+  symbol: SymbolId,
+  /// 整个声明的范围
+  range: Span,
+  /// 名字的范围
+  name: Span,
+  children: Vec<Outline>,
+}
+
+pub struct Document {
+  text: String,
+  /// 各行起始处的字节偏移
+  line_starts: Vec<usize>,
+  /// 有语法错误时没有分析的结果
+  unit: Option<TypedCompUnit>,
+  diagnostics: Vec<Diagnostic>,
+  /// 与 `unit` 同时存在
+  index: Option<SymbolIndex>,
+  outline: Vec<Outline>,
+}
+
+/// 各行起始处的字节偏移
+fn line_starts(text: &str) -> Vec<usize> {
+  let mut line_starts = vec![0];
+  line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+  line_starts
+}
+
+/// LSP `Position` 处的字节偏移；超出行末时为行末
+fn offset(text: &str, line_starts: &[usize], position: &Json) -> Option<Pos> {
+  let line = position.get("line")?.as_i64()? as usize;
+  let column = position.get("character")?.as_i64()? as usize;
+  let start = *line_starts.get(line)?;
+  let mut units = 0;
+  for (i, c) in text[start..].char_indices() {
+    if units >= column || c == '\n' {
+      return Some(start + i);
+    }
+    units += c.len_utf16();
+  }
+  Some(text.len())
+}
+
+/// 把 `didChange` 中的一处改动应用到 `text`；没有 `range` 的改动是整个文件的内容
+pub fn apply_change(text: &mut String, change: &Json) -> Option<()> {
+  let new = change.get("text")?.as_str()?;
+  let Some(range) = change.get("range") else {
+    *text = new.into();
+    return Some(());
+  };
+  let line_starts = line_starts(text);
+  let start = offset(text, &line_starts, range.get("start")?)?;
+  let end = offset(text, &line_starts, range.get("end")?)?;
+  text.replace_range(start..end.max(start), new);
+  Some(())
+}
+
+impl Document {
+  pub fn new(text: String) -> Self {
+    let parsed = parse(&text);
+    Self::analyze(text, parsed)
+  }
+
+  /// 内容改为 `text` 之后的文件：以原有的语法树增量地重新解析
+  pub fn edit(self, text: String) -> Self {
+    let parsed = match self.unit {
+      Some(unit) => reparse(unit.ast, &self.text, &text),
+      None => parse(&text),
+    };
+    Self::analyze(text, parsed)
+  }
+
+  pub fn text(&self) -> &str {
+    &self.text
+  }
+
+  fn analyze(text: String, parsed: Result<Ast, Vec<Diagnostic>>) -> Self {
+    let (unit, diagnostics) = match parsed {
+      Ok(ast) => {
+        let (unit, diagnostics) = analyze_all(ast);
+        (Some(unit), diagnostics)
+      }
+      Err(diagnostics) => (None, diagnostics),
+    };
+    let index = unit.as_ref().map(|unit| SymbolIndex::new(unit, &text));
+    let outline = index.as_ref().map_or(vec![], outline);
+    Self {
+      line_starts: line_starts(&text),
+      unit,
+      diagnostics,
+      index,
+      outline,
+      text,
+    }
+  }
+
+  /// 字节偏移处的行号与列号（均从 0 开始，列以 UTF-16 编码单元计）
+  fn line_column(&self, pos: Pos) -> (usize, usize) {
+    let pos = pos.min(self.text.len());
+    let line = self.line_starts.partition_point(|&start| start <= pos) - 1;
+    let column = self.text[self.line_starts[line]..pos]
+      .encode_utf16()
+      .count();
+    (line, column)
+  }
+
+  /// 字节偏移处的 LSP `Position`
+  fn position(&self, pos: Pos) -> Json {
+    let (line, column) = self.line_column(pos);
+    Json::object([("line", line.into()), ("character", column.into())])
+  }
+
+  fn range(&self, (start, end): Span) -> Json {
+    Json::object([("start", self.position(start)), ("end", self.position(end))])
+  }
+
+  /// LSP `Position` 处的字节偏移
+  pub fn offset(&self, position: &Json) -> Option<Pos> {
+    offset(&self.text, &self.line_starts, position)
+  }
+
+  /// 位于 `pos` 处的名字
+  fn name_at(&self, pos: Pos) -> Option<&Reference> {
+    self.index.as_ref()?.at(pos)
+  }
+
+  fn symbol(&self, id: SymbolId) -> &SymbolInfo {
+    &self.unit.as_ref().unwrap().symbols[id]
+  }
+
+  /// `publishDiagnostics` 的 `diagnostics`
+  pub fn diagnostics(&self, uri: &str) -> Json {
+    let diagnostics = self.diagnostics.iter().map(|d| self.diagnostic(uri, d));
+    Json::Array(diagnostics.collect())
+  }
+
+  fn diagnostic(&self, uri: &str, diagnostic: &Diagnostic) -> Json {
+    // 没有位置的诊断标在文件开头，只有起始位置的标出其后的单词
+    let (start, mut end) = diagnostic.span.unwrap_or((0, 0));
+    if start == end {
+      let word = self.text[start..].find(|c: char| !c.is_ascii_alphanumeric() && c != '_');
+      end = start + word.unwrap_or(self.text.len() - start);
+    }
+    let mut message = diagnostic.message.clone();
+    for note in &diagnostic.notes {
+      message += &format!("\nnote: {}", note);
+    }
+    if let Some(help) = &diagnostic.help {
+      message += &format!("\nhelp: {}", help);
+    }
+    let severity = match diagnostic.severity {
+      Severity::Error => 1,
+      Severity::Warning => 2,
+    };
+    let mut json = Json::object([
+      ("range", self.range((start, end))),
+      ("severity", severity.into()),
+      ("source", "sysyc".into()),
+      ("message", message.into()),
+    ]);
+    if !diagnostic.labels.is_empty() {
+      let related = diagnostic.labels.iter().map(|label| {
+        let location = self.location(uri, label.span);
+        Json::object([
+          ("location", location),
+          ("message", label.message.as_str().into()),
+        ])
+      });
+      json.push("relatedInformation", Json::Array(related.collect()));
+    }
+    json
+  }
+
+  fn location(&self, uri: &str, span: Span) -> Json {
+    Json::object([("uri", uri.into()), ("range", self.range(span))])
+  }
+
+  /// `textDocument/hover`：名字所指符号的声明与种类
+  pub fn hover(&self, pos: Pos) -> Json {
+    let Some(name) = self.name_at(pos) else {
+      return Json::Null;
+    };
+    let symbol = self.symbol(name.symbol);
+    let kind = match (symbol.kind, symbol.global) {
+      (SymbolKind::Const, true) => "全局常量",
+      (SymbolKind::Const, false) => "局部常量",
+      (SymbolKind::Var, true) => "全局变量",
+      (SymbolKind::Var, false) => "局部变量",
+      (SymbolKind::Param, _) => "参数",
+      (SymbolKind::Func, _) if symbol.pos.is_none() => "运行时库函数",
+      (SymbolKind::Func, _) => "函数",
+    };
+    let value = format!("```sysy\n{}\n```\n{}", declaration(symbol), kind);
+    let contents = Json::object([("kind", "markdown".into()), ("value", value.into())]);
+    Json::object([("contents", contents), ("range", self.range(name.span))])
+  }
+
+  /// `textDocument/definition`；运行时库函数没有定义
+  pub fn definition(&self, uri: &str, pos: Pos) -> Json {
+    let definition = self.name_at(pos).and_then(|name| {
+      let index = self.index.as_ref()?;
+      index.definition(name.symbol)
+    });
+    match definition {
+      Some(span) => self.location(uri, span),
+      None => Json::Null,
+    }
+  }
+
+  /// `textDocument/references`：指向同一符号的各处名字，`declarations` 为假时不含声明处
+  pub fn references(&self, uri: &str, pos: Pos, declarations: bool) -> Json {
+    let (Some(index), Some(name)) = (&self.index, self.name_at(pos)) else {
+      return Json::Null;
+    };
+    let references = index.references_to(name.symbol);
+    let references = references.filter(|r| declarations || r.declaration.is_none());
+    Json::Array(references.map(|r| self.location(uri, r.span)).collect())
+  }
+
+  /// `textDocument/documentSymbol`：各个全局符号，函数之下是其参数与局部变量
+  pub fn symbols(&self) -> Json {
+    Json::Array(
+      self
+        .outline
+        .iter()
+        .map(|item| self.outline_json(item))
+        .collect(),
+    )
+  }
+
+  fn outline_json(&self, item: &Outline) -> Json {
+    let symbol = self.symbol(item.symbol);
+    let kind = match symbol.kind {
+      SymbolKind::Func => 12,
+      SymbolKind::Var | SymbolKind::Param => 13,
+      SymbolKind::Const => 14,
+    };
+    let mut json = Json::object([
+      ("name", symbol.name.as_str().into()),
+      ("detail", declaration(symbol).into()),
+      ("kind", kind.into()),
+      ("range", self.range(item.range)),
+      ("selectionRange", self.range(item.name)),
+    ]);
+    if !item.children.is_empty() {
+      let children = item.children.iter().map(|item| self.outline_json(item));
+      json.push("children", Json::Array(children.collect()));
+    }
+    json
+  }
+
+  /// `textDocument/semanticTokens/full`：各记号按 LSP 的格式相对于前一个记号编码，类别是
+  /// [`TOKEN_TYPES`] 中的序号；跨行的注释在每行分别是一个记号
+  pub fn semantic_tokens(&self) -> Json {
+    let mut data = vec![];
+    let (mut last_line, mut last_column) = (0, 0);
+    for token in highlight(&self.text) {
+      let (ty, modifiers) = match token.class {
+        TokenClass::Keyword => (0, 0),
+        TokenClass::Ident(Some(SymbolKind::Func)) => (1, 0),
+        TokenClass::Ident(Some(SymbolKind::Var)) => (2, 0),
+        TokenClass::Ident(Some(SymbolKind::Const)) => (2, READONLY),
+        TokenClass::Ident(Some(SymbolKind::Param)) => (3, 0),
+        TokenClass::Ident(None) => continue,
+        TokenClass::Number => (4, 0),
+        TokenClass::String => (5, 0),
+        TokenClass::Comment => (6, 0),
+      };
+      let (mut start, end) = token.span;
+      while start < end {
+        let (line, column) = self.line_column(start);
+        let line_end = self.line_starts.get(line + 1).map_or(end, |&next| next - 1);
+        let line_end = line_end.min(end);
+        let length = self.text[start..line_end].encode_utf16().count();
+        if length > 0 {
+          let delta = match line == last_line {
+            true => column - last_column,
+            false => column,
+          };
+          data.extend([line - last_line, delta, length, ty, modifiers]);
+          (last_line, last_column) = (line, column);
+        }
+        start = line_end + 1;
+      }
+    }
+    Json::Array(data.into_iter().map(Json::from).collect())
+  }
+}
+
+/// 语义高亮的记号类别，`initialize` 时告知编辑器
+pub const TOKEN_TYPES: &[&str] = &[
+  "keyword",
+  "function",
+  "variable",
+  "parameter",
+  "number",
+  "string",
+  "comment",
+];
+
+/// 语义高亮的修饰，`initialize` 时告知编辑器：常量是只读的变量
+pub const TOKEN_MODIFIERS: &[&str] = &["readonly"];
+
+const READONLY: usize = 1;
+
+/// 符号的 C 风格的声明，如 `int a[2][3]`、`int (*p)[3]` 与 `int f(int *, int)`
+fn declaration(symbol: &SymbolInfo) -> String {
+  declare(&symbol.ty, symbol.name.as_str().into())
+}
+
+/// 类型为 `ty` 的声明，`inner` 是其中的声明符
+fn declare(ty: &SysyType, inner: String) -> String {
+  match ty {
+    SysyType::Int => format!("int {}", inner).trim_end().into(),
+    SysyType::Void => format!("void {}", inner).trim_end().into(),
+    SysyType::Array(element, len) => declare(element, format!("{}[{}]", inner, len)),
+    SysyType::Pointer(base) => match base.as_ref() {
+      SysyType::Array(..) | SysyType::Function(..) => declare(base, format!("(*{})", inner)),
+      _ => declare(base, format!("*{}", inner)),
+    },
+    SysyType::Function(ret, params) => {
+      let params = params.iter().map(|param| declare(param, String::new()));
+      let params = params.collect::<Vec<_>>().join(", ");
+      declare(ret, format!("{}({})", inner, params))
+    }
+  }
+}
+
+/// 由各个声明处的名字得到大纲：范围在前一个全局声明之内的声明（函数的参数与局部变量）是它的子项
+fn outline(index: &SymbolIndex) -> Vec<Outline> {
+  let mut outline = vec![];
+  let mut func: Option<Outline> = None;
+  for reference in index.references() {
+    let Some(range) = reference.declaration else {
+      continue;
+    };
+    let item = Outline {
+      symbol: reference.symbol,
+      range,
+      name: reference.span,
+      children: vec![],
+    };
+    match &mut func {
+      Some(func) if range.1 <= func.range.1 => func.children.push(item),
+      _ => {
+        outline.extend(func.take());
+        func = Some(item);
+      }
+    }
+  }
+  outline.extend(func);
+  outline
+}
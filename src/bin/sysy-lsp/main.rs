@@ -0,0 +1,173 @@
+//! SysY 的语言服务器：经由标准输入输出以 LSP 与编辑器通信，打开与改动源文件时报告语法与语义错误，
+//! 并提供悬停（符号的类型）、跳转到定义、查找引用、文档大纲与语义高亮。改动以增量的方式同步，
+//! 每次只重新解析改动所在的顶层声明。
+
+use std::collections::HashMap;
+use std::io::{stdin, stdout, BufReader};
+use std::process::exit;
+
+use sysyc::serialize::Json;
+
+use document::{apply_change, Document, TOKEN_MODIFIERS, TOKEN_TYPES};
+use rpc::{error_response, notification, read_message, response, write_message};
+
+mod document;
+mod rpc;
+
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_REQUEST: i32 = -32600;
+const PARSE_ERROR: i32 = -32700;
+
+#[derive(Default)]
+struct Server {
+  documents: HashMap<String, Document>,
+  shutdown: bool,
+}
+
+impl Server {
+  /// 处理一个请求，返回其结果；`Err` 为错误码与信息
+  fn request(&mut self, method: &str, params: &Json) -> Result<Json, (i32, String)> {
+    if self.shutdown {
+      return Err((INVALID_REQUEST, "server is shut down".into()));
+    }
+    let uri = params.get("textDocument").and_then(|doc| doc.get("uri"));
+    let uri = uri.and_then(Json::as_str).unwrap_or_default();
+    let document = self.documents.get(uri);
+    let pos = params.get("position");
+    let pos = document
+      .zip(pos)
+      .and_then(|(document, pos)| document.offset(pos));
+    Ok(match method {
+      "initialize" => initialize_result(),
+      "shutdown" => {
+        self.shutdown = true;
+        Json::Null
+      }
+      "textDocument/hover" => document
+        .zip(pos)
+        .map_or(Json::Null, |(d, pos)| d.hover(pos)),
+      "textDocument/definition" => match document.zip(pos) {
+        Some((document, pos)) => document.definition(uri, pos),
+        None => Json::Null,
+      },
+      "textDocument/references" => {
+        let context = params
+          .get("context")
+          .and_then(|c| c.get("includeDeclaration"));
+        let declarations = context.and_then(Json::as_bool).unwrap_or(true);
+        match document.zip(pos) {
+          Some((document, pos)) => document.references(uri, pos, declarations),
+          None => Json::Null,
+        }
+      }
+      "textDocument/documentSymbol" => document.map_or(Json::Array(vec![]), Document::symbols),
+      "textDocument/semanticTokens/full" => match document {
+        Some(document) => Json::object([("data", document.semantic_tokens())]),
+        None => Json::Null,
+      },
+      _ => return Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+    })
+  }
+
+  /// 处理一个通知，返回要发给编辑器的通知
+  fn notification(&mut self, method: &str, params: &Json) -> Option<Json> {
+    let uri = params
+      .get("textDocument")?
+      .get("uri")?
+      .as_str()?
+      .to_string();
+    let document = match method {
+      "textDocument/didOpen" => {
+        let text = params.get("textDocument")?.get("text")?.as_str()?;
+        Document::new(text.into())
+      }
+      // 依次应用各处改动，再以原有的语法树增量地重新解析
+      "textDocument/didChange" => {
+        let mut text = self.documents.get(&uri)?.text().to_string();
+        for change in params.get("contentChanges")?.as_array()? {
+          apply_change(&mut text, change)?;
+        }
+        self.documents.remove(&uri)?.edit(text)
+      }
+      "textDocument/didClose" => {
+        self.documents.remove(&uri);
+        return Some(publish(&uri, Json::Array(vec![])));
+      }
+      _ => return None,
+    };
+    let diagnostics = document.diagnostics(&uri);
+    self.documents.insert(uri.clone(), document);
+    Some(publish(&uri, diagnostics))
+  }
+}
+
+fn initialize_result() -> Json {
+  let capabilities = Json::object([
+    // 增量同步
+    ("textDocumentSync", 2.into()),
+    ("hoverProvider", true.into()),
+    ("definitionProvider", true.into()),
+    ("referencesProvider", true.into()),
+    ("documentSymbolProvider", true.into()),
+    ("semanticTokensProvider", semantic_tokens_options()),
+  ]);
+  let server_info = Json::object([
+    ("name", "sysy-lsp".into()),
+    ("version", env!("CARGO_PKG_VERSION").into()),
+  ]);
+  Json::object([("capabilities", capabilities), ("serverInfo", server_info)])
+}
+
+fn semantic_tokens_options() -> Json {
+  let names = |names: &[&str]| Json::Array(names.iter().map(|&name| name.into()).collect());
+  let legend = Json::object([
+    ("tokenTypes", names(TOKEN_TYPES)),
+    ("tokenModifiers", names(TOKEN_MODIFIERS)),
+  ]);
+  Json::object([("legend", legend), ("full", true.into())])
+}
+
+fn publish(uri: &str, diagnostics: Json) -> Json {
+  let params = Json::object([("uri", uri.into()), ("diagnostics", diagnostics)]);
+  notification("textDocument/publishDiagnostics", params)
+}
+
+fn main() {
+  let mut input = BufReader::new(stdin().lock());
+  let mut output = stdout().lock();
+  let mut server = Server::default();
+  loop {
+    let message = match read_message(&mut input) {
+      Ok(Some(Ok(message))) => message,
+      Ok(Some(Err(e))) => {
+        let reply = error_response(Json::Null, PARSE_ERROR, &e);
+        write_message(&mut output, &reply).unwrap();
+        continue;
+      }
+      // 输入结束而没有收到 exit 视为异常退出
+      Ok(None) => exit(1),
+      Err(e) => {
+        eprintln!("sysy-lsp: {}", e);
+        exit(1);
+      }
+    };
+    let method = message
+      .get("method")
+      .and_then(Json::as_str)
+      .unwrap_or_default();
+    let params = message.get("params").cloned().unwrap_or(Json::Null);
+    if method == "exit" {
+      exit(if server.shutdown { 0 } else { 1 });
+    }
+    let reply = match message.get("id") {
+      Some(id) => Some(match server.request(method, &params) {
+        Ok(result) => response(id.clone(), result),
+        Err((code, message)) => error_response(id.clone(), code, &message),
+      }),
+      None => server.notification(method, &params),
+    };
+    if let Some(reply) = reply {
+      write_message(&mut output, &reply).unwrap();
+    }
+  }
+}
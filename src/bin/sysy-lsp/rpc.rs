@@ -0,0 +1,65 @@
+//! JSON-RPC 消息的收发：每条消息是 `Content-Length` 等首部、一个空行与 UTF-8 编码的 JSON。
+
+use std::io::{self, BufRead, Write};
+
+use sysyc::serialize::Json;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+  io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+/// 读入一条消息；输入已经结束时为 `None`。消息体不是 JSON 时为 `Some(Err(_))`，连接仍可继续
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Result<Json, String>>> {
+  let mut length = None;
+  loop {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+      return Ok(None);
+    }
+    let line = line.trim_end();
+    if line.is_empty() {
+      break;
+    }
+    if let Some((name, value)) = line.split_once(':') {
+      if name.eq_ignore_ascii_case("Content-Length") {
+        length = Some(
+          value
+            .trim()
+            .parse()
+            .map_err(|_| invalid("invalid Content-Length"))?,
+        );
+      }
+    }
+  }
+  let length = length.ok_or_else(|| invalid("missing Content-Length"))?;
+  let mut body = vec![0; length];
+  input.read_exact(&mut body)?;
+  let body = String::from_utf8(body).map_err(|_| invalid("message is not UTF-8"))?;
+  Ok(Some(Json::parse(&body)))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Json) -> io::Result<()> {
+  let body = message.to_string();
+  write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+  output.flush()
+}
+
+/// 对请求 `id` 的成功响应
+pub fn response(id: Json, result: Json) -> Json {
+  Json::object([("jsonrpc", "2.0".into()), ("id", id), ("result", result)])
+}
+
+/// 对请求 `id` 的错误响应
+pub fn error_response(id: Json, code: i32, message: &str) -> Json {
+  let error = Json::object([("code", code.into()), ("message", message.into())]);
+  Json::object([("jsonrpc", "2.0".into()), ("id", id), ("error", error)])
+}
+
+/// 服务器发出的通知
+pub fn notification(method: &str, params: Json) -> Json {
+  Json::object([
+    ("jsonrpc", "2.0".into()),
+    ("method", method.into()),
+    ("params", params),
+  ])
+}
@@ -0,0 +1,313 @@
+//! `sysyc completions <shell>`：生成 bash、zsh 与 fish 的补全脚本。
+//!
+//! 选项与子命令取自 `--help` 的用法说明，选项的取值（目标、处理器、遍名等）取自各自的定义，
+//! 因此新增选项只需写进用法说明即可补全。
+
+use crate::argparse::{ErrorFormat, USAGE};
+use crate::backend::riscv::Cpu;
+use crate::optimization::PASSES;
+
+/// 选项所取的值
+enum Values {
+  None,
+  /// 取其中之一
+  Choices(Vec<String>),
+  /// 以逗号分隔的若干个
+  List(Vec<String>),
+  File,
+  Dir,
+  Command,
+  /// 下一个参数是任意的名字，不补全
+  Name,
+}
+
+struct Opt {
+  /// 如 `-koopa`、`--emit=`、`-o`、`-L`；以 `=` 结尾的选项与取值写在一起
+  name: String,
+  help: String,
+  values: Values,
+}
+
+fn strings(items: &[&str]) -> Vec<String> {
+  items.iter().map(|s| s.to_string()).collect()
+}
+
+/// `--name=<...>` 的取值
+fn values_of(name: &str) -> Values {
+  match name {
+    "--target=" => Values::Choices(strings(&[
+      "riscv32",
+      "riscv64",
+      "riscv32-unknown-linux-gnu",
+      "riscv64-unknown-linux-gnu",
+      "riscv32-unknown-none-elf",
+      "riscv64-unknown-none-elf",
+    ])),
+    "--march=" => Values::Choices(strings(&[
+      "rv32i", "rv32im", "rv32imc", "rv64i", "rv64im", "rv64imc", "rv64gc",
+    ])),
+    "--mcpu=" => Values::Choices(strings(Cpu::NAMES)),
+    "--error-format=" => Values::Choices(strings(ErrorFormat::NAMES)),
+    "--passes=" => Values::List(strings(&PASSES[1..])),
+    "--cfg-after=" => Values::Choices(strings(PASSES)),
+    "--dump-ir-before=" | "--dump-ir-after=" => Values::List(strings(&[&["all"], PASSES].concat())),
+    "--linker=" | "--runner=" | "--reference=" | "--baseline=" => Values::Command,
+    "--jobs=" => Values::Choices(strings(&["1", "2", "4", "8"])),
+    "--count=" => Values::Choices(strings(&["10", "100", "1000"])),
+    "--seed=" | "--ssh=" => Values::None,
+    _ => Values::File,
+  }
+}
+
+/// 解析用法说明，得到子命令与选项
+fn parse_usage() -> (Vec<(String, String)>, Vec<Opt>) {
+  // 说明从第 25 列开始；选项名太长时说明在下一行
+  const HELP_COLUMN: usize = 25;
+  let mut subcommands = vec![];
+  let mut opts: Vec<Opt> = vec![];
+  let lines: Vec<_> = USAGE.lines().collect();
+  for (i, line) in lines.iter().enumerate() {
+    let Some(rest) = line.strip_prefix("  ") else {
+      continue;
+    };
+    let (names, mut help) = match line.get(..HELP_COLUMN) {
+      Some(names) if names.ends_with(' ') => (names.trim(), line[HELP_COLUMN..].trim().to_string()),
+      _ => (line.trim(), String::new()),
+    };
+    // 说明可能延续多行
+    let continued = |line: &&&str| line.get(..HELP_COLUMN).is_some_and(|s| s.trim().is_empty());
+    for line in lines[i + 1..].iter().take_while(continued) {
+      if !help.is_empty() {
+        help.push(' ');
+      }
+      help += line.trim();
+    }
+    if rest.starts_with(|c: char| c.is_ascii_lowercase()) {
+      // 子命令，如 `completions <shell>`
+      let name = names.split(' ').next().unwrap();
+      subcommands.push((name.to_string(), help.to_string()));
+      continue;
+    }
+    if !rest.starts_with('-') {
+      continue;
+    }
+    for name in names.split(", ") {
+      let opt = if let Some(name) = name.strip_suffix(" <file>") {
+        Opt {
+          name: name.into(),
+          help: help.clone(),
+          values: Values::File,
+        }
+      } else if let Some(name) = name.strip_suffix(" <name>") {
+        Opt {
+          name: name.into(),
+          help: help.clone(),
+          values: Values::Name,
+        }
+      } else if name.starts_with("-L") {
+        Opt {
+          name: "-L".into(),
+          help: help.clone(),
+          values: Values::Dir,
+        }
+      } else if let Some((key, value)) = name.split_once('=') {
+        let key = format!("{}=", key);
+        if value.starts_with('<') {
+          Opt {
+            values: values_of(&key),
+            name: key,
+            help: help.clone(),
+          }
+        } else {
+          // 如 `--emit=koopa`：合并为一个选项，各个值是其取值
+          match opts.iter_mut().find(|opt| opt.name == key) {
+            Some(Opt {
+              values: Values::Choices(choices),
+              ..
+            }) => {
+              choices.push(value.into());
+              continue;
+            }
+            _ => Opt {
+              name: key,
+              help: "Output format".into(),
+              values: Values::Choices(vec![value.into()]),
+            },
+          }
+        }
+      } else {
+        Opt {
+          name: name.into(),
+          help: help.clone(),
+          values: Values::None,
+        }
+      };
+      opts.push(opt);
+    }
+  }
+  (subcommands, opts)
+}
+
+fn bash(subcommands: &[(String, String)], opts: &[Opt]) -> String {
+  let words = |items: &[String]| items.join(" ");
+  let names: Vec<_> = subcommands.iter().map(|(name, _)| name.clone()).collect();
+  let flags: Vec<_> = opts.iter().map(|opt| opt.name.clone()).collect();
+  let mut values = String::new();
+  for opt in opts {
+    let reply = match &opt.values {
+      Values::Choices(choices) => format!("compgen -W \"{}\" -- \"$value\"", words(choices)),
+      // 只补全最后一个逗号之后的部分
+      Values::List(items) => format!(
+        "compgen -P \"$head\" -W \"{}\" -- \"${{value##*,}}\"",
+        words(items)
+      ),
+      Values::Command => "compgen -c -- \"$value\"".into(),
+      Values::File | Values::Dir | Values::None | Values::Name => continue,
+    };
+    values += &format!("    {}*) COMPREPLY=($({}));;\n", opt.name, reply);
+  }
+  format!(
+    r#"# bash completion for sysyc; eval "$(sysyc completions bash)"
+_sysyc() {{
+  local line="${{COMP_LINE:0:$COMP_POINT}}"
+  local cur="${{line##* }}" prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+  COMPREPLY=()
+  if [[ $cur == *=* ]]; then
+    # bash 在 = 处断词，补全的只是 = 之后的部分
+    local value="${{cur#*=}}" head=""
+    [[ $value == *,* ]] && head="${{value%,*}},"
+    case "$cur" in
+{values}    esac
+    return
+  fi
+  case "$prev" in
+    -o) COMPREPLY=($(compgen -f -- "$cur")); return;;
+    completions) COMPREPLY=($(compgen -W "bash zsh fish" -- "$cur")); return;;
+  esac
+  case "$cur" in
+    -L*) COMPREPLY=($(compgen -P -L -d -- "${{cur#-L}}"));;
+    -*)
+      COMPREPLY=($(compgen -W "{flags}" -- "$cur"))
+      [[ ${{COMPREPLY[0]}} == *= ]] && compopt -o nospace;;
+    *)
+      [[ $COMP_CWORD -eq 1 ]] && COMPREPLY=($(compgen -W "{names}" -- "$cur"))
+      COMPREPLY+=($(compgen -f -- "$cur"));;
+  esac
+}}
+complete -F _sysyc sysyc
+"#,
+    values = values,
+    flags = words(&flags),
+    names = words(&names),
+  )
+}
+
+/// zsh 的 `_arguments` 说明中需转义的字符
+fn zsh_escape(text: &str) -> String {
+  let mut escaped = String::new();
+  for c in text.chars() {
+    match c {
+      '[' | ']' | ':' | '\\' => escaped.extend(['\\', c]),
+      '\'' => escaped += "'\\''",
+      _ => escaped.push(c),
+    }
+  }
+  escaped
+}
+
+fn zsh(subcommands: &[(String, String)], opts: &[Opt]) -> String {
+  let mut specs = String::new();
+  for opt in opts {
+    let action = match &opt.values {
+      Values::None => String::new(),
+      Values::Choices(choices) => format!(":value:({})", choices.join(" ")),
+      Values::List(items) => format!(":passes:_sequence compadd - {}", items.join(" ")),
+      Values::File => ":file:_files".into(),
+      Values::Dir => ":directory:_files -/".into(),
+      Values::Command => ":command:_command_names -e".into(),
+      Values::Name => ":name: ".into(),
+    };
+    // `-o` 的文件名与 `--find-refs` 的名字是下一个参数，其余带值的选项与值连在一起
+    let name = match &opt.values {
+      Values::Dir => format!("{}-", opt.name),
+      _ => opt.name.clone(),
+    };
+    specs += &format!("    '{}[{}]{}' \\\n", name, zsh_escape(&opt.help), action);
+  }
+  let names: Vec<_> = subcommands.iter().map(|(name, _)| name.as_str()).collect();
+  format!(
+    r#"#compdef sysyc
+# zsh completion for sysyc; sysyc completions zsh > "${{fpath[1]}}/_sysyc"
+_sysyc() {{
+  if [[ $words[CURRENT-1] == completions ]]; then
+    _values shell bash zsh fish
+    return
+  fi
+  _arguments -S \
+{specs}    '1:: :_alternative "subcommands:subcommand:({names})" "files:input:_files"' \
+    '*:input:_files'
+}}
+_sysyc "$@"
+"#,
+    specs = specs,
+    names = names.join(" "),
+  )
+}
+
+fn fish(subcommands: &[(String, String)], opts: &[Opt]) -> String {
+  let quote = |text: &str| format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"));
+  let mut out = String::from("# fish completion for sysyc; sysyc completions fish | source\n");
+  out += "complete -c sysyc -n '__fish_seen_subcommand_from completions' -x -a 'bash zsh fish'\n";
+  for (name, help) in subcommands {
+    out += &format!(
+      "complete -c sysyc -n '__fish_is_first_arg' -a {} -d {}\n",
+      name,
+      quote(help)
+    );
+  }
+  for opt in opts {
+    let name = opt.name.trim_end_matches('=');
+    // fish 区分 `-x`（单个字母）、`-xyz`（旧式长选项）与 `--xyz`
+    let switch = match name.strip_prefix("--") {
+      Some(long) => format!("-l {}", long),
+      None if name.len() == 2 => format!("-s {}", &name[1..]),
+      None => format!("-o {}", &name[1..]),
+    };
+    let values = match &opt.values {
+      Values::None => String::new(),
+      Values::Choices(choices) => format!(" -x -a {}", quote(&choices.join(" "))),
+      Values::List(items) => {
+        let list = format!(
+          "(__fish_complete_list , \"string split , {}\")",
+          items.join(",")
+        );
+        format!(" -x -a {}", quote(&list))
+      }
+      Values::File => " -r -F".into(),
+      Values::Dir => " -x -a '(__fish_complete_directories)'".into(),
+      Values::Command => " -x -a '(__fish_complete_command)'".into(),
+      Values::Name => " -x".into(),
+    };
+    out += &format!(
+      "complete -c sysyc {}{} -d {}\n",
+      switch,
+      values,
+      quote(&opt.help)
+    );
+  }
+  out
+}
+
+/// 指定 shell 的补全脚本
+pub fn generate(shell: &str) -> Result<String, String> {
+  let (subcommands, opts) = parse_usage();
+  match shell {
+    "bash" => Ok(bash(&subcommands, &opts)),
+    "zsh" => Ok(zsh(&subcommands, &opts)),
+    "fish" => Ok(fish(&subcommands, &opts)),
+    _ => Err(format!(
+      "unknown shell '{}': expect bash, zsh or fish",
+      shell
+    )),
+  }
+}
@@ -0,0 +1,166 @@
+//! 工作目录下的配置文件 `sysy.toml`：以 TOML 的子集给出各选项的默认值，命令行上的同名选项优先。
+//!
+//! ```toml
+//! march = "rv32imc"
+//! opt-level = 1
+//! lib-dirs = ["../runtime"]
+//! ```
+//!
+//! 只支持顶层的键值对，值可以是字符串、整数、布尔值或字符串数组（写在一行内，元素中不能有逗号）。
+
+use std::fs;
+use std::io::ErrorKind;
+
+use crate::Result;
+
+pub const CONFIG_FILE: &str = "sysy.toml";
+
+#[derive(Debug)]
+enum TomlValue {
+  String(String),
+  Integer(i64),
+  Boolean(bool),
+  Array(Vec<String>),
+}
+
+/// 读取当前目录下的配置文件，转换为等价的命令行选项；文件不存在时为空
+pub fn load() -> Result<Vec<String>> {
+  let text = match fs::read_to_string(CONFIG_FILE) {
+    Ok(text) => text,
+    Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+    Err(e) => return Err(format!("{}: {}", CONFIG_FILE, e).into()),
+  };
+  let mut flags = vec![];
+  for (i, line) in text.lines().enumerate() {
+    let error = |message: String| format!("{}:{}: {}", CONFIG_FILE, i + 1, message);
+    let line = strip_comment(line).trim();
+    if line.is_empty() {
+      continue;
+    }
+    if line.starts_with('[') {
+      return Err(error(format!("tables are not supported: {}", line)).into());
+    }
+    let (key, value) = line
+      .split_once('=')
+      .ok_or_else(|| error(format!("expect 'key = value', found '{}'", line)))?;
+    let key = key.trim();
+    let value = parse_value(value.trim()).map_err(error)?;
+    flags.extend(to_flags(key, value).map_err(error)?);
+  }
+  Ok(flags)
+}
+
+/// 去掉不在字符串中的 `#` 之后的注释
+fn strip_comment(line: &str) -> &str {
+  let mut quote = None;
+  let mut escaped = false;
+  for (i, c) in line.char_indices() {
+    match (quote, c) {
+      (Some('"'), '\\') if !escaped => {
+        escaped = true;
+        continue;
+      }
+      (Some(q), _) if c == q && !escaped => quote = None,
+      (None, '"' | '\'') => quote = Some(c),
+      (None, '#') => return &line[..i],
+      _ => {}
+    }
+    escaped = false;
+  }
+  line
+}
+
+fn parse_string(text: &str) -> std::result::Result<String, String> {
+  let invalid = || format!("invalid string {}", text);
+  if let Some(literal) = text.strip_prefix('\'').and_then(|t| t.strip_suffix('\'')) {
+    return Ok(literal.into());
+  }
+  let inner = text
+    .strip_prefix('"')
+    .and_then(|t| t.strip_suffix('"'))
+    .ok_or_else(invalid)?;
+  let mut string = String::new();
+  let mut chars = inner.chars();
+  while let Some(c) = chars.next() {
+    string.push(match c {
+      '\\' => match chars.next() {
+        Some('n') => '\n',
+        Some('t') => '\t',
+        Some(c @ ('"' | '\\')) => c,
+        _ => return Err(invalid()),
+      },
+      '"' => return Err(invalid()),
+      _ => c,
+    });
+  }
+  Ok(string)
+}
+
+fn parse_value(text: &str) -> std::result::Result<TomlValue, String> {
+  match text {
+    "true" => return Ok(TomlValue::Boolean(true)),
+    "false" => return Ok(TomlValue::Boolean(false)),
+    _ => {}
+  }
+  if let Some(items) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+    // 允许末尾的逗号
+    let items = items
+      .split(',')
+      .map(str::trim)
+      .filter(|item| !item.is_empty());
+    return Ok(TomlValue::Array(
+      items
+        .map(parse_string)
+        .collect::<std::result::Result<_, _>>()?,
+    ));
+  }
+  if text.starts_with(['"', '\'']) {
+    return Ok(TomlValue::String(parse_string(text)?));
+  }
+  match text.replace('_', "").parse() {
+    Ok(n) => Ok(TomlValue::Integer(n)),
+    Err(_) => Err(format!("unsupported value '{}'", text)),
+  }
+}
+
+/// 配置项对应的命令行选项
+fn to_flags(key: &str, value: TomlValue) -> std::result::Result<Vec<String>, String> {
+  let mismatch = |expect: &str| format!("'{}' expects {}", key, expect);
+  let flags = match (key, value) {
+    ("target", TomlValue::String(target)) => vec![format!("--target={}", target)],
+    ("march", TomlValue::String(march)) => vec![format!("--march={}", march)],
+    ("mcpu", TomlValue::String(cpu)) => vec![format!("--mcpu={}", cpu)],
+    ("opt-level", TomlValue::Integer(level @ (0 | 1))) => vec![format!("-O{}", level)],
+    ("jobs", TomlValue::Integer(jobs)) if jobs > 0 => vec![format!("--jobs={}", jobs)],
+    ("passes", TomlValue::Array(passes)) => vec![format!("--passes={}", passes.join(","))],
+    ("lib-dirs", TomlValue::Array(dirs)) => dirs.iter().map(|dir| format!("-L{}", dir)).collect(),
+    ("linker", TomlValue::String(linker)) => vec![format!("--linker={}", linker)],
+    ("runner", TomlValue::String(runner)) => vec![format!("--runner={}", runner)],
+    ("pic", TomlValue::Boolean(pic)) => vec![if pic { "-fpic" } else { "-fno-pic" }.into()],
+    ("const-pool" | "verify", TomlValue::Boolean(enabled)) => match enabled {
+      true => vec![format!("--{}", key)],
+      false => vec![],
+    },
+    ("target" | "march" | "mcpu" | "linker" | "runner", _) => return Err(mismatch("a string")),
+    ("opt-level", _) => return Err(mismatch("0 or 1")),
+    ("jobs", _) => return Err(mismatch("a positive integer")),
+    ("passes" | "lib-dirs", _) => return Err(mismatch("an array of strings")),
+    ("pic" | "const-pool" | "verify", _) => return Err(mismatch("a boolean")),
+    _ => return Err(format!("unknown key '{}'", key)),
+  };
+  Ok(flags)
+}
+
+/// 命令行选项所设置的项；命令行上已设置的项不再取配置文件中的值。`-L` 可以累加，总是保留
+pub fn option_key(flag: &str) -> Option<&str> {
+  match flag {
+    _ if flag.starts_with("-L") => None,
+    // 两者都决定寄存器宽度
+    _ if flag.starts_with("--target=") || flag.starts_with("--march=") => Some("isa"),
+    _ if flag.starts_with("-O") && flag.len() > 2 => Some("-O"),
+    "-fpic" | "-fPIC" | "-fpie" | "-fPIE" | "-fno-pic" | "-fno-PIC" | "-fno-pie" | "-fno-PIE" => {
+      Some("pic")
+    }
+    _ => Some(flag.split('=').next().unwrap()),
+  }
+}
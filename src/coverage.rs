@@ -0,0 +1,80 @@
+//! `sysyc cov report <input> <counts>...`：以 `--coverage` 编译的程序退出时向标准错误输出各行
+//! 执行的次数，这里把一次或多次运行的输出相加，按 gcov 的格式标注源代码的每一行：
+//!
+//! ```text
+//!         -:    1:int fib(int n) {
+//!       177:    2:  if (n < 2) return n;
+//!     #####:    6:void never() {}
+//! ```
+//!
+//! 没有生成代码的行标为 `-`，生成了代码却从未执行的行标为 `#####`。
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{stdout, Write};
+
+use crate::argparse::ParsedArgs;
+use crate::Result;
+
+/// 计数之前的一行，见 `frontend/coverage.sy`
+const HEADER: &str = "sysyc: coverage";
+
+/// 把 `text` 中每一次运行的计数加到 `counts` 上，返回其中运行的次数。每次运行的计数是
+/// [`HEADER`] 之后的若干行「行号 次数」；同一行分属多个基本块时取其中最多的次数
+fn add_counts(text: &str, counts: &mut BTreeMap<usize, u64>) -> usize {
+  let mut runs = 0;
+  let mut lines = text.lines();
+  while lines.any(|line| line == HEADER) {
+    runs += 1;
+    let mut run = BTreeMap::new();
+    // 到第一个不是计数的行（如调用计数的报告）为止
+    let pairs = lines.clone().map_while(|line| {
+      let (line, count) = line.split_once(' ')?;
+      Some((line.parse::<usize>().ok()?, count.parse::<u64>().ok()?))
+    });
+    for (line, count) in pairs {
+      let max: &mut u64 = run.entry(line).or_default();
+      *max = count.max(*max);
+    }
+    for (line, count) in run {
+      *counts.entry(line).or_default() += count;
+    }
+  }
+  runs
+}
+
+pub fn run(args: &ParsedArgs) -> Result<()> {
+  let source = fs::read_to_string(&args.input[0])?;
+  let mut counts = BTreeMap::new();
+  for file in &args.input[1..] {
+    let text = fs::read_to_string(file)?;
+    if add_counts(&text, &mut counts) == 0 {
+      return Err(format!("{}: no counts written by --coverage", file).into());
+    }
+  }
+
+  let mut output: Box<dyn Write> = match args.output.as_deref() {
+    None | Some("-") => Box::new(stdout()),
+    Some(path) => Box::new(fs::File::create(path)?),
+  };
+  let mut executed = 0;
+  for (i, text) in source.lines().enumerate() {
+    let count = match counts.get(&(i + 1)) {
+      None => "-".to_string(),
+      Some(0) => "#####".to_string(),
+      Some(count) => {
+        executed += 1;
+        count.to_string()
+      }
+    };
+    writeln!(output, "{:>9}:{:>5}:{}", count, i + 1, text)?;
+  }
+  let total = counts.len().max(1);
+  eprintln!(
+    "lines executed: {} of {} ({:.1}%)",
+    executed,
+    counts.len(),
+    executed as f64 * 100. / total as f64
+  );
+  Ok(())
+}
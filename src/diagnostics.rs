@@ -0,0 +1,312 @@
+//! 诊断信息及其输出。编译的各个阶段把错误转换为 [`Diagnostic`]，交给 [`DiagnosticEmitter`]：
+//! 命令行以 [`TerminalEmitter`] 输出到终端，或以 [`PrettyEmitter`] 连同出错处的源代码一起输出；
+//! 库的使用者可以用 [`Collector`] 收集，或以 [`JsonEmitter`] 输出给编辑器等工具。
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use crate::frontend::ast::Pos;
+use crate::frontend::{self, SourceMap};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+  Error,
+  Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+  /// 源程序中的错误：语法、语义错误
+  Source,
+  /// 其他错误：命令行、文件、汇编与链接等
+  Other,
+}
+
+/// 与诊断相关的另一处位置，如此前的定义
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+  pub span: (Pos, Pos),
+  pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+  pub severity: Severity,
+  pub kind: Kind,
+  pub message: String,
+  /// 出错处的起止位置（字节偏移），出错在文件末尾时两者都是文件的长度；不知道位置时为 `None`
+  pub span: Option<(Pos, Pos)>,
+  pub labels: Vec<Label>,
+  /// 出错的源文件。几个源文件一同编译时，编译返回的错误带有文件名；其余的诊断属于调用者
+  /// 给出的源文件，为 `None`
+  pub file: Option<String>,
+  /// 补充说明
+  pub notes: Vec<String>,
+  /// 修改的建议
+  pub help: Option<String>,
+}
+
+impl Diagnostic {
+  /// 源程序中 `start..end` 处的错误
+  pub fn at(start: Pos, end: Pos, message: String) -> Self {
+    Self {
+      severity: Severity::Error,
+      kind: Kind::Source,
+      message,
+      span: Some((start, end)),
+      labels: vec![],
+      file: None,
+      notes: vec![],
+      help: None,
+    }
+  }
+
+  pub fn with_label(mut self, start: Pos, end: Pos, message: &str) -> Self {
+    self.labels.push(Label {
+      span: (start, end),
+      message: message.into(),
+    });
+    self
+  }
+
+  pub fn with_note(mut self, note: &str) -> Self {
+    self.notes.push(note.into());
+    self
+  }
+
+  pub fn with_help(mut self, help: &str) -> Self {
+    self.help = Some(help.into());
+    self
+  }
+
+  /// 由编译过程中返回的错误得到的诊断，没有位置
+  pub fn from_error(e: &(dyn Error + 'static)) -> Self {
+    if let Some(e) = e.downcast_ref::<frontend::FileError>() {
+      let mut diagnostic = Self::from_error(e.error.as_ref());
+      diagnostic.file = Some(e.file.clone());
+      return diagnostic;
+    }
+    let (kind, message) = match frontend::error_message(e) {
+      Some(message) => (Kind::Source, message),
+      None => (Kind::Other, e.to_string()),
+    };
+    Self {
+      severity: Severity::Error,
+      kind,
+      message,
+      span: None,
+      labels: vec![],
+      file: None,
+      notes: vec![],
+      help: None,
+    }
+  }
+
+  /// 起始位置的行号与列号（均从 1 开始，列以字节计）
+  pub fn position(&self, source: &str) -> Option<(usize, usize)> {
+    let (start, _) = self.span?;
+    Some(SourceMap::new(source).position(start))
+  }
+}
+
+impl fmt::Display for Diagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl Error for Diagnostic {}
+
+pub trait DiagnosticEmitter {
+  fn emit(&mut self, diagnostic: &Diagnostic);
+}
+
+/// 源文件的名字与内容，用于给出出错处的行号与列号
+type SourceFile = (String, String);
+
+/// 输出到标准错误，与编译失败时命令行的输出相同
+#[derive(Default)]
+pub struct TerminalEmitter {
+  source: Option<SourceFile>,
+}
+
+impl TerminalEmitter {
+  /// 有位置的诊断前加上 `文件:行:列:`
+  pub fn with_source(name: &str, text: &str) -> Self {
+    Self {
+      source: Some((name.into(), text.into())),
+    }
+  }
+}
+
+impl DiagnosticEmitter for TerminalEmitter {
+  fn emit(&mut self, diagnostic: &Diagnostic) {
+    let mut line = String::new();
+    if let Some(file) = &diagnostic.file {
+      line += &format!("{}: ", file);
+    } else if let Some((name, text)) = &self.source {
+      if let Some((row, column)) = diagnostic.position(text) {
+        line += &format!("{}:{}:{}: ", name, row, column);
+      }
+    }
+    match (diagnostic.kind, diagnostic.severity) {
+      (Kind::Source, Severity::Error) => line += "\x1b[0;31m编译错误\x1b[0m ",
+      (_, Severity::Warning) => line += "\x1b[0;33m警告\x1b[0m ",
+      (Kind::Other, Severity::Error) => {}
+    }
+    eprintln!("{}{}", line, diagnostic.message);
+    for note in &diagnostic.notes {
+      eprintln!("  note: {}", note);
+    }
+    if let Some(help) = &diagnostic.help {
+      eprintln!("  help: {}", help);
+    }
+  }
+}
+
+/// 输出到标准错误，并列出出错处及各个标注所在的源代码行（`--error-format=pretty`）：
+///
+/// ```text
+/// 编译错误: 符号 'a' 重复定义
+///  --> a.sy:2:1
+///   |
+/// 1 | int a;
+///   | - 此前的定义
+/// 2 | int a;
+///   | ^
+///   = help: ...
+/// ```
+#[derive(Default)]
+pub struct PrettyEmitter {
+  source: Option<SourceFile>,
+}
+
+impl PrettyEmitter {
+  pub fn with_source(name: &str, text: &str) -> Self {
+    Self {
+      source: Some((name.into(), text.into())),
+    }
+  }
+}
+
+/// 源代码中的一处标注：起止位置、下划线所用的字符与说明
+type Mark<'a> = ((Pos, Pos), char, &'a str);
+
+/// 标注所在的源代码行，各行之下以 `^`（出错处）或 `-`（其他标注）画出位置；`pad` 是与行号等宽的空白
+fn snippet(text: &str, marks: &[Mark], pad: &str) -> String {
+  let map = SourceMap::new(text);
+  let lines: Vec<_> = text.lines().collect();
+  let mut marks = marks.to_vec();
+  marks.sort_by_key(|&((start, _), ..)| start);
+  let mut out = String::new();
+  let mut last_line = 0;
+  for ((start, end), c, message) in marks {
+    let (line, column) = map.position(start);
+    let code = lines.get(line - 1).copied().unwrap_or_default();
+    if line != last_line {
+      out += &format!("{:>2$} | {}\n", line, code, pad.len());
+      last_line = line;
+    }
+    // 下划线与源代码对齐：保留制表符，其余字符换为空格；跨行时画到行末
+    let prefix = code.get(..column - 1).unwrap_or(code);
+    let indent: String = prefix
+      .chars()
+      .map(|c| if c == '\t' { '\t' } else { ' ' })
+      .collect();
+    let rest = code.get(column - 1..).unwrap_or_default();
+    let width = rest.get(..end - start).unwrap_or(rest).chars().count();
+    let underline = c.to_string().repeat(width.max(1));
+    out += &format!("{} | {}{} {}", pad, indent, underline, message);
+    out = out.trim_end().to_string() + "\n";
+  }
+  out
+}
+
+impl DiagnosticEmitter for PrettyEmitter {
+  fn emit(&mut self, diagnostic: &Diagnostic) {
+    let title = match (diagnostic.kind, diagnostic.severity) {
+      (Kind::Source, Severity::Error) => "\x1b[1;31m编译错误\x1b[0m",
+      (Kind::Other, Severity::Error) => "\x1b[1;31m错误\x1b[0m",
+      (_, Severity::Warning) => "\x1b[1;33m警告\x1b[0m",
+    };
+    let mut out = format!("{}: {}\n", title, diagnostic.message);
+    let mut pad = " ".to_string();
+    if let (Some((name, text)), Some(span)) = (&self.source, diagnostic.span) {
+      let mut marks = vec![(span, '^', "")];
+      for label in &diagnostic.labels {
+        marks.push((label.span, '-', label.message.as_str()));
+      }
+      let map = SourceMap::new(text);
+      let last_line = marks.iter().map(|&((start, _), ..)| map.line_at(start));
+      pad = " ".repeat(last_line.max().unwrap_or(1).to_string().len());
+      let (line, column) = map.position(span.0);
+      out += &format!("{}--> {}:{}:{}\n", pad, name, line, column);
+      out += &format!("{} |\n", pad);
+      out += &snippet(text, &marks, &pad);
+    } else if let Some(file) = &diagnostic.file {
+      out += &format!("{}--> {}\n", pad, file);
+    }
+    for note in &diagnostic.notes {
+      out += &format!("{} = note: {}\n", pad, note);
+    }
+    if let Some(help) = &diagnostic.help {
+      out += &format!("{} = help: {}\n", pad, help);
+    }
+    eprint!("{}", out);
+  }
+}
+
+/// 每个诊断输出为一行 JSON 对象（见 [`Diagnostic::to_json`]）：
+/// `{"version":1,"severity":"error","kind":"source","message":"...","start":12,"end":13,"line":2,"column":3}`。
+/// 没有位置时不含 `start` 等字段，没有给出源文件时不含 `line` 与 `column`。
+/// 另有标注、说明与建议时依次加上 `"labels":[{"start":1,"end":2,"message":"..."}]`、
+/// `"notes":["..."]` 与 `"help":"..."`
+pub struct JsonEmitter<W: Write> {
+  writer: W,
+  source: Option<SourceFile>,
+}
+
+impl<W: Write> JsonEmitter<W> {
+  pub fn new(writer: W) -> Self {
+    Self {
+      writer,
+      source: None,
+    }
+  }
+
+  /// 诊断中加上 `"file"`、`"line"` 与 `"column"`
+  pub fn with_source(writer: W, name: &str, text: &str) -> Self {
+    Self {
+      writer,
+      source: Some((name.into(), text.into())),
+    }
+  }
+}
+
+impl JsonEmitter<io::Stderr> {
+  pub fn stderr() -> Self {
+    Self::new(io::stderr())
+  }
+}
+
+impl<W: Write> DiagnosticEmitter for JsonEmitter<W> {
+  fn emit(&mut self, diagnostic: &Diagnostic) {
+    let source = self
+      .source
+      .as_ref()
+      .map(|(name, text)| (name.as_str(), text.as_str()));
+    // 输出失败时无处报告
+    let _ = writeln!(self.writer, "{}", diagnostic.to_json(source));
+  }
+}
+
+/// 收集所有的诊断
+#[derive(Debug, Default)]
+pub struct Collector(pub Vec<Diagnostic>);
+
+impl DiagnosticEmitter for Collector {
+  fn emit(&mut self, diagnostic: &Diagnostic) {
+    self.0.push(diagnostic.clone());
+  }
+}
@@ -0,0 +1,225 @@
+//! 按行比较两段文本，输出统一格式（`diff -u`）的差异，供 `--print-changed` 使用。
+//!
+//! 先去掉相同的开头与结尾，再以两边各只出现一次的相同的行（如标号与栈上的位置各不相同的存取）
+//! 为锚点把其余部分分段（即 patience diff 的做法），各段以 Myers 的算法求最短的编辑序列。指令
+//! 调度、压缩等遍会改动很长的块中的大部分指令，锚点使每段都很短。
+
+use std::collections::HashMap;
+
+/// 差异的每一处前后保留的相同的行数，与 `diff -u` 相同
+const CONTEXT: usize = 3;
+
+/// 一段中的编辑超过这么多时不再求最短的编辑序列，整段改为先删后增，以免用时与内存过多
+const MAX_EDITS: isize = 2000;
+
+enum Edit<'a> {
+  Equal(&'a str),
+  Delete(&'a str),
+  Insert(&'a str),
+}
+
+/// 两边各只出现一次的相同的行中，在两边顺序一致的最多的一组，按位置排列
+fn anchors(old: &[&str], new: &[&str]) -> Vec<(usize, usize)> {
+  // 各行在两边出现的次数与最后一次的位置
+  let mut lines: HashMap<&str, (usize, usize, usize, usize)> = HashMap::new();
+  for (i, &line) in old.iter().enumerate() {
+    let entry = lines.entry(line).or_default();
+    (entry.0, entry.2) = (entry.0 + 1, i);
+  }
+  for (j, &line) in new.iter().enumerate() {
+    if let Some(entry) = lines.get_mut(line) {
+      (entry.1, entry.3) = (entry.1 + 1, j);
+    }
+  }
+  let mut unique: Vec<_> = lines
+    .into_values()
+    .filter(|&(in_old, in_new, ..)| in_old == 1 && in_new == 1)
+    .map(|(_, _, i, j)| (i, j))
+    .collect();
+  unique.sort_unstable();
+
+  // 按 `old` 中的位置排列后，取 `new` 中位置最长的递增子序列：`tails[l]` 是长为 `l + 1` 的
+  // 子序列中末项最小的一个，`prev` 是各项在子序列中的前一项
+  let mut tails: Vec<usize> = vec![];
+  let mut prev = vec![None; unique.len()];
+  for (n, &(_, j)) in unique.iter().enumerate() {
+    let l = tails.partition_point(|&t| unique[t].1 < j);
+    prev[n] = l.checked_sub(1).map(|l| tails[l]);
+    match tails.get_mut(l) {
+      Some(tail) => *tail = n,
+      None => tails.push(n),
+    }
+  }
+  let mut result = vec![];
+  let mut next = tails.last().copied();
+  while let Some(n) = next {
+    result.push(unique[n]);
+    next = prev[n];
+  }
+  result.reverse();
+  result
+}
+
+/// 把 `old` 变为 `new` 的最短编辑序列；编辑过多时整段先删后增
+fn shortest_edit<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+  let (n, m) = (old.len() as isize, new.len() as isize);
+  let max = n + m;
+  // `v[k + max]` 是第 `d` 步在对角线 `k = x - y` 上到达的最远的 `x`
+  let mut v = vec![0; 2 * max as usize + 2];
+  // 第 `d` 步开始时 `v` 中对角线 `-d..=d` 的部分，回溯时使用
+  let mut trace = vec![];
+  let index = |k: isize| (k + max) as usize;
+  'search: for d in 0..=max {
+    if d > MAX_EDITS {
+      let deleted = old.iter().copied().map(Edit::Delete);
+      return deleted
+        .chain(new.iter().copied().map(Edit::Insert))
+        .collect();
+    }
+    trace.push(v[index(-d)..=index(d)].to_vec());
+    for k in (-d..=d).step_by(2) {
+      let mut x = if k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)]) {
+        v[index(k + 1)]
+      } else {
+        v[index(k - 1)] + 1
+      };
+      let mut y = x - k;
+      while x < n && y < m && old[x as usize] == new[y as usize] {
+        x += 1;
+        y += 1;
+      }
+      v[index(k)] = x;
+      if x >= n && y >= m {
+        break 'search;
+      }
+    }
+  }
+
+  let mut edits = vec![];
+  let (mut x, mut y) = (n, m);
+  for (d, v) in trace.iter().enumerate().rev() {
+    let d = d as isize;
+    let at = |k: isize| v[(k + d) as usize];
+    // 第 `d` 步之前所在的位置，与搜索时的选择相同
+    let (prev_x, prev_y) = if d == 0 {
+      (0, 0)
+    } else {
+      let k = x - y;
+      let prev_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+        k + 1
+      } else {
+        k - 1
+      };
+      (at(prev_k), at(prev_k) - prev_k)
+    };
+    while x > prev_x && y > prev_y {
+      x -= 1;
+      y -= 1;
+      edits.push(Edit::Equal(old[x as usize]));
+    }
+    if d > 0 {
+      if x == prev_x {
+        edits.push(Edit::Insert(new[prev_y as usize]));
+      } else {
+        edits.push(Edit::Delete(old[prev_x as usize]));
+      }
+    }
+    (x, y) = (prev_x, prev_y);
+  }
+  edits.reverse();
+  edits
+}
+
+/// 把编辑序列中的一段写为一处差异，起始是这一段之前两边各有的行数
+fn hunk(edits: &[Edit], (old_start, new_start): (usize, usize), out: &mut String) {
+  let old_len = edits
+    .iter()
+    .filter(|e| !matches!(e, Edit::Insert(_)))
+    .count();
+  let new_len = edits
+    .iter()
+    .filter(|e| !matches!(e, Edit::Delete(_)))
+    .count();
+  // 没有行时起始的行号是其前一行，与 `diff -u` 相同
+  let range = |start: usize, len: usize| match len {
+    0 => format!("{},0", start),
+    1 => format!("{}", start + 1),
+    _ => format!("{},{}", start + 1, len),
+  };
+  *out += &format!(
+    "@@ -{} +{} @@\n",
+    range(old_start, old_len),
+    range(new_start, new_len)
+  );
+  for edit in edits {
+    let (sign, line) = match edit {
+      Edit::Equal(line) => (' ', line),
+      Edit::Delete(line) => ('-', line),
+      Edit::Insert(line) => ('+', line),
+    };
+    *out += &format!("{}{}\n", sign, line);
+  }
+}
+
+/// `old` 与 `new` 的统一格式的差异，两者以 `old_name`、`new_name` 标识；相同时返回 `None`
+pub fn unified(old: &str, new: &str, old_name: &str, new_name: &str) -> Option<String> {
+  if old == new {
+    return None;
+  }
+  let old: Vec<_> = old.lines().collect();
+  let new: Vec<_> = new.lines().collect();
+  let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+  let suffix = old[prefix..]
+    .iter()
+    .rev()
+    .zip(new[prefix..].iter().rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let mut edits: Vec<_> = old[..prefix].iter().copied().map(Edit::Equal).collect();
+  let (old_middle, new_middle) = (
+    &old[prefix..old.len() - suffix],
+    &new[prefix..new.len() - suffix],
+  );
+  let (mut i, mut j) = (0, 0);
+  for (anchor_i, anchor_j) in anchors(old_middle, new_middle) {
+    edits.extend(shortest_edit(
+      &old_middle[i..anchor_i],
+      &new_middle[j..anchor_j],
+    ));
+    edits.push(Edit::Equal(old_middle[anchor_i]));
+    (i, j) = (anchor_i + 1, anchor_j + 1);
+  }
+  edits.extend(shortest_edit(&old_middle[i..], &new_middle[j..]));
+  edits.extend(old[old.len() - suffix..].iter().copied().map(Edit::Equal));
+
+  // 每处改动连同前后的若干行合为一段，相互重叠或相邻的段合并
+  let mut ranges: Vec<(usize, usize)> = vec![];
+  for (i, edit) in edits.iter().enumerate() {
+    if matches!(edit, Edit::Equal(_)) {
+      continue;
+    }
+    let (start, end) = (
+      i.saturating_sub(CONTEXT),
+      (i + 1 + CONTEXT).min(edits.len()),
+    );
+    match ranges.last_mut() {
+      Some(last) if start <= last.1 => last.1 = end,
+      _ => ranges.push((start, end)),
+    }
+  }
+  // 只有行尾等 `lines` 不区分的差异
+  if ranges.is_empty() {
+    return None;
+  }
+  let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+  let (mut old_line, mut new_line, mut done) = (0, 0, 0);
+  for (start, end) in ranges {
+    for edit in &edits[done..start] {
+      old_line += !matches!(edit, Edit::Insert(_)) as usize;
+      new_line += !matches!(edit, Edit::Delete(_)) as usize;
+    }
+    hunk(&edits[start..end], (old_line, new_line), &mut out);
+    done = start;
+  }
+  Some(out)
+}
@@ -0,0 +1,128 @@
+//! `sysyc gen`：以随机生成的程序（见 [`progen`](crate::progen)）做差分测试，相当于简化的
+//! Csmith。
+//!
+//! 每个程序由本编译器编译后解释执行 Koopa IR（加上 `--qemu` 则生成 RISC-V 程序在模拟器中运行），
+//! 同时作为 C 程序由参考编译器（`--reference`，默认 `cc`）编译、直接运行；`--reference=interpret`
+//! 则以解释执行为参考，只检查 RISC-V 后端。两者的输出与退出码按评测的格式比较，不一致的程序
+//! 连同参考的结果保存为 `gen-<seed>.sy` 与 `.out`，即可以由 `sysyc test` 重现的用例。
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::argparse::ParsedArgs;
+use crate::{generate_ir, interpreter, link, progen, testsuite, Result, Timings};
+
+/// 作为 C 程序编译时，在程序之前给出 SysY 运行时库中用到的函数
+const PRELUDE: &str = "\
+#include <stdio.h>
+void putint(int n) { printf(\"%d\", n); }
+void putch(int c) { putchar(c); }
+";
+
+/// 以参考编译器编译、运行，或者解释执行，返回评测格式的输出
+fn reference(args: &ParsedArgs, source: &str, timings: &Timings) -> Result<Vec<u8>> {
+  if args.reference == "interpret" {
+    let (ir, _) = generate_ir(args, &[("<gen>", source.to_string())], timings)?;
+    let (output, code) = interpreter::run_captured(&ir, vec![])?;
+    return Ok(link::judge_output(output, code & 0xff));
+  }
+  let mut words = args.reference.split_whitespace();
+  let cc = words.next().ok_or("empty reference compiler command")?;
+  let exe = env::temp_dir().join(format!("sysyc-gen-ref-{}", std::process::id()));
+  // 有符号溢出按补码回绕，与 SysY 相同
+  let mut child = Command::new(cc)
+    .args(words)
+    .args(["-fwrapv", "-w", "-x", "c", "-", "-o"])
+    .arg(&exe)
+    .stdin(Stdio::piped())
+    .spawn()
+    .map_err(|e| format!("cannot run reference compiler '{}': {}", cc, e))?;
+  let mut stdin = child.stdin.take().unwrap();
+  stdin.write_all(PRELUDE.as_bytes())?;
+  stdin.write_all(source.as_bytes())?;
+  drop(stdin);
+  let status = child.wait()?;
+  if !status.success() {
+    return Err(format!("reference compiler '{}' failed: {}", cc, status).into());
+  }
+  let output = Command::new(&exe).stdin(Stdio::null()).output();
+  fs::remove_file(&exe)?;
+  let output = output?;
+  let code = output
+    .status
+    .code()
+    .ok_or_else(|| format!("reference program terminated abnormally: {}", output.status))?;
+  Ok(link::judge_output(output.stdout, code))
+}
+
+/// 由本编译器编译、运行程序，与参考的结果比较
+fn check(args: &ParsedArgs, source: &str, expected: &[u8], timings: &Timings) -> Result<()> {
+  let case = env::temp_dir().join(format!("sysyc-gen-{}.sy", std::process::id()));
+  fs::write(&case, source)?;
+  let output = testsuite::run_case(args, &case, args.qemu, timings);
+  fs::remove_file(&case)?;
+  Ok(testsuite::compare(expected, &output?)?)
+}
+
+/// 未给出 `--seed` 时取自时钟，并输出以便重现
+fn first_seed(args: &ParsedArgs) -> u64 {
+  args.seed.unwrap_or_else(|| {
+    let now = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap_or_default();
+    now.as_nanos() as u64 % 1_000_000_000
+  })
+}
+
+/// 生成 `args.count` 个程序逐个比较，不一致的保存到 `args.input[0]`（默认为当前目录）下，
+/// 最后是汇总；有不一致时返回错误。给出 `-o` 时只把 `--seed` 的程序写入该文件
+pub fn run(args: &ParsedArgs, timings: &Timings) -> Result<()> {
+  let seed = first_seed(args);
+  if let Some(output) = &args.output {
+    let program = progen::program(seed);
+    match output.as_str() {
+      "-" => print!("{}", program),
+      path => fs::write(path, program)?,
+    }
+    return Ok(());
+  }
+  let dir = Path::new(args.input.first().map_or(".", String::as_str));
+  println!("seeds {}..{}", seed, seed.wrapping_add(args.count as u64));
+
+  let start = Instant::now();
+  let mut failed = vec![];
+  for i in 0..args.count as u64 {
+    let seed = seed.wrapping_add(i);
+    let program = progen::program(seed);
+    // 参考出错是生成器或环境的问题，不再继续
+    let expected = reference(args, &program, timings)?;
+    if let Err(e) = check(args, &program, &expected, timings) {
+      let name = format!("gen-{}", seed);
+      println!("FAIL {}", name);
+      println!("     {}", e.to_string().replace('\n', "\n     "));
+      fs::create_dir_all(dir)?;
+      fs::write(dir.join(format!("{}.sy", name)), &program)?;
+      fs::write(dir.join(format!("{}.out", name)), &expected)?;
+      failed.push(name);
+    }
+  }
+  let passed = args.count - failed.len();
+  let elapsed = start.elapsed().as_secs_f64();
+  println!(
+    "\n{} passed, {} failed ({} programs) in {:.3} s",
+    passed,
+    failed.len(),
+    args.count,
+    elapsed
+  );
+  if failed.is_empty() {
+    Ok(())
+  } else {
+    let saved = format!("saved to {}", dir.display());
+    Err(format!("failed: {} ({})", failed.join(", "), saved).into())
+  }
+}
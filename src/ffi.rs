@@ -0,0 +1,207 @@
+//! C 语言接口，供 C/C++ 的评测程序或 Python（`ctypes`）调用编译器；声明见 `include/sysyc.h`。
+//!
+//! 字符串均为以 NUL 结尾的 UTF-8。编译的结果或错误写入 [`SysyBuffer`]，由调用者以
+//! [`sysy_buffer_free`] 释放。与库接口相同，多个线程可以同时编译。
+
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use crate::backend::riscv::{Cpu, Extensions, Os, Target};
+use crate::diagnostics::JsonEmitter;
+use crate::frontend::Sanitizers;
+use crate::CompileOptions;
+
+/// 编译成功
+pub const SYSY_OK: c_int = 0;
+/// 源程序有错误，缓冲区中是诊断，每行一个 JSON 对象（与 `--error-format=json` 相同）
+pub const SYSY_COMPILE_ERROR: c_int = 1;
+/// 参数有误（空指针、非 UTF-8、未知的选项等），缓冲区中是错误信息
+pub const SYSY_INVALID_ARGUMENT: c_int = 2;
+/// 编译器内部错误，缓冲区中是错误信息
+pub const SYSY_INTERNAL_ERROR: c_int = 3;
+
+/// 输出的种类
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SysyOutput {
+  /// Koopa IR 文本（`-koopa`）
+  Koopa = 0,
+  /// RISC-V 汇编（`-riscv`）
+  Riscv = 1,
+}
+
+/// 编译选项；字符串为空指针时取默认值，与命令行上同名的选项含义相同
+#[repr(C)]
+pub struct SysyOptions {
+  pub output: SysyOutput,
+  /// `--target`，如 `riscv64-unknown-linux-gnu`
+  pub target: *const c_char,
+  /// `--march`，如 `rv32imc`
+  pub march: *const c_char,
+  /// `--mcpu`
+  pub mcpu: *const c_char,
+  /// `--passes`，以逗号分隔
+  pub passes: *const c_char,
+  /// `-fpic`
+  pub pic: bool,
+  /// `--const-pool`
+  pub const_pool: bool,
+}
+
+/// 由本库分配的字符串，`data` 以 NUL 结尾，`len` 不含 NUL
+#[repr(C)]
+pub struct SysyBuffer {
+  pub data: *mut c_char,
+  pub len: usize,
+}
+
+impl SysyBuffer {
+  fn new(text: String) -> Self {
+    let mut bytes = text.into_bytes();
+    let len = bytes.len();
+    bytes.push(0);
+    let data = Box::into_raw(bytes.into_boxed_slice()) as *mut c_char;
+    Self { data, len }
+  }
+}
+
+/// 空指针为 `None`
+pub(crate) unsafe fn optional_str<'a>(
+  s: *const c_char,
+  name: &str,
+) -> Result<Option<&'a str>, String> {
+  if s.is_null() {
+    return Ok(None);
+  }
+  let s = CStr::from_ptr(s).to_str();
+  s.map(Some)
+    .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+/// 由 C 的选项得到库的编译选项，`--target` 与 `--march` 的检查与命令行相同
+unsafe fn parse_options(options: &SysyOptions) -> Result<CompileOptions, String> {
+  let mut target = Target::default();
+  let mut os = Os::Linux;
+  if let Some(triple) = optional_str(options.target, "target")? {
+    let (xlen, triple_os) = Target::parse_triple(triple)?;
+    os = triple_os.unwrap_or(os);
+    target = Target::new(xlen, target.ext, os);
+  }
+  if let Some(march) = optional_str(options.march, "march")? {
+    let (xlen, ext) = Extensions::parse_march(march)?;
+    if !options.target.is_null() && xlen != target.xlen {
+      return Err(format!("target conflicts with march '{}'", march));
+    }
+    target = Target::new(xlen, ext, os);
+  }
+  let cpu = match optional_str(options.mcpu, "mcpu")? {
+    Some(name) => Cpu::parse(name)?,
+    None => Cpu::default(),
+  };
+  let passes = optional_str(options.passes, "passes")?.unwrap_or_default();
+  let passes = passes.split(',').filter(|pass| !pass.is_empty());
+  Ok(CompileOptions {
+    target,
+    cpu,
+    pic: options.pic,
+    const_pool: options.const_pool,
+    passes: passes.map(Into::into).collect(),
+    sanitize: Sanitizers::default(),
+    instrument_functions: false,
+    coverage: false,
+    auto_timing: false,
+    extensions: false,
+    jobs: 0,
+  })
+}
+
+unsafe fn compile(source: *const c_char, options: *const SysyOptions) -> (c_int, String) {
+  if source.is_null() || options.is_null() {
+    return (
+      SYSY_INVALID_ARGUMENT,
+      "source and options must not be null".into(),
+    );
+  }
+  let source = match optional_str(source, "source") {
+    Ok(source) => source.unwrap_or_default(),
+    Err(e) => return (SYSY_INVALID_ARGUMENT, e),
+  };
+  let options = &*options;
+  let compile_options = match parse_options(options) {
+    Ok(compile_options) => compile_options,
+    Err(e) => return (SYSY_INVALID_ARGUMENT, e),
+  };
+  let mut diagnostics = vec![];
+  let mut emitter = JsonEmitter::with_source(&mut diagnostics, "<input>", source);
+  let output = match options.output {
+    SysyOutput::Koopa => {
+      let ir = crate::compile_to_koopa_with(source, &compile_options, &mut emitter);
+      ir.map(|ir| crate::koopa_text(&ir))
+    }
+    SysyOutput::Riscv => {
+      let asm = crate::compile_to_riscv_with(source, &compile_options, &mut emitter);
+      asm.map(Ok)
+    }
+  };
+  match output {
+    Some(Ok(text)) => (SYSY_OK, text),
+    Some(Err(e)) => (SYSY_INTERNAL_ERROR, e.to_string()),
+    None => (
+      SYSY_COMPILE_ERROR,
+      String::from_utf8_lossy(&diagnostics).into_owned(),
+    ),
+  }
+}
+
+/// 编译 `source`，结果写入 `out_buf`，返回 `SYSY_OK` 等状态码。失败时 `out_buf` 中是错误信息；
+/// `out_buf` 为空指针时只返回状态码。
+///
+/// # Safety
+///
+/// `source` 须为以 NUL 结尾的字符串，`options` 须指向有效的 [`SysyOptions`]，其中的字符串同样以 NUL
+/// 结尾或为空指针；`out_buf` 须为空指针或可写。
+#[no_mangle]
+pub unsafe extern "C" fn sysy_compile(
+  source: *const c_char,
+  options: *const SysyOptions,
+  out_buf: *mut SysyBuffer,
+) -> c_int {
+  run(out_buf, || compile(source, options))
+}
+
+/// 运行 `f`，把得到的文本写入 `out_buf`（不为空指针时），返回状态码
+pub(crate) unsafe fn run(out_buf: *mut SysyBuffer, f: impl FnOnce() -> (c_int, String)) -> c_int {
+  // 不能让 panic 越过 C 的栈帧
+  let result = panic::catch_unwind(AssertUnwindSafe(f));
+  let (status, text) = result.unwrap_or_else(|payload| {
+    let message = payload
+      .downcast_ref::<&str>()
+      .map(|s| s.to_string())
+      .or_else(|| payload.downcast_ref::<String>().cloned())
+      .unwrap_or_else(|| "the compiler panicked".into());
+    (SYSY_INTERNAL_ERROR, message)
+  });
+  if !out_buf.is_null() {
+    out_buf.write(SysyBuffer::new(text));
+  }
+  status
+}
+
+/// 释放 [`sysy_compile`] 写入的缓冲区，之后 `buf` 为空；对空的缓冲区不做任何事。
+///
+/// # Safety
+///
+/// `buf` 须为空指针，或指向由 [`sysy_compile`] 写入、尚未释放的缓冲区。
+#[no_mangle]
+pub unsafe extern "C" fn sysy_buffer_free(buf: *mut SysyBuffer) {
+  let Some(buf) = buf.as_mut() else {
+    return;
+  };
+  if !buf.data.is_null() {
+    let bytes = ptr::slice_from_raw_parts_mut(buf.data as *mut u8, buf.len + 1);
+    drop(Box::from_raw(bytes));
+  }
+  buf.data = ptr::null_mut();
+  buf.len = 0;
+}
@@ -1,23 +1,234 @@
-use koopa::ir::Program;
-use lalrpop_util::lalrpop_mod;
+use koopa::ir::Type;
+use lalrpop_util::ParseError;
 
+use crate::backend::riscv::Target;
+pub use crate::diagnostics::Diagnostic;
+use crate::stack;
+use crate::timing::Timings;
+
+pub use self::annotations::{Annotations, Ir};
+pub use self::decl::FuncAttrs;
+use self::decl::Unit;
 use self::error::CompileError;
+pub use self::error::FileError;
+pub use self::expr::ty::SysyType;
+pub use self::instrument::Instrumentation;
+use self::lexer::{LexError, Lexer, Token};
+pub use self::sanitize::Sanitizers;
+pub use self::sema::{analyze, analyze_all, analyze_with, TypedCompUnit};
+pub use self::source::SourceMap;
 
-mod ast;
+mod annotations;
+pub mod ast;
 mod decl;
+mod desugar;
+mod diagnostic;
+mod dump;
 mod error;
 mod expr;
+pub mod highlight;
+mod instrument;
+mod lexer;
+mod name;
+pub mod overload;
+pub mod query;
+mod sanitize;
+pub mod sema;
+mod source;
 mod stmt;
 mod symbol;
+pub mod visit;
+
+// 文法开头的 `use` 由生成的各子模块使用，在顶层未被用到
+#[allow(unused_imports)]
+mod parser {
+  include!(concat!(env!("OUT_DIR"), "/frontend/sysy.rs"));
+}
+
+/// 裸机运行（`--freestanding`）时的库函数：字符 I/O 之外的部分以 SysY 实现
+const FREESTANDING_RUNTIME: &str = concat!(
+  include_str!("frontend/libsysy.sy"),
+  include_str!("frontend/freestanding.sy")
+);
+
+/// 内置的运行时库：链接时找不到 `libsysy` 时代替之，系统调用之外的部分以 SysY 实现
+const HOSTED_RUNTIME: &str = concat!(
+  include_str!("frontend/libsysy.sy"),
+  include_str!("frontend/hosted.sy")
+);
+
+/// 前端返回的源程序错误（语法、语义错误）的内容，不含“编译错误”的前缀；其他错误为 `None`
+pub(crate) fn error_message(e: &(dyn std::error::Error + 'static)) -> Option<String> {
+  e.downcast_ref::<CompileError>().map(CompileError::message)
+}
+
+/// 解析一个源文件
+fn parse_source(input: &str) -> Result<ast::Ast, ParseError<ast::Pos, Token<'_>, LexError>> {
+  let mut ast = ast::Ast::new();
+  let unit = parser::CompUnitParser::new().parse(&mut ast, Lexer::new(input))?;
+  ast.unit = unit;
+  Ok(ast)
+}
+
+/// 只做语法分析，得到语法树；不检查语义，也不生成 IR
+pub fn parse(input: &str) -> Result<ast::Ast, Vec<Diagnostic>> {
+  parse_source(input).map_err(|e| vec![e.into()])
+}
+
+/// 源代码由 `old` 改为 `new` 之后重新解析，`previous` 是 `old` 的语法树；结果与 [`parse`] 相同。
+///
+/// 改动的范围是两者不同的部分。只重新解析与之相交或相邻的顶层声明（及其间的空白与注释），
+/// 其余的声明保留原有的节点，其后的移动到新的位置；因此编辑器中每次改动只需解析所在的函数。
+/// 重新解析有错误时（如改动使其后的源代码成为注释）、或替换掉的节点累计超过一份源代码时，
+/// 整个重新解析
+pub fn reparse(mut previous: ast::Ast, old: &str, new: &str) -> Result<ast::Ast, Vec<Diagnostic>> {
+  if old == new {
+    return Ok(previous);
+  }
+  let prefix = old
+    .bytes()
+    .zip(new.bytes())
+    .take_while(|(a, b)| a == b)
+    .count();
+  let suffix = old.bytes().rev().zip(new.bytes().rev());
+  let suffix = suffix.take_while(|(a, b)| a == b).count();
+  let suffix = suffix.min(old.len().min(new.len()) - prefix);
+  let (start, end) = (prefix, old.len() - suffix);
+
+  // 与改动处相交或相邻的顶层声明是 `unit[first..last]`，重新解析其前后两个声明之间的部分
+  let unit = &previous.unit;
+  let first = unit.partition_point(|&decl| previous.span(decl).1 < start);
+  let last = unit.partition_point(|&decl| previous.span(decl).0 <= end);
+  let from = first.checked_sub(1).map_or(0, |i| previous.span(unit[i]).1);
+  let to = unit.get(last).map_or(old.len(), |&decl| previous.pos(decl));
+  let stale = to - from;
+  if previous.stale + stale > old.len() {
+    return parse(new);
+  }
+  let delta = new.len() as isize - old.len() as isize;
+  previous.shift(to, delta);
+  let to = to.wrapping_add_signed(delta);
+  // 改动可能使其后的声明并入注释或相邻的记号，此时在新的源代码中其开头不是一个记号的开头
+  if to < new.len() {
+    let tokens = Lexer::starting_at(new, from).map_while(Result::ok);
+    let next = tokens.map(|(start, ..)| start).find(|&start| start >= to);
+    if next != Some(to) {
+      return parse(new);
+    }
+  }
 
-lalrpop_mod!(parser, "/frontend/sysy.rs");
+  let lexer = Lexer::starting_at(&new[..to], from);
+  let Ok(decls) = parser::CompUnitParser::new().parse(&mut previous, lexer) else {
+    return parse(new);
+  };
+  previous.unit.splice(first..last, decls);
+  previous.stale += stale;
+  Ok(previous)
+}
+
+/// 解析源代码，以 S 表达式输出语法树（`--emit=ast`）
+pub fn dump_ast(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+  let ast = parse_source(input).map_err(|e| CompileError::Other(e.to_string()))?;
+  Ok(stack::run(|| dump::dump_ast(&ast, input)))
+}
+
+/// 解析源代码，以 Graphviz DOT 格式输出语法树（`--emit=ast-dot`）
+pub fn dump_ast_dot(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+  let ast = parse_source(input).map_err(|e| CompileError::Other(e.to_string()))?;
+  Ok(stack::run(|| dump::dump_ast_dot(&ast, input)))
+}
+
+/// 解析源代码，以 JSON 输出语法树（`--emit=ast-json`，格式见 [`crate::serialize`]）
+pub fn dump_ast_json(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+  let ast = parse_source(input).map_err(|e| CompileError::Other(e.to_string()))?;
+  // 语法树的 JSON 与表达式嵌套得一样深，输出与释放都是递归的
+  Ok(stack::run(|| {
+    crate::serialize::serialize_ast(&ast).to_string()
+  }))
+}
 
+/// 只做词法分析，逐行输出各记号的位置、种类与原文（`--emit=tokens`）
+pub fn dump_tokens(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+  let source = SourceMap::new(input);
+  let tokens = Lexer::new(input).map(|token| match token {
+    Ok((start, token, end)) => Ok((start, token.text, end)),
+    Err(e) => {
+      let (line, column) = source.position(e.start);
+      let message = format!("{} at {}:{}", e.kind.message(), line, column);
+      Err(CompileError::Other(message))
+    }
+  });
+  let tokens = tokens.collect::<Result<_, CompileError>>()?;
+  Ok(dump::dump_tokens(tokens, input))
+}
+
+/// 生成 IR，同时返回指令到源代码行的映射。`inputs` 是各源文件的名字与内容，多个源文件合并为
+/// 一个程序，源代码行的映射只记录第一个文件。扩展的语法在生成之前展开。裸机运行的目标上库函数
+/// 随程序一同生成，`instrument` 是插入的运行时检查等插桩。`extensions` 时（`--extensions`）允许
+/// 函数重载，各源文件中的函数先改为改编后的名字（见 [`overload`]）。各阶段的用时记入 `timings`
+pub fn generate_ir(
+  inputs: &[(&str, String)],
+  target: &Target,
+  instrument: Instrumentation,
+  extensions: bool,
+  timings: &Timings,
+) -> Result<(Ir, SourceMap), Box<dyn std::error::Error>> {
+  stack::run(|| generate(inputs, target, instrument, extensions, timings))
+}
+
+/// [`generate_ir`] 在切换后的栈上所做的部分
 // https://github.com/rust-lang/rust/issues/24580
 #[allow(unused_mut, unused_variables, unused_assignments)]
-pub fn generate_ir(input: String) -> Result<Program, Box<dyn std::error::Error>> {
-  let mut ast = parser::CompUnitParser::new()
-    .parse(&input)
-    .map_err(|e| CompileError::Other(e.to_string()))?;
+fn generate(
+  inputs: &[(&str, String)],
+  target: &Target,
+  instrument: Instrumentation,
+  extensions: bool,
+  timings: &Timings,
+) -> Result<(Ir, SourceMap), Box<dyn std::error::Error>> {
+  // 数组与指针的大小随目标而定，解释执行等直接使用 IR 的后端也依此计算
+  Type::set_ptr_size(target.ptr_size());
+  let mut units = vec![];
+  for (name, text) in inputs {
+    if timings.enabled() {
+      // 解析时逐个取得记号，这里另做一遍词法分析只为单独计时
+      let tokens = || Lexer::new(text).take_while(Result::is_ok).count();
+      timings.time("lex", tokens);
+    }
+    let parse = || parse_source(text);
+    let mut ast = timings.time("parse", parse).map_err(|e| {
+      let e = CompileError::Other(e.to_string());
+      decl::with_file_name(e.into(), name, inputs.len())
+    })?;
+    // 无法展开的扩展语法在重新分析时报告其位置，这里只报告第一处
+    if let Some((_, e)) = desugar::desugar(&mut ast).into_iter().next() {
+      return Err(decl::with_file_name(e.into(), name, inputs.len()));
+    }
+    if extensions {
+      let mangle = || overload::mangle(ast);
+      ast = timings
+        .time("overload", mangle)
+        .map_err(|e| decl::with_file_name(e.into(), name, inputs.len()))?;
+    }
+    units.push(Unit { ast, name, text });
+  }
+  let runtime = target.freestanding().then(|| {
+    let ast = parse_source(FREESTANDING_RUNTIME).unwrap();
+    (ast, FREESTANDING_RUNTIME)
+  });
+
+  let mut source = SourceMap::new(&inputs[0].1);
+  let generate = || decl::generate_program(units, &mut source, runtime, instrument);
+  let ir = timings.time("irgen", generate)?;
+  Ok((ir, source))
+}
 
-  decl::generate_program(ast)
+/// 内置运行时库的 IR，与 `libsysy` 的接口相同，单独编译为目标文件后与程序链接；不依赖 C 库，
+/// 其中的 `_start` 是程序的入口
+pub fn generate_runtime_ir(target: &Target) -> Result<Ir, Box<dyn std::error::Error>> {
+  Type::set_ptr_size(target.ptr_size());
+  let ast = parse_source(HOSTED_RUNTIME).unwrap();
+  let mut source = SourceMap::new(HOSTED_RUNTIME);
+  let runtime = Some((ast, HOSTED_RUNTIME));
+  decl::generate_program(vec![], &mut source, runtime, Instrumentation::default())
 }
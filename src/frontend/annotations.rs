@@ -0,0 +1,75 @@
+//! Koopa IR 表达不了、与 IR 一同交给后端的信息：内联汇编、分支预测、函数属性、`volatile` 变量与
+//! 局部数组的生存期。其中的值与函数只在生成它们的 [`Program`] 中有意义（编号在各线程中分别分配），
+//! 因此随 IR 保存在 [`Ir`] 中，每次编译各有一份。
+
+use std::collections::{HashMap, HashSet};
+use std::ops::{Deref, DerefMut, Range};
+
+use koopa::ir::dfg::DataFlowGraph;
+use koopa::ir::{Function, Program, Value, ValueKind};
+
+use super::decl::FuncAttrs;
+
+/// 生成的 Koopa IR 及其附带的信息；可当作 [`Program`] 使用
+pub struct Ir {
+  pub program: Program,
+  pub annotations: Annotations,
+}
+
+impl Deref for Ir {
+  type Target = Program;
+
+  fn deref(&self) -> &Program {
+    &self.program
+  }
+}
+
+impl DerefMut for Ir {
+  fn deref_mut(&mut self) -> &mut Program {
+    &mut self.program
+  }
+}
+
+/// 不是由 SysY 生成的 IR（如读入的 Koopa IR 文本）没有附带的信息
+impl From<Program> for Ir {
+  fn from(program: Program) -> Self {
+    Self {
+      program,
+      annotations: Annotations::default(),
+    }
+  }
+}
+
+#[derive(Debug, Default)]
+pub struct Annotations {
+  /// 内联汇编在 IR 中表示为对函数声明 `@__sysy_asm_N` 的调用：输入操作数为参数，
+  /// 输出操作数为返回值。这里记录各声明对应的汇编模板，由后端原样输出
+  pub inline_asm: HashMap<Function, String>,
+  /// `__builtin_expect` 给出的分支预测：条件分支是否可能转移到真分支
+  pub branch_hints: HashMap<Value, bool>,
+  /// 各函数的属性；没有属性的函数不在其中
+  pub func_attrs: HashMap<Function, FuncAttrs>,
+  /// `volatile` 变量的 `alloc` 与 `global_alloc`。对其中的读写（见 [`Annotations::is_volatile`]）
+  /// 都视为有副作用：后端不合并、删除或重排这些访存，其他输出中也标为 volatile
+  pub volatile: HashSet<Value>,
+  /// 块作用域中局部数组（Alloc）的生存期。块作用域按进入的先后编号，数组的生存期是进入与离开
+  /// 所在块时的编号 `start..end`；作用域相互嵌套当且仅当区间相互包含，区间不相交的两个数组不会
+  /// 同时存活，后端令它们共用栈上的空间。没有记录的局部变量在整个函数中存活
+  pub lifetimes: HashMap<Value, Range<u32>>,
+}
+
+impl Annotations {
+  /// 以 `ptr` 为地址的 `load`/`store` 是否访问 volatile 变量：`ptr` 是这一变量，或是由其经
+  /// `getelemptr`/`getptr` 得到的元素地址。经由指针（如数组参数）的访问不是 volatile 的
+  pub fn is_volatile(&self, dfg: &DataFlowGraph, ptr: Value) -> bool {
+    if ptr.is_global() {
+      return self.volatile.contains(&ptr);
+    }
+    match dfg.value(ptr).kind() {
+      ValueKind::GetElemPtr(gep) => self.is_volatile(dfg, gep.src()),
+      ValueKind::GetPtr(gp) => self.is_volatile(dfg, gp.src()),
+      ValueKind::Alloc(_) => self.volatile.contains(&ptr),
+      _ => false,
+    }
+  }
+}
@@ -1,70 +1,347 @@
+//! 语法树。节点都存放在 [`Ast`] 中，以 [`Id`] 互相引用；每个节点记录其在源代码中的范围。
+//!
+//! 节点按种类分别存放在连续的表中，解析时不再为每个节点单独分配内存；分析的结果也可以
+//! 以节点的 [`Id`] 为键记录在节点之外。
+//!
+//! `for` 循环、复合赋值、自增自减与条件表达式是在 SysY 之上扩展的语法，在语义分析与生成 IR 之前
+//! 展开为其余的节点。
+
+use std::any::TypeId;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
 use std::rc::Rc;
 
-pub type CompUnit = Vec<Decl>;
+pub use super::name::Name;
+
+/// 顶层的各个声明
+pub type CompUnit = Vec<Id<Decl>>;
+
+/// 源代码中的位置（字节偏移）
+pub type Pos = usize;
+
+/// 源代码中的范围：起止位置
+pub type Span = (Pos, Pos);
+
+/// [`Ast`] 中类型为 `T` 的节点
+pub struct Id<T> {
+  index: u32,
+  marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Id<T> {
+  /// 节点在同种节点中的序号，按解析完成的顺序
+  pub fn index(self) -> usize {
+    self.index as usize
+  }
+}
+
+impl<T> Clone for Id<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+  fn eq(&self, other: &Self) -> bool {
+    self.index == other.index
+  }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> Hash for Id<T> {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.index.hash(state);
+  }
+}
+
+impl<T> fmt::Debug for Id<T> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "#{}", self.index)
+  }
+}
+
+/// 节点的序号与种类：各种节点分别编号，以此区分任意种类的节点
+pub type NodeKey = (usize, TypeId);
+
+pub fn key<T: Node>(node: Id<T>) -> NodeKey {
+  (node.index(), TypeId::of::<T>())
+}
+
+/// 起始位置不在 `from` 之前时移动 `delta`
+fn shift_span(span: &mut Span, from: Pos, delta: isize) {
+  if span.0 >= from {
+    span.0 = span.0.wrapping_add_signed(delta);
+    span.1 = span.1.wrapping_add_signed(delta);
+  }
+}
+
+/// 同一种节点及其范围
+#[derive(Debug)]
+pub struct Arena<T> {
+  nodes: Vec<T>,
+  spans: Vec<Span>,
+}
+
+impl<T> Default for Arena<T> {
+  fn default() -> Self {
+    Self {
+      nodes: vec![],
+      spans: vec![],
+    }
+  }
+}
+
+/// 可以存放在 [`Ast`] 中的节点
+pub trait Node: Sized + 'static {
+  fn arena(ast: &Ast) -> &Arena<Self>;
+  fn arena_mut(ast: &mut Ast) -> &mut Arena<Self>;
+}
+
+macro_rules! arenas {
+  ($($field:ident: $ty:ty,)*) => {
+    #[derive(Debug, Default)]
+    struct Arenas {
+      $($field: Arena<$ty>,)*
+    }
+
+    impl Arenas {
+      /// 起始位置不在 `from` 之前的节点移动 `delta`
+      fn shift(&mut self, from: Pos, delta: isize) {
+        $(for span in &mut self.$field.spans {
+          shift_span(span, from, delta);
+        })*
+      }
+    }
+
+    $(impl Node for $ty {
+      fn arena(ast: &Ast) -> &Arena<Self> {
+        &ast.arenas.$field
+      }
+      fn arena_mut(ast: &mut Ast) -> &mut Arena<Self> {
+        &mut ast.arenas.$field
+      }
+    })*
+  };
+}
+
+arenas! {
+  decls: Decl,
+  declarators: Declarator,
+  initializers: Initializer,
+  stmts: Stmt,
+  exps: Exp,
+  assign_exps: AssignExp,
+  lor_exps: LOrExp,
+  land_exps: LAndExp,
+  eq_exps: EqExp,
+  rel_exps: RelExp,
+  add_exps: AddExp,
+  mul_exps: MulExp,
+  unary_exps: UnaryExp,
+  postfix_exps: PostfixExp,
+  primary_exps: PrimaryExp,
+}
+
+/// 一个源文件的语法树
+#[derive(Debug, Default)]
+pub struct Ast {
+  pub unit: CompUnit,
+  arenas: Arenas,
+  /// 增量解析（[`super::reparse`]）替换掉的源代码的字节数，其中的节点仍留在表中
+  pub(super) stale: usize,
+}
+
+impl Ast {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// 加入源代码中 `span` 处的节点
+  pub fn alloc<T: Node>(&mut self, node: T, span: Span) -> Id<T> {
+    let arena = T::arena_mut(self);
+    let index = u32::try_from(arena.nodes.len()).expect("too many syntax tree nodes");
+    arena.nodes.push(node);
+    arena.spans.push(span);
+    Id {
+      index,
+      marker: PhantomData,
+    }
+  }
+
+  /// 节点在源代码中的范围
+  pub fn span<T: Node>(&self, id: Id<T>) -> Span {
+    T::arena(self).spans[id.index()]
+  }
+
+  /// 节点的起始位置
+  pub fn pos<T: Node>(&self, id: Id<T>) -> Pos {
+    self.span(id).0
+  }
+
+  /// 起始位置不在 `from` 之前的所有节点在源代码中移动 `delta` 个字节
+  pub(super) fn shift(&mut self, from: Pos, delta: isize) {
+    self.arenas.shift(from, delta);
+    // 调用中函数名的范围记录在节点之中
+    for exp in &mut self.arenas.postfix_exps.nodes {
+      if let PostfixExp::Call(_, span, _) = exp {
+        shift_span(span, from, delta);
+      }
+    }
+  }
+}
+
+impl<T: Node> Index<Id<T>> for Ast {
+  type Output = T;
+
+  fn index(&self, id: Id<T>) -> &T {
+    &T::arena(self).nodes[id.index()]
+  }
+}
+
+impl<T: Node> IndexMut<Id<T>> for Ast {
+  fn index_mut(&mut self, id: Id<T>) -> &mut T {
+    &mut T::arena_mut(self).nodes[id.index()]
+  }
+}
 
 #[derive(Debug)]
 pub struct FuncDecl {
+  /// 写在返回类型之前的 `__attribute__((...))`，按出现的顺序
+  pub attrs: Vec<Attribute>,
   pub func_type: TypeSpec,
-  pub ident: String,
+  pub ident: Name,
   pub params: ParamList,
   pub body: Option<Block>,
 }
 
+/// 函数的属性，写法与 GCC 相同
+#[derive(Debug, Clone, PartialEq)]
+pub enum Attribute {
+  /// `noinline`：不内联到调用处。编译器本身不做内联，只随 LLVM IR 与 C 输出
+  NoInline,
+  /// `always_inline`：总是内联到调用处。与 `noinline` 相同，只随 LLVM IR 与 C 输出
+  AlwaysInline,
+  /// `noreturn`：调用之后不会返回，其后的代码不可达
+  NoReturn,
+  /// `section("名字")`：函数的代码放在该节中
+  Section(String),
+}
+
+impl Attribute {
+  /// 由属性的名字与括号中的参数得到属性；名字也可以写作 `__noinline__` 的形式。
+  /// 名字未知或参数不对时为 `None`
+  pub fn new(name: &str, args: Option<Vec<String>>) -> Option<Self> {
+    let name = name
+      .strip_prefix("__")
+      .and_then(|name| name.strip_suffix("__"))
+      .unwrap_or(name);
+    match (name, args.as_deref()) {
+      ("noinline", None) => Some(Attribute::NoInline),
+      ("always_inline", None) => Some(Attribute::AlwaysInline),
+      ("noreturn", None) => Some(Attribute::NoReturn),
+      ("section", Some([section])) => Some(Attribute::Section(section.clone())),
+      _ => None,
+    }
+  }
+
+  /// 属性的名字
+  pub fn name(&self) -> &'static str {
+    match self {
+      Attribute::NoInline => "noinline",
+      Attribute::AlwaysInline => "always_inline",
+      Attribute::NoReturn => "noreturn",
+      Attribute::Section(_) => "section",
+    }
+  }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum TypeSpec {
   Void,
   Int,
 }
 
-pub type ParamList = Vec<Box<Declarator>>;
+pub type ParamList = Vec<Param>;
+
+/// 参数的类型与声明符；参数的类型只能是 `int`，`void` 由语义分析报告
+pub type Param = (TypeSpec, Id<Declarator>);
 
 pub type Block = Vec<BlockItem>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum BlockItem {
-  Decl(Decl),
-  Stmt(Stmt),
+  Decl(Id<Decl>),
+  Stmt(Id<Stmt>),
 }
 
 #[derive(Debug)]
 pub enum Stmt {
-  Exp(Option<Box<Exp>>),
-  Block(Box<Block>),
-  If(Box<Exp>, Box<Stmt>, Option<Box<Stmt>>),
-  While(Box<Exp>, Box<Stmt>),
+  Exp(Option<Id<Exp>>),
+  Block(Block),
+  If(Id<Exp>, Id<Stmt>, Option<Id<Stmt>>),
+  While(Id<Exp>, Id<Stmt>),
+  /// `for (初始化; 条件; 步进) 循环体`：初始化是声明或表达式语句（可以为空）
+  For(BlockItem, Option<Id<Exp>>, Option<Id<Exp>>, Id<Stmt>),
   Break,
   Continue,
-  Return(Option<Box<Exp>>),
+  Return(Option<Id<Exp>>),
+  Asm(AsmStmt),
 }
 
+/// 内联汇编 `asm("模板" : 输出操作数 : 输入操作数)`。操作数为（约束，表达式）
+#[derive(Debug)]
+pub struct AsmStmt {
+  pub template: String,
+  pub outputs: Vec<AsmOperand>,
+  pub inputs: Vec<AsmOperand>,
+}
+
+pub type AsmOperand = (String, Id<AssignExp>);
+
 #[derive(Debug)]
 pub enum Exp {
-  Assign(Box<AssignExp>),
-  Comma(Box<Exp>, Box<AssignExp>),
+  Assign(Id<AssignExp>),
+  Comma(Id<Exp>, Id<AssignExp>),
 }
 
 #[derive(Debug)]
 pub enum AssignExp {
-  LOr(Box<LOrExp>),
-  Assign(Box<LOrExp>, Box<AssignExp>),
+  LOr(Id<LOrExp>),
+  Assign(Id<LOrExp>, Id<AssignExp>),
+  /// `lhs op= rhs`
+  Compound(Id<LOrExp>, CompoundOp, Id<AssignExp>),
+  /// `cond ? then : else`。与 C++ 相同，`:` 之后是赋值表达式
+  Cond(Id<LOrExp>, Id<Exp>, Id<AssignExp>),
+}
+
+/// 复合赋值中的运算
+#[derive(Debug, Clone, Copy)]
+pub enum CompoundOp {
+  Add(AddOp),
+  Mul(MulOp),
 }
 
 #[derive(Debug)]
 pub enum LOrExp {
-  And(Box<LAndExp>),
-  Or(Box<LOrExp>, Box<LAndExp>),
+  And(Id<LAndExp>),
+  Or(Id<LOrExp>, Id<LAndExp>),
 }
 
 #[derive(Debug)]
 pub enum LAndExp {
-  Eq(Box<EqExp>),
-  And(Box<LAndExp>, Box<EqExp>),
+  Eq(Id<EqExp>),
+  And(Id<LAndExp>, Id<EqExp>),
 }
 
 #[derive(Debug)]
 pub enum EqExp {
-  Rel(Box<RelExp>),
-  Eq(Box<EqExp>, EqOp, Box<RelExp>),
+  Rel(Id<RelExp>),
+  Eq(Id<EqExp>, EqOp, Id<RelExp>),
 }
 
 #[derive(Debug)]
@@ -75,8 +352,8 @@ pub enum EqOp {
 
 #[derive(Debug)]
 pub enum RelExp {
-  Add(Box<AddExp>),
-  Rel(Box<RelExp>, RelOp, Box<AddExp>),
+  Add(Id<AddExp>),
+  Rel(Id<RelExp>, RelOp, Id<AddExp>),
 }
 
 #[derive(Debug)]
@@ -89,11 +366,11 @@ pub enum RelOp {
 
 #[derive(Debug)]
 pub enum AddExp {
-  Mul(Box<MulExp>),
-  Add(Box<AddExp>, AddOp, Box<MulExp>),
+  Mul(Id<MulExp>),
+  Add(Id<AddExp>, AddOp, Id<MulExp>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum AddOp {
   Plus,
   Minus,
@@ -101,11 +378,11 @@ pub enum AddOp {
 
 #[derive(Debug)]
 pub enum MulExp {
-  Unary(Box<UnaryExp>),
-  Mul(Box<MulExp>, MulOp, Box<UnaryExp>),
+  Unary(Id<UnaryExp>),
+  Mul(Id<MulExp>, MulOp, Id<UnaryExp>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub enum MulOp {
   Multiply,
   Divide,
@@ -114,10 +391,12 @@ pub enum MulOp {
 
 #[derive(Debug)]
 pub enum UnaryExp {
-  Postfix(Box<PostfixExp>),
-  Address(Box<UnaryExp>),
-  Deref(Box<UnaryExp>),
-  Op(UnaryOp, Box<UnaryExp>),
+  Postfix(Id<PostfixExp>),
+  Address(Id<UnaryExp>),
+  Deref(Id<UnaryExp>),
+  Op(UnaryOp, Id<UnaryExp>),
+  /// 前缀的 `++`、`--`
+  Step(StepOp, Id<UnaryExp>),
 }
 
 #[derive(Debug)]
@@ -129,16 +408,25 @@ pub enum UnaryOp {
 
 #[derive(Debug)]
 pub enum PostfixExp {
-  Primary(PrimaryExp),
-  Subscript(Box<PostfixExp>, Box<Exp>),
-  Call(String, Vec<Box<AssignExp>>),
+  Primary(Id<PrimaryExp>),
+  Subscript(Id<PostfixExp>, Id<Exp>),
+  /// 被调用的函数名、函数名在源代码中的范围与实参
+  Call(Name, Span, Vec<Id<AssignExp>>),
+  /// 后缀的 `++`、`--`
+  Step(Id<PostfixExp>, StepOp),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum StepOp {
+  Increment,
+  Decrement,
 }
 
 #[derive(Debug)]
 pub enum PrimaryExp {
   Num(i32),
-  Ident(String),
-  Paren(Box<Exp>),
+  Ident(Name),
+  Paren(Id<Exp>),
 }
 
 #[derive(Debug)]
@@ -150,23 +438,49 @@ pub enum Decl {
 #[derive(Debug)]
 pub struct DeclaratorAndInitializerList {
   pub is_const: bool,
+  /// `volatile` 变量：对它（及其中元素）的每次读写都保留，不被合并、删除或重排
+  pub is_volatile: bool,
   pub ty: TypeSpec,
   pub list: Vec<DeclaratorAndInitializer>,
 }
 
-pub type DeclaratorAndInitializer = (Box<Declarator>, Option<Rc<Initializer>>);
+pub type DeclaratorAndInitializer = (Id<Declarator>, Option<Id<Initializer>>);
 
 #[derive(Debug)]
 pub enum Declarator {
-  Ident(String),
-  Pointer(Box<Declarator>),
-  Array(Box<Declarator>, Box<Exp>),
+  Ident(Name),
+  Pointer(Id<Declarator>),
+  Array(Id<Declarator>, Id<Exp>),
 }
 
-pub type Initializer = InitializerLike<Box<AssignExp>>;
+#[derive(Debug)]
+pub enum Initializer {
+  Simple(Id<AssignExp>),
+  Aggregate(Vec<Id<Initializer>>),
+}
 
+/// 初始化器求值的结果，与 [`Initializer`] 的结构相同
 #[derive(Debug)]
 pub enum InitializerLike<T> {
   Simple(T),
   Aggregate(Vec<Rc<InitializerLike<T>>>),
 }
+
+/// 去掉字符串字面量的引号并处理转义序列
+pub fn unescape(lit: &str) -> String {
+  let mut result = String::new();
+  let mut chars = lit[1..lit.len() - 1].chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      result.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => result.push('\n'),
+      Some('t') => result.push('\t'),
+      Some(c) => result.push(c),
+      None => {}
+    }
+  }
+  result
+}
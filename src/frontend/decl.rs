@@ -1,35 +1,134 @@
-use koopa::ir::builder::{BasicBlockBuilder, GlobalInstBuilder, LocalInstBuilder, ValueBuilder};
+use koopa::ir::builder::{
+  BasicBlockBuilder, GlobalInstBuilder, LocalBuilder, LocalInstBuilder, ValueBuilder,
+};
 use koopa::ir::dfg::DataFlowGraph;
-use koopa::ir::layout::{InstList, Layout};
+use koopa::ir::layout::Layout;
 use koopa::ir::{BasicBlock, Function, FunctionData, Program, Type, TypeKind, Value, ValueKind};
+use log::trace;
 use std::borrow::BorrowMut;
+use std::collections::{HashMap, HashSet};
 
-use super::ast::{CompUnit, Decl, Declarator, FuncDecl, InitializerLike, TypeSpec};
-use super::error::CompileError;
+use super::annotations::{Annotations, Ir};
+use super::ast::{Ast, Attribute, Decl, FuncDecl, InitializerLike, Name, ParamList, Pos, TypeSpec};
+use super::error::{CompileError, FileError};
 #[allow(unused_imports)]
 use super::error::{PushKeyError, UnimplementedError};
+use super::instrument::{self, Instrumentation};
+use super::sanitize::{self, Sanitizers};
+use super::source::SourceMap;
 use super::stmt::{self, get_layout};
 use super::symbol::ConstValue;
-use super::symbol::{Symbol, SymbolTable};
+use super::symbol::{EvalMemo, GlobalSymbols, Scope, Symbol, SymbolTable};
+use crate::analysis::Cfg;
 use crate::frontend::expr::ty::{GetType, SysyType};
 use crate::Result;
 
+/// 函数的属性（`__attribute__`），合并了函数的各次声明
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FuncAttrs {
+  /// `noinline` 与 `always_inline` 只影响 `--emit=llvm`、`--emit=c` 的输出，其余后端不做内联
+  pub noinline: bool,
+  pub always_inline: bool,
+  pub noreturn: bool,
+  /// 代码所在的节；`None` 即 `.text`
+  pub section: Option<String>,
+}
+
+impl FuncAttrs {
+  /// 加上函数 `name` 的一次声明中的属性。不能既是 `noinline` 又是 `always_inline`，
+  /// 各次声明也不能指定不同的节
+  pub fn merge(
+    &mut self,
+    name: &Name,
+    attrs: &[Attribute],
+  ) -> std::result::Result<(), CompileError> {
+    for attr in attrs {
+      match attr {
+        Attribute::NoInline => self.noinline = true,
+        Attribute::AlwaysInline => self.always_inline = true,
+        Attribute::NoReturn => self.noreturn = true,
+        Attribute::Section(section) => match &self.section {
+          Some(previous) if previous != section => {
+            let conflict = format!("节 \"{}\" 与 \"{}\"", previous, section);
+            return Err(CompileError::ConflictingAttributes(name.into(), conflict));
+          }
+          _ => self.section = Some(section.clone()),
+        },
+      }
+    }
+    if self.noinline && self.always_inline {
+      let conflict = "noinline 与 always_inline".into();
+      return Err(CompileError::ConflictingAttributes(name.into(), conflict));
+    }
+    Ok(())
+  }
+}
+
+/// 一次编译（可能有多个源文件）共有的状态；各次编译互不影响
+#[derive(Default)]
+pub struct CompilationContext {
+  pub globals: GlobalSymbols,
+  /// 插入的运行时检查（`-fsanitize=`）
+  pub sanitize: Sanitizers,
+  /// 是否为函数调用计数（`-finstrument-functions`）
+  pub profile: bool,
+  /// 各函数的名字与其调用计数器，按定义的顺序
+  pub call_counters: Vec<(Name, Value)>,
+  /// 随 IR 一同输出的信息
+  pub annotations: Annotations,
+  /// 调用不返回的函数之后开始的基本块，生成完毕后删去其中不可达的部分
+  pub after_noreturn: HashSet<BasicBlock>,
+}
+
 pub struct GenerateContext<'a> {
+  /// 正在生成的源文件的语法树
+  pub ast: &'a Ast,
   pub program: &'a mut Program,
+  pub compilation: &'a mut CompilationContext,
   pub func: Function,
   pub bb: Option<BasicBlock>,
   pub symbol: SymbolTable,
 
+  /// 记录指令来源行的映射，以及当前所在行
+  source: &'a mut SourceMap,
+  line: usize,
+
   next_bb_no: i32,
 
   /// 循环中 break/continue 跳转位置
   pub loop_jump_pt: Vec<(BasicBlock, BasicBlock)>,
+
+  /// 由 `__builtin_expect` 得知的条件值的期望：是否期望非零
+  pub expected: HashMap<Value, bool>,
+
+  /// 尚未离开的块作用域：进入时的编号与其中声明的数组，以及下一个作用域的编号
+  /// （见 [`Annotations::lifetimes`]）
+  scopes: Vec<(u32, Vec<Value>)>,
+  next_scope: u32,
+
+  memo: EvalMemo,
 }
 
-fn generate_param_list(params: &Vec<Box<Declarator>>) -> Result<Vec<(Option<String>, Type)>> {
+/// 函数 `func` 的各参数在 IR 中的名字与类型；参数不能重名，不能与函数同名，也不能是 `void` 类型
+fn generate_param_list(
+  func: &Name,
+  params: &ParamList,
+  scope: Scope,
+) -> Result<Vec<(Option<String>, Type)>> {
   let mut ir = vec![];
-  for param in params {
-    let (ty, name) = SysyType::parse(param.as_ref(), None)?;
+  let mut names = vec![];
+  for &(spec, param) in params {
+    let (ty, name) = SysyType::parse(param, scope)?;
+    if spec == TypeSpec::Void {
+      Err(CompileError::VoidParameter(name.to_string()))?;
+    }
+    if name == *func {
+      Err(CompileError::ParameterShadowsFunction(name.to_string()))?;
+    }
+    if names.contains(&name) {
+      Err(CompileError::DuplicateParameter(name.to_string()))?;
+    }
+    names.push(name.clone());
     let mut ir_ty = ty.to_ir();
     // Perform array-to-pointer conversion
     if let TypeKind::Array(ty, _) = ir_ty.kind() {
@@ -41,20 +140,35 @@ fn generate_param_list(params: &Vec<Box<Declarator>>) -> Result<Vec<(Option<Stri
 }
 
 impl<'a> GenerateContext<'a> {
-  pub fn new(program: &'a mut Program, func_ast: &FuncDecl) -> Result<Self> {
+  /// 为 `ast` 中位于 `pos` 的函数声明 `func_ast` 生成 IR 的上下文
+  pub fn new(
+    program: &'a mut Program,
+    compilation: &'a mut CompilationContext,
+    source: &'a mut SourceMap,
+    ast: &'a Ast,
+    func_ast: &FuncDecl,
+    pos: Pos,
+  ) -> Result<Self> {
     let func_ir_name = format!("@{}", func_ast.ident);
-    let func_ir_param = generate_param_list(&func_ast.params)?;
+    let scope = Scope::global(ast, &compilation.globals);
+    let func_ir_param = generate_param_list(&func_ast.ident, &func_ast.params, scope)?;
     let func_ir_type = match func_ast.func_type {
       TypeSpec::Int => Type::get_i32(),
       TypeSpec::Void => Type::get_unit(),
     };
 
     // Koopa IR 不允许重复声明函数。移除之前的声明。如果函数已有定义，则在符号表插入阶段报错。
-    let func = if let Some((&f, _)) = program
+    let func = if let Some((&f, fd)) = program
       .funcs()
       .iter()
       .find(|(_, fd)| fd.name() == func_ir_name)
     {
+      let params = func_ir_param.iter().map(|(_, ty)| ty.clone()).collect();
+      if *fd.ty() != Type::get_function(params, func_ir_type) {
+        Err(CompileError::ConflictingDeclaration(
+          func_ast.ident.to_string(),
+        ))?;
+      }
       f
     } else {
       program.new_func(FunctionData::with_param_names(
@@ -63,42 +177,72 @@ impl<'a> GenerateContext<'a> {
         func_ir_type,
       ))
     };
+    if !func_ast.attrs.is_empty() {
+      let attrs = &mut compilation.annotations.func_attrs;
+      attrs
+        .entry(func)
+        .or_default()
+        .merge(&func_ast.ident, &func_ast.attrs)?;
+    }
 
     let mut this = Self {
+      ast,
       program: program,
+      compilation,
       func,
       bb: None,
       symbol: SymbolTable::new(),
+      line: source.line_at(pos),
+      source,
       next_bb_no: 0,
       loop_jump_pt: vec![],
+      expected: HashMap::new(),
+      scopes: vec![],
+      next_scope: 0,
+      memo: EvalMemo::default(),
     };
 
     if func_ast.body.is_some() {
+      this.source.record_func(func, this.line);
       // %entry basic block
       let entry = this.add_bb("entry")?;
       this.bb = Some(entry);
 
       // Store parameters to local variable
-      for (i, param) in func_ast.params.iter().enumerate() {
-        let (ty, name) = SysyType::parse(param.as_ref(), None)?;
+      for (i, &(_, param)) in func_ast.params.iter().enumerate() {
+        let scope = Scope::global(ast, &this.compilation.globals);
+        let (ty, name) = SysyType::parse(param, scope)?;
         let param = this.program.func(this.func).params()[i];
         let param_type = this.dfg().value(param).ty().clone();
 
         let alloc = this.dfg().new_value().alloc(param_type);
         let store = this.dfg().new_value().store(param, alloc);
 
-        this.add_inst(alloc)?;
         this.dfg().set_value_name(alloc, Some(format!("%{}", name)));
-        this.add_inst(store)?;
+        this.add_insts(&[alloc, store])?;
 
-        if !this.symbol.insert(&name, Symbol::Var(ty, alloc)) {
+        if !this.symbol.insert(name.clone(), Symbol::Var(ty, alloc)) {
           Err(CompileError::Redefinition(name.into()))?;
         }
       }
+      if this.compilation.profile {
+        instrument::count_call(&mut this, func_ast.ident.clone())?;
+      }
     }
     Ok(this)
   }
 
+  /// 在函数中查找符号的作用域
+  pub fn scope(&self) -> Scope<'_, 'a> {
+    Scope {
+      ast: self.ast,
+      globals: &self.compilation.globals,
+      locals: Some(&self.symbol),
+      memo: Some(&self.memo),
+      calls: None,
+    }
+  }
+
   pub fn dfg(&mut self) -> &mut DataFlowGraph {
     self.program.func_mut(self.func).dfg_mut()
   }
@@ -106,6 +250,28 @@ impl<'a> GenerateContext<'a> {
     self.program.func_mut(self.func).layout_mut()
   }
 
+  /// 进入一个块作用域
+  pub fn enter_scope(&mut self) {
+    self.scopes.push((self.next_scope, vec![]));
+    self.next_scope += 1;
+  }
+
+  /// 离开当前的块作用域，记录其中各数组的生存期
+  pub fn exit_scope(&mut self) {
+    let (start, arrays) = self.scopes.pop().unwrap();
+    let lifetimes = &mut self.compilation.annotations.lifetimes;
+    for alloc in arrays {
+      lifetimes.insert(alloc, start..self.next_scope);
+    }
+  }
+
+  /// 块作用域中声明的数组 `alloc` 在离开作用域时记录生存期；函数体最外层的不记录
+  pub fn add_scoped_array(&mut self, alloc: Value) {
+    if let Some((_, arrays)) = self.scopes.last_mut() {
+      arrays.push(alloc);
+    }
+  }
+
   pub fn new_bb_set(&mut self) {
     self.next_bb_no = self.next_bb_no + 1;
   }
@@ -121,10 +287,6 @@ impl<'a> GenerateContext<'a> {
     Ok(bb)
   }
 
-  pub fn insts(&mut self, bb: BasicBlock) -> &mut InstList {
-    self.layout().bb_mut(bb).insts_mut()
-  }
-
   pub fn value_ty_kind(&self, value: Value) -> TypeKind {
     let val = self.program.func(self.func).dfg().values().get(&value);
     if let Some(val) = val {
@@ -133,31 +295,144 @@ impl<'a> GenerateContext<'a> {
     self.program.borrow_value(value).ty().kind().clone()
   }
 
+  /// 之后生成的指令来源于 `pos` 所在行
+  pub fn set_pos(&mut self, pos: Pos) {
+    self.line = self.source.line_at(pos);
+  }
+
+  /// 当前所在的源代码行
+  pub fn line(&self) -> usize {
+    self.line
+  }
+
   pub fn add_inst(&mut self, value: Value) -> Result<()> {
+    self.add_insts(&[value])
+  }
+
+  /// 依次把 `values` 插入当前基本块的末尾，只查找一次所在的函数
+  pub fn add_insts(&mut self, values: &[Value]) -> Result<()> {
+    for &value in values {
+      self.source.record(value, self.line);
+    }
     if self.bb.is_none() {
       self.new_bb_set();
       self.bb = Some(self.add_bb("unreachable")?);
     }
-    self
-      .insts(self.bb.unwrap())
-      .push_key_back(value)
-      .map_err(|k| {
-        let vd = self.dfg().value(k).clone();
+    let bb = self.bb.unwrap();
+    let func = self.program.func_mut(self.func);
+    for &value in values {
+      let insts = func.layout_mut().bb_mut(bb).insts_mut();
+      insts.push_key_back(value).map_err(|k| {
+        let vd = func.dfg().value(k).clone();
         PushKeyError(Box::new(vd))
       })?;
+    }
     Ok(())
   }
 
+  /// 以 `build` 创建一条指令并插入当前基本块，如 `context.insert(|b| b.load(ptr))?`
+  pub fn insert(&mut self, build: impl FnOnce(LocalBuilder) -> Value) -> Result<Value> {
+    let value = build(self.dfg().new_value());
+    self.add_inst(value)?;
+    Ok(value)
+  }
+
+  /// 把 `value` 保存到 `dest` 所指处；类型不符时报错，而不是在创建指令时 panic
+  pub fn store(&mut self, value: Value, dest: Value) -> Result<Value> {
+    match self.value_ty_kind(dest) {
+      TypeKind::Pointer(base) if *base.kind() == self.value_ty_kind(value) => {
+        self.insert(|b| b.store(value, dest))
+      }
+      _ => Err(CompileError::TypeMismatch(
+        "左值",
+        "".into(),
+        "不匹配的类型",
+      ))?,
+    }
+  }
+
+  /// 以 `cond` 为条件的分支；条件的期望已知时记录分支预测
+  pub fn branch(
+    &mut self,
+    cond: Value,
+    true_bb: BasicBlock,
+    false_bb: BasicBlock,
+  ) -> Result<Value> {
+    let branch = self.dfg().new_value().branch(cond, true_bb, false_bb);
+    if let Some(&likely) = self.expected.get(&cond) {
+      self
+        .compilation
+        .annotations
+        .branch_hints
+        .insert(branch, likely);
+    }
+    Ok(branch)
+  }
+
   pub fn switch_bb(&mut self, final_inst: Value, new_bb: Option<BasicBlock>) -> Result<()> {
     self.add_inst(final_inst)?;
     self.bb = new_bb;
     Ok(())
   }
+
+  /// 其后的代码不可达（调用了不返回的函数）：以返回结束当前基本块，返回值为 0。
+  /// 之后的指令放在新的块中，生成完毕后删去（见 [`remove_unreachable`]）
+  pub fn end_unreachable(&mut self) -> Result<()> {
+    let returns_i32 = match self.program.func(self.func).ty().kind() {
+      TypeKind::Function(_, ret) => Type::is_i32(ret),
+      _ => false,
+    };
+    let ret = if returns_i32 {
+      let zero = self.dfg().new_value().integer(0);
+      self.dfg().new_value().ret(Some(zero))
+    } else {
+      self.dfg().new_value().ret(None)
+    };
+    self.new_bb_set();
+    let after = self.add_bb("unreachable")?;
+    self.compilation.after_noreturn.insert(after);
+    self.switch_bb(ret, Some(after))
+  }
 }
 
-pub fn generate_program(ast: CompUnit) -> Result<Program> {
-  // 参考 https://github.com/pku-minic/sysy-runtime-lib/blob/master/src/sysy.h
-  let prelude = r#"
+/// 一个源文件
+pub struct Unit<'a> {
+  pub ast: Ast,
+  /// 文件名，用于指明错误所在的文件
+  pub name: &'a str,
+  pub text: &'a str,
+}
+
+/// 共有 `count` 个源文件时，错误带上出错的文件名（见 [`FileError`]）
+pub fn with_file_name(
+  e: Box<dyn std::error::Error>,
+  name: &str,
+  count: usize,
+) -> Box<dyn std::error::Error> {
+  if count > 1 {
+    Box::new(FileError {
+      file: name.into(),
+      error: e,
+    })
+  } else {
+    e
+  }
+}
+
+/// 生成整个程序的 IR，各源文件的全局符号合并在一起。`source` 只记录第一个源文件。
+/// `runtime` 为裸机运行（`--freestanding`）时以 SysY 实现的库函数及其源代码，取代运行时库的声明；
+/// 启用了 `instrument` 中的插桩时另生成其诊断与报告的例程
+pub fn generate_program(
+  units: Vec<Unit>,
+  source: &mut SourceMap,
+  runtime: Option<(Ast, &str)>,
+  instrument: Instrumentation,
+) -> Result<Ir> {
+  let mut compilation = CompilationContext::default();
+  let mut program = match runtime {
+    None => {
+      // 参考 https://github.com/pku-minic/sysy-runtime-lib/blob/master/src/sysy.h
+      let prelude = r#"
 decl @getint(): i32
 decl @getch(): i32
 decl @getarray(*i32): i32
@@ -167,41 +442,110 @@ decl @putarray(i32, *i32): i32
 decl @starttime(): i32
 decl @stoptime(): i32
 "#;
-  let driver = koopa::front::Driver::from(prelude);
-  let mut program = driver.generate_program().unwrap();
-  for (f, fd) in program.funcs() {
-    let name = &fd.name()[1..];
-    if !SymbolTable::insert_global_def(name, Symbol::Func(SysyType::from_ir(fd.ty()), *f)) {
-      Err(CompileError::Redefinition(name.to_string()))?;
+      let driver = koopa::front::Driver::from(prelude);
+      let program = driver.generate_program().unwrap();
+      for (f, fd) in program.funcs() {
+        let name = Name::intern(&fd.name()[1..]);
+        let symbol = Symbol::Func(SysyType::from_ir(fd.ty()), *f);
+        if !compilation.globals.insert_def(name.clone(), symbol) {
+          Err(CompileError::Redefinition(name.to_string()))?;
+        }
+      }
+      program
     }
+    Some((runtime, text)) => {
+      let mut program = Program::new();
+      let source = &mut SourceMap::new(text);
+      generate_decls(&runtime, &mut program, &mut compilation, source)?;
+      program
+    }
+  };
+  let runtimes = [
+    (instrument.any(), instrument::TRAP_RUNTIME),
+    (instrument.sanitize.any(), sanitize::RUNTIME),
+    (instrument.profile, instrument::PROFILE_RUNTIME),
+    (instrument.coverage, instrument::COVERAGE_RUNTIME),
+  ];
+  for (_, text) in runtimes.into_iter().filter(|(enabled, _)| *enabled) {
+    let ast = super::parse_source(text).unwrap();
+    let source = &mut SourceMap::new(text);
+    generate_decls(&ast, &mut program, &mut compilation, source)?;
+  }
+  // 这些例程本身不做检查，也不计数
+  compilation.sanitize = instrument.sanitize;
+  compilation.profile = instrument.profile;
+  let count = units.len();
+  for (i, unit) in units.iter().enumerate() {
+    let result = if i == 0 {
+      generate_decls(&unit.ast, &mut program, &mut compilation, source)
+    } else {
+      let source = &mut SourceMap::new(unit.text);
+      generate_decls(&unit.ast, &mut program, &mut compilation, source)
+    };
+    result.map_err(|e| with_file_name(e, unit.name, count))?;
   }
 
-  for decl in &ast {
-    match decl {
+  for (_, fd) in program.funcs_mut().iter_mut() {
+    add_extra_ret(fd);
+    // 覆盖率报告中不可达的代码仍列为未执行
+    if !instrument.coverage {
+      remove_unreachable(fd, &compilation.after_noreturn);
+    }
+  }
+  if instrument.timing {
+    instrument::add_timing(&mut program, &compilation)?;
+  }
+  if instrument.coverage {
+    instrument::add_coverage(&mut program, &compilation, source)?;
+  }
+  if instrument.profile {
+    instrument::add_report(&mut program, &compilation)?;
+  }
+
+  Ok(Ir {
+    program,
+    annotations: compilation.annotations,
+  })
+}
+
+fn generate_decls(
+  ast: &Ast,
+  program: &mut Program,
+  compilation: &mut CompilationContext,
+  source: &mut SourceMap,
+) -> Result<()> {
+  for &id in &ast.unit {
+    match &ast[id] {
       Decl::Func(decl) => {
-        let name = &decl.ident;
-        let mut context = GenerateContext::new(&mut program, &decl)?;
+        let name = decl.ident.clone();
+        let pos = ast.pos(id);
+        let mut context = GenerateContext::new(program, compilation, source, ast, decl, pos)?;
+        let ty = decl.get_type(Scope::global(ast, &context.compilation.globals))?;
+        let symbol = Symbol::Func(ty, context.func);
 
         if let Some(block) = &decl.body {
           // Function definition
-          if !SymbolTable::insert_global_def(name, Symbol::Func(decl.get_type(None)?, context.func))
-          {
-            Err(CompileError::Redefinition(decl.ident.clone()))?;
+          if !context.compilation.globals.insert_def(name.clone(), symbol) {
+            Err(CompileError::Redefinition(name.into()))?;
           }
           for i in block.iter() {
             stmt::generate(i, &mut context)?;
           }
         } else {
           // Function declaration
-          SymbolTable::insert_global_decl(name, Symbol::Func(decl.get_type(None)?, context.func));
+          context.compilation.globals.insert_decl(name, symbol);
         }
       }
       Decl::Var(declaration) => {
         if declaration.ty == TypeSpec::Void {
           Err(CompileError::IllegalVoid)?;
         }
-        for (decl, init) in &declaration.list {
-          let (ty, name) = SysyType::parse(decl.as_ref(), None)?;
+        for &(decl, init) in &declaration.list {
+          let scope = Scope::global(ast, &compilation.globals);
+          let (ty, name) = SysyType::parse(decl, scope)?;
+          if declaration.is_volatile && (declaration.is_const || !ty.is_int_or_array()) {
+            Err(CompileError::IllegalVolatile(name.to_string()))?;
+          }
           if declaration.is_const {
             // 全局常量声明
             if matches!(ty, SysyType::Pointer(_)) {
@@ -209,61 +553,67 @@ decl @stoptime(): i32
                 "不支持指向常量的指针（不支持 ODR-使用常量）。".into(),
               ))?;
             }
-            let init = init
-              .as_ref()
-              .ok_or(CompileError::InitializerRequired(name.into()))?;
+            let init = init.ok_or(CompileError::InitializerRequired(name.to_string()))?;
             // 对初始化器求值；若非常量表达式报错
-            let const_value = match init.eval(None) {
+            let const_value = match ast[init].eval(scope) {
               Err(e) => Err(e.to_compile_error("全局常量初始化器"))?,
               Ok(exp) => match &exp {
                 InitializerLike::Simple(exp) => ConstValue::int(*exp),
                 InitializerLike::Aggregate(_) => {
                   let size = ty.get_array_size();
-                  let layout = get_layout(&size, &exp, &mut || 0)?;
+                  let layout = get_layout(&size, &exp)?;
                   ConstValue::from(ty, layout)
                 }
               },
             };
-            if !SymbolTable::insert_global_def(name, Symbol::Const(const_value)) {
-              Err(CompileError::Redefinition(name.into()))?;
+            if !compilation
+              .globals
+              .insert_def(name.clone(), Symbol::Const(const_value))
+            {
+              Err(CompileError::Redefinition(name.to_string()))?;
             }
           } else {
             // 全局变量声明
             let value = match init {
               // 对初始化器求值，转换为 IR
-              Some(init) => match init.eval(None) {
+              Some(init) => match ast[init].eval(scope) {
                 Err(e) => Err(e.to_compile_error("全局变量初始化器"))?,
                 Ok(exp) => match &exp {
                   InitializerLike::Simple(int) => program.new_value().integer(*int),
                   InitializerLike::Aggregate(_) => {
                     let size = ty.get_array_size();
-                    let layout = get_layout(&size, &exp, &mut || 0)?;
-                    // println!("{:#?}", &layout);
+                    let layout = get_layout(&size, &exp)?;
+                    trace!("layout of {}: {:?}", name, layout);
                     let const_value = ConstValue::from(ty.clone(), layout);
-                    const_value.to_ir(&mut program)
+                    const_value.to_ir(program)
                   }
                 },
               },
               None => program.new_value().zero_init(ty.to_ir()),
             };
             let alloc = program.new_value().global_alloc(value);
+            if declaration.is_volatile {
+              compilation.annotations.volatile.insert(alloc);
+            }
             // https://gitlab.eduxiji.net/pku-minic/QA-2022s/-/issues/1
-            let ir_name = format!("%{}", if name == "init" { "glb_var_init" } else { name });
+            let ir_name = match name.as_str() {
+              "init" => "glb_var_init",
+              name => name,
+            };
+            let ir_name = format!("%{}", ir_name);
             program.borrow_mut().set_value_name(alloc, Some(ir_name));
-            if !SymbolTable::insert_global_def(&name, Symbol::Var(ty, alloc)) {
-              Err(CompileError::Redefinition(name.into()))?;
+            if !compilation
+              .globals
+              .insert_def(name.clone(), Symbol::Var(ty, alloc))
+            {
+              Err(CompileError::Redefinition(name.to_string()))?;
             }
           }
         }
       }
     }
   }
-
-  for (_, fd) in program.funcs_mut().iter_mut() {
-    add_extra_ret(fd);
-  }
-
-  Ok(program)
+  Ok(())
 }
 
 /// Add `ret` value for bbs not ends with `ret`
@@ -300,22 +650,91 @@ fn add_extra_ret(fd: &mut FunctionData) {
   }
 }
 
+/// 删去调用不返回的函数之后的代码：从 `after_noreturn` 中的块出发，沿转移（正向或反向）
+/// 经过的从入口不可达的基本块，如其后的循环中 `break` 之后的块也在其中；`return`、`break`
+/// 等之后与这些块无关的不可达块不做改动。不可达的块中的值被可达的指令使用时
+/// （如在不可达处声明、其后又使用的变量）不做改动
+fn remove_unreachable(fd: &mut FunctionData, after_noreturn: &HashSet<BasicBlock>) {
+  let cfg = Cfg::new(fd);
+  let reachable: HashSet<_> = cfg.reverse_postorder().into_iter().collect();
+  let mut visited = HashSet::new();
+  let mut stack: Vec<_> = cfg
+    .blocks()
+    .iter()
+    .copied()
+    .filter(|bb| after_noreturn.contains(bb))
+    .collect();
+  while let Some(bb) = stack.pop() {
+    if !reachable.contains(&bb) && visited.insert(bb) {
+      stack.extend(cfg.succs(bb).chain(cfg.preds(bb)));
+    }
+  }
+  let dead: Vec<_> = cfg
+    .blocks()
+    .iter()
+    .copied()
+    .filter(|bb| visited.contains(bb))
+    .collect();
+  let mut values = vec![];
+  for &bb in &dead {
+    values.extend(fd.layout().bbs()[&bb].insts().keys().copied());
+  }
+  let dead_values: HashSet<_> = values.iter().copied().collect();
+  let used = |value: &Value| {
+    let used_by = fd.dfg().value(*value).used_by();
+    used_by.iter().any(|user| !dead_values.contains(user))
+  };
+  if values.iter().any(used) {
+    return;
+  }
+
+  for &bb in &dead {
+    let (_, mut node) = fd.layout_mut().bbs_mut().remove(&bb).unwrap();
+    while node.insts_mut().pop_back().is_some() {}
+  }
+  // 先删去使用者，再删去被使用的值
+  while !values.is_empty() {
+    values.retain(|&value| {
+      let unused = fd.dfg().value(value).used_by().is_empty();
+      if unused {
+        fd.dfg_mut().remove_value(value);
+      }
+      !unused
+    });
+  }
+  for bb in dead {
+    fd.dfg_mut().remove_bb(bb);
+  }
+}
+
 trait ToIr {
   fn to_ir(&self, program: &mut Program) -> Value;
 }
 
 impl ToIr for ConstValue {
   fn to_ir(&self, program: &mut Program) -> Value {
-    match self.ty {
-      SysyType::Int => program.new_value().integer(self.data[0]),
-      SysyType::Array(_, len) => {
-        let mut values = vec![];
-        for i in 0..len {
-          values.push(self.item(i as i32).unwrap().to_ir(program));
-        }
-        program.new_value().aggregate(values)
+    let zero = program.new_value().integer(0);
+    const_to_ir(self, zero, program)
+  }
+}
+
+/// 全零的子数组以 `zeroinit` 表示，其余零值元素共用同一个 `zero`
+fn const_to_ir(value: &ConstValue, zero: Value, program: &mut Program) -> Value {
+  match value.ty {
+    SysyType::Int => match value.as_int().unwrap() {
+      0 => zero,
+      int => program.new_value().integer(int),
+    },
+    SysyType::Array(..) if value.data.elems.iter().all(|&(_, x)| x == 0) => {
+      program.new_value().zero_init(value.ir_type())
+    }
+    SysyType::Array(_, len) => {
+      let mut values = vec![];
+      for i in 0..len {
+        values.push(const_to_ir(&value.item(i as i32).unwrap(), zero, program));
       }
-      _ => panic!("Expect array or int, found {:?}", self.ty),
+      program.new_value().aggregate(values)
     }
+    _ => panic!("Expect array or int, found {:?}", value.ty),
   }
 }
@@ -0,0 +1,594 @@
+//! 扩展语法的展开：在语义分析与生成 IR 之前，把 `for` 循环、复合赋值、自增自减与条件表达式改写为
+//! SysY 本身的节点，此后的各阶段不必了解这些语法。
+//!
+//! 展开由几趟 [`VisitMut`] 依次完成，每一趟只处理一种语法，其结果可以含有之后展开的语法：
+//!
+//! - `for (init; cond; step) body` 成为 `{ init; while (cond) { body; step; } }`。循环体中有属于这个
+//!   循环的 `continue` 时，步进放在条件中，以一个标记跳过第一次：
+//!   `{ init; int f = 1; while ((f || (step, 0), f = 0, cond)) body }`；
+//! - `++x` 成为 `(x += 1)`，`x++` 成为 `((x += 1) - 1)`，值不被使用时（表达式语句、逗号的左边）
+//!   也成为 `(x += 1)`；
+//! - `lhs op= rhs` 成为 `lhs = (lhs) op (rhs)`；`lhs` 有副作用（赋值、调用等）时先取其地址，
+//!   `(t = &lhs, *t = *t op (rhs))`，使之只求值一次；
+//! - 作为表达式语句、返回值或赋值给没有副作用的左值的条件表达式成为 `if` 语句；其余的成为
+//!   `(cond && (t = then, 1) || (t = else), +t)`，因此只支持整数。
+//!
+//! 其中的临时变量 `t` 名为 `__sysy_<种类>_<序号>`，声明在所在函数的开头；`for` 循环的标记声明在
+//! 展开的块中。临时变量的声明与引用在源代码中的范围是所在的函数或循环的起始处的空范围，由此与
+//! 源程序中的名字区分；其余新建的节点的范围是所展开的节点的范围。函数之外没有声明临时变量的
+//! 地方，需要临时变量的展开是错误。
+
+use std::mem;
+
+use super::ast::*;
+use super::error::CompileError;
+use super::visit::{Visit, VisitMut};
+
+/// 展开中发现的错误及其位置
+type Errors = Vec<(Span, CompileError)>;
+
+/// 展开语法树中的扩展语法，返回无法展开之处的错误；有错误时其余部分仍然展开
+pub fn desugar(ast: &mut Ast) -> Errors {
+  let passes: [&mut dyn Pass; 4] = [
+    &mut ForLoops::default(),
+    &mut Steps::default(),
+    &mut Compound::default(),
+    &mut Conditionals::default(),
+  ];
+  let mut errors = vec![];
+  for pass in passes {
+    pass.visit_comp_unit(ast);
+    errors.extend(pass.errors());
+  }
+  errors
+}
+
+/// 展开的一趟
+trait Pass: VisitMut {
+  fn errors(&mut self) -> Errors {
+    vec![]
+  }
+}
+
+/// 表达式节点向上一层包装，直到成为 [`Exp`]
+trait Lift: Node {
+  fn lift(ast: &mut Ast, span: Span, id: Id<Self>) -> Id<Exp>;
+}
+
+/// 以基本表达式构成的表达式节点
+trait Atom: Node {
+  fn atom(ast: &mut Ast, span: Span, primary: Id<PrimaryExp>) -> Id<Self>;
+}
+
+/// 表达式的各层：`$inner` 经 `$outer::$variant` 成为上一层的 `$outer`
+macro_rules! levels {
+  ($($inner:ident => $outer:ident::$variant:ident,)*) => {
+    $(impl Lift for $inner {
+      fn lift(ast: &mut Ast, span: Span, id: Id<Self>) -> Id<Exp> {
+        let outer = ast.alloc($outer::$variant(id), span);
+        <$outer as Lift>::lift(ast, span, outer)
+      }
+    }
+
+    impl Atom for $outer {
+      fn atom(ast: &mut Ast, span: Span, primary: Id<PrimaryExp>) -> Id<Self> {
+        let inner = <$inner as Atom>::atom(ast, span, primary);
+        ast.alloc($outer::$variant(inner), span)
+      }
+    })*
+  };
+}
+
+levels! {
+  PrimaryExp => PostfixExp::Primary,
+  PostfixExp => UnaryExp::Postfix,
+  UnaryExp => MulExp::Unary,
+  MulExp => AddExp::Mul,
+  AddExp => RelExp::Add,
+  RelExp => EqExp::Rel,
+  EqExp => LAndExp::Eq,
+  LAndExp => LOrExp::And,
+  LOrExp => AssignExp::LOr,
+  AssignExp => Exp::Assign,
+}
+
+impl Lift for Exp {
+  fn lift(_: &mut Ast, _: Span, id: Id<Self>) -> Id<Exp> {
+    id
+  }
+}
+
+impl Atom for PrimaryExp {
+  fn atom(_: &mut Ast, _: Span, primary: Id<PrimaryExp>) -> Id<Self> {
+    primary
+  }
+}
+
+/// `(exp)`
+fn paren<T: Atom>(ast: &mut Ast, span: Span, exp: Id<Exp>) -> Id<T> {
+  let primary = ast.alloc(PrimaryExp::Paren(exp), span);
+  T::atom(ast, span, primary)
+}
+
+/// 任意一层的表达式作为另一层使用，必要时加上括号
+fn convert<T: Lift, U: Atom>(ast: &mut Ast, span: Span, id: Id<T>) -> Id<U> {
+  let exp = T::lift(ast, span, id);
+  paren(ast, span, exp)
+}
+
+fn num<T: Atom>(ast: &mut Ast, span: Span, n: i32) -> Id<T> {
+  let primary = ast.alloc(PrimaryExp::Num(n), span);
+  T::atom(ast, span, primary)
+}
+
+/// 表达式作为赋值表达式使用，逗号表达式加上括号
+fn assign_of(ast: &mut Ast, span: Span, exp: Id<Exp>) -> Id<AssignExp> {
+  match ast[exp] {
+    Exp::Assign(exp) => exp,
+    Exp::Comma(..) => paren(ast, span, exp),
+  }
+}
+
+/// `lhs = rhs`
+fn assign(ast: &mut Ast, span: Span, lhs: Id<LOrExp>, rhs: Id<AssignExp>) -> Id<Exp> {
+  let exp = ast.alloc(AssignExp::Assign(lhs, rhs), span);
+  ast.alloc(Exp::Assign(exp), span)
+}
+
+/// `lhs, rhs`
+fn comma(ast: &mut Ast, span: Span, lhs: Id<Exp>, rhs: Id<Exp>) -> Id<Exp> {
+  let rhs = assign_of(ast, span, rhs);
+  ast.alloc(Exp::Comma(lhs, rhs), span)
+}
+
+/// `(lhs) op (rhs)`
+fn binary(ast: &mut Ast, span: Span, lhs: Id<Exp>, op: CompoundOp, rhs: Id<Exp>) -> Id<Exp> {
+  match op {
+    CompoundOp::Add(op) => {
+      let lhs = paren(ast, span, lhs);
+      let rhs = paren(ast, span, rhs);
+      let exp = ast.alloc(AddExp::Add(lhs, op, rhs), span);
+      AddExp::lift(ast, span, exp)
+    }
+    CompoundOp::Mul(op) => {
+      let lhs = paren(ast, span, lhs);
+      let rhs = paren(ast, span, rhs);
+      let exp = ast.alloc(MulExp::Mul(lhs, op, rhs), span);
+      MulExp::lift(ast, span, exp)
+    }
+  }
+}
+
+/// 没有括号与运算、只是一个后缀表达式的表达式
+fn postfix(ast: &Ast, exp: Id<Exp>) -> Option<Id<PostfixExp>> {
+  let Exp::Assign(exp) = ast[exp] else {
+    return None;
+  };
+  let AssignExp::LOr(exp) = ast[exp] else {
+    return None;
+  };
+  let LOrExp::And(exp) = ast[exp] else {
+    return None;
+  };
+  let LAndExp::Eq(exp) = ast[exp] else {
+    return None;
+  };
+  let EqExp::Rel(exp) = ast[exp] else {
+    return None;
+  };
+  let RelExp::Add(exp) = ast[exp] else {
+    return None;
+  };
+  let AddExp::Mul(exp) = ast[exp] else {
+    return None;
+  };
+  let MulExp::Unary(exp) = ast[exp] else {
+    return None;
+  };
+  let UnaryExp::Postfix(exp) = ast[exp] else {
+    return None;
+  };
+  Some(exp)
+}
+
+/// 表达式是否有副作用：赋值、自增自减与函数调用
+#[derive(Default)]
+struct Effects(bool);
+
+impl Visit for Effects {
+  fn visit_assign_exp(&mut self, ast: &Ast, exp: Id<AssignExp>) {
+    self.0 |= !matches!(ast[exp], AssignExp::LOr(_));
+    self.walk_assign_exp(ast, exp);
+  }
+
+  fn visit_unary_exp(&mut self, ast: &Ast, exp: Id<UnaryExp>) {
+    self.0 |= matches!(ast[exp], UnaryExp::Step(..));
+    self.walk_unary_exp(ast, exp);
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    self.0 |= matches!(ast[exp], PostfixExp::Step(..) | PostfixExp::Call(..));
+    self.walk_postfix_exp(ast, exp);
+  }
+}
+
+/// 没有副作用、可以求值多次的表达式
+fn pure(ast: &Ast, exp: Id<LOrExp>) -> bool {
+  let mut effects = Effects::default();
+  effects.visit_lor_exp(ast, exp);
+  !effects.0
+}
+
+/// 一个临时变量：名字与引用它的位置
+#[derive(Clone)]
+struct Temp(Name, Pos);
+
+impl Temp {
+  /// 引用临时变量的表达式
+  fn get<T: Atom>(&self, ast: &mut Ast) -> Id<T> {
+    let span = (self.1, self.1);
+    let primary = ast.alloc(PrimaryExp::Ident(self.0.clone()), span);
+    T::atom(ast, span, primary)
+  }
+}
+
+/// `pos` 处的 `int` 变量的声明
+fn declare(ast: &mut Ast, pos: Pos, list: Vec<DeclaratorAndInitializer>) -> Id<Decl> {
+  let decl = DeclaratorAndInitializerList {
+    is_const: false,
+    is_volatile: false,
+    ty: TypeSpec::Int,
+    list,
+  };
+  ast.alloc(Decl::Var(decl), (pos, pos))
+}
+
+/// 正在展开的函数中声明的临时变量
+#[derive(Default)]
+struct Temps {
+  /// 函数定义的起始位置；不在函数中时为 `None`
+  func: Option<Pos>,
+  declarators: Vec<Id<Declarator>>,
+}
+
+impl Temps {
+  /// 开始展开一个声明，是函数定义时返回 `true`，展开之后须调用 [`Temps::leave`]
+  fn enter(&mut self, ast: &Ast, decl: Id<Decl>) -> bool {
+    let entered =
+      self.func.is_none() && matches!(&ast[decl], Decl::Func(func) if func.body.is_some());
+    if entered {
+      self.func = Some(ast.pos(decl));
+    }
+    entered
+  }
+
+  /// 声明一个类型为 `int` 或 `int *` 的临时变量；不在函数中时为 `None`
+  fn declare(&mut self, ast: &mut Ast, kind: &str, pointer: bool) -> Option<Temp> {
+    let pos = self.func?;
+    let name = Name::intern(&format!("__sysy_{}_{}", kind, self.declarators.len()));
+    let mut declarator = ast.alloc(Declarator::Ident(name.clone()), (pos, pos));
+    if pointer {
+      declarator = ast.alloc(Declarator::Pointer(declarator), (pos, pos));
+    }
+    self.declarators.push(declarator);
+    Some(Temp(name, pos))
+  }
+
+  /// 函数展开完毕，在其开头声明各个临时变量
+  fn leave(&mut self, ast: &mut Ast, decl: Id<Decl>) {
+    let Temps { func, declarators } = mem::take(self);
+    if declarators.is_empty() {
+      return;
+    }
+    let list = declarators.into_iter().map(|d| (d, None)).collect();
+    let temps = declare(ast, func.unwrap(), list);
+    if let Decl::Func(FuncDecl {
+      body: Some(body), ..
+    }) = &mut ast[decl]
+    {
+      body.insert(0, BlockItem::Decl(temps));
+    }
+  }
+}
+
+/// 循环体中是否有属于这个循环的 `continue`
+#[derive(Default)]
+struct Continues(bool);
+
+impl Visit for Continues {
+  fn visit_stmt(&mut self, ast: &Ast, stmt: Id<Stmt>) {
+    match ast[stmt] {
+      Stmt::Continue => self.0 = true,
+      Stmt::While(..) | Stmt::For(..) => {}
+      _ => self.walk_stmt(ast, stmt),
+    }
+  }
+}
+
+/// 展开 `for` 循环
+#[derive(Default)]
+struct ForLoops {
+  /// 已声明的标记数
+  flags: usize,
+}
+
+impl Pass for ForLoops {}
+
+impl VisitMut for ForLoops {
+  fn visit_stmt(&mut self, ast: &mut Ast, stmt: Id<Stmt>) {
+    self.walk_stmt(ast, stmt);
+    let Stmt::For(init, cond, step, body) = ast[stmt] else {
+      return;
+    };
+    let span = ast.span(stmt);
+    let mut block = vec![init];
+    let cond = match cond {
+      Some(cond) => cond,
+      None => num(ast, span, 1),
+    };
+    let mut continues = Continues::default();
+    continues.visit_stmt(ast, body);
+    let (cond, body) = match step {
+      Some(step) if continues.0 => {
+        let flag = Temp(Name::intern(&format!("__sysy_for_{}", self.flags)), span.0);
+        self.flags += 1;
+        let declarator = ast.alloc(Declarator::Ident(flag.0.clone()), (span.0, span.0));
+        let one = num(ast, (span.0, span.0), 1);
+        let init = ast.alloc(Initializer::Simple(one), (span.0, span.0));
+        block.push(BlockItem::Decl(declare(
+          ast,
+          span.0,
+          vec![(declarator, Some(init))],
+        )));
+        // (f || (step, 0), f = 0, cond)
+        let zero = num(ast, span, 0);
+        let step = comma(ast, span, step, zero);
+        let step = paren(ast, span, step);
+        let first = flag.get(ast);
+        let first = ast.alloc(LOrExp::Or(first, step), span);
+        let first = LOrExp::lift(ast, span, first);
+        let target = flag.get(ast);
+        let zero = num(ast, span, 0);
+        let reset = assign(ast, span, target, zero);
+        let first = comma(ast, span, first, reset);
+        (comma(ast, span, first, cond), body)
+      }
+      Some(step) => {
+        let step = ast.alloc(Stmt::Exp(Some(step)), ast.span(step));
+        let items = vec![BlockItem::Stmt(body), BlockItem::Stmt(step)];
+        (cond, ast.alloc(Stmt::Block(items), ast.span(body)))
+      }
+      None => (cond, body),
+    };
+    block.push(BlockItem::Stmt(ast.alloc(Stmt::While(cond, body), span)));
+    ast[stmt] = Stmt::Block(block);
+  }
+}
+
+/// 展开自增自减
+#[derive(Default)]
+struct Steps {
+  /// 值不被使用的后缀表达式
+  unused: Option<Id<PostfixExp>>,
+}
+
+impl Pass for Steps {}
+
+/// `(lhs += 1)` 或 `(lhs -= 1)`
+fn step(ast: &mut Ast, span: Span, lhs: Id<LOrExp>, op: StepOp) -> Id<Exp> {
+  let op = match op {
+    StepOp::Increment => AddOp::Plus,
+    StepOp::Decrement => AddOp::Minus,
+  };
+  let one = num(ast, span, 1);
+  let exp = ast.alloc(AssignExp::Compound(lhs, CompoundOp::Add(op), one), span);
+  ast.alloc(Exp::Assign(exp), span)
+}
+
+impl VisitMut for Steps {
+  fn visit_stmt(&mut self, ast: &mut Ast, stmt: Id<Stmt>) {
+    if let Stmt::Exp(Some(exp)) = ast[stmt] {
+      self.unused = postfix(ast, exp);
+    }
+    self.walk_stmt(ast, stmt);
+  }
+
+  fn visit_exp(&mut self, ast: &mut Ast, exp: Id<Exp>) {
+    if let Exp::Comma(lhs, _) = ast[exp] {
+      self.unused = postfix(ast, lhs);
+    }
+    self.walk_exp(ast, exp);
+  }
+
+  fn visit_unary_exp(&mut self, ast: &mut Ast, exp: Id<UnaryExp>) {
+    self.walk_unary_exp(ast, exp);
+    if let UnaryExp::Step(op, operand) = ast[exp] {
+      let span = ast.span(exp);
+      let lhs = convert(ast, span, operand);
+      let value = step(ast, span, lhs, op);
+      ast[exp] = UnaryExp::Postfix(paren(ast, span, value));
+    }
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &mut Ast, exp: Id<PostfixExp>) {
+    self.walk_postfix_exp(ast, exp);
+    if let PostfixExp::Step(operand, op) = ast[exp] {
+      let span = ast.span(exp);
+      let lhs = convert(ast, span, operand);
+      let mut value = step(ast, span, lhs, op);
+      if self.unused != Some(exp) {
+        let op = match op {
+          StepOp::Increment => AddOp::Minus,
+          StepOp::Decrement => AddOp::Plus,
+        };
+        let one = num(ast, span, 1);
+        value = binary(ast, span, value, CompoundOp::Add(op), one);
+      }
+      ast[exp] = PostfixExp::Primary(paren(ast, span, value));
+    }
+  }
+}
+
+/// 展开复合赋值
+#[derive(Default)]
+struct Compound {
+  temps: Temps,
+  errors: Errors,
+}
+
+impl Pass for Compound {
+  fn errors(&mut self) -> Errors {
+    mem::take(&mut self.errors)
+  }
+}
+
+impl VisitMut for Compound {
+  fn visit_decl(&mut self, ast: &mut Ast, decl: Id<Decl>) {
+    let entered = self.temps.enter(ast, decl);
+    self.walk_decl(ast, decl);
+    if entered {
+      self.temps.leave(ast, decl);
+    }
+  }
+
+  fn visit_assign_exp(&mut self, ast: &mut Ast, exp: Id<AssignExp>) {
+    self.walk_assign_exp(ast, exp);
+    let AssignExp::Compound(lhs, op, rhs) = ast[exp] else {
+      return;
+    };
+    let span = ast.span(exp);
+    let rhs = ast.alloc(Exp::Assign(rhs), span);
+    ast[exp] = if pure(ast, lhs) {
+      let value = LOrExp::lift(ast, span, lhs);
+      let value = binary(ast, span, value, op, rhs);
+      AssignExp::Assign(lhs, assign_of(ast, span, value))
+    } else if let Some(temp) = self.temps.declare(ast, "lhs", true) {
+      // (t = &lhs, *t = *t op (rhs))
+      let address = convert(ast, span, lhs);
+      let address = ast.alloc(UnaryExp::Address(address), span);
+      let address = UnaryExp::lift(ast, span, address);
+      let target = temp.get(ast);
+      let address = assign_of(ast, span, address);
+      let first = assign(ast, span, target, address);
+      let target = temp.get(ast);
+      let target = ast.alloc(UnaryExp::Deref(target), span);
+      let target = convert(ast, span, target);
+      let value = temp.get(ast);
+      let value = ast.alloc(UnaryExp::Deref(value), span);
+      let value = UnaryExp::lift(ast, span, value);
+      let value = binary(ast, span, value, op, rhs);
+      let value = assign_of(ast, span, value);
+      let second = assign(ast, span, target, value);
+      let value = comma(ast, span, first, second);
+      AssignExp::LOr(paren(ast, span, value))
+    } else {
+      self
+        .errors
+        .push((span, CompileError::NotInFunction("复合赋值")));
+      return;
+    };
+  }
+}
+
+/// 展开条件表达式
+#[derive(Default)]
+struct Conditionals {
+  temps: Temps,
+  errors: Errors,
+}
+
+impl Pass for Conditionals {
+  fn errors(&mut self) -> Errors {
+    mem::take(&mut self.errors)
+  }
+}
+
+impl Conditionals {
+  /// 整个是条件表达式（或将其赋值给没有副作用的左值）的表达式语句与返回语句成为 `if` 语句
+  fn branches(ast: &mut Ast, stmt: Id<Stmt>) -> Option<Stmt> {
+    let (Stmt::Exp(Some(exp)) | Stmt::Return(Some(exp))) = ast[stmt] else {
+      return None;
+    };
+    let Exp::Assign(top) = ast[exp] else {
+      return None;
+    };
+    let span = ast.span(exp);
+    let (cond, then, otherwise) = match ast[top] {
+      AssignExp::Cond(cond, then, otherwise) => {
+        (cond, then, ast.alloc(Exp::Assign(otherwise), span))
+      }
+      AssignExp::Assign(lhs, rhs) if pure(ast, lhs) => match ast[rhs] {
+        AssignExp::Cond(cond, then, otherwise) => {
+          let then = assign_of(ast, span, then);
+          let then = assign(ast, span, lhs, then);
+          (cond, then, assign(ast, span, lhs, otherwise))
+        }
+        _ => return None,
+      },
+      _ => return None,
+    };
+    let stmt_span = ast.span(stmt);
+    let is_return = matches!(ast[stmt], Stmt::Return(_));
+    let mut branch = |exp| {
+      let stmt = match is_return {
+        true => Stmt::Return(Some(exp)),
+        false => Stmt::Exp(Some(exp)),
+      };
+      ast.alloc(stmt, stmt_span)
+    };
+    let (then, otherwise) = (branch(then), branch(otherwise));
+    let cond = LOrExp::lift(ast, span, cond);
+    Some(Stmt::If(cond, then, Some(otherwise)))
+  }
+}
+
+impl VisitMut for Conditionals {
+  fn visit_decl(&mut self, ast: &mut Ast, decl: Id<Decl>) {
+    let entered = self.temps.enter(ast, decl);
+    self.walk_decl(ast, decl);
+    if entered {
+      self.temps.leave(ast, decl);
+    }
+  }
+
+  fn visit_stmt(&mut self, ast: &mut Ast, stmt: Id<Stmt>) {
+    if let Some(branches) = Self::branches(ast, stmt) {
+      ast[stmt] = branches;
+    }
+    self.walk_stmt(ast, stmt);
+  }
+
+  fn visit_assign_exp(&mut self, ast: &mut Ast, exp: Id<AssignExp>) {
+    self.walk_assign_exp(ast, exp);
+    let AssignExp::Cond(cond, then, otherwise) = ast[exp] else {
+      return;
+    };
+    let span = ast.span(exp);
+    let Some(temp) = self.temps.declare(ast, "cond", false) else {
+      self
+        .errors
+        .push((span, CompileError::NotInFunction("条件表达式")));
+      return;
+    };
+    // (cond && (t = then, 1) || (t = otherwise), +t)
+    let target = temp.get(ast);
+    let then = assign_of(ast, span, then);
+    let then = assign(ast, span, target, then);
+    let one = num(ast, span, 1);
+    let then = comma(ast, span, then, one);
+    let then = paren(ast, span, then);
+    let cond = convert(ast, span, cond);
+    let cond = ast.alloc(LAndExp::And(cond, then), span);
+    let cond = ast.alloc(LOrExp::And(cond), span);
+    let target = temp.get(ast);
+    let otherwise = assign(ast, span, target, otherwise);
+    let otherwise = paren(ast, span, otherwise);
+    let cond = ast.alloc(LOrExp::Or(cond, otherwise), span);
+    let cond = LOrExp::lift(ast, span, cond);
+    let value = temp.get(ast);
+    let value = ast.alloc(UnaryExp::Op(UnaryOp::Positive, value), span);
+    let value = UnaryExp::lift(ast, span, value);
+    let value = comma(ast, span, cond, value);
+    ast[exp] = AssignExp::LOr(paren(ast, span, value));
+  }
+}
@@ -0,0 +1,42 @@
+//! 语法分析的错误转换为带有位置的 [`Diagnostic`]。
+
+use lalrpop_util::ParseError;
+
+use super::ast::Pos;
+use super::lexer::{LexError, Token};
+use crate::diagnostics::Diagnostic;
+
+/// `expected` 是记号的名字，如 `"\";\""`
+fn expected_one_of(expected: &[String]) -> String {
+  match expected {
+    [] => String::new(),
+    [one] => format!(", expected {}", one),
+    _ => format!(", expected one of {}", expected.join(", ")),
+  }
+}
+
+impl From<ParseError<Pos, Token<'_>, LexError>> for Diagnostic {
+  fn from(e: ParseError<Pos, Token<'_>, LexError>) -> Self {
+    let (start, end, message) = match e {
+      ParseError::InvalidToken { location } => (location, location, "invalid token".into()),
+      ParseError::UnrecognizedEOF { location, expected } => (
+        location,
+        location,
+        format!("unexpected end of file{}", expected_one_of(&expected)),
+      ),
+      ParseError::UnrecognizedToken {
+        token: (start, Token { text, .. }, end),
+        expected,
+      } => (
+        start,
+        end,
+        format!("unexpected '{}'{}", text, expected_one_of(&expected)),
+      ),
+      ParseError::ExtraToken {
+        token: (start, Token { text, .. }, end),
+      } => (start, end, format!("extra token '{}'", text)),
+      ParseError::User { error } => (error.start, error.end, error.kind.message().into()),
+    };
+    Diagnostic::at(start, end, message)
+  }
+}
@@ -0,0 +1,458 @@
+//! 语法树的 S 表达式输出（`--emit=ast`）与 Graphviz DOT 输出（`--emit=ast-dot`）。
+//!
+//! 每个节点是 `(种类 ...)`，运算以 C 的运算符为种类；只有一个子节点的文法层次
+//! （如 `AddExp::Mul`）不单独成为节点。声明与语句带有起始位置 `@行:列`。
+
+use super::ast::*;
+use super::source::SourceMap;
+use crate::analysis::dot::quote;
+
+enum Sexp {
+  Atom(String),
+  List(Vec<Sexp>),
+}
+
+/// 一行的最大宽度，超出时子节点分行缩进
+const WIDTH: usize = 100;
+
+impl Sexp {
+  fn list(head: &str, rest: impl IntoIterator<Item = Sexp>) -> Sexp {
+    let mut items = vec![Sexp::Atom(head.into())];
+    items.extend(rest);
+    Sexp::List(items)
+  }
+
+  fn flat(&self) -> String {
+    match self {
+      Sexp::Atom(atom) => atom.clone(),
+      Sexp::List(items) => {
+        let items: Vec<_> = items.iter().map(Sexp::flat).collect();
+        format!("({})", items.join(" "))
+      }
+    }
+  }
+
+  /// 单行输出的长度，超过 `limit` 时为 `None`。至多看 `limit` 个字符，不随节点的大小增长，
+  /// 因此很深的语法树也不会对每一层都输出一遍整棵子树
+  fn flat_len(&self, limit: usize) -> Option<usize> {
+    let len = match self {
+      Sexp::Atom(atom) => atom.len(),
+      Sexp::List(items) => {
+        // 两侧的括号与各项之间的空格
+        let mut len = 1 + items.len().max(1);
+        for item in items {
+          len += item.flat_len(limit.checked_sub(len)?)?;
+        }
+        len
+      }
+    };
+    (len <= limit).then_some(len)
+  }
+
+  fn write(&self, out: &mut String, indent: usize) {
+    let items = match self {
+      Sexp::List(items) if self.flat_len(WIDTH.saturating_sub(indent)).is_none() => items,
+      _ => return *out += &self.flat(),
+    };
+    // 开头的原子（种类、名字、位置）留在第一行
+    let atoms = items
+      .iter()
+      .take_while(|item| matches!(item, Sexp::Atom(_)));
+    let atoms: Vec<_> = atoms.map(Sexp::flat).collect();
+    *out += &format!("({}", atoms.join(" "));
+    for item in &items[atoms.len()..] {
+      // 嵌套很深时缩进可能超出格式化宽度的上限 `u16::MAX`
+      *out += "\n";
+      *out += &" ".repeat(indent + 2);
+      item.write(out, indent + 2);
+    }
+    *out += ")";
+  }
+}
+
+impl Sexp {
+  /// 作为 DOT 的节点输出，返回节点的编号。列表以种类与位置为标签，其余各项依次是子节点，
+  /// 原子（名字、类型、字面量）是椭圆的叶节点
+  fn write_dot(&self, out: &mut String, count: &mut usize) -> usize {
+    let id = *count;
+    *count += 1;
+    let items = match self {
+      Sexp::Atom(atom) => {
+        *out += &format!("  n{} [label={}, shape=ellipse];\n", id, quote(atom));
+        return id;
+      }
+      Sexp::List(items) => items,
+    };
+    let (label, children): (Vec<_>, Vec<_>) = items
+      .iter()
+      .enumerate()
+      .partition(|&(i, item)| i == 0 || matches!(item, Sexp::Atom(atom) if atom.starts_with('@')));
+    let label: Vec<_> = label.into_iter().map(|(_, item)| item.flat()).collect();
+    *out += &format!("  n{} [label={}];\n", id, quote(&label.join(" ")));
+    for (_, child) in children {
+      let child = child.write_dot(out, count);
+      *out += &format!("  n{} -> n{};\n", id, child);
+    }
+    id
+  }
+}
+
+impl From<&str> for Sexp {
+  fn from(atom: &str) -> Self {
+    Sexp::Atom(atom.into())
+  }
+}
+
+struct Dumper<'a> {
+  ast: &'a Ast,
+  source: &'a SourceMap,
+}
+
+impl Dumper<'_> {
+  fn pos(&self, pos: Pos) -> Sexp {
+    let (line, column) = self.source.position(pos);
+    Sexp::Atom(format!("@{}:{}", line, column))
+  }
+
+  fn decl(&self, id: Id<Decl>) -> Sexp {
+    match &self.ast[id] {
+      Decl::Var(decl) => {
+        let head = if decl.is_const { "const" } else { "var" };
+        let defs = decl.list.iter().map(|&(declarator, init)| {
+          let init = init.map(|init| self.initializer(init));
+          Sexp::list("def", [self.declarator(declarator)].into_iter().chain(init))
+        });
+        let volatile = decl.is_volatile.then(|| Sexp::Atom("volatile".into()));
+        let items = [type_spec(decl.ty), self.pos(self.ast.pos(id))];
+        Sexp::list(head, volatile.into_iter().chain(items).chain(defs))
+      }
+      Decl::Func(func) => {
+        let head = if func.body.is_some() {
+          "func"
+        } else {
+          "func-decl"
+        };
+        let params = func.params.iter().map(|&(_, param)| self.declarator(param));
+        let items = [
+          type_spec(func.func_type),
+          func.ident.as_str().into(),
+          self.pos(self.ast.pos(id)),
+          Sexp::list("params", params),
+        ];
+        // 没有属性时省略
+        let attrs = (!func.attrs.is_empty()).then(|| {
+          let attrs = func.attrs.iter().map(|attr| match attr {
+            Attribute::Section(section) => {
+              Sexp::list("section", [Sexp::Atom(format!("{:?}", section))])
+            }
+            _ => attr.name().into(),
+          });
+          Sexp::list("attributes", attrs)
+        });
+        let body = func.body.as_ref().map(|body| self.block(body));
+        Sexp::list(head, items.into_iter().chain(attrs).chain(body))
+      }
+    }
+  }
+
+  fn declarator(&self, declarator: Id<Declarator>) -> Sexp {
+    match &self.ast[declarator] {
+      Declarator::Ident(ident) => ident.as_str().into(),
+      Declarator::Pointer(base) => Sexp::list("pointer", [self.declarator(*base)]),
+      Declarator::Array(base, len) => Sexp::list("array", [self.declarator(*base), self.exp(*len)]),
+    }
+  }
+
+  fn initializer(&self, init: Id<Initializer>) -> Sexp {
+    match &self.ast[init] {
+      Initializer::Simple(exp) => self.assign_exp(*exp),
+      Initializer::Aggregate(list) => {
+        Sexp::list("init-list", list.iter().map(|&i| self.initializer(i)))
+      }
+    }
+  }
+
+  fn block(&self, block: &Block) -> Sexp {
+    let items = block.iter().map(|&item| match item {
+      BlockItem::Decl(decl) => self.decl(decl),
+      BlockItem::Stmt(stmt) => self.stmt(stmt),
+    });
+    Sexp::list("block", items)
+  }
+
+  fn stmt(&self, id: Id<Stmt>) -> Sexp {
+    let pos = self.pos(self.ast.pos(id));
+    match &self.ast[id] {
+      Stmt::Exp(exp) => {
+        let exp = exp.map(|exp| self.exp(exp));
+        Sexp::list("exp", [pos].into_iter().chain(exp))
+      }
+      Stmt::Block(block) => self.block(block),
+      &Stmt::If(cond, then, els) => {
+        let items = [pos, self.exp(cond), self.stmt(then)];
+        let els = els.map(|els| self.stmt(els));
+        Sexp::list("if", items.into_iter().chain(els))
+      }
+      &Stmt::While(cond, body) => Sexp::list("while", [pos, self.exp(cond), self.stmt(body)]),
+      &Stmt::For(init, cond, step, body) => {
+        let init = match init {
+          BlockItem::Decl(decl) => self.decl(decl),
+          BlockItem::Stmt(stmt) => self.stmt(stmt),
+        };
+        // 省略的条件与步进为空表
+        let exp = |exp: Option<Id<Exp>>| exp.map_or(Sexp::List(vec![]), |exp| self.exp(exp));
+        Sexp::list("for", [pos, init, exp(cond), exp(step), self.stmt(body)])
+      }
+      Stmt::Break => Sexp::list("break", [pos]),
+      Stmt::Continue => Sexp::list("continue", [pos]),
+      Stmt::Return(exp) => {
+        let exp = exp.map(|exp| self.exp(exp));
+        Sexp::list("return", [pos].into_iter().chain(exp))
+      }
+      Stmt::Asm(asm) => {
+        let operands = |head, operands: &[AsmOperand]| {
+          let operands = operands.iter().map(|(constraint, exp)| {
+            let constraint = Sexp::Atom(format!("{:?}", constraint));
+            Sexp::List(vec![constraint, self.assign_exp(*exp)])
+          });
+          Sexp::list(head, operands)
+        };
+        let items = [
+          pos,
+          Sexp::Atom(format!("{:?}", asm.template)),
+          operands("outputs", &asm.outputs),
+          operands("inputs", &asm.inputs),
+        ];
+        Sexp::list("asm", items)
+      }
+    }
+  }
+
+  fn exp(&self, exp: Id<Exp>) -> Sexp {
+    match &self.ast[exp] {
+      Exp::Assign(exp) => self.assign_exp(*exp),
+      Exp::Comma(lhs, rhs) => Sexp::list(",", [self.exp(*lhs), self.assign_exp(*rhs)]),
+    }
+  }
+
+  fn assign_exp(&self, exp: Id<AssignExp>) -> Sexp {
+    match &self.ast[exp] {
+      AssignExp::LOr(exp) => self.lor_exp(*exp),
+      AssignExp::Assign(lhs, rhs) => Sexp::list("=", [self.lor_exp(*lhs), self.assign_exp(*rhs)]),
+      AssignExp::Compound(lhs, op, rhs) => {
+        let op = match op {
+          CompoundOp::Add(AddOp::Plus) => "+=",
+          CompoundOp::Add(AddOp::Minus) => "-=",
+          CompoundOp::Mul(MulOp::Multiply) => "*=",
+          CompoundOp::Mul(MulOp::Divide) => "/=",
+          CompoundOp::Mul(MulOp::Modulo) => "%=",
+        };
+        Sexp::list(op, [self.lor_exp(*lhs), self.assign_exp(*rhs)])
+      }
+      AssignExp::Cond(cond, then, els) => {
+        let items = [self.lor_exp(*cond), self.exp(*then), self.assign_exp(*els)];
+        Sexp::list("?:", items)
+      }
+    }
+  }
+
+  fn lor_exp(&self, exp: Id<LOrExp>) -> Sexp {
+    match &self.ast[exp] {
+      LOrExp::And(exp) => self.land_exp(*exp),
+      LOrExp::Or(lhs, rhs) => Sexp::list("||", [self.lor_exp(*lhs), self.land_exp(*rhs)]),
+    }
+  }
+
+  fn land_exp(&self, exp: Id<LAndExp>) -> Sexp {
+    match &self.ast[exp] {
+      LAndExp::Eq(exp) => self.eq_exp(*exp),
+      LAndExp::And(lhs, rhs) => Sexp::list("&&", [self.land_exp(*lhs), self.eq_exp(*rhs)]),
+    }
+  }
+
+  fn eq_exp(&self, exp: Id<EqExp>) -> Sexp {
+    match &self.ast[exp] {
+      EqExp::Rel(exp) => self.rel_exp(*exp),
+      EqExp::Eq(lhs, op, rhs) => {
+        let op = match op {
+          EqOp::Equal => "==",
+          EqOp::NotEqual => "!=",
+        };
+        Sexp::list(op, [self.eq_exp(*lhs), self.rel_exp(*rhs)])
+      }
+    }
+  }
+
+  fn rel_exp(&self, exp: Id<RelExp>) -> Sexp {
+    match &self.ast[exp] {
+      RelExp::Add(exp) => self.add_exp(*exp),
+      RelExp::Rel(lhs, op, rhs) => {
+        let op = match op {
+          RelOp::Less => "<",
+          RelOp::LessEqual => "<=",
+          RelOp::Greater => ">",
+          RelOp::GreaterEqual => ">=",
+        };
+        Sexp::list(op, [self.rel_exp(*lhs), self.add_exp(*rhs)])
+      }
+    }
+  }
+
+  fn add_exp(&self, exp: Id<AddExp>) -> Sexp {
+    match &self.ast[exp] {
+      AddExp::Mul(exp) => self.mul_exp(*exp),
+      AddExp::Add(lhs, op, rhs) => {
+        let op = match op {
+          AddOp::Plus => "+",
+          AddOp::Minus => "-",
+        };
+        Sexp::list(op, [self.add_exp(*lhs), self.mul_exp(*rhs)])
+      }
+    }
+  }
+
+  fn mul_exp(&self, exp: Id<MulExp>) -> Sexp {
+    match &self.ast[exp] {
+      MulExp::Unary(exp) => self.unary_exp(*exp),
+      MulExp::Mul(lhs, op, rhs) => {
+        let op = match op {
+          MulOp::Multiply => "*",
+          MulOp::Divide => "/",
+          MulOp::Modulo => "%",
+        };
+        Sexp::list(op, [self.mul_exp(*lhs), self.unary_exp(*rhs)])
+      }
+    }
+  }
+
+  fn unary_exp(&self, exp: Id<UnaryExp>) -> Sexp {
+    match &self.ast[exp] {
+      UnaryExp::Postfix(exp) => self.postfix_exp(*exp),
+      UnaryExp::Address(exp) => Sexp::list("address", [self.unary_exp(*exp)]),
+      UnaryExp::Deref(exp) => Sexp::list("deref", [self.unary_exp(*exp)]),
+      UnaryExp::Op(op, exp) => {
+        let op = match op {
+          UnaryOp::Positive => "+",
+          UnaryOp::Negative => "-",
+          UnaryOp::Not => "!",
+        };
+        Sexp::list(op, [self.unary_exp(*exp)])
+      }
+      UnaryExp::Step(op, exp) => {
+        let op = match op {
+          StepOp::Increment => "++",
+          StepOp::Decrement => "--",
+        };
+        Sexp::list(op, [self.unary_exp(*exp)])
+      }
+    }
+  }
+
+  fn postfix_exp(&self, exp: Id<PostfixExp>) -> Sexp {
+    match &self.ast[exp] {
+      &PostfixExp::Primary(exp) => match &self.ast[exp] {
+        PrimaryExp::Num(num) => Sexp::Atom(num.to_string()),
+        PrimaryExp::Ident(ident) => ident.as_str().into(),
+        &PrimaryExp::Paren(exp) => Sexp::list("paren", [self.exp(exp)]),
+      },
+      PostfixExp::Subscript(array, index) => {
+        Sexp::list("[]", [self.postfix_exp(*array), self.exp(*index)])
+      }
+      PostfixExp::Call(func, _, args) => {
+        let args = args.iter().map(|&arg| self.assign_exp(arg));
+        Sexp::list("call", [func.as_str().into()].into_iter().chain(args))
+      }
+      PostfixExp::Step(exp, op) => {
+        let op = match op {
+          StepOp::Increment => "post++",
+          StepOp::Decrement => "post--",
+        };
+        Sexp::list(op, [self.postfix_exp(*exp)])
+      }
+    }
+  }
+}
+
+fn type_spec(ty: TypeSpec) -> Sexp {
+  match ty {
+    TypeSpec::Void => "void".into(),
+    TypeSpec::Int => "int".into(),
+  }
+}
+
+/// SysY 的关键字；`__builtin_expect` 等内建函数在词法上是普通的标识符
+const KEYWORDS: &[&str] = &[
+  "__attribute__",
+  "asm",
+  "break",
+  "const",
+  "continue",
+  "else",
+  "for",
+  "if",
+  "int",
+  "return",
+  "void",
+  "volatile",
+  "while",
+];
+
+/// 记号的种类，由其原文得出
+fn token_kind(text: &str) -> &'static str {
+  match text.as_bytes()[0] {
+    b'0'..=b'9' => "number",
+    b'\'' => "char",
+    b'"' => "string",
+    b'_' | b'a'..=b'z' | b'A'..=b'Z' if KEYWORDS.contains(&text) => "keyword",
+    b'_' | b'a'..=b'z' | b'A'..=b'Z' => "ident",
+    _ => "punct",
+  }
+}
+
+/// 输出记号（起止位置、原文）：每行是 `起始行:列-结束行:列`、种类与原文
+pub fn dump_tokens(tokens: Vec<(Pos, &str, Pos)>, input: &str) -> String {
+  let source = SourceMap::new(input);
+  let mut out = String::new();
+  for (start, text, end) in tokens {
+    let (start_line, start_column) = source.position(start);
+    let (end_line, end_column) = source.position(end);
+    let span = format!(
+      "{}:{}-{}:{}",
+      start_line, start_column, end_line, end_column
+    );
+    out += &format!("{:<14} {:<8} {}\n", span, token_kind(text), text);
+  }
+  out
+}
+
+/// 以 S 表达式输出语法树
+pub fn dump_ast(ast: &Ast, input: &str) -> String {
+  let source = SourceMap::new(input);
+  let dumper = Dumper {
+    ast,
+    source: &source,
+  };
+  let mut out = String::new();
+  for &decl in &ast.unit {
+    dumper.decl(decl).write(&mut out, 0);
+    out.push('\n');
+  }
+  out
+}
+
+/// 以 DOT 格式输出语法树，每个顶层的声明是一棵树；子节点按运算数的顺序从左到右排列
+pub fn dump_ast_dot(ast: &Ast, input: &str) -> String {
+  let source = SourceMap::new(input);
+  let dumper = Dumper {
+    ast,
+    source: &source,
+  };
+  let mut out = String::from("digraph ast {\n  ordering=out;\n");
+  out += "  node [shape=box, fontname=\"monospace\"];\n";
+  let mut count = 0;
+  for &decl in &ast.unit {
+    dumper.decl(decl).write_dot(&mut out, &mut count);
+  }
+  out += "}\n";
+  out
+}
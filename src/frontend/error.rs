@@ -14,37 +14,71 @@ impl fmt::Display for UnimplementedError {
 }
 
 #[derive(Debug)]
-pub enum CompileError{
+pub enum CompileError {
   UndeclaredSymbol(String),
+  ImplicitDeclaration(String),
   TypeMismatch(&'static str, String, &'static str),
   IllegalBreak,
   IllegalContinue,
   IllegalVoid,
   Redefinition(String),
+  ConflictingDeclaration(String),
+  /// 函数的属性相互冲突：函数名与冲突的属性
+  ConflictingAttributes(String, String),
+  DuplicateParameter(String),
+  ParameterShadowsFunction(String),
+  VoidParameter(String),
+  /// 扩展模式下调用的函数没有与实参的类型相符的重载
+  NoMatchingOverload(String),
   ConstexprRequired(&'static str),
   NegativeSubscript(i32),
   IndexOutOfBounds(i32, usize),
   InitializerRequired(String),
+  /// 声明为 `volatile` 的常量或指针
+  IllegalVolatile(String),
   TooManyInitializers,
+  ScalarInitializerList,
   NotLValue,
+  /// 需要临时变量才能展开的扩展语法出现在函数之外
+  NotInFunction(&'static str),
   Other(String),
 }
 
 impl CompileError {
-  fn message(&self) -> String {
+  pub fn message(&self) -> String {
     match self {
       Self::UndeclaredSymbol(ident) => format!("符号 '{}' 未定义", ident),
-      Self::TypeMismatch(expect, val, now) => format!("类型不匹配： 期望{}类型，但 '{}' 是{}类型", expect, val, now),
+      Self::ImplicitDeclaration(ident) => format!("隐式声明函数 '{}'：调用前须声明", ident),
+      Self::TypeMismatch(expect, val, now) => format!(
+        "类型不匹配： 期望{}类型，但 '{}' 是{}类型",
+        expect, val, now
+      ),
       Self::IllegalBreak => "break 只能在循环中使用".into(),
       Self::IllegalContinue => "continue 只能在循环中使用".into(),
       Self::IllegalVoid => "不能将变量声明为 void 类型".into(),
       Self::Redefinition(ident) => format!("符号 '{}' 重复定义", ident),
+      Self::ConflictingDeclaration(ident) => format!("函数 '{}' 的类型与之前的声明不一致", ident),
+      Self::ConflictingAttributes(ident, conflict) => {
+        format!("函数 '{}' 的属性冲突：{}", ident, conflict)
+      }
+      Self::DuplicateParameter(ident) => format!("参数 '{}' 重复", ident),
+      Self::ParameterShadowsFunction(ident) => format!("参数 '{}' 与所在的函数同名", ident),
+      Self::VoidParameter(ident) => format!("不能将参数 '{}' 声明为 void 类型", ident),
+      Self::NoMatchingOverload(ident) => format!("没有与实参类型相符的函数 '{}' 的重载", ident),
       Self::ConstexprRequired(ty) => format!("{}必须是常量表达式", ty),
       Self::NegativeSubscript(val) => format!("不能用负数 {} 作为数组大小", val),
       Self::IndexOutOfBounds(val, lim) => format!("数组索引 {} 超出范围 [0, {})", val, lim),
       Self::InitializerRequired(val) => format!("常量 {} 的声明需带初始化器", val),
+      Self::IllegalVolatile(ident) => {
+        format!(
+          "不能将 '{}' 声明为 volatile：常量与指针不能是 volatile",
+          ident
+        )
+      }
       Self::TooManyInitializers => "初始化器太多".into(),
+      Self::ScalarInitializerList => "不能用初始化列表初始化标量".into(),
       Self::NotLValue => "不是左值".into(),
+      Self::NotInFunction(what) => format!("{}只能在函数中使用", what),
       Self::Other(msg) => msg.clone(),
     }
   }
@@ -58,6 +92,26 @@ impl fmt::Display for CompileError {
   }
 }
 
+/// 几个源文件一同编译时，出错的源文件与其中的错误。文件名单独保存，诊断（见
+/// [`crate::Diagnostic::from_error`]）由此得到 `file`，错误本身的种类与信息不变
+#[derive(Debug)]
+pub struct FileError {
+  pub file: String,
+  pub error: Box<dyn Error>,
+}
+
+impl Error for FileError {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    Some(self.error.as_ref())
+  }
+}
+
+impl fmt::Display for FileError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: {}", self.file, self.error)
+  }
+}
+
 #[derive(Debug)]
 pub struct PushKeyError(pub Box<dyn fmt::Debug>);
 
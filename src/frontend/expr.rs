@@ -4,33 +4,45 @@ pub mod ty;
 
 use koopa::ir::builder::{LocalInstBuilder, ValueBuilder};
 use koopa::ir::{BinaryOp, Type, Value};
+use log::trace;
 
 use self::category::Category;
 
 use super::ast::{
-  AddExp, AddOp, AssignExp, EqExp, EqOp, Exp, LAndExp, LOrExp, MulExp, MulOp, PostfixExp,
+  AddExp, AddOp, AssignExp, EqExp, EqOp, Exp, Id, LAndExp, LOrExp, MulExp, MulOp, Node, PostfixExp,
   PrimaryExp, RelExp, RelOp, UnaryExp, UnaryOp,
 };
 use super::decl::GenerateContext;
 use super::error::CompileError;
-use super::stmt::store_value_layout;
-use super::symbol::{Symbol, SymbolTable};
+use super::sanitize;
+use super::stmt::{store_value_layout, Layout};
+use super::symbol::Symbol;
 use crate::Result;
 
-use category::{GetCategory, ExpectCategory};
+use category::{ExpectCategory, GetCategory};
 use consteval::{Eval, EvalError};
-use ty::GetType;
+use ty::{GetType, SysyType};
 
 #[allow(unused_imports)]
 use super::error::UnimplementedError;
 
-pub fn generate<EvalExp: ToIrValue>(
+/// `__builtin_expect(exp, c)`：值为 `exp`，并提示 `exp` 很可能等于常量 `c`。
+/// 以其为条件（或条件的一部分）的分支据此排布基本块
+pub const BUILTIN_EXPECT: &str = "__builtin_expect";
+
+/// 检查类型并生成表达式；常量表达式直接得到其值
+pub fn generate<EvalExp: ToIrValue>(exp: &EvalExp, context: &mut GenerateContext) -> Result<Value> {
+  exp.get_type(context.scope())?;
+  generate_checked(exp, context)
+}
+
+/// 生成已检查过类型的表达式。推导类型时检查了整个表达式，各个子表达式因此不必再推导，
+/// 嵌套很深时也不会对每一层都推导一遍
+pub fn generate_checked<EvalExp: ToIrValue>(
   exp: &EvalExp,
   context: &mut GenerateContext,
 ) -> Result<Value> {
-  let eval_result = exp.eval(Some(context));
-  exp.get_type(Some(context))?;
-  match eval_result {
+  match exp.eval(context.scope()) {
     Ok(cv) => {
       if let Ok(int) = cv.as_int() {
         // 如果常量表达式是整数，则直接生成整数 Value
@@ -38,14 +50,18 @@ pub fn generate<EvalExp: ToIrValue>(
       } else {
         // 否则，意味着使用变量下标索引常量数组；
         // 必须将常量数组引入内存。
-        let alloc = context.dfg().new_value().alloc(cv.ir_type());
-        context.add_inst(alloc)?;
-        let data: Vec<_> = cv
+        let alloc = context.insert(|b| b.alloc(cv.ir_type()))?;
+        let elems = cv
           .data
+          .elems
           .iter()
-          .map(|&x| context.dfg().new_value().integer(x))
+          .map(|&(i, x)| (i, context.dfg().new_value().integer(x)))
           .collect();
-        store_value_layout(cv.ty.get_array_size(), alloc, data, context)?;
+        let layout = Layout {
+          len: cv.data.len,
+          elems,
+        };
+        store_value_layout(cv.ty.get_array_size(), alloc, layout, context)?;
         Ok(alloc)
       }
     }
@@ -58,17 +74,27 @@ pub fn generate<EvalExp: ToIrValue>(
   }
 }
 
+/// 生成表达式的 IR，不再尝试求值。只有一个子表达式的文法层次（如括号）的值与类型都与子表达式
+/// 相同，已在 [`generate`] 中求过，因此直接生成子表达式
 pub trait ToIrValue: Eval + GetType + GetCategory {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value>;
 }
 
+impl<T: ToIrValue + Node> ToIrValue for Id<T> {
+  fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
+    let ast = context.ast;
+    ast[*self].to_ir_value(context)
+  }
+}
+
 impl ToIrValue for Exp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      Exp::Assign(exp) => generate(exp.as_ref(), context),
+      Exp::Assign(exp) => exp.to_ir_value(context),
       Exp::Comma(lhs, rhs) => {
-        let _lhs = generate(lhs.as_ref(), context)?;
-        let rhs = generate(rhs.as_ref(), context)?;
+        // 逗号左侧的类型不属于整个表达式，另行检查
+        let _lhs = generate(lhs, context)?;
+        let rhs = generate_checked(rhs, context)?;
         Ok(rhs)
       }
     }
@@ -78,16 +104,18 @@ impl ToIrValue for Exp {
 impl ToIrValue for AssignExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      AssignExp::LOr(exp) => generate(exp.as_ref(), context),
+      AssignExp::LOr(exp) => exp.to_ir_value(context),
       AssignExp::Assign(lhs, rhs) => {
-        let lhs = lhs.expect(Category::LValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
-        // println!("ASSIGN-L: {:?}", context.dfg().value(lhs));
-        // println!("ASSIGN-R: {:?}", context.dfg().value(rhs));
-        let store = context.dfg().new_value().store(rhs, lhs);
-        context.add_inst(store)?;
+        let lhs = lhs.expect(Category::LValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
+        trace!("assign {:?}", context.dfg().value(rhs));
+        trace!("    to {:?}", context.dfg().value(lhs));
+        context.store(rhs, lhs)?;
         Ok(lhs)
       }
+      AssignExp::Compound(..) | AssignExp::Cond(..) => {
+        unreachable!("extended syntax should be desugared")
+      }
     }
   }
 }
@@ -117,45 +145,44 @@ where
   };
   let init_result = context.dfg().new_value().store(init_value, result);
 
-  let lhs = lhs.expect(Category::RValue)?.generate(context)?;
+  let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
   let branch_op = match op {
     ShortCircuitingOp::Or => BinaryOp::Eq,
     ShortCircuitingOp::And => BinaryOp::NotEq,
   };
   let lhs_op_zero = context.dfg().new_value().binary(branch_op, lhs, zero);
-  context.add_inst(result)?;
-  context.add_inst(init_result)?;
-  context.add_inst(lhs_op_zero)?;
+  context.add_insts(&[result, init_result, lhs_op_zero])?;
+  // 期望左侧为零时，`||` 可能求值右侧，`&&` 则可能不求值
+  if let Some(&expected) = context.expected.get(&lhs) {
+    let evaluates_rhs = match op {
+      ShortCircuitingOp::Or => !expected,
+      ShortCircuitingOp::And => expected,
+    };
+    context.expected.insert(lhs_op_zero, evaluates_rhs);
+  }
 
   context.new_bb_set();
   let true_bb = context.add_bb("sc_if_true")?;
   let end_bb = context.add_bb("sc_if_end")?;
-  let branch = context
-    .dfg()
-    .new_value()
-    .branch(lhs_op_zero, true_bb, end_bb);
+  let branch = context.branch(lhs_op_zero, true_bb, end_bb)?;
   context.switch_bb(branch, Some(true_bb))?;
 
-  let rhs = rhs.expect(Category::RValue)?.generate(context)?;
+  let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
   let rhs_neq_zero = context.dfg().new_value().binary(BinaryOp::NotEq, rhs, zero);
-  let rhs_store = context.dfg().new_value().store(rhs, result);
-  context.add_inst(rhs_neq_zero)?;
-  context.add_inst(rhs_store)?;
+  let rhs_store = context.dfg().new_value().store(rhs_neq_zero, result);
+  context.add_insts(&[rhs_neq_zero, rhs_store])?;
 
   let jump = context.dfg().new_value().jump(end_bb);
-  let load = context.dfg().new_value().load(result);
   context.switch_bb(jump, Some(end_bb))?;
-  context.add_inst(load)?;
-
-  Ok(load)
+  context.insert(|b| b.load(result))
 }
 
 impl ToIrValue for LOrExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      LOrExp::And(exp) => generate(exp.as_ref(), context),
+      LOrExp::And(exp) => exp.to_ir_value(context),
       LOrExp::Or(lhs, rhs) => {
-        generate_with_short_circuiting(context, lhs.as_ref(), ShortCircuitingOp::Or, rhs.as_ref())
+        generate_with_short_circuiting(context, lhs, ShortCircuitingOp::Or, rhs)
       }
     }
   }
@@ -164,9 +191,9 @@ impl ToIrValue for LOrExp {
 impl ToIrValue for LAndExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      LAndExp::Eq(exp) => generate(exp.as_ref(), context),
+      LAndExp::Eq(exp) => exp.to_ir_value(context),
       LAndExp::And(lhs, rhs) => {
-        generate_with_short_circuiting(context, lhs.as_ref(), ShortCircuitingOp::And, rhs.as_ref())
+        generate_with_short_circuiting(context, lhs, ShortCircuitingOp::And, rhs)
       }
     }
   }
@@ -175,17 +202,15 @@ impl ToIrValue for LAndExp {
 impl ToIrValue for EqExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      EqExp::Rel(exp) => generate(exp.as_ref(), context),
+      EqExp::Rel(exp) => exp.to_ir_value(context),
       EqExp::Eq(lhs, op, rhs) => {
-        let lhs = lhs.expect(Category::RValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
+        let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
         let op = match op {
           EqOp::Equal => BinaryOp::Eq,
           EqOp::NotEqual => BinaryOp::NotEq,
         };
-        let result = context.dfg().new_value().binary(op, lhs, rhs);
-        context.add_inst(result)?;
-        Ok(result)
+        context.insert(|b| b.binary(op, lhs, rhs))
       }
     }
   }
@@ -194,19 +219,17 @@ impl ToIrValue for EqExp {
 impl ToIrValue for RelExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      RelExp::Add(exp) => generate(exp.as_ref(), context),
+      RelExp::Add(exp) => exp.to_ir_value(context),
       RelExp::Rel(lhs, op, rhs) => {
-        let lhs = lhs.expect(Category::RValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
+        let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
         let op = match op {
           RelOp::Less => BinaryOp::Lt,
           RelOp::LessEqual => BinaryOp::Le,
           RelOp::Greater => BinaryOp::Gt,
           RelOp::GreaterEqual => BinaryOp::Ge,
         };
-        let result = context.dfg().new_value().binary(op, lhs, rhs);
-        context.add_inst(result)?;
-        Ok(result)
+        context.insert(|b| b.binary(op, lhs, rhs))
       }
     }
   }
@@ -215,17 +238,15 @@ impl ToIrValue for RelExp {
 impl ToIrValue for AddExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      AddExp::Mul(exp) => generate(exp.as_ref(), context),
+      AddExp::Mul(exp) => exp.to_ir_value(context),
       AddExp::Add(lhs, op, rhs) => {
-        let lhs = lhs.expect(Category::RValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
+        let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
         let op = match op {
           AddOp::Plus => BinaryOp::Add,
           AddOp::Minus => BinaryOp::Sub,
         };
-        let result = context.dfg().new_value().binary(op, lhs, rhs);
-        context.add_inst(result)?;
-        Ok(result)
+        context.insert(|b| b.binary(op, lhs, rhs))
       }
     }
   }
@@ -234,18 +255,19 @@ impl ToIrValue for AddExp {
 impl ToIrValue for MulExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      MulExp::Unary(exp) => generate(exp.as_ref(), context),
+      MulExp::Unary(exp) => exp.to_ir_value(context),
       MulExp::Mul(lhs, op, rhs) => {
-        let lhs = lhs.expect(Category::RValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
+        let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
         let op = match op {
           MulOp::Multiply => BinaryOp::Mul,
           MulOp::Divide => BinaryOp::Div,
           &MulOp::Modulo => BinaryOp::Mod,
         };
-        let result = context.dfg().new_value().binary(op, lhs, rhs);
-        context.add_inst(result)?;
-        Ok(result)
+        if op != BinaryOp::Mul && context.compilation.sanitize.integer {
+          sanitize::check_division(context, lhs, rhs)?;
+        }
+        context.insert(|b| b.binary(op, lhs, rhs))
       }
     }
   }
@@ -254,30 +276,27 @@ impl ToIrValue for MulExp {
 impl ToIrValue for UnaryExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      UnaryExp::Postfix(exp) => generate(exp.as_ref(), context),
-      UnaryExp::Address(exp) => {
-        exp.expect(Category::LValue)?.generate(context)
-      }
-      UnaryExp::Deref(exp) => {
-        exp.expect(Category::RValue)?.generate(context)
-      },
+      UnaryExp::Postfix(exp) => exp.to_ir_value(context),
+      UnaryExp::Address(exp) => exp.expect(Category::LValue).generate_checked(context),
+      UnaryExp::Deref(exp) => exp.expect(Category::RValue).generate_checked(context),
       UnaryExp::Op(op, exp) => match op {
-        UnaryOp::Positive => exp.expect(Category::RValue)?.generate(context),
+        UnaryOp::Positive => exp.expect(Category::RValue).generate_checked(context),
         UnaryOp::Negative => {
-          let value = exp.expect(Category::RValue)?.generate(context)?;
+          let value = exp.expect(Category::RValue).generate_checked(context)?;
           let zero = context.dfg().new_value().integer(0);
-          let result = context.dfg().new_value().binary(BinaryOp::Sub, zero, value);
-          context.add_inst(result)?;
-          Ok(result)
+          context.insert(|b| b.binary(BinaryOp::Sub, zero, value))
         }
         UnaryOp::Not => {
-          let value = exp.expect(Category::RValue)?.generate(context)?;
+          let value = exp.expect(Category::RValue).generate_checked(context)?;
           let zero = context.dfg().new_value().integer(0);
-          let result = context.dfg().new_value().binary(BinaryOp::Eq, value, zero);
-          context.add_inst(result)?;
+          let result = context.insert(|b| b.binary(BinaryOp::Eq, value, zero))?;
+          if let Some(&expected) = context.expected.get(&value) {
+            context.expected.insert(result, !expected);
+          }
           Ok(result)
         }
       },
+      UnaryExp::Step(..) => unreachable!("prefix step should be desugared"),
     }
   }
 }
@@ -285,35 +304,55 @@ impl ToIrValue for UnaryExp {
 impl ToIrValue for PostfixExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      PostfixExp::Primary(exp) => generate(exp, context),
-      PostfixExp::Call(func_name, args) => {
-        let func = SymbolTable::get_global(func_name)
-          .ok_or(CompileError::UndeclaredSymbol(func_name.clone()))?;
+      PostfixExp::Primary(exp) => exp.to_ir_value(context),
+      PostfixExp::Call(func_name, _, args) if func_name == BUILTIN_EXPECT => {
+        let expected = args[1].eval(context.scope()).and_then(|v| Ok(v.as_int()?));
+        let expected = expected.map_err(|e| e.to_compile_error("__builtin_expect 的第二个参数"))?;
+        let value = args[0].expect(Category::RValue).generate_checked(context)?;
+        context.expected.insert(value, expected != 0);
+        Ok(value)
+      }
+      PostfixExp::Call(func_name, _, args) => {
+        let func = context
+          .compilation
+          .globals
+          .get(func_name)
+          .ok_or(CompileError::ImplicitDeclaration(func_name.to_string()))?;
 
+        // 与函数同名的全局变量或常量，同样没有声明函数
         if let Symbol::Func(_, func) = func {
           let args = args
             .iter()
-            .map(|arg| arg.expect(Category::RValue)?.generate(context))
+            .map(|arg| arg.expect(Category::RValue).generate_checked(context))
             .collect::<Result<Vec<_>>>()?;
-          let result = context.dfg().new_value().call(func, args);
-          context.add_inst(result)?;
-          Ok(result)
+          let call = context.insert(|b| b.call(func, args))?;
+          let attrs = &context.compilation.annotations.func_attrs;
+          if attrs.get(&func).is_some_and(|attrs| attrs.noreturn) {
+            context.end_unreachable()?;
+          }
+          Ok(call)
         } else {
-          Err(CompileError::TypeMismatch(
-            "函数",
-            func_name.clone(),
-            "变量/常量",
-          ))?
+          Err(CompileError::ImplicitDeclaration(func_name.to_string()))?
         }
       }
       PostfixExp::Subscript(lhs, rhs) => {
-        let lhs = lhs.expect(Category::RValue)?.generate(context)?;
-        let rhs = rhs.expect(Category::RValue)?.generate(context)?;
-        // println!("SUB: {:?}", context.dfg().value(lhs));
-        let result = context.dfg().new_value().get_ptr(lhs, rhs);
-        context.add_inst(result)?;
-        Ok(result)
+        // 只有数组有长度；指针（如数组形参的第一维）无从检查
+        let len = match context.compilation.sanitize.bounds {
+          true => match lhs.get_type(context.scope())? {
+            SysyType::Array(_, len) => Some(len),
+            _ => None,
+          },
+          false => None,
+        };
+        let lhs = lhs.expect(Category::RValue).generate_checked(context)?;
+        let rhs = rhs.expect(Category::RValue).generate_checked(context)?;
+        if let Some(len) = len {
+          sanitize::check_bounds(context, rhs, len)?;
+        }
+        trace!("subscript {:?}", context.dfg().value(lhs));
+        context.insert(|b| b.get_ptr(lhs, rhs))
       }
+      PostfixExp::Step(..) => unreachable!("postfix step should be desugared"),
     }
   }
 }
@@ -321,25 +360,19 @@ impl ToIrValue for PostfixExp {
 impl ToIrValue for PrimaryExp {
   fn to_ir_value(&self, context: &mut GenerateContext) -> Result<Value> {
     match self {
-      PrimaryExp::Paren(exp) => generate(exp.as_ref(), context),
+      PrimaryExp::Paren(exp) => exp.to_ir_value(context),
       PrimaryExp::Num(num) => {
         let value = context.dfg().new_value().integer(*num);
         Ok(value)
       }
-      PrimaryExp::Ident(lval) => {
-        let symbol = context
-          .symbol
-          .get(lval)
-          .or_else(|| SymbolTable::get_global(lval));
-        match symbol {
-          None => Err(CompileError::UndeclaredSymbol(lval.into()))?,
-          Some(symbol) => match symbol {
-            Symbol::Const(_) => panic!("constant identifier: should unreachable"),
-            Symbol::Var(_, val) => Ok(val),
-            Symbol::Func(..) => Err(CompileError::TypeMismatch("变量", lval.clone(), "函数"))?,
-          },
-        }
-      }
+      PrimaryExp::Ident(lval) => match context.scope().get(lval) {
+        None => Err(CompileError::UndeclaredSymbol(lval.to_string()))?,
+        Some(symbol) => match symbol {
+          Symbol::Const(_) => panic!("constant identifier: should unreachable"),
+          Symbol::Var(_, val) => Ok(val),
+          Symbol::Func(..) => Err(CompileError::TypeMismatch("变量", lval.to_string(), "函数"))?,
+        },
+      },
     }
   }
 }
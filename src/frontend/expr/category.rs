@@ -1,13 +1,13 @@
 use koopa::ir::builder::{LocalInstBuilder, ValueBuilder};
 use koopa::ir::{TypeKind, Value};
 
-use super::{generate, ToIrValue};
+use super::{generate, generate_checked, ToIrValue};
 use crate::frontend::error::CompileError;
 use crate::{
   frontend::{
     ast::{
-      AddExp, AssignExp, EqExp, Exp, LAndExp, LOrExp, MulExp, PostfixExp, PrimaryExp, RelExp,
-      UnaryExp,
+      AddExp, AssignExp, Ast, EqExp, Exp, Id, LAndExp, LOrExp, MulExp, Node, PostfixExp,
+      PrimaryExp, RelExp, UnaryExp,
     },
     decl::GenerateContext,
   },
@@ -21,135 +21,166 @@ pub enum Category {
 }
 
 pub trait GetCategory {
-  fn get_category(&self) -> Category;
+  fn get_category(&self, ast: &Ast) -> Category;
+}
+
+impl<T: GetCategory + Node> GetCategory for Id<T> {
+  fn get_category(&self, ast: &Ast) -> Category {
+    ast[*self].get_category(ast)
+  }
 }
 
 impl GetCategory for Exp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      Exp::Assign(exp) => exp.get_category(),
-      Exp::Comma(_, rhs) => rhs.get_category(),
+      Exp::Assign(exp) => exp.get_category(ast),
+      Exp::Comma(_, rhs) => rhs.get_category(ast),
     }
   }
 }
 
 impl GetCategory for AssignExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      AssignExp::LOr(exp) => exp.get_category(),
+      AssignExp::LOr(exp) => exp.get_category(ast),
       AssignExp::Assign(..) => Category::LValue,
+      AssignExp::Compound(..) | AssignExp::Cond(..) => {
+        unreachable!("extended syntax should be desugared")
+      }
     }
   }
 }
 
 impl GetCategory for LOrExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      LOrExp::And(exp) => exp.get_category(),
+      LOrExp::And(exp) => exp.get_category(ast),
       LOrExp::Or(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for LAndExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      LAndExp::Eq(exp) => exp.get_category(),
+      LAndExp::Eq(exp) => exp.get_category(ast),
       LAndExp::And(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for EqExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      EqExp::Rel(exp) => exp.get_category(),
+      EqExp::Rel(exp) => exp.get_category(ast),
       EqExp::Eq(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for RelExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      RelExp::Add(exp) => exp.get_category(),
+      RelExp::Add(exp) => exp.get_category(ast),
       RelExp::Rel(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for AddExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      AddExp::Mul(exp) => exp.get_category(),
+      AddExp::Mul(exp) => exp.get_category(ast),
       AddExp::Add(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for MulExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      MulExp::Unary(exp) => exp.get_category(),
+      MulExp::Unary(exp) => exp.get_category(ast),
       MulExp::Mul(..) => Category::RValue,
     }
   }
 }
 
 impl GetCategory for UnaryExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      UnaryExp::Postfix(exp) => exp.get_category(),
+      UnaryExp::Postfix(exp) => exp.get_category(ast),
       UnaryExp::Deref(..) => Category::LValue,
       UnaryExp::Address(..) => Category::RValue,
       UnaryExp::Op(..) => Category::RValue,
+      UnaryExp::Step(..) => unreachable!("prefix step should be desugared"),
     }
   }
 }
 
 impl GetCategory for PostfixExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
-      PostfixExp::Primary(exp) => exp.get_category(),
+      PostfixExp::Primary(exp) => exp.get_category(ast),
       PostfixExp::Call(..) => Category::RValue,
       PostfixExp::Subscript(..) => Category::LValue,
+      PostfixExp::Step(..) => unreachable!("postfix step should be desugared"),
     }
   }
 }
 
 impl GetCategory for PrimaryExp {
-  fn get_category(&self) -> Category {
+  fn get_category(&self, ast: &Ast) -> Category {
     match self {
       PrimaryExp::Ident(..) => Category::LValue,
-      PrimaryExp::Paren(exp) => exp.get_category(),
+      PrimaryExp::Paren(exp) => exp.get_category(ast),
       PrimaryExp::Num(..) => Category::RValue,
     }
   }
 }
 
 pub trait ExpectCategory<T: ToIrValue> {
-  fn expect(&self, category: Category) -> Result<ValueGenerator<T>>;
+  fn expect(&self, category: Category) -> ValueGenerator<'_, T>;
 }
 
-pub struct ValueGenerator<'a, T: ToIrValue>(&'a T, bool);
+/// 按所期望的值类别生成表达式；类别在生成时检查，此时才能从语法树得知表达式本身的类别
+pub struct ValueGenerator<'a, T: ToIrValue>(&'a T, Category);
 
 impl<'a, T: ToIrValue> ValueGenerator<'a, T> {
   pub fn generate(&self, context: &mut GenerateContext) -> Result<Value> {
-    let val = generate(self.0, context)?;
-    if self.1 {
+    self.generate_impl(context, true)
+  }
+
+  /// 生成已检查过类型的子表达式，见 [`generate_checked`]
+  pub fn generate_checked(&self, context: &mut GenerateContext) -> Result<Value> {
+    self.generate_impl(context, false)
+  }
+
+  fn generate_impl(&self, context: &mut GenerateContext, check_type: bool) -> Result<Value> {
+    // 是否需要从左值中取值
+    let load = match (self.1, self.0.get_category(context.ast)) {
+      (Category::LValue, Category::LValue) => false,
+      (Category::LValue, Category::RValue) => Err(CompileError::NotLValue)?,
+      (Category::RValue, Category::RValue) => false,
+      (Category::RValue, Category::LValue) => true,
+    };
+    let val = if check_type {
+      generate(self.0, context)?
+    } else {
+      generate_checked(self.0, context)?
+    };
+    let is_ptr = matches!(context.value_ty_kind(val), TypeKind::Pointer(_));
+    if matches!(self.1, Category::LValue) && !is_ptr {
+      // 常量折叠成了整数，没有地址
+      Err(CompileError::NotLValue)?;
+    }
+    if load {
       match context.value_ty_kind(val) {
         TypeKind::Pointer(base) => match base.kind() {
           TypeKind::Array(..) => {
             let zero = context.dfg().new_value().integer(0);
-            let elem = context.dfg().new_value().get_elem_ptr(val, zero);
-            context.add_inst(elem)?;
-            Ok(elem)
-          }
-          _ => {
-            let load = context.dfg().new_value().load(val);
-            context.add_inst(load)?;
-            Ok(load)
+            context.insert(|b| b.get_elem_ptr(val, zero))
           }
+          _ => context.insert(|b| b.load(val)),
         },
         _ => {
           // Constexpr value
@@ -163,16 +194,7 @@ impl<'a, T: ToIrValue> ValueGenerator<'a, T> {
 }
 
 impl<T: ToIrValue> ExpectCategory<T> for T {
-  fn expect(&self, category: Category) -> Result<ValueGenerator<T>> {
-    match category {
-      Category::LValue => match self.get_category() {
-        Category::LValue => Ok(ValueGenerator(self, false)),
-        Category::RValue => Err(CompileError::NotLValue)?,
-      },
-      Category::RValue => match self.get_category() {
-        Category::RValue => Ok(ValueGenerator(self, false)),
-        Category::LValue => Ok(ValueGenerator(self, true)),
-      },
-    }
+  fn expect(&self, category: Category) -> ValueGenerator<'_, T> {
+    ValueGenerator(self, category)
   }
 }
@@ -1,12 +1,14 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
-use super::GenerateContext;
 use crate::frontend::ast::{
-  AddExp, AddOp, AssignExp, EqExp, EqOp, Exp, Initializer, InitializerLike, LAndExp, LOrExp,
-  MulExp, MulOp, PostfixExp, PrimaryExp, RelExp, RelOp, UnaryExp, UnaryOp,
+  AddExp, AddOp, AssignExp, Ast, Block, BlockItem, Decl, Declarator, EqExp, EqOp, Exp, FuncDecl,
+  Id, Initializer, InitializerLike, LAndExp, LOrExp, MulExp, MulOp, Name, Node, PostfixExp,
+  PrimaryExp, RelExp, RelOp, Stmt, TypeSpec, UnaryExp, UnaryOp,
 };
 use crate::frontend::error::CompileError;
-use crate::frontend::symbol::{ConstValue, Symbol, SymbolTable};
+use crate::frontend::symbol::{ConstValue, GlobalSymbols, Scope, Symbol, SymbolTable};
 
 pub enum EvalError {
   NotConstexpr,
@@ -31,40 +33,57 @@ impl From<CompileError> for EvalError {
 pub type EvalResult = std::result::Result<ConstValue, EvalError>;
 
 pub trait Eval {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult;
+  fn eval(&self, scope: Scope) -> EvalResult;
+}
+
+impl<T: Eval + Node> Eval for Id<T> {
+  fn eval(&self, scope: Scope) -> EvalResult {
+    let Some(memo) = scope.memo else {
+      return scope.ast[*self].eval(scope);
+    };
+    if memo.is_not_constexpr(*self) {
+      return Err(EvalError::NotConstexpr);
+    }
+    let result = scope.ast[*self].eval(scope);
+    if let Err(EvalError::NotConstexpr) = result {
+      memo.record_not_constexpr(*self);
+    }
+    result
+  }
 }
 
 impl Eval for Exp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      Exp::Assign(exp) => exp.eval(context),
+      Exp::Assign(exp) => exp.eval(scope),
       Exp::Comma(..) => Err(EvalError::NotConstexpr),
     }
   }
 }
 
 impl Eval for AssignExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      AssignExp::LOr(exp) => exp.eval(context),
-      AssignExp::Assign(_, rhs) => match context {
-        None => rhs.eval(None),
+      AssignExp::LOr(exp) => exp.eval(scope),
+      AssignExp::Assign(_, rhs) => match scope.locals {
+        None => rhs.eval(scope),
         Some(_) => Err(EvalError::NotConstexpr),
-      }
+      },
+      AssignExp::Compound(..) | AssignExp::Cond(..) => Err(EvalError::NotConstexpr),
     }
   }
 }
 
 impl Eval for LOrExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      LOrExp::And(exp) => exp.eval(context),
+      LOrExp::And(exp) => exp.eval(scope),
       LOrExp::Or(lhs, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()? != 0;
+        let lhs = lhs.eval(scope)?.as_int()? != 0;
         if lhs {
           Ok(ConstValue::int(1))
         } else {
-          let rhs = rhs.eval(context)?.as_int()? != 0;
+          let rhs = rhs.eval(scope)?.as_int()? != 0;
           Ok(ConstValue::int(rhs as i32))
         }
       }
@@ -73,13 +92,13 @@ impl Eval for LOrExp {
 }
 
 impl Eval for LAndExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      LAndExp::Eq(exp) => exp.eval(context),
+      LAndExp::Eq(exp) => exp.eval(scope),
       LAndExp::And(lhs, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()? != 0;
+        let lhs = lhs.eval(scope)?.as_int()? != 0;
         if lhs {
-          let rhs = rhs.eval(context)?.as_int()? != 0;
+          let rhs = rhs.eval(scope)?.as_int()? != 0;
           Ok(ConstValue::int(rhs as i32))
         } else {
           Ok(ConstValue::int(0))
@@ -90,12 +109,12 @@ impl Eval for LAndExp {
 }
 
 impl Eval for EqExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      EqExp::Rel(exp) => exp.eval(context),
+      EqExp::Rel(exp) => exp.eval(scope),
       EqExp::Eq(lhs, op, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()?;
-        let rhs = rhs.eval(context)?.as_int()?;
+        let lhs = lhs.eval(scope)?.as_int()?;
+        let rhs = rhs.eval(scope)?.as_int()?;
         let result = match op {
           EqOp::Equal => (lhs == rhs) as i32,
           EqOp::NotEqual => (lhs != rhs) as i32,
@@ -107,12 +126,12 @@ impl Eval for EqExp {
 }
 
 impl Eval for RelExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      RelExp::Add(exp) => exp.eval(context),
+      RelExp::Add(exp) => exp.eval(scope),
       RelExp::Rel(lhs, op, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()?;
-        let rhs = rhs.eval(context)?.as_int()?;
+        let lhs = lhs.eval(scope)?.as_int()?;
+        let rhs = rhs.eval(scope)?.as_int()?;
         let result = match op {
           RelOp::Less => (lhs < rhs) as i32,
           RelOp::LessEqual => (lhs <= rhs) as i32,
@@ -126,15 +145,15 @@ impl Eval for RelExp {
 }
 
 impl Eval for AddExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      AddExp::Mul(exp) => exp.eval(context),
+      AddExp::Mul(exp) => exp.eval(scope),
       AddExp::Add(lhs, op, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()?;
-        let rhs = rhs.eval(context)?.as_int()?;
+        let lhs = lhs.eval(scope)?.as_int()?;
+        let rhs = rhs.eval(scope)?.as_int()?;
         let result = match op {
-          AddOp::Plus => lhs + rhs,
-          AddOp::Minus => lhs - rhs,
+          AddOp::Plus => lhs.wrapping_add(rhs),
+          AddOp::Minus => lhs.wrapping_sub(rhs),
         };
         Ok(ConstValue::int(result))
       }
@@ -143,96 +162,363 @@ impl Eval for AddExp {
 }
 
 impl Eval for MulExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      MulExp::Unary(exp) => exp.eval(context),
+      MulExp::Unary(exp) => exp.eval(scope),
       MulExp::Mul(lhs, op, rhs) => {
-        let lhs = lhs.eval(context)?.as_int()?;
-        let rhs = rhs.eval(context)?.as_int()?;
+        let lhs = lhs.eval(scope)?.as_int()?;
+        let rhs = rhs.eval(scope)?.as_int()?;
+        // 除以零不是常量，留到运行时
         let result = match op {
-          MulOp::Multiply => lhs * rhs,
-          MulOp::Divide => lhs / rhs,
-          MulOp::Modulo => lhs % rhs,
+          MulOp::Multiply => Some(lhs.wrapping_mul(rhs)),
+          MulOp::Divide => lhs.checked_div(rhs),
+          MulOp::Modulo => lhs.checked_rem(rhs),
         };
-        Ok(ConstValue::int(result))
+        result.map(ConstValue::int).ok_or(EvalError::NotConstexpr)
       }
     }
   }
 }
 
 impl Eval for UnaryExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      UnaryExp::Postfix(exp) => exp.eval(context),
+      UnaryExp::Postfix(exp) => exp.eval(scope),
       UnaryExp::Op(op, exp) => {
-        let exp = exp.eval(context)?.as_int()?;
+        let exp = exp.eval(scope)?.as_int()?;
         let result = match op {
           UnaryOp::Positive => exp,
-          UnaryOp::Negative => -exp,
+          UnaryOp::Negative => exp.wrapping_neg(),
           UnaryOp::Not => (exp == 0) as i32,
         };
         Ok(ConstValue::int(result))
       }
       UnaryExp::Deref(_) => Err(EvalError::NotConstexpr),
       &UnaryExp::Address(_) => Err(EvalError::NotConstexpr),
+      UnaryExp::Step(..) => Err(EvalError::NotConstexpr),
     }
   }
 }
 
 impl Eval for PostfixExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      PostfixExp::Primary(exp) => exp.eval(context),
-      PostfixExp::Call(..) => Err(EvalError::NotConstexpr),
+      PostfixExp::Primary(exp) => exp.eval(scope),
+      PostfixExp::Call(func, _, args) => {
+        let Some(Symbol::Func(..)) = scope.get(func) else {
+          return Err(EvalError::NotConstexpr);
+        };
+        let mut values = vec![];
+        for arg in args {
+          match arg.eval(scope)?.as_int() {
+            Ok(value) => values.push(value),
+            Err(_) => return Err(EvalError::NotConstexpr),
+          }
+        }
+        call(func, &values, scope)
+          .map(ConstValue::int)
+          .ok_or(EvalError::NotConstexpr)
+      }
       PostfixExp::Subscript(lval, exp) => {
-        let exp = exp.eval(context)?.as_int()?;
-        let lval = lval.eval(context)?;
+        let exp = exp.eval(scope)?.as_int()?;
+        let lval = lval.eval(scope)?;
         lval.item(exp).map_err(|e| EvalError::CompileError(e))
       }
+      PostfixExp::Step(..) => Err(EvalError::NotConstexpr),
     }
   }
 }
 
 impl Eval for PrimaryExp {
-  fn eval(&self, context: Option<&GenerateContext>) -> EvalResult {
+  fn eval(&self, scope: Scope) -> EvalResult {
     match self {
-      PrimaryExp::Ident(ident) => {
-        let symbol = match context {
-          Some(context) => context
-            .symbol
-            .get(ident)
-            .or_else(|| SymbolTable::get_global(ident)),
-          None => SymbolTable::get_global(ident),
-        };
-        match symbol {
-          Some(symbol) => match symbol {
-            Symbol::Const(i) => Ok(i.clone()),
-            Symbol::Var(..) => Err(EvalError::NotConstexpr)?,
-            Symbol::Func(..) => Err(CompileError::TypeMismatch("变量", ident.clone(), "函数"))?,
-          },
-          None => Err(CompileError::UndeclaredSymbol(ident.clone()))?,
-        }
-      }
+      PrimaryExp::Ident(ident) => match scope.get(ident) {
+        Some(symbol) => match symbol {
+          Symbol::Const(i) => Ok(i.clone()),
+          Symbol::Var(..) => Err(EvalError::NotConstexpr)?,
+          Symbol::Func(..) => Err(CompileError::TypeMismatch(
+            "变量",
+            ident.to_string(),
+            "函数",
+          ))?,
+        },
+        None => Err(CompileError::UndeclaredSymbol(ident.to_string()))?,
+      },
       PrimaryExp::Num(i) => Ok(ConstValue::int(*i)),
-      PrimaryExp::Paren(exp) => exp.eval(context),
+      PrimaryExp::Paren(exp) => exp.eval(scope),
     }
   }
 }
 
 impl Initializer {
-  pub fn eval(
-    &self,
-    context: Option<&GenerateContext>,
-  ) -> std::result::Result<InitializerLike<i32>, EvalError> {
+  pub fn eval(&self, scope: Scope) -> std::result::Result<InitializerLike<i32>, EvalError> {
     match self {
-      Initializer::Simple(exp) => Ok(InitializerLike::Simple(exp.eval(context)?.as_int()?)),
+      Initializer::Simple(exp) => Ok(InitializerLike::Simple(exp.eval(scope)?.as_int()?)),
       Initializer::Aggregate(aggr) => {
         let mut result: Vec<Rc<_>> = vec![];
-        for exp in aggr {
-          result.push(exp.eval(context)?.into());
+        for &init in aggr {
+          result.push(scope.ast[init].eval(scope)?.into());
         }
         Ok(InitializerLike::Aggregate(result))
       }
     }
   }
 }
+
+/// 编译时求值一次调用至多执行的步数（语句、循环的轮数与调用），超过时留到运行时调用
+const STEP_LIMIT: usize = 100_000;
+
+/// 编译时求值函数调用的状态，由一次求值中的各层调用共用
+#[derive(Default)]
+pub struct CallStack {
+  /// 正在求值的函数；递归的调用不在编译时求值
+  funcs: RefCell<Vec<Name>>,
+  steps: Cell<usize>,
+}
+
+impl CallStack {
+  fn step(&self) -> std::result::Result<(), EvalError> {
+    self.steps.set(self.steps.get() + 1);
+    match self.steps.get() > STEP_LIMIT {
+      true => Err(EvalError::NotConstexpr),
+      false => Ok(()),
+    }
+  }
+}
+
+/// 在编译时求值以 `args` 调用 `scope.ast` 中定义的函数 `func`。函数须返回 `int`、形参都是
+/// `int`，函数体只读写 `int` 的局部变量、读取全局常量，只调用同样的函数且不递归，并在
+/// [`STEP_LIMIT`] 步之内返回；否则返回 `None`，留到运行时调用
+pub fn call(func: &Name, args: &[i32], scope: Scope) -> Option<i32> {
+  let stack;
+  let calls = match scope.calls {
+    Some(calls) => calls,
+    None => {
+      stack = CallStack::default();
+      &stack
+    }
+  };
+  calls.step().ok()?;
+  if calls.funcs.borrow().contains(func) {
+    return None;
+  }
+  let ast = scope.ast;
+  // 语义分析时重载的函数尚未改名，形参个数相同且都是 `int` 的重载至多一个
+  let int_params = |decl: &FuncDecl| {
+    let mut params = decl.params.iter();
+    params.len() == args.len()
+      && params.all(|&(ty, p)| ty == TypeSpec::Int && matches!(ast[p], Declarator::Ident(_)))
+  };
+  let decl = ast.unit.iter().find_map(|&decl| match &ast[decl] {
+    Decl::Func(decl) if decl.ident == *func && decl.body.is_some() && int_params(decl) => {
+      Some(decl)
+    }
+    _ => None,
+  })?;
+  if decl.func_type != TypeSpec::Int {
+    return None;
+  }
+  let mut frame = Frame {
+    ast,
+    globals: scope.globals,
+    calls,
+    locals: SymbolTable::new(),
+    vars: vec![HashMap::new()],
+  };
+  for (&(_, param), &arg) in decl.params.iter().zip(args) {
+    let Declarator::Ident(param) = &ast[param] else {
+      return None;
+    };
+    frame.define(param.clone(), arg, true).ok()?;
+  }
+  calls.funcs.borrow_mut().push(func.clone());
+  // 函数体中的错误在生成函数时报告
+  let result = frame.block(decl.body.as_ref().unwrap());
+  calls.funcs.borrow_mut().pop();
+  match result {
+    Ok(Flow::Return(value)) => Some(value),
+    _ => None,
+  }
+}
+
+/// 语句执行之后的去向
+enum Flow {
+  Next,
+  Break,
+  Continue,
+  Return(i32),
+}
+
+/// 在编译时执行的一层函数调用
+struct Frame<'a> {
+  ast: &'a Ast,
+  globals: &'a GlobalSymbols,
+  calls: &'a CallStack,
+  /// 局部变量的值，都是 `Symbol::Const`
+  locals: SymbolTable,
+  /// 与 `locals` 的各层对应，各名字是否可以赋值（不是常量）
+  vars: Vec<HashMap<Name, bool>>,
+}
+
+type FlowResult = std::result::Result<Flow, EvalError>;
+
+impl<'a> Frame<'a> {
+  fn eval(&self, exp: &impl Eval) -> std::result::Result<i32, EvalError> {
+    let scope = Scope {
+      ast: self.ast,
+      globals: self.globals,
+      locals: Some(&self.locals),
+      memo: None,
+      calls: Some(self.calls),
+    };
+    exp
+      .eval(scope)?
+      .as_int()
+      .map_err(|_| EvalError::NotConstexpr)
+  }
+
+  fn define(
+    &mut self,
+    name: Name,
+    value: i32,
+    mutable: bool,
+  ) -> std::result::Result<(), EvalError> {
+    if !self
+      .locals
+      .insert(name.clone(), Symbol::Const(ConstValue::int(value)))
+    {
+      return Err(EvalError::NotConstexpr);
+    }
+    self.vars.last_mut().unwrap().insert(name, mutable);
+    Ok(())
+  }
+
+  fn block(&mut self, block: &Block) -> FlowResult {
+    self.locals.push();
+    self.vars.push(HashMap::new());
+    let result = self.block_items(block);
+    self.locals.pop();
+    self.vars.pop();
+    result
+  }
+
+  fn block_items(&mut self, block: &Block) -> FlowResult {
+    for &item in block {
+      let flow = match item {
+        BlockItem::Decl(decl) => self.decl(decl).map(|_| Flow::Next)?,
+        BlockItem::Stmt(stmt) => self.stmt(stmt)?,
+      };
+      if !matches!(flow, Flow::Next) {
+        return Ok(flow);
+      }
+    }
+    Ok(Flow::Next)
+  }
+
+  /// 只有带初始化器的 `int` 变量与常量
+  fn decl(&mut self, decl: Id<Decl>) -> std::result::Result<(), EvalError> {
+    let ast = self.ast;
+    let Decl::Var(decl) = &ast[decl] else {
+      return Err(EvalError::NotConstexpr);
+    };
+    for &(declarator, init) in &decl.list {
+      let (Declarator::Ident(name), Some(init)) = (&ast[declarator], init) else {
+        return Err(EvalError::NotConstexpr);
+      };
+      let Initializer::Simple(init) = &ast[init] else {
+        return Err(EvalError::NotConstexpr);
+      };
+      let value = self.eval(init)?;
+      self.define(name.clone(), value, !decl.is_const)?;
+    }
+    Ok(())
+  }
+
+  fn stmt(&mut self, stmt: Id<Stmt>) -> FlowResult {
+    self.calls.step()?;
+    let ast = self.ast;
+    Ok(match &ast[stmt] {
+      Stmt::Exp(None) => Flow::Next,
+      &Stmt::Exp(Some(exp)) => {
+        self.exp_stmt(exp)?;
+        Flow::Next
+      }
+      Stmt::Block(block) => self.block(block)?,
+      &Stmt::If(cond, then, otherwise) => match (self.eval(&cond)? != 0, otherwise) {
+        (true, _) => self.stmt(then)?,
+        (false, Some(otherwise)) => self.stmt(otherwise)?,
+        (false, None) => Flow::Next,
+      },
+      &Stmt::While(cond, body) => loop {
+        self.calls.step()?;
+        if self.eval(&cond)? == 0 {
+          break Flow::Next;
+        }
+        match self.stmt(body)? {
+          Flow::Break => break Flow::Next,
+          Flow::Return(value) => break Flow::Return(value),
+          Flow::Next | Flow::Continue => {}
+        }
+      },
+      Stmt::Break => Flow::Break,
+      Stmt::Continue => Flow::Continue,
+      &Stmt::Return(Some(exp)) => Flow::Return(self.eval(&exp)?),
+      Stmt::Return(None) | Stmt::Asm(_) | Stmt::For(..) => return Err(EvalError::NotConstexpr),
+    })
+  }
+
+  /// 表达式语句：对局部变量赋值，或只求值而舍去结果
+  fn exp_stmt(&mut self, exp: Id<Exp>) -> std::result::Result<(), EvalError> {
+    let ast = self.ast;
+    let &Exp::Assign(exp) = &ast[exp] else {
+      return Err(EvalError::NotConstexpr);
+    };
+    let &AssignExp::Assign(lhs, rhs) = &ast[exp] else {
+      self.eval(&exp)?;
+      return Ok(());
+    };
+    let name = lvalue_name(ast, lhs).ok_or(EvalError::NotConstexpr)?;
+    // 最内层的同名符号须是局部变量；全局变量与常量不在编译时赋值
+    let mutable = self.vars.iter().rev().find_map(|vars| vars.get(&name));
+    if mutable != Some(&true) {
+      return Err(EvalError::NotConstexpr);
+    }
+    let value = self.eval(&rhs)?;
+    self
+      .locals
+      .assign(&name, Symbol::Const(ConstValue::int(value)));
+    Ok(())
+  }
+}
+
+/// 只是一个名字的左值，如 `x = 1` 中的 `x`
+fn lvalue_name(ast: &Ast, exp: Id<LOrExp>) -> Option<Name> {
+  let &LOrExp::And(exp) = &ast[exp] else {
+    return None;
+  };
+  let &LAndExp::Eq(exp) = &ast[exp] else {
+    return None;
+  };
+  let &EqExp::Rel(exp) = &ast[exp] else {
+    return None;
+  };
+  let &RelExp::Add(exp) = &ast[exp] else {
+    return None;
+  };
+  let &AddExp::Mul(exp) = &ast[exp] else {
+    return None;
+  };
+  let &MulExp::Unary(exp) = &ast[exp] else {
+    return None;
+  };
+  let &UnaryExp::Postfix(exp) = &ast[exp] else {
+    return None;
+  };
+  let &PostfixExp::Primary(exp) = &ast[exp] else {
+    return None;
+  };
+  match &ast[exp] {
+    PrimaryExp::Ident(name) => Some(name.clone()),
+    _ => None,
+  }
+}
@@ -2,12 +2,12 @@ use koopa::ir::{Type, TypeKind};
 
 use super::consteval::Eval;
 use crate::frontend::ast::{
-  AddExp, Declarator, EqExp, FuncDecl, LAndExp, LOrExp, MulExp, PrimaryExp, RelExp, TypeSpec,
-  UnaryExp, Exp, AssignExp, PostfixExp,
+  AddExp, AssignExp, Declarator, EqExp, Exp, FuncDecl, Id, LAndExp, LOrExp, MulExp, Name, Node,
+  PostfixExp, PrimaryExp, RelExp, TypeSpec, UnaryExp,
 };
-use crate::frontend::decl::GenerateContext;
 use crate::frontend::error::CompileError;
-use crate::frontend::symbol::{Symbol, SymbolTable};
+use crate::frontend::expr::BUILTIN_EXPECT;
+use crate::frontend::symbol::{Scope, Symbol};
 use crate::Result;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -21,42 +21,36 @@ pub enum SysyType {
 
 impl SysyType {
   /// 从 AST 获取声明符的类型和名字
-  pub fn parse<'a>(
-    declarator: &'a Declarator,
-    context: Option<&GenerateContext>,
-  ) -> Result<(SysyType, &'a str)> {
+  pub fn parse(declarator: Id<Declarator>, scope: Scope) -> Result<(SysyType, Name)> {
     enum TyImpl {
       Array(usize),
       Pointer,
     }
 
-    fn decl_to_vec<'a>(
-      declarator: &'a Declarator,
-      context: Option<&GenerateContext>,
-    ) -> Result<(Vec<TyImpl>, &'a str)> {
-      match declarator {
-        Declarator::Ident(ident) => Ok((vec![], ident)),
+    fn decl_to_vec(declarator: Id<Declarator>, scope: Scope) -> Result<(Vec<TyImpl>, Name)> {
+      match &scope.ast[declarator] {
+        Declarator::Ident(ident) => Ok((vec![], ident.clone())),
         Declarator::Pointer(decl) => {
-          let (mut tys, ident) = decl_to_vec(decl, context)?;
+          let (mut tys, ident) = decl_to_vec(*decl, scope)?;
           tys.push(TyImpl::Pointer);
           Ok((tys, ident))
         }
         Declarator::Array(decl, exp) => {
           let value = exp
-            .eval(context)
+            .eval(scope)
             .map_err(|e| e.to_compile_error("数组长度"))?;
           let len = value.as_int()?;
           if len <= 0 {
             return Err(CompileError::NegativeSubscript(len))?;
           }
-          let (mut tys, ident) = decl_to_vec(decl, context)?;
+          let (mut tys, ident) = decl_to_vec(*decl, scope)?;
           tys.push(TyImpl::Array(len as usize));
           Ok((tys, ident))
         }
       }
     }
 
-    let (vec, ident) = decl_to_vec(declarator, context)?;
+    let (vec, ident) = decl_to_vec(declarator, scope)?;
 
     fn vec_to_ty(vec: &[TyImpl]) -> SysyType {
       if vec.len() == 0 {
@@ -120,6 +114,14 @@ impl SysyType {
     matches!(self, SysyType::Int)
   }
 
+  /// 是否是 `int` 或元素为 `int` 的（多维）数组，即不含指针
+  pub fn is_int_or_array(&self) -> bool {
+    match self {
+      SysyType::Array(ele, _) => ele.is_int_or_array(),
+      _ => self.is_int(),
+    }
+  }
+
   pub fn decay(&self) -> Self {
     match self {
       SysyType::Array(ele, _) => SysyType::Pointer(ele.clone()),
@@ -130,44 +132,57 @@ impl SysyType {
 }
 
 pub trait GetType {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType>;
+  fn get_type(&self, scope: Scope) -> Result<SysyType>;
+}
+
+impl<T: GetType + Node> GetType for Id<T> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
+    scope.ast[*self].get_type(scope)
+  }
 }
 
 impl GetType for Exp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      Exp::Assign(exp) => exp.get_type(context),
-      Exp::Comma(_, rhs) => rhs.get_type(context),
+      Exp::Assign(exp) => exp.get_type(scope),
+      Exp::Comma(_, rhs) => rhs.get_type(scope),
     }
   }
 }
 
 impl GetType for AssignExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      AssignExp::LOr(exp) => exp.get_type(context),
+      AssignExp::LOr(exp) => exp.get_type(scope),
       AssignExp::Assign(lhs, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if matches!(lhs_ty, SysyType::Array(..)) {
           return Err(CompileError::TypeMismatch("可修改左值", "".into(), "数组"))?;
-        } 
+        }
         if lhs_ty != rhs_ty {
-          return Err(CompileError::TypeMismatch("左值", "".into(), "不匹配的类型"))?;
+          return Err(CompileError::TypeMismatch(
+            "左值",
+            "".into(),
+            "不匹配的类型",
+          ))?;
         }
         Ok(lhs_ty)
       }
+      AssignExp::Compound(..) | AssignExp::Cond(..) => {
+        unreachable!("extended syntax should be desugared")
+      }
     }
   }
 }
 
 impl GetType for LOrExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      LOrExp::And(exp) => exp.get_type(context),
+      LOrExp::And(exp) => exp.get_type(scope),
       LOrExp::Or(lhs, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -181,12 +196,12 @@ impl GetType for LOrExp {
 }
 
 impl GetType for LAndExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      LAndExp::Eq(exp) => exp.get_type(context),
+      LAndExp::Eq(exp) => exp.get_type(scope),
       LAndExp::And(lhs, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -200,12 +215,12 @@ impl GetType for LAndExp {
 }
 
 impl GetType for EqExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      EqExp::Rel(exp) => exp.get_type(context),
+      EqExp::Rel(exp) => exp.get_type(scope),
       EqExp::Eq(lhs, _, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -219,12 +234,12 @@ impl GetType for EqExp {
 }
 
 impl GetType for RelExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      RelExp::Add(exp) => exp.get_type(context),
+      RelExp::Add(exp) => exp.get_type(scope),
       RelExp::Rel(lhs, _, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -238,12 +253,12 @@ impl GetType for RelExp {
 }
 
 impl GetType for AddExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      AddExp::Mul(exp) => exp.get_type(context),
+      AddExp::Mul(exp) => exp.get_type(scope),
       AddExp::Add(lhs, _, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -257,12 +272,12 @@ impl GetType for AddExp {
 }
 
 impl GetType for MulExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      MulExp::Unary(exp) => exp.get_type(context),
+      MulExp::Unary(exp) => exp.get_type(scope),
       MulExp::Mul(lhs, _, rhs) => {
-        let lhs_ty = lhs.get_type(context)?;
-        let rhs_ty = rhs.get_type(context)?;
+        let lhs_ty = lhs.get_type(scope)?;
+        let rhs_ty = rhs.get_type(scope)?;
         if !lhs_ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else if !rhs_ty.is_int() {
@@ -276,37 +291,57 @@ impl GetType for MulExp {
 }
 
 impl GetType for UnaryExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      UnaryExp::Postfix(exp) => exp.get_type(context),
-      UnaryExp::Address(exp) => Ok(SysyType::Pointer(exp.get_type(context)?.into())),
+      UnaryExp::Postfix(exp) => exp.get_type(scope),
+      UnaryExp::Address(exp) => Ok(SysyType::Pointer(exp.get_type(scope)?.into())),
       UnaryExp::Deref(exp) => {
-        let ty = exp.get_type(context)?;
+        let ty = exp.get_type(scope)?;
         match ty {
           SysyType::Pointer(ty) => Ok(ty.as_ref().clone()),
           _ => Err(CompileError::TypeMismatch("指针", "".into(), "?"))?,
         }
       }
       UnaryExp::Op(_, exp) => {
-        let ty = exp.get_type(context)?;
+        let ty = exp.get_type(scope)?;
         if !ty.is_int() {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         } else {
           Ok(SysyType::Int)
         }
       }
+      UnaryExp::Step(..) => unreachable!("prefix step should be desugared"),
     }
   }
 }
 
 impl GetType for PostfixExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
-      PostfixExp::Primary(exp) => exp.get_type(context),
-      PostfixExp::Call(lhs, args) => {
-        let (ret, params) = match SymbolTable::get_global(&lhs) {
+      PostfixExp::Primary(exp) => exp.get_type(scope),
+      PostfixExp::Call(lhs, _, args) if lhs == BUILTIN_EXPECT => {
+        if args.len() != 2 {
+          Err(CompileError::TypeMismatch(
+            "函数",
+            lhs.to_string(),
+            "数量不同的参数",
+          ))?
+        }
+        for arg in args {
+          if arg.get_type(scope)? != SysyType::Int {
+            Err(CompileError::TypeMismatch(
+              "整数",
+              lhs.to_string(),
+              "错误的参数类型",
+            ))?
+          }
+        }
+        Ok(SysyType::Int)
+      }
+      PostfixExp::Call(lhs, _, args) => {
+        let (ret, params) = match scope.globals.get(lhs) {
           Some(Symbol::Func(SysyType::Function(ret, args), _)) => (ret, args),
-          _ => Err(CompileError::TypeMismatch("函数", "".into(), "?"))?,
+          _ => Err(CompileError::ImplicitDeclaration(lhs.to_string()))?,
         };
         if params.len() != args.len() {
           Err(CompileError::TypeMismatch(
@@ -316,34 +351,38 @@ impl GetType for PostfixExp {
           ))?
         }
         for (p, a) in Iterator::zip(params.iter(), args.iter()) {
-          if p.as_ref().clone() != a.get_type(context)?.decay() {
+          if p.as_ref().clone() != a.get_type(scope)?.decay() {
             Err(CompileError::TypeMismatch("?", "".into(), "错误的参数类型"))?
           }
         }
         Ok(ret.as_ref().clone())
       }
       PostfixExp::Subscript(lval, exp) => {
-        let exp_ty = exp.get_type(context)?;
+        let exp_ty = exp.get_type(scope)?;
         if exp_ty != SysyType::Int {
           Err(CompileError::TypeMismatch("整数", "".into(), "?"))?
         }
-        match lval.get_type(context)? {
+        match lval.get_type(scope)? {
           SysyType::Array(ele, _) => Ok(ele.as_ref().clone()),
           SysyType::Pointer(ele) => Ok(ele.as_ref().clone()),
           _ => Err(CompileError::TypeMismatch("数组", "".into(), "?"))?,
         }
       }
+      PostfixExp::Step(..) => unreachable!("postfix step should be desugared"),
     }
   }
 }
 
 impl GetType for FuncDecl {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     let params_ty = self
       .params
       .iter()
-      .map(|arg| {
-        let (ty, _) = SysyType::parse(arg, context)?;
+      .map(|&(spec, arg)| {
+        let (ty, name) = SysyType::parse(arg, scope)?;
+        if spec == TypeSpec::Void {
+          return Err(CompileError::VoidParameter(name.into()).into());
+        }
         Ok(ty.into())
       })
       .collect::<Result<Vec<_>>>()?;
@@ -356,25 +395,21 @@ impl GetType for FuncDecl {
 }
 
 impl GetType for PrimaryExp {
-  fn get_type(&self, context: Option<&GenerateContext>) -> Result<SysyType> {
+  fn get_type(&self, scope: Scope) -> Result<SysyType> {
     match self {
       PrimaryExp::Ident(ident) => {
-        let symbol = match context {
-          Some(context) => context.symbol.get(ident).or_else(|| SymbolTable::get_global(ident)),
-          None => SymbolTable::get_global(ident),
-        };
-        let ty = match symbol {
+        let ty = match scope.get(ident) {
           Some(symbol) => match symbol {
             Symbol::Const(cv) => cv.ty,
             Symbol::Var(ty, _) => ty,
             Symbol::Func(ty, _) => ty,
           },
-          None => Err(CompileError::UndeclaredSymbol(ident.clone()))?,
+          None => Err(CompileError::UndeclaredSymbol(ident.to_string()))?,
         };
         Ok(ty)
       }
       PrimaryExp::Num(_) => Ok(SysyType::Int),
-      PrimaryExp::Paren(exp) => exp.get_type(context),
+      PrimaryExp::Paren(exp) => exp.get_type(scope),
     }
   }
 }
@@ -0,0 +1,246 @@
+//! 语法高亮：把源代码切分为带有类别的记号，供语言服务器的语义高亮与 `--emit=highlight-html` 使用。
+//!
+//! 记号由词法分析得到，另外加上词法分析跳过的注释；标识符再经语义分析区分为所指符号的种类。
+//! 声明处的名字按声明的种类区分，无需语义分析；使用处的名字是其所指的符号，无法解析时（如未
+//! 声明或有语法错误）没有种类。空白与运算符不是记号；词法错误之后的部分不再切分。
+//!
+//! ```
+//! use sysyc::frontend::highlight::{highlight, TokenClass};
+//! use sysyc::frontend::sema::SymbolKind;
+//!
+//! let tokens = highlight("int x; // 注释\nint main() { return x; }");
+//! assert_eq!(tokens[1].class, TokenClass::Ident(Some(SymbolKind::Var)));
+//! assert_eq!(tokens[2].class, TokenClass::Comment);
+//! assert_eq!(tokens[2].span, (7, 16));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use super::ast::*;
+use super::lexer::{LexErrorKind, Lexer, TokenKind};
+use super::query::func_name;
+use super::sema::{analyze_all, SymbolKind, TypedCompUnit};
+use super::visit::Visit;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TokenClass {
+  Keyword,
+  /// 标识符及其所指符号的种类；无法解析的名字为 `None`
+  Ident(Option<SymbolKind>),
+  /// 整数与字符字面量
+  Number,
+  /// 字符串字面量（内联汇编的模板与约束）
+  String,
+  Comment,
+}
+
+impl TokenClass {
+  /// 类别的名字，也是 `--emit=highlight-html` 中 `<span>` 的 `class`
+  pub fn name(self) -> &'static str {
+    match self {
+      TokenClass::Keyword => "keyword",
+      TokenClass::Ident(Some(SymbolKind::Const)) => "const",
+      TokenClass::Ident(Some(SymbolKind::Var)) => "var",
+      TokenClass::Ident(Some(SymbolKind::Param)) => "param",
+      TokenClass::Ident(Some(SymbolKind::Func)) => "func",
+      TokenClass::Ident(None) => "ident",
+      TokenClass::Number => "number",
+      TokenClass::String => "string",
+      TokenClass::Comment => "comment",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token {
+  pub class: TokenClass,
+  pub span: Span,
+}
+
+/// 源代码中的各个记号，按在源代码中的顺序
+pub fn highlight(source: &str) -> Vec<Token> {
+  let (kinds, funcs) = match super::parse(source) {
+    Ok(ast) => {
+      let (unit, _) = analyze_all(ast);
+      let mut idents = Idents {
+        source,
+        unit: &unit,
+        kinds: HashMap::new(),
+        funcs: HashSet::new(),
+      };
+      crate::stack::run(|| idents.visit_comp_unit(&unit.ast));
+      (idents.kinds, idents.funcs)
+    }
+    Err(_) => Default::default(),
+  };
+  let mut tokens = vec![];
+  let mut last = 0;
+  for token in Lexer::new(source) {
+    let (start, token, end) = match token {
+      Ok(token) => token,
+      Err(e) => {
+        comments(source, (last, e.start), &mut tokens);
+        if e.kind == LexErrorKind::UnterminatedComment {
+          let span = (e.start, e.end);
+          tokens.push(Token {
+            class: TokenClass::Comment,
+            span,
+          });
+        }
+        return tokens;
+      }
+    };
+    comments(source, (last, start), &mut tokens);
+    last = end;
+    let class = match token.kind {
+      TokenKind::Asm
+      | TokenKind::Attribute
+      | TokenKind::Break
+      | TokenKind::Const
+      | TokenKind::Continue
+      | TokenKind::Else
+      | TokenKind::For
+      | TokenKind::If
+      | TokenKind::Int
+      | TokenKind::Return
+      | TokenKind::Void
+      | TokenKind::Volatile
+      | TokenKind::While => TokenClass::Keyword,
+      TokenKind::Ident if funcs.contains(&start) => TokenClass::Ident(Some(SymbolKind::Func)),
+      TokenKind::Ident => TokenClass::Ident(kinds.get(&start).copied()),
+      TokenKind::Integer(_) => TokenClass::Number,
+      TokenKind::Str => TokenClass::String,
+      _ => continue,
+    };
+    tokens.push(Token {
+      class,
+      span: (start, end),
+    });
+  }
+  comments(source, (last, source.len()), &mut tokens);
+  tokens
+}
+
+/// `--emit=highlight-html` 的默认样式，可由页面中其他的样式覆盖
+const STYLE: &str = "<style>
+.sysy .keyword { color: #0033b3; font-weight: bold; }
+.sysy .func { color: #00627a; }
+.sysy .var { color: #871094; }
+.sysy .const { color: #871094; font-style: italic; }
+.sysy .param { color: #6b4f00; }
+.sysy .number { color: #1750eb; }
+.sysy .string { color: #067d17; }
+.sysy .comment { color: #8c8c8c; font-style: italic; }
+</style>
+";
+
+/// 以 HTML 输出高亮的源代码（`--emit=highlight-html`）：`<pre class="sysy">` 中的各个记号是以
+/// [`TokenClass::name`] 为 `class` 的 `<span>`，前面是默认的样式
+pub fn highlight_html(source: &str) -> String {
+  let mut html = format!("{}<pre class=\"sysy\">", STYLE);
+  let mut last = 0;
+  for token in highlight(source) {
+    let (start, end) = token.span;
+    escape(&source[last..start], &mut html);
+    html += &format!("<span class=\"{}\">", token.class.name());
+    escape(&source[start..end], &mut html);
+    html += "</span>";
+    last = end;
+  }
+  escape(&source[last..], &mut html);
+  html + "</pre>\n"
+}
+
+fn escape(text: &str, html: &mut String) {
+  for c in text.chars() {
+    match c {
+      '<' => *html += "&lt;",
+      '>' => *html += "&gt;",
+      '&' => *html += "&amp;",
+      '"' => *html += "&quot;",
+      _ => html.push(c),
+    }
+  }
+}
+
+/// 记号之间 `span` 处的注释；其中只有空白与注释
+fn comments(source: &str, (start, end): Span, tokens: &mut Vec<Token>) {
+  let mut pos = start;
+  while let Some(offset) = source[pos..end].find('/') {
+    let begin = pos + offset;
+    pos = match source.as_bytes().get(begin + 1) {
+      Some(b'*') => source[begin + 2..end]
+        .find("*/")
+        .map_or(end, |len| begin + len + 4),
+      _ => source[begin..end]
+        .find(['\n', '\r'])
+        .map_or(end, |len| begin + len),
+    };
+    tokens.push(Token {
+      class: TokenClass::Comment,
+      span: (begin, pos),
+    });
+  }
+}
+
+/// 各处名字的起始位置及其种类
+struct Idents<'a> {
+  source: &'a str,
+  unit: &'a TypedCompUnit,
+  kinds: HashMap<Pos, SymbolKind>,
+  /// 函数声明中函数名的起始位置
+  funcs: HashSet<Pos>,
+}
+
+impl Idents<'_> {
+  /// 声明符中的名字
+  fn declare(&mut self, ast: &Ast, mut declarator: Id<Declarator>, kind: SymbolKind) {
+    while let Declarator::Pointer(inner) | Declarator::Array(inner, _) = ast[declarator] {
+      declarator = inner;
+    }
+    self.kinds.insert(ast.pos(declarator), kind);
+  }
+}
+
+impl Visit for Idents<'_> {
+  fn visit_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    if let Decl::Func(_) = ast[decl] {
+      if let Some((start, _)) = func_name(self.source, ast.pos(decl)) {
+        self.funcs.insert(start);
+      }
+    }
+    self.walk_decl(ast, decl);
+  }
+
+  fn visit_func_decl(&mut self, ast: &Ast, decl: &FuncDecl) {
+    for &(_, param) in &decl.params {
+      self.declare(ast, param, SymbolKind::Param);
+    }
+    self.walk_func_decl(ast, decl);
+  }
+
+  fn visit_var_decl(&mut self, ast: &Ast, decl: &DeclaratorAndInitializerList) {
+    let kind = match decl.is_const {
+      true => SymbolKind::Const,
+      false => SymbolKind::Var,
+    };
+    for &(declarator, _) in &decl.list {
+      self.declare(ast, declarator, kind);
+    }
+    self.walk_var_decl(ast, decl);
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    if let Some(symbol) = self.unit.symbol_of(exp) {
+      self.kinds.insert(ast.pos(exp), symbol.kind);
+    }
+    self.walk_postfix_exp(ast, exp);
+  }
+
+  fn visit_primary_exp(&mut self, ast: &Ast, exp: Id<PrimaryExp>) {
+    if let Some(symbol) = self.unit.symbol_of(exp) {
+      self.kinds.insert(ast.pos(exp), symbol.kind);
+    }
+    self.walk_primary_exp(ast, exp);
+  }
+}
@@ -0,0 +1,287 @@
+//! 插桩：运行时检查（`-fsanitize=`，见 [`super::sanitize`]）、函数调用计数
+//! （`-finstrument-functions`）与行覆盖（`--coverage`）。诊断与报告的例程以 SysY 实现，随程序一同
+//! 生成，共用 `trap.sy` 中的输出。
+//!
+//! 调用计数在每个函数的入口处把它的计数器（一个全局变量）加一；`main` 返回之前调用生成的
+//! `@__sysy_profile_report`，把各函数的名字与计数交给 `profile.sy` 中的例程，按次数从多到少输出到
+//! 标准错误。行覆盖同样在第一个源文件中各基本块的开头计数，`main` 返回之前由
+//! `@__sysy_cov_report` 输出各块的次数及其所含的源代码行，由 `sysyc cov report` 汇总为各行的次数。
+//!
+//! 自动计时（`--auto-timing`）在程序本身不调用 `starttime`、`stoptime` 时，在 `main` 的开头调用
+//! `starttime`、每个返回之前调用 `stoptime`，无需改动源程序即可由运行时库报告用时。
+
+use koopa::ir::builder::{BasicBlockBuilder, GlobalInstBuilder, LocalInstBuilder, ValueBuilder};
+use koopa::ir::dfg::DataFlowGraph;
+use koopa::ir::{BinaryOp, Function, FunctionData, Program, Type, Value, ValueKind};
+use std::borrow::BorrowMut;
+
+use super::ast::Name;
+use super::decl::{CompilationContext, GenerateContext};
+use super::error::PushKeyError;
+use super::sanitize::Sanitizers;
+use super::source::SourceMap;
+use super::symbol::Symbol;
+use crate::Result;
+
+/// 诊断与报告共用的字符输出，是插桩中最先生成的部分
+pub const TRAP_RUNTIME: &str = include_str!("trap.sy");
+/// 调用计数的记录与输出
+pub const PROFILE_RUNTIME: &str = include_str!("profile.sy");
+/// 行覆盖的输出
+pub const COVERAGE_RUNTIME: &str = include_str!("coverage.sy");
+
+const PROFILE_ADD: &str = "__sysy_profile_add";
+const PROFILE_PRINT: &str = "__sysy_profile_print";
+const COVERAGE_PRINT: &str = "__sysy_cov_print";
+
+/// 启用的插桩
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Instrumentation {
+  /// 运行时检查（`-fsanitize=`）
+  pub sanitize: Sanitizers,
+  /// 函数调用计数（`-finstrument-functions`）
+  pub profile: bool,
+  /// 行覆盖（`--coverage`）
+  pub coverage: bool,
+  /// 自动计时（`--auto-timing`）
+  pub timing: bool,
+}
+
+impl Instrumentation {
+  /// 是否需要生成 `trap.sy`；自动计时只调用运行时库
+  pub fn any(self) -> bool {
+    self.sanitize.any() || self.profile || self.coverage
+  }
+}
+
+fn runtime_func(compilation: &CompilationContext, name: &str) -> Function {
+  let Some(Symbol::Func(_, func)) = compilation.globals.get(&Name::intern(name)) else {
+    panic!("instrumentation runtime function {} is not generated", name);
+  };
+  func
+}
+
+/// 以 `values` 为元素的全局数组
+fn global_array(program: &mut Program, name: &str, values: &[i32]) -> Value {
+  let values = values.iter().map(|&v| program.new_value().integer(v));
+  let values = values.collect::<Vec<_>>();
+  let array = program.new_value().aggregate(values);
+  let array = program.new_value().global_alloc(array);
+  program
+    .borrow_mut()
+    .set_value_name(array, Some(name.into()));
+  array
+}
+
+/// 生成无参数、无返回值的函数 `name`，其中依次是 `body` 创建的指令，并在 `main` 的每个 `ret`
+/// 之前调用它
+fn call_on_exit(
+  program: &mut Program,
+  main: Function,
+  name: &str,
+  body: impl FnOnce(&mut DataFlowGraph) -> Vec<Value>,
+) -> Result<()> {
+  let report = FunctionData::new(name.into(), vec![], Type::get_unit());
+  let report = program.new_func(report);
+  let fd = program.func_mut(report);
+  let entry = fd.dfg_mut().new_bb().basic_block(Some("%entry".into()));
+  let mut insts = body(fd.dfg_mut());
+  insts.push(fd.dfg_mut().new_value().ret(None));
+  fd.layout_mut()
+    .bbs_mut()
+    .push_key_back(entry)
+    .map_err(|k| PushKeyError(Box::new(k)))?;
+  let bb = fd.layout_mut().bb_mut(entry).insts_mut();
+  for inst in insts {
+    bb.push_key_back(inst)
+      .map_err(|k| PushKeyError(Box::new(k)))?;
+  }
+
+  before_returns(program, main, report)
+}
+
+/// 在 `main` 的每个 `ret` 之前调用无参数的函数 `func`
+fn before_returns(program: &mut Program, main: Function, func: Function) -> Result<()> {
+  let fd = program.func_mut(main);
+  let mut rets = vec![];
+  for (&bb, bbn) in fd.layout().bbs() {
+    for &inst in bbn.insts().keys() {
+      if let ValueKind::Return(_) = fd.dfg().value(inst).kind() {
+        rets.push((bb, inst));
+      }
+    }
+  }
+  for (bb, ret) in rets {
+    let call = fd.dfg_mut().new_value().call(func, vec![]);
+    let insts = fd.layout_mut().bb_mut(bb).insts_mut();
+    let mut cursor = insts.cursor_mut(ret);
+    cursor
+      .insert_key_before(call)
+      .map_err(|k| PushKeyError(Box::new(k)))?;
+  }
+  Ok(())
+}
+
+/// `--auto-timing`：程序中没有对 `starttime`、`stoptime` 的调用时，在 `@main` 的开头调用前者、
+/// 每个 `ret` 之前调用后者；没有 `main` 时不做改动。须在生成报告之前进行，报告的输出不计入用时
+pub fn add_timing(program: &mut Program, compilation: &CompilationContext) -> Result<()> {
+  let Some(Symbol::Func(_, main)) = compilation.globals.get(&Name::intern("main")) else {
+    return Ok(());
+  };
+  let start = runtime_func(compilation, "starttime");
+  let stop = runtime_func(compilation, "stoptime");
+  let calls_timer = program.funcs().values().any(|fd| {
+    let bbs = fd.layout().bbs().nodes();
+    let mut insts = bbs.flat_map(|bbn| bbn.insts().keys());
+    insts.any(|&inst| match fd.dfg().value(inst).kind() {
+      ValueKind::Call(call) => call.callee() == start || call.callee() == stop,
+      _ => false,
+    })
+  });
+  if calls_timer {
+    return Ok(());
+  }
+
+  let fd = program.func_mut(main);
+  let entry = fd.layout().entry_bb().unwrap();
+  let call = fd.dfg_mut().new_value().call(start, vec![]);
+  fd.layout_mut()
+    .bb_mut(entry)
+    .insts_mut()
+    .push_key_front(call)
+    .map_err(|k| PushKeyError(Box::new(k)))?;
+  before_returns(program, main, stop)
+}
+
+/// 在函数 `name` 的入口处把其计数器加一，计数器记入 `compilation.call_counters`
+pub fn count_call(context: &mut GenerateContext, name: Name) -> Result<()> {
+  let program = &mut *context.program;
+  let zero = program.new_value().zero_init(Type::get_i32());
+  let counter = program.new_value().global_alloc(zero);
+  let ir_name = format!("%__sysy_calls_{}", name);
+  program.borrow_mut().set_value_name(counter, Some(ir_name));
+  context.compilation.call_counters.push((name, counter));
+
+  let count = context.insert(|b| b.load(counter))?;
+  let one = context.dfg().new_value().integer(1);
+  let count = context.insert(|b| b.binary(BinaryOp::Add, count, one))?;
+  context.insert(|b| b.store(count, counter))?;
+  Ok(())
+}
+
+/// 生成 `@__sysy_profile_report`，并在 `@main` 的每个 `ret` 之前调用它；没有 `main` 时不生成
+pub fn add_report(program: &mut Program, compilation: &CompilationContext) -> Result<()> {
+  let Some(Symbol::Func(_, main)) = compilation.globals.get(&Name::intern("main")) else {
+    return Ok(());
+  };
+  let add = runtime_func(compilation, PROFILE_ADD);
+  let print = runtime_func(compilation, PROFILE_PRINT);
+
+  // 以 0 结尾的函数名
+  let mut names = vec![];
+  for (name, _) in &compilation.call_counters {
+    let chars = name.as_str().bytes().chain([0]).map(i32::from);
+    let ir_name = format!("%__sysy_name_{}", name);
+    names.push(global_array(program, &ir_name, &chars.collect::<Vec<_>>()));
+  }
+
+  call_on_exit(program, main, "@__sysy_profile_report", |dfg| {
+    let mut insts = vec![];
+    for (&name, &(_, counter)) in names.iter().zip(&compilation.call_counters) {
+      let zero = dfg.new_value().integer(0);
+      let name = dfg.new_value().get_elem_ptr(name, zero);
+      let count = dfg.new_value().load(counter);
+      let call = dfg.new_value().call(add, vec![name, count]);
+      insts.extend([name, count, call]);
+    }
+    insts.push(dfg.new_value().call(print, vec![]));
+    insts
+  })
+}
+
+/// `--coverage`：在 `source` 记录了来源的函数（即第一个源文件中定义的函数）的各基本块开头把其
+/// 计数器加一，并生成 `@__sysy_cov_report` 在 `@main` 的每个 `ret` 之前输出；没有 `main` 时不生成
+pub fn add_coverage(
+  program: &mut Program,
+  compilation: &CompilationContext,
+  source: &SourceMap,
+) -> Result<()> {
+  let Some(Symbol::Func(_, main)) = compilation.globals.get(&Name::intern("main")) else {
+    return Ok(());
+  };
+  let print = runtime_func(compilation, COVERAGE_PRINT);
+
+  // 各基本块及其所含的源代码行；没有来源的块（如函数末尾补上的返回）不计数
+  let mut blocks = vec![];
+  for &func in program.func_layout() {
+    if source.func_line(func).is_none() {
+      continue;
+    }
+    let fd = program.func(func);
+    for (&bb, bbn) in fd.layout().bbs() {
+      let lines = bbn.insts().keys().filter_map(|&inst| source.line_of(inst));
+      let mut lines = lines.map(|(line, _)| line as i32).collect::<Vec<_>>();
+      lines.sort_unstable();
+      lines.dedup();
+      if !lines.is_empty() {
+        blocks.push((func, bb, lines));
+      }
+    }
+  }
+  if blocks.is_empty() {
+    return Ok(());
+  }
+
+  let ty = Type::get_array(Type::get_i32(), blocks.len());
+  let zero = program.new_value().zero_init(ty);
+  let counts = program.new_value().global_alloc(zero);
+  let counts_name = Some("%__sysy_cov_counts".into());
+  program.borrow_mut().set_value_name(counts, counts_name);
+  for (i, (func, bb, _)) in blocks.iter().enumerate() {
+    let fd = program.func_mut(*func);
+    let dfg = fd.dfg_mut();
+    let index = dfg.new_value().integer(i as i32);
+    let ptr = dfg.new_value().get_elem_ptr(counts, index);
+    let count = dfg.new_value().load(ptr);
+    let one = dfg.new_value().integer(1);
+    let add = dfg.new_value().binary(BinaryOp::Add, count, one);
+    let store = dfg.new_value().store(add, ptr);
+    // 插在入口的局部变量分配之后
+    let mut insts = fd.layout().bbs().node(bb).unwrap().insts().keys();
+    let not_alloc = |inst: &&Value| !matches!(fd.dfg().value(**inst).kind(), ValueKind::Alloc(_));
+    let first = *insts.find(not_alloc).unwrap();
+    let mut cursor = fd.layout_mut().bb_mut(*bb).insts_mut().cursor_mut(first);
+    for inst in [ptr, count, add, store] {
+      cursor
+        .insert_key_before(inst)
+        .map_err(|k| PushKeyError(Box::new(k)))?;
+    }
+  }
+
+  let block_ids = blocks.iter().enumerate();
+  let block_ids = block_ids.flat_map(|(i, (_, _, lines))| lines.iter().map(move |_| i as i32));
+  let block_ids = global_array(
+    program,
+    "%__sysy_cov_blocks",
+    &block_ids.collect::<Vec<_>>(),
+  );
+  let lines = blocks
+    .iter()
+    .flat_map(|(_, _, lines)| lines.iter().copied());
+  let lines = lines.collect::<Vec<_>>();
+  let len = lines.len() as i32;
+  let lines = global_array(program, "%__sysy_cov_lines", &lines);
+  call_on_exit(program, main, "@__sysy_cov_report", |dfg| {
+    let mut insts = vec![];
+    let mut args = vec![];
+    for array in [counts, block_ids, lines] {
+      let zero = dfg.new_value().integer(0);
+      let ptr = dfg.new_value().get_elem_ptr(array, zero);
+      insts.push(ptr);
+      args.push(ptr);
+    }
+    args.push(dfg.new_value().integer(len));
+    insts.push(dfg.new_value().call(print, args));
+    insts
+  })
+}
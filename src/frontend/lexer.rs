@@ -0,0 +1,366 @@
+//! 词法分析：把源代码切分为带有字节区间的记号，供 LALRPOP 生成的语法分析器使用。
+//!
+//! 空白与注释被跳过。块注释与 C 相同不嵌套，其中的 `/*`、`//` 只是注释的内容，遇到第一个
+//! `*/` 即结束。整数字面量（十进制、八进制、十六进制与字符）在这里求值，记号仍保留原文。
+
+use std::fmt;
+
+use super::ast::Pos;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+  // 关键字
+  Asm,
+  /// `__attribute__`
+  Attribute,
+  Break,
+  Const,
+  Continue,
+  Else,
+  For,
+  If,
+  Int,
+  Return,
+  Void,
+  Volatile,
+  While,
+
+  Ident,
+  /// 整数或字符字面量的值；超出 `i32` 而不超出 `u32` 的整数按补码回绕，
+  /// 以便写出 `-2147483648`
+  Integer(i32),
+  /// 字符串字面量，含两侧的引号与转义
+  Str,
+
+  LParen,
+  RParen,
+  LBrace,
+  RBrace,
+  LBracket,
+  RBracket,
+  Semi,
+  Comma,
+  Colon,
+  Question,
+  Assign,
+  /// 复合赋值 `+=`、`-=`、`*=`、`/=`、`%=`
+  PlusAssign,
+  MinusAssign,
+  StarAssign,
+  SlashAssign,
+  PercentAssign,
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Plus,
+  Minus,
+  /// `++` 与 `--`
+  Increment,
+  Decrement,
+  Star,
+  Slash,
+  Percent,
+  Not,
+  Amp,
+  AndAnd,
+  OrOr,
+}
+
+/// 记号：种类与源代码中的原文
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'input> {
+  pub kind: TokenKind,
+  pub text: &'input str,
+}
+
+impl fmt::Display for Token<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.text)
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LexErrorKind {
+  InvalidToken,
+  UnterminatedComment,
+  /// 字符或字符串字面量在行末或文件末尾仍未结束
+  UnterminatedLiteral,
+  IntegerOverflow,
+  /// 不是词法错误，由语法分析报告：未知的属性或属性的参数不对
+  InvalidAttribute,
+}
+
+impl LexErrorKind {
+  pub fn message(self) -> &'static str {
+    match self {
+      LexErrorKind::InvalidToken => "invalid token",
+      LexErrorKind::UnterminatedComment => "unterminated comment",
+      LexErrorKind::UnterminatedLiteral => "unterminated literal",
+      LexErrorKind::IntegerOverflow => "integer literal is too large",
+      LexErrorKind::InvalidAttribute => "unknown attribute or invalid attribute arguments",
+    }
+  }
+}
+
+/// 词法错误及其所在的区间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LexError {
+  pub kind: LexErrorKind,
+  pub start: Pos,
+  pub end: Pos,
+}
+
+impl fmt::Display for LexError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let message = self.kind.message();
+    write!(
+      f,
+      "{}{} at {}",
+      message[..1].to_ascii_uppercase(),
+      &message[1..],
+      self.start
+    )
+  }
+}
+
+pub type Spanned<'input> = Result<(Pos, Token<'input>, Pos), LexError>;
+
+/// 逐个产生记号；遇到错误后不再产生记号
+pub struct Lexer<'input> {
+  input: &'input str,
+  pos: usize,
+}
+
+impl<'input> Lexer<'input> {
+  pub fn new(input: &'input str) -> Self {
+    Self { input, pos: 0 }
+  }
+
+  /// 从 `pos` 处开始切分，记号的位置仍是在整个 `input` 中的偏移
+  pub fn starting_at(input: &'input str, pos: Pos) -> Self {
+    Self { input, pos }
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  fn peek_at(&self, offset: usize) -> Option<u8> {
+    self.input.as_bytes().get(self.pos + offset).copied()
+  }
+
+  fn eat_while(&mut self, pred: impl Fn(u8) -> bool) {
+    while self.peek().is_some_and(&pred) {
+      self.pos += 1;
+    }
+  }
+
+  /// 跳过空白与注释
+  fn skip_trivia(&mut self) -> Result<(), LexError> {
+    loop {
+      match self.peek() {
+        Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c') => self.pos += 1,
+        Some(b'/') if self.peek_at(1) == Some(b'/') => {
+          self.eat_while(|c| c != b'\n' && c != b'\r');
+        }
+        Some(b'/') if self.peek_at(1) == Some(b'*') => {
+          match self.input[self.pos + 2..].find("*/") {
+            Some(len) => self.pos += len + 4,
+            None => {
+              let start = self.pos;
+              self.pos = self.input.len();
+              return Err(LexError {
+                kind: LexErrorKind::UnterminatedComment,
+                start,
+                end: self.pos,
+              });
+            }
+          }
+        }
+        Some(c) if !c.is_ascii() => match self.input[self.pos..].chars().next() {
+          Some(c) if c.is_whitespace() => self.pos += c.len_utf8(),
+          _ => return Ok(()),
+        },
+        _ => return Ok(()),
+      }
+    }
+  }
+
+  fn ident_or_keyword(&mut self, start: usize) -> TokenKind {
+    self.eat_while(|c| c == b'_' || c.is_ascii_alphanumeric());
+    match &self.input[start..self.pos] {
+      "asm" => TokenKind::Asm,
+      "__attribute__" => TokenKind::Attribute,
+      "break" => TokenKind::Break,
+      "const" => TokenKind::Const,
+      "continue" => TokenKind::Continue,
+      "else" => TokenKind::Else,
+      "for" => TokenKind::For,
+      "if" => TokenKind::If,
+      "int" => TokenKind::Int,
+      "return" => TokenKind::Return,
+      "void" => TokenKind::Void,
+      "volatile" => TokenKind::Volatile,
+      "while" => TokenKind::While,
+      _ => TokenKind::Ident,
+    }
+  }
+
+  /// 数字连同其后紧接的字母、数字一起作为一个记号，因此 `09`、`0x`、`12ab` 都是错误
+  fn number(&mut self, start: usize) -> Result<TokenKind, LexErrorKind> {
+    self.eat_while(|c| c == b'_' || c.is_ascii_alphanumeric());
+    let text = &self.input[start..self.pos];
+    let (digits, radix) = match text.as_bytes() {
+      [b'0', b'x' | b'X', ..] => (&text[2..], 16),
+      [b'0', ..] => (text, 8),
+      _ => (text, 10),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+      return Err(LexErrorKind::InvalidToken);
+    }
+    match u32::from_str_radix(digits, radix) {
+      Ok(value) => Ok(TokenKind::Integer(value as i32)),
+      Err(_) => Err(LexErrorKind::IntegerOverflow),
+    }
+  }
+
+  /// 字符字面量：可打印的 ASCII 字符，或 `\n`、`\t`、`\r`、`\0`、`\\`、`\'`、`\"` 转义
+  fn char_lit(&mut self) -> Result<TokenKind, LexErrorKind> {
+    self.pos += 1;
+    let value = match self.peek() {
+      None | Some(b'\n' | b'\r') => return Err(LexErrorKind::UnterminatedLiteral),
+      Some(b'\\') => {
+        self.pos += 1;
+        match self.peek() {
+          Some(b'n') => b'\n',
+          Some(b't') => b'\t',
+          Some(b'r') => b'\r',
+          Some(b'0') => 0,
+          Some(c @ (b'\\' | b'\'' | b'"')) => c,
+          None | Some(b'\n' | b'\r') => return Err(LexErrorKind::UnterminatedLiteral),
+          Some(_) => return Err(LexErrorKind::InvalidToken),
+        }
+      }
+      Some(b'\'') => return Err(LexErrorKind::InvalidToken),
+      Some(c @ b' '..=b'~') => c,
+      Some(_) => return Err(LexErrorKind::InvalidToken),
+    };
+    self.pos += 1;
+    match self.peek() {
+      Some(b'\'') => {
+        self.pos += 1;
+        Ok(TokenKind::Integer(value as i32))
+      }
+      None | Some(b'\n' | b'\r') => Err(LexErrorKind::UnterminatedLiteral),
+      Some(_) => Err(LexErrorKind::InvalidToken),
+    }
+  }
+
+  /// 字符串字面量：反斜杠之后的任一字符都被转义，不能跨行
+  fn string_lit(&mut self) -> Result<TokenKind, LexErrorKind> {
+    self.pos += 1;
+    loop {
+      match self.peek() {
+        None | Some(b'\n') => return Err(LexErrorKind::UnterminatedLiteral),
+        Some(b'"') => {
+          self.pos += 1;
+          return Ok(TokenKind::Str);
+        }
+        Some(b'\\') if self.peek_at(1).is_some_and(|c| c != b'\n') => self.pos += 2,
+        Some(_) => self.pos += 1,
+      }
+    }
+  }
+
+  fn punct(&mut self, c: u8) -> Result<TokenKind, LexErrorKind> {
+    let two = match (c, self.peek_at(1)) {
+      (b'=', Some(b'=')) => Some(TokenKind::Eq),
+      (b'!', Some(b'=')) => Some(TokenKind::Ne),
+      (b'<', Some(b'=')) => Some(TokenKind::Le),
+      (b'>', Some(b'=')) => Some(TokenKind::Ge),
+      (b'&', Some(b'&')) => Some(TokenKind::AndAnd),
+      (b'|', Some(b'|')) => Some(TokenKind::OrOr),
+      (b'+', Some(b'=')) => Some(TokenKind::PlusAssign),
+      (b'-', Some(b'=')) => Some(TokenKind::MinusAssign),
+      (b'*', Some(b'=')) => Some(TokenKind::StarAssign),
+      (b'/', Some(b'=')) => Some(TokenKind::SlashAssign),
+      (b'%', Some(b'=')) => Some(TokenKind::PercentAssign),
+      (b'+', Some(b'+')) => Some(TokenKind::Increment),
+      (b'-', Some(b'-')) => Some(TokenKind::Decrement),
+      _ => None,
+    };
+    if let Some(kind) = two {
+      self.pos += 2;
+      return Ok(kind);
+    }
+    let kind = match c {
+      b'(' => TokenKind::LParen,
+      b')' => TokenKind::RParen,
+      b'{' => TokenKind::LBrace,
+      b'}' => TokenKind::RBrace,
+      b'[' => TokenKind::LBracket,
+      b']' => TokenKind::RBracket,
+      b';' => TokenKind::Semi,
+      b',' => TokenKind::Comma,
+      b':' => TokenKind::Colon,
+      b'?' => TokenKind::Question,
+      b'=' => TokenKind::Assign,
+      b'<' => TokenKind::Lt,
+      b'>' => TokenKind::Gt,
+      b'+' => TokenKind::Plus,
+      b'-' => TokenKind::Minus,
+      b'*' => TokenKind::Star,
+      b'/' => TokenKind::Slash,
+      b'%' => TokenKind::Percent,
+      b'!' => TokenKind::Not,
+      b'&' => TokenKind::Amp,
+      _ => {
+        // 错误的区间包含整个（可能是多字节的）字符
+        let len = self.input[self.pos..]
+          .chars()
+          .next()
+          .map_or(1, char::len_utf8);
+        self.pos += len;
+        return Err(LexErrorKind::InvalidToken);
+      }
+    };
+    self.pos += 1;
+    Ok(kind)
+  }
+}
+
+impl<'input> Iterator for Lexer<'input> {
+  type Item = Spanned<'input>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if let Err(e) = self.skip_trivia() {
+      return Some(Err(e));
+    }
+    let start = self.pos;
+    let c = self.peek()?;
+    let kind = match c {
+      b'_' | b'a'..=b'z' | b'A'..=b'Z' => Ok(self.ident_or_keyword(start)),
+      b'0'..=b'9' => self.number(start),
+      b'\'' => self.char_lit(),
+      b'"' => self.string_lit(),
+      _ => self.punct(c),
+    };
+    match kind {
+      Ok(kind) => {
+        let token = Token {
+          kind,
+          text: &self.input[start..self.pos],
+        };
+        Some(Ok((start, token, self.pos)))
+      }
+      Err(kind) => {
+        let end = self.pos;
+        self.pos = self.input.len();
+        Some(Err(LexError { kind, start, end }))
+      }
+    }
+  }
+}
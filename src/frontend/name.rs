@@ -0,0 +1,65 @@
+//! 名字：标识符以 [`Name`] 表示，复制时只复制引用，不复制字符串。
+//!
+//! 语法树、符号表与生成 IR 时的命名都使用 [`Name`]。同一次解析中的名字共享字符串，各次编译
+//! 之间没有共用的字符串表：语法树与符号表释放后，其中的名字也随之释放，因此长期运行的语言
+//! 服务器不会因反复分析而占用越来越多的内存。
+
+use std::fmt;
+use std::sync::Arc;
+
+/// 名字；相等与哈希按字符串的内容
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Name(Arc<str>);
+
+impl Name {
+  /// 名为 `name` 的项
+  pub fn intern(name: &str) -> Self {
+    Self(name.into())
+  }
+
+  pub fn as_str(&self) -> &str {
+    &self.0
+  }
+}
+
+impl From<&str> for Name {
+  fn from(name: &str) -> Self {
+    Self::intern(name)
+  }
+}
+
+impl From<Name> for String {
+  fn from(name: Name) -> Self {
+    name.as_str().into()
+  }
+}
+
+impl From<&Name> for String {
+  fn from(name: &Name) -> Self {
+    name.as_str().into()
+  }
+}
+
+impl PartialEq<str> for Name {
+  fn eq(&self, other: &str) -> bool {
+    self.as_str() == other
+  }
+}
+
+impl PartialEq<&str> for Name {
+  fn eq(&self, other: &&str) -> bool {
+    self.as_str() == *other
+  }
+}
+
+impl fmt::Display for Name {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    f.write_str(self.as_str())
+  }
+}
+
+impl fmt::Debug for Name {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    fmt::Debug::fmt(self.as_str(), f)
+  }
+}
@@ -0,0 +1,140 @@
+//! 函数重载（`--extensions`）：同名的函数以参数的类型区分，调用按实参的类型选择其一。
+//!
+//! 语义分析（[`analyze_with`]）解析各处调用所指的重载之后，按 Itanium C++ ABI 改编函数名，改写
+//! 语法树中的函数声明与调用，此后生成 IR 时各个重载已是不同的函数。改编后的名字只由函数名与参数
+//! 类型决定，分别编译的目标文件之间也能相互调用，并可由 `c++filt` 还原：
+//!
+//! ```
+//! use sysyc::frontend::overload::mangle_name;
+//! use sysyc::frontend::SysyType;
+//!
+//! let int = || Box::new(SysyType::Int);
+//! let ptr = || Box::new(SysyType::Pointer(int()));
+//! assert_eq!(mangle_name("f", &[]), "_Z1fv");
+//! assert_eq!(mangle_name("f", &[int(), ptr()]), "_Z1fiPi");
+//! assert_eq!(mangle_name("f", &[ptr(), ptr()]), "_Z1fPiS_");
+//! let rows = Box::new(SysyType::Pointer(Box::new(SysyType::Array(int(), 3))));
+//! assert_eq!(mangle_name("sum", &[rows]), "_Z3sumPA3_i");
+//! ```
+//!
+//! `main` 与运行时库中的函数（及与之类型相同的声明）不改名，仍能被运行时与链接器找到。
+
+use super::ast::*;
+use super::error::CompileError;
+use super::expr::ty::SysyType;
+use super::sema::{analyze_with, SymbolKind, TypedCompUnit};
+use super::visit::Visit;
+
+/// 函数 `name` 以 `params` 为参数类型时改编后的名字
+pub fn mangle_name(name: &str, params: &[Box<SysyType>]) -> String {
+  let mut mangled = format!("_Z{}{}", name.len(), name);
+  if params.is_empty() {
+    mangled.push('v');
+  }
+  let mut substitutions = vec![];
+  for param in params {
+    mangled += &mangle_type(param, &mut substitutions);
+  }
+  mangled
+}
+
+/// 类型改编后的名字。指针与数组是可替换的成分，`substitutions` 是已出现的成分（未替换时的名字），
+/// 按完成的顺序；再次出现时改为 `S_`、`S0_`、`S1_` 等
+fn mangle_type(ty: &SysyType, substitutions: &mut Vec<String>) -> String {
+  let (inner, prefix) = match ty {
+    SysyType::Int => return "i".into(),
+    SysyType::Void => return "v".into(),
+    SysyType::Pointer(inner) => (inner, "P".to_string()),
+    SysyType::Array(inner, len) => (inner, format!("A{}_", len)),
+    SysyType::Function(..) => unreachable!("function parameter"),
+  };
+  let plain = prefix.clone() + &mangle_type(inner, &mut vec![]);
+  if let Some(index) = substitutions.iter().position(|s| *s == plain) {
+    return match index {
+      0 => "S_".into(),
+      _ => format!("S{}_", base36(index - 1)),
+    };
+  }
+  let mangled = prefix + &mangle_type(inner, substitutions);
+  substitutions.push(plain);
+  mangled
+}
+
+/// 替换的序号：数字与大写字母组成的 36 进制
+fn base36(mut n: usize) -> String {
+  const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+  let mut digits = vec![];
+  loop {
+    digits.push(DIGITS[n % 36]);
+    n /= 36;
+    if n == 0 {
+      break;
+    }
+  }
+  digits.reverse();
+  String::from_utf8(digits).unwrap()
+}
+
+/// 分析语法树中的重载，把函数声明与调用改为改编后的名字；有语义错误时返回第一个
+pub(super) fn mangle(ast: Ast) -> Result<Ast, CompileError> {
+  let (unit, diagnostics) = analyze_with(ast, true);
+  if let Some(diagnostic) = diagnostics.into_iter().next() {
+    return Err(CompileError::Other(diagnostic.message));
+  }
+  let mut renames = Renames {
+    unit: &unit,
+    decls: vec![],
+    calls: vec![],
+  };
+  renames.visit_comp_unit(&unit.ast);
+  let Renames { decls, calls, .. } = renames;
+  let mut ast = unit.ast;
+  for (decl, name) in decls {
+    if let Decl::Func(func) = &mut ast[decl] {
+      func.ident = name;
+    }
+  }
+  for (exp, name) in calls {
+    if let PostfixExp::Call(func, ..) = &mut ast[exp] {
+      *func = name;
+    }
+  }
+  Ok(ast)
+}
+
+/// 收集须改名的函数声明与调用
+struct Renames<'a> {
+  unit: &'a TypedCompUnit,
+  decls: Vec<(Id<Decl>, Name)>,
+  calls: Vec<(Id<PostfixExp>, Name)>,
+}
+
+impl Renames<'_> {
+  /// 节点所指的函数改编后的名字；`main` 与运行时库中的函数（没有位置）不改名
+  fn mangled<T: Node>(&self, node: Id<T>) -> Option<Name> {
+    let symbol = self.unit.symbol_of(node)?;
+    if symbol.kind != SymbolKind::Func || symbol.pos.is_none() || symbol.name.as_str() == "main" {
+      return None;
+    }
+    let SysyType::Function(_, params) = &symbol.ty else {
+      return None;
+    };
+    Some(Name::intern(&mangle_name(symbol.name.as_str(), params)))
+  }
+}
+
+impl Visit for Renames<'_> {
+  fn visit_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    if let Some(name) = self.mangled(decl) {
+      self.decls.push((decl, name));
+    }
+    self.walk_decl(ast, decl);
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    if let (PostfixExp::Call(..), Some(name)) = (&ast[exp], self.mangled(exp)) {
+      self.calls.push((exp, name));
+    }
+    self.walk_postfix_exp(ast, exp);
+  }
+}
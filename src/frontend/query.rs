@@ -0,0 +1,207 @@
+//! 符号查询：由语义分析的结果得到源代码中各处名字所指的符号，由位置查询其所指的符号、定义与所有
+//! 引用之处，供语言服务器的跳转、查找引用与大纲，以及 `--find-refs` 使用。
+//!
+//! 名字的位置由语法树得到：声明处是声明符中的标识符、函数名是返回类型之后的记号，使用处是表达式
+//! 中的名字与调用的函数名。展开扩展语法时引入的临时变量不在源代码中，也就不在其中。
+//!
+//! ```
+//! use sysyc::frontend::query::SymbolIndex;
+//!
+//! let source = "int x; int main() { x = 1; return x; }";
+//! let unit = sysyc::analyze(sysyc::parse(source).unwrap()).unwrap();
+//! let index = SymbolIndex::new(&unit, source);
+//! let x = index.at(34).unwrap().symbol;
+//! assert_eq!(index.definition(x), Some((4, 5)));
+//! let spans: Vec<_> = index.references_to(x).map(|r| r.span).collect();
+//! assert_eq!(spans, [(4, 5), (20, 21), (34, 35)]);
+//! ```
+
+use std::collections::HashMap;
+
+use super::ast::*;
+use super::error::CompileError;
+use super::lexer::{Lexer, TokenKind};
+use super::sema::{analyze_all, SymbolId, TypedCompUnit};
+use super::source::SourceMap;
+use super::visit::Visit;
+
+/// 源代码中的一处名字及其所指的符号
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reference {
+  pub symbol: SymbolId,
+  /// 名字的范围
+  pub span: Span,
+  /// 声明处的名字是所在的声明符或函数声明的范围，使用处为 `None`
+  pub declaration: Option<Span>,
+}
+
+/// 各处名字所指的符号与各符号的定义
+pub struct SymbolIndex {
+  /// 按在源代码中的顺序
+  references: Vec<Reference>,
+  /// 各符号定义处的名字；只有声明的函数是其第一个声明
+  definitions: HashMap<SymbolId, Span>,
+}
+
+impl SymbolIndex {
+  /// `source` 是分析的源代码，用于找出函数声明中名字的位置
+  pub fn new(unit: &TypedCompUnit, source: &str) -> Self {
+    let mut collector = Collector {
+      source,
+      unit,
+      references: vec![],
+      definitions: HashMap::new(),
+    };
+    crate::stack::run(|| collector.visit_comp_unit(&unit.ast));
+    let Collector {
+      mut references,
+      definitions,
+      ..
+    } = collector;
+    references.sort_by_key(|r| r.span);
+    // 复合赋值展开后左边的名字出现两次
+    references.dedup_by_key(|r| r.span);
+    Self {
+      references,
+      definitions,
+    }
+  }
+
+  /// 所有的名字，按在源代码中的顺序
+  pub fn references(&self) -> &[Reference] {
+    &self.references
+  }
+
+  /// 位于 `pos` 处（含名字的末尾）的名字
+  pub fn at(&self, pos: Pos) -> Option<&Reference> {
+    let index = self.references.partition_point(|r| r.span.1 < pos);
+    let reference = self.references.get(index)?;
+    (reference.span.0 <= pos).then_some(reference)
+  }
+
+  /// 符号定义处的名字；运行时库函数没有定义
+  pub fn definition(&self, symbol: SymbolId) -> Option<Span> {
+    self.definitions.get(&symbol).copied()
+  }
+
+  /// 指向符号的各处名字（包括声明处），按在源代码中的顺序
+  pub fn references_to(&self, symbol: SymbolId) -> impl Iterator<Item = &Reference> {
+    self.references.iter().filter(move |r| r.symbol == symbol)
+  }
+}
+
+/// 列出名为 `name` 的各个符号的定义与引用之处（`--find-refs`），每行是 `文件:行:列: 种类`，
+/// 种类是 `definition`、`declaration` 或 `reference`。同名的符号（如不同函数中的局部变量）按
+/// 声明的顺序依次列出，其间空一行。有语义错误时仍列出能够解析的名字
+pub fn find_references(
+  file: &str,
+  input: &str,
+  name: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+  let ast = super::parse_source(input).map_err(|e| CompileError::Other(e.to_string()))?;
+  let (unit, _) = analyze_all(ast);
+  let index = SymbolIndex::new(&unit, input);
+  let source = SourceMap::new(input);
+  let mut groups = vec![];
+  for (symbol, info) in unit.symbols.iter().enumerate() {
+    let references: Vec<_> = index.references_to(symbol).collect();
+    if info.name.as_str() != name || references.is_empty() {
+      continue;
+    }
+    let mut lines = String::new();
+    for reference in references {
+      let kind = match reference.declaration {
+        _ if index.definition(symbol) == Some(reference.span) => "definition",
+        Some(_) => "declaration",
+        None => "reference",
+      };
+      let (line, column) = source.position(reference.span.0);
+      lines += &format!("{}:{}:{}: {}\n", file, line, column, kind);
+    }
+    groups.push(lines);
+  }
+  Ok(groups.join("\n"))
+}
+
+/// 从 `pos` 开始的函数声明中函数名的范围：跳过开头的属性，是返回类型之后的记号
+pub(super) fn func_name(source: &str, pos: Pos) -> Option<Span> {
+  let mut tokens = Lexer::starting_at(source, pos).map_while(Result::ok);
+  tokens.find(|(_, token, _)| matches!(token.kind, TokenKind::Int | TokenKind::Void))?;
+  let (start, token, end) = tokens.next()?;
+  (token.kind == TokenKind::Ident).then_some((start, end))
+}
+
+struct Collector<'a> {
+  source: &'a str,
+  unit: &'a TypedCompUnit,
+  references: Vec<Reference>,
+  definitions: HashMap<SymbolId, Span>,
+}
+
+impl Collector<'_> {
+  fn declare(&mut self, symbol: SymbolId, declaration: Span, span: Span, defines: bool) {
+    self.references.push(Reference {
+      symbol,
+      span,
+      declaration: Some(declaration),
+    });
+    if defines || !self.definitions.contains_key(&symbol) {
+      self.definitions.insert(symbol, span);
+    }
+  }
+
+  fn refer(&mut self, symbol: SymbolId, span: Span) {
+    self.references.push(Reference {
+      symbol,
+      span,
+      declaration: None,
+    });
+  }
+}
+
+/// 展开扩展语法时引入的临时变量的声明与引用，在源代码中是空范围
+fn is_temp<T: Node>(ast: &Ast, node: Id<T>) -> bool {
+  let (start, end) = ast.span(node);
+  start == end
+}
+
+impl Visit for Collector<'_> {
+  fn visit_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    if let (Decl::Func(func), Some(symbol)) = (&ast[decl], self.unit.symbol_id(decl)) {
+      let range = ast.span(decl);
+      if let Some(name) = func_name(self.source, range.0) {
+        self.declare(symbol, range, name, func.body.is_some());
+      }
+    }
+    self.walk_decl(ast, decl);
+  }
+
+  fn visit_declarator(&mut self, ast: &Ast, declarator: Id<Declarator>) {
+    if let Some(symbol) = self
+      .unit
+      .symbol_id(declarator)
+      .filter(|_| !is_temp(ast, declarator))
+    {
+      let mut ident = declarator;
+      while let Declarator::Pointer(inner) | Declarator::Array(inner, _) = ast[ident] {
+        ident = inner;
+      }
+      self.declare(symbol, ast.span(declarator), ast.span(ident), true);
+    }
+    self.walk_declarator(ast, declarator);
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    if let (&PostfixExp::Call(_, span, _), Some(symbol)) = (&ast[exp], self.unit.symbol_id(exp)) {
+      self.refer(symbol, span);
+    }
+    self.walk_postfix_exp(ast, exp);
+  }
+
+  fn visit_primary_exp(&mut self, ast: &Ast, exp: Id<PrimaryExp>) {
+    if let Some(symbol) = self.unit.symbol_id(exp).filter(|_| !is_temp(ast, exp)) {
+      self.refer(symbol, ast.span(exp));
+    }
+    self.walk_primary_exp(ast, exp);
+  }
+}
@@ -0,0 +1,113 @@
+//! 运行时检查（`-fsanitize=`）：在可能出错的运算之前插入检查，失败时调用以 SysY 实现的诊断例程
+//! （`sanitize.sy`），向标准错误输出源代码行与原因后终止程序。
+//!
+//! 检查失败的一侧标为不太可能的分支，排布基本块时移到函数末尾，不打断正常执行的顺序。
+
+use koopa::ir::builder::{LocalInstBuilder, ValueBuilder};
+use koopa::ir::{BinaryOp, Value, ValueKind};
+
+use super::ast::Name;
+use super::decl::GenerateContext;
+use super::symbol::Symbol;
+use crate::Result;
+
+/// 诊断例程的源代码，随程序一同生成
+pub const RUNTIME: &str = include_str!("sanitize.sy");
+
+/// 下标越界时调用，参数是行号、下标与数组长度
+const BOUNDS_FAIL: &str = "__sysy_bounds_fail";
+/// 除法出错时调用，参数是行号与是否溢出（否则是除数为零）
+const INTEGER_FAIL: &str = "__sysy_integer_fail";
+
+/// 启用的检查
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sanitizers {
+  /// 数组下标越界（`bounds`）
+  pub bounds: bool,
+  /// 除数为零与除法溢出（`integer`）
+  pub integer: bool,
+}
+
+impl Sanitizers {
+  pub const NAMES: &'static [&'static str] = &["bounds", "integer"];
+
+  /// 启用 `names`（逗号分隔）中的各项检查
+  pub fn enable(&mut self, names: &str) -> std::result::Result<(), String> {
+    for name in names.split(',') {
+      match name {
+        "bounds" => self.bounds = true,
+        "integer" => self.integer = true,
+        _ => {
+          let expect = Self::NAMES.join(", ");
+          return Err(format!("unknown -fsanitize '{}': expect {}", name, expect));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// 是否启用了任何一项检查，即需要诊断例程
+  pub fn any(self) -> bool {
+    self.bounds || self.integer
+  }
+}
+
+/// `cond` 非零时以当前行号与 `args` 调用诊断例程 `fail`，之后回到正常执行的基本块
+fn fail_if(context: &mut GenerateContext, cond: Value, fail: &str, args: &[Value]) -> Result<()> {
+  let Some(Symbol::Func(_, func)) = context.compilation.globals.get(&Name::intern(fail)) else {
+    panic!("sanitizer runtime function {} is not generated", fail);
+  };
+  context.expected.insert(cond, false);
+  context.new_bb_set();
+  let fail_bb = context.add_bb("sanitize_fail")?;
+  let ok_bb = context.add_bb("sanitize_ok")?;
+  let branch = context.branch(cond, fail_bb, ok_bb)?;
+  context.switch_bb(branch, Some(fail_bb))?;
+
+  let line = context.line() as i32;
+  let line = context.dfg().new_value().integer(line);
+  let args = [line].into_iter().chain(args.iter().copied()).collect();
+  context.insert(|b| b.call(func, args))?;
+  // 诊断例程不会返回
+  let jump = context.dfg().new_value().jump(ok_bb);
+  context.switch_bb(jump, Some(ok_bb))
+}
+
+/// `-fsanitize=bounds`：检查长为 `len` 的数组的下标 `index`；常量下标在范围内时不必检查
+pub fn check_bounds(context: &mut GenerateContext, index: Value, len: usize) -> Result<()> {
+  if let ValueKind::Integer(int) = context.dfg().value(index).kind() {
+    if (0..len as i32).contains(&int.value()) {
+      return Ok(());
+    }
+  }
+  let zero = context.dfg().new_value().integer(0);
+  let len = context.dfg().new_value().integer(len as i32);
+  let below = context.insert(|b| b.binary(BinaryOp::Lt, index, zero))?;
+  let above = context.insert(|b| b.binary(BinaryOp::Ge, index, len))?;
+  let out = context.insert(|b| b.binary(BinaryOp::Or, below, above))?;
+  fail_if(context, out, BOUNDS_FAIL, &[index, len])
+}
+
+/// `-fsanitize=integer`：检查除法与取模 `lhs / rhs`，常量除数只需检查可能出错的一项。
+/// SysY 没有移位运算，不必检查移位的位数
+pub fn check_division(context: &mut GenerateContext, lhs: Value, rhs: Value) -> Result<()> {
+  let divisor = match context.dfg().value(rhs).kind() {
+    ValueKind::Integer(int) => Some(int.value()),
+    _ => None,
+  };
+  if matches!(divisor, None | Some(0)) {
+    let zero = context.dfg().new_value().integer(0);
+    let by_zero = context.insert(|b| b.binary(BinaryOp::Eq, rhs, zero))?;
+    fail_if(context, by_zero, INTEGER_FAIL, &[zero])?;
+  }
+  if matches!(divisor, None | Some(-1)) {
+    let min = context.dfg().new_value().integer(i32::MIN);
+    let minus_one = context.dfg().new_value().integer(-1);
+    let lhs_min = context.insert(|b| b.binary(BinaryOp::Eq, lhs, min))?;
+    let rhs_minus_one = context.insert(|b| b.binary(BinaryOp::Eq, rhs, minus_one))?;
+    let overflow = context.insert(|b| b.binary(BinaryOp::And, lhs_min, rhs_minus_one))?;
+    let one = context.dfg().new_value().integer(1);
+    fail_if(context, overflow, INTEGER_FAIL, &[one])?;
+  }
+  Ok(())
+}
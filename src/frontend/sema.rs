@@ -0,0 +1,1071 @@
+//! 语义分析：解析各处名字所指的符号，计算表达式的类型，不生成 IR。
+//!
+//! 分析的结果 [`TypedCompUnit`] 持有语法树，另以表记录各个表达式节点的类型与各处名字所指的符号。
+//! 表以节点的 [`Id`] 为键，因此只能以 [`TypedCompUnit::ast`] 中的节点查询：
+//!
+//! ```
+//! use sysyc::frontend::ast::{BlockItem, Decl, Stmt};
+//! use sysyc::frontend::SysyType;
+//!
+//! let ast = sysyc::parse("int a[2][3]; int main() { return a[1][2]; }").unwrap();
+//! let unit = sysyc::analyze(ast).unwrap();
+//! let Decl::Func(main) = &unit.ast[unit.ast.unit[1]] else { unreachable!() };
+//! let BlockItem::Stmt(ret) = main.body.as_ref().unwrap()[0] else { unreachable!() };
+//! let Stmt::Return(Some(exp)) = unit.ast[ret] else { unreachable!() };
+//! assert_eq!(unit.type_of(exp), Some(&SysyType::Int));
+//! ```
+//!
+//! 检查的规则与生成 IR 时相同；此外检查返回值的类型。裸机运行时以 SysY 实现的库函数不在其中，
+//! 库函数总是运行时库中的声明。
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use koopa::ir::{FunctionData, Program, Type};
+
+use super::ast::*;
+use super::decl::FuncAttrs;
+use super::desugar::desugar;
+use super::error::CompileError;
+use super::expr::consteval;
+use super::expr::ty::SysyType;
+use super::expr::BUILTIN_EXPECT;
+use super::stmt::{expand_initializer, Layout};
+use super::symbol::{ConstValue, GlobalSymbols, Scope, Symbol};
+use super::visit::Visit;
+use crate::diagnostics::Diagnostic;
+
+/// 符号在 [`TypedCompUnit::symbols`] 中的序号
+pub type SymbolId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+  Const,
+  Var,
+  Param,
+  Func,
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolInfo {
+  pub name: Name,
+  pub kind: SymbolKind,
+  pub ty: SysyType,
+  /// 所在声明的位置；运行时库中的函数没有位置
+  pub pos: Option<Pos>,
+  /// 变量、常量与参数的声明中名字的范围
+  pub ident: Option<Span>,
+  pub global: bool,
+}
+
+/// 语义分析的结果
+pub struct TypedCompUnit {
+  pub ast: Ast,
+  /// 所有符号，按声明的顺序；同一函数的多次声明是同一个符号
+  pub symbols: Vec<SymbolInfo>,
+  types: HashMap<NodeKey, SysyType>,
+  resolved: HashMap<NodeKey, SymbolId>,
+}
+
+impl TypedCompUnit {
+  /// 表达式节点（`Exp`、`AddExp`、`PrimaryExp` 等任一层次）的类型
+  pub fn type_of<T: Node>(&self, exp: Id<T>) -> Option<&SysyType> {
+    self.types.get(&key(exp))
+  }
+
+  /// 节点所指或所声明的符号：名字（`PrimaryExp::Ident`）、函数调用（`PostfixExp::Call`）、
+  /// 变量与参数的声明符（`Declarator`）以及函数声明（`Decl::Func`）
+  pub fn symbol_of<T: Node>(&self, node: Id<T>) -> Option<&SymbolInfo> {
+    Some(&self.symbols[self.symbol_id(node)?])
+  }
+
+  /// 节点所指的符号在 [`TypedCompUnit::symbols`] 中的序号，用于判断两处是否是同一个符号
+  pub fn symbol_id<T: Node>(&self, node: Id<T>) -> Option<SymbolId> {
+    self.resolved.get(&key(node)).copied()
+  }
+
+  /// 名为 `name` 的全局符号
+  pub fn global(&self, name: &str) -> Option<&SymbolInfo> {
+    self.symbols.iter().find(|s| s.global && s.name == *name)
+  }
+}
+
+/// 运行时库中的函数：（名字，参数是否为指针）；返回值都是整数
+const RUNTIME_FUNCS: &[(&str, &[bool])] = &[
+  ("getint", &[]),
+  ("getch", &[]),
+  ("getarray", &[true]),
+  ("putint", &[false]),
+  ("putch", &[false]),
+  ("putarray", &[false, true]),
+  ("starttime", &[]),
+  ("stoptime", &[]),
+];
+
+/// 对语法树做语义分析。出错时仍分析其余部分，返回所有的错误；错误的位置是所在的声明或语句，
+/// 初始化器中的错误是其中出错的元素
+pub fn analyze(ast: Ast) -> Result<TypedCompUnit, Vec<Diagnostic>> {
+  match analyze_all(ast) {
+    (unit, diagnostics) if diagnostics.is_empty() => Ok(unit),
+    (_, diagnostics) => Err(diagnostics),
+  }
+}
+
+/// 与 [`analyze`] 相同，但出错时也返回分析的结果（如供编辑器在有错误的源程序中查询符号），
+/// 出错的节点没有类型，无法解析的名字没有符号。
+///
+/// `for` 循环、复合赋值等扩展的语法先展开，分析的是展开后的语法树，结果中的语法树也是如此。
+/// 复合赋值展开后左边出现两次，相同的错误只报告一次
+pub fn analyze_all(ast: Ast) -> (TypedCompUnit, Vec<Diagnostic>) {
+  analyze_with(ast, false)
+}
+
+/// 与 [`analyze_all`] 相同；`extensions` 时（`--extensions`）允许函数重载：参数类型不同的同名
+/// 函数是不同的符号，调用按实参的类型解析为其中之一
+pub fn analyze_with(ast: Ast, extensions: bool) -> (TypedCompUnit, Vec<Diagnostic>) {
+  crate::stack::run(|| analyze_unit(ast, extensions))
+}
+
+/// [`analyze_with`] 在切换后的栈上所做的部分
+fn analyze_unit(mut ast: Ast, extensions: bool) -> (TypedCompUnit, Vec<Diagnostic>) {
+  let errors = desugar(&mut ast).into_iter();
+  let errors = errors.map(|((start, end), e)| Diagnostic::at(start, end, e.message()));
+  let mut analyzer = Analyzer {
+    symbols: vec![],
+    scopes: vec![HashMap::new()],
+    overloads: HashMap::new(),
+    overloading: extensions,
+    defined: vec![],
+    attrs: HashMap::new(),
+    consts: HashMap::new(),
+    const_values: HashMap::new(),
+    types: HashMap::new(),
+    resolved: HashMap::new(),
+    pos: 0,
+    return_ty: None,
+    loops: 0,
+    diagnostics: errors.collect(),
+  };
+  for (name, params) in RUNTIME_FUNCS {
+    let params = params.iter().map(|&pointer| match pointer {
+      true => SysyType::Pointer(SysyType::Int.into()).into(),
+      false => SysyType::Int.into(),
+    });
+    let ty = SysyType::Function(SysyType::Int.into(), params.collect());
+    let id = analyzer.declare(&Name::intern(name), SymbolKind::Func, ty, None);
+    analyzer.defined.push(id);
+  }
+  analyzer.visit_comp_unit(&ast);
+  let unit = TypedCompUnit {
+    ast,
+    symbols: analyzer.symbols,
+    types: analyzer.types,
+    resolved: analyzer.resolved,
+  };
+  let mut diagnostics = vec![];
+  for diagnostic in analyzer.diagnostics {
+    if !diagnostics.contains(&diagnostic) {
+      diagnostics.push(diagnostic);
+    }
+  }
+  (unit, diagnostics)
+}
+
+/// 错误信息中类型的名字
+fn describe(ty: &SysyType) -> &'static str {
+  match ty {
+    SysyType::Int => "整数",
+    SysyType::Void => "void",
+    SysyType::Array(..) => "数组",
+    SysyType::Pointer(_) => "指针",
+    SysyType::Function(..) => "函数",
+  }
+}
+
+/// 错误信息中符号种类的名字
+fn kind_name(kind: SymbolKind) -> &'static str {
+  match kind {
+    SymbolKind::Const => "常量",
+    SymbolKind::Var => "变量",
+    SymbolKind::Param => "参数",
+    SymbolKind::Func => "函数",
+  }
+}
+
+/// 声明符中名字的范围
+fn ident_span(ast: &Ast, mut declarator: Id<Declarator>) -> Span {
+  while let Declarator::Pointer(inner) | Declarator::Array(inner, _) = ast[declarator] {
+    declarator = inner;
+  }
+  ast.span(declarator)
+}
+
+/// `candidates` 中有与 `name` 拼写相近的名字时，建议改用其中最相近的
+fn suggest<'a>(
+  diagnostic: Diagnostic,
+  name: &Name,
+  candidates: impl Iterator<Item = &'a Name>,
+) -> Diagnostic {
+  let name = name.as_str();
+  let similar = candidates
+    .map(|other| (edit_distance(name, other.as_str()), other.as_str()))
+    .filter(|&(distance, _)| distance <= name.len().div_ceil(3))
+    .min();
+  match similar {
+    Some((_, other)) => diagnostic.with_help(&format!("是否想用 '{}'？", other)),
+    None => diagnostic,
+  }
+}
+
+/// 两个名字的编辑距离，用于给出拼写相近的名字
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<_> = b.chars().collect();
+  let mut row: Vec<_> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut diagonal = row[0];
+    row[0] = i + 1;
+    for (j, &cb) in b.iter().enumerate() {
+      let substitute = diagonal + (ca != cb) as usize;
+      diagonal = row[j + 1];
+      row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+    }
+  }
+  row[b.len()]
+}
+
+struct Analyzer {
+  symbols: Vec<SymbolInfo>,
+  /// 各层作用域中的名字，第一层是全局作用域
+  scopes: Vec<HashMap<Name, SymbolId>>,
+  /// 各函数名的所有重载，按声明的顺序；全局作用域中的是其中第一个
+  overloads: HashMap<Name, Vec<SymbolId>>,
+  /// 允许函数重载（`--extensions`）
+  overloading: bool,
+  /// 已有定义的函数
+  defined: Vec<SymbolId>,
+  /// 函数在各次声明中的属性
+  attrs: HashMap<SymbolId, FuncAttrs>,
+  /// 常量表达式的值；数组按行优先展开
+  consts: HashMap<NodeKey, Layout<i32>>,
+  /// 常量的值
+  const_values: HashMap<SymbolId, Layout<i32>>,
+  types: HashMap<NodeKey, SysyType>,
+  resolved: HashMap<NodeKey, SymbolId>,
+  /// 当前所在的声明或语句的位置
+  pos: Pos,
+  /// 当前函数的返回类型
+  return_ty: Option<SysyType>,
+  /// 所在循环的层数
+  loops: usize,
+  diagnostics: Vec<Diagnostic>,
+}
+
+impl Analyzer {
+  fn error(&mut self, e: CompileError) {
+    let diagnostic = self.diagnostic(e);
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// 报告 `span` 处的错误，而不是所在的声明或语句
+  fn error_at(&mut self, span: Span, e: CompileError) {
+    let diagnostic = Diagnostic::at(span.0, span.1, e.message());
+    self.diagnostics.push(diagnostic);
+  }
+
+  fn diagnostic(&self, e: CompileError) -> Diagnostic {
+    Diagnostic::at(self.pos, self.pos, e.message())
+  }
+
+  /// 报告与此前的声明 `id` 冲突，并标出此前声明的位置
+  fn conflict(&mut self, e: CompileError, id: SymbolId, label: &str) {
+    let mut diagnostic = self.diagnostic(e);
+    if let Some(pos) = self.symbols[id].pos {
+      diagnostic = diagnostic.with_label(pos, pos, label);
+    }
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// 报告符号未定义；有拼写相近的可见符号时给出建议
+  fn undeclared(&mut self, name: &Name, span: Span) {
+    let e = CompileError::UndeclaredSymbol(name.to_string());
+    let diagnostic = Diagnostic::at(span.0, span.1, e.message());
+    let visible = self.scopes.iter().flat_map(|scope| scope.keys());
+    let diagnostic = suggest(diagnostic, name, visible);
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// 报告 `span` 处调用的 `name` 不是已声明的函数；`id` 是可见的同名符号（变量、常量或参数），
+  /// 此时标出它的名字。有拼写相近的函数时给出建议
+  fn implicit_declaration(&mut self, name: &Name, span: Span, id: Option<SymbolId>) {
+    let e = CompileError::ImplicitDeclaration(name.to_string());
+    let mut diagnostic = Diagnostic::at(span.0, span.1, e.message());
+    if let Some(id) = id {
+      let symbol = &self.symbols[id];
+      let note = format!(
+        "'{}' 是{}类型的{}，不是函数",
+        name,
+        describe(&symbol.ty),
+        kind_name(symbol.kind)
+      );
+      diagnostic = diagnostic.with_note(&note);
+      if let Some((start, end)) = symbol.ident {
+        diagnostic = diagnostic.with_label(start, end, "在此声明");
+      }
+    }
+    // 函数都在全局作用域中
+    let funcs = self.scopes[0]
+      .iter()
+      .filter(|&(_, &id)| self.symbols[id].kind == SymbolKind::Func)
+      .map(|(func, _)| func);
+    let diagnostic = suggest(diagnostic, name, funcs);
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// 报告错误，出错的表达式没有类型
+  fn fail<T>(&mut self, e: CompileError) -> Option<T> {
+    self.error(e);
+    None
+  }
+
+  /// 报告 `span` 处的错误，出错的表达式没有类型
+  fn fail_at<T>(&mut self, span: Span, e: CompileError) -> Option<T> {
+    self.error_at(span, e);
+    None
+  }
+
+  /// 子节点的类型；出错的子节点没有类型，也不再报告由此引起的错误
+  fn ty<T: Node>(&self, node: Id<T>) -> Option<SysyType> {
+    self.types.get(&key(node)).cloned()
+  }
+
+  fn value<T: Node>(&self, node: Id<T>) -> Option<i32> {
+    let data = self.consts.get(&key(node))?;
+    match data.len {
+      1 => Some(data.elems.first().map_or(0, |&(_, value)| value)),
+      _ => None,
+    }
+  }
+
+  fn record<T: Node>(&mut self, node: Id<T>, ty: Option<SysyType>, value: Option<Layout<i32>>) {
+    if let Some(ty) = ty {
+      self.types.insert(key(node), ty);
+    }
+    if let Some(value) = value {
+      self.consts.insert(key(node), value);
+    }
+  }
+
+  /// 只有一个子节点的文法层次，类型与值都与子节点相同
+  fn copy<T: Node, U: Node>(&mut self, node: Id<T>, child: Id<U>) {
+    let value = self.consts.get(&key(child)).cloned();
+    self.record(node, self.ty(child), value);
+  }
+
+  fn expect_int(&mut self, ty: Option<SysyType>) -> Option<SysyType> {
+    match ty? {
+      SysyType::Int => Some(SysyType::Int),
+      ty => {
+        self.error(CompileError::TypeMismatch("整数", "".into(), describe(&ty)));
+        None
+      }
+    }
+  }
+
+  /// 两个操作数都是整数的运算；两者都是常量时以 `fold` 求值
+  fn binary<T: Node, L: Node, R: Node>(
+    &mut self,
+    node: Id<T>,
+    lhs: Id<L>,
+    rhs: Id<R>,
+    fold: impl FnOnce(i32, i32) -> Option<i32>,
+  ) {
+    let lhs_ty = self.expect_int(self.ty(lhs));
+    let rhs_ty = self.expect_int(self.ty(rhs));
+    let ty = lhs_ty.and(rhs_ty);
+    let value = match (self.value(lhs), self.value(rhs)) {
+      (Some(lhs), Some(rhs)) => fold(lhs, rhs).map(Layout::scalar),
+      _ => None,
+    };
+    self.record(node, ty, value);
+  }
+
+  /// 在编译时求值对 `func` 的调用，与生成 IR 时相同（见 [`consteval::call`]）
+  fn call_value(&self, ast: &Ast, func: &Name, args: &[i32]) -> Option<i32> {
+    // 函数体中可见的全局符号只有常量与函数，函数只用于确认被调用的名字是函数
+    let mut program = Program::new();
+    let mut globals = GlobalSymbols::default();
+    for (name, &id) in &self.scopes[0] {
+      let symbol = &self.symbols[id];
+      let symbol = match (symbol.kind, self.const_values.get(&id)) {
+        (SymbolKind::Const, Some(data)) => {
+          Symbol::Const(ConstValue::from(symbol.ty.clone(), data.clone()))
+        }
+        (SymbolKind::Func, _) => {
+          let decl = FunctionData::new_decl(format!("@{}", name), vec![], Type::get_unit());
+          Symbol::Func(symbol.ty.clone(), program.new_func(decl))
+        }
+        _ => continue,
+      };
+      let _ = globals.insert_def(name.clone(), symbol);
+    }
+    consteval::call(func, args, Scope::global(ast, &globals))
+  }
+
+  fn lookup(&self, name: &Name) -> Option<SymbolId> {
+    self
+      .scopes
+      .iter()
+      .rev()
+      .find_map(|scope| scope.get(name).copied())
+  }
+
+  fn declare(&mut self, name: &Name, kind: SymbolKind, ty: SysyType, pos: Option<Pos>) -> SymbolId {
+    let id = self.symbols.len();
+    if let Some(&previous) = self.scopes.last().unwrap().get(name) {
+      let e = CompileError::Redefinition(name.to_string());
+      self.conflict(e, previous, "此前的定义");
+    }
+    self.scopes.last_mut().unwrap().insert(name.clone(), id);
+    self.push_symbol(name, kind, ty, pos)
+  }
+
+  /// 新建符号，不加入作用域
+  fn push_symbol(
+    &mut self,
+    name: &Name,
+    kind: SymbolKind,
+    ty: SysyType,
+    pos: Option<Pos>,
+  ) -> SymbolId {
+    let id = self.symbols.len();
+    if kind == SymbolKind::Func {
+      self.overloads.entry(name.clone()).or_default().push(id);
+    }
+    self.symbols.push(SymbolInfo {
+      name: name.clone(),
+      kind,
+      ty,
+      pos,
+      ident: None,
+      global: self.scopes.len() == 1,
+    });
+    id
+  }
+
+  /// 调用 `func` 时按实参的类型选择的重载：参数个数相同，各实参退化后的类型与参数相同，出错而
+  /// 没有类型的实参与任何参数相符。只有一个重载时总是选择它，参数的错误由调用处报告
+  fn overload(&self, func: &Name, args: &[Id<AssignExp>]) -> Option<SymbolId> {
+    let overloads = &self.overloads[func];
+    if let &[id] = overloads.as_slice() {
+      return Some(id);
+    }
+    overloads
+      .iter()
+      .copied()
+      .find(|&id| match &self.symbols[id].ty {
+        SysyType::Function(_, params) => {
+          params.len() == args.len()
+            && params.iter().zip(args).all(|(param, &arg)| {
+              self
+                .ty(arg)
+                .is_none_or(|arg| &arg.decay() == param.as_ref())
+            })
+        }
+        _ => false,
+      })
+  }
+
+  /// 声明符的类型与名字，与 [`SysyType::parse`] 相同；数组长度取自已求得的常量
+  fn declarator_type(&mut self, ast: &Ast, declarator: Id<Declarator>) -> Option<(SysyType, Name)> {
+    // 声明符由外向内的各层即类型由内向外的各层：`None` 是指针，`Some(len)` 是数组
+    let mut layers = vec![];
+    let mut declarator = declarator;
+    let ident = loop {
+      match ast[declarator] {
+        Declarator::Ident(ref ident) => break ident.clone(),
+        Declarator::Pointer(inner) => {
+          layers.push(None);
+          declarator = inner;
+        }
+        Declarator::Array(inner, len) => {
+          layers.push(Some(match self.value(len) {
+            Some(len) if len > 0 => len as usize,
+            Some(len) => return self.fail(CompileError::NegativeSubscript(len)),
+            None => return self.fail(CompileError::ConstexprRequired("数组长度")),
+          }));
+          declarator = inner;
+        }
+      }
+    };
+    let ty = layers
+      .into_iter()
+      .fold(SysyType::Int, |ty, layer| match layer {
+        None => SysyType::Pointer(ty.into()),
+        Some(len) => SysyType::Array(ty.into(), len),
+      });
+    Some((ty, ident))
+  }
+
+  /// 检查初始化器：单个表达式与变量的类型相同；初始化列表只用于数组，其中都是整数，且不多于数组的
+  /// 元素。返回展开后的初始化器，其中不是常量的元素为 `None`
+  fn check_initializer(
+    &mut self,
+    ast: &Ast,
+    ty: &SysyType,
+    init: Id<Initializer>,
+  ) -> Option<Layout<Option<i32>>> {
+    match ast[init] {
+      Initializer::Simple(exp) => {
+        let exp_ty = self.ty(exp)?;
+        if &exp_ty != ty {
+          return self.fail(CompileError::TypeMismatch(
+            describe(ty),
+            "".into(),
+            describe(&exp_ty),
+          ));
+        }
+        Some(Layout::scalar(self.value(exp)))
+      }
+      Initializer::Aggregate(_) if !matches!(ty, SysyType::Array(..)) => {
+        self.error_at(ast.span(init), CompileError::ScalarInitializerList);
+        None
+      }
+      Initializer::Aggregate(_) => {
+        if !self.check_elements(ast, init) {
+          return None;
+        }
+        // 展开时只能得到多出的元素本身，由此找回它在源代码中的位置
+        let mut nodes = HashMap::new();
+        let values = self.initializer_values(ast, init, &mut nodes);
+        let InitializerLike::Aggregate(list) = values.as_ref() else {
+          unreachable!()
+        };
+        match expand_initializer(&ty.get_array_size(), list) {
+          Ok(layout) => Some(layout),
+          Err(excess) => {
+            let excess = nodes[&Rc::as_ptr(&excess)];
+            self.error_at(ast.span(excess), CompileError::TooManyInitializers);
+            None
+          }
+        }
+      }
+    }
+  }
+
+  /// 初始化列表中的表达式都是整数
+  fn check_elements(&mut self, ast: &Ast, init: Id<Initializer>) -> bool {
+    match &ast[init] {
+      &Initializer::Simple(exp) => self.expect_int(self.ty(exp)).is_some(),
+      Initializer::Aggregate(list) => {
+        let checked: Vec<_> = list
+          .iter()
+          .map(|&init| self.check_elements(ast, init))
+          .collect();
+        checked.into_iter().all(|ok| ok)
+      }
+    }
+  }
+
+  /// 初始化列表中各个表达式的值，不是常量的为 `None`；`nodes` 记录各个元素对应的节点
+  fn initializer_values(
+    &self,
+    ast: &Ast,
+    init: Id<Initializer>,
+    nodes: &mut HashMap<*const InitializerLike<Option<i32>>, Id<Initializer>>,
+  ) -> Rc<InitializerLike<Option<i32>>> {
+    let values = match &ast[init] {
+      &Initializer::Simple(exp) => InitializerLike::Simple(self.value(exp)),
+      Initializer::Aggregate(list) => InitializerLike::Aggregate(
+        list
+          .iter()
+          .map(|&init| self.initializer_values(ast, init, nodes))
+          .collect(),
+      ),
+    };
+    let values = Rc::new(values);
+    nodes.insert(Rc::as_ptr(&values), init);
+    values
+  }
+
+  /// 报告初始化器中每个不是常量的表达式；`what` 是需要常量的初始化器
+  fn require_constant(&mut self, ast: &Ast, init: Id<Initializer>, what: &'static str) {
+    match &ast[init] {
+      &Initializer::Simple(exp) => {
+        if self.value(exp).is_none() {
+          self.error_at(ast.span(init), CompileError::ConstexprRequired(what));
+        }
+      }
+      Initializer::Aggregate(list) => {
+        for &init in list {
+          self.require_constant(ast, init, what);
+        }
+      }
+    }
+  }
+}
+
+impl Analyzer {
+  fn func_decl(&mut self, ast: &Ast, id: Id<Decl>, decl: &FuncDecl) {
+    let pos = self.pos;
+    let mut params = vec![];
+    for &(_, param) in &decl.params {
+      self.visit_declarator(ast, param);
+      params.push(self.declarator_type(ast, param));
+    }
+    let Some(params) = params.into_iter().collect::<Option<Vec<_>>>() else {
+      return;
+    };
+    self.check_params(ast, decl, &params);
+    let ret = match decl.func_type {
+      TypeSpec::Int => SysyType::Int,
+      TypeSpec::Void => SysyType::Void,
+    };
+    let param_tys: Vec<_> = params.iter().map(|(ty, _)| ty.clone().into()).collect();
+    let ty = SysyType::Function(ret.clone().into(), param_tys.clone());
+
+    // 函数可以多次声明，但类型须一致、只能定义一次；允许重载时参数类型不同的是另一个重载
+    let previous = match self.overloading {
+      true => self.overloads.get(&decl.ident).and_then(|overloads| {
+        let same = |&&f: &&SymbolId| {
+          matches!(&self.symbols[f].ty, SysyType::Function(_, p) if *p == param_tys)
+        };
+        overloads.iter().find(same).copied()
+      }),
+      false => self.scopes[0].get(&decl.ident).copied(),
+    };
+    let symbol = match previous {
+      None if self.overloading && self.overloads.contains_key(&decl.ident) => {
+        self.push_symbol(&decl.ident, SymbolKind::Func, ty, Some(pos))
+      }
+      Some(symbol) if self.symbols[symbol].kind == SymbolKind::Func => {
+        if self.symbols[symbol].ty != ty {
+          let e = CompileError::ConflictingDeclaration(decl.ident.to_string());
+          self.conflict(e, symbol, "此前的声明");
+        } else if decl.body.is_some() && self.defined.contains(&symbol) {
+          self.error(CompileError::Redefinition(decl.ident.to_string()));
+        }
+        symbol
+      }
+      _ => self.declare(&decl.ident, SymbolKind::Func, ty, Some(pos)),
+    };
+    self.resolved.insert(key(id), symbol);
+    let attrs = self.attrs.entry(symbol).or_default();
+    if let Err(e) = attrs.merge(&decl.ident, &decl.attrs) {
+      self.error(e);
+    }
+    let Some(body) = &decl.body else {
+      return;
+    };
+    self.defined.push(symbol);
+
+    // 参数与函数体在同一作用域中
+    self.scopes.push(HashMap::new());
+    for (&(_, param), (ty, name)) in decl.params.iter().zip(params) {
+      // 重复的参数已经报告过
+      if self.scopes.last().unwrap().contains_key(&name) {
+        continue;
+      }
+      let symbol = self.declare(&name, SymbolKind::Param, ty, Some(ast.pos(param)));
+      self.symbols[symbol].ident = Some(ident_span(ast, param));
+      self.resolved.insert(key(param), symbol);
+    }
+    self.return_ty = Some(ret);
+    for &item in body {
+      self.visit_block_item(ast, item);
+    }
+    self.return_ty = None;
+    self.scopes.pop();
+  }
+
+  /// 参数不能重名，不能与所在的函数同名，也不能是 `void` 类型；错误标出出错的参数
+  fn check_params(&mut self, ast: &Ast, decl: &FuncDecl, params: &[(SysyType, Name)]) {
+    let mut seen: Vec<(Name, Id<Declarator>)> = vec![];
+    for (&(spec, param), (_, name)) in decl.params.iter().zip(params) {
+      let span = ast.span(param);
+      if spec == TypeSpec::Void {
+        self.error_at(span, CompileError::VoidParameter(name.into()));
+      }
+      if *name == decl.ident {
+        self.error_at(span, CompileError::ParameterShadowsFunction(name.into()));
+      }
+      match seen.iter().find(|(other, _)| other == name) {
+        Some(&(_, previous)) => {
+          let e = CompileError::DuplicateParameter(name.into());
+          let (start, end) = ast.span(previous);
+          let diagnostic = Diagnostic::at(span.0, span.1, e.message());
+          let diagnostic = diagnostic.with_label(start, end, "此前的参数");
+          self.diagnostics.push(diagnostic);
+        }
+        None => seen.push((name.clone(), param)),
+      }
+    }
+  }
+}
+
+impl Visit for Analyzer {
+  fn visit_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    self.pos = ast.pos(decl);
+    match &ast[decl] {
+      Decl::Func(f) if self.scopes.len() > 1 => {
+        self.error(CompileError::Other(format!(
+          "不能在块作用域内声明函数 {}",
+          f.ident
+        )));
+      }
+      Decl::Func(f) => self.func_decl(ast, decl, f),
+      Decl::Var(var) => self.visit_var_decl(ast, var),
+    }
+  }
+
+  fn visit_var_decl(&mut self, ast: &Ast, decl: &DeclaratorAndInitializerList) {
+    let pos = self.pos;
+    if decl.ty == TypeSpec::Void {
+      return self.error(CompileError::IllegalVoid);
+    }
+    for &(declarator, init) in &decl.list {
+      self.visit_declarator(ast, declarator);
+      // 初始化器中的名字不会是正在声明的变量
+      if let Some(init) = init {
+        self.visit_initializer(ast, init);
+      }
+      let Some((ty, name)) = self.declarator_type(ast, declarator) else {
+        continue;
+      };
+      if decl.is_volatile && (decl.is_const || !ty.is_int_or_array()) {
+        self.error(CompileError::IllegalVolatile(name.to_string()));
+      }
+      let layout = match init {
+        Some(init) => self.check_initializer(ast, &ty, init),
+        None => None,
+      };
+      let global = self.scopes.len() == 1;
+      match (decl.is_const, init) {
+        (true, None) => self.error(CompileError::InitializerRequired(name.to_string())),
+        // 初始化器有误时已经报告过
+        _ if layout.is_none() => {}
+        (true, Some(init)) => self.require_constant(ast, init, "常量初始化器"),
+        (false, Some(init)) if global => self.require_constant(ast, init, "全局变量初始化器"),
+        _ => {}
+      }
+      // 未给出的元素为零，给出的元素都是常量时整个初始化器是常量
+      let value = layout.and_then(|layout| {
+        let elems = layout.elems.into_iter().map(|(i, x)| Some((i, x?)));
+        Some(Layout {
+          len: layout.len,
+          elems: elems.collect::<Option<_>>()?,
+        })
+      });
+      let kind = match decl.is_const {
+        true => SymbolKind::Const,
+        false => SymbolKind::Var,
+      };
+      let id = self.declare(&name, kind, ty, Some(pos));
+      self.symbols[id].ident = Some(ident_span(ast, declarator));
+      self.resolved.insert(key(declarator), id);
+      if let (true, Some(value)) = (decl.is_const, value) {
+        self.const_values.insert(id, value);
+      }
+    }
+  }
+
+  fn visit_stmt(&mut self, ast: &Ast, stmt: Id<Stmt>) {
+    if !matches!(ast[stmt], Stmt::Block(_)) {
+      self.pos = ast.pos(stmt);
+    }
+    match &ast[stmt] {
+      Stmt::Block(block) => {
+        self.scopes.push(HashMap::new());
+        self.walk_block(ast, block);
+        self.scopes.pop();
+      }
+      &Stmt::If(cond, then, otherwise) => {
+        self.visit_exp(ast, cond);
+        self.expect_int(self.ty(cond));
+        self.visit_stmt(ast, then);
+        if let Some(otherwise) = otherwise {
+          self.visit_stmt(ast, otherwise);
+        }
+      }
+      &Stmt::While(cond, body) => {
+        self.visit_exp(ast, cond);
+        self.expect_int(self.ty(cond));
+        self.loops += 1;
+        self.visit_stmt(ast, body);
+        self.loops -= 1;
+      }
+      Stmt::Break if self.loops == 0 => self.error(CompileError::IllegalBreak),
+      Stmt::Continue if self.loops == 0 => self.error(CompileError::IllegalContinue),
+      &Stmt::Return(Some(exp)) => {
+        self.walk_stmt(ast, stmt);
+        match (&self.return_ty, self.ty(exp)) {
+          (Some(SysyType::Void), _) => {
+            self.error(CompileError::Other("void 函数不能返回值".into()))
+          }
+          (_, Some(ty)) if ty != SysyType::Int => {
+            self.error(CompileError::TypeMismatch("整数", "".into(), describe(&ty)))
+          }
+          _ => {}
+        }
+      }
+      _ => self.walk_stmt(ast, stmt),
+    }
+  }
+
+  fn visit_exp(&mut self, ast: &Ast, exp: Id<Exp>) {
+    self.walk_exp(ast, exp);
+    match &ast[exp] {
+      Exp::Assign(assign) => self.copy(exp, *assign),
+      Exp::Comma(_, rhs) => self.record(exp, self.ty(*rhs), None),
+    }
+  }
+
+  fn visit_assign_exp(&mut self, ast: &Ast, exp: Id<AssignExp>) {
+    self.walk_assign_exp(ast, exp);
+    match &ast[exp] {
+      AssignExp::LOr(lor) => self.copy(exp, *lor),
+      AssignExp::Assign(lhs, rhs) => {
+        let ty = match (self.ty(*lhs), self.ty(*rhs)) {
+          // 值已知的常量（含 `const` 变量）没有地址，与生成 IR 时相同
+          _ if self.value(*lhs).is_some() => self.fail(CompileError::NotLValue),
+          (Some(SysyType::Array(..)), _) => {
+            let e = CompileError::TypeMismatch("可修改左值", "".into(), "数组");
+            self.fail(e)
+          }
+          (Some(lhs), Some(rhs)) if lhs != rhs => {
+            let e = CompileError::TypeMismatch("左值", "".into(), "不匹配的类型");
+            self.fail(e)
+          }
+          (lhs, _) => lhs,
+        };
+        self.record(exp, ty, None);
+      }
+      // 无法展开的扩展语法（已报告错误）没有类型
+      AssignExp::Compound(..) | AssignExp::Cond(..) => {}
+    }
+  }
+
+  fn visit_lor_exp(&mut self, ast: &Ast, exp: Id<LOrExp>) {
+    self.walk_lor_exp(ast, exp);
+    match &ast[exp] {
+      LOrExp::And(land) => self.copy(exp, *land),
+      LOrExp::Or(lhs, rhs) => {
+        self.binary(exp, *lhs, *rhs, |l, r| Some((l != 0 || r != 0) as i32));
+        // 短路：左边非零时整个表达式是常量
+        if self.value(*lhs).is_some_and(|l| l != 0) {
+          self.consts.insert(key(exp), Layout::scalar(1));
+        }
+      }
+    }
+  }
+
+  fn visit_land_exp(&mut self, ast: &Ast, exp: Id<LAndExp>) {
+    self.walk_land_exp(ast, exp);
+    match &ast[exp] {
+      LAndExp::Eq(eq) => self.copy(exp, *eq),
+      LAndExp::And(lhs, rhs) => {
+        self.binary(exp, *lhs, *rhs, |l, r| Some((l != 0 && r != 0) as i32));
+        if self.value(*lhs) == Some(0) {
+          self.consts.insert(key(exp), Layout::scalar(0));
+        }
+      }
+    }
+  }
+
+  fn visit_eq_exp(&mut self, ast: &Ast, exp: Id<EqExp>) {
+    self.walk_eq_exp(ast, exp);
+    match &ast[exp] {
+      EqExp::Rel(rel) => self.copy(exp, *rel),
+      EqExp::Eq(lhs, op, rhs) => self.binary(exp, *lhs, *rhs, |l, r| {
+        Some(match op {
+          EqOp::Equal => (l == r) as i32,
+          EqOp::NotEqual => (l != r) as i32,
+        })
+      }),
+    }
+  }
+
+  fn visit_rel_exp(&mut self, ast: &Ast, exp: Id<RelExp>) {
+    self.walk_rel_exp(ast, exp);
+    match &ast[exp] {
+      RelExp::Add(add) => self.copy(exp, *add),
+      RelExp::Rel(lhs, op, rhs) => self.binary(exp, *lhs, *rhs, |l, r| {
+        Some(match op {
+          RelOp::Less => (l < r) as i32,
+          RelOp::LessEqual => (l <= r) as i32,
+          RelOp::Greater => (l > r) as i32,
+          RelOp::GreaterEqual => (l >= r) as i32,
+        })
+      }),
+    }
+  }
+
+  fn visit_add_exp(&mut self, ast: &Ast, exp: Id<AddExp>) {
+    self.walk_add_exp(ast, exp);
+    match &ast[exp] {
+      AddExp::Mul(mul) => self.copy(exp, *mul),
+      AddExp::Add(lhs, op, rhs) => self.binary(exp, *lhs, *rhs, |l, r| {
+        Some(match op {
+          AddOp::Plus => l.wrapping_add(r),
+          AddOp::Minus => l.wrapping_sub(r),
+        })
+      }),
+    }
+  }
+
+  fn visit_mul_exp(&mut self, ast: &Ast, exp: Id<MulExp>) {
+    self.walk_mul_exp(ast, exp);
+    match &ast[exp] {
+      MulExp::Unary(unary) => self.copy(exp, *unary),
+      // 除以零不是常量
+      MulExp::Mul(lhs, op, rhs) => self.binary(exp, *lhs, *rhs, |l, r| match op {
+        MulOp::Multiply => Some(l.wrapping_mul(r)),
+        MulOp::Divide => l.checked_div(r),
+        MulOp::Modulo => l.checked_rem(r),
+      }),
+    }
+  }
+
+  fn visit_unary_exp(&mut self, ast: &Ast, exp: Id<UnaryExp>) {
+    self.walk_unary_exp(ast, exp);
+    match &ast[exp] {
+      UnaryExp::Postfix(postfix) => self.copy(exp, *postfix),
+      UnaryExp::Address(base) => {
+        let ty = self.ty(*base).map(|ty| SysyType::Pointer(ty.into()));
+        self.record(exp, ty, None);
+      }
+      UnaryExp::Deref(base) => {
+        let ty = match self.ty(*base) {
+          Some(SysyType::Pointer(ty)) => Some(*ty),
+          Some(ty) => {
+            let e = CompileError::TypeMismatch("指针", "".into(), describe(&ty));
+            self.fail(e)
+          }
+          None => None,
+        };
+        self.record(exp, ty, None);
+      }
+      UnaryExp::Op(op, operand) => {
+        let ty = self.expect_int(self.ty(*operand));
+        let value = self.value(*operand).map(|value| match op {
+          UnaryOp::Positive => value,
+          UnaryOp::Negative => value.wrapping_neg(),
+          UnaryOp::Not => (value == 0) as i32,
+        });
+        let value = value.map(Layout::scalar);
+        self.record(exp, ty, value);
+      }
+      UnaryExp::Step(..) => unreachable!("prefix step should be desugared"),
+    }
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    self.walk_postfix_exp(ast, exp);
+    match &ast[exp] {
+      PostfixExp::Primary(primary) => self.copy(exp, *primary),
+      PostfixExp::Subscript(array, index) => {
+        let index_ty = self.expect_int(self.ty(*index));
+        let ele = match self.ty(*array) {
+          Some(SysyType::Array(ele, _) | SysyType::Pointer(ele)) => Some(*ele),
+          Some(ty) => {
+            let e = CompileError::TypeMismatch("数组", "".into(), describe(&ty));
+            self.fail(e)
+          }
+          None => None,
+        };
+        // 常量数组以常量下标访问时仍是常量
+        let mut value = None;
+        if let (Some(data), Some(SysyType::Array(_, len)), Some(i)) = (
+          self.consts.get(&key(*array)),
+          self.ty(*array),
+          self.value(*index),
+        ) {
+          let step = data.len / len;
+          match usize::try_from(i) {
+            Ok(i) if i < len => value = Some(data.slice(i * step, step)),
+            _ => self.error(CompileError::IndexOutOfBounds(i, len)),
+          }
+        }
+        self.record(exp, index_ty.and(ele), value);
+      }
+      PostfixExp::Call(func, span, args) if *func == BUILTIN_EXPECT => {
+        let mut ty = Some(SysyType::Int);
+        if args.len() != 2 {
+          let e = CompileError::TypeMismatch("函数", func.into(), "数量不同的参数");
+          ty = self.fail_at(*span, e);
+        }
+        for arg in args {
+          if self.ty(*arg).is_some_and(|ty| ty != SysyType::Int) {
+            let e = CompileError::TypeMismatch("整数", func.into(), "错误的参数类型");
+            ty = self.fail_at(ast.span(*arg), e);
+          }
+        }
+        self.record(exp, ty, None);
+      }
+      PostfixExp::Call(func, span, args) => {
+        let span = *span;
+        let Some(mut id) = self.lookup(func) else {
+          return self.implicit_declaration(func, span, None);
+        };
+        if self.overloading && self.symbols[id].kind == SymbolKind::Func {
+          match self.overload(func, args) {
+            Some(overload) => id = overload,
+            None => return self.error_at(span, CompileError::NoMatchingOverload(func.into())),
+          }
+        }
+        self.resolved.insert(key(exp), id);
+        // 同名的函数被局部的变量遮蔽
+        let shadowed = self.scopes[0]
+          .get(func)
+          .is_some_and(|&f| self.symbols[f].kind == SymbolKind::Func);
+        let (ret, params) = match &self.symbols[id].ty {
+          SysyType::Function(ret, params) => (ret.as_ref().clone(), params.clone()),
+          ty if shadowed => {
+            let e = CompileError::TypeMismatch("函数", func.into(), describe(ty));
+            return self.error_at(span, e);
+          }
+          _ => return self.implicit_declaration(func, span, Some(id)),
+        };
+        let mut ty = Some(ret);
+        if params.len() != args.len() {
+          let e = CompileError::TypeMismatch("函数", func.into(), "数量不同的参数");
+          ty = self.fail_at(span, e);
+        }
+        for (param, arg) in params.iter().zip(args) {
+          if self
+            .ty(*arg)
+            .is_some_and(|arg| &arg.decay() != param.as_ref())
+          {
+            let e = CompileError::TypeMismatch(describe(param), func.into(), "错误的参数类型");
+            ty = self.fail_at(ast.span(*arg), e);
+          }
+        }
+        let args = args
+          .iter()
+          .map(|&arg| self.value(arg))
+          .collect::<Option<Vec<_>>>();
+        let value = match (&ty, args) {
+          (Some(SysyType::Int), Some(args)) => self.call_value(ast, func, &args),
+          _ => None,
+        };
+        self.record(exp, ty, value.map(Layout::scalar));
+      }
+      PostfixExp::Step(..) => unreachable!("postfix step should be desugared"),
+    }
+  }
+
+  fn visit_primary_exp(&mut self, ast: &Ast, exp: Id<PrimaryExp>) {
+    self.walk_primary_exp(ast, exp);
+    match &ast[exp] {
+      PrimaryExp::Num(num) => self.record(exp, Some(SysyType::Int), Some(Layout::scalar(*num))),
+      PrimaryExp::Ident(ident) => {
+        let Some(id) = self.lookup(ident) else {
+          return self.undeclared(ident, ast.span(exp));
+        };
+        self.resolved.insert(key(exp), id);
+        let value = self.const_values.get(&id).cloned();
+        self.record(exp, Some(self.symbols[id].ty.clone()), value);
+      }
+      PrimaryExp::Paren(inner) => self.copy(exp, *inner),
+    }
+  }
+}
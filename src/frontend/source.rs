@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use koopa::ir::{Function, Value};
+
+use super::ast::Pos;
+
+/// IR 指令到源代码行的映射，用于汇编注释（`--asm-comments`）与调试信息（`-g`）
+pub struct SourceMap {
+  /// 各行起始处的字节偏移
+  line_starts: Vec<usize>,
+  lines: Vec<String>,
+  /// 指令所属的行号（从 1 开始）
+  value_lines: HashMap<Value, usize>,
+  /// 函数定义所在的行号
+  func_lines: HashMap<Function, usize>,
+}
+
+impl SourceMap {
+  pub fn new(input: &str) -> Self {
+    let mut line_starts = vec![0];
+    line_starts.extend(input.match_indices('\n').map(|(i, _)| i + 1));
+    Self {
+      line_starts,
+      lines: input.lines().map(|line| line.trim().to_string()).collect(),
+      value_lines: HashMap::new(),
+      func_lines: HashMap::new(),
+    }
+  }
+
+  /// 位置所在的行号
+  pub fn line_at(&self, pos: Pos) -> usize {
+    self.line_starts.partition_point(|&start| start <= pos)
+  }
+
+  /// 位置所在的行号与列号（均从 1 开始，列以字节计）
+  pub fn position(&self, pos: Pos) -> (usize, usize) {
+    let line = self.line_at(pos);
+    (line, pos - self.line_starts[line - 1] + 1)
+  }
+
+  pub fn record(&mut self, value: Value, line: usize) {
+    self.value_lines.insert(value, line);
+  }
+
+  pub fn record_func(&mut self, func: Function, line: usize) {
+    self.func_lines.insert(func, line);
+  }
+
+  pub fn func_line(&self, func: Function) -> Option<usize> {
+    self.func_lines.get(&func).copied()
+  }
+
+  /// 指令所属的行号，以及该行（去除首尾空白的）源代码
+  pub fn line_of(&self, value: Value) -> Option<(usize, &str)> {
+    let line = *self.value_lines.get(&value)?;
+    let text = self.lines.get(line - 1).map_or("", |text| text.as_str());
+    Some((line, text))
+  }
+}
@@ -1,12 +1,11 @@
-use std::vec::IntoIter;
 use std::fmt::Debug;
 use std::iter::Peekable;
 use std::rc::Rc;
 
 use koopa::ir::builder::{LocalInstBuilder, ValueBuilder};
-use koopa::ir::Value;
+use koopa::ir::{FunctionData, Type, TypeKind, Value, ValueKind};
 
-use super::ast::{BlockItem, Decl, Initializer, InitializerLike, Stmt, TypeSpec};
+use super::ast::{AsmStmt, BlockItem, Decl, Id, Initializer, InitializerLike, Stmt, TypeSpec};
 use super::decl::GenerateContext;
 use super::error::CompileError;
 use super::expr;
@@ -30,36 +29,42 @@ trait GenerateStmt {
   fn generate(&self, context: &mut GenerateContext) -> Result<()>;
 }
 
-impl GenerateStmt for Stmt {
+impl GenerateStmt for Id<Stmt> {
   fn generate(&self, context: &mut GenerateContext) -> Result<()> {
-    match self {
+    let ast = context.ast;
+    let pos = ast.pos(*self);
+    match &ast[*self] {
       Stmt::Exp(exp) => {
+        context.set_pos(pos);
         if let Some(exp) = exp {
-          expr::generate(exp.as_ref(), context)?;
+          expr::generate(exp, context)?;
         }
       }
       Stmt::Block(block) => {
         context.symbol.push();
+        context.enter_scope();
         for item in block.iter() {
           generate(item, context)?;
         }
+        context.exit_scope();
         context.symbol.pop();
       }
       Stmt::If(exp, true_stmt, false_stmt) => {
-        let cond = exp.expect(Category::RValue)?.generate(context)?;
+        context.set_pos(pos);
+        let cond = exp.expect(Category::RValue).generate(context)?;
         context.new_bb_set();
         let true_bb = context.add_bb("if_true")?;
         let end_bb = context.add_bb("if_end")?;
         match false_stmt {
           None => {
-            let br = context.dfg().new_value().branch(cond, true_bb, end_bb);
+            let br = context.branch(cond, true_bb, end_bb)?;
             context.switch_bb(br, Some(true_bb))?;
             true_stmt.generate(context)?;
           }
           Some(false_stmt) => {
             let false_bb = context.add_bb("if_false")?;
 
-            let br = context.dfg().new_value().branch(cond, true_bb, false_bb);
+            let br = context.branch(cond, true_bb, false_bb)?;
             context.switch_bb(br, Some(true_bb))?;
             true_stmt.generate(context)?;
             let jump = context.dfg().new_value().jump(end_bb);
@@ -71,6 +76,7 @@ impl GenerateStmt for Stmt {
         context.switch_bb(jump, Some(end_bb))?;
       }
       Stmt::While(exp, stmt) => {
+        context.set_pos(pos);
         context.new_bb_set();
         let entry_bb = context.add_bb("while_entry")?;
         let body_bb = context.add_bb("while_body")?;
@@ -79,8 +85,8 @@ impl GenerateStmt for Stmt {
         let jump_into_entry = context.dfg().new_value().jump(entry_bb);
         context.switch_bb(jump_into_entry, Some(entry_bb))?;
 
-        let cond = exp.expect(Category::RValue)?.generate(context)?;
-        let br = context.dfg().new_value().branch(cond, body_bb, end_bb);
+        let cond = exp.expect(Category::RValue).generate(context)?;
+        let br = context.branch(cond, body_bb, end_bb)?;
         context.switch_bb(br, Some(body_bb))?;
 
         context.loop_jump_pt.push((end_bb, entry_bb));
@@ -89,7 +95,9 @@ impl GenerateStmt for Stmt {
         let jump = context.dfg().new_value().jump(entry_bb);
         context.switch_bb(jump, Some(end_bb))?;
       }
+      Stmt::For(..) => unreachable!("for loop should be desugared"),
       Stmt::Break => {
+        context.set_pos(pos);
         if context.loop_jump_pt.len() == 0 {
           Err(CompileError::IllegalBreak)?;
         }
@@ -98,6 +106,7 @@ impl GenerateStmt for Stmt {
         context.switch_bb(jump, None)?;
       }
       Stmt::Continue => {
+        context.set_pos(pos);
         if context.loop_jump_pt.len() == 0 {
           Err(CompileError::IllegalContinue)?;
         }
@@ -106,27 +115,119 @@ impl GenerateStmt for Stmt {
         context.switch_bb(jump, None)?;
       }
       Stmt::Return(exp) => {
+        context.set_pos(pos);
         let ret_val = match exp {
-          Some(e) => Some(e.expect(Category::RValue)?.generate(context)?),
+          Some(e) => Some(e.expect(Category::RValue).generate(context)?),
           None => None,
         };
+        // 返回值与函数的返回类型不符时报错，而不是在创建指令时 panic
+        let returns_int = match context.program.func(context.func).ty().kind() {
+          TypeKind::Function(_, ret) => ret.is_i32(),
+          _ => unreachable!("function should have function type"),
+        };
+        match ret_val.map(|val| context.value_ty_kind(val)) {
+          Some(_) if !returns_int => Err(CompileError::Other("void 函数不能返回值".into()))?,
+          Some(TypeKind::Int32) | None => {}
+          Some(TypeKind::Unit) => Err(CompileError::TypeMismatch("整数", "".into(), "void"))?,
+          Some(_) => Err(CompileError::TypeMismatch("整数", "".into(), "指针"))?,
+        }
         let ret = context.dfg().new_value().ret(ret_val);
         context.switch_bb(ret, None)?;
       }
+      Stmt::Asm(asm) => {
+        context.set_pos(pos);
+        asm.generate(context)?;
+      }
     }
     Ok(())
   }
 }
 
-impl GenerateStmt for Decl {
+impl GenerateStmt for AsmStmt {
   fn generate(&self, context: &mut GenerateContext) -> Result<()> {
-    match self {
+    // 输出操作数总是不与输入操作数共用寄存器，因此 "=r" 与 "=&r" 相同
+    let constraints = self.outputs.iter().map(|(c, _)| (c, &["=r", "=&r"][..]));
+    let constraints = constraints.chain(self.inputs.iter().map(|(c, _)| (c, &["r"][..])));
+    for (constraint, allowed) in constraints {
+      if !allowed.contains(&constraint.as_str()) {
+        Err(CompileError::Other(format!(
+          "不支持内联汇编约束 \"{}\"：输出操作数只能是 \"=r\"，输入操作数只能是 \"r\"",
+          constraint
+        )))?;
+      }
+    }
+    if self.outputs.len() > 1 {
+      Err(CompileError::Other("内联汇编至多有一个输出操作数".into()))?;
+    }
+    let count = self.outputs.len() + self.inputs.len();
+    let mut chars = self.template.chars().peekable();
+    while let Some(c) = chars.next() {
+      if c != '%' || chars.next_if_eq(&'%').is_some() {
+        continue;
+      }
+      let mut index = String::new();
+      while let Some(d) = chars.next_if(char::is_ascii_digit) {
+        index.push(d);
+      }
+      if !matches!(index.parse::<usize>(), Ok(i) if i < count) {
+        Err(CompileError::Other(format!(
+          "内联汇编模板中的 %{} 不是有效的操作数（共 {} 个操作数）",
+          index, count
+        )))?;
+      }
+    }
+
+    let output = match self.outputs.first() {
+      None => None,
+      Some((_, exp)) => Some(exp.expect(Category::LValue).generate(context)?),
+    };
+    let ret_ty = match output.map(|ptr| context.value_ty_kind(ptr)) {
+      None => Type::get_unit(),
+      Some(TypeKind::Pointer(base)) if !matches!(base.kind(), TypeKind::Array(..)) => base,
+      Some(_) => Err(CompileError::Other("内联汇编的输出操作数不能是数组".into()))?,
+    };
+    let args = self
+      .inputs
+      .iter()
+      .map(|(_, exp)| exp.expect(Category::RValue).generate(context))
+      .collect::<Result<Vec<_>>>()?;
+    let params = args
+      .iter()
+      .map(|&arg| context.dfg().value(arg).ty().clone())
+      .collect();
+
+    let name = format!(
+      "@__sysy_asm_{}",
+      context.compilation.annotations.inline_asm.len()
+    );
+    let func = context
+      .program
+      .new_func(FunctionData::new_decl(name, params, ret_ty));
+    let inline_asm = &mut context.compilation.annotations.inline_asm;
+    inline_asm.insert(func, self.template.clone());
+
+    let call = context.insert(|b| b.call(func, args))?;
+    if let Some(output) = output {
+      context.insert(|b| b.store(call, output))?;
+    }
+    Ok(())
+  }
+}
+
+impl GenerateStmt for Id<Decl> {
+  fn generate(&self, context: &mut GenerateContext) -> Result<()> {
+    let ast = context.ast;
+    match &ast[*self] {
       Decl::Var(declaration) => {
         if declaration.ty == TypeSpec::Void {
           Err(CompileError::IllegalVoid)?;
         }
-        for (decl, init) in &declaration.list {
-          let (ty, name) = SysyType::parse(decl.as_ref(), Some(context))?;
+        context.set_pos(ast.pos(*self));
+        for &(decl, init) in &declaration.list {
+          let (ty, name) = SysyType::parse(decl, context.scope())?;
+          if declaration.is_volatile && (declaration.is_const || !ty.is_int_or_array()) {
+            Err(CompileError::IllegalVolatile(name.to_string()))?;
+          }
           if declaration.is_const {
             // 局部常量声明
             if matches!(ty, SysyType::Pointer(_)) {
@@ -134,39 +235,42 @@ impl GenerateStmt for Decl {
                 "不支持指向常量的指针（不支持 ODR-使用常量）。".into(),
               ))?;
             }
-            let init = init
-              .as_ref()
-              .ok_or(CompileError::InitializerRequired(name.into()))?;
-            let const_value = match init.eval(Some(context)) {
+            let init = init.ok_or(CompileError::InitializerRequired(name.to_string()))?;
+            let const_value = match ast[init].eval(context.scope()) {
               Err(e) => Err(e.to_compile_error("常量初始化器"))?,
               Ok(exp) => match &exp {
                 InitializerLike::Simple(exp) => ConstValue::int(*exp),
                 InitializerLike::Aggregate(_) => {
                   let size = ty.get_array_size();
-                  let layout = get_layout(&size, &exp, &mut || 0)?;
+                  let layout = get_layout(&size, &exp)?;
                   ConstValue::from(ty, layout)
                 }
               },
             };
-            if !context.symbol.insert(name, Symbol::Const(const_value)) {
-              Err(CompileError::Redefinition(name.into()))?;
+            if !context
+              .symbol
+              .insert(name.clone(), Symbol::Const(const_value))
+            {
+              Err(CompileError::Redefinition(name.to_string()))?;
             }
           } else {
             // 局部变量声明
-            let alloc = context.dfg().new_value().alloc(ty.to_ir());
-            context.add_inst(alloc)?;
-            if let Some(ref init) = init {
-              let init_value = init.to_value(context)?;
+            let alloc = context.insert(|b| b.alloc(ty.to_ir()))?;
+            if declaration.is_volatile {
+              context.compilation.annotations.volatile.insert(alloc);
+            }
+            if matches!(ty, SysyType::Array(..)) {
+              context.add_scoped_array(alloc);
+            }
+            if let Some(init) = init {
+              let init_value = ast[init].to_value(context)?;
               match init_value {
                 InitializerLike::Simple(exp) => {
-                  let store = context.dfg().new_value().store(exp, alloc);
-                  context.add_inst(store)?;
+                  context.store(exp, alloc)?;
                 }
                 InitializerLike::Aggregate(_) => {
                   let size = ty.get_array_size();
-                  let layout = get_layout(&size, &init_value, &mut || {
-                    context.dfg().new_value().integer(0)
-                  })?;
+                  let layout = get_layout(&size, &init_value)?;
                   store_value_layout(size, alloc, layout, context)?;
                 }
               }
@@ -174,8 +278,8 @@ impl GenerateStmt for Decl {
             context
               .dfg()
               .set_value_name(alloc, Some(format!("@{}", name)));
-            if !context.symbol.insert(&name, Symbol::Var(ty, alloc)) {
-              return Err(CompileError::Redefinition(name.into()))?;
+            if !context.symbol.insert(name.clone(), Symbol::Var(ty, alloc)) {
+              return Err(CompileError::Redefinition(name.to_string()))?;
             }
           }
         }
@@ -189,73 +293,126 @@ impl GenerateStmt for Decl {
   }
 }
 
-fn get_layout_from_iter<T, DefaultFn>(
-  size: &Vec<usize>,
-  iter: &mut Peekable<IntoIter<Rc<InitializerLike<T>>>>,
-  default: &mut DefaultFn,
-) -> Result<Vec<T>>
+/// 初始化器多于数组的元素时为多出的第一个元素
+type Excess<T> = Rc<InitializerLike<T>>;
+
+/// 展开后的聚合初始化器：对象按行优先共有 `len` 个元素，`elems` 按下标的顺序记录给出了初始值
+/// 的元素 `(下标, 值)`，其余元素为零。零值占多数的大数组只需记录很少的元素
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout<T> {
+  pub len: usize,
+  pub elems: Vec<(usize, T)>,
+}
+
+impl<T: Clone> Layout<T> {
+  /// 只有一个元素的对象
+  pub fn scalar(value: T) -> Self {
+    Self {
+      len: 1,
+      elems: vec![(0, value)],
+    }
+  }
+
+  /// 第 `start` 个元素起 `len` 个元素构成的子对象
+  pub fn slice(&self, start: usize, len: usize) -> Self {
+    let from = self.elems.partition_point(|&(i, _)| i < start);
+    let to = self.elems.partition_point(|&(i, _)| i < start + len);
+    let elems = &self.elems[from..to];
+    Self {
+      len,
+      elems: elems.iter().map(|(i, x)| (i - start, x.clone())).collect(),
+    }
+  }
+}
+
+/// 从 `iter` 中取出大小为 `size`、从第 `start` 个元素开始的对象所需的元素，记入 `elems`，其余的
+/// 留在 `iter` 中。与 C 相同（C11 6.7.9），以花括号开头的初始化器初始化当前的整个子对象，否则
+/// 省略了子对象的花括号，从同一列表中依次取出它所需的元素
+fn get_layout_from_iter<T, I>(
+  size: &[usize],
+  iter: &mut Peekable<I>,
+  start: usize,
+  elems: &mut Vec<(usize, T)>,
+) -> std::result::Result<(), Excess<T>>
 where
   T: Clone + Copy + Debug,
-  DefaultFn: FnMut() -> T,
+  I: Iterator<Item = Rc<InitializerLike<T>>>,
 {
-  let total = size.iter().fold(1, |acc, x| acc * x);
-
   if size.len() == 0 {
     return match iter.next() {
-      None => Ok(vec![default()]),
+      None => Ok(()),
       Some(item) => match item.as_ref() {
-        InitializerLike::Simple(exp) => Ok(vec![exp.clone()]),
-        InitializerLike::Aggregate(list) => {
-          let mut iter = list.clone().into_iter().peekable();
-          get_layout_from_iter(size, &mut iter, default)
+        InitializerLike::Simple(exp) => {
+          elems.push((start, *exp));
+          Ok(())
         }
+        // 标量外的花括号，其中只能有一个元素
+        InitializerLike::Aggregate(list) => expand_initializer_at(size, list, start, elems),
       },
     };
   }
 
-  let mut current = vec![];
-  let new_size: Vec<_> = size[1..].iter().cloned().collect();
-  let new_total = new_size.iter().fold(1, |acc, x| acc * x);
-
+  let step: usize = size[1..].iter().product();
+  let end = start + size[0] * step;
+  let mut current = start;
   while let Some(item) = iter.peek() {
-    if current.len() == total {
-      return Ok(current);
+    if current == end {
+      break;
     }
     match item.as_ref() {
-      InitializerLike::Simple(_) => {
-        let result = get_layout_from_iter(&new_size, iter, default)?;
-        current.extend(result);
-      }
+      InitializerLike::Simple(_) => get_layout_from_iter(&size[1..], iter, current, elems)?,
       InitializerLike::Aggregate(list) => {
-        if list.len() > new_total {
-          eprintln!("expect {}, got > {}", new_total, list.len());
-          Err(CompileError::TooManyInitializers)?;
-        }
-        let mut list_iter = list.clone().into_iter().peekable();
-        let result = get_layout_from_iter(&new_size, &mut list_iter, default)?;
-        current.extend(result);
+        expand_initializer_at(&size[1..], list, current, elems)?;
         iter.next();
       }
     }
+    current += step;
   }
-  while current.len() < total {
-    current.push(default());
+  Ok(())
+}
+
+/// 以花括号中的 `list` 初始化大小为 `size`、从第 `start` 个元素开始的对象；`list` 多于对象的
+/// 元素时返回多出的第一个元素
+fn expand_initializer_at<T: Clone + Copy + Debug>(
+  size: &[usize],
+  list: &[Rc<InitializerLike<T>>],
+  start: usize,
+  elems: &mut Vec<(usize, T)>,
+) -> std::result::Result<(), Excess<T>> {
+  let mut iter = list.iter().cloned().peekable();
+  get_layout_from_iter(size, &mut iter, start, elems)?;
+  match iter.next() {
+    Some(excess) => Err(excess),
+    None => Ok(()),
   }
-  Ok(current)
+}
+
+/// 以花括号中的 `list` 初始化大小为 `size` 的对象并展开；`list` 多于对象的元素时返回多出的
+/// 第一个元素
+pub fn expand_initializer<T: Clone + Copy + Debug>(
+  size: &[usize],
+  list: &[Rc<InitializerLike<T>>],
+) -> std::result::Result<Layout<T>, Excess<T>> {
+  let mut elems = vec![];
+  expand_initializer_at(size, list, 0, &mut elems)?;
+  Ok(Layout {
+    len: size.iter().product(),
+    elems,
+  })
 }
 
 /// 将聚合初始化器展开
-pub fn get_layout<T: Clone + Copy + Debug, DefaultFn: FnMut() -> T>(
-  size: &Vec<usize>,
+pub fn get_layout<T: Clone + Copy + Debug>(
+  size: &[usize],
   init: &InitializerLike<T>,
-  default: &mut DefaultFn,
-) -> Result<Vec<T>> {
+) -> Result<Layout<T>> {
   match init {
-    InitializerLike::Simple(i) => Ok(vec![i.clone()]),
-    InitializerLike::Aggregate(aggr) => {
-      let mut iter = aggr.clone().into_iter().peekable();
-      get_layout_from_iter(size, &mut iter, default)
-    }
+    InitializerLike::Simple(i) => Ok(Layout::scalar(*i)),
+    InitializerLike::Aggregate(_) if size.is_empty() => Err(CompileError::ScalarInitializerList)?,
+    InitializerLike::Aggregate(aggr) => match expand_initializer(size, aggr) {
+      Ok(layout) => Ok(layout),
+      Err(_) => Err(CompileError::TooManyInitializers)?,
+    },
   }
 }
 
@@ -263,12 +420,13 @@ impl Initializer {
   fn to_value(&self, context: &mut GenerateContext) -> Result<InitializerLike<Value>> {
     match self {
       Initializer::Simple(exp) => Ok(InitializerLike::Simple(
-        exp.expect(Category::RValue)?.generate(context)?,
+        exp.expect(Category::RValue).generate(context)?,
       )),
       Initializer::Aggregate(aggr) => {
         let mut result: Vec<Rc<_>> = vec![];
-        for exp in aggr {
-          result.push(exp.to_value(context)?.into());
+        let ast = context.ast;
+        for &init in aggr {
+          result.push(ast[init].to_value(context)?.into());
         }
         Ok(InitializerLike::Aggregate(result))
       }
@@ -276,31 +434,84 @@ impl Initializer {
   }
 }
 
-/// 将 IR Value 构成的扁平数组初始化值保存到内存
+/// 零值至少有这么多个且占多数时，先将整个数组清零，再只保存非零元素
+const MIN_SPARSE_ZEROS: usize = 16;
+
+/// 将 IR Value 构成的数组初始化值保存到内存
 pub fn store_value_layout(
   size: Vec<usize>,
   base: Value,
-  data: Vec<Value>,
+  layout: Layout<Value>,
   context: &mut GenerateContext,
 ) -> Result<()> {
-  if size.len() == 0 {
-    let store = context.dfg().new_value().store(data[0], base);
-    context.add_inst(store)?;
+  let nonzeros = layout
+    .elems
+    .iter()
+    .filter(|&&(_, v)| !is_zero(context, v))
+    .count();
+  let zeros = layout.len - nonzeros;
+  let sparse = !size.is_empty() && zeros >= MIN_SPARSE_ZEROS && zeros * 2 > layout.len;
+  if sparse {
+    let ty = match context.dfg().value(base).ty().kind() {
+      TypeKind::Pointer(ty) => ty.clone(),
+      _ => panic!("array base should have pointer type"),
+    };
+    let zero_init = context.dfg().new_value().zero_init(ty);
+    context.insert(|b| b.store(zero_init, base))?;
+    let elems: Vec<_> = layout
+      .elems
+      .into_iter()
+      .filter(|&(_, v)| !is_zero(context, v))
+      .collect();
+    store_elements(&size, base, 0, &elems, None, context)
   } else {
-    let step = size[1..].iter().fold(1, |acc, x| acc * x);
-    for i in 0..size[0] {
-      let start_index = i * step;
-      let end_index = start_index + step;
-      let index = context.dfg().new_value().integer(i as i32);
-      let ptr = context.dfg().new_value().get_elem_ptr(base, index);
-      context.add_inst(ptr)?;
-      store_value_layout(
-        size[1..].into(),
-        ptr,
-        data[start_index..end_index].into(),
-        context,
-      )?;
+    // 未给出的元素共用同一个零值
+    let zero = context.dfg().new_value().integer(0);
+    store_elements(&size, base, 0, &layout.elems, Some(zero), context)
+  }
+}
+
+fn is_zero(context: &mut GenerateContext, value: Value) -> bool {
+  matches!(context.dfg().value(value).kind(), ValueKind::Integer(i) if i.value() == 0)
+}
+
+/// 保存从第 `start` 个元素开始的子数组中的元素 `elems`；其余元素在给出 `zero` 时保存为零值，
+/// 否则内存已清零，跳过这些元素与全零的子数组
+fn store_elements(
+  size: &[usize],
+  base: Value,
+  start: usize,
+  mut elems: &[(usize, Value)],
+  zero: Option<Value>,
+  context: &mut GenerateContext,
+) -> Result<()> {
+  if size.len() == 0 {
+    match (elems.first(), zero) {
+      (Some(&(_, value)), _) | (None, Some(value)) => {
+        context.store(value, base)?;
+      }
+      (None, None) => {}
+    }
+    return Ok(());
+  }
+  let step: usize = size[1..].iter().product();
+  let mut i = 0;
+  while i < size[0] {
+    if zero.is_none() {
+      // 直接跳到下一个有元素的子数组
+      match elems.first() {
+        Some(&(index, _)) => i = (index - start) / step,
+        None => break,
+      }
     }
+    let sub_start = start + i * step;
+    let end = elems.partition_point(|&(index, _)| index < sub_start + step);
+    let (sub, rest) = elems.split_at(end);
+    let index = context.dfg().new_value().integer(i as i32);
+    let ptr = context.insert(|b| b.get_elem_ptr(base, index))?;
+    store_elements(&size[1..], ptr, sub_start, sub, zero, context)?;
+    elems = rest;
+    i += 1;
   }
   Ok(())
 }
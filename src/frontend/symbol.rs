@@ -1,14 +1,18 @@
-use std::{collections::HashMap, sync::RwLock};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 
 use koopa::ir::{Function, Type, Value};
-use once_cell::sync::Lazy;
 
+use super::ast::{key, Ast, Id, Name, Node, NodeKey};
 use super::error::CompileError;
+use super::expr::consteval::CallStack;
 use super::expr::ty::SysyType;
+use super::stmt::Layout;
 
 #[derive(Debug, Clone)]
 pub struct ConstValue {
-  pub data: Vec<i32>,
+  /// 按行优先展开的值，未记录的元素为零
+  pub data: Layout<i32>,
 
   /// 当以变量下标访问常量数组时，仍然需要生成数组的 IR
   pub value: Option<Value>,
@@ -20,14 +24,14 @@ pub struct ConstValue {
 impl ConstValue {
   pub fn int(number: i32) -> Self {
     Self {
-      data: vec![number],
+      data: Layout::scalar(number),
       value: None,
       ty: SysyType::Int,
       size: vec![],
     }
   }
 
-  pub fn from(ty: SysyType, data: Vec<i32>) -> Self {
+  pub fn from(ty: SysyType, data: Layout<i32>) -> Self {
     Self {
       data,
       value: None,
@@ -38,7 +42,7 @@ impl ConstValue {
 
   pub fn as_int(&self) -> std::result::Result<i32, CompileError> {
     if self.size.len() == 0 {
-      Ok(self.data[0])
+      Ok(self.data.elems.first().map_or(0, |&(_, x)| x))
     } else {
       Err(CompileError::TypeMismatch("整数", "".into(), "数组"))?
     }
@@ -56,11 +60,9 @@ impl ConstValue {
     }
     let index = index as usize;
     let step = self.size[1..].iter().fold(1, |acc, &x| acc * x);
-    let start_index = index * step;
-    let end_index = start_index + step;
     Ok(Self::from(
       ele_ty.as_ref().clone(),
-      self.data[start_index..end_index].into(),
+      self.data.slice(index * step, step),
     ))
   }
 
@@ -83,10 +85,81 @@ pub enum Symbol {
   Func(SysyType, Function),
 }
 
-pub struct SymbolTable(Vec<HashMap<String, Symbol>>);
+pub struct SymbolTable(Vec<HashMap<Name, Symbol>>);
 
-/// (符号名, 存在定义)
-static GLOBAL: Lazy<RwLock<HashMap<String, (Symbol, bool)>>> = Lazy::new(|| RwLock::default());
+/// 全局符号：符号名到 (符号, 存在定义)。每次编译各有一份，由多个源文件共用
+#[derive(Default)]
+pub struct GlobalSymbols(HashMap<Name, (Symbol, bool)>);
+
+impl GlobalSymbols {
+  #[must_use]
+  pub fn insert_def(&mut self, key: Name, value: Symbol) -> bool {
+    let exists = self.0.insert(key, (value, true));
+    if let Some(exists) = exists {
+      if exists.1 {
+        return false;
+      }
+    }
+    true
+  }
+
+  /// 插入声明；已有定义时保留定义，之后的重复定义仍会报错
+  pub fn insert_decl(&mut self, key: Name, value: Symbol) -> bool {
+    if self.0.get(&key).is_some_and(|exists| exists.1) {
+      return false;
+    }
+    self.0.insert(key, (value, false)).is_none()
+  }
+
+  pub fn get(&self, key: &Name) -> Option<Symbol> {
+    self.0.get(key).cloned().map(|v| v.0)
+  }
+}
+
+/// 常量求值与类型推导时查找符号的作用域：先找局部符号，再找全局符号。`ast` 是所在的语法树
+#[derive(Clone, Copy)]
+pub struct Scope<'a, 'ast> {
+  pub ast: &'ast Ast,
+  pub globals: &'a GlobalSymbols,
+  /// 在全局作用域中时为 `None`
+  pub locals: Option<&'a SymbolTable>,
+  /// 在函数中生成 IR 时记录求值的结果
+  pub memo: Option<&'a EvalMemo>,
+  /// 求值函数调用时正在求值的各层调用；不在调用中时为 `None`
+  pub calls: Option<&'a CallStack>,
+}
+
+impl<'a, 'ast> Scope<'a, 'ast> {
+  pub fn global(ast: &'ast Ast, globals: &'a GlobalSymbols) -> Self {
+    Self {
+      ast,
+      globals,
+      locals: None,
+      memo: None,
+      calls: None,
+    }
+  }
+
+  pub fn get(&self, key: &Name) -> Option<Symbol> {
+    let local = self.locals.and_then(|locals| locals.get(key));
+    local.or_else(|| self.globals.get(key))
+  }
+}
+
+/// 生成 IR 时已知不是常量表达式的节点。每一层子表达式都先尝试求值以折叠常量，记录之后
+/// 各节点至多求值失败一次，嵌套很深的表达式也只需线性的时间
+#[derive(Default)]
+pub struct EvalMemo(RefCell<HashSet<NodeKey>>);
+
+impl EvalMemo {
+  pub fn is_not_constexpr<T: Node>(&self, node: Id<T>) -> bool {
+    self.0.borrow().contains(&key(node))
+  }
+
+  pub fn record_not_constexpr<T: Node>(&self, node: Id<T>) {
+    self.0.borrow_mut().insert(key(node));
+  }
+}
 
 impl SymbolTable {
   pub fn new() -> SymbolTable {
@@ -94,34 +167,15 @@ impl SymbolTable {
   }
 
   #[must_use]
-  pub fn insert(&mut self, key: &str, value: Symbol) -> bool {
+  pub fn insert(&mut self, key: Name, value: Symbol) -> bool {
     if self.0.len() == 0 {
       return false;
     }
     let current = self.0.last_mut().unwrap();
-    current.insert(key.into(), value).is_none()
-  }
-
-  #[must_use]
-  pub fn insert_global_def(key: &str, value: Symbol) -> bool {
-    let exists = GLOBAL.write().unwrap().insert(key.into(), (value, true));
-    if let Some(exists) = exists {
-      if exists.1 {
-        return false;
-      }
-    }
-    true
-  }
-
-  pub fn insert_global_decl(key: &str, value: Symbol) -> bool {
-    GLOBAL
-      .write()
-      .unwrap()
-      .insert(key.into(), (value, false))
-      .is_none()
+    current.insert(key, value).is_none()
   }
 
-  pub fn get(&self, key: &str) -> Option<Symbol> {
+  pub fn get(&self, key: &Name) -> Option<Symbol> {
     for i in self.0.iter().rev() {
       if let Some(v) = i.get(key) {
         return Some(v.clone());
@@ -130,8 +184,15 @@ impl SymbolTable {
     None
   }
 
-  pub fn get_global(key: &str) -> Option<Symbol> {
-    GLOBAL.read().ok()?.get(key).cloned().map(|v| v.0)
+  /// 改变最内层名为 `key` 的符号；没有时返回 `false`
+  pub fn assign(&mut self, key: &Name, value: Symbol) -> bool {
+    for i in self.0.iter_mut().rev() {
+      if let Some(v) = i.get_mut(key) {
+        *v = value;
+        return true;
+      }
+    }
+    false
   }
 
   pub fn push(&mut self) {
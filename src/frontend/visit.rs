@@ -0,0 +1,593 @@
+//! 语法树的遍历：[`Visit`] 只读地访问各个节点，[`VisitMut`] 可以就地修改。
+//!
+//! 每种节点有一个 `visit_*` 方法，默认实现调用对应的 `walk_*`，按源代码的顺序访问各子节点。分析
+//! 只需覆盖关心的节点，需要继续向下时再调用 `walk_*`。节点以 [`Id`] 给出，从一同传入的 [`Ast`]
+//! 中取得：
+//!
+//! ```
+//! use sysyc::frontend::ast::{Ast, Id, Name, PostfixExp};
+//! use sysyc::frontend::visit::Visit;
+//!
+//! /// 被调用的函数
+//! #[derive(Default)]
+//! struct Calls(Vec<Name>);
+//!
+//! impl Visit for Calls {
+//!   fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+//!     if let PostfixExp::Call(func, ..) = &ast[exp] {
+//!       self.0.push(func.clone());
+//!     }
+//!     self.walk_postfix_exp(ast, exp);
+//!   }
+//! }
+//!
+//! let ast = sysyc::parse("int main() { putint(getint()); return 0; }").unwrap();
+//! let mut calls = Calls::default();
+//! calls.visit_comp_unit(&ast);
+//! assert_eq!(calls.0, ["putint", "getint"]);
+//! ```
+
+use super::ast::*;
+
+/// 只读地遍历语法树
+pub trait Visit {
+  fn visit_comp_unit(&mut self, ast: &Ast) {
+    self.walk_comp_unit(ast);
+  }
+  fn walk_comp_unit(&mut self, ast: &Ast) {
+    for &decl in &ast.unit {
+      self.visit_decl(ast, decl);
+    }
+  }
+
+  fn visit_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    self.walk_decl(ast, decl);
+  }
+  fn walk_decl(&mut self, ast: &Ast, decl: Id<Decl>) {
+    match &ast[decl] {
+      Decl::Var(decl) => self.visit_var_decl(ast, decl),
+      Decl::Func(decl) => self.visit_func_decl(ast, decl),
+    }
+  }
+
+  fn visit_func_decl(&mut self, ast: &Ast, decl: &FuncDecl) {
+    self.walk_func_decl(ast, decl);
+  }
+  fn walk_func_decl(&mut self, ast: &Ast, decl: &FuncDecl) {
+    for &(_, param) in &decl.params {
+      self.visit_declarator(ast, param);
+    }
+    if let Some(body) = &decl.body {
+      self.visit_block(ast, body);
+    }
+  }
+
+  fn visit_var_decl(&mut self, ast: &Ast, decl: &DeclaratorAndInitializerList) {
+    self.walk_var_decl(ast, decl);
+  }
+  fn walk_var_decl(&mut self, ast: &Ast, decl: &DeclaratorAndInitializerList) {
+    for &(declarator, init) in &decl.list {
+      self.visit_declarator(ast, declarator);
+      if let Some(init) = init {
+        self.visit_initializer(ast, init);
+      }
+    }
+  }
+
+  fn visit_declarator(&mut self, ast: &Ast, declarator: Id<Declarator>) {
+    self.walk_declarator(ast, declarator);
+  }
+  fn walk_declarator(&mut self, ast: &Ast, declarator: Id<Declarator>) {
+    match ast[declarator] {
+      Declarator::Ident(_) => {}
+      Declarator::Pointer(inner) => self.visit_declarator(ast, inner),
+      Declarator::Array(inner, len) => {
+        self.visit_declarator(ast, inner);
+        self.visit_exp(ast, len);
+      }
+    }
+  }
+
+  fn visit_initializer(&mut self, ast: &Ast, init: Id<Initializer>) {
+    self.walk_initializer(ast, init);
+  }
+  fn walk_initializer(&mut self, ast: &Ast, init: Id<Initializer>) {
+    match &ast[init] {
+      Initializer::Simple(exp) => self.visit_assign_exp(ast, *exp),
+      Initializer::Aggregate(list) => {
+        for &init in list {
+          self.visit_initializer(ast, init);
+        }
+      }
+    }
+  }
+
+  fn visit_block(&mut self, ast: &Ast, block: &Block) {
+    self.walk_block(ast, block);
+  }
+  fn walk_block(&mut self, ast: &Ast, block: &Block) {
+    for &item in block {
+      self.visit_block_item(ast, item);
+    }
+  }
+
+  fn visit_block_item(&mut self, ast: &Ast, item: BlockItem) {
+    self.walk_block_item(ast, item);
+  }
+  fn walk_block_item(&mut self, ast: &Ast, item: BlockItem) {
+    match item {
+      BlockItem::Decl(decl) => self.visit_decl(ast, decl),
+      BlockItem::Stmt(stmt) => self.visit_stmt(ast, stmt),
+    }
+  }
+
+  fn visit_stmt(&mut self, ast: &Ast, stmt: Id<Stmt>) {
+    self.walk_stmt(ast, stmt);
+  }
+  fn walk_stmt(&mut self, ast: &Ast, stmt: Id<Stmt>) {
+    match &ast[stmt] {
+      Stmt::Exp(exp) | Stmt::Return(exp) => {
+        if let Some(exp) = exp {
+          self.visit_exp(ast, *exp);
+        }
+      }
+      Stmt::Block(block) => self.visit_block(ast, block),
+      Stmt::If(cond, then, otherwise) => {
+        self.visit_exp(ast, *cond);
+        self.visit_stmt(ast, *then);
+        if let Some(otherwise) = otherwise {
+          self.visit_stmt(ast, *otherwise);
+        }
+      }
+      Stmt::While(cond, body) => {
+        self.visit_exp(ast, *cond);
+        self.visit_stmt(ast, *body);
+      }
+      Stmt::For(init, cond, step, body) => {
+        self.visit_block_item(ast, *init);
+        for exp in [cond, step].into_iter().flatten() {
+          self.visit_exp(ast, *exp);
+        }
+        self.visit_stmt(ast, *body);
+      }
+      Stmt::Break | Stmt::Continue => {}
+      Stmt::Asm(asm) => self.visit_asm_stmt(ast, asm),
+    }
+  }
+
+  fn visit_asm_stmt(&mut self, ast: &Ast, asm: &AsmStmt) {
+    self.walk_asm_stmt(ast, asm);
+  }
+  fn walk_asm_stmt(&mut self, ast: &Ast, asm: &AsmStmt) {
+    for &(_, exp) in asm.outputs.iter().chain(&asm.inputs) {
+      self.visit_assign_exp(ast, exp);
+    }
+  }
+
+  fn visit_exp(&mut self, ast: &Ast, exp: Id<Exp>) {
+    self.walk_exp(ast, exp);
+  }
+  fn walk_exp(&mut self, ast: &Ast, exp: Id<Exp>) {
+    match ast[exp] {
+      Exp::Assign(exp) => self.visit_assign_exp(ast, exp),
+      Exp::Comma(lhs, rhs) => {
+        self.visit_exp(ast, lhs);
+        self.visit_assign_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_assign_exp(&mut self, ast: &Ast, exp: Id<AssignExp>) {
+    self.walk_assign_exp(ast, exp);
+  }
+  fn walk_assign_exp(&mut self, ast: &Ast, exp: Id<AssignExp>) {
+    match ast[exp] {
+      AssignExp::LOr(exp) => self.visit_lor_exp(ast, exp),
+      AssignExp::Assign(lhs, rhs) | AssignExp::Compound(lhs, _, rhs) => {
+        self.visit_lor_exp(ast, lhs);
+        self.visit_assign_exp(ast, rhs);
+      }
+      AssignExp::Cond(cond, then, els) => {
+        self.visit_lor_exp(ast, cond);
+        self.visit_exp(ast, then);
+        self.visit_assign_exp(ast, els);
+      }
+    }
+  }
+
+  fn visit_lor_exp(&mut self, ast: &Ast, exp: Id<LOrExp>) {
+    self.walk_lor_exp(ast, exp);
+  }
+  fn walk_lor_exp(&mut self, ast: &Ast, exp: Id<LOrExp>) {
+    match ast[exp] {
+      LOrExp::And(exp) => self.visit_land_exp(ast, exp),
+      LOrExp::Or(lhs, rhs) => {
+        self.visit_lor_exp(ast, lhs);
+        self.visit_land_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_land_exp(&mut self, ast: &Ast, exp: Id<LAndExp>) {
+    self.walk_land_exp(ast, exp);
+  }
+  fn walk_land_exp(&mut self, ast: &Ast, exp: Id<LAndExp>) {
+    match ast[exp] {
+      LAndExp::Eq(exp) => self.visit_eq_exp(ast, exp),
+      LAndExp::And(lhs, rhs) => {
+        self.visit_land_exp(ast, lhs);
+        self.visit_eq_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_eq_exp(&mut self, ast: &Ast, exp: Id<EqExp>) {
+    self.walk_eq_exp(ast, exp);
+  }
+  fn walk_eq_exp(&mut self, ast: &Ast, exp: Id<EqExp>) {
+    match ast[exp] {
+      EqExp::Rel(exp) => self.visit_rel_exp(ast, exp),
+      EqExp::Eq(lhs, _, rhs) => {
+        self.visit_eq_exp(ast, lhs);
+        self.visit_rel_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_rel_exp(&mut self, ast: &Ast, exp: Id<RelExp>) {
+    self.walk_rel_exp(ast, exp);
+  }
+  fn walk_rel_exp(&mut self, ast: &Ast, exp: Id<RelExp>) {
+    match ast[exp] {
+      RelExp::Add(exp) => self.visit_add_exp(ast, exp),
+      RelExp::Rel(lhs, _, rhs) => {
+        self.visit_rel_exp(ast, lhs);
+        self.visit_add_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_add_exp(&mut self, ast: &Ast, exp: Id<AddExp>) {
+    self.walk_add_exp(ast, exp);
+  }
+  fn walk_add_exp(&mut self, ast: &Ast, exp: Id<AddExp>) {
+    match ast[exp] {
+      AddExp::Mul(exp) => self.visit_mul_exp(ast, exp),
+      AddExp::Add(lhs, _, rhs) => {
+        self.visit_add_exp(ast, lhs);
+        self.visit_mul_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_mul_exp(&mut self, ast: &Ast, exp: Id<MulExp>) {
+    self.walk_mul_exp(ast, exp);
+  }
+  fn walk_mul_exp(&mut self, ast: &Ast, exp: Id<MulExp>) {
+    match ast[exp] {
+      MulExp::Unary(exp) => self.visit_unary_exp(ast, exp),
+      MulExp::Mul(lhs, _, rhs) => {
+        self.visit_mul_exp(ast, lhs);
+        self.visit_unary_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_unary_exp(&mut self, ast: &Ast, exp: Id<UnaryExp>) {
+    self.walk_unary_exp(ast, exp);
+  }
+  fn walk_unary_exp(&mut self, ast: &Ast, exp: Id<UnaryExp>) {
+    match ast[exp] {
+      UnaryExp::Postfix(exp) => self.visit_postfix_exp(ast, exp),
+      UnaryExp::Address(exp)
+      | UnaryExp::Deref(exp)
+      | UnaryExp::Op(_, exp)
+      | UnaryExp::Step(_, exp) => self.visit_unary_exp(ast, exp),
+    }
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    self.walk_postfix_exp(ast, exp);
+  }
+  fn walk_postfix_exp(&mut self, ast: &Ast, exp: Id<PostfixExp>) {
+    match &ast[exp] {
+      PostfixExp::Primary(exp) => self.visit_primary_exp(ast, *exp),
+      PostfixExp::Subscript(array, index) => {
+        self.visit_postfix_exp(ast, *array);
+        self.visit_exp(ast, *index);
+      }
+      PostfixExp::Step(exp, _) => self.visit_postfix_exp(ast, *exp),
+      PostfixExp::Call(.., args) => {
+        for &arg in args {
+          self.visit_assign_exp(ast, arg);
+        }
+      }
+    }
+  }
+
+  fn visit_primary_exp(&mut self, ast: &Ast, exp: Id<PrimaryExp>) {
+    self.walk_primary_exp(ast, exp);
+  }
+  fn walk_primary_exp(&mut self, ast: &Ast, exp: Id<PrimaryExp>) {
+    match ast[exp] {
+      PrimaryExp::Num(_) | PrimaryExp::Ident(_) => {}
+      PrimaryExp::Paren(exp) => self.visit_exp(ast, exp),
+    }
+  }
+}
+
+/// 与 [`Visit`] 相同，但可以修改语法树：访问到的节点可以经由 `ast[id]` 就地修改。函数声明、块与
+/// 内联汇编不单独存放，在所属的声明或语句的 `walk_*` 中遍历。修改节点后，`walk_*` 访问的是修改后
+/// 的子节点
+pub trait VisitMut {
+  fn visit_comp_unit(&mut self, ast: &mut Ast) {
+    self.walk_comp_unit(ast);
+  }
+  fn walk_comp_unit(&mut self, ast: &mut Ast) {
+    for decl in ast.unit.clone() {
+      self.visit_decl(ast, decl);
+    }
+  }
+
+  fn visit_decl(&mut self, ast: &mut Ast, decl: Id<Decl>) {
+    self.walk_decl(ast, decl);
+  }
+  fn walk_decl(&mut self, ast: &mut Ast, decl: Id<Decl>) {
+    match &ast[decl] {
+      Decl::Var(decl) => {
+        for (declarator, init) in decl.list.clone() {
+          self.visit_declarator(ast, declarator);
+          if let Some(init) = init {
+            self.visit_initializer(ast, init);
+          }
+        }
+      }
+      Decl::Func(decl) => {
+        let params = decl.params.clone();
+        let body = decl.body.clone();
+        for (_, param) in params {
+          self.visit_declarator(ast, param);
+        }
+        for item in body.into_iter().flatten() {
+          self.visit_block_item(ast, item);
+        }
+      }
+    }
+  }
+
+  fn visit_declarator(&mut self, ast: &mut Ast, declarator: Id<Declarator>) {
+    self.walk_declarator(ast, declarator);
+  }
+  fn walk_declarator(&mut self, ast: &mut Ast, declarator: Id<Declarator>) {
+    match ast[declarator] {
+      Declarator::Ident(_) => {}
+      Declarator::Pointer(inner) => self.visit_declarator(ast, inner),
+      Declarator::Array(inner, len) => {
+        self.visit_declarator(ast, inner);
+        self.visit_exp(ast, len);
+      }
+    }
+  }
+
+  fn visit_initializer(&mut self, ast: &mut Ast, init: Id<Initializer>) {
+    self.walk_initializer(ast, init);
+  }
+  fn walk_initializer(&mut self, ast: &mut Ast, init: Id<Initializer>) {
+    match &ast[init] {
+      Initializer::Simple(exp) => self.visit_assign_exp(ast, *exp),
+      Initializer::Aggregate(list) => {
+        for init in list.clone() {
+          self.visit_initializer(ast, init);
+        }
+      }
+    }
+  }
+
+  fn visit_block_item(&mut self, ast: &mut Ast, item: BlockItem) {
+    self.walk_block_item(ast, item);
+  }
+  fn walk_block_item(&mut self, ast: &mut Ast, item: BlockItem) {
+    match item {
+      BlockItem::Decl(decl) => self.visit_decl(ast, decl),
+      BlockItem::Stmt(stmt) => self.visit_stmt(ast, stmt),
+    }
+  }
+
+  fn visit_stmt(&mut self, ast: &mut Ast, stmt: Id<Stmt>) {
+    self.walk_stmt(ast, stmt);
+  }
+  fn walk_stmt(&mut self, ast: &mut Ast, stmt: Id<Stmt>) {
+    match &ast[stmt] {
+      Stmt::Exp(exp) | Stmt::Return(exp) => {
+        if let &Some(exp) = exp {
+          self.visit_exp(ast, exp);
+        }
+      }
+      Stmt::Block(block) => {
+        for item in block.clone() {
+          self.visit_block_item(ast, item);
+        }
+      }
+      &Stmt::If(cond, then, otherwise) => {
+        self.visit_exp(ast, cond);
+        self.visit_stmt(ast, then);
+        if let Some(otherwise) = otherwise {
+          self.visit_stmt(ast, otherwise);
+        }
+      }
+      &Stmt::While(cond, body) => {
+        self.visit_exp(ast, cond);
+        self.visit_stmt(ast, body);
+      }
+      &Stmt::For(init, cond, step, body) => {
+        self.visit_block_item(ast, init);
+        for exp in [cond, step].into_iter().flatten() {
+          self.visit_exp(ast, exp);
+        }
+        self.visit_stmt(ast, body);
+      }
+      Stmt::Break | Stmt::Continue => {}
+      Stmt::Asm(asm) => {
+        let operands: Vec<_> = asm
+          .outputs
+          .iter()
+          .chain(&asm.inputs)
+          .map(|op| op.1)
+          .collect();
+        for exp in operands {
+          self.visit_assign_exp(ast, exp);
+        }
+      }
+    }
+  }
+
+  fn visit_exp(&mut self, ast: &mut Ast, exp: Id<Exp>) {
+    self.walk_exp(ast, exp);
+  }
+  fn walk_exp(&mut self, ast: &mut Ast, exp: Id<Exp>) {
+    match ast[exp] {
+      Exp::Assign(exp) => self.visit_assign_exp(ast, exp),
+      Exp::Comma(lhs, rhs) => {
+        self.visit_exp(ast, lhs);
+        self.visit_assign_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_assign_exp(&mut self, ast: &mut Ast, exp: Id<AssignExp>) {
+    self.walk_assign_exp(ast, exp);
+  }
+  fn walk_assign_exp(&mut self, ast: &mut Ast, exp: Id<AssignExp>) {
+    match ast[exp] {
+      AssignExp::LOr(exp) => self.visit_lor_exp(ast, exp),
+      AssignExp::Assign(lhs, rhs) | AssignExp::Compound(lhs, _, rhs) => {
+        self.visit_lor_exp(ast, lhs);
+        self.visit_assign_exp(ast, rhs);
+      }
+      AssignExp::Cond(cond, then, els) => {
+        self.visit_lor_exp(ast, cond);
+        self.visit_exp(ast, then);
+        self.visit_assign_exp(ast, els);
+      }
+    }
+  }
+
+  fn visit_lor_exp(&mut self, ast: &mut Ast, exp: Id<LOrExp>) {
+    self.walk_lor_exp(ast, exp);
+  }
+  fn walk_lor_exp(&mut self, ast: &mut Ast, exp: Id<LOrExp>) {
+    match ast[exp] {
+      LOrExp::And(exp) => self.visit_land_exp(ast, exp),
+      LOrExp::Or(lhs, rhs) => {
+        self.visit_lor_exp(ast, lhs);
+        self.visit_land_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_land_exp(&mut self, ast: &mut Ast, exp: Id<LAndExp>) {
+    self.walk_land_exp(ast, exp);
+  }
+  fn walk_land_exp(&mut self, ast: &mut Ast, exp: Id<LAndExp>) {
+    match ast[exp] {
+      LAndExp::Eq(exp) => self.visit_eq_exp(ast, exp),
+      LAndExp::And(lhs, rhs) => {
+        self.visit_land_exp(ast, lhs);
+        self.visit_eq_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_eq_exp(&mut self, ast: &mut Ast, exp: Id<EqExp>) {
+    self.walk_eq_exp(ast, exp);
+  }
+  fn walk_eq_exp(&mut self, ast: &mut Ast, exp: Id<EqExp>) {
+    match ast[exp] {
+      EqExp::Rel(exp) => self.visit_rel_exp(ast, exp),
+      EqExp::Eq(lhs, _, rhs) => {
+        self.visit_eq_exp(ast, lhs);
+        self.visit_rel_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_rel_exp(&mut self, ast: &mut Ast, exp: Id<RelExp>) {
+    self.walk_rel_exp(ast, exp);
+  }
+  fn walk_rel_exp(&mut self, ast: &mut Ast, exp: Id<RelExp>) {
+    match ast[exp] {
+      RelExp::Add(exp) => self.visit_add_exp(ast, exp),
+      RelExp::Rel(lhs, _, rhs) => {
+        self.visit_rel_exp(ast, lhs);
+        self.visit_add_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_add_exp(&mut self, ast: &mut Ast, exp: Id<AddExp>) {
+    self.walk_add_exp(ast, exp);
+  }
+  fn walk_add_exp(&mut self, ast: &mut Ast, exp: Id<AddExp>) {
+    match ast[exp] {
+      AddExp::Mul(exp) => self.visit_mul_exp(ast, exp),
+      AddExp::Add(lhs, _, rhs) => {
+        self.visit_add_exp(ast, lhs);
+        self.visit_mul_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_mul_exp(&mut self, ast: &mut Ast, exp: Id<MulExp>) {
+    self.walk_mul_exp(ast, exp);
+  }
+  fn walk_mul_exp(&mut self, ast: &mut Ast, exp: Id<MulExp>) {
+    match ast[exp] {
+      MulExp::Unary(exp) => self.visit_unary_exp(ast, exp),
+      MulExp::Mul(lhs, _, rhs) => {
+        self.visit_mul_exp(ast, lhs);
+        self.visit_unary_exp(ast, rhs);
+      }
+    }
+  }
+
+  fn visit_unary_exp(&mut self, ast: &mut Ast, exp: Id<UnaryExp>) {
+    self.walk_unary_exp(ast, exp);
+  }
+  fn walk_unary_exp(&mut self, ast: &mut Ast, exp: Id<UnaryExp>) {
+    match ast[exp] {
+      UnaryExp::Postfix(exp) => self.visit_postfix_exp(ast, exp),
+      UnaryExp::Address(exp)
+      | UnaryExp::Deref(exp)
+      | UnaryExp::Op(_, exp)
+      | UnaryExp::Step(_, exp) => self.visit_unary_exp(ast, exp),
+    }
+  }
+
+  fn visit_postfix_exp(&mut self, ast: &mut Ast, exp: Id<PostfixExp>) {
+    self.walk_postfix_exp(ast, exp);
+  }
+  fn walk_postfix_exp(&mut self, ast: &mut Ast, exp: Id<PostfixExp>) {
+    match &ast[exp] {
+      &PostfixExp::Primary(exp) => self.visit_primary_exp(ast, exp),
+      &PostfixExp::Subscript(array, index) => {
+        self.visit_postfix_exp(ast, array);
+        self.visit_exp(ast, index);
+      }
+      &PostfixExp::Step(exp, _) => self.visit_postfix_exp(ast, exp),
+      PostfixExp::Call(.., args) => {
+        for arg in args.clone() {
+          self.visit_assign_exp(ast, arg);
+        }
+      }
+    }
+  }
+
+  fn visit_primary_exp(&mut self, ast: &mut Ast, exp: Id<PrimaryExp>) {
+    self.walk_primary_exp(ast, exp);
+  }
+  fn walk_primary_exp(&mut self, ast: &mut Ast, exp: Id<PrimaryExp>) {
+    if let PrimaryExp::Paren(exp) = ast[exp] {
+      self.visit_exp(ast, exp);
+    }
+  }
+}
@@ -0,0 +1,440 @@
+//! Koopa IR 解释器（`sysyc run`），不需要 RISC-V 工具链即可运行程序。
+//!
+//! 内存是一段按字节编址、按字访问的连续空间：地址 0 不可访问，其后依次是全局变量与栈。
+//! 指针与 `int` 一样占 4 字节。运行时库函数直接读写标准输入输出，算术运算的结果与
+//! RISC-V 后端一致。函数调用不使用宿主的栈，深度递归的程序也能运行。
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{stderr, stdin, stdout, BufWriter, Read, Write};
+
+use koopa::ir::{BasicBlock, BinaryOp, Function, FunctionData, Type, TypeKind};
+use koopa::ir::{Value, ValueKind};
+
+use crate::frontend::Ir;
+use crate::Result;
+
+/// 栈空间的大小（字节）
+const STACK_SIZE: usize = 256 << 20;
+/// 函数调用的最大深度
+const MAX_CALL_DEPTH: usize = 1 << 20;
+
+#[derive(Debug)]
+pub struct RuntimeError(pub String);
+
+impl Error for RuntimeError {}
+
+impl fmt::Display for RuntimeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "runtime error: {}", self.0)
+  }
+}
+
+fn size_of(ty: &Type) -> usize {
+  match ty.kind() {
+    TypeKind::Int32 | TypeKind::Pointer(_) => 4,
+    TypeKind::Array(base, len) => size_of(base) * len,
+    TypeKind::Unit | TypeKind::Function(..) => 0,
+  }
+}
+
+/// 指针类型所指向对象的大小
+fn pointee_size(ty: &Type) -> usize {
+  match ty.kind() {
+    TypeKind::Pointer(base) => size_of(base),
+    _ => panic!("expected pointer type, found {}", ty),
+  }
+}
+
+/// 把初始值展开为字；`value_of` 取得全局或局部的值
+fn flatten<F>(value_of: &F, value: Value, words: &mut Vec<i32>)
+where
+  F: Fn(Value) -> (ValueKind, Type),
+{
+  let (kind, ty) = value_of(value);
+  match kind {
+    ValueKind::Integer(i) => words.push(i.value()),
+    ValueKind::Aggregate(agg) => {
+      for &elem in agg.elems() {
+        flatten(value_of, elem, words);
+      }
+    }
+    _ => words.extend(std::iter::repeat_n(0, size_of(&ty) / 4)),
+  }
+}
+
+/// 一层函数调用
+struct Frame<'a> {
+  fd: &'a FunctionData,
+  args: Vec<i32>,
+  /// 指令与基本块参数的值
+  values: HashMap<Value, i32>,
+  bb: BasicBlock,
+  /// 下一条指令在基本块中的位置
+  pos: usize,
+  /// 进入函数时的栈顶，返回时释放其后的 `alloc`
+  sp: usize,
+}
+
+impl Frame<'_> {
+  fn eval(&self, globals: &HashMap<Value, i32>, value: Value) -> i32 {
+    if value.is_global() {
+      return globals[&value];
+    }
+    match self.fd.dfg().value(value).kind() {
+      ValueKind::Integer(i) => i.value(),
+      ValueKind::ZeroInit(_) | ValueKind::Undef(_) => 0,
+      ValueKind::FuncArgRef(arg) => self.args[arg.index()],
+      _ => self.values[&value],
+    }
+  }
+
+  /// 跳转到 `target`，同时传递基本块参数
+  fn jump(&mut self, globals: &HashMap<Value, i32>, target: BasicBlock, args: &[Value]) {
+    let args: Vec<_> = args.iter().map(|&arg| self.eval(globals, arg)).collect();
+    for (&param, arg) in self.fd.dfg().bb(target).params().iter().zip(args) {
+      self.values.insert(param, arg);
+    }
+    self.bb = target;
+    self.pos = 0;
+  }
+}
+
+/// 执行一条指令后的去向
+enum Step {
+  Next,
+  Call(Function, Vec<i32>),
+  Return(i32),
+}
+
+/// 标准输入；第一次读取时读入全部内容，或事先给出（`sysyc test`）
+#[derive(Default)]
+pub(crate) struct Input {
+  bytes: Option<Vec<u8>>,
+  pos: usize,
+}
+
+impl Input {
+  /// `bytes` 为 `None` 时读取标准输入
+  pub(crate) fn new(bytes: Option<Vec<u8>>) -> Self {
+    Self { bytes, pos: 0 }
+  }
+
+  fn peek(&mut self) -> Result<Option<u8>> {
+    if self.bytes.is_none() {
+      let mut bytes = vec![];
+      stdin().read_to_end(&mut bytes)?;
+      self.bytes = Some(bytes);
+    }
+    Ok(self.bytes.as_ref().unwrap().get(self.pos).copied())
+  }
+
+  pub(crate) fn getch(&mut self) -> Result<i32> {
+    let c = self.peek()?;
+    self.pos += c.is_some() as usize;
+    Ok(c.map_or(-1, i32::from))
+  }
+
+  /// 同 `scanf("%d")`；没有读到整数时得到 0
+  pub(crate) fn getint(&mut self) -> Result<i32> {
+    while self.peek()?.is_some_and(|c| c.is_ascii_whitespace()) {
+      self.pos += 1;
+    }
+    let neg = match self.peek()? {
+      Some(c @ (b'-' | b'+')) => {
+        self.pos += 1;
+        c == b'-'
+      }
+      _ => false,
+    };
+    let mut value = 0i32;
+    while let Some(c @ b'0'..=b'9') = self.peek()? {
+      self.pos += 1;
+      value = value.wrapping_mul(10).wrapping_add((c - b'0') as i32);
+    }
+    Ok(if neg { value.wrapping_neg() } else { value })
+  }
+}
+
+struct Machine<'a> {
+  ir: &'a Ir,
+  /// 各基本块中的指令
+  blocks: HashMap<BasicBlock, Vec<Value>>,
+  /// 全局变量的地址
+  globals: HashMap<Value, i32>,
+  memory: Vec<i32>,
+  sp: usize,
+  stack_limit: usize,
+  input: Input,
+  output: BufWriter<Box<dyn Write + 'a>>,
+}
+
+impl<'a> Machine<'a> {
+  fn new(ir: &'a Ir, input: Input, output: Box<dyn Write + 'a>) -> Self {
+    let mut blocks = HashMap::new();
+    for &f in ir.func_layout() {
+      for (&bb, node) in ir.func(f).layout().bbs() {
+        blocks.insert(bb, node.insts().keys().copied().collect());
+      }
+    }
+
+    let mut memory = vec![0];
+    let mut globals = HashMap::new();
+    let value_of = |v| {
+      let vd = ir.borrow_value(v);
+      (vd.kind().clone(), vd.ty().clone())
+    };
+    for &v in ir.inst_layout() {
+      if let ValueKind::GlobalAlloc(alloc) = ir.borrow_value(v).kind() {
+        globals.insert(v, (memory.len() * 4) as i32);
+        flatten(&value_of, alloc.init(), &mut memory);
+      }
+    }
+
+    let sp = memory.len() * 4;
+    Self {
+      ir,
+      blocks,
+      globals,
+      memory,
+      sp,
+      stack_limit: sp + STACK_SIZE,
+      input,
+      output: BufWriter::new(output),
+    }
+  }
+
+  fn word(&self, addr: i32) -> Result<usize> {
+    let index = addr as u32 as usize / 4;
+    if addr % 4 != 0 || index == 0 || index >= self.memory.len() {
+      return Err(RuntimeError(format!("invalid memory access at {:#x}", addr as u32)).into());
+    }
+    Ok(index)
+  }
+
+  fn load(&self, addr: i32) -> Result<i32> {
+    Ok(self.memory[self.word(addr)?])
+  }
+
+  fn store(&mut self, addr: i32, value: i32) -> Result<()> {
+    let index = self.word(addr)?;
+    self.memory[index] = value;
+    Ok(())
+  }
+
+  /// 在栈上分配并清零 `size` 字节
+  fn alloc(&mut self, size: usize) -> Result<i32> {
+    let addr = self.sp;
+    if addr + size > self.stack_limit {
+      return Err(RuntimeError("stack overflow".into()).into());
+    }
+    self.sp += size;
+    let (start, end) = (addr / 4, self.sp / 4);
+    if self.memory.len() < end {
+      self.memory.resize(end, 0);
+    }
+    self.memory[start..end].fill(0);
+    Ok(addr as i32)
+  }
+
+  fn enter(&self, func: Function, args: Vec<i32>) -> Frame<'a> {
+    let fd = self.ir.func(func);
+    Frame {
+      fd,
+      args,
+      values: HashMap::new(),
+      bb: fd.layout().entry_bb().unwrap(),
+      pos: 0,
+      sp: self.sp,
+    }
+  }
+
+  /// 调用运行时库函数
+  fn call_runtime(&mut self, func: Function, args: &[i32]) -> Result<i32> {
+    let name = self.ir.func(func).name();
+    if self.ir.annotations.inline_asm.contains_key(&func) {
+      return Err("inline assembly cannot be interpreted".into());
+    }
+    Ok(match name {
+      "@getint" => self.input.getint()?,
+      "@getch" => self.input.getch()?,
+      "@getarray" => {
+        let n = self.input.getint()?;
+        for i in 0..n {
+          let value = self.input.getint()?;
+          self.store(args[0].wrapping_add(i.wrapping_mul(4)), value)?;
+        }
+        n
+      }
+      "@putint" => {
+        write!(self.output, "{}", args[0])?;
+        0
+      }
+      "@putch" => {
+        self.output.write_all(&[args[0] as u8])?;
+        0
+      }
+      "@putarray" => {
+        write!(self.output, "{}:", args[0])?;
+        for i in 0..args[0] {
+          let value = self.load(args[1].wrapping_add(i.wrapping_mul(4)))?;
+          write!(self.output, " {}", value)?;
+        }
+        writeln!(self.output)?;
+        0
+      }
+      "@starttime" | "@stoptime" => 0,
+      // `-fsanitize=` 的诊断例程所用
+      "@__sysy_trap_putchar" => {
+        stderr().write_all(&[args[0] as u8])?;
+        0
+      }
+      "@__sysy_trap_exit" => {
+        let message = format!("aborted by -fsanitize (exit code {})", args[0]);
+        return Err(RuntimeError(message).into());
+      }
+      _ => return Err(RuntimeError(format!("call to undefined function {}", name)).into()),
+    })
+  }
+
+  fn step(&mut self, frame: &mut Frame<'a>, inst: Value) -> Result<Step> {
+    let globals = &self.globals;
+    let eval = |v| frame.eval(globals, v);
+    let vd = frame.fd.dfg().value(inst);
+    let result = match vd.kind() {
+      ValueKind::Alloc(_) => self.alloc(pointee_size(vd.ty()))?,
+      ValueKind::Load(load) => self.load(eval(load.src()))?,
+      ValueKind::Store(store) => {
+        let dest = eval(store.dest());
+        match frame.fd.dfg().value(store.value()).kind() {
+          ValueKind::Aggregate(_) | ValueKind::ZeroInit(_) => {
+            let value_of = |v| {
+              let vd = frame.fd.dfg().value(v);
+              (vd.kind().clone(), vd.ty().clone())
+            };
+            let mut words = vec![];
+            flatten(&value_of, store.value(), &mut words);
+            for (i, word) in words.into_iter().enumerate() {
+              self.store(dest.wrapping_add(i as i32 * 4), word)?;
+            }
+          }
+          _ => self.store(dest, eval(store.value()))?,
+        }
+        return Ok(Step::Next);
+      }
+      ValueKind::GetPtr(ptr) => {
+        let offset = eval(ptr.index()).wrapping_mul(pointee_size(vd.ty()) as i32);
+        eval(ptr.src()).wrapping_add(offset)
+      }
+      ValueKind::GetElemPtr(ptr) => {
+        let offset = eval(ptr.index()).wrapping_mul(pointee_size(vd.ty()) as i32);
+        eval(ptr.src()).wrapping_add(offset)
+      }
+      ValueKind::Binary(binary) => {
+        let (l, r) = (eval(binary.lhs()), eval(binary.rhs()));
+        match binary.op() {
+          BinaryOp::NotEq => (l != r) as i32,
+          BinaryOp::Eq => (l == r) as i32,
+          BinaryOp::Gt => (l > r) as i32,
+          BinaryOp::Lt => (l < r) as i32,
+          BinaryOp::Ge => (l >= r) as i32,
+          BinaryOp::Le => (l <= r) as i32,
+          BinaryOp::Add => l.wrapping_add(r),
+          BinaryOp::Sub => l.wrapping_sub(r),
+          BinaryOp::Mul => l.wrapping_mul(r),
+          // 同 RISC-V 的 `div`/`rem`：除数为 0 时不陷入
+          BinaryOp::Div if r == 0 => -1,
+          BinaryOp::Div => l.wrapping_div(r),
+          BinaryOp::Mod if r == 0 => l,
+          BinaryOp::Mod => l.wrapping_rem(r),
+          BinaryOp::And => l & r,
+          BinaryOp::Or => l | r,
+          BinaryOp::Xor => l ^ r,
+          BinaryOp::Shl => l.wrapping_shl(r as u32),
+          BinaryOp::Shr => (l as u32).wrapping_shr(r as u32) as i32,
+          BinaryOp::Sar => l.wrapping_shr(r as u32),
+        }
+      }
+      ValueKind::Call(call) => {
+        let args = call.args().iter().map(|&arg| eval(arg)).collect();
+        return Ok(Step::Call(call.callee(), args));
+      }
+      ValueKind::Branch(branch) => {
+        if eval(branch.cond()) != 0 {
+          frame.jump(globals, branch.true_bb(), branch.true_args());
+        } else {
+          frame.jump(globals, branch.false_bb(), branch.false_args());
+        }
+        return Ok(Step::Next);
+      }
+      ValueKind::Jump(jump) => {
+        frame.jump(globals, jump.target(), jump.args());
+        return Ok(Step::Next);
+      }
+      ValueKind::Return(ret) => return Ok(Step::Return(ret.value().map_or(0, eval))),
+      kind => panic!("unexpected instruction {:?}", kind),
+    };
+    frame.values.insert(inst, result);
+    Ok(Step::Next)
+  }
+
+  fn run(&mut self, main: Function) -> Result<i32> {
+    let mut frames = vec![self.enter(main, vec![])];
+    loop {
+      let frame = frames.last_mut().unwrap();
+      let inst = self.blocks[&frame.bb][frame.pos];
+      frame.pos += 1;
+      match self.step(frame, inst)? {
+        Step::Next => {}
+        Step::Call(func, args) => {
+          if self.ir.func(func).layout().entry_bb().is_some() {
+            if frames.len() >= MAX_CALL_DEPTH {
+              return Err(RuntimeError("call stack overflow".into()).into());
+            }
+            frames.push(self.enter(func, args));
+          } else {
+            let result = self.call_runtime(func, &args)?;
+            frame.values.insert(inst, result);
+          }
+        }
+        Step::Return(value) => {
+          self.sp = frames.pop().unwrap().sp;
+          match frames.last_mut() {
+            None => return Ok(value),
+            Some(caller) => {
+              let call = self.blocks[&caller.bb][caller.pos - 1];
+              caller.values.insert(call, value);
+            }
+          }
+        }
+      }
+    }
+  }
+}
+
+/// 解释执行 `ir` 中的 `main` 函数，返回其返回值
+pub fn run(ir: &Ir) -> Result<i32> {
+  execute(ir, Input::default(), Box::new(stdout()))
+}
+
+/// 以 `input` 为标准输入解释执行，返回输出与 `main` 的返回值（`sysyc test`）
+pub fn run_captured(ir: &Ir, input: Vec<u8>) -> Result<(Vec<u8>, i32)> {
+  let mut output = vec![];
+  let code = execute(ir, Input::new(Some(input)), Box::new(&mut output))?;
+  Ok((output, code))
+}
+
+fn execute<'a>(ir: &'a Ir, input: Input, output: Box<dyn Write + 'a>) -> Result<i32> {
+  let main = ir
+    .func_layout()
+    .iter()
+    .copied()
+    .find(|&f| ir.func(f).name() == "@main");
+  let main = main.ok_or_else(|| RuntimeError("missing main function".into()))?;
+  let mut machine = Machine::new(ir, input, output);
+  let result = machine.run(main);
+  // 出错时也输出已经写出的内容
+  machine.output.flush()?;
+  result
+}
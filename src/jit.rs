@@ -0,0 +1,288 @@
+//! 即时编译执行（`sysyc run --jit`）：把 Koopa IR 翻译为 x86-64 机器码，放在可执行的内存中直接
+//! 调用 `main`，运行性能测试等耗时的程序时比解释执行（[`crate::interpreter`]）快得多。
+//!
+//! 运行时库函数是本模块中的 Rust 函数，输入输出与解释器相同；算术运算的结果与 RISC-V 后端一致。
+//! 与解释器不同，访存不做检查，未初始化的局部变量也不清零。程序在另外的栈（见 [`crate::stack`]）上
+//! 运行，深度递归的程序不会耗尽调用者的栈，栈用尽时报告错误。只支持 x86-64 Linux，机器码由
+//! [`x86`] 直接编码，不依赖代码生成库。
+//!
+//! 库函数 `starttime`、`stoptime` 计时，`run` 结束时同 `libsysy` 在标准错误输出总的用时。
+
+use std::cell::{Cell, RefCell};
+use std::error::Error;
+use std::io::{stderr, stdout, Write};
+use std::time::{Duration, Instant};
+
+use crate::frontend::Ir;
+use crate::interpreter::{Input, RuntimeError};
+use crate::Result;
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod lower;
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod x86;
+
+/// 栈底之上留给运行时库函数的空间；生成的代码用到这里即是栈溢出
+const STACK_RESERVE: usize = 1 << 20;
+/// 输出在内存中累积到这么多字节时写到标准输出
+const OUTPUT_BUFFER: usize = 64 << 10;
+
+/// 与生成的代码共用的状态，代码中直接是这两个字段的地址
+#[derive(Default)]
+struct Control {
+  /// 运行时库函数出错时置为 1
+  failed: Cell<i32>,
+  /// 入口处的栈指针，出错时由此直接返回
+  saved_rsp: Cell<u64>,
+}
+
+/// 运行时库的状态，执行期间放在 [`STATE`] 中
+struct State {
+  input: Input,
+  output: Vec<u8>,
+  /// 输出写到标准输出，而非返回给调用者
+  echo: bool,
+  /// 最近一次 `starttime` 的时刻
+  start: Option<Instant>,
+  /// 各次计时的总和；没有计时为 `None`
+  total: Option<Duration>,
+  error: Option<Box<dyn Error>>,
+  control: Box<Control>,
+  /// 生成的代码中报告的错误信息
+  messages: Vec<String>,
+}
+
+thread_local! {
+  static STATE: RefCell<Option<State>> = const { RefCell::new(None) };
+}
+
+impl State {
+  fn write(&mut self, bytes: &[u8]) -> Result<i32> {
+    self.output.extend_from_slice(bytes);
+    if self.echo && self.output.len() >= OUTPUT_BUFFER {
+      self.flush()?;
+    }
+    Ok(0)
+  }
+
+  fn flush(&mut self) -> Result<()> {
+    if self.echo {
+      stdout().write_all(&self.output)?;
+      stdout().flush()?;
+      self.output.clear();
+    }
+    Ok(())
+  }
+}
+
+/// 运行时库函数的公共部分：出错时记下错误并设置 [`Control::failed`]，生成的代码随即返回
+fn with_state(f: impl FnOnce(&mut State) -> Result<i32>) -> i32 {
+  STATE.with(|state| {
+    let mut state = state.borrow_mut();
+    let state = state.as_mut().expect("no JIT-compiled program is running");
+    f(state).unwrap_or_else(|e| {
+      state.error = Some(e);
+      state.control.failed.set(1);
+      0
+    })
+  })
+}
+
+extern "C" fn getint() -> i32 {
+  with_state(|state| state.input.getint())
+}
+
+extern "C" fn getch() -> i32 {
+  with_state(|state| state.input.getch())
+}
+
+/// # Safety
+///
+/// `array` 须有足够的空间存放读入的整数
+unsafe extern "C" fn getarray(array: *mut i32) -> i32 {
+  with_state(|state| {
+    let n = state.input.getint()?;
+    for i in 0..n.max(0) as usize {
+      let value = state.input.getint()?;
+      array.add(i).write(value);
+    }
+    Ok(n)
+  })
+}
+
+extern "C" fn putint(value: i32) -> i32 {
+  with_state(|state| state.write(value.to_string().as_bytes()))
+}
+
+extern "C" fn putch(c: i32) -> i32 {
+  with_state(|state| state.write(&[c as u8]))
+}
+
+/// # Safety
+///
+/// `array` 须有 `n` 个整数
+unsafe extern "C" fn putarray(n: i32, array: *const i32) -> i32 {
+  with_state(|state| {
+    let mut line = format!("{}:", n);
+    for i in 0..n.max(0) as usize {
+      line += &format!(" {}", array.add(i).read());
+    }
+    line.push('\n');
+    state.write(line.as_bytes())
+  })
+}
+
+extern "C" fn starttime() -> i32 {
+  with_state(|state| {
+    state.start = Some(Instant::now());
+    Ok(0)
+  })
+}
+
+extern "C" fn stoptime() -> i32 {
+  with_state(|state| {
+    let elapsed = state.start.map_or(Duration::ZERO, |start| start.elapsed());
+    state.total = Some(state.total.unwrap_or_default() + elapsed);
+    Ok(0)
+  })
+}
+
+/// `-fsanitize=` 的诊断例程所用
+extern "C" fn trap_putchar(c: i32) -> i32 {
+  with_state(|_| {
+    stderr().write_all(&[c as u8])?;
+    Ok(0)
+  })
+}
+
+extern "C" fn trap_exit(code: i32) -> i32 {
+  with_state(|_| {
+    let message = format!("aborted by -fsanitize (exit code {})", code);
+    Err(RuntimeError(message).into())
+  })
+}
+
+/// 生成的代码报告运行时错误，`message` 是 [`State::messages`] 中的序号
+extern "C" fn fail(message: usize) -> i32 {
+  with_state(|state| Err(RuntimeError(state.messages[message].clone()).into()))
+}
+
+/// 运行时库函数的地址
+fn runtime(name: &str) -> Option<u64> {
+  let addr = match name {
+    "@getint" => getint as *const (),
+    "@getch" => getch as *const (),
+    "@getarray" => getarray as *const (),
+    "@putint" => putint as *const (),
+    "@putch" => putch as *const (),
+    "@putarray" => putarray as *const (),
+    "@starttime" => starttime as *const (),
+    "@stoptime" => stoptime as *const (),
+    "@__sysy_trap_putchar" => trap_putchar as *const (),
+    "@__sysy_trap_exit" => trap_exit as *const (),
+    _ => return None,
+  };
+  Some(addr as u64)
+}
+
+/// 即时编译并执行 `ir` 中的 `main` 函数，返回其返回值
+pub fn run(ir: &Ir) -> Result<i32> {
+  execute(ir, Input::new(None), true).map(|(_, code)| code)
+}
+
+/// 以 `input` 为标准输入执行，返回输出与 `main` 的返回值（`sysyc test --jit`）
+pub fn run_captured(ir: &Ir, input: Vec<u8>) -> Result<(Vec<u8>, i32)> {
+  execute(ir, Input::new(Some(input)), false)
+}
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+fn execute(_: &Ir, _: Input, _: bool) -> Result<(Vec<u8>, i32)> {
+  Err(RuntimeError("--jit supports only x86-64 Linux".into()).into())
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+fn execute(ir: &Ir, input: Input, echo: bool) -> Result<(Vec<u8>, i32)> {
+  use std::collections::HashMap;
+
+  use koopa::ir::ValueKind;
+
+  use crate::mman::{Mapping, PROT_EXEC, PROT_READ};
+
+  /// 代码所在的内存按页分配
+  const PAGE_SIZE: usize = 4096;
+
+  let main = ir
+    .func_layout()
+    .iter()
+    .copied()
+    .find(|&f| ir.func(f).name() == "@main");
+  let main = main.ok_or_else(|| RuntimeError("missing main function".into()))?;
+  crate::stack::run(|| {
+    let bottom = crate::stack::bottom();
+    let bottom = bottom.ok_or_else(|| RuntimeError("cannot allocate the stack".into()))?;
+
+    // 全局变量依次放在一段内存中
+    let mut memory = vec![];
+    let mut offsets = vec![];
+    for &v in ir.inst_layout() {
+      if let ValueKind::GlobalAlloc(alloc) = ir.borrow_value(v).kind() {
+        offsets.push((v, memory.len()));
+        lower::flatten(ir, None, alloc.init(), &mut memory);
+      }
+    }
+    let base = memory.as_mut_ptr() as u64;
+    let globals: HashMap<_, _> = offsets
+      .into_iter()
+      .map(|(v, offset)| (v, base + offset as u64 * 4))
+      .collect();
+
+    let control = Box::<Control>::default();
+    let env = lower::Env {
+      ir,
+      globals,
+      failed: control.failed.as_ptr() as u64,
+      saved_rsp: control.saved_rsp.as_ptr() as u64,
+      stack_limit: (bottom + STACK_RESERVE) as u64,
+      runtime,
+      fail: fail as *const () as u64,
+    };
+    let compiled = lower::compile(&env, main);
+    let len = compiled.bytes.len().div_ceil(PAGE_SIZE) * PAGE_SIZE;
+    let mapping = Mapping::new(len).ok_or_else(|| RuntimeError("cannot map memory".into()))?;
+    let bytes = &compiled.bytes;
+    // SAFETY: 映射至少有 `bytes.len()` 字节，此后只执行、不再写入
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), mapping.as_ptr(), bytes.len()) };
+    if !mapping.protect(len, PROT_READ | PROT_EXEC) {
+      return Err(RuntimeError("cannot make the generated code executable".into()).into());
+    }
+
+    let state = State {
+      input,
+      output: vec![],
+      echo,
+      start: None,
+      total: None,
+      error: None,
+      control,
+      messages: compiled.messages,
+    };
+    STATE.with(|cell| *cell.borrow_mut() = Some(state));
+    // SAFETY: 机器码的开头是 `lower::compile` 生成的入口。其中的全局变量、出错信息与
+    // `Control` 的地址在执行期间一直有效
+    let code = unsafe {
+      let entry: extern "C" fn() -> i32 = std::mem::transmute(mapping.as_ptr());
+      entry()
+    };
+    let mut state = STATE.with(|cell| cell.borrow_mut().take()).unwrap();
+    // 出错时也输出已经写出的内容
+    state.flush()?;
+    if let (true, Some(total)) = (echo, state.total) {
+      let (s, us) = (total.as_secs(), total.subsec_micros());
+      eprintln!("TOTAL: {}H-{}M-{}S-{}us", s / 3600, s / 60 % 60, s % 60, us);
+    }
+    match state.error {
+      Some(e) => Err(e),
+      None => Ok((state.output, code)),
+    }
+  })
+}
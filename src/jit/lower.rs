@@ -0,0 +1,523 @@
+//! 把 Koopa IR 逐条翻译为 x86-64 机器码。不做寄存器分配：每个有结果的指令、函数参数与基本块
+//! 参数各占栈帧中的 8 字节，运算时取到 `rax`、`rcx` 中，结果写回；局部数组（`alloc`）也在栈帧中。
+//! 函数之间按 System V 调用约定调用，运行时库函数因此可以直接是 Rust 的 `extern "C"` 函数。
+//!
+//! 机器码的开头是入口（见 [`compile`]），其后依次是各函数。
+
+use std::collections::HashMap;
+
+use koopa::ir::{BasicBlock, BinaryOp, Function, FunctionData, Type, TypeKind, Value, ValueKind};
+
+use super::x86::{Alu, Asm, Cond, Label, Reg, Shift, ARG_REGS};
+use crate::frontend::Ir;
+
+/// 连续这么多个字以上的零改用 `rep stosd` 写入
+const MIN_ZERO_RUN: usize = 8;
+
+/// 生成的代码用到的地址
+pub struct Env<'a> {
+  pub ir: &'a Ir,
+  /// 全局变量的地址
+  pub globals: HashMap<Value, u64>,
+  /// 运行时库函数出错时置为非零的 `i32`，每次调用之后检查
+  pub failed: u64,
+  /// 入口处保存的 `rsp`，出错时由此直接返回
+  pub saved_rsp: u64,
+  /// 栈指针低于这一地址即是栈溢出
+  pub stack_limit: u64,
+  /// 运行时库函数的名字（如 `@getint`）到其地址
+  pub runtime: fn(&str) -> Option<u64>,
+  /// `extern "C" fn(usize)`：报告运行时错误，参数是 [`Code::messages`] 中错误信息的序号
+  pub fail: u64,
+}
+
+/// 生成的机器码与其中报告的错误信息
+pub struct Code {
+  pub bytes: Vec<u8>,
+  pub messages: Vec<String>,
+}
+
+/// JIT 代码中的大小：`i32` 占 4 字节，指针占 8 字节
+fn size_of(ty: &Type) -> usize {
+  match ty.kind() {
+    TypeKind::Int32 => 4,
+    TypeKind::Pointer(_) => 8,
+    TypeKind::Array(base, len) => size_of(base) * len,
+    TypeKind::Unit | TypeKind::Function(..) => 0,
+  }
+}
+
+fn pointee_size(ty: &Type) -> usize {
+  match ty.kind() {
+    TypeKind::Pointer(base) => size_of(base),
+    _ => panic!("expected pointer type, found {}", ty),
+  }
+}
+
+fn is_pointer(ty: &Type) -> bool {
+  matches!(ty.kind(), TypeKind::Pointer(_))
+}
+
+/// 取得值的方式
+#[derive(Clone, Copy)]
+enum Operand {
+  Imm(i32),
+  /// 栈帧中 `[rbp + disp]` 处的值
+  Slot(i32),
+  /// 地址 `rbp + disp`，即局部变量
+  Local(i32),
+  /// 全局变量的地址
+  Global(u64),
+}
+
+/// 把初始值展开为字，`value` 在 `dfg` 中（局部）或是全局的值
+pub fn flatten(ir: &Ir, fd: Option<&FunctionData>, value: Value, words: &mut Vec<i32>) {
+  let (kind, ty) = match fd {
+    Some(fd) => {
+      let vd = fd.dfg().value(value);
+      (vd.kind().clone(), vd.ty().clone())
+    }
+    None => {
+      let vd = ir.borrow_value(value);
+      (vd.kind().clone(), vd.ty().clone())
+    }
+  };
+  match kind {
+    ValueKind::Integer(i) => words.push(i.value()),
+    ValueKind::Aggregate(agg) => {
+      for &elem in agg.elems() {
+        flatten(ir, fd, elem, words);
+      }
+    }
+    _ => words.extend(std::iter::repeat_n(0, size_of(&ty) / 4)),
+  }
+}
+
+struct Lowering<'a> {
+  env: &'a Env<'a>,
+  asm: Asm,
+  messages: Vec<String>,
+  funcs: HashMap<Function, Label>,
+  /// 出错后恢复入口处的栈指针并返回
+  abort: Label,
+  overflow: Label,
+}
+
+/// 生成整个程序。开头的入口是 `extern "C" fn() -> i32`：保存栈指针后调用 `main`，返回其返回值
+pub fn compile(env: &Env, main: Function) -> Code {
+  let mut asm = Asm::default();
+  let mut funcs = HashMap::new();
+  for &f in env.ir.func_layout() {
+    if env.ir.func(f).layout().entry_bb().is_some() {
+      funcs.insert(f, asm.new_label());
+    }
+  }
+  let abort = asm.new_label();
+  let overflow = asm.new_label();
+  let mut lowering = Lowering {
+    env,
+    asm,
+    messages: vec![],
+    funcs,
+    abort,
+    overflow,
+  };
+  lowering.entry(main);
+  for &f in env.ir.func_layout() {
+    if let Some(&label) = lowering.funcs.get(&f) {
+      lowering.asm.bind(label);
+      lowering.function(env.ir.func(f));
+    }
+  }
+  Code {
+    bytes: lowering.asm.finish(),
+    messages: lowering.messages,
+  }
+}
+
+impl Lowering<'_> {
+  /// 入口，以及出错与栈溢出时返回的路径
+  fn entry(&mut self, main: Function) {
+    const SAVED: [Reg; 6] = [Reg::Rbx, Reg::Rbp, Reg::R12, Reg::R13, Reg::R14, Reg::R15];
+    let asm = &mut self.asm;
+    for reg in SAVED {
+      asm.push(reg);
+    }
+    // 连同返回地址共 7 个字，再减 8 使调用时栈按 16 字节对齐
+    asm.alu_imm(true, Alu::Sub, Reg::Rsp, 8);
+    asm.mov_imm64(Reg::Rax, self.env.saved_rsp);
+    asm.store(true, Reg::Rax, 0, Reg::Rsp);
+    asm.call(self.funcs[&main]);
+    let exit = asm.new_label();
+    asm.bind(exit);
+    asm.alu_imm(true, Alu::Add, Reg::Rsp, 8);
+    for reg in SAVED.into_iter().rev() {
+      asm.pop(reg);
+    }
+    asm.ret();
+
+    asm.bind(self.abort);
+    asm.mov_imm64(Reg::Rcx, self.env.saved_rsp);
+    asm.load(true, Reg::Rsp, Reg::Rcx, 0);
+    asm.jmp(exit);
+
+    // 栈指针可能已越过栈底，先恢复再调用运行时库报告错误
+    asm.bind(self.overflow);
+    asm.mov_imm64(Reg::Rcx, self.env.saved_rsp);
+    asm.load(true, Reg::Rsp, Reg::Rcx, 0);
+    self.fail("stack overflow".into());
+    self.asm.jmp(exit);
+  }
+
+  /// 调用 `env.fail` 报告运行时错误
+  fn fail(&mut self, message: String) {
+    let asm = &mut self.asm;
+    asm.mov_imm(Reg::Rdi, self.messages.len() as i32);
+    asm.mov_imm64(Reg::Rax, self.env.fail);
+    asm.call_reg(Reg::Rax);
+    self.messages.push(message);
+  }
+
+  fn function(&mut self, fd: &FunctionData) {
+    let mut frame = Frame::new(fd);
+    let asm = &mut self.asm;
+    asm.push(Reg::Rbp);
+    asm.mov(true, Reg::Rbp, Reg::Rsp);
+    if frame.size > 0 {
+      asm.alu_imm(true, Alu::Sub, Reg::Rsp, frame.size as i32);
+    }
+    asm.mov_imm64(Reg::Rax, self.env.stack_limit);
+    asm.alu(true, Alu::Cmp, Reg::Rsp, Reg::Rax);
+    asm.jcc(Cond::B, self.overflow);
+    for (&param, &reg) in fd.params().iter().zip(&ARG_REGS) {
+      asm.store(true, Reg::Rbp, frame.slots[&param], reg);
+    }
+
+    for &bb in fd.layout().bbs().keys() {
+      let label = asm.new_label();
+      frame.blocks.insert(bb, label);
+    }
+    for (&bb, node) in fd.layout().bbs() {
+      self.asm.bind(frame.blocks[&bb]);
+      for &inst in node.insts().keys() {
+        self.inst(fd, &frame, inst);
+      }
+    }
+  }
+
+  fn operand(&self, fd: &FunctionData, frame: &Frame, value: Value) -> Operand {
+    if value.is_global() {
+      return Operand::Global(self.env.globals[&value]);
+    }
+    match fd.dfg().value(value).kind() {
+      ValueKind::Integer(i) => Operand::Imm(i.value()),
+      ValueKind::ZeroInit(_) | ValueKind::Undef(_) => Operand::Imm(0),
+      ValueKind::Alloc(_) => Operand::Local(frame.slots[&value]),
+      _ => Operand::Slot(frame.slots[&value]),
+    }
+  }
+
+  /// 把值取到 `reg` 中；指针取 8 字节，整数至少低 32 位有效
+  fn load(&mut self, fd: &FunctionData, frame: &Frame, reg: Reg, value: Value) {
+    match self.operand(fd, frame, value) {
+      Operand::Imm(imm) => self.asm.mov_imm(reg, imm),
+      Operand::Slot(disp) => self.asm.load(true, reg, Reg::Rbp, disp),
+      Operand::Local(disp) => self.asm.lea(reg, Reg::Rbp, disp),
+      Operand::Global(addr) => self.asm.mov_imm64(reg, addr),
+    }
+  }
+
+  /// 把指针 `ptr` 所指的地址表示为 `[基址 + disp]`，基址不是 `rbp` 时取到 `reg` 中
+  fn address(&mut self, fd: &FunctionData, frame: &Frame, reg: Reg, ptr: Value) -> (Reg, i32) {
+    match self.operand(fd, frame, ptr) {
+      Operand::Local(disp) => (Reg::Rbp, disp),
+      _ => {
+        self.load(fd, frame, reg, ptr);
+        (reg, 0)
+      }
+    }
+  }
+
+  fn inst(&mut self, fd: &FunctionData, frame: &Frame, inst: Value) {
+    let vd = fd.dfg().value(inst);
+    match vd.kind() {
+      ValueKind::Alloc(_) => return,
+      ValueKind::Load(load) => {
+        let (base, disp) = self.address(fd, frame, Reg::Rcx, load.src());
+        self.asm.load(is_pointer(vd.ty()), Reg::Rax, base, disp);
+      }
+      ValueKind::Store(store) => {
+        let value = fd.dfg().value(store.value());
+        if let ValueKind::Aggregate(_) | ValueKind::ZeroInit(_) = value.kind() {
+          let mut words = vec![];
+          flatten(self.env.ir, Some(fd), store.value(), &mut words);
+          let (base, disp) = self.address(fd, frame, Reg::Rdx, store.dest());
+          self.fill(base, disp, &words);
+        } else {
+          let wide = is_pointer(value.ty());
+          self.load(fd, frame, Reg::Rax, store.value());
+          let (base, disp) = self.address(fd, frame, Reg::Rcx, store.dest());
+          self.asm.store(wide, base, disp, Reg::Rax);
+        }
+        return;
+      }
+      ValueKind::GetPtr(ptr) => self.offset(fd, frame, vd.ty(), ptr.src(), ptr.index()),
+      ValueKind::GetElemPtr(ptr) => self.offset(fd, frame, vd.ty(), ptr.src(), ptr.index()),
+      ValueKind::Binary(binary) => {
+        self.load(fd, frame, Reg::Rax, binary.lhs());
+        let rhs = self.operand(fd, frame, binary.rhs());
+        self.binary(binary.op(), rhs, fd, frame, binary.rhs());
+      }
+      ValueKind::Call(call) => {
+        self.call(fd, frame, call.callee(), call.args());
+        if vd.ty().is_unit() {
+          return;
+        }
+      }
+      ValueKind::Branch(branch) => {
+        self.load(fd, frame, Reg::Rax, branch.cond());
+        self.asm.test(Reg::Rax, Reg::Rax);
+        let otherwise = self.asm.new_label();
+        self.asm.jcc(Cond::E, otherwise);
+        self.jump(fd, frame, branch.true_bb(), branch.true_args());
+        self.asm.bind(otherwise);
+        self.jump(fd, frame, branch.false_bb(), branch.false_args());
+        return;
+      }
+      ValueKind::Jump(jump) => {
+        self.jump(fd, frame, jump.target(), jump.args());
+        return;
+      }
+      ValueKind::Return(ret) => {
+        if let Some(value) = ret.value() {
+          self.load(fd, frame, Reg::Rax, value);
+        }
+        self.asm.mov(true, Reg::Rsp, Reg::Rbp);
+        self.asm.pop(Reg::Rbp);
+        self.asm.ret();
+        return;
+      }
+      kind => panic!("unexpected instruction {:?}", kind),
+    }
+    self.asm.store(true, Reg::Rbp, frame.slots[&inst], Reg::Rax);
+  }
+
+  /// `rax = src + index * 所指对象的大小`
+  fn offset(&mut self, fd: &FunctionData, frame: &Frame, ty: &Type, src: Value, index: Value) {
+    let size = pointee_size(ty) as i64;
+    self.load(fd, frame, Reg::Rax, src);
+    match self.operand(fd, frame, index) {
+      Operand::Imm(i) => match i32::try_from(i as i64 * size) {
+        Ok(0) => {}
+        Ok(offset) => self.asm.alu_imm(true, Alu::Add, Reg::Rax, offset),
+        Err(_) => {
+          self.asm.mov_imm64(Reg::Rcx, (i as i64 * size) as u64);
+          self.asm.alu(true, Alu::Add, Reg::Rax, Reg::Rcx);
+        }
+      },
+      _ => {
+        self.load(fd, frame, Reg::Rcx, index);
+        self.asm.movsxd(Reg::Rcx, Reg::Rcx);
+        self.asm.imul_imm(true, Reg::Rcx, Reg::Rcx, size as i32);
+        self.asm.alu(true, Alu::Add, Reg::Rax, Reg::Rcx);
+      }
+    }
+  }
+
+  /// `eax = eax op rhs`，结果与 RISC-V 后端一致：除以零的商为 -1、余数为被除数，
+  /// `-2147483648 / -1` 的商为被除数、余数为 0
+  fn binary(&mut self, op: BinaryOp, rhs: Operand, fd: &FunctionData, frame: &Frame, value: Value) {
+    let cond = match op {
+      BinaryOp::Eq => Some(Cond::E),
+      BinaryOp::NotEq => Some(Cond::Ne),
+      BinaryOp::Lt => Some(Cond::L),
+      BinaryOp::Gt => Some(Cond::G),
+      BinaryOp::Le => Some(Cond::Le),
+      BinaryOp::Ge => Some(Cond::Ge),
+      _ => None,
+    };
+    let alu = match op {
+      BinaryOp::Add => Some(Alu::Add),
+      BinaryOp::Sub => Some(Alu::Sub),
+      BinaryOp::And => Some(Alu::And),
+      BinaryOp::Or => Some(Alu::Or),
+      BinaryOp::Xor => Some(Alu::Xor),
+      _ if cond.is_some() => Some(Alu::Cmp),
+      _ => None,
+    };
+    match (op, rhs) {
+      (_, Operand::Imm(imm)) if alu.is_some() => {
+        self.asm.alu_imm(false, alu.unwrap(), Reg::Rax, imm)
+      }
+      (BinaryOp::Mul, Operand::Imm(imm)) => self.asm.imul_imm(false, Reg::Rax, Reg::Rax, imm),
+      _ => {
+        self.load(fd, frame, Reg::Rcx, value);
+        match op {
+          _ if alu.is_some() => self.asm.alu(false, alu.unwrap(), Reg::Rax, Reg::Rcx),
+          BinaryOp::Mul => self.asm.imul(false, Reg::Rax, Reg::Rcx),
+          BinaryOp::Shl => self.asm.shift(Shift::Shl, Reg::Rax),
+          BinaryOp::Shr => self.asm.shift(Shift::Shr, Reg::Rax),
+          BinaryOp::Sar => self.asm.shift(Shift::Sar, Reg::Rax),
+          BinaryOp::Div | BinaryOp::Mod => self.divide(op == BinaryOp::Mod, rhs),
+          _ => unreachable!(),
+        }
+      }
+    }
+    if let Some(cond) = cond {
+      self.asm.set(cond);
+    }
+  }
+
+  /// `eax` 除以 `ecx`，`rhs` 是除数；余数时结果也在 `eax` 中
+  fn divide(&mut self, rem: bool, rhs: Operand) {
+    let asm = &mut self.asm;
+    let done = asm.new_label();
+    let (zero, minus_one) = (asm.new_label(), asm.new_label());
+    let checked = !matches!(rhs, Operand::Imm(imm) if imm != 0 && imm != -1);
+    if checked {
+      asm.test(Reg::Rcx, Reg::Rcx);
+      asm.jcc(Cond::E, zero);
+      asm.alu_imm(false, Alu::Cmp, Reg::Rcx, -1);
+      asm.jcc(Cond::E, minus_one);
+    }
+    asm.cdq_idiv(Reg::Rcx);
+    if rem {
+      asm.mov(false, Reg::Rax, Reg::Rdx);
+    }
+    if checked {
+      asm.jmp(done);
+      asm.bind(minus_one);
+      match rem {
+        true => asm.mov_imm(Reg::Rax, 0),
+        false => asm.neg(Reg::Rax),
+      }
+      asm.jmp(done);
+      asm.bind(zero);
+      if !rem {
+        asm.mov_imm(Reg::Rax, -1);
+      }
+      asm.bind(done);
+    }
+  }
+
+  /// 把 `words` 写到 `[base + disp]` 起的内存中
+  fn fill(&mut self, base: Reg, disp: i32, words: &[i32]) {
+    let asm = &mut self.asm;
+    let mut i = 0;
+    while i < words.len() {
+      let zeros = words[i..].iter().take_while(|&&w| w == 0).count();
+      if zeros >= MIN_ZERO_RUN {
+        asm.lea(Reg::Rdi, base, disp + i as i32 * 4);
+        asm.mov_imm(Reg::Rcx, zeros as i32);
+        asm.mov_imm(Reg::Rax, 0);
+        asm.rep_stosd();
+        i += zeros;
+      } else {
+        asm.store_imm(base, disp + i as i32 * 4, words[i]);
+        i += 1;
+      }
+    }
+  }
+
+  /// 跳转到 `target`，先把实参写入其基本块参数。各实参先全部压栈再依次弹出，
+  /// 实参中引用同一块的参数时也不会被提前覆盖
+  fn jump(&mut self, fd: &FunctionData, frame: &Frame, target: BasicBlock, args: &[Value]) {
+    for &arg in args {
+      self.load(fd, frame, Reg::Rax, arg);
+      self.asm.push(Reg::Rax);
+    }
+    for &param in fd.dfg().bb(target).params().iter().rev() {
+      self.asm.pop(Reg::Rax);
+      self
+        .asm
+        .store(true, Reg::Rbp, frame.slots[&param], Reg::Rax);
+    }
+    self.asm.jmp(frame.blocks[&target]);
+  }
+
+  /// 调用 `callee`，返回值在 `rax` 中。运行时库函数调用之后检查是否出错
+  fn call(&mut self, fd: &FunctionData, frame: &Frame, callee: Function, args: &[Value]) {
+    let stack_args = args.len().saturating_sub(ARG_REGS.len());
+    // 调用时栈按 16 字节对齐
+    let pad = stack_args % 2 * 8;
+    if pad > 0 {
+      self.asm.alu_imm(true, Alu::Sub, Reg::Rsp, pad as i32);
+    }
+    for &arg in args[ARG_REGS.len().min(args.len())..].iter().rev() {
+      self.load(fd, frame, Reg::Rax, arg);
+      self.asm.push(Reg::Rax);
+    }
+    for (&arg, &reg) in args.iter().zip(&ARG_REGS) {
+      self.load(fd, frame, reg, arg);
+    }
+    let data = self.env.ir.func(callee);
+    if let Some(&label) = self.funcs.get(&callee) {
+      self.asm.call(label);
+    } else if self.env.ir.annotations.inline_asm.contains_key(&callee) {
+      self.fail("inline assembly cannot be JIT-compiled".into());
+    } else if let Some(addr) = (self.env.runtime)(data.name()) {
+      self.asm.mov_imm64(Reg::Rax, addr);
+      self.asm.call_reg(Reg::Rax);
+    } else {
+      self.fail(format!("call to undefined function {}", data.name()));
+    }
+    if !self.funcs.contains_key(&callee) {
+      let asm = &mut self.asm;
+      asm.mov_imm64(Reg::Rcx, self.env.failed);
+      asm.load(false, Reg::Rcx, Reg::Rcx, 0);
+      asm.test(Reg::Rcx, Reg::Rcx);
+      asm.jcc(Cond::Ne, self.abort);
+    }
+    let popped = stack_args * 8 + pad;
+    if popped > 0 {
+      self.asm.alu_imm(true, Alu::Add, Reg::Rsp, popped as i32);
+    }
+  }
+}
+
+/// 一个函数的栈帧：各值在 `[rbp + disp]` 处，局部数组从 `rbp + disp` 开始
+struct Frame {
+  slots: HashMap<Value, i32>,
+  blocks: HashMap<BasicBlock, Label>,
+  /// `rbp` 之下的部分的大小，16 字节对齐
+  size: usize,
+}
+
+impl Frame {
+  fn new(fd: &FunctionData) -> Self {
+    let mut slots = HashMap::new();
+    let mut size = 0;
+    // 在已分配的部分之下再分配 `bytes` 字节，按 8 字节对齐
+    let alloc = |size: &mut usize, bytes: usize| {
+      *size += bytes.div_ceil(8) * 8;
+      -(*size as i32)
+    };
+    for (i, &param) in fd.params().iter().enumerate() {
+      // 前六个参数由寄存器传递，其余的在调用者压栈的位置：返回地址与 rbp 之上
+      let disp = match i.checked_sub(ARG_REGS.len()) {
+        None => alloc(&mut size, 8),
+        Some(i) => 16 + i as i32 * 8,
+      };
+      slots.insert(param, disp);
+    }
+    for (&bb, node) in fd.layout().bbs() {
+      for &param in fd.dfg().bb(bb).params() {
+        slots.insert(param, alloc(&mut size, 8));
+      }
+      for &inst in node.insts().keys() {
+        let vd = fd.dfg().value(inst);
+        let bytes = match vd.kind() {
+          ValueKind::Alloc(_) => pointee_size(vd.ty()),
+          _ if vd.ty().is_unit() => continue,
+          _ => 8,
+        };
+        slots.insert(inst, alloc(&mut size, bytes));
+      }
+    }
+    Self {
+      slots,
+      blocks: HashMap::new(),
+      size: size.div_ceil(16) * 16,
+    }
+  }
+}
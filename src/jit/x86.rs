@@ -0,0 +1,254 @@
+//! x86-64 机器码的编码：只有代码生成用到的指令，访存的地址都是 `[基址 + disp32]`。
+//! 跳转与调用先指向标签，全部生成之后由 [`Asm::finish`] 回填相对偏移。
+
+/// 通用寄存器，值即编码中的编号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reg {
+  Rax = 0,
+  Rcx = 1,
+  Rdx = 2,
+  Rbx = 3,
+  Rsp = 4,
+  Rbp = 5,
+  Rsi = 6,
+  Rdi = 7,
+  R8 = 8,
+  R9 = 9,
+  R12 = 12,
+  R13 = 13,
+  R14 = 14,
+  R15 = 15,
+}
+
+/// System V 调用约定中依次传递前六个参数的寄存器
+pub const ARG_REGS: [Reg; 6] = [Reg::Rdi, Reg::Rsi, Reg::Rdx, Reg::Rcx, Reg::R8, Reg::R9];
+
+/// 条件码，值即 `jcc`/`setcc` 编码的低四位
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cond {
+  B = 0x2,
+  E = 0x4,
+  Ne = 0x5,
+  L = 0xc,
+  Ge = 0xd,
+  Le = 0xe,
+  G = 0xf,
+}
+
+/// 以寄存器与寄存器或立即数为操作数的算术运算，值即 `81 /n` 中的 `n`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alu {
+  Add = 0,
+  Or = 1,
+  And = 4,
+  Sub = 5,
+  Xor = 6,
+  Cmp = 7,
+}
+
+/// 移位，值即 `d3 /n` 中的 `n`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shift {
+  Shl = 4,
+  Shr = 5,
+  Sar = 7,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label(usize);
+
+#[derive(Default)]
+pub struct Asm {
+  pub code: Vec<u8>,
+  /// 各标签的位置，尚未放置的为 `None`
+  labels: Vec<Option<usize>>,
+  /// 待回填的 rel32 的位置与目标
+  fixups: Vec<(usize, Label)>,
+}
+
+impl Asm {
+  pub fn new_label(&mut self) -> Label {
+    self.labels.push(None);
+    Label(self.labels.len() - 1)
+  }
+
+  /// 把标签放在当前位置
+  pub fn bind(&mut self, label: Label) {
+    self.labels[label.0] = Some(self.code.len());
+  }
+
+  /// 回填所有跳转与调用，返回机器码
+  pub fn finish(mut self) -> Vec<u8> {
+    for &(pos, label) in &self.fixups {
+      let target = self.labels[label.0].expect("unbound label");
+      let rel = target as i64 - (pos as i64 + 4);
+      self.code[pos..pos + 4].copy_from_slice(&(rel as i32).to_le_bytes());
+    }
+    self.code
+  }
+
+  fn byte(&mut self, b: u8) {
+    self.code.push(b);
+  }
+
+  fn imm32(&mut self, imm: i32) {
+    self.code.extend_from_slice(&imm.to_le_bytes());
+  }
+
+  /// REX 前缀；不需要时不输出
+  fn rex(&mut self, w: bool, reg: u8, base: u8) {
+    let rex = 0x40 | (w as u8) << 3 | (reg >> 3) << 2 | base >> 3;
+    if rex != 0x40 {
+      self.byte(rex);
+    }
+  }
+
+  /// 操作数为两个寄存器的指令：`reg` 是 ModRM 的 reg 字段（或扩展的操作码），`rm` 是另一个
+  fn op_rr(&mut self, w: bool, opcode: &[u8], reg: u8, rm: Reg) {
+    self.rex(w, reg, rm as u8);
+    self.code.extend_from_slice(opcode);
+    self.byte(0xc0 | (reg & 7) << 3 | rm as u8 & 7);
+  }
+
+  /// 操作数为寄存器与内存 `[base + disp]` 的指令
+  fn op_rm(&mut self, w: bool, opcode: &[u8], reg: u8, base: Reg, disp: i32) {
+    self.rex(w, reg, base as u8);
+    self.code.extend_from_slice(opcode);
+    self.byte(0x80 | (reg & 7) << 3 | base as u8 & 7);
+    // rsp 与 r12 作基址时须有 SIB
+    if base as u8 & 7 == 4 {
+      self.byte(0x24);
+    }
+    self.imm32(disp);
+  }
+
+  /// `dst = [base + disp]`，`w` 时读 8 字节，否则读 4 字节并零扩展
+  pub fn load(&mut self, w: bool, dst: Reg, base: Reg, disp: i32) {
+    self.op_rm(w, &[0x8b], dst as u8, base, disp);
+  }
+
+  /// `[base + disp] = src`
+  pub fn store(&mut self, w: bool, base: Reg, disp: i32, src: Reg) {
+    self.op_rm(w, &[0x89], src as u8, base, disp);
+  }
+
+  /// `[base + disp] = imm` 写 4 字节
+  pub fn store_imm(&mut self, base: Reg, disp: i32, imm: i32) {
+    self.op_rm(false, &[0xc7], 0, base, disp);
+    self.imm32(imm);
+  }
+
+  pub fn lea(&mut self, dst: Reg, base: Reg, disp: i32) {
+    self.op_rm(true, &[0x8d], dst as u8, base, disp);
+  }
+
+  pub fn mov(&mut self, w: bool, dst: Reg, src: Reg) {
+    self.op_rr(w, &[0x89], src as u8, dst);
+  }
+
+  /// 32 位的 `dst = src` 并符号扩展到 64 位
+  pub fn movsxd(&mut self, dst: Reg, src: Reg) {
+    self.op_rr(true, &[0x63], dst as u8, src);
+  }
+
+  /// `dst = imm`，高 32 位清零
+  pub fn mov_imm(&mut self, dst: Reg, imm: i32) {
+    self.rex(false, 0, dst as u8);
+    self.byte(0xb8 | dst as u8 & 7);
+    self.imm32(imm);
+  }
+
+  pub fn mov_imm64(&mut self, dst: Reg, imm: u64) {
+    self.rex(true, 0, dst as u8);
+    self.byte(0xb8 | dst as u8 & 7);
+    self.code.extend_from_slice(&imm.to_le_bytes());
+  }
+
+  pub fn alu(&mut self, w: bool, op: Alu, dst: Reg, src: Reg) {
+    self.op_rr(w, &[(op as u8) << 3 | 1], src as u8, dst);
+  }
+
+  pub fn alu_imm(&mut self, w: bool, op: Alu, dst: Reg, imm: i32) {
+    self.op_rr(w, &[0x81], op as u8, dst);
+    self.imm32(imm);
+  }
+
+  pub fn imul(&mut self, w: bool, dst: Reg, src: Reg) {
+    self.op_rr(w, &[0x0f, 0xaf], dst as u8, src);
+  }
+
+  pub fn imul_imm(&mut self, w: bool, dst: Reg, src: Reg, imm: i32) {
+    self.op_rr(w, &[0x69], dst as u8, src);
+    self.imm32(imm);
+  }
+
+  /// 按 `cl` 移位
+  pub fn shift(&mut self, op: Shift, dst: Reg) {
+    self.op_rr(false, &[0xd3], op as u8, dst);
+  }
+
+  pub fn neg(&mut self, dst: Reg) {
+    self.op_rr(false, &[0xf7], 3, dst);
+  }
+
+  /// `edx:eax` 除以 `src`，商在 `eax`、余数在 `edx`
+  pub fn cdq_idiv(&mut self, src: Reg) {
+    self.byte(0x99);
+    self.op_rr(false, &[0xf7], 7, src);
+  }
+
+  /// `eax = cond ? 1 : 0`
+  pub fn set(&mut self, cond: Cond) {
+    self
+      .code
+      .extend_from_slice(&[0x0f, 0x90 | cond as u8, 0xc0]);
+    self.code.extend_from_slice(&[0x0f, 0xb6, 0xc0]);
+  }
+
+  pub fn test(&mut self, a: Reg, b: Reg) {
+    self.op_rr(false, &[0x85], b as u8, a);
+  }
+
+  fn rel32(&mut self, label: Label) {
+    self.fixups.push((self.code.len(), label));
+    self.imm32(0);
+  }
+
+  pub fn jmp(&mut self, label: Label) {
+    self.byte(0xe9);
+    self.rel32(label);
+  }
+
+  pub fn jcc(&mut self, cond: Cond, label: Label) {
+    self.code.extend_from_slice(&[0x0f, 0x80 | cond as u8]);
+    self.rel32(label);
+  }
+
+  pub fn call(&mut self, label: Label) {
+    self.byte(0xe8);
+    self.rel32(label);
+  }
+
+  pub fn call_reg(&mut self, target: Reg) {
+    self.op_rr(false, &[0xff], 2, target);
+  }
+
+  pub fn push(&mut self, reg: Reg) {
+    self.rex(false, 0, reg as u8);
+    self.byte(0x50 | reg as u8 & 7);
+  }
+
+  pub fn pop(&mut self, reg: Reg) {
+    self.rex(false, 0, reg as u8);
+    self.byte(0x58 | reg as u8 & 7);
+  }
+
+  pub fn ret(&mut self) {
+    self.byte(0xc3);
+  }
+
+  /// 把 `[rdi]` 起的 `ecx` 个字写为 `eax`
+  pub fn rep_stosd(&mut self) {
+    self.code.extend_from_slice(&[0xf3, 0xab]);
+  }
+}
@@ -0,0 +1,209 @@
+//! SysY 编译器的库接口：不经过文件系统、不输出任何内容，直接从源代码得到 Koopa IR、RISC-V 汇编或目标文件。
+//!
+//! ```no_run
+//! let options = sysyc::CompileOptions::default();
+//! let ir = sysyc::compile_to_koopa("int main() { return 0; }", &options)?;
+//! let asm = sysyc::compile_to_riscv("int main() { return 0; }", &options)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! 每次编译的状态（符号表、随 IR 附带的 [`frontend::Annotations`]、代码生成时的注释与来源等）
+//! 各有一份，多个线程可以同时编译。Koopa IR 中值的编号与类型在各线程中分别分配，[`Ir`] 因此只能在
+//! 生成它的线程中使用。一次编译之内，生成 IR 与指令选择在调用的线程中依次进行，其后各函数
+//! 相互独立的寄存器分配、合法化与指令调度才由 [`parallel`] 分给 [`CompileOptions::jobs`] 个线程。
+
+use std::error::Error;
+
+use koopa::back::KoopaGenerator;
+use koopa::ir::Program;
+
+use crate::backend::riscv::{Cpu, Riscv, Target};
+use crate::diagnostics::DiagnosticEmitter;
+use crate::frontend::{Instrumentation, Sanitizers};
+use crate::timing::Timings;
+
+pub mod analysis;
+pub mod backend;
+pub mod diagnostics;
+pub mod ffi;
+pub mod frontend;
+pub mod interpreter;
+pub mod jit;
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod mman;
+pub mod optimization;
+pub mod parallel;
+pub mod playground;
+pub mod serialize;
+mod stack;
+pub mod stats;
+pub mod timing;
+
+pub use diagnostics::Diagnostic;
+pub use frontend::{analyze, parse, Ir};
+
+pub type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+/// 编译选项，与命令行上同名的选项含义相同；默认即不加任何选项
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions {
+  /// 目标架构（`--target`、`--march`）
+  pub target: Target,
+  /// 目标处理器（`--mcpu`）
+  pub cpu: Cpu,
+  /// 生成位置无关代码（`-fpic`）
+  pub pic: bool,
+  /// 使用常量池（`--const-pool`）
+  pub const_pool: bool,
+  /// 代码生成之后依次运行的遍（`--passes`），取自 [`optimization::pass_names`]
+  pub passes: Vec<String>,
+  /// 插入的运行时检查（`-fsanitize=`）
+  pub sanitize: Sanitizers,
+  /// 为函数调用计数（`-finstrument-functions`）
+  pub instrument_functions: bool,
+  /// 统计各源代码行执行的次数（`--coverage`）
+  pub coverage: bool,
+  /// 程序不调用 `starttime`、`stoptime` 时在 `main` 的首尾自动调用（`--auto-timing`）
+  pub auto_timing: bool,
+  /// 允许函数重载（`--extensions`）
+  pub extensions: bool,
+  /// 寄存器分配、合法化与指令调度等按函数并行的阶段所用的线程数（`--jobs`），0 即处理器的
+  /// 核数，1 即不创建线程
+  pub jobs: usize,
+}
+
+/// 编译为 Koopa IR
+pub fn compile_to_koopa(source: &str, options: &CompileOptions) -> Result<Ir> {
+  let inputs = [("<input>", source.to_string())];
+  let instrument = Instrumentation {
+    sanitize: options.sanitize,
+    profile: options.instrument_functions,
+    coverage: options.coverage,
+    timing: options.auto_timing,
+  };
+  let timings = Timings::default();
+  let extensions = options.extensions;
+  let (ir, _) = frontend::generate_ir(&inputs, &options.target, instrument, extensions, &timings)?;
+  Ok(ir)
+}
+
+/// Koopa IR 的文本形式，与 `-koopa` 的输出相同
+pub(crate) fn koopa_text(ir: &Program) -> Result<String> {
+  let mut koopa = KoopaGenerator::new(Vec::new());
+  koopa.generate_on(ir)?;
+  Ok(String::from_utf8(koopa.writer())?)
+}
+
+/// 生成 RISC-V 程序，再依次运行 `options.passes` 中的遍
+fn generate_riscv(source: &str, options: &CompileOptions) -> Result<Riscv> {
+  // 在编译之前检查遍名，与命令行一致
+  let names = optimization::pass_names();
+  if let Some(pass) = options
+    .passes
+    .iter()
+    .find(|p| !names[1..].contains(&p.as_str()))
+  {
+    return Err(format!("unknown pass '{}'", pass).into());
+  }
+  let ir = compile_to_koopa(source, options)?;
+  let backend_options = backend::Options {
+    cpu: options.cpu,
+    pic: options.pic,
+    const_pool: options.const_pool,
+    verify: false,
+    jobs: options.jobs,
+  };
+  let timings = Timings::default();
+  let mut riscv =
+    backend::generate_riscv(&ir, options.target, backend_options, None, &timings, None)?;
+  for pass in &options.passes {
+    riscv = optimization::run_pass(pass, &riscv, options.cpu, options.target.xlen, options.jobs)?;
+  }
+  Ok(riscv)
+}
+
+/// 编译为 RISC-V 汇编
+pub fn compile_to_riscv(source: &str, options: &CompileOptions) -> Result<String> {
+  Ok(generate_riscv(source, options)?.to_string())
+}
+
+/// 编译为可重定位的 ELF 目标文件：以内置汇编器（[`backend::assemble`]）编码
+/// [`compile_to_riscv`] 所得的程序
+pub fn compile_to_object(source: &str, options: &CompileOptions) -> Result<Vec<u8>> {
+  let riscv = generate_riscv(source, options)?;
+  backend::assemble(&riscv, options.target.xlen)
+}
+
+/// 内置运行时库的目标文件（见 [`frontend::generate_runtime_ir`]），链接时找不到 `libsysy` 时与程序
+/// 一同链接。只取 `options` 中的目标、处理器与位置无关代码，总是运行优化的遍
+pub fn compile_runtime(options: &CompileOptions) -> Result<Vec<u8>> {
+  let ir = frontend::generate_runtime_ir(&options.target)?;
+  let backend_options = backend::Options {
+    cpu: options.cpu,
+    pic: options.pic,
+    const_pool: false,
+    verify: false,
+    jobs: options.jobs,
+  };
+  let timings = Timings::default();
+  let mut riscv =
+    backend::generate_riscv(&ir, options.target, backend_options, None, &timings, None)?;
+  let mut passes = vec!["peephole", "schedule"];
+  if options.target.ext.c {
+    passes.push("compress");
+  }
+  for pass in passes {
+    riscv = optimization::run_pass(pass, &riscv, options.cpu, options.target.xlen, options.jobs)?;
+  }
+  backend::assemble(&riscv, options.target.xlen)
+}
+
+/// 源程序中带有位置的语法与语义错误。生成 IR 时的错误没有位置，出错后可以据此重新分析
+pub fn diagnose(source: &str) -> Vec<Diagnostic> {
+  diagnose_with(source, false)
+}
+
+/// 与 [`diagnose`] 相同；`extensions` 时按允许函数重载（`--extensions`）分析
+pub fn diagnose_with(source: &str, extensions: bool) -> Vec<Diagnostic> {
+  match parse(source) {
+    Ok(ast) => frontend::analyze_with(ast, extensions).1,
+    Err(diagnostics) => diagnostics,
+  }
+}
+
+/// 编译出错时把错误交给 `emitter`
+pub(crate) fn report<T>(
+  result: Result<T>,
+  source: &str,
+  options: &CompileOptions,
+  emitter: &mut dyn DiagnosticEmitter,
+) -> Option<T> {
+  let e = match result {
+    Ok(value) => return Some(value),
+    Err(e) => e,
+  };
+  let diagnostics = diagnose_with(source, options.extensions);
+  match diagnostics.is_empty() {
+    true => emitter.emit(&Diagnostic::from_error(e.as_ref())),
+    false => diagnostics.iter().for_each(|d| emitter.emit(d)),
+  }
+  None
+}
+
+/// 与 [`compile_to_koopa`] 相同，但错误交给 `emitter`，失败时返回 `None`
+pub fn compile_to_koopa_with(
+  source: &str,
+  options: &CompileOptions,
+  emitter: &mut dyn DiagnosticEmitter,
+) -> Option<Ir> {
+  report(compile_to_koopa(source, options), source, options, emitter)
+}
+
+/// 与 [`compile_to_riscv`] 相同，但错误交给 `emitter`，失败时返回 `None`
+pub fn compile_to_riscv_with(
+  source: &str,
+  options: &CompileOptions,
+  emitter: &mut dyn DiagnosticEmitter,
+) -> Option<String> {
+  report(compile_to_riscv(source, options), source, options, emitter)
+}
@@ -0,0 +1,155 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::argparse::ParsedArgs;
+use crate::backend::riscv::Xlen;
+use crate::{Result, Timings};
+use sysyc::CompileOptions;
+
+/// 默认链接器；与课程文档中的工作流一致
+pub const DEFAULT_LINKER: &str = "ld.lld";
+
+/// 裸机运行（`--freestanding`）时代码段的地址：QEMU `virt` 平台上 OpenSBI 之后的内存
+const FREESTANDING_TEXT_ADDR: &str = "0x80200000";
+
+/// 默认的模拟器：课程环境中的 QEMU 用户态模拟
+fn default_runner(xlen: Xlen) -> &'static str {
+  match xlen {
+    Xlen::Rv32 => "qemu-riscv32-static",
+    Xlen::Rv64 => "qemu-riscv64-static",
+  }
+}
+
+/// 运行时库所在目录：`$CDE_LIBRARY_PATH/riscv32`（或 `riscv64`）
+pub fn default_lib_dir(xlen: Xlen) -> Option<PathBuf> {
+  let base = env::var_os("CDE_LIBRARY_PATH")?;
+  let arch = match xlen {
+    Xlen::Rv32 => "riscv32",
+    Xlen::Rv64 => "riscv64",
+  };
+  Some(PathBuf::from(base).join(arch))
+}
+
+/// 库搜索路径中是否有 `libsysy`（静态库或动态库）
+fn has_libsysy(args: &ParsedArgs) -> bool {
+  let dirs = args.lib_dirs.iter().map(PathBuf::from);
+  let mut dirs = dirs.chain(default_lib_dir(args.target.xlen));
+  dirs.any(|dir| {
+    ["libsysy.a", "libsysy.so"]
+      .iter()
+      .any(|lib| dir.join(lib).is_file())
+  })
+}
+
+/// 将各目标文件与 `libsysy` 链接为可执行文件 `output`；`-fpic` 时生成位置无关可执行文件。
+/// 库搜索路径中没有 `libsysy` 时改为链接内置的运行时库（[`sysyc::compile_runtime`]），不依赖
+/// C 库。裸机运行时库函数已在目标文件中，不链接 `libsysy`，而以 `_start` 为入口
+pub fn link(objects: &[Vec<u8>], output: &str, args: &ParsedArgs, timings: &Timings) -> Result<()> {
+  let linker = &args.linker;
+  let embedded = !args.target.freestanding() && !has_libsysy(args);
+  let mut objects = objects.to_vec();
+  if embedded {
+    let options = CompileOptions {
+      target: args.target,
+      cpu: args.cpu,
+      pic: args.pic,
+      jobs: args.jobs,
+      ..Default::default()
+    };
+    objects.push(timings.time("runtime", || sysyc::compile_runtime(&options))?);
+  }
+  let mut obj_paths = vec![];
+  for (i, object) in objects.iter().enumerate() {
+    let path = env::temp_dir().join(format!("sysyc-{}-{}.o", std::process::id(), i));
+    fs::write(&path, object)?;
+    obj_paths.push(path);
+  }
+
+  let mut command = Command::new(linker);
+  command.args(&obj_paths);
+  if args.target.freestanding() {
+    command.args([
+      "-e",
+      "_start",
+      &format!("-Ttext={}", FREESTANDING_TEXT_ADDR),
+    ]);
+  } else {
+    for dir in &args.lib_dirs {
+      command.arg(format!("-L{}", dir));
+    }
+    if let Some(dir) = default_lib_dir(args.target.xlen) {
+      command.arg("-L").arg(dir);
+    }
+    if args.pic {
+      command.arg("-pie");
+    }
+    if !embedded {
+      command.arg("-lsysy");
+    }
+  }
+  command.args(["-o", output]);
+
+  let status = command.status();
+  for path in &obj_paths {
+    fs::remove_file(path)?;
+  }
+  match status {
+    Ok(status) if status.success() => Ok(()),
+    Ok(status) => Err(format!("linker '{}' failed: {}", linker, status).into()),
+    Err(e) => Err(format!("cannot run linker '{}': {}", linker, e).into()),
+  }
+}
+
+/// 在模拟器中运行可执行文件 `exe`，标准输入取自 `stdin`（未给出时继承），
+/// 返回标准输出与退出码。`--runner` 可指定其他模拟器，如 `spike pk`
+pub fn run(exe: &str, stdin: Option<&str>, args: &ParsedArgs) -> Result<(Vec<u8>, i32)> {
+  let (stdout, _, code) = execute(exe, stdin, args, Stdio::inherit())?;
+  Ok((stdout, code))
+}
+
+/// 与 [`run`] 相同，另外返回标准错误的内容，如运行时库输出的计时
+pub fn run_with_stderr(
+  exe: &str,
+  stdin: Option<&str>,
+  args: &ParsedArgs,
+) -> Result<(Vec<u8>, Vec<u8>, i32)> {
+  execute(exe, stdin, args, Stdio::piped())
+}
+
+fn execute(
+  exe: &str,
+  stdin: Option<&str>,
+  args: &ParsedArgs,
+  stderr: Stdio,
+) -> Result<(Vec<u8>, Vec<u8>, i32)> {
+  let runner = args
+    .runner
+    .as_deref()
+    .unwrap_or(default_runner(args.target.xlen));
+  let mut words = runner.split_whitespace();
+  let program = words.next().ok_or("empty runner command")?;
+  let mut command = Command::new(program);
+  command.args(words).arg(exe).stderr(stderr);
+  if let Some(path) = stdin {
+    command.stdin(fs::File::open(path)?);
+  }
+  let output = command
+    .output()
+    .map_err(|e| format!("cannot run '{}': {}", program, e))?;
+  let code = output
+    .status
+    .code()
+    .ok_or_else(|| format!("program terminated abnormally: {}", output.status))?;
+  Ok((output.stdout, output.stderr, code))
+}
+
+/// 评测的输出格式：程序的输出（不以换行结尾时补上换行），随后一行是退出码
+pub fn judge_output(mut stdout: Vec<u8>, code: i32) -> Vec<u8> {
+  if !stdout.is_empty() && !stdout.ends_with(b"\n") {
+    stdout.push(b'\n');
+  }
+  stdout.extend(format!("{}\n", code).bytes());
+  stdout
+}
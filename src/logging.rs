@@ -0,0 +1,83 @@
+//! 编译器内部的跟踪信息，经由 `log` 输出到标准错误。
+//!
+//! `-v` 输出 debug 级别，`-vv` 输出 trace 级别；环境变量 `SYSYC_LOG` 可按模块指定级别，
+//! 如 `SYSYC_LOG=backend::regalloc=trace,frontend=debug`，模块路径不含 `sysyc::`，
+//! 不带模块的一项（如 `SYSYC_LOG=info`）指定其余模块的级别。
+
+use std::env;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+const CRATE: &str = "sysyc";
+
+struct Logger {
+  /// 未单独指定的模块的级别
+  default: LevelFilter,
+  /// 各个模块（含其子模块）的级别
+  modules: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+  /// 记录所在模块的级别：取路径最长的匹配项
+  fn level(&self, target: &str) -> LevelFilter {
+    let path = target.strip_prefix(CRATE).unwrap_or_default();
+    let path = path.strip_prefix("::").unwrap_or(path);
+    let matches = |module: &str| {
+      path == module || path.starts_with(module) && path[module.len()..].starts_with("::")
+    };
+    self
+      .modules
+      .iter()
+      .filter(|(module, _)| matches(module))
+      .max_by_key(|(module, _)| module.len())
+      .map_or(self.default, |&(_, level)| level)
+  }
+}
+
+impl Log for Logger {
+  fn enabled(&self, metadata: &Metadata) -> bool {
+    // 只输出编译器自身的记录
+    let target = metadata.target();
+    let ours = target == CRATE || target.starts_with("sysyc::");
+    ours && metadata.level() <= self.level(target)
+  }
+
+  fn log(&self, record: &Record) {
+    if self.enabled(record.metadata()) {
+      eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+    }
+  }
+
+  fn flush(&self) {}
+}
+
+fn parse_level(level: &str) -> Result<LevelFilter, String> {
+  level
+    .parse()
+    .map_err(|_| format!("invalid log level '{}' in SYSYC_LOG", level))
+}
+
+/// 安装日志；`verbose` 为 `-v` 出现的次数
+pub fn init(verbose: u8) -> Result<(), Box<dyn std::error::Error>> {
+  let mut logger = Logger {
+    default: match verbose {
+      0 => LevelFilter::Warn,
+      1 => LevelFilter::Debug,
+      _ => LevelFilter::Trace,
+    },
+    modules: vec![],
+  };
+  if let Ok(spec) = env::var("SYSYC_LOG") {
+    for item in spec.split(',').filter(|item| !item.is_empty()) {
+      match item.split_once('=') {
+        Some((module, level)) => logger.modules.push((module.into(), parse_level(level)?)),
+        None => logger.default = parse_level(item)?,
+      }
+    }
+  }
+  let max = logger.modules.iter().map(|&(_, level)| level);
+  log::set_max_level(max.fold(logger.default, Ord::max));
+  // 日志在整个进程中只安装一次
+  let logger = Box::leak(Box::new(logger));
+  log::set_logger(logger).map_err(|e| e.to_string().into())
+}
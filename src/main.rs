@@ -1,53 +1,551 @@
-use argparse::{Mode, ParsedArgs};
+use argparse::{ErrorFormat, Mode, ParsedArgs};
+use backend::riscv::directive::{Directive, SymbolType};
+use backend::riscv::{Riscv, RiscvItem};
+use backend::{Options, SourceInfo};
+use frontend::{Instrumentation, Ir, SourceMap};
 use koopa::back::KoopaGenerator;
-use std::env::args;
-use std::error::Error;
+use std::env::{self, args};
+use std::fmt::Display;
 use std::fs;
-use std::io::{stdout, Write};
+use std::io::{self, stderr, stdin, stdout, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use sysyc::analysis::{self, CallGraph};
+use sysyc::diagnostics::{
+  Diagnostic, DiagnosticEmitter, JsonEmitter, PrettyEmitter, TerminalEmitter,
+};
+use sysyc::stats::{self, Stats};
+use sysyc::timing::Timings;
+use sysyc::{backend, frontend, interpreter, jit, optimization, Result};
 
 mod argparse;
-mod backend;
-mod frontend;
-mod optimization;
-
-type Result<T> = std::result::Result<T, Box<dyn Error>>;
-
-fn compile() -> Result<()> {
-  let ParsedArgs {
-    mode,
-    input,
-    output,
-  } = argparse::parse(args())?;
-  
-  let input = fs::read_to_string(&input[0])?;
-  let mut output: Box<dyn Write> = if output.is_none() {
-    Box::new(stdout())
+mod bench;
+mod completions;
+mod config;
+mod coverage;
+mod diff;
+mod difftest;
+mod link;
+mod logging;
+mod progen;
+mod testsuite;
+
+/// `--dump-ir-before`/`--dump-ir-after`：把第 `index` 遍 `pass` 之前或之后的 IR 写入以输入的
+/// 文件名、遍的序号与名字命名的文件，如 `foo.02.peephole.after.s`
+fn dump_ir(
+  args: &ParsedArgs,
+  input: &str,
+  (index, pass): (usize, &str),
+  after: bool,
+  ir: &dyn Display,
+) -> Result<()> {
+  let passes = if after {
+    &args.dump_ir_after
+  } else {
+    &args.dump_ir_before
+  };
+  if !passes.iter().any(|p| p == pass) {
+    return Ok(());
+  }
+  let when = if after { "after" } else { "before" };
+  // 代码生成之前是 Koopa IR，其后是汇编
+  let extension = if pass == "codegen" && !after {
+    "koopa"
   } else {
-    Box::new(fs::File::create(output.unwrap())?)
+    "s"
   };
+  let suffix = format!("{:02}.{}.{}.{}", index, pass, when, extension);
+  let path = default_output(input, &suffix);
+  with_path(&path, fs::write(&path, ir.to_string()))
+}
+
+/// 汇编中各个函数（`.type f, @function` 至 `.size f, ...`）的名字与文本，按出现的顺序
+fn function_texts(riscv: &Riscv) -> Vec<(String, String)> {
+  let mut funcs: Vec<(String, String)> = vec![];
+  let mut inside = false;
+  for item in &riscv.0 {
+    if let RiscvItem::Directive(Directive::Type(name, SymbolType::Function)) = item {
+      funcs.push((name.clone(), String::new()));
+      inside = true;
+    }
+    if let Some((_, text)) = funcs.last_mut().filter(|_| inside) {
+      *text += &format!("{}\n", item);
+    }
+    if let RiscvItem::Directive(Directive::SizeTo(..)) = item {
+      inside = false;
+    }
+  }
+  funcs
+}
 
-  let ir = frontend::generate_ir(input)?;
+/// `--print-changed`：向标准错误输出第 `index` 遍 `pass` 改变了的各函数的差异，没有改变的不输出
+fn print_changed((index, pass): (usize, &str), before: &[(String, String)], after: &Riscv) {
+  let after = function_texts(after);
+  let text = |funcs: &[(String, String)], name: &str| {
+    let func = funcs.iter().find(|(func, _)| func == name);
+    func.map_or(String::new(), |(_, text)| text.clone())
+  };
+  // 被这一遍删去的函数在最后
+  let removed = before
+    .iter()
+    .filter(|(name, _)| after.iter().all(|(func, _)| func != name));
+  for (name, _) in after.iter().chain(removed) {
+    let old_name = format!("{} before {:02}.{}", name, index, pass);
+    let new_name = format!("{} after {:02}.{}", name, index, pass);
+    let (old, new) = (text(before, name), text(&after, name));
+    if let Some(diff) = diff::unified(&old, &new, &old_name, &new_name) {
+      eprint!("{}", diff);
+    }
+  }
+}
+
+/// 作为第 `index` 遍运行汇编上的遍 `pass`，按需输出其前后的汇编
+fn run_pass(
+  args: &ParsedArgs,
+  input: &str,
+  index: usize,
+  pass: &str,
+  riscv: Riscv,
+  timings: &Timings,
+) -> Result<Riscv> {
+  // 计时以遍名为键
+  let names = optimization::pass_names();
+  let pass = names.into_iter().find(|&p| p == pass).unwrap();
+  dump_ir(args, input, (index, pass), false, &riscv)?;
+  let before = args.print_changed.then(|| function_texts(&riscv));
+  let run = || optimization::run_pass(pass, &riscv, args.cpu, args.target.xlen, args.jobs);
+  let riscv = timings.time(pass, run)?;
+  if args.verify {
+    backend::verify_asm(&riscv, pass, true)?;
+  }
+  dump_ir(args, input, (index, pass), true, &riscv)?;
+  if let Some(before) = before {
+    print_changed((index, pass), &before, &riscv);
+  }
+  Ok(riscv)
+}
+
+/// `--save-temps`：把中间结果写入输出所在目录下与输入同名、扩展名为 `extension` 的文件
+fn save_temp(args: &ParsedArgs, input: &str, extension: &str, contents: &[u8]) -> Result<()> {
+  if !args.save_temps {
+    return Ok(());
+  }
+  let dir = match args.output.as_deref() {
+    Some(path) if path != "-" => Path::new(path).parent().unwrap_or(Path::new("")),
+    _ => Path::new(""),
+  };
+  let path = dir.join(default_output(input, extension));
+  with_path(&path, fs::write(&path, contents))
+}
 
-  match mode {
+/// 生成 Koopa IR，`--verify` 时随即检查
+fn generate_ir(
+  args: &ParsedArgs,
+  inputs: &[(&str, String)],
+  timings: &Timings,
+) -> Result<(Ir, SourceMap)> {
+  for (input, text) in inputs {
+    // SysY 没有预处理指令，预处理的结果即源代码
+    save_temp(args, input, "i", text.as_bytes())?;
+  }
+  let instrument = Instrumentation {
+    sanitize: args.sanitize,
+    profile: args.instrument_functions,
+    coverage: args.coverage,
+    timing: args.auto_timing,
+  };
+  let extensions = args.extensions;
+  let (ir, source) = frontend::generate_ir(inputs, &args.target, instrument, extensions, timings)?;
+  if args.verify {
+    backend::verify_ir(&ir)?;
+  }
+  if args.save_temps {
+    let mut koopa = KoopaGenerator::new(Vec::new());
+    koopa.generate_on(&ir)?;
+    save_temp(args, inputs[0].0, "koopa", &koopa.writer())?;
+  }
+  Ok((ir, source))
+}
+
+fn generate_riscv(
+  ir: &Ir,
+  args: &ParsedArgs,
+  input: &str,
+  source: Option<SourceInfo>,
+  timings: &Timings,
+) -> Result<Riscv> {
+  let options = Options {
+    cpu: args.cpu,
+    pic: args.pic,
+    const_pool: args.const_pool,
+    verify: args.verify,
+    jobs: args.jobs,
+  };
+  if args.dump_ir_before.iter().any(|p| p == "codegen") {
+    let mut koopa = KoopaGenerator::new(Vec::new());
+    koopa.generate_on(ir)?;
+    let text = String::from_utf8(koopa.writer())?;
+    dump_ir(args, input, (1, "codegen"), false, &text)?;
+  }
+  let mut stats = args.stats.then(Stats::default);
+  let mut riscv =
+    backend::generate_riscv(ir, args.target, options, source, timings, stats.as_mut())?;
+  dump_ir(args, input, (1, "codegen"), true, &riscv)?;
+  let codegen = args.stats.then(|| stats::count_insts(&riscv));
+  // 代码生成是第 1 遍，其后依次是 `args.passes`
+  for (i, pass) in args.passes.iter().enumerate() {
+    riscv = run_pass(args, input, i + 2, pass, riscv, timings)?;
+  }
+  if args.rvc_report {
+    eprintln!("{}", optimization::CompressReport::of(&riscv));
+  }
+  if let (Some(codegen), Some(stats)) = (codegen, stats) {
+    let output = stats::count_insts(&riscv);
+    eprint!("{}", stats::report(ir, &stats, &codegen, &output));
+  }
+  save_temp(args, input, "s", riscv.to_string().as_bytes())?;
+  Ok(riscv)
+}
+
+/// 未给出 `-o` 时 `-S`/`-c` 的输出：当前目录下与输入同名、扩展名为 `extension` 的文件
+fn default_output(input: &str, extension: &str) -> String {
+  let stem = Path::new(input).file_stem().unwrap_or_default();
+  let path = Path::new(stem).with_extension(extension);
+  path.to_string_lossy().into_owned()
+}
+
+/// 读入源文件，`-` 即标准输入
+fn read_input(input: &str) -> Result<String> {
+  if input == "-" {
+    let mut text = String::new();
+    stdin().read_to_string(&mut text)?;
+    Ok(text)
+  } else {
+    with_path(input, fs::read_to_string(input))
+  }
+}
+
+/// 读写文件 `path` 的结果；出错时错误信息带有文件名，如 `foo.sy: No such file or directory`
+fn with_path<T>(path: impl AsRef<Path>, result: io::Result<T>) -> Result<T> {
+  result.map_err(|e| format!("{}: {}", path.as_ref().display(), e).into())
+}
+
+/// 按 `args.mode` 编译源文件，`output` 为 `None` 时输出到标准输出。多个源文件合并为一个程序；
+/// `-E` 与 `--emit=ast`/`--emit=tokens` 则依次输出各个文件
+fn compile_file(
+  args: &ParsedArgs,
+  inputs: &[String],
+  output: Option<&str>,
+  timings: &Timings,
+) -> Result<()> {
+  let open_output = || -> Result<Box<dyn Write>> {
+    Ok(match output {
+      None | Some("-") => Box::new(stdout()),
+      Some(path) => Box::new(with_path(path, fs::File::create(path))?),
+    })
+  };
+
+  let mut texts = vec![];
+  for input in inputs {
+    texts.push((input.as_str(), read_input(input)?));
+  }
+  if matches!(
+    args.mode,
+    Mode::Preprocess
+      | Mode::Ast
+      | Mode::AstJson
+      | Mode::AstDot
+      | Mode::Tokens
+      | Mode::HighlightHtml
+      | Mode::FindRefs
+  ) {
+    let mut output = open_output()?;
+    for (input, text) in &texts {
+      let dump = match args.mode {
+        // SysY 没有预处理指令
+        Mode::Preprocess => text.clone(),
+        Mode::Ast => frontend::dump_ast(text)?,
+        Mode::AstJson => frontend::dump_ast_json(text)? + "\n",
+        Mode::AstDot => frontend::dump_ast_dot(text)?,
+        Mode::HighlightHtml => frontend::highlight::highlight_html(text),
+        Mode::FindRefs => {
+          let name = args.find_refs.as_deref().unwrap();
+          frontend::query::find_references(input, text, name)?
+        }
+        _ => frontend::dump_tokens(text)?,
+      };
+      output.write_all(dump.as_bytes())?;
+    }
+    return Ok(());
+  }
+  // 源代码行的映射、各遍输出的文件名均取第一个源文件
+  let input = &inputs[0];
+  let (ir, source) = generate_ir(args, &texts, timings)?;
+  // `--stats` 以源代码行标出寄存器压力最大处
+  let source =
+    (args.asm_comments || args.debug || args.stats || args.source_map).then_some(SourceInfo {
+      map: &source,
+      file: input,
+      comments: args.asm_comments,
+      debug: args.debug,
+      origins: args.source_map,
+    });
+
+  match args.mode {
+    Mode::Run => {
+      let code = match args.jit {
+        true => timings.time("jit", || jit::run(&ir))?,
+        false => timings.time("interpret", || interpreter::run(&ir))?,
+      };
+      if args.time_passes {
+        eprint!("{}", timings.report());
+      }
+      std::process::exit(code);
+    }
     Mode::Koopa => {
-      KoopaGenerator::new(output).generate_on(&ir)?;
+      let mut koopa = KoopaGenerator::new(open_output()?);
+      timings.time("emit", || koopa.generate_on(&ir))?;
+    }
+    Mode::Llvm => {
+      let output = open_output()?;
+      timings.time("emit", || backend::generate_llvm(&ir, output))?;
+    }
+    Mode::C => {
+      let output = open_output()?;
+      timings.time("emit", || backend::generate_c(&ir, output))?;
+    }
+    Mode::Cfg => {
+      let dot = match &args.cfg_after {
+        // 只运行到这一遍
+        Some(pass) => {
+          let end = args
+            .passes
+            .iter()
+            .position(|p| p == pass)
+            .map_or(0, |i| i + 1);
+          let args = ParsedArgs {
+            passes: args.passes[..end].to_vec(),
+            ..args.clone()
+          };
+          let riscv = generate_riscv(&ir, &args, input, source, timings)?;
+          timings.time("emit", || backend::cfg_dot(&riscv))
+        }
+        None => timings.time("emit", || analysis::cfg_dot(&ir))?,
+      };
+      open_output()?.write_all(dot.as_bytes())?;
+    }
+    Mode::DomTree => {
+      let dot = timings.time("emit", || analysis::domtree_dot(&ir));
+      open_output()?.write_all(dot.as_bytes())?;
+    }
+    Mode::CallGraph => {
+      let dot = timings.time("emit", || CallGraph::new(&ir).to_dot(&ir));
+      open_output()?.write_all(dot.as_bytes())?;
+    }
+    Mode::Wasm | Mode::Wat => {
+      let module = timings.time("wasm", || backend::wasm::generate(&ir))?;
+      let mut output = open_output()?;
+      if matches!(args.mode, Mode::Wat) {
+        output.write_all(module.to_string().as_bytes())?;
+      } else {
+        output.write_all(&module.encode())?;
+      }
+    }
+    Mode::Riscv | Mode::Perf => {
+      let mut riscv = generate_riscv(&ir, args, input, source, timings)?;
+      if args.source_map {
+        let map;
+        (riscv, map) = backend::extract_source_map(riscv);
+        // 与汇编并列；输出到标准输出时按输入文件命名
+        let path = match output {
+          Some(path) if path != "-" => format!("{}.map", path),
+          _ => default_output(input, "s.map"),
+        };
+        with_path(&path, fs::write(&path, map.to_json(input).to_string() + "\n"))?;
+      }
+      let mut output = open_output()?;
+      if args.emit_obj {
+        let object = timings.time("assemble", || backend::assemble(&riscv, args.target.xlen))?;
+        output.write_all(&object)?;
+      } else {
+        let text = timings.time("emit", || riscv.to_string());
+        output.write(text.as_bytes())?;
+      }
+    }
+    Mode::Preprocess
+    | Mode::Ast
+    | Mode::AstJson
+    | Mode::AstDot
+    | Mode::Tokens
+    | Mode::HighlightHtml
+    | Mode::FindRefs
+    | Mode::Link
+    | Mode::RunQemu
+    | Mode::Test
+    | Mode::Gen
+    | Mode::Bench
+    | Mode::CovReport => {
+      unreachable!()
+    }
+  }
+  Ok(())
+}
+
+/// 编译为目标文件以供链接；已是目标文件（`.o`）的输入原样读入
+fn compile_object(args: &ParsedArgs, input: &str, timings: &Timings) -> Result<Vec<u8>> {
+  if input.ends_with(".o") {
+    return with_path(input, fs::read(input));
+  }
+  let (ir, _) = generate_ir(args, &[(input, read_input(input)?)], timings)?;
+  let riscv = generate_riscv(&ir, args, input, None, timings)?;
+  let object = timings.time("assemble", || backend::assemble(&riscv, args.target.xlen))?;
+  save_temp(args, input, "o", &object)?;
+  Ok(object)
+}
+
+/// 按参数完成一次编译
+fn build(args: &ParsedArgs) -> Result<()> {
+  // 每次编译（`--watch` 的每次重新编译）从头计时
+  let timings = &Timings::new(args.time_passes);
+  match args.mode {
+    Mode::Link => {
+      let objects = args.input.iter().map(|input| compile_object(args, input, timings));
+      let objects = objects.collect::<Result<Vec<_>>>()?;
+      // 同 gcc，默认输出 `a.out`
+      let output = args.output.as_deref().unwrap_or("a.out");
+      timings.time("link", || link::link(&objects, output, args, timings))?;
     }
-    Mode::Riscv => {
-      let riscv = backend::generate_riscv(&ir)?;
-      output.write(riscv.to_string().as_bytes())?;
+    Mode::RunQemu => {
+      let object = compile_object(args, &args.input[0], timings)?;
+      // 未给出 -o 时，可执行文件是运行后即删除的临时文件；`--save-temps` 时则保留为与输入同名的文件
+      let temp = env::temp_dir().join(format!("sysyc-{}", std::process::id()));
+      let exe = match &args.output {
+        Some(path) => path.clone(),
+        None if args.save_temps => default_output(&args.input[0], ""),
+        None => temp.to_string_lossy().into_owned(),
+      };
+      timings.time("link", || link::link(&[object], &exe, args, timings))?;
+      let stdin = args.input.get(1).map(String::as_str);
+      let result = timings.time("run", || link::run(&exe, stdin, args));
+      if args.output.is_none() && !args.save_temps {
+        fs::remove_file(&temp)?;
+      }
+      let (output, code) = result?;
+      let output = link::judge_output(output, code);
+      stdout().write_all(&output)?;
+      if let Some(stdin) = stdin {
+        testsuite::verify_opt(args, &args.input[0], stdin, &output, timings)?;
+      }
     }
-    Mode::Perf => {
-      let mut riscv = backend::generate_riscv(&ir)?;
-      riscv = optimization::pass_peephole(&riscv);
-      output.write(riscv.to_string().as_bytes())?;
+    Mode::Test => testsuite::run(args, timings)?,
+    Mode::Gen => difftest::run(args, timings)?,
+    Mode::Bench => bench::run(args, timings)?,
+    Mode::CovReport => coverage::run(args)?,
+    // 同 gcc，`-S`/`-c` 分别编译各个源文件
+    _ if args.driver => {
+      let extension = if args.emit_obj { "o" } else { "s" };
+      for input in &args.input {
+        let output = match &args.output {
+          Some(path) => path.clone(),
+          None => default_output(input, extension),
+        };
+        compile_file(args, std::slice::from_ref(input), Some(&output), timings)?;
+      }
     }
+    _ => compile_file(args, &args.input, args.output.as_deref(), timings)?,
+  }
+  if args.time_passes {
+    eprint!("{}", timings.report());
   }
   Ok(())
 }
 
+/// `--watch`：每当输入文件改动就重新编译；出错时只报告错误，继续等待下一次改动
+fn watch(args: &ParsedArgs) -> ! {
+  // 各文件的修改时间；读不到（如正被替换）时为 `None`
+  let modified = || {
+    let modified = |input: &String| fs::metadata(input).and_then(|m| m.modified()).ok();
+    args.input.iter().map(modified).collect::<Vec<_>>()
+  };
+  loop {
+    let last = modified();
+    match build(args) {
+      Ok(()) => eprintln!("[watch] build succeeded"),
+      Err(e) => report(args, e),
+    }
+    eprintln!("[watch] waiting for changes (Ctrl-C to stop)");
+    while modified() == last {
+      thread::sleep(Duration::from_millis(200));
+    }
+    // 等编辑器写完文件
+    thread::sleep(Duration::from_millis(50));
+  }
+}
+
+fn compile(args: &ParsedArgs) -> Result<()> {
+  logging::init(args.verbose)?;
+  if args.debug && (args.emit_obj || matches!(args.mode, Mode::Link | Mode::RunQemu)) {
+    // 内置汇编器尚不支持调试信息所需的伪指令
+    return Err("-g is only supported for assembly output".into());
+  }
+  if args.watch {
+    watch(args);
+  }
+  build(args)
+}
+
+/// 以 `--error-format` 指定的格式输出诊断；`source` 是其所在源文件的名字与内容
+fn emit(format: ErrorFormat, source: Option<(&str, &str)>, diagnostic: &Diagnostic) {
+  let mut emitter: Box<dyn DiagnosticEmitter> = match (format, source) {
+    (ErrorFormat::Human, _) => Box::new(TerminalEmitter::default()),
+    (ErrorFormat::Pretty, Some((name, text))) => Box::new(PrettyEmitter::with_source(name, text)),
+    (ErrorFormat::Pretty, None) => Box::new(PrettyEmitter::default()),
+    (ErrorFormat::Json, Some((name, text))) => {
+      Box::new(JsonEmitter::with_source(stderr(), name, text))
+    }
+    (ErrorFormat::Json, None) => Box::new(JsonEmitter::stderr()),
+  };
+  emitter.emit(diagnostic);
+}
+
+/// 输出编译失败的原因。`--error-format=pretty`/`json` 时重新分析源文件，给出源程序中带有位置的
+/// 错误。几个源文件一同编译时，单独分析其中一个会把其他文件中定义的符号误报为未定义，
+/// 因此只输出带有文件名的错误
+fn report(args: &ParsedArgs, e: Box<dyn std::error::Error>) {
+  let mut found = false;
+  let sources = args.input.iter().filter(|input| !input.ends_with(".o"));
+  if args.error_format != ErrorFormat::Human && sources.count() == 1 {
+    for input in &args.input {
+      // 标准输入已经读完，无法重新分析
+      if input == "-" || input.ends_with(".o") {
+        continue;
+      }
+      let Ok(text) = fs::read_to_string(input) else {
+        continue;
+      };
+      for diagnostic in sysyc::diagnose_with(&text, args.extensions) {
+        emit(args.error_format, Some((input, &text)), &diagnostic);
+        found = true;
+      }
+    }
+  }
+  if !found {
+    emit(args.error_format, None, &Diagnostic::from_error(e.as_ref()));
+  }
+}
+
 fn main() {
-  if let Err(e) = compile() {
-    eprintln!("{}", e);
+  let args = match argparse::parse(args()) {
+    Ok(args) => args,
+    Err(e) => {
+      // 尚不知道 `--error-format`
+      TerminalEmitter::default().emit(&Diagnostic::from_error(e.as_ref()));
+      std::process::exit(1);
+    }
+  };
+  if let Err(e) = compile(&args) {
+    report(&args, e);
     std::process::exit(1);
   }
 }
@@ -0,0 +1,68 @@
+//! Linux 上的匿名内存映射（`mmap(2)`），用作切换的栈（见 [`crate::stack`]）与 JIT 生成的机器码
+//! （见 [`crate::jit`]）。只声明用到的几个系统调用，不依赖 `libc` crate。
+
+use std::ffi::{c_int, c_void};
+use std::ptr;
+
+pub const PROT_NONE: c_int = 0;
+pub const PROT_READ: c_int = 1;
+pub const PROT_WRITE: c_int = 2;
+pub const PROT_EXEC: c_int = 4;
+const MAP_PRIVATE: c_int = 0x02;
+const MAP_ANONYMOUS: c_int = 0x20;
+const MAP_NORESERVE: c_int = 0x4000;
+const MAP_FAILED: *mut c_void = !0 as *mut c_void;
+
+extern "C" {
+  fn mmap(
+    addr: *mut c_void,
+    len: usize,
+    prot: c_int,
+    flags: c_int,
+    fd: c_int,
+    offset: i64,
+  ) -> *mut c_void;
+  fn munmap(addr: *mut c_void, len: usize) -> c_int;
+  fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+}
+
+/// 一段可读写的匿名映射，按页对齐，释放时解除映射。只预留地址空间，用到时才占用内存
+pub struct Mapping {
+  base: *mut u8,
+  len: usize,
+}
+
+impl Mapping {
+  /// 映射 `len` 字节；失败时为 `None`
+  pub fn new(len: usize) -> Option<Self> {
+    let prot = PROT_READ | PROT_WRITE;
+    let flags = MAP_PRIVATE | MAP_ANONYMOUS | MAP_NORESERVE;
+    // SAFETY: 新建匿名映射，不涉及已有的内存
+    let base = unsafe { mmap(ptr::null_mut(), len, prot, flags, -1, 0) };
+    (base != MAP_FAILED).then_some(Self {
+      base: base.cast(),
+      len,
+    })
+  }
+
+  pub fn as_ptr(&self) -> *mut u8 {
+    self.base
+  }
+
+  pub fn len(&self) -> usize {
+    self.len
+  }
+
+  /// 把开头的 `len` 字节（页大小的整数倍）改为 `prot`，返回是否成功
+  pub fn protect(&self, len: usize, prot: c_int) -> bool {
+    // SAFETY: 只改变这一映射之内的页
+    len <= self.len && unsafe { mprotect(self.base.cast(), len, prot) } == 0
+  }
+}
+
+impl Drop for Mapping {
+  fn drop(&mut self) {
+    // SAFETY: 映射由 `new` 建立，此后不再访问
+    unsafe { munmap(self.base.cast(), self.len) };
+  }
+}
@@ -0,0 +1,63 @@
+use crate::backend::riscv::compressed::compress;
+use crate::backend::riscv::directive::Directive;
+use crate::backend::riscv::{Riscv, RiscvItem, Xlen};
+
+/// 压缩效果统计
+pub struct CompressReport {
+  pub compressed: usize,
+  pub total: usize,
+}
+
+impl CompressReport {
+  pub fn of(riscv: &Riscv) -> Self {
+    let mut report = Self {
+      compressed: 0,
+      total: 0,
+    };
+    for item in &riscv.0 {
+      match item {
+        RiscvItem::Inst(_) => report.total += 1,
+        RiscvItem::Compressed(_) => {
+          report.total += 1;
+          report.compressed += 1;
+        }
+        _ => {}
+      }
+    }
+    report
+  }
+}
+
+impl std::fmt::Display for CompressReport {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let ratio = if self.total == 0 {
+      0.0
+    } else {
+      self.compressed as f64 * 100.0 / self.total as f64
+    };
+    write!(
+      f,
+      "rvc: compressed {} of {} instructions ({:.1}%), saving {} bytes",
+      self.compressed,
+      self.total,
+      ratio,
+      self.compressed * 2
+    )
+  }
+}
+
+/// 将可以压缩的指令替换为 RVC 指令（`c.*`）
+pub fn pass_compress(old: &Riscv, xlen: Xlen) -> Riscv {
+  let mut result = vec![RiscvItem::Directive(Directive::Option("rvc".into()))];
+  for item in &old.0 {
+    let item = match item {
+      RiscvItem::Inst(inst) => match compress(inst, xlen) {
+        Some(c) => RiscvItem::Compressed(c),
+        None => item.clone(),
+      },
+      _ => item.clone(),
+    };
+    result.push(item);
+  }
+  Riscv(result)
+}
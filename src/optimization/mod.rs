@@ -1,3 +1,86 @@
+mod compress;
 mod peephole;
+mod schedule;
 
+pub use compress::{pass_compress, CompressReport};
 pub use peephole::pass_peephole;
+pub use schedule::pass_schedule;
+
+use std::sync::{Arc, RwLock};
+
+use log::debug;
+use once_cell::sync::Lazy;
+
+use crate::backend::riscv::directive::Directive;
+use crate::backend::riscv::{Cpu, Riscv, RiscvItem, Xlen};
+use crate::{parallel, Result};
+
+/// 生成汇编的流水线中的各遍，按运行的顺序；`--dump-ir-before`/`--dump-ir-after` 以此命名
+pub const PASSES: &[&str] = &["codegen", "peephole", "schedule", "compress"];
+
+/// 代码生成之后在汇编上运行的遍
+pub type Pass = dyn Fn(&Riscv, Cpu, Xlen) -> Riscv + Send + Sync;
+
+type NamedPass = (&'static str, Arc<Pass>);
+
+/// 由 [`register_pass`] 注册的遍，按注册的顺序
+static REGISTERED: Lazy<RwLock<Vec<NamedPass>>> = Lazy::new(RwLock::default);
+
+/// 注册名为 `name` 的遍，此后即可与内置的遍一样出现在 `--passes` 与
+/// [`CompileOptions::passes`](crate::CompileOptions::passes) 中。遍名不能与已有的遍相同
+pub fn register_pass(
+  name: &'static str,
+  pass: impl Fn(&Riscv, Cpu, Xlen) -> Riscv + Send + Sync + 'static,
+) -> Result<()> {
+  if pass_names().contains(&name) {
+    return Err(format!("pass '{}' already exists", name).into());
+  }
+  REGISTERED.write()?.push((name, Arc::new(pass)));
+  Ok(())
+}
+
+/// 所有的遍：先是 [`PASSES`]，其后是注册的遍
+pub fn pass_names() -> Vec<&'static str> {
+  let registered = REGISTERED.read().unwrap();
+  let registered = registered.iter().map(|(name, _)| *name);
+  PASSES.iter().copied().chain(registered).collect()
+}
+
+/// 运行代码生成之后的遍 `pass`；只在函数之内变换的遍以 `jobs` 个线程并行（见 [`parallel::map`]）
+pub fn run_pass(pass: &str, riscv: &Riscv, cpu: Cpu, xlen: Xlen, jobs: usize) -> Result<Riscv> {
+  debug!("running pass '{}' on {} items", pass, riscv.0.len());
+  match pass {
+    "peephole" => Ok(pass_peephole(riscv)),
+    "schedule" => Ok(per_function(riscv, jobs, |func| pass_schedule(func, cpu))),
+    "compress" => Ok(pass_compress(riscv, xlen)),
+    _ => {
+      let registered = REGISTERED.read()?;
+      let found = registered.iter().find(|(name, _)| *name == pass);
+      // 不持有锁运行，遍中可以再注册遍
+      let pass = match found {
+        Some((_, pass)) => pass.clone(),
+        None => return Err(format!("unknown pass '{}'", pass).into()),
+      };
+      drop(registered);
+      Ok(pass(riscv, cpu, xlen))
+    }
+  }
+}
+
+/// 在各个函数（以 `.text` 开始的一段）上并行地运行只在函数之内变换的遍，按原来的顺序拼接
+fn per_function(riscv: &Riscv, jobs: usize, pass: impl Fn(&Riscv) -> Riscv + Sync) -> Riscv {
+  let mut funcs = vec![];
+  let mut start = 0;
+  for (i, item) in riscv.0.iter().enumerate() {
+    if i > start && matches!(item, RiscvItem::Directive(Directive::Text)) {
+      funcs.push(Riscv(riscv.0[start..i].to_vec()));
+      start = i;
+    }
+  }
+  funcs.push(Riscv(riscv.0[start..].to_vec()));
+  let mut result = Riscv::new();
+  for func in parallel::map(jobs, funcs, |func| pass(&func)) {
+    result.extend(func);
+  }
+  result
+}
@@ -1,4 +1,22 @@
-use crate::backend::riscv::{Riscv, RiscvItem, inst::Inst};
+use crate::backend::riscv::directive::Directive;
+use crate::backend::riscv::{inst::Inst, reg::Reg, Riscv, RiscvItem};
+
+/// 拆出访存指令的（宽度，数据寄存器，偏移，基址寄存器）
+fn as_store(item: &RiscvItem) -> Option<(usize, Reg, i32, Reg)> {
+  match item {
+    RiscvItem::Inst(Inst::Sw(rs2, imm, rs1)) => Some((4, *rs2, *imm, *rs1)),
+    RiscvItem::Inst(Inst::Sd(rs2, imm, rs1)) => Some((8, *rs2, *imm, *rs1)),
+    _ => None,
+  }
+}
+
+fn as_load(item: &RiscvItem) -> Option<(usize, Reg, i32, Reg)> {
+  match item {
+    RiscvItem::Inst(Inst::Lw(rs, imm, rd)) => Some((4, *rs, *imm, *rd)),
+    RiscvItem::Inst(Inst::Ld(rs, imm, rd)) => Some((8, *rs, *imm, *rd)),
+    _ => None,
+  }
+}
 
 pub fn pass_peephole(old: &Riscv) -> Riscv {
   let old: Vec<_> = old
@@ -12,13 +30,19 @@ pub fn pass_peephole(old: &Riscv) -> Riscv {
   let mut i = 0;
   while i < old.len() - 1 {
     result.push(old[i].clone());
-    if let RiscvItem::Inst(Inst::Sw(s_rs2, s_imm, s_rs1)) = old[i] {
-      if let RiscvItem::Inst(Inst::Lw(l_rs, l_imm, l_rd)) = old[i + 1] {
-        if l_rd == s_rs1 && l_imm == s_imm {
+    if let Some((s_width, s_rs2, s_imm, s_rs1)) = as_store(old[i]) {
+      // 两者之间的来源标记（`--source-map`）不影响合并，保留在 mv 之前
+      let mut j = i + 1;
+      while j < old.len() - 1 && matches!(old[j], RiscvItem::Directive(Directive::Origin(_))) {
+        j += 1;
+      }
+      if let Some((l_width, l_rs, l_imm, l_rd)) = as_load(old[j]) {
+        if l_rd == s_rs1 && l_imm == s_imm && l_width == s_width {
+          result.extend(old[i + 1..j].iter().map(|&item| item.clone()));
           if s_rs2 != l_rs {
-            result.push(RiscvItem::Inst(Inst::Addi(*l_rs, *s_rs2, 0)));
+            result.push(RiscvItem::Inst(Inst::Addi(l_rs, s_rs2, 0)));
           }
-          i += 2;
+          i = j + 1;
           continue;
         }
       }
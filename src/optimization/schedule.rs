@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use crate::backend::riscv::directive::Directive;
+use crate::backend::riscv::inst::{Inst, MemAccess};
+use crate::backend::riscv::reg::Reg;
+use crate::backend::riscv::{Cpu, Riscv, RiscvItem};
+use crate::backend::Origin;
+
+/// 单个调度区域的最大指令数；过长的基本块被切分，以限制依赖图的规模
+const MAX_REGION: usize = 128;
+
+/// 两条访存指令是否可能访问重叠的内存。`version` 为基址寄存器在各自位置被写入的次数，
+/// 只有基址相同且其间未被改写、偏移量均为常数时才能通过偏移量判定。
+fn may_alias(a: (MemAccess, usize), b: (MemAccess, usize)) -> bool {
+  let ((a, a_version), (b, b_version)) = (a, b);
+  if a.base != b.base || a_version != b_version || a.lo || b.lo {
+    return true;
+  }
+  let (a_end, b_end) = (a.offset + a.width as i32, b.offset + b.width as i32);
+  a.offset < b_end && b.offset < a_end
+}
+
+/// 对一个不含控制流的指令序列做表调度，各指令附带的数据（来源）随指令移动
+fn schedule_region<T>(insts: Vec<(Inst, T)>, cpu: Cpu) -> Vec<(Inst, T)> {
+  let n = insts.len();
+  if n <= 2 {
+    return insts;
+  }
+  let defs: Vec<_> = insts.iter().map(|(inst, _)| inst.defs()).collect();
+  let uses: Vec<_> = insts.iter().map(|(inst, _)| inst.uses()).collect();
+  let latency: Vec<_> = insts.iter().map(|(inst, _)| cpu.latency(inst)).collect();
+
+  // 访存指令的基址版本
+  let mut versions = [0usize; 32];
+  let mut mems = vec![];
+  for (i, (inst, _)) in insts.iter().enumerate() {
+    mems.push(inst.mem().map(|m| (m, versions[m.base as usize])));
+    for &r in &defs[i] {
+      versions[r as usize] += 1;
+    }
+  }
+
+  // preds[i]：(前驱, 延迟)
+  let mut preds: Vec<Vec<(usize, u32)>> = vec![vec![]; n];
+  for i in 0..n {
+    for j in 0..i {
+      let hits = |a: &[Reg], b: &[Reg]| a.iter().any(|r| *r != Reg::Zero && b.contains(r));
+      let mut edge = None;
+      if hits(&defs[j], &uses[i]) {
+        edge = Some(latency[j]);
+      } else if hits(&defs[j], &defs[i]) {
+        edge = Some(1);
+      } else if hits(&uses[j], &defs[i]) {
+        edge = Some(0);
+      }
+      if let (Some(a), Some(b)) = (mems[j], mems[i]) {
+        if (a.0.store || b.0.store) && may_alias(a, b) {
+          let mem = if a.0.store { 1 } else { 0 };
+          edge = Some(edge.map_or(mem, |e: u32| e.max(mem)));
+        }
+      }
+      if let Some(latency) = edge {
+        preds[i].push((j, latency));
+      }
+    }
+  }
+
+  // 关键路径长度
+  let mut height = latency.clone();
+  for i in (0..n).rev() {
+    for &(p, l) in &preds[i] {
+      height[p] = height[p].max(l + height[i]);
+    }
+  }
+
+  let mut remaining: Vec<usize> = preds.iter().map(Vec::len).collect();
+  let mut succs: Vec<Vec<usize>> = vec![vec![]; n];
+  for (i, ps) in preds.iter().enumerate() {
+    for &(p, _) in ps {
+      succs[p].push(i);
+    }
+  }
+  let mut earliest = vec![0u32; n];
+  let mut ready: Vec<usize> = (0..n).filter(|&i| remaining[i] == 0).collect();
+  let mut order = vec![];
+  let mut cycle = 0;
+  while !ready.is_empty() {
+    // 优先选择操作数已就绪、关键路径最长的指令；都未就绪时选最早可发射的
+    let pick = ready
+      .iter()
+      .copied()
+      .filter(|&i| earliest[i] <= cycle)
+      .max_by_key(|&i| (height[i], std::cmp::Reverse(i)))
+      .unwrap_or_else(|| {
+        *ready
+          .iter()
+          .min_by_key(|&&i| (earliest[i], std::cmp::Reverse(height[i]), i))
+          .unwrap()
+      });
+    ready.retain(|&i| i != pick);
+    cycle = cycle.max(earliest[pick]);
+    order.push(pick);
+    for &s in &succs[pick] {
+      let l = preds[s].iter().find(|&&(p, _)| p == pick).unwrap().1;
+      earliest[s] = earliest[s].max(cycle + l);
+      remaining[s] -= 1;
+      if remaining[s] == 0 {
+        ready.push(s);
+      }
+    }
+    cycle += 1;
+  }
+
+  let mut insts: Vec<_> = insts.into_iter().map(Some).collect();
+  order
+    .into_iter()
+    .map(|i| insts[i].take().unwrap())
+    .collect()
+}
+
+/// 调度后的指令序列，来源与前一条指令不同时在其前重新给出来源标记
+#[derive(Default)]
+struct Output {
+  items: Vec<RiscvItem>,
+  /// 最近给出的来源标记
+  origin: Option<Arc<Origin>>,
+}
+
+impl Output {
+  fn push_inst(&mut self, inst: Inst, origin: Option<Arc<Origin>>) {
+    let same = |a: &Arc<Origin>| self.origin.as_ref().is_some_and(|b| Arc::ptr_eq(a, b));
+    if let Some(origin) = origin.filter(|origin| !same(origin)) {
+      self
+        .items
+        .push(RiscvItem::Directive(Directive::Origin(origin.clone())));
+      self.origin = Some(origin);
+    }
+    self.items.push(RiscvItem::Inst(inst));
+  }
+
+  fn flush(&mut self, region: &mut Vec<(Inst, Option<Arc<Origin>>)>, cpu: Cpu) {
+    for (inst, origin) in schedule_region(std::mem::take(region), cpu) {
+      self.push_inst(inst, origin);
+    }
+  }
+}
+
+/// 在基本块内重排指令，使访存与乘除法的结果远离其使用者。来源标记（`--source-map`）
+/// 随其后的指令移动
+pub fn pass_schedule(old: &Riscv, cpu: Cpu) -> Riscv {
+  let mut result = Output::default();
+  let mut region = vec![];
+  // 当前指令的来源
+  let mut origin = None;
+  for item in &old.0 {
+    match item {
+      RiscvItem::Directive(Directive::Origin(id)) => origin = Some(id.clone()),
+      RiscvItem::Inst(inst) if !inst.is_control() => {
+        region.push((inst.clone(), origin.clone()));
+        if region.len() == MAX_REGION {
+          result.flush(&mut region, cpu);
+        }
+      }
+      RiscvItem::Inst(inst) => {
+        result.flush(&mut region, cpu);
+        result.push_inst(inst.clone(), origin.clone());
+      }
+      _ => {
+        result.flush(&mut region, cpu);
+        // 标号之后的指令在新的来源标记之前没有来源
+        if let RiscvItem::Label(_) = item {
+          (origin, result.origin) = (None, None);
+        }
+        result.items.push(item.clone());
+      }
+    }
+  }
+  result.flush(&mut region, cpu);
+  Riscv(result.items)
+}
@@ -0,0 +1,61 @@
+//! 指令选择之后并行地处理各个函数：函数之间相互独立的阶段（寄存器分配、合法化与指令调度）
+//! 分给多个线程，结果按函数原来的顺序合并，因此输出与单线程编译完全相同。
+//!
+//! Koopa IR（`koopa::ir::Program`）以 `Rc` 共享数据，不能跨线程访问，生成 IR 与指令选择仍在
+//! 当前线程中依次进行，并不并行。
+//!
+//! 线程数是每次编译的选项（[`CompileOptions::jobs`](crate::CompileOptions::jobs)、`--jobs`），
+//! 由调用者逐次传入，同时进行的各次编译互不影响。
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// 线程数 `jobs` 实际对应的线程数：0 即处理器的核数，无法得知核数时（如 WebAssembly）为 1
+pub fn threads(jobs: usize) -> usize {
+  match jobs {
+    0 => thread::available_parallelism().map_or(1, |n| n.get()),
+    jobs => jobs,
+  }
+}
+
+/// 以至多 `jobs` 个线程（见 [`threads`]，1 即不创建线程）用 `f` 变换 `items` 中的各项，结果的
+/// 顺序与 `items` 相同。各线程每次取下一个尚未处理的项，因此大小悬殊的函数也能均匀地分配；
+/// `f` 中的 panic 传回当前线程
+pub fn map<T: Send, U: Send>(jobs: usize, items: Vec<T>, f: impl Fn(T) -> U + Sync) -> Vec<U> {
+  let jobs = threads(jobs).min(items.len());
+  if jobs <= 1 {
+    return items.into_iter().map(f).collect();
+  }
+  let len = items.len();
+  let items: Vec<_> = items
+    .into_iter()
+    .map(|item| Mutex::new(Some(item)))
+    .collect();
+  let next = AtomicUsize::new(0);
+  let worker = || {
+    let mut done = vec![];
+    loop {
+      let i = next.fetch_add(1, Ordering::Relaxed);
+      let Some(item) = items.get(i) else {
+        return done;
+      };
+      let item = item.lock().unwrap().take().unwrap();
+      done.push((i, f(item)));
+    }
+  };
+  let mut results: Vec<Option<U>> = (0..len).map(|_| None).collect();
+  thread::scope(|s| {
+    let workers: Vec<_> = (0..jobs).map(|_| s.spawn(worker)).collect();
+    for worker in workers {
+      let done = worker
+        .join()
+        .unwrap_or_else(|payload| panic::resume_unwind(payload));
+      for (i, result) in done {
+        results[i] = Some(result);
+      }
+    }
+  });
+  results.into_iter().map(Option::unwrap).collect()
+}
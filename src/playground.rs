@@ -0,0 +1,93 @@
+//! 浏览器中的在线演示：库以 `--no-default-features` 编译为 `wasm32-unknown-unknown` 之后，
+//! 由 `playground/sysyc.js` 加载，经由 [`sysy_playground_compile`] 调用 [`compile`]。
+//!
+//! 依赖中没有 `wasm-bindgen`，因此导出的是与 [`crate::ffi`] 相同的 C 接口，字符串经由
+//! [`sysy_alloc`] 分配的线性内存传递。
+
+use std::ffi::{c_char, c_int};
+use std::ptr;
+
+use crate::diagnostics::{Diagnostic, DiagnosticEmitter, JsonEmitter};
+use crate::ffi::{self, SysyBuffer, SYSY_COMPILE_ERROR, SYSY_INVALID_ARGUMENT, SYSY_OK};
+use crate::{frontend, CompileOptions};
+
+/// 可选的输出，与命令行上同名的选项相同
+pub const MODES: &[&str] = &["koopa", "riscv", "perf", "ast", "ast-json", "tokens"];
+
+/// 以 `mode`（取自 [`MODES`]）编译 `source`，得到输出的文本；失败时得到诊断，每行一个 JSON 对象
+/// （与 `--error-format=json` 相同）
+pub fn compile(source: &str, mode: &str) -> Result<String, String> {
+  let mut options = CompileOptions::default();
+  let result = match mode {
+    "koopa" => crate::compile_to_koopa(source, &options).and_then(|ir| crate::koopa_text(&ir)),
+    "riscv" => crate::compile_to_riscv(source, &options),
+    "perf" => {
+      options.passes = vec!["peephole".into(), "schedule".into()];
+      crate::compile_to_riscv(source, &options)
+    }
+    "ast" => frontend::dump_ast(source),
+    "ast-json" => frontend::dump_ast_json(source),
+    "tokens" => frontend::dump_tokens(source),
+    _ => Err(format!("unknown mode '{}': expect {}", mode, MODES.join(", ")).into()),
+  };
+  let mut diagnostics = vec![];
+  let mut emitter = JsonEmitter::new(&mut diagnostics);
+  match result {
+    Ok(text) => return Ok(text),
+    // 源程序的错误重新分析得到位置
+    Err(e) if MODES.contains(&mode) => {
+      crate::report::<()>(Err(e), source, &options, &mut emitter);
+    }
+    Err(e) => emitter.emit(&Diagnostic::from_error(e.as_ref())),
+  }
+  Err(String::from_utf8_lossy(&diagnostics).into_owned())
+}
+
+/// 分配 `len` 字节的线性内存，供 JavaScript 写入字符串等参数；以 [`sysy_dealloc`] 释放
+#[no_mangle]
+pub extern "C" fn sysy_alloc(len: usize) -> *mut u8 {
+  Box::into_raw(vec![0u8; len].into_boxed_slice()) as *mut u8
+}
+
+/// 释放 [`sysy_alloc`] 分配的内存
+///
+/// # Safety
+///
+/// `data` 须为 [`sysy_alloc`] 以同样的 `len` 分配、尚未释放的内存。
+#[no_mangle]
+pub unsafe extern "C" fn sysy_dealloc(data: *mut u8, len: usize) {
+  drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+}
+
+/// 以 `mode` 编译 `source`，结果或诊断写入 `out_buf`，返回 `SYSY_OK`、`SYSY_COMPILE_ERROR` 等状态码
+///
+/// # Safety
+///
+/// `source` 与 `mode` 须为以 NUL 结尾的字符串；`out_buf` 须为空指针或可写。
+#[no_mangle]
+pub unsafe extern "C" fn sysy_playground_compile(
+  source: *const c_char,
+  mode: *const c_char,
+  out_buf: *mut SysyBuffer,
+) -> c_int {
+  ffi::run(out_buf, || {
+    let args = ffi::optional_str(source, "source").and_then(|source| {
+      let mode = ffi::optional_str(mode, "mode")?;
+      source
+        .zip(mode)
+        .ok_or_else(|| "source and mode must not be null".into())
+    });
+    let (source, mode) = match args {
+      Ok(args) => args,
+      Err(e) => return (SYSY_INVALID_ARGUMENT, e),
+    };
+    if !MODES.contains(&mode) {
+      let message = format!("unknown mode '{}': expect {}", mode, MODES.join(", "));
+      return (SYSY_INVALID_ARGUMENT, message);
+    }
+    match compile(source, mode) {
+      Ok(text) => (SYSY_OK, text),
+      Err(diagnostics) => (SYSY_COMPILE_ERROR, diagnostics),
+    }
+  })
+}
@@ -0,0 +1,537 @@
+//! 随机生成类型正确、必定终止且没有未定义行为的 SysY 程序，供 `sysyc gen` 做差分测试。
+//!
+//! 程序含全局变量与数组、带数组参数的函数、有界的循环、`break` 与 `continue`。生成的程序同时
+//! 是合法的 C 程序，在参考编译器上应得到相同的结果：
+//!
+//! - 除数是 0 与 -1 以外的常数，下标取模后总在范围内，所有的变量都在声明时初始化；
+//! - 有符号溢出按补码回绕，参考编译器须以 `-fwrapv` 编译；
+//! - 函数不改写全局变量与数组参数，也不输出，表达式中的调用没有副作用，求值顺序不影响结果；
+//! - 全局的初始化器与数组长度只用字面量，C 的常量表达式不能引用 `const` 变量。
+//!
+//! 循环次数与调用都计入执行的代价，代价有上限，程序很快就能运行结束。
+
+/// 表达式的最大深度
+const MAX_EXP_DEPTH: usize = 4;
+/// 语句的最大嵌套层数
+const MAX_BLOCK_DEPTH: usize = 3;
+/// 每个函数中循环的最大嵌套层数与每层的最大次数
+const MAX_LOOP_DEPTH: usize = 2;
+const MAX_TRIPS: usize = 6;
+/// 每个函数一次执行的代价上限：执行一条语句的代价为 1，调用的代价是被调函数的代价
+const MAX_COST: usize = 5000;
+/// 数组的最大长度
+const MAX_ARRAY_LEN: usize = 8;
+
+/// splitmix64 伪随机数；同一个种子总是生成同一个程序
+struct Rng(u64);
+
+impl Rng {
+  fn next(&mut self) -> u64 {
+    self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.0;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  /// `[0, n)` 中的随机数
+  fn below(&mut self, n: usize) -> usize {
+    (self.next() % n as u64) as usize
+  }
+
+  /// `[lo, hi]` 中的随机数
+  fn range(&mut self, lo: usize, hi: usize) -> usize {
+    lo + self.below(hi - lo + 1)
+  }
+
+  /// 以 `percent`% 的概率为真
+  fn chance(&mut self, percent: u64) -> bool {
+    self.next() % 100 < percent
+  }
+
+  fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+    &items[self.below(items.len())]
+  }
+}
+
+/// 数组的名字与各维的长度
+#[derive(Clone)]
+struct Array {
+  name: String,
+  dims: Vec<usize>,
+}
+
+/// 已生成的函数
+struct Func {
+  name: String,
+  /// 整数参数的个数
+  params: usize,
+  /// 一维数组参数至少的长度
+  array: Option<usize>,
+  /// 一次调用的代价
+  cost: usize,
+}
+
+/// 函数体中可以使用的名字；离开语句块时截断为进入时的长度
+#[derive(Default)]
+struct Scope {
+  /// 可以读写的整数变量
+  vars: Vec<String>,
+  /// 常量，可以用在常量表达式中
+  consts: Vec<String>,
+  /// 循环变量，只读
+  counters: Vec<String>,
+  /// 可以读写的数组
+  arrays: Vec<Array>,
+  /// 只读的数组，即数组参数
+  params: Vec<Array>,
+}
+
+impl Scope {
+  fn marks(&self) -> [usize; 5] {
+    [
+      self.vars.len(),
+      self.consts.len(),
+      self.counters.len(),
+      self.arrays.len(),
+      self.params.len(),
+    ]
+  }
+
+  fn restore(&mut self, [vars, consts, counters, arrays, params]: [usize; 5]) {
+    self.vars.truncate(vars);
+    self.consts.truncate(consts);
+    self.counters.truncate(counters);
+    self.arrays.truncate(arrays);
+    self.params.truncate(params);
+  }
+}
+
+struct Generator {
+  rng: Rng,
+  out: String,
+  indent: usize,
+  next_id: usize,
+  globals: Vec<String>,
+  global_consts: Vec<String>,
+  global_arrays: Vec<Array>,
+  funcs: Vec<Func>,
+  scope: Scope,
+  /// 只有 `main` 改写全局变量与数组、输出
+  in_main: bool,
+  /// 所在循环的层数与各层次数之积
+  loops: usize,
+  trips: usize,
+  /// 当前函数已生成部分的代价
+  cost: usize,
+}
+
+impl Generator {
+  fn line(&mut self, text: &str) {
+    self.out += &"  ".repeat(self.indent);
+    self.out += text;
+    self.out.push('\n');
+  }
+
+  fn name(&mut self, prefix: &str) -> String {
+    self.next_id += 1;
+    format!("{}{}", prefix, self.next_id)
+  }
+
+  fn literal(&mut self) -> String {
+    match self.rng.below(20) {
+      0 => "2147483647".into(),
+      // C 中的 2147483648 是更宽的类型
+      1 => "(-2147483647 - 1)".into(),
+      2 => format!("{}", self.rng.range(100, 100000)),
+      3 | 4 => format!("(-{})", self.rng.range(1, 20)),
+      _ => format!("{}", self.rng.range(0, 20)),
+    }
+  }
+
+  /// 除数：0 之外会除零，-1 之外会使最小的整数溢出
+  fn divisor(&mut self) -> String {
+    self
+      .rng
+      .pick(&["1", "2", "3", "7", "10", "16", "(-2)", "(-5)"])
+      .to_string()
+  }
+
+  /// 可以读的数组
+  fn readable_arrays(&self) -> Vec<Array> {
+    let arrays = self.scope.arrays.iter().chain(&self.scope.params);
+    arrays.chain(&self.global_arrays).cloned().collect()
+  }
+
+  /// 可以改写的数组
+  fn writable_arrays(&self) -> Vec<Array> {
+    let globals = self.global_arrays.iter().filter(|_| self.in_main);
+    self.scope.arrays.iter().chain(globals).cloned().collect()
+  }
+
+  /// 可以改写的整数变量
+  fn writable_vars(&self) -> Vec<String> {
+    let globals = self.globals.iter().filter(|_| self.in_main);
+    self.scope.vars.iter().chain(globals).cloned().collect()
+  }
+
+  /// 取模后落在 `[0, len)` 中的下标
+  fn index(&mut self, len: usize, depth: usize) -> String {
+    let exp = self.exp(depth.min(2));
+    format!("(({} % {len} + {len}) % {len})", exp, len = len)
+  }
+
+  fn element(&mut self, array: &Array, depth: usize) -> String {
+    let mut text = array.name.clone();
+    for &len in &array.dims {
+      text += &format!("[{}]", self.index(len, depth));
+    }
+    text
+  }
+
+  /// 字面量、变量或数组元素
+  fn atom(&mut self, depth: usize) -> String {
+    let mut names = self.scope.vars.clone();
+    names.extend(self.scope.consts.iter().cloned());
+    names.extend(self.scope.counters.iter().cloned());
+    names.extend(self.globals.iter().cloned());
+    names.extend(self.global_consts.iter().cloned());
+    let arrays = self.readable_arrays();
+    match self.rng.below(10) {
+      0..=2 => self.literal(),
+      3 if !arrays.is_empty() && depth > 0 => {
+        let array = self.rng.pick(&arrays).clone();
+        self.element(&array, depth - 1)
+      }
+      _ if !names.is_empty() => self.rng.pick(&names).clone(),
+      _ => self.literal(),
+    }
+  }
+
+  /// 调用已生成的函数，代价超出上限或没有合适的数组实参时为 `None`
+  fn call(&mut self, depth: usize) -> Option<String> {
+    let budget = MAX_COST.saturating_sub(self.cost) / self.trips;
+    let funcs: Vec<_> = (0..self.funcs.len())
+      .filter(|&i| self.funcs[i].cost <= budget)
+      .collect();
+    if funcs.is_empty() {
+      return None;
+    }
+    let func = &self.funcs[*self.rng.pick(&funcs)];
+    let (name, params, array, cost) = (func.name.clone(), func.params, func.array, func.cost);
+    let mut args: Vec<_> = (0..params).map(|_| self.exp(depth.min(2))).collect();
+    if let Some(len) = array {
+      let arrays = self.readable_arrays();
+      let fits: Vec<_> = arrays
+        .iter()
+        .filter(|a| a.dims.len() == 1 && a.dims[0] >= len)
+        .collect();
+      if fits.is_empty() {
+        return None;
+      }
+      args.push(self.rng.pick(&fits).name.clone());
+    }
+    self.cost += cost * self.trips;
+    Some(format!("{}({})", name, args.join(", ")))
+  }
+
+  fn exp(&mut self, depth: usize) -> String {
+    if depth == 0 || self.rng.chance(20) {
+      return self.atom(depth);
+    }
+    let d = depth - 1;
+    match self.rng.below(12) {
+      0..=2 => {
+        let op = self.rng.pick(&["+", "-", "*"]);
+        format!("({} {} {})", self.exp(d), op, self.exp(d))
+      }
+      3 => {
+        let op = self.rng.pick(&["/", "%"]);
+        format!("({} {} {})", self.exp(d), op, self.divisor())
+      }
+      4 | 5 => {
+        let op = self.rng.pick(&["<", "<=", ">", ">=", "==", "!="]);
+        format!("({} {} {})", self.exp(d), op, self.exp(d))
+      }
+      6 => {
+        let op = self.rng.pick(&["&&", "||"]);
+        format!("({} {} {})", self.exp(d), op, self.exp(d))
+      }
+      7 => {
+        let op = self.rng.pick(&["-", "!"]);
+        format!("({}{})", op, self.exp(d))
+      }
+      8 | 9 => self.call(d).unwrap_or_else(|| self.atom(d)),
+      _ => self.atom(depth),
+    }
+  }
+
+  /// 只含字面量与常量的常量表达式
+  fn const_exp(&mut self, depth: usize) -> String {
+    if depth == 0 || self.rng.chance(30) {
+      let mut names = self.scope.consts.clone();
+      names.extend(self.global_consts.iter().cloned());
+      return match names.is_empty() || self.rng.chance(50) {
+        true => self.literal(),
+        false => self.rng.pick(&names).clone(),
+      };
+    }
+    let op = self.rng.pick(&["+", "-", "*"]);
+    let (lhs, rhs) = (self.const_exp(depth - 1), self.const_exp(depth - 1));
+    format!("({} {} {})", lhs, op, rhs)
+  }
+
+  /// 数组的初始化器：每一维给出不超过长度的若干个元素，其余为零
+  fn initializer(
+    &mut self,
+    dims: &[usize],
+    element: &mut dyn FnMut(&mut Self) -> String,
+  ) -> String {
+    let Some((&len, rest)) = dims.split_first() else {
+      return element(self);
+    };
+    let count = self.rng.range(0, len);
+    let items: Vec<_> = (0..count)
+      .map(|_| self.initializer(rest, element))
+      .collect();
+    format!("{{{}}}", items.join(", "))
+  }
+
+  fn array_dims(&mut self) -> Vec<usize> {
+    match self.rng.chance(25) {
+      true => vec![self.rng.range(1, 3), self.rng.range(1, 4)],
+      false => vec![self.rng.range(1, MAX_ARRAY_LEN)],
+    }
+  }
+
+  fn dims_text(dims: &[usize]) -> String {
+    dims.iter().map(|len| format!("[{}]", len)).collect()
+  }
+
+  fn block(&mut self, depth: usize, count: usize) {
+    let marks = self.scope.marks();
+    self.indent += 1;
+    for _ in 0..count {
+      self.stmt(depth + 1);
+    }
+    self.indent -= 1;
+    self.scope.restore(marks);
+  }
+
+  fn stmt(&mut self, depth: usize) {
+    if self.cost + self.trips > MAX_COST {
+      return;
+    }
+    self.cost += self.trips;
+    let nested = depth < MAX_BLOCK_DEPTH;
+    match self.rng.below(14) {
+      0 | 1 => {
+        let name = self.name("v");
+        let init = self.exp(MAX_EXP_DEPTH);
+        self.line(&format!("int {} = {};", name, init));
+        self.scope.vars.push(name);
+      }
+      2 => {
+        let name = self.name("a");
+        let dims = self.array_dims();
+        let init = self.initializer(&dims, &mut |g| g.exp(2));
+        self.line(&format!(
+          "int {}{} = {};",
+          name,
+          Self::dims_text(&dims),
+          init
+        ));
+        self.scope.arrays.push(Array { name, dims });
+      }
+      3 => {
+        let name = self.name("c");
+        let init = self.const_exp(2);
+        self.line(&format!("const int {} = {};", name, init));
+        self.scope.consts.push(name);
+      }
+      4 if nested => {
+        let cond = self.exp(MAX_EXP_DEPTH);
+        self.line(&format!("if ({}) {{", cond));
+        let count = self.rng.range(1, 3);
+        self.block(depth, count);
+        if self.rng.chance(50) {
+          self.line("} else {");
+          let count = self.rng.range(1, 3);
+          self.block(depth, count);
+        }
+        self.line("}");
+      }
+      5 if nested && self.loops < MAX_LOOP_DEPTH => {
+        let trips = self.rng.range(1, MAX_TRIPS);
+        if self.cost + self.trips * trips > MAX_COST {
+          return;
+        }
+        // 计数在循环体的开头，`continue` 也不会跳过
+        let counter = self.name("i");
+        self.line(&format!("int {} = 0;", counter));
+        self.line(&format!("while ({} < {}) {{", counter, trips));
+        self.indent += 1;
+        self.line(&format!("{0} = {0} + 1;", counter));
+        self.indent -= 1;
+        self.scope.counters.push(counter);
+        let (loops, outer) = (self.loops, self.trips);
+        self.loops += 1;
+        self.trips *= trips;
+        let count = self.rng.range(1, 4);
+        self.block(depth, count);
+        self.loops = loops;
+        self.trips = outer;
+        self.line("}");
+      }
+      6 if self.loops > 0 => {
+        let cond = self.exp(2);
+        let jump = self.rng.pick(&["break", "continue"]);
+        self.line(&format!("if ({}) {};", cond, jump));
+      }
+      7 | 8 if self.in_main => {
+        let value = self.exp(MAX_EXP_DEPTH);
+        let sep = self.rng.pick(&["10", "32"]);
+        self.line(&format!("putint({});", value));
+        self.line(&format!("putch({});", sep));
+      }
+      9 | 10 => {
+        let arrays = self.writable_arrays();
+        if arrays.is_empty() {
+          return;
+        }
+        let array = self.rng.pick(&arrays).clone();
+        let lhs = self.element(&array, 2);
+        let rhs = self.exp(MAX_EXP_DEPTH);
+        self.line(&format!("{} = {};", lhs, rhs));
+      }
+      11 if nested => {
+        self.line("{");
+        let count = self.rng.range(1, 3);
+        self.block(depth, count);
+        self.line("}");
+      }
+      _ => {
+        let vars = self.writable_vars();
+        if vars.is_empty() {
+          return;
+        }
+        let lhs = self.rng.pick(&vars).clone();
+        let rhs = self.exp(MAX_EXP_DEPTH);
+        self.line(&format!("{} = {};", lhs, rhs));
+      }
+    }
+  }
+
+  fn globals(&mut self) {
+    for _ in 0..self.rng.range(1, 4) {
+      let name = self.name("g");
+      let init = self.literal();
+      self.line(&format!("int {} = {};", name, init));
+      self.globals.push(name);
+    }
+    for _ in 0..self.rng.range(0, 2) {
+      let name = self.name("k");
+      let init = self.literal();
+      self.line(&format!("const int {} = {};", name, init));
+      self.global_consts.push(name);
+    }
+    for _ in 0..self.rng.range(0, 2) {
+      let name = self.name("G");
+      let dims = self.array_dims();
+      let init = self.initializer(&dims, &mut Self::literal);
+      self.line(&format!(
+        "int {}{} = {};",
+        name,
+        Self::dims_text(&dims),
+        init
+      ));
+      self.global_arrays.push(Array { name, dims });
+    }
+  }
+
+  fn func(&mut self) {
+    let name = self.name("f");
+    let params = self.rng.range(0, 3);
+    let array = self
+      .rng
+      .chance(40)
+      .then(|| self.rng.range(1, MAX_ARRAY_LEN));
+    self.scope = Scope::default();
+    self.scope.vars = (0..params).map(|_| self.name("p")).collect();
+    let mut decls: Vec<_> = self
+      .scope
+      .vars
+      .iter()
+      .map(|p| format!("int {}", p))
+      .collect();
+    if let Some(len) = array {
+      let param = self.name("q");
+      decls.push(format!("int {}[]", param));
+      self.scope.params.push(Array {
+        name: param,
+        dims: vec![len],
+      });
+    }
+    self.line(&format!("int {}({}) {{", name, decls.join(", ")));
+    self.cost = 0;
+    let count = self.rng.range(1, 6);
+    self.block(0, count);
+    self.indent += 1;
+    let value = self.exp(MAX_EXP_DEPTH);
+    self.line(&format!("return {};", value));
+    self.indent -= 1;
+    self.line("}");
+    self.funcs.push(Func {
+      name,
+      params,
+      array,
+      cost: self.cost + 1,
+    });
+  }
+
+  fn main(&mut self) {
+    self.in_main = true;
+    self.scope = Scope::default();
+    self.cost = 0;
+    self.line("int main() {");
+    let count = self.rng.range(4, 12);
+    self.block(0, count);
+    self.indent += 1;
+    // 最后输出全局变量，检查对它们的改写
+    for global in self.globals.clone() {
+      self.line(&format!("putint({});", global));
+      self.line("putch(10);");
+    }
+    let value = self.exp(MAX_EXP_DEPTH);
+    self.line(&format!("return {};", value));
+    self.indent -= 1;
+    self.line("}");
+  }
+}
+
+/// 由 `seed` 生成一个程序
+pub fn program(seed: u64) -> String {
+  let mut generator = Generator {
+    rng: Rng(seed),
+    out: format!("// sysyc gen --seed={}\n", seed),
+    indent: 0,
+    next_id: 0,
+    globals: vec![],
+    global_consts: vec![],
+    global_arrays: vec![],
+    funcs: vec![],
+    scope: Scope::default(),
+    in_main: false,
+    loops: 0,
+    trips: 1,
+    cost: 0,
+  };
+  generator.globals();
+  for _ in 0..generator.rng.range(0, 4) {
+    generator.out.push('\n');
+    generator.func();
+  }
+  generator.out.push('\n');
+  generator.main();
+  generator.out
+}
@@ -0,0 +1,803 @@
+//! 语法树、类型与诊断的 JSON 表示：`--emit=ast-json`、`--error-format=json` 与库的使用者共用这一格式。
+//!
+//! 语法树的节点是带有 `"kind"` 的对象，位置 `"pos"` 是字节偏移。只有一个子节点的文法层次
+//! （如 `AddExp::Mul`）不单独成为节点，与 `--emit=ast` 相同。格式有所改变（删去字段、改变字段的
+//! 含义）时 [`SCHEMA_VERSION`] 加一；只增加字段时不变。
+
+use std::fmt;
+
+use crate::diagnostics::{Diagnostic, Kind, Label, Severity};
+use crate::frontend::ast::*;
+use crate::frontend::sema::{SymbolInfo, SymbolKind};
+use crate::frontend::SourceMap;
+use crate::frontend::SysyType;
+
+/// 格式的版本，输出在顶层对象的 `"version"` 中
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+  Null,
+  Bool(bool),
+  Number(i64),
+  String(String),
+  Array(Vec<Json>),
+  /// 按插入的顺序输出各个字段
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Json {
+    let fields = fields
+      .into_iter()
+      .map(|(key, value)| (key.to_string(), value));
+    Json::Object(fields.collect())
+  }
+
+  /// 种类为 `kind` 的节点
+  fn node<'a>(kind: &str, fields: impl IntoIterator<Item = (&'a str, Json)>) -> Json {
+    Json::object([("kind", kind.into())].into_iter().chain(fields))
+  }
+
+  fn array<T: Serialize>(items: &[T]) -> Json {
+    Json::Array(items.iter().map(Serialize::serialize).collect())
+  }
+
+  /// 在对象末尾加上一个字段
+  pub fn push(&mut self, key: &str, value: Json) {
+    if let Json::Object(fields) = self {
+      fields.push((key.into(), value));
+    }
+  }
+
+  /// 解析 JSON 文本（如语言服务器收到的消息）。数只取整数部分
+  pub fn parse(text: &str) -> Result<Json, String> {
+    let mut parser = Parser { text, pos: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.pos == text.len() {
+      true => Ok(value),
+      false => Err(parser.error("trailing characters")),
+    }
+  }
+
+  /// 对象中名为 `key` 的字段
+  pub fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+      _ => None,
+    }
+  }
+
+  pub fn as_str(&self) -> Option<&str> {
+    match self {
+      Json::String(text) => Some(text),
+      _ => None,
+    }
+  }
+
+  pub fn as_array(&self) -> Option<&[Json]> {
+    match self {
+      Json::Array(items) => Some(items),
+      _ => None,
+    }
+  }
+
+  pub fn as_i64(&self) -> Option<i64> {
+    match self {
+      Json::Number(number) => Some(*number),
+      _ => None,
+    }
+  }
+
+  pub fn as_bool(&self) -> Option<bool> {
+    match self {
+      Json::Bool(value) => Some(*value),
+      _ => None,
+    }
+  }
+}
+
+/// [`Json::parse`] 的递归下降分析
+struct Parser<'a> {
+  text: &'a str,
+  pos: usize,
+}
+
+impl Parser<'_> {
+  fn error(&self, message: &str) -> String {
+    format!("invalid JSON at {}: {}", self.pos, message)
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.text.as_bytes().get(self.pos).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+      self.pos += 1;
+    }
+  }
+
+  fn expect(&mut self, literal: &str) -> Result<(), String> {
+    match self.text[self.pos..].starts_with(literal) {
+      true => {
+        self.pos += literal.len();
+        Ok(())
+      }
+      false => Err(self.error(&format!("expected '{}'", literal))),
+    }
+  }
+
+  fn value(&mut self) -> Result<Json, String> {
+    self.skip_whitespace();
+    match self.peek() {
+      Some(b'n') => self.expect("null").map(|_| Json::Null),
+      Some(b't') => self.expect("true").map(|_| Json::Bool(true)),
+      Some(b'f') => self.expect("false").map(|_| Json::Bool(false)),
+      Some(b'"') => self.string().map(Json::String),
+      Some(b'[') => {
+        self.pos += 1;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+          self.pos += 1;
+          return Ok(Json::Array(items));
+        }
+        loop {
+          items.push(self.value()?);
+          self.skip_whitespace();
+          match self.peek() {
+            Some(b',') => self.pos += 1,
+            Some(b']') => {
+              self.pos += 1;
+              return Ok(Json::Array(items));
+            }
+            _ => return Err(self.error("expected ',' or ']'")),
+          }
+        }
+      }
+      Some(b'{') => {
+        self.pos += 1;
+        let mut fields = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+          self.pos += 1;
+          return Ok(Json::Object(fields));
+        }
+        loop {
+          self.skip_whitespace();
+          let key = self.string()?;
+          self.skip_whitespace();
+          self.expect(":")?;
+          fields.push((key, self.value()?));
+          self.skip_whitespace();
+          match self.peek() {
+            Some(b',') => self.pos += 1,
+            Some(b'}') => {
+              self.pos += 1;
+              return Ok(Json::Object(fields));
+            }
+            _ => return Err(self.error("expected ',' or '}'")),
+          }
+        }
+      }
+      Some(b'-' | b'0'..=b'9') => self.number(),
+      _ => Err(self.error("expected a value")),
+    }
+  }
+
+  fn number(&mut self) -> Result<Json, String> {
+    let start = self.pos;
+    let is_number = |c: u8| c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.' | b'e' | b'E');
+    while self.peek().is_some_and(is_number) {
+      self.pos += 1;
+    }
+    let text = &self.text[start..self.pos];
+    match text.parse::<i64>() {
+      Ok(number) => Ok(Json::Number(number)),
+      Err(_) => match text.parse::<f64>() {
+        Ok(number) => Ok(Json::Number(number as i64)),
+        Err(_) => Err(self.error("invalid number")),
+      },
+    }
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    self.expect("\"")?;
+    let mut result = String::new();
+    loop {
+      let Some(c) = self.text[self.pos..].chars().next() else {
+        return Err(self.error("unterminated string"));
+      };
+      self.pos += c.len_utf8();
+      match c {
+        '"' => return Ok(result),
+        '\\' => {
+          let Some(escape) = self.peek() else {
+            return Err(self.error("unterminated string"));
+          };
+          self.pos += 1;
+          match escape {
+            b'"' => result.push('"'),
+            b'\\' => result.push('\\'),
+            b'/' => result.push('/'),
+            b'b' => result.push('\u{8}'),
+            b'f' => result.push('\u{c}'),
+            b'n' => result.push('\n'),
+            b'r' => result.push('\r'),
+            b't' => result.push('\t'),
+            b'u' => {
+              let mut code = self.hex4()?;
+              // UTF-16 的代理对
+              if (0xd800..0xdc00).contains(&code) && self.text[self.pos..].starts_with("\\u") {
+                self.pos += 2;
+                let low = self.hex4()?;
+                code = 0x10000 + ((code - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff);
+              }
+              result.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            _ => return Err(self.error("invalid escape")),
+          }
+        }
+        c => result.push(c),
+      }
+    }
+  }
+
+  fn hex4(&mut self) -> Result<u32, String> {
+    let digits = self.text.get(self.pos..self.pos + 4);
+    let code = digits.and_then(|digits| u32::from_str_radix(digits, 16).ok());
+    let code = code.ok_or_else(|| self.error("invalid unicode escape"))?;
+    self.pos += 4;
+    Ok(code)
+  }
+}
+
+impl From<&str> for Json {
+  fn from(text: &str) -> Self {
+    Json::String(text.into())
+  }
+}
+
+impl From<usize> for Json {
+  fn from(number: usize) -> Self {
+    Json::Number(number as i64)
+  }
+}
+
+impl From<String> for Json {
+  fn from(text: String) -> Self {
+    Json::String(text)
+  }
+}
+
+impl From<bool> for Json {
+  fn from(value: bool) -> Self {
+    Json::Bool(value)
+  }
+}
+
+impl From<i32> for Json {
+  fn from(number: i32) -> Self {
+    Json::Number(number as i64)
+  }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+  fn from(value: Option<T>) -> Self {
+    value.map_or(Json::Null, Into::into)
+  }
+}
+
+/// JSON 字符串字面量
+fn quote(text: &str) -> String {
+  let mut quoted = String::from('"');
+  for c in text.chars() {
+    match c {
+      '"' => quoted += "\\\"",
+      '\\' => quoted += "\\\\",
+      '\n' => quoted += "\\n",
+      '\t' => quoted += "\\t",
+      c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+      c => quoted.push(c),
+    }
+  }
+  quoted.push('"');
+  quoted
+}
+
+/// 不含空白的紧凑格式，每个值占一行
+impl fmt::Display for Json {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Json::Null => write!(f, "null"),
+      Json::Bool(value) => write!(f, "{}", value),
+      Json::Number(number) => write!(f, "{}", number),
+      Json::String(text) => write!(f, "{}", quote(text)),
+      Json::Array(items) => {
+        let items: Vec<_> = items.iter().map(Json::to_string).collect();
+        write!(f, "[{}]", items.join(","))
+      }
+      Json::Object(fields) => {
+        let fields: Vec<_> = fields
+          .iter()
+          .map(|(key, value)| format!("{}:{}", quote(key), value))
+          .collect();
+        write!(f, "{{{}}}", fields.join(","))
+      }
+    }
+  }
+}
+
+pub trait Serialize {
+  fn serialize(&self) -> Json;
+}
+
+impl<T: Serialize> Serialize for Box<T> {
+  fn serialize(&self) -> Json {
+    self.as_ref().serialize()
+  }
+}
+
+/// 语法树中的节点，子节点从 `ast` 中取得
+pub trait SerializeNode {
+  fn serialize(&self, ast: &Ast) -> Json;
+}
+
+impl<T: SerializeNode + Node> SerializeNode for Id<T> {
+  fn serialize(&self, ast: &Ast) -> Json {
+    ast[*self].serialize(ast)
+  }
+}
+
+/// 各个节点构成的数组
+fn nodes<T: SerializeNode>(ast: &Ast, nodes: &[T]) -> Json {
+  Json::Array(nodes.iter().map(|node| node.serialize(ast)).collect())
+}
+
+/// 一个源文件的语法树：`{"version":1,"ast":[声明...]}`。结果与表达式嵌套得一样深，其输出与
+/// 释放都是递归的；只要文本时用 [`crate::frontend::dump_ast_json`]，它在足够大的栈上完成这些
+pub fn serialize_ast(ast: &Ast) -> Json {
+  Json::object([
+    ("version", Json::Number(SCHEMA_VERSION as i64)),
+    ("ast", nodes(ast, &ast.unit)),
+  ])
+}
+
+/// 属性是只有 `"kind"` 的节点，`section` 另有节的名字 `"name"`
+fn attribute(attr: &Attribute) -> Json {
+  match attr {
+    Attribute::Section(section) => Json::node("section", [("name", section.as_str().into())]),
+    _ => Json::node(attr.name(), []),
+  }
+}
+
+fn type_spec(ty: TypeSpec) -> Json {
+  match ty {
+    TypeSpec::Void => "void".into(),
+    TypeSpec::Int => "int".into(),
+  }
+}
+
+impl SerializeNode for Id<Decl> {
+  fn serialize(&self, ast: &Ast) -> Json {
+    let pos = ast.pos(*self);
+    match &ast[*self] {
+      Decl::Var(decl) => {
+        let defs = decl.list.iter().map(|(declarator, init)| {
+          let init = init.map_or(Json::Null, |init| init.serialize(ast));
+          Json::object([("declarator", declarator.serialize(ast)), ("init", init)])
+        });
+        Json::node(
+          if decl.is_const { "const" } else { "var" },
+          [
+            ("type", type_spec(decl.ty)),
+            ("volatile", decl.is_volatile.into()),
+            ("pos", pos.into()),
+            ("defs", Json::Array(defs.collect())),
+          ],
+        )
+      }
+      Decl::Func(func) => {
+        let params: Vec<_> = func.params.iter().map(|&(_, param)| param).collect();
+        let body = func
+          .body
+          .as_ref()
+          .map_or(Json::Null, |body| nodes(ast, body));
+        Json::node(
+          "func",
+          [
+            ("type", type_spec(func.func_type)),
+            ("name", func.ident.as_str().into()),
+            ("pos", pos.into()),
+            (
+              "param_types",
+              Json::Array(func.params.iter().map(|&(ty, _)| type_spec(ty)).collect()),
+            ),
+            ("params", nodes(ast, &params)),
+            (
+              "attributes",
+              Json::Array(func.attrs.iter().map(attribute).collect()),
+            ),
+            ("body", body),
+          ],
+        )
+      }
+    }
+  }
+}
+
+impl SerializeNode for Declarator {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      Declarator::Ident(ident) => Json::node("ident", [("name", ident.as_str().into())]),
+      Declarator::Pointer(base) => Json::node("pointer", [("base", base.serialize(ast))]),
+      Declarator::Array(base, len) => Json::node(
+        "array",
+        [("base", base.serialize(ast)), ("len", len.serialize(ast))],
+      ),
+    }
+  }
+}
+
+impl SerializeNode for Initializer {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      Initializer::Simple(exp) => exp.serialize(ast),
+      Initializer::Aggregate(list) => {
+        let items = list.iter().map(|init| init.serialize(ast));
+        Json::node("init-list", [("items", Json::Array(items.collect()))])
+      }
+    }
+  }
+}
+
+impl SerializeNode for BlockItem {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      BlockItem::Decl(decl) => decl.serialize(ast),
+      BlockItem::Stmt(stmt) => stmt.serialize(ast),
+    }
+  }
+}
+
+impl SerializeNode for AsmOperand {
+  fn serialize(&self, ast: &Ast) -> Json {
+    let (constraint, exp) = self;
+    Json::object([
+      ("constraint", constraint.as_str().into()),
+      ("exp", exp.serialize(ast)),
+    ])
+  }
+}
+
+impl SerializeNode for Id<Stmt> {
+  fn serialize(&self, ast: &Ast) -> Json {
+    let optional = |exp: &Option<Id<Exp>>| exp.map_or(Json::Null, |exp| exp.serialize(ast));
+    let pos: Json = ast.pos(*self).into();
+    match &ast[*self] {
+      Stmt::Exp(exp) => Json::node("exp", [("pos", pos), ("exp", optional(exp))]),
+      Stmt::Block(block) => Json::node("block", [("items", nodes(ast, block))]),
+      Stmt::If(cond, then, els) => Json::node(
+        "if",
+        [
+          ("pos", pos),
+          ("cond", cond.serialize(ast)),
+          ("then", then.serialize(ast)),
+          ("else", els.map_or(Json::Null, |els| els.serialize(ast))),
+        ],
+      ),
+      Stmt::While(cond, body) => Json::node(
+        "while",
+        [
+          ("pos", pos),
+          ("cond", cond.serialize(ast)),
+          ("body", body.serialize(ast)),
+        ],
+      ),
+      Stmt::For(init, cond, step, body) => Json::node(
+        "for",
+        [
+          ("pos", pos),
+          ("init", init.serialize(ast)),
+          ("cond", optional(cond)),
+          ("step", optional(step)),
+          ("body", body.serialize(ast)),
+        ],
+      ),
+      Stmt::Break => Json::node("break", [("pos", pos)]),
+      Stmt::Continue => Json::node("continue", [("pos", pos)]),
+      Stmt::Return(exp) => Json::node("return", [("pos", pos), ("exp", optional(exp))]),
+      Stmt::Asm(asm) => Json::node(
+        "asm",
+        [
+          ("pos", pos),
+          ("template", asm.template.as_str().into()),
+          ("outputs", nodes(ast, &asm.outputs)),
+          ("inputs", nodes(ast, &asm.inputs)),
+        ],
+      ),
+    }
+  }
+}
+
+/// 二元运算 `{"kind":"binary","op":"+","lhs":...,"rhs":...}`
+fn binary(op: &str, lhs: Json, rhs: Json) -> Json {
+  Json::node("binary", [("op", op.into()), ("lhs", lhs), ("rhs", rhs)])
+}
+
+impl SerializeNode for Exp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      Exp::Assign(exp) => exp.serialize(ast),
+      Exp::Comma(lhs, rhs) => binary(",", lhs.serialize(ast), rhs.serialize(ast)),
+    }
+  }
+}
+
+impl SerializeNode for AssignExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      AssignExp::LOr(exp) => exp.serialize(ast),
+      AssignExp::Assign(lhs, rhs) => binary("=", lhs.serialize(ast), rhs.serialize(ast)),
+      AssignExp::Compound(lhs, op, rhs) => {
+        let op = match op {
+          CompoundOp::Add(AddOp::Plus) => "+=",
+          CompoundOp::Add(AddOp::Minus) => "-=",
+          CompoundOp::Mul(MulOp::Multiply) => "*=",
+          CompoundOp::Mul(MulOp::Divide) => "/=",
+          CompoundOp::Mul(MulOp::Modulo) => "%=",
+        };
+        binary(op, lhs.serialize(ast), rhs.serialize(ast))
+      }
+      AssignExp::Cond(cond, then, els) => Json::node(
+        "conditional",
+        [
+          ("cond", cond.serialize(ast)),
+          ("then", then.serialize(ast)),
+          ("else", els.serialize(ast)),
+        ],
+      ),
+    }
+  }
+}
+
+impl SerializeNode for LOrExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      LOrExp::And(exp) => exp.serialize(ast),
+      LOrExp::Or(lhs, rhs) => binary("||", lhs.serialize(ast), rhs.serialize(ast)),
+    }
+  }
+}
+
+impl SerializeNode for LAndExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      LAndExp::Eq(exp) => exp.serialize(ast),
+      LAndExp::And(lhs, rhs) => binary("&&", lhs.serialize(ast), rhs.serialize(ast)),
+    }
+  }
+}
+
+impl SerializeNode for EqExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      EqExp::Rel(exp) => exp.serialize(ast),
+      EqExp::Eq(lhs, op, rhs) => {
+        let op = match op {
+          EqOp::Equal => "==",
+          EqOp::NotEqual => "!=",
+        };
+        binary(op, lhs.serialize(ast), rhs.serialize(ast))
+      }
+    }
+  }
+}
+
+impl SerializeNode for RelExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      RelExp::Add(exp) => exp.serialize(ast),
+      RelExp::Rel(lhs, op, rhs) => {
+        let op = match op {
+          RelOp::Less => "<",
+          RelOp::LessEqual => "<=",
+          RelOp::Greater => ">",
+          RelOp::GreaterEqual => ">=",
+        };
+        binary(op, lhs.serialize(ast), rhs.serialize(ast))
+      }
+    }
+  }
+}
+
+impl SerializeNode for AddExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      AddExp::Mul(exp) => exp.serialize(ast),
+      AddExp::Add(lhs, op, rhs) => {
+        let op = match op {
+          AddOp::Plus => "+",
+          AddOp::Minus => "-",
+        };
+        binary(op, lhs.serialize(ast), rhs.serialize(ast))
+      }
+    }
+  }
+}
+
+impl SerializeNode for MulExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      MulExp::Unary(exp) => exp.serialize(ast),
+      MulExp::Mul(lhs, op, rhs) => {
+        let op = match op {
+          MulOp::Multiply => "*",
+          MulOp::Divide => "/",
+          MulOp::Modulo => "%",
+        };
+        binary(op, lhs.serialize(ast), rhs.serialize(ast))
+      }
+    }
+  }
+}
+
+impl SerializeNode for UnaryExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    let unary = |op: &str, exp: &Id<UnaryExp>| {
+      Json::node(
+        "unary",
+        [("op", op.into()), ("operand", exp.serialize(ast))],
+      )
+    };
+    match self {
+      UnaryExp::Postfix(exp) => exp.serialize(ast),
+      UnaryExp::Address(exp) => unary("&", exp),
+      UnaryExp::Deref(exp) => unary("*", exp),
+      UnaryExp::Op(UnaryOp::Positive, exp) => unary("+", exp),
+      UnaryExp::Op(UnaryOp::Negative, exp) => unary("-", exp),
+      UnaryExp::Op(UnaryOp::Not, exp) => unary("!", exp),
+      UnaryExp::Step(StepOp::Increment, exp) => unary("++", exp),
+      UnaryExp::Step(StepOp::Decrement, exp) => unary("--", exp),
+    }
+  }
+}
+
+impl SerializeNode for PostfixExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      PostfixExp::Primary(exp) => exp.serialize(ast),
+      PostfixExp::Subscript(array, index) => Json::node(
+        "subscript",
+        [
+          ("array", array.serialize(ast)),
+          ("index", index.serialize(ast)),
+        ],
+      ),
+      PostfixExp::Call(func, _, args) => Json::node(
+        "call",
+        [("func", func.as_str().into()), ("args", nodes(ast, args))],
+      ),
+      PostfixExp::Step(exp, op) => {
+        let op = match op {
+          StepOp::Increment => "++",
+          StepOp::Decrement => "--",
+        };
+        Json::node(
+          "postfix",
+          [("op", op.into()), ("operand", exp.serialize(ast))],
+        )
+      }
+    }
+  }
+}
+
+impl SerializeNode for PrimaryExp {
+  fn serialize(&self, ast: &Ast) -> Json {
+    match self {
+      PrimaryExp::Num(num) => Json::node("number", [("value", (*num).into())]),
+      PrimaryExp::Ident(ident) => Json::node("ident", [("name", ident.as_str().into())]),
+      PrimaryExp::Paren(exp) => Json::node("paren", [("exp", exp.serialize(ast))]),
+    }
+  }
+}
+
+impl Serialize for SysyType {
+  fn serialize(&self) -> Json {
+    match self {
+      SysyType::Int => Json::node("int", []),
+      SysyType::Void => Json::node("void", []),
+      SysyType::Array(element, len) => Json::node(
+        "array",
+        [("element", element.serialize()), ("len", (*len).into())],
+      ),
+      SysyType::Pointer(pointee) => Json::node("pointer", [("pointee", pointee.serialize())]),
+      SysyType::Function(ret, params) => Json::node(
+        "function",
+        [("return", ret.serialize()), ("params", Json::array(params))],
+      ),
+    }
+  }
+}
+
+impl Serialize for SymbolInfo {
+  fn serialize(&self) -> Json {
+    let kind = match self.kind {
+      SymbolKind::Const => "const",
+      SymbolKind::Var => "var",
+      SymbolKind::Param => "param",
+      SymbolKind::Func => "func",
+    };
+    Json::object([
+      ("name", self.name.as_str().into()),
+      ("kind", kind.into()),
+      ("type", self.ty.serialize()),
+      ("pos", self.pos.into()),
+      ("global", Json::Bool(self.global)),
+    ])
+  }
+}
+
+impl Serialize for Label {
+  fn serialize(&self) -> Json {
+    let (start, end) = self.span;
+    Json::object([
+      ("start", start.into()),
+      ("end", end.into()),
+      ("message", self.message.as_str().into()),
+    ])
+  }
+}
+
+impl Diagnostic {
+  /// 诊断的 JSON 对象；给出源文件的名字与内容时加上 `"file"`、`"line"` 与 `"column"`，诊断本身
+  /// 带有文件名（几个源文件一同编译时的错误）时 `"file"` 取后者。没有位置的诊断不含 `"start"`
+  /// 等字段，没有的标注、说明与建议也不输出
+  pub fn to_json(&self, source: Option<(&str, &str)>) -> Json {
+    let severity = match self.severity {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+    };
+    let kind = match self.kind {
+      Kind::Source => "source",
+      Kind::Other => "other",
+    };
+    let mut json = Json::object([
+      ("version", Json::Number(SCHEMA_VERSION as i64)),
+      ("severity", severity.into()),
+      ("kind", kind.into()),
+      ("message", self.message.as_str().into()),
+    ]);
+    if let Some(file) = self.file.as_deref().or(source.map(|(name, _)| name)) {
+      json.push("file", file.into());
+    }
+    if let Some((start, end)) = self.span {
+      json.push("start", start.into());
+      json.push("end", end.into());
+      if let Some((_, text)) = source {
+        let (line, column) = SourceMap::new(text).position(start);
+        json.push("line", line.into());
+        json.push("column", column.into());
+      }
+    }
+    if !self.labels.is_empty() {
+      json.push("labels", Json::array(&self.labels));
+    }
+    if !self.notes.is_empty() {
+      let notes = self.notes.iter().map(|note| note.as_str().into());
+      json.push("notes", Json::Array(notes.collect()));
+    }
+    if let Some(help) = &self.help {
+      json.push("help", help.as_str().into());
+    }
+    json
+  }
+}
+
+impl Serialize for Diagnostic {
+  fn serialize(&self) -> Json {
+    self.to_json(None)
+  }
+}
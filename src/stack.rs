@@ -0,0 +1,99 @@
+//! 在足够大的栈上运行递归的遍。语法树上的各遍（语义分析、生成 IR、输出语法树等）都是递归的，
+//! 机器生成的、嵌套上万层括号或运算符的表达式会耗尽调用者的栈。库的入口因此先切换到另外分配的
+//! 栈上再运行这些遍：Koopa IR 中值的编号与类型只在生成它们的线程中有效，[`crate::Ir`] 不能交给
+//! 别的线程，所以不另开线程，而是在同一个线程中换一个栈。
+//!
+//! 栈的大小是 [`STACK_SIZE`]，只预留地址空间，用到时才占用内存；最低的一段不可访问，用尽时出错
+//! 而不会改写别的内存。目前只在 x86-64 Linux 上切换，其他平台上仍在调用者的栈上运行。
+
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+
+/// 递归的遍所用的栈大小
+pub(crate) const STACK_SIZE: usize = 1 << 30;
+
+thread_local! {
+  /// 当前线程切换后的栈可用部分的最低地址，没有切换时为零；嵌套的入口（如编译时重新分析）
+  /// 不再切换
+  static BOTTOM: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 在大小为 [`STACK_SIZE`] 的栈上调用 `f` 并返回其结果；`f` 中的 panic 在切换回来之后继续传播。
+/// 分配栈失败或平台不支持时直接调用
+pub(crate) fn run<R>(f: impl FnOnce() -> R) -> R {
+  if BOTTOM.with(Cell::get) != 0 {
+    return f();
+  }
+  let mut f = Some(f);
+  let mut result = None;
+  let mut call = || result = Some(panic::catch_unwind(AssertUnwindSafe(f.take().unwrap())));
+  if !imp::on_stack(&mut call) {
+    return f.take().unwrap()();
+  }
+  match result.unwrap() {
+    Ok(value) => value,
+    Err(payload) => panic::resume_unwind(payload),
+  }
+}
+
+/// 在 [`run`] 切换后的栈上时，栈可用部分的最低地址；再往下是不可访问的部分
+pub(crate) fn bottom() -> Option<usize> {
+  Some(BOTTOM.with(Cell::get)).filter(|&bottom| bottom != 0)
+}
+
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod imp {
+  use std::arch::asm;
+  use std::ffi::c_void;
+  use std::ptr;
+
+  use super::{BOTTOM, STACK_SIZE};
+  use crate::mman::{Mapping, PROT_NONE};
+
+  /// 栈底不可访问的部分
+  const GUARD_SIZE: usize = 64 << 10;
+
+  /// 在新分配的栈上调用 `f`，返回是否调用了。`f` 不得 panic（调用者已捕获）
+  pub fn on_stack(f: &mut dyn FnMut()) -> bool {
+    let Some(stack) = Mapping::new(STACK_SIZE) else {
+      return false;
+    };
+    if !stack.protect(GUARD_SIZE, PROT_NONE) {
+      return false;
+    }
+    BOTTOM.with(|bottom| bottom.set(stack.as_ptr() as usize + GUARD_SIZE));
+    // SAFETY: 栈从高地址向低地址增长，映射的末尾按页对齐，满足调用约定要求的 16 字节对齐。
+    // 汇编保存并恢复原来的栈指针，`entry` 返回之前栈一直有效
+    unsafe {
+      let top = stack.as_ptr().add(stack.len());
+      let mut f = f;
+      let data = ptr::addr_of_mut!(f).cast::<c_void>();
+      asm!(
+        "mov r12, rsp",
+        "mov rsp, {top}",
+        "call {entry}",
+        "mov rsp, r12",
+        top = in(reg) top,
+        entry = in(reg) entry as unsafe extern "C" fn(*mut c_void),
+        in("rdi") data,
+        out("r12") _,
+        clobber_abi("C"),
+      );
+    }
+    BOTTOM.with(|bottom| bottom.set(0));
+    true
+  }
+
+  /// 切换后的栈上的第一个函数，`data` 指向要调用的 `&mut dyn FnMut()`
+  unsafe extern "C" fn entry(data: *mut c_void) {
+    let f = &mut *data.cast::<&mut dyn FnMut()>();
+    f();
+  }
+}
+
+#[cfg(not(all(target_os = "linux", target_arch = "x86_64")))]
+mod imp {
+  pub fn on_stack(_: &mut dyn FnMut()) -> bool {
+    false
+  }
+}
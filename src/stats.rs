@@ -0,0 +1,146 @@
+//! `--stats`：统计各函数的基本块与指令数、保存到栈上与从栈上重新加载的计算结果、寄存器压力
+//! 与栈帧大小，用于定量评估优化。
+//!
+//! 各函数的统计记入每次代码生成各自的 [`Stats`]（见 [`crate::backend::generate_riscv`]），
+//! 同时进行的编译互不影响。
+
+use std::collections::HashMap;
+
+use koopa::ir::Program;
+
+use crate::backend::riscv::directive::{Directive, SymbolType};
+use crate::backend::riscv::{Riscv, RiscvItem};
+
+/// 一个函数的统计：代码生成时得到栈帧，寄存器分配时得到寄存器压力
+#[derive(Debug, Clone, Default)]
+pub struct Frame {
+  /// 保存到栈上的计算结果个数
+  spills: usize,
+  /// 从栈上重新加载计算结果的次数
+  reloads: usize,
+  /// 栈帧字节数
+  size: i32,
+  /// MIR 中各源代码行开始的位置（指令序号，行号），按位置排列
+  lines: Vec<(usize, usize)>,
+  /// 同时占用的可分配寄存器的最多个数，及其首次出现的源代码行
+  pressure: usize,
+  pressure_line: Option<usize>,
+}
+
+impl Frame {
+  /// 函数保存与重新加载计算结果的次数、栈帧大小，以及 MIR 中各源代码行开始的位置，由代码生成
+  /// 给出
+  pub fn new((spills, reloads): (usize, usize), size: i32, lines: Vec<(usize, usize)>) -> Self {
+    Self {
+      spills,
+      reloads,
+      size,
+      lines,
+      ..Default::default()
+    }
+  }
+
+  /// 记录函数的最大寄存器压力，出现在 MIR 的第 `index` 项处
+  pub fn set_pressure(&mut self, pressure: usize, index: usize) {
+    let start = self.lines.partition_point(|&(start, _)| start <= index);
+    self.pressure = pressure;
+    self.pressure_line = start.checked_sub(1).map(|i| self.lines[i].1);
+  }
+}
+
+/// 一次代码生成中各函数的统计，以函数在汇编中的名字为键
+#[derive(Debug, Default)]
+pub struct Stats {
+  frames: HashMap<String, Frame>,
+}
+
+impl Stats {
+  pub fn record(&mut self, func: &str, frame: Frame) {
+    self.frames.insert(func.into(), frame);
+  }
+}
+
+/// 汇编中各个函数（`.type f, @function` 至 `.size f, ...`）的指令数
+pub fn count_insts(riscv: &Riscv) -> HashMap<String, usize> {
+  let mut counts = HashMap::new();
+  let mut current = None;
+  for item in &riscv.0 {
+    match item {
+      RiscvItem::Directive(Directive::Type(name, SymbolType::Function)) => {
+        counts.insert(name.clone(), 0);
+        current = Some(name.clone());
+      }
+      RiscvItem::Directive(Directive::SizeTo(..)) => current = None,
+      RiscvItem::Inst(_) | RiscvItem::Compressed(_) => {
+        if let Some(count) = current.as_ref().and_then(|name| counts.get_mut(name)) {
+          *count += 1;
+        }
+      }
+      _ => {}
+    }
+  }
+  counts
+}
+
+fn line(name: &str, values: [usize; 7], pressure: &str) -> String {
+  let [blocks, ir, codegen, output, spills, reloads, stack] = values;
+  format!(
+    "{:<20} {:>6} {:>8} {:>8} {:>8} {:>7} {:>8} {:>9} {:>7}\n",
+    name, blocks, ir, codegen, output, spills, reloads, pressure, stack
+  )
+}
+
+/// 寄存器压力及其所在的行，如 `5 @12`；没有源代码的行号时只有压力
+fn pressure(pressure: usize, line: Option<usize>) -> String {
+  match line {
+    Some(line) => format!("{} @{}", pressure, line),
+    None => pressure.to_string(),
+  }
+}
+
+/// 各函数的统计表。`codegen` 与 `output` 分别是代码生成后、经过所有遍后的汇编指令数；合计中的
+/// 寄存器压力是各函数中最大的
+pub fn report(
+  ir: &Program,
+  stats: &Stats,
+  codegen: &HashMap<String, usize>,
+  output: &HashMap<String, usize>,
+) -> String {
+  let frames = &stats.frames;
+  let mut out = format!(
+    "{:<20} {:>6} {:>8} {:>8} {:>8} {:>7} {:>8} {:>9} {:>7}\n",
+    "function", "blocks", "ir", "codegen", "output", "spills", "reloads", "pressure", "stack"
+  );
+  let mut functions = 0;
+  let mut total = [0; 7];
+  let mut max_pressure = 0;
+  for &func in ir.func_layout() {
+    let data = ir.func(func);
+    if data.layout().entry_bb().is_none() {
+      continue;
+    }
+    let name = &data.name()[1..];
+    let bbs = data.layout().bbs();
+    let insts = bbs.iter().map(|(_, node)| node.insts().len()).sum();
+    let frame = frames.get(name).cloned().unwrap_or_default();
+    let get = |counts: &HashMap<String, usize>| counts.get(name).copied().unwrap_or_default();
+    let (codegen, output) = (get(codegen), get(output));
+    let row = [
+      bbs.len(),
+      insts,
+      codegen,
+      output,
+      frame.spills,
+      frame.reloads,
+      frame.size as usize,
+    ];
+    for (total, value) in total.iter_mut().zip(row) {
+      *total += value;
+    }
+    max_pressure = max_pressure.max(frame.pressure);
+    functions += 1;
+    out += &line(name, row, &pressure(frame.pressure, frame.pressure_line));
+  }
+  let name = format!("total ({} functions)", functions);
+  out + &line(&name, total, &max_pressure.to_string())
+}
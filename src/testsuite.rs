@@ -0,0 +1,177 @@
+//! `sysyc test <dir>`：运行目录下的测试用例，即课程公开测例中的 `.sy`、`.in`、`.out` 三元组。
+//!
+//! 每个有 `.out` 的 `.sy` 文件是一个用例：编译后解释执行 Koopa IR（加上 `--jit` 时即时编译执行），
+//! 或加上 `--qemu` 生成 RISC-V 程序在模拟器中运行，标准输入取自同名的 `.in`（没有时为空）。输出按评测的格式（程序的输出与
+//! 退出码）与 `.out` 比较，忽略行末的空白与末尾的空行。
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::argparse::ParsedArgs;
+use crate::{compile_object, generate_ir, interpreter, jit, link, Result, Timings};
+
+/// 递归找出 `dir` 下有对应 `.out` 的 `.sy` 文件
+pub fn collect(dir: &Path, cases: &mut Vec<PathBuf>) -> Result<()> {
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+    if path.is_dir() {
+      collect(&path, cases)?;
+    } else if path.extension().is_some_and(|ext| ext == "sy") && path.with_extension("out").exists()
+    {
+      cases.push(path);
+    }
+  }
+  Ok(())
+}
+
+/// 编译并运行一个用例，返回评测格式的输出；`qemu` 时在模拟器中运行，否则按 `args.jit` 即时编译
+/// 或解释执行。编译各阶段的用时记入 `timings`
+pub fn run_case(args: &ParsedArgs, case: &Path, qemu: bool, timings: &Timings) -> Result<Vec<u8>> {
+  let input = case.with_extension("in");
+  let source = case.to_string_lossy();
+  if qemu {
+    // 没有 `.in` 时也不继承终端的输入
+    let stdin = match input.exists() {
+      true => input.to_string_lossy().into_owned(),
+      false => "/dev/null".into(),
+    };
+    let output = run_riscv(args, &source, &stdin, timings)?;
+    verify_opt(args, &source, &stdin, &output, timings)?;
+    return Ok(output);
+  }
+  let (ir, _) = generate_ir(args, &[(&source, fs::read_to_string(case)?)], timings)?;
+  let stdin = match input.exists() {
+    true => fs::read(&input)?,
+    false => vec![],
+  };
+  let (output, code) = match args.jit {
+    true => jit::run_captured(&ir, stdin)?,
+    false => interpreter::run_captured(&ir, stdin)?,
+  };
+  // 同进程的退出码，只保留低 8 位
+  Ok(link::judge_output(output, code & 0xff))
+}
+
+/// 编译、链接为临时的可执行文件，以 `stdin` 为输入在模拟器中运行，返回评测格式的输出
+fn run_riscv(args: &ParsedArgs, input: &str, stdin: &str, timings: &Timings) -> Result<Vec<u8>> {
+  let object = compile_object(args, input, timings)?;
+  let exe = env::temp_dir().join(format!("sysyc-test-{}", std::process::id()));
+  let exe = exe.to_string_lossy();
+  link::link(&[object], &exe, args, timings)?;
+  let result = link::run(&exe, Some(stdin), args);
+  fs::remove_file(exe.as_ref())?;
+  let (output, code) = result?;
+  Ok(link::judge_output(output, code))
+}
+
+/// `--verify-opt`：只运行 -O0 的遍，重新编译、运行 `input`，其结果须与优化后的 `optimized` 相同
+pub fn verify_opt(
+  args: &ParsedArgs,
+  input: &str,
+  stdin: &str,
+  optimized: &[u8],
+  timings: &Timings,
+) -> Result<()> {
+  let Some(passes) = &args.verify_opt else {
+    return Ok(());
+  };
+  // 对照的一次编译不再输出中间文件与报告
+  let baseline = ParsedArgs {
+    passes: passes.clone(),
+    verify_opt: None,
+    save_temps: false,
+    stats: false,
+    rvc_report: false,
+    dump_ir_before: vec![],
+    dump_ir_after: vec![],
+    print_changed: false,
+    ..args.clone()
+  };
+  let expected = run_riscv(&baseline, input, stdin, timings)?;
+  compare(&expected, optimized).map_err(|e| {
+    let passes = args.passes.join(",");
+    let message = format!("--verify-opt: output of {} with passes {}", input, passes);
+    format!("{} differs from -O0: {}", message, e).into()
+  })
+}
+
+/// 按行比较，忽略行末的空白与末尾的空行；不同时给出第一处不同
+pub fn compare(expected: &[u8], actual: &[u8]) -> std::result::Result<(), String> {
+  let lines = |bytes: &[u8]| {
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines: Vec<_> = text
+      .lines()
+      .map(|line| line.trim_end().to_string())
+      .collect();
+    while lines.last().is_some_and(String::is_empty) {
+      lines.pop();
+    }
+    lines
+  };
+  let (expected, actual) = (lines(expected), lines(actual));
+  let line = |lines: &[String], i: usize| match lines.get(i) {
+    Some(line) => format!("'{}'", line),
+    None => "end of output".into(),
+  };
+  match (0..expected.len().max(actual.len())).find(|&i| expected.get(i) != actual.get(i)) {
+    None => Ok(()),
+    Some(i) => Err(format!(
+      "line {}: expected {}, found {}",
+      i + 1,
+      line(&expected, i),
+      line(&actual, i)
+    )),
+  }
+}
+
+/// 运行 `args.input[0]` 下的所有用例，逐个输出结果，最后是汇总；有用例失败时返回错误
+pub fn run(args: &ParsedArgs, timings: &Timings) -> Result<()> {
+  let dir = Path::new(&args.input[0]);
+  if !dir.is_dir() {
+    return Err(format!("test expects a directory, found {}", dir.display()).into());
+  }
+  let mut cases = vec![];
+  collect(dir, &mut cases)?;
+  if cases.is_empty() {
+    return Err(format!("no test cases (.sy with .out) found in {}", dir.display()).into());
+  }
+  cases.sort();
+
+  let start = Instant::now();
+  let mut failed = vec![];
+  for case in &cases {
+    let name = case.strip_prefix(dir).unwrap_or(case).with_extension("");
+    let name = name.display();
+    let case_start = Instant::now();
+    let result = run_case(args, case, args.qemu, timings).and_then(|output| {
+      let expected = fs::read(case.with_extension("out"))?;
+      Ok(compare(&expected, &output)?)
+    });
+    let ms = case_start.elapsed().as_secs_f64() * 1000.0;
+    match result {
+      Ok(()) => println!("PASS {:<40} {:>10.1} ms", name, ms),
+      Err(e) => {
+        println!("FAIL {:<40} {:>10.1} ms", name, ms);
+        // 编译错误可能有多行
+        println!("     {}", e.to_string().replace('\n', "\n     "));
+        failed.push(name.to_string());
+      }
+    }
+  }
+  let passed = cases.len() - failed.len();
+  let elapsed = start.elapsed().as_secs_f64();
+  println!(
+    "\n{} passed, {} failed ({} cases) in {:.3} s",
+    passed,
+    failed.len(),
+    cases.len(),
+    elapsed
+  );
+  if failed.is_empty() {
+    Ok(())
+  } else {
+    Err(format!("failed: {}", failed.join(", ")).into())
+  }
+}
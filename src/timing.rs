@@ -0,0 +1,73 @@
+//! `--time-passes`：统计编译各阶段所用的时间，结束时按用时从多到少输出。
+//!
+//! 每次编译（`--watch` 的每次重新编译）各有一份 [`Timings`]，由调用者传给编译的各个阶段；
+//! 同时进行的编译互不影响。
+
+use std::cmp::Reverse;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 一次编译中各阶段的用时；未启用时 [`Timings::time`] 不计时。记录有锁保护，并行的阶段
+/// （如各个函数的寄存器分配）可以同时记入
+pub struct Timings {
+  enabled: bool,
+  /// 开始计时的时刻，用于统计总用时
+  start: Instant,
+  /// 各阶段的名字与累计用时，按首次出现的顺序
+  times: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl Default for Timings {
+  /// 不计时
+  fn default() -> Self {
+    Self::new(false)
+  }
+}
+
+impl Timings {
+  /// `enabled` 时从此刻开始计时
+  pub fn new(enabled: bool) -> Self {
+    Self {
+      enabled,
+      start: Instant::now(),
+      times: Mutex::default(),
+    }
+  }
+
+  pub fn enabled(&self) -> bool {
+    self.enabled
+  }
+
+  /// 运行 `f`，其用时记入阶段 `name`；同名的阶段累加（如各个函数的寄存器分配）
+  pub fn time<T>(&self, name: &'static str, f: impl FnOnce() -> T) -> T {
+    if !self.enabled {
+      return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let mut times = self.times.lock().unwrap();
+    match times.iter_mut().find(|(n, _)| *n == name) {
+      Some((_, total)) => *total += elapsed,
+      None => times.push((name, elapsed)),
+    }
+    result
+  }
+
+  /// 按用时从多到少排列的各阶段用时，及其占总用时的比例
+  pub fn report(&self) -> String {
+    let total = self.start.elapsed();
+    let mut times = self.times.lock().unwrap().clone();
+    times.sort_by_key(|&(_, d)| Reverse(d));
+    let percent = |d: Duration| d.as_secs_f64() * 100.0 / total.as_secs_f64().max(f64::EPSILON);
+    let row = |name: &str, d: Duration| {
+      let ms = d.as_secs_f64() * 1000.0;
+      format!("{:<12} {:>10.3} ms {:>6.1}%\n", name, ms, percent(d))
+    };
+    let mut out = format!("{:<12} {:>13} {:>7}\n", "pass", "time", "share");
+    for &(name, d) in &times {
+      out += &row(name, d);
+    }
+    out + &row("total", total)
+  }
+}
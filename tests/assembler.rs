@@ -0,0 +1,359 @@
+//! 内置汇编器（`-c` 与链接时使用）的测试：汇编得到的机器码同 `llvm-mc -show-encoding` 给出的
+//! 编码逐字节比较；目标文件的段表、符号表与重定位表同 GNU as 输出的布局比较。
+
+use sysyc::backend::riscv::inst::Inst;
+use sysyc::backend::riscv::reg::Reg;
+use sysyc::backend::riscv::{Riscv, RiscvItem, Xlen};
+use sysyc::optimization::{pass_compress, CompressReport};
+
+use elf::{read, Elf};
+
+mod elf;
+
+/// ELF 目标文件中 `.text` 段的内容
+fn text(object: &[u8]) -> Vec<u8> {
+  Elf::parse(object).data(".text").to_vec()
+}
+
+/// 压缩各条指令：能压缩的须得到 `expected` 中的压缩指令，汇编后是其中的 2 字节编码；不能压缩的
+/// 保持原样，仍占 4 字节。压缩率的报告与实际压缩的条数一致
+fn check_compress(xlen: Xlen, cases: &[(Inst, Option<(&str, [u8; 2])>)]) {
+  let items = cases.iter().map(|(inst, _)| RiscvItem::Inst(inst.clone()));
+  let compressed = pass_compress(&Riscv(items.collect()), xlen);
+  let insts: Vec<_> = compressed
+    .0
+    .iter()
+    .filter(|item| matches!(item, RiscvItem::Inst(_) | RiscvItem::Compressed(_)))
+    .collect();
+  let mut code = vec![];
+  for ((inst, expected), item) in cases.iter().zip(&insts) {
+    match (expected, item) {
+      (Some((text, encoding)), RiscvItem::Compressed(c)) => {
+        assert_eq!(c.to_string().trim(), *text, "{}", inst);
+        code.extend(encoding);
+      }
+      (None, RiscvItem::Inst(_)) => {
+        let alone = sysyc::backend::assemble(&Riscv(vec![RiscvItem::Inst(inst.clone())]), xlen);
+        code.extend(text(&alone.unwrap()));
+      }
+      _ => panic!("{}: expected {:?}, got {}", inst, expected, item),
+    }
+  }
+  assert_eq!(
+    text(&sysyc::backend::assemble(&compressed, xlen).unwrap()),
+    code
+  );
+
+  let report = CompressReport::of(&compressed);
+  let count = cases
+    .iter()
+    .filter(|(_, expected)| expected.is_some())
+    .count();
+  assert_eq!((report.compressed, report.total), (count, cases.len()));
+  let ratio = count as f64 * 100.0 / cases.len() as f64;
+  let summary = format!(
+    "rvc: compressed {} of {} instructions ({:.1}%), saving {} bytes",
+    count,
+    cases.len(),
+    ratio,
+    count * 2
+  );
+  assert_eq!(report.to_string(), summary);
+}
+
+#[test]
+fn compress_rv32() {
+  use Reg::*;
+  check_compress(
+    Xlen::Rv32,
+    &[
+      (
+        Inst::Addi(T1, T1, -1),
+        Some(("c.addi t1, -1", [0x7d, 0x13])),
+      ),
+      (Inst::Addi(A0, Zero, 5), Some(("c.li a0, 5", [0x15, 0x45]))),
+      (Inst::Li(A5, 4096), Some(("c.lui a5, 1", [0x85, 0x67]))),
+      (
+        Inst::Li(A5, -4096),
+        Some(("c.lui a5, 1048575", [0xfd, 0x77])),
+      ),
+      (Inst::Slli(T1, T0, 0), Some(("c.mv t1, t0", [0x16, 0x83]))),
+      (Inst::Xor(T0, T0, Zero), Some(("c.mv t0, t0", [0x96, 0x82]))),
+      (Inst::Mv(A0, Zero), Some(("c.li a0, 0", [0x01, 0x45]))),
+      (Inst::Srli(A0, A0, 3), Some(("c.srli a0, 3", [0x0d, 0x81]))),
+      (Inst::Sub(A0, A0, A1), Some(("c.sub a0, a1", [0x0d, 0x8d]))),
+      (
+        Inst::Lw(A0, 4, Sp),
+        Some(("c.lwsp a0, 4(sp)", [0x12, 0x45])),
+      ),
+      (
+        Inst::Sw(Ra, 12, Sp),
+        Some(("c.swsp ra, 12(sp)", [0x06, 0xc6])),
+      ),
+      (
+        Inst::Addi(Sp, Sp, -32),
+        Some(("c.addi16sp sp, -32", [0x3d, 0x71])),
+      ),
+      (Inst::Ret, Some(("c.jr ra", [0x82, 0x80]))),
+      // 立即数超出 6 位、寄存器不在 x8 ~ x15 中、目的寄存器不是源寄存器
+      (Inst::Addi(T1, T1, 32), None),
+      (Inst::Li(A5, 4097), None),
+      (Inst::Srli(T0, T0, 3), None),
+      (Inst::Lw(T0, 0, T6), None),
+      (Inst::Sub(T0, Zero, T0), None),
+    ],
+  );
+}
+
+#[test]
+fn compress_rv64() {
+  use Reg::*;
+  check_compress(
+    Xlen::Rv64,
+    &[
+      (Inst::SextW(A0, A0), Some(("c.addiw a0, 0", [0x01, 0x25]))),
+      (
+        Inst::Addw(A0, A0, A1),
+        Some(("c.addw a0, a1", [0x2d, 0x9d])),
+      ),
+      (
+        Inst::Ld(Fp, 8, Sp),
+        Some(("c.ldsp fp, 8(sp)", [0x22, 0x64])),
+      ),
+      (Inst::SextW(A0, A1), None),
+    ],
+  );
+}
+
+/// 汇编 `items`：`.text` 须依次是 `expected` 中的编码，同段内的分支与跳转都已解析，不留重定位
+fn check_encodings(xlen: Xlen, items: Vec<RiscvItem>, expected: &[[u8; 4]]) {
+  let object = sysyc::backend::assemble(&Riscv(items), xlen).unwrap();
+  let elf = Elf::parse(&object);
+  assert_eq!(elf.data(".text"), expected.concat());
+  assert!(elf.relocs(".rela.text").is_empty());
+}
+
+/// R、I、S、B、U、J 各类格式的指令与伪指令的展开
+#[test]
+fn formats_rv32() {
+  use Reg::*;
+  let insts = [
+    Inst::Add(A0, A1, A2),
+    Inst::Sub(T0, T1, T2),
+    Inst::Mul(A0, A0, A1),
+    Inst::Rem(S1, A2, A3),
+    Inst::Sgt(A0, A1, A2),
+    Inst::Addi(A0, A1, -2048),
+    Inst::Xori(T0, T0, 255),
+    Inst::Seqz(A0, A1),
+    Inst::Snez(A0, A1),
+    Inst::Srli(A0, A1, 31),
+    Inst::Lw(A0, -4, Sp),
+    Inst::Sw(Ra, 2044, Sp),
+    Inst::Li(A0, 0x12345678),
+    Inst::Li(A1, -4096),
+    Inst::Beqz(A0, ".L1".into()),
+    Inst::Bnez(A1, "f".into()),
+    Inst::J("f".into()),
+  ];
+  let mut items = vec![RiscvItem::Label("f".into())];
+  items.extend(insts.into_iter().map(RiscvItem::Inst));
+  items.push(RiscvItem::Label(".L1".into()));
+  items.push(RiscvItem::Inst(Inst::Ecall));
+  items.push(RiscvItem::Inst(Inst::Ret));
+  check_encodings(
+    Xlen::Rv32,
+    items,
+    &[
+      [0x33, 0x85, 0xc5, 0x00],
+      [0xb3, 0x02, 0x73, 0x40],
+      [0x33, 0x05, 0xb5, 0x02],
+      [0xb3, 0x64, 0xd6, 0x02],
+      // slt a0, a2, a1
+      [0x33, 0x25, 0xb6, 0x00],
+      [0x13, 0x85, 0x05, 0x80],
+      [0x93, 0xc2, 0xf2, 0x0f],
+      [0x13, 0xb5, 0x15, 0x00],
+      [0x33, 0x35, 0xb0, 0x00],
+      [0x13, 0xd5, 0xf5, 0x01],
+      [0x03, 0x25, 0xc1, 0xff],
+      [0x23, 0x2e, 0x11, 0x7e],
+      // lui a0, 0x12345; addi a0, a0, 0x678
+      [0x37, 0x55, 0x34, 0x12],
+      [0x13, 0x05, 0x85, 0x67],
+      // 低 12 位为零时只有 lui
+      [0xb7, 0xf5, 0xff, 0xff],
+      // beqz a0, +12; bnez a1, -64; j -68
+      [0x63, 0x06, 0x05, 0x00],
+      [0xe3, 0x90, 0x05, 0xfc],
+      [0x6f, 0xf0, 0xdf, 0xfb],
+      [0x73, 0x00, 0x00, 0x00],
+      [0x67, 0x80, 0x00, 0x00],
+    ],
+  );
+}
+
+/// RV64 特有的双字访存、`*w` 运算，以及 `li` 以 `addiw` 补上低位
+#[test]
+fn formats_rv64() {
+  use Reg::*;
+  let insts = [
+    Inst::Ld(A0, 8, Sp),
+    Inst::Sd(S1, -16, Fp),
+    Inst::Addw(A0, A0, A1),
+    Inst::Subw(A0, A0, A1),
+    Inst::Mulw(T0, T1, T2),
+    Inst::Divw(A0, A0, A1),
+    Inst::Remw(A0, A0, A1),
+    Inst::SextW(A0, A1),
+    Inst::Li(A0, 0x12345678),
+  ];
+  check_encodings(
+    Xlen::Rv64,
+    insts.into_iter().map(RiscvItem::Inst).collect(),
+    &[
+      [0x03, 0x35, 0x81, 0x00],
+      [0x23, 0x38, 0x94, 0xfe],
+      [0x3b, 0x05, 0xb5, 0x00],
+      [0x3b, 0x05, 0xb5, 0x40],
+      [0xbb, 0x02, 0x73, 0x02],
+      [0x3b, 0x45, 0xb5, 0x02],
+      [0x3b, 0x65, 0xb5, 0x02],
+      [0x1b, 0x85, 0x05, 0x00],
+      [0x37, 0x55, 0x34, 0x12],
+      [0x1b, 0x05, 0x85, 0x67],
+    ],
+  );
+}
+
+/// 引用其他段与外部符号的指令留下重定位，立即数为零；段表与符号表同 GNU as 的布局一致：
+/// 局部符号在前，未引用的 `.L` 标号不进入符号表，未定义的符号排在最后
+#[test]
+fn object() {
+  use sysyc::backend::riscv::directive::{Directive, SymbolType};
+  use Reg::*;
+  const R_RISCV_CALL_PLT: u64 = 19;
+  const R_RISCV_PCREL_HI20: u64 = 23;
+  const R_RISCV_PCREL_LO12_I: u64 = 24;
+  const R_RISCV_HI20: u64 = 26;
+  const R_RISCV_LO12_I: u64 = 27;
+  const R_RISCV_LO12_S: u64 = 28;
+  const SHT_PROGBITS: u32 = 1;
+  const SHT_SYMTAB: u32 = 2;
+  const SHT_STRTAB: u32 = 3;
+  const SHT_RELA: u32 = 4;
+  const SHT_NOBITS: u32 = 8;
+
+  let items = vec![
+    RiscvItem::Directive(Directive::Text),
+    RiscvItem::Directive(Directive::Globl("main".into())),
+    RiscvItem::Directive(Directive::Type("main".into(), SymbolType::Function)),
+    RiscvItem::Label("main".into()),
+    RiscvItem::Inst(Inst::Call("putint".into())),
+    RiscvItem::Inst(Inst::La(A0, "arr".into())),
+    RiscvItem::Inst(Inst::Lui(A1, "g".into())),
+    RiscvItem::Inst(Inst::LwLo(A0, "g".into(), A1)),
+    RiscvItem::Inst(Inst::SwLo(A0, "g".into(), A1)),
+    RiscvItem::Inst(Inst::Ret),
+    RiscvItem::Label(".Lend".into()),
+    RiscvItem::Directive(Directive::SizeTo("main".into(), ".Lend".into())),
+    RiscvItem::Directive(Directive::Data),
+    RiscvItem::Directive(Directive::Globl("g".into())),
+    RiscvItem::Directive(Directive::Type("g".into(), SymbolType::Object)),
+    RiscvItem::Directive(Directive::Size("g".into(), 4)),
+    RiscvItem::Label("g".into()),
+    RiscvItem::Directive(Directive::Word(vec![7])),
+    RiscvItem::Directive(Directive::Bss),
+    RiscvItem::Directive(Directive::Type("arr".into(), SymbolType::Object)),
+    RiscvItem::Directive(Directive::Size("arr".into(), 8)),
+    RiscvItem::Label("arr".into()),
+    RiscvItem::Directive(Directive::Zero(8)),
+  ];
+  for xlen in [Xlen::Rv32, Xlen::Rv64] {
+    let object = sysyc::backend::assemble(&Riscv(items.clone()), xlen).unwrap();
+    let elf = Elf::parse(&object);
+    assert_eq!(elf.elf64, xlen == Xlen::Rv64);
+    // ET_REL、EM_RISCV，没有压缩指令时不置 EF_RISCV_RVC
+    assert_eq!(read::<2>(&object, 0x10), 1);
+    assert_eq!(read::<2>(&object, 0x12), 243);
+    let flags = if elf.elf64 { 0x30 } else { 0x24 };
+    assert_eq!(read::<4>(&object, flags), 0);
+
+    let sections: Vec<_> = elf
+      .sections
+      .iter()
+      .map(|s| (s.name.as_str(), s.ty, s.link, s.info))
+      .collect();
+    assert_eq!(
+      sections,
+      [
+        ("", 0, 0, 0),
+        (".text", SHT_PROGBITS, 0, 0),
+        (".data", SHT_PROGBITS, 0, 0),
+        (".bss", SHT_NOBITS, 0, 0),
+        (".rodata", SHT_PROGBITS, 0, 0),
+        // 链接到 .symtab，作用于 .text
+        (".rela.text", SHT_RELA, 6, 1),
+        // 链接到 .strtab，第一个全局符号的下标为 3
+        (".symtab", SHT_SYMTAB, 7, 3),
+        (".strtab", SHT_STRTAB, 0, 0),
+        (".shstrtab", SHT_STRTAB, 0, 0),
+      ]
+    );
+    assert_eq!(elf.section(".bss").size, 8);
+    assert_eq!(elf.data(".data"), [7, 0, 0, 0]);
+    assert!(elf.data(".rodata").is_empty());
+    assert_eq!(
+      elf.data(".text"),
+      [
+        // auipc ra, 0; jalr ra, 0(ra)
+        [0x97, 0x00, 0x00, 0x00],
+        [0xe7, 0x80, 0x00, 0x00],
+        // auipc a0, 0; addi a0, a0, 0
+        [0x17, 0x05, 0x00, 0x00],
+        [0x13, 0x05, 0x05, 0x00],
+        // lui a1, 0; lw a0, 0(a1); sw a0, 0(a1)
+        [0xb7, 0x05, 0x00, 0x00],
+        [0x03, 0xa5, 0x05, 0x00],
+        [0x23, 0xa0, 0xa5, 0x00],
+        [0x67, 0x80, 0x00, 0x00],
+      ]
+      .concat()
+    );
+
+    let symbols = elf.symbols();
+    let symbols: Vec<_> = symbols
+      .iter()
+      .map(|(name, value, size, info, shndx)| (name.as_str(), *value, *size, *info, *shndx))
+      .collect();
+    // st_info 的高 4 位为绑定（0 局部、1 全局），低 4 位为类型（0 无、1 对象、2 函数）
+    assert_eq!(
+      symbols,
+      [
+        ("", 0, 0, 0x00, 0),
+        ("arr", 0, 8, 0x01, 3),
+        (".Lpcrel_hi0", 8, 0, 0x00, 1),
+        ("main", 0, 32, 0x12, 1),
+        ("g", 0, 4, 0x11, 2),
+        ("putint", 0, 0, 0x10, 0),
+      ]
+    );
+
+    let relocs = elf.relocs(".rela.text");
+    let relocs: Vec<_> = relocs
+      .iter()
+      .map(|(offset, kind, symbol)| (*offset, *kind, symbol.as_str()))
+      .collect();
+    assert_eq!(
+      relocs,
+      [
+        (0, R_RISCV_CALL_PLT, "putint"),
+        (8, R_RISCV_PCREL_HI20, "arr"),
+        (12, R_RISCV_PCREL_LO12_I, ".Lpcrel_hi0"),
+        (16, R_RISCV_HI20, "g"),
+        (20, R_RISCV_LO12_I, "g"),
+        (24, R_RISCV_LO12_S, "g"),
+      ]
+    );
+  }
+}
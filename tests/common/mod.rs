@@ -0,0 +1,54 @@
+//! 回归测试共用的检入结果比较：不同时给出第一处不同的行，`UPDATE_GOLDEN=1` 时改为写入。
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn manifest_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// 是否以实际的输出重新生成检入的结果
+pub fn update() -> bool {
+  env::var_os("UPDATE_GOLDEN").is_some_and(|v| v == "1")
+}
+
+/// 比较输出与检入的结果，不同时给出第一处不同的行
+pub fn compare(golden: &Path, actual: &str) -> Result<(), String> {
+  let name = golden.file_name().unwrap().to_string_lossy();
+  let Ok(expected) = fs::read_to_string(golden) else {
+    return Err(format!("{}: missing (run with UPDATE_GOLDEN=1)", name));
+  };
+  if expected == actual {
+    return Ok(());
+  }
+  let mut expected_lines = expected.lines();
+  let mut actual_lines = actual.lines();
+  for line in 1.. {
+    match (expected_lines.next(), actual_lines.next()) {
+      (Some(e), Some(a)) if e == a => continue,
+      (None, None) => break,
+      (e, a) => {
+        return Err(format!(
+          "{}:{}: expected {:?}, got {:?}",
+          name,
+          line,
+          e.unwrap_or("<end of file>"),
+          a.unwrap_or("<end of file>")
+        ))
+      }
+    }
+  }
+  // 只有末尾的换行不同
+  Err(format!("{}: trailing newline differs", name))
+}
+
+/// `UPDATE_GOLDEN=1` 时写入 `golden`，否则与之比较
+pub fn check(golden: &Path, actual: &str) -> Result<(), String> {
+  if update() {
+    fs::create_dir_all(golden.parent().unwrap()).unwrap();
+    fs::write(golden, actual).unwrap();
+    return Ok(());
+  }
+  compare(golden, actual)
+}
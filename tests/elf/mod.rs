@@ -0,0 +1,145 @@
+//! 测试用的 ELF 目标文件读取，供检查汇编器的输出与在模拟器中链接目标文件。
+
+/// 读取小端序的 `N` 字节无符号数
+pub fn read<const N: usize>(bytes: &[u8], offset: usize) -> u64 {
+  let mut le = [0; 8];
+  le[..N].copy_from_slice(&bytes[offset..offset + N]);
+  u64::from_le_bytes(le)
+}
+
+/// ELF 目标文件中的一段
+pub struct Section {
+  pub name: String,
+  pub ty: u32,
+  pub offset: usize,
+  pub size: usize,
+  pub link: u32,
+  pub info: u32,
+}
+
+/// 读取 ELF32/ELF64 目标文件中测试用到的部分：各段、符号表与重定位表
+pub struct Elf<'a> {
+  bytes: &'a [u8],
+  pub elf64: bool,
+  pub sections: Vec<Section>,
+}
+
+impl<'a> Elf<'a> {
+  pub fn parse(bytes: &'a [u8]) -> Self {
+    assert_eq!(&bytes[..4], b"\x7fELF");
+    let elf64 = bytes[4] == 2;
+    let mut elf = Self {
+      bytes,
+      elf64,
+      sections: vec![],
+    };
+    let (shoff, shnum, shstrndx) = match elf64 {
+      true => (
+        read::<8>(bytes, 0x28),
+        read::<2>(bytes, 0x3c),
+        read::<2>(bytes, 0x3e),
+      ),
+      false => (
+        read::<4>(bytes, 0x20),
+        read::<2>(bytes, 0x30),
+        read::<2>(bytes, 0x32),
+      ),
+    };
+    // 先取得各段的名字在段名表中的偏移，读完段名表后再换成名字
+    let mut names = vec![];
+    for index in 0..shnum {
+      let at = (shoff + index * if elf64 { 64 } else { 40 }) as usize;
+      let field = |offset32: usize, offset64: usize| match elf64 {
+        true => read::<8>(bytes, at + offset64),
+        false => read::<4>(bytes, at + offset32),
+      };
+      names.push(read::<4>(bytes, at) as usize);
+      elf.sections.push(Section {
+        name: String::new(),
+        ty: read::<4>(bytes, at + 4) as u32,
+        offset: field(16, 24) as usize,
+        size: field(20, 32) as usize,
+        link: read::<4>(bytes, at + if elf64 { 40 } else { 24 }) as u32,
+        info: read::<4>(bytes, at + if elf64 { 44 } else { 28 }) as u32,
+      });
+    }
+    let shstrtab = elf.sections[shstrndx as usize].offset;
+    for (section, name) in elf.sections.iter_mut().zip(names) {
+      section.name = string(bytes, shstrtab + name);
+    }
+    elf
+  }
+
+  pub fn section(&self, name: &str) -> &Section {
+    let section = self.sections.iter().find(|section| section.name == name);
+    section.unwrap_or_else(|| panic!("no {} section", name))
+  }
+
+  /// 段 `name` 的内容
+  pub fn data(&self, name: &str) -> &'a [u8] {
+    let section = self.section(name);
+    &self.bytes[section.offset..section.offset + section.size]
+  }
+
+  /// 符号表的各项：(名字, 值, 大小, `st_info`, 所在段的下标)
+  pub fn symbols(&self) -> Vec<(String, u64, u64, u8, u64)> {
+    let symtab = self.section(".symtab");
+    let strtab = self.sections[symtab.link as usize].offset;
+    let size = if self.elf64 { 24 } else { 16 };
+    let entry = |at: usize| match self.elf64 {
+      true => (
+        read::<4>(self.bytes, at),
+        read::<8>(self.bytes, at + 8),
+        read::<8>(self.bytes, at + 16),
+        self.bytes[at + 4],
+        read::<2>(self.bytes, at + 6),
+      ),
+      false => (
+        read::<4>(self.bytes, at),
+        read::<4>(self.bytes, at + 4),
+        read::<4>(self.bytes, at + 8),
+        self.bytes[at + 12],
+        read::<2>(self.bytes, at + 14),
+      ),
+    };
+    (symtab.offset..symtab.offset + symtab.size)
+      .step_by(size)
+      .map(entry)
+      .map(|(name, value, size, info, shndx)| {
+        let name = string(self.bytes, strtab + name as usize);
+        (name, value, size, info, shndx)
+      })
+      .collect()
+  }
+
+  /// 重定位表 `name` 的各项：(偏移量, 类型, 符号名)；加数总为零
+  pub fn relocs(&self, name: &str) -> Vec<(u64, u64, String)> {
+    let symbols = self.symbols();
+    let rela = self.section(name);
+    // 每项依次是 r_offset、r_info 与 r_addend，各占一个字长
+    let (word, width) = match self.elf64 {
+      true => (read::<8> as fn(&[u8], usize) -> u64, 8),
+      false => (read::<4> as fn(&[u8], usize) -> u64, 4),
+    };
+    (rela.offset..rela.offset + rela.size)
+      .step_by(3 * width)
+      .map(|at| {
+        let offset = word(self.bytes, at);
+        let info = word(self.bytes, at + width);
+        let addend = word(self.bytes, at + 2 * width);
+        assert_eq!(addend, 0);
+        let (symbol, kind) = match self.elf64 {
+          true => (info >> 32, info & 0xffff_ffff),
+          false => (info >> 8, info & 0xff),
+        };
+        (offset, kind, symbols[symbol as usize].0.clone())
+      })
+      .collect()
+  }
+}
+
+/// 以 `\0` 结尾的字符串
+fn string(bytes: &[u8], offset: usize) -> String {
+  let len = bytes[offset..].iter().position(|&b| b == 0).unwrap();
+  String::from_utf8(bytes[offset..offset + len].to_vec()).unwrap()
+}
@@ -0,0 +1,341 @@
+//! 测试用的 RV32IM 模拟器：把目标文件链接到固定的地址，从 `_start` 开始运行到 `exit_group`。
+//!
+//! 只模拟内置运行时库（[`sysyc::compile_runtime`]）所用的 Linux 系统调用：`read`、`write`、
+//! `exit_group` 与 `clock_gettime64`（时间总为零）。
+
+use std::collections::HashMap;
+
+use crate::elf::Elf;
+
+/// 内存的大小；栈从末尾向下增长
+const MEMORY_SIZE: usize = 64 << 20;
+/// 第一段的地址；其下的地址不可访问，访问空指针即出错
+const BASE: usize = 0x10000;
+/// 运行的指令数的上限，超出即认为程序陷入死循环
+const MAX_STEPS: u64 = 1 << 30;
+
+const SHT_PROGBITS: u32 = 1;
+const SHT_RELA: u32 = 4;
+const SHT_NOBITS: u32 = 8;
+
+const R_RISCV_BRANCH: u64 = 16;
+const R_RISCV_JAL: u64 = 17;
+const R_RISCV_CALL_PLT: u64 = 19;
+const R_RISCV_PCREL_HI20: u64 = 23;
+const R_RISCV_PCREL_LO12_I: u64 = 24;
+const R_RISCV_HI20: u64 = 26;
+const R_RISCV_LO12_I: u64 = 27;
+const R_RISCV_LO12_S: u64 = 28;
+
+const SYS_READ: u32 = 63;
+const SYS_WRITE: u32 = 64;
+const SYS_EXIT_GROUP: u32 = 94;
+const SYS_CLOCK_GETTIME64: u32 = 403;
+
+/// 各类格式的立即数在指令中的编码
+fn i_imm(x: u32) -> u32 {
+  (x & 0xfff) << 20
+}
+
+fn s_imm(x: u32) -> u32 {
+  (x >> 5 & 0x7f) << 25 | (x & 0x1f) << 7
+}
+
+fn b_imm(x: u32) -> u32 {
+  (x >> 12 & 1) << 31 | (x >> 5 & 0x3f) << 25 | (x >> 1 & 0xf) << 8 | (x >> 11 & 1) << 7
+}
+
+fn u_imm(x: u32) -> u32 {
+  x.wrapping_add(0x800) & 0xffff_f000
+}
+
+fn j_imm(x: u32) -> u32 {
+  (x >> 20 & 1) << 31 | (x >> 1 & 0x3ff) << 21 | (x >> 11 & 1) << 20 | (x >> 12 & 0xff) << 12
+}
+
+/// 指令中各类格式的立即数，符号扩展
+fn s_offset(inst: u32) -> u32 {
+  ((inst as i32 >> 25) << 5) as u32 | (inst >> 7 & 0x1f)
+}
+
+fn b_offset(inst: u32) -> u32 {
+  ((inst as i32 >> 31) << 12) as u32
+    | (inst >> 7 & 1) << 11
+    | (inst >> 25 & 0x3f) << 5
+    | (inst >> 8 & 0xf) << 1
+}
+
+fn j_offset(inst: u32) -> u32 {
+  ((inst as i32 >> 31) << 20) as u32
+    | (inst >> 12 & 0xff) << 12
+    | (inst >> 20 & 1) << 11
+    | (inst >> 21 & 0x3ff) << 1
+}
+
+/// 整数运算；`imm` 时 `b` 是 I 型指令的立即数，没有减法
+fn alu(funct3: u32, funct7: u32, imm: bool, a: u32, b: u32) -> u32 {
+  match funct3 {
+    0 if !imm && funct7 == 0x20 => a.wrapping_sub(b),
+    0 => a.wrapping_add(b),
+    1 => a << (b & 31),
+    2 => ((a as i32) < (b as i32)) as u32,
+    3 => (a < b) as u32,
+    4 => a ^ b,
+    5 if funct7 == 0x20 => (a as i32 >> (b & 31)) as u32,
+    5 => a >> (b & 31),
+    6 => a | b,
+    _ => a & b,
+  }
+}
+
+/// M 扩展的乘除法；除以零与 `INT_MIN / -1` 按规范给出结果而不陷入
+fn muldiv(funct3: u32, a: u32, b: u32) -> u32 {
+  let (sa, sb) = (a as i32 as i64, b as i32 as i64);
+  match funct3 {
+    0 => a.wrapping_mul(b),
+    1 => (sa * sb >> 32) as u32,
+    2 => (sa * b as i64 >> 32) as u32,
+    3 => ((a as u64 * b as u64) >> 32) as u32,
+    4 if b == 0 => u32::MAX,
+    4 => (a as i32).wrapping_div(b as i32) as u32,
+    5 if b == 0 => u32::MAX,
+    5 => a / b,
+    6 if b == 0 => a,
+    6 => (a as i32).wrapping_rem(b as i32) as u32,
+    _ if b == 0 => a,
+    _ => a % b,
+  }
+}
+
+/// 程序运行的结果
+pub struct Output {
+  pub stdout: Vec<u8>,
+  pub stderr: Vec<u8>,
+  pub code: i32,
+}
+
+struct Machine<'a> {
+  memory: Vec<u8>,
+  input: &'a [u8],
+  stdout: Vec<u8>,
+  stderr: Vec<u8>,
+}
+
+impl Machine<'_> {
+  /// `address` 起的 `len` 字节；越界时 panic
+  fn bytes(&mut self, address: u32, len: usize) -> &mut [u8] {
+    let start = address as usize;
+    match start.checked_add(len) {
+      Some(end) if start >= BASE && end <= MEMORY_SIZE => &mut self.memory[start..end],
+      _ => panic!("access to {:#x} out of memory", address),
+    }
+  }
+
+  fn load(&mut self, address: u32, len: usize) -> u32 {
+    let mut le = [0; 4];
+    le[..len].copy_from_slice(self.bytes(address, len));
+    u32::from_le_bytes(le)
+  }
+
+  fn store(&mut self, address: u32, value: u32, len: usize) {
+    self
+      .bytes(address, len)
+      .copy_from_slice(&value.to_le_bytes()[..len]);
+  }
+
+  /// 在 `address` 处的指令中填入立即数
+  fn patch(&mut self, address: u32, imm: u32) {
+    let inst = self.load(address, 4);
+    self.store(address, inst | imm, 4);
+  }
+
+  /// 依次把各目标文件的各段放入内存并完成重定位；返回 `_start` 的地址
+  fn link(&mut self, objects: &[Vec<u8>]) -> u32 {
+    let elfs: Vec<_> = objects.iter().map(|object| Elf::parse(object)).collect();
+    // 各目标文件中各段的地址，下标同段表
+    let mut cursor = BASE;
+    let mut addresses = vec![];
+    for elf in &elfs {
+      assert!(!elf.elf64, "only RV32 objects can run");
+      let mut section_addresses = vec![0; elf.sections.len()];
+      for (index, section) in elf.sections.iter().enumerate() {
+        if section.ty != SHT_PROGBITS && section.ty != SHT_NOBITS {
+          continue;
+        }
+        cursor = cursor.next_multiple_of(16);
+        section_addresses[index] = cursor as u32;
+        if section.ty == SHT_PROGBITS {
+          let data = elf.data(&section.name);
+          self.memory[cursor..cursor + data.len()].copy_from_slice(data);
+        }
+        cursor += section.size;
+      }
+      addresses.push(section_addresses);
+    }
+
+    let symbols: Vec<_> = elfs.iter().map(Elf::symbols).collect();
+    let mut globals = HashMap::new();
+    for (symbols, addresses) in symbols.iter().zip(&addresses) {
+      for (name, value, _, info, shndx) in symbols {
+        if info >> 4 == 1 && *shndx != 0 {
+          globals.insert(name.as_str(), addresses[*shndx as usize] + *value as u32);
+        }
+      }
+    }
+
+    for ((elf, symbols), addresses) in elfs.iter().zip(&symbols).zip(&addresses) {
+      // 先在本目标文件中查找，再查找全局符号
+      let resolve = |name: &str| {
+        let local = symbols.iter().find(|s| s.0 == name && s.4 != 0);
+        let local = local.map(|s| addresses[s.4 as usize] + s.1 as u32);
+        let address = local.or_else(|| globals.get(name).copied());
+        address.unwrap_or_else(|| panic!("undefined symbol {}", name))
+      };
+      // `auipc` 的地址 -> 其目标相对于它的偏移，供 `%pcrel_lo` 使用
+      let mut pcrel_hi = HashMap::new();
+      for rela in elf.sections.iter().filter(|s| s.ty == SHT_RELA) {
+        let base = addresses[rela.info as usize];
+        for (offset, kind, symbol) in elf.relocs(&rela.name) {
+          let pc = base + offset as u32;
+          let target = resolve(&symbol);
+          let delta = target.wrapping_sub(pc);
+          match kind {
+            R_RISCV_BRANCH => self.patch(pc, b_imm(delta)),
+            R_RISCV_JAL => self.patch(pc, j_imm(delta)),
+            R_RISCV_CALL_PLT => {
+              self.patch(pc, u_imm(delta));
+              self.patch(pc + 4, i_imm(delta));
+            }
+            R_RISCV_PCREL_HI20 => {
+              self.patch(pc, u_imm(delta));
+              pcrel_hi.insert(pc, delta);
+            }
+            R_RISCV_PCREL_LO12_I => self.patch(pc, i_imm(pcrel_hi[&target])),
+            R_RISCV_HI20 => self.patch(pc, u_imm(target)),
+            R_RISCV_LO12_I => self.patch(pc, i_imm(target)),
+            R_RISCV_LO12_S => self.patch(pc, s_imm(target)),
+            _ => panic!("unsupported relocation {} against {}", kind, symbol),
+          }
+        }
+      }
+    }
+    globals["_start"]
+  }
+
+  /// 系统调用：号码在 `a7`，参数在 `a0` 起的寄存器，返回值放入 `a0`；`exit_group` 时返回退出码
+  fn syscall(&mut self, x: &mut [u32; 32]) -> Option<i32> {
+    let (a0, a1, a2) = (x[10], x[11], x[12] as usize);
+    x[10] = match x[17] {
+      SYS_READ => {
+        let len = a2.min(self.input.len());
+        let (read, rest) = self.input.split_at(len);
+        self.bytes(a1, len).copy_from_slice(read);
+        self.input = rest;
+        len as u32
+      }
+      SYS_WRITE => {
+        let data = self.bytes(a1, a2).to_vec();
+        match a0 {
+          1 => self.stdout.extend(data),
+          2 => self.stderr.extend(data),
+          _ => panic!("write to file descriptor {}", a0),
+        }
+        a2 as u32
+      }
+      SYS_EXIT_GROUP => return Some(a0 as i32),
+      SYS_CLOCK_GETTIME64 => {
+        self.bytes(a1, 16).fill(0);
+        0
+      }
+      number => panic!("unsupported system call {}", number),
+    };
+    None
+  }
+
+  /// 从 `entry` 开始逐条执行指令；返回退出码
+  fn execute(&mut self, entry: u32) -> i32 {
+    let mut x = [0u32; 32];
+    x[2] = MEMORY_SIZE as u32;
+    let mut pc = entry;
+    for _ in 0..MAX_STEPS {
+      let inst = self.load(pc, 4);
+      let rd = (inst >> 7 & 31) as usize;
+      let (a, b) = (x[(inst >> 15 & 31) as usize], x[(inst >> 20 & 31) as usize]);
+      let (funct3, funct7) = (inst >> 12 & 7, inst >> 25);
+      let imm = (inst as i32 >> 20) as u32;
+      let mut next = pc.wrapping_add(4);
+      let value = match inst & 0x7f {
+        0x37 => Some(inst & 0xffff_f000),
+        0x17 => Some(pc.wrapping_add(inst & 0xffff_f000)),
+        0x6f => {
+          next = pc.wrapping_add(j_offset(inst));
+          Some(pc.wrapping_add(4))
+        }
+        0x67 => {
+          next = a.wrapping_add(imm) & !1;
+          Some(pc.wrapping_add(4))
+        }
+        0x63 => {
+          let taken = match funct3 {
+            0 => a == b,
+            1 => a != b,
+            4 => (a as i32) < (b as i32),
+            5 => (a as i32) >= (b as i32),
+            6 => a < b,
+            7 => a >= b,
+            _ => panic!("illegal branch {:#010x} at {:#x}", inst, pc),
+          };
+          if taken {
+            next = pc.wrapping_add(b_offset(inst));
+          }
+          None
+        }
+        0x03 => {
+          let address = a.wrapping_add(imm);
+          Some(match funct3 {
+            0 => self.load(address, 1) as i8 as u32,
+            1 => self.load(address, 2) as i16 as u32,
+            2 => self.load(address, 4),
+            4 => self.load(address, 1),
+            5 => self.load(address, 2),
+            _ => panic!("illegal load {:#010x} at {:#x}", inst, pc),
+          })
+        }
+        0x23 => {
+          self.store(a.wrapping_add(s_offset(inst)), b, 1 << funct3);
+          None
+        }
+        0x13 => Some(alu(funct3, funct7, true, a, imm)),
+        0x33 if funct7 == 1 => Some(muldiv(funct3, a, b)),
+        0x33 => Some(alu(funct3, funct7, false, a, b)),
+        0x73 if inst == 0x73 => match self.syscall(&mut x) {
+          Some(code) => return code,
+          None => None,
+        },
+        _ => panic!("illegal instruction {:#010x} at {:#x}", inst, pc),
+      };
+      if let Some(value) = value.filter(|_| rd != 0) {
+        x[rd] = value;
+      }
+      pc = next;
+    }
+    panic!("still running after {} instructions", MAX_STEPS)
+  }
+}
+
+/// 链接 `objects` 并运行，标准输入为 `input`
+pub fn run(objects: &[Vec<u8>], input: &[u8]) -> Output {
+  let mut machine = Machine {
+    memory: vec![0; MEMORY_SIZE],
+    input,
+    stdout: vec![],
+    stderr: vec![],
+  };
+  let entry = machine.link(objects);
+  let code = machine.execute(entry);
+  Output {
+    stdout: machine.stdout,
+    stderr: machine.stderr,
+    code,
+  }
+}
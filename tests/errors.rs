@@ -0,0 +1,117 @@
+//! 错误程序的回归测试：`tests/errors/` 下的每个程序都有错，其中多是模糊测试（见 `fuzz/`）找到的、
+//! 曾使编译器 panic 的输入。语义分析须给出诊断，生成 IR 时须返回错误而不是 panic，错误信息（不含
+//! 终端的颜色）同同名的 `.err` 文件逐字比较。
+//!
+//! 新增或有意改变错误信息时，以 `UPDATE_GOLDEN=1 cargo test --test errors` 重新生成 `.err` 文件。
+
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::process::Command;
+
+use common::manifest_dir;
+use sysyc::diagnostics::Kind;
+use sysyc::timing::Timings;
+use sysyc::{CompileOptions, Diagnostic};
+
+mod common;
+
+#[test]
+fn errors() {
+  let dir = manifest_dir().join("tests").join("errors");
+  let entries = fs::read_dir(&dir).expect("cannot read tests/errors/");
+  let mut cases: Vec<_> = entries
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "sy"))
+    .collect();
+  cases.sort();
+  let mut failures = vec![];
+  for case in cases {
+    let stem = case.file_stem().unwrap().to_string_lossy().into_owned();
+    let source = fs::read_to_string(&case).unwrap();
+    if sysyc::diagnose(&source).is_empty() {
+      failures.push(format!("{}: no diagnostics", stem));
+    }
+    let compile = || sysyc::compile_to_koopa(&source, &CompileOptions::default());
+    let message = match panic::catch_unwind(AssertUnwindSafe(compile)) {
+      Ok(Ok(_)) => {
+        failures.push(format!("{}: compiled without error", stem));
+        continue;
+      }
+      Ok(Err(e)) => Diagnostic::from_error(&*e).message,
+      Err(_) => {
+        failures.push(format!("{}: panicked", stem));
+        continue;
+      }
+    };
+    if let Err(e) = common::check(&dir.join(format!("{}.err", stem)), &(message + "\n")) {
+      failures.push(e);
+    }
+  }
+  assert!(
+    failures.is_empty(),
+    "unexpected results for tests/errors/ (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+    failures.join("\n")
+  );
+}
+
+/// 几个源文件一同编译时，错误的诊断带有出错的文件名，信息与种类同只有一个源文件时相同
+#[test]
+fn file_name() {
+  let inputs = [
+    ("a.sy", "int f() { return 1; }".to_string()),
+    ("b.sy", "int main() { return f() + g(); }".to_string()),
+  ];
+  let options = CompileOptions::default();
+  let instrument = Default::default();
+  let timings = Timings::default();
+  let result = sysyc::frontend::generate_ir(&inputs, &options.target, instrument, false, &timings);
+  let Err(e) = result else {
+    panic!("compiled without error");
+  };
+  let diagnostic = Diagnostic::from_error(e.as_ref());
+  assert_eq!(diagnostic.file.as_deref(), Some("b.sy"));
+  assert_eq!(diagnostic.kind, Kind::Source);
+  assert_eq!(diagnostic.message, "隐式声明函数 'g'：调用前须声明");
+  let json = diagnostic.to_json(None).to_string();
+  assert!(json.contains(r#""file":"b.sy""#), "{}", json);
+}
+
+/// 调用的错误标出被调用的函数名，与之同名的变量标出其声明中的名字，实参类型的错误标出该实参
+#[test]
+fn call_spans() {
+  let source = "int main() {\n  int fo = 1, a[2];\n  fo(1);\n  bar();\n  putint(1, 2);\n  putint(a);\n  return fo;\n}\n";
+  // `source` 中首个 `pattern` 开头的 `len` 个字节
+  let span = |pattern: &str, len: usize| {
+    let start = source.find(pattern).unwrap();
+    (start, start + len)
+  };
+  let diagnostics = sysyc::diagnose(source);
+  let spans: Vec<_> = diagnostics.iter().map(|d| d.span.unwrap()).collect();
+  let expected = [
+    span("fo(", 2),
+    span("bar", 3),
+    span("putint(1", 6),
+    span("a)", 1),
+  ];
+  assert_eq!(spans, expected);
+  let label = &diagnostics[0].labels[0];
+  assert_eq!(label.span, span("fo =", 2));
+  assert_eq!(label.message, "在此声明");
+}
+
+/// 读写文件出错时，错误信息带有文件名
+#[test]
+fn io_error_path() {
+  let missing = manifest_dir()
+    .join("tests")
+    .join("errors")
+    .join("missing.sy");
+  let output = Command::new(env!("CARGO_BIN_EXE_sysyc"))
+    .arg("-koopa")
+    .arg(&missing)
+    .output()
+    .unwrap();
+  assert_eq!(output.status.code(), Some(1));
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  assert!(stderr.contains(&*missing.to_string_lossy()), "{}", stderr);
+}
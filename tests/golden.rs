@@ -0,0 +1,204 @@
+//! 代码生成的回归测试：编译 `testcases/` 下的每个程序，把 Koopa IR 与 RISC-V 汇编同
+//! `tests/golden/` 中检入的结果逐字比较，重构时代码生成的任何变化都会使测试失败。
+//!
+//! 有意改变代码生成时，以 `UPDATE_GOLDEN=1 cargo test --test golden` 重新生成这些文件，
+//! 随改动一同提交，审阅时即可看到输出的变化。
+//!
+//! 压力测试的输入（[`STRESS`]）输出很大，不逐字比较，而是检查能否编译、运行结果是否正确以及
+//! 输出的大小。
+
+use std::ffi::CString;
+use std::fs;
+use std::path::PathBuf;
+use std::{ptr, thread};
+
+use common::manifest_dir;
+use koopa::back::KoopaGenerator;
+use sysyc::ffi::{sysy_compile, SysyOptions, SysyOutput, SYSY_OK};
+use sysyc::{interpreter, CompileOptions};
+
+mod common;
+
+/// 压力测试的输入，不与检入的结果比较
+const STRESS: &[&str] = &["deep_nesting", "sparse_init"];
+
+/// `testcases/` 下的 `.sy` 文件，按文件名排序
+fn cases() -> Vec<PathBuf> {
+  let dir = manifest_dir().join("testcases");
+  let entries = fs::read_dir(dir).expect("cannot read testcases/");
+  let mut cases: Vec<_> = entries
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "sy"))
+    .collect();
+  cases.sort();
+  cases
+}
+
+/// `testcases/` 下的 `stem` 的源代码
+fn source(stem: &str) -> String {
+  let path = manifest_dir()
+    .join("testcases")
+    .join(format!("{}.sy", stem));
+  fs::read_to_string(path).unwrap()
+}
+
+/// 解释执行 `source`，输出与退出码须同 `testcases/` 下的 `.out` 一致
+fn assert_runs(stem: &str, source: &str) {
+  let path = manifest_dir()
+    .join("testcases")
+    .join(format!("{}.out", stem));
+  let expected = fs::read_to_string(path).unwrap();
+  let ir = sysyc::compile_to_koopa(source, &CompileOptions::default()).unwrap();
+  let (stdout, code) = interpreter::run_captured(&ir, vec![]).unwrap();
+  let mut actual = String::from_utf8(stdout).unwrap();
+  if !actual.is_empty() && !actual.ends_with('\n') {
+    actual.push('\n');
+  }
+  actual += &format!("{}\n", code & 0xff);
+  assert_eq!(actual.trim_end(), expected.trim_end(), "{}", stem);
+}
+
+/// 一个程序的各项输出：扩展名与内容
+fn outputs(source: &str) -> sysyc::Result<Vec<(&'static str, String)>> {
+  let options = CompileOptions::default();
+  let ir = sysyc::compile_to_koopa(source, &options)?;
+  let mut koopa = KoopaGenerator::new(Vec::new());
+  koopa.generate_on(&ir)?;
+  let koopa = String::from_utf8(koopa.writer())?;
+  let riscv = sysyc::compile_to_riscv(source, &options)?;
+  Ok(vec![("koopa", koopa), ("s", riscv)])
+}
+
+fn check_all() {
+  let golden_dir = manifest_dir().join("tests").join("golden");
+  let mut failures = vec![];
+  for case in cases() {
+    let stem = case.file_stem().unwrap().to_string_lossy().into_owned();
+    if STRESS.contains(&stem.as_str()) {
+      continue;
+    }
+    let source = fs::read_to_string(&case).unwrap();
+    let outputs = match outputs(&source) {
+      Ok(outputs) => outputs,
+      Err(e) => {
+        failures.push(format!("{}: compile error: {}", stem, e));
+        continue;
+      }
+    };
+    for (extension, actual) in outputs {
+      let golden = golden_dir.join(format!("{}.{}", stem, extension));
+      if let Err(e) = common::check(&golden, &actual) {
+        failures.push(e);
+      }
+    }
+  }
+  assert!(
+    failures.is_empty(),
+    "output differs from tests/golden/ (rerun with UPDATE_GOLDEN=1 if intended):\n{}",
+    failures.join("\n")
+  );
+}
+
+#[test]
+fn golden() {
+  check_all();
+}
+
+/// 各次编译的状态互不共享：几个线程同时编译全部用例，输出仍与检入的结果相同
+#[test]
+fn concurrent() {
+  if common::update() {
+    return;
+  }
+  let handles: Vec<_> = (0..2).map(|_| thread::spawn(check_all)).collect();
+  for handle in handles {
+    if let Err(payload) = handle.join() {
+      std::panic::resume_unwind(payload);
+    }
+  }
+}
+
+/// 线程数只是各次编译的选项：单线程与多线程（经过并行的指令调度）的输出逐字相同
+#[test]
+fn jobs() {
+  for case in cases() {
+    let source = fs::read_to_string(&case).unwrap();
+    let compile = |jobs| {
+      let options = CompileOptions {
+        passes: vec!["schedule".into()],
+        jobs,
+        ..Default::default()
+      };
+      sysyc::compile_to_riscv(&source, &options).unwrap()
+    };
+    assert_eq!(compile(1), compile(4), "{}", case.display());
+  }
+}
+
+/// 以零为主的大数组只有非零元素占据输出：`sparse_init.sy` 中的数组共约 1.6MB，生成的
+/// RISC-V 汇编、WebAssembly 模块、C 源代码与 LLVM IR 都不随数组的大小增长
+#[test]
+fn sparse_init() {
+  let source = source("sparse_init");
+  assert_runs("sparse_init", &source);
+  let riscv = sysyc::compile_to_riscv(&source, &CompileOptions::default()).unwrap();
+  assert!(
+    riscv.len() < 16 << 10,
+    "RISC-V output has {} bytes",
+    riscv.len()
+  );
+  let ir = sysyc::compile_to_koopa(&source, &CompileOptions::default()).unwrap();
+  let mut c = vec![];
+  sysyc::backend::generate_c(&ir, &mut c).unwrap();
+  assert!(c.len() < 16 << 10, "C output has {} bytes", c.len());
+  let mut llvm = vec![];
+  sysyc::backend::generate_llvm(&ir, &mut llvm).unwrap();
+  assert!(
+    llvm.len() < 16 << 10,
+    "LLVM output has {} bytes",
+    llvm.len()
+  );
+  let wasm = sysyc::backend::wasm::generate(&ir).unwrap().encode();
+  assert!(
+    wasm.len() < 16 << 10,
+    "wasm output has {} bytes",
+    wasm.len()
+  );
+}
+
+/// 库的各个入口自行准备递归所需的栈：在只有 1 MiB 栈的线程中也能编译、分析与输出
+/// `deep_nesting.sy` 中嵌套上千层的表达式，运行结果正确，汇编的大小随嵌套的层数线性增长
+#[test]
+fn deep_nesting() {
+  let source = source("deep_nesting");
+  let compile = move || {
+    assert_runs("deep_nesting", &source);
+    let options = CompileOptions::default();
+    let riscv = sysyc::compile_to_riscv(&source, &options).unwrap();
+    assert!(
+      riscv.len() < 1 << 20,
+      "RISC-V output has {} bytes",
+      riscv.len()
+    );
+    assert!(sysyc::diagnose(&source).is_empty());
+    sysyc::frontend::dump_ast_json(&source).unwrap();
+    sysyc::playground::compile(&source, "koopa").unwrap();
+    let source = CString::new(source).unwrap();
+    let options = SysyOptions {
+      output: SysyOutput::Riscv,
+      target: ptr::null(),
+      march: ptr::null(),
+      mcpu: ptr::null(),
+      passes: ptr::null(),
+      pic: false,
+      const_pool: false,
+    };
+    // SAFETY: 参数都是有效的字符串与选项，不取输出
+    let status = unsafe { sysy_compile(source.as_ptr(), &options, ptr::null_mut()) };
+    assert_eq!(status, SYSY_OK);
+  };
+  let handle = thread::Builder::new().stack_size(1 << 20).spawn(compile);
+  if let Err(payload) = handle.unwrap().join() {
+    std::panic::resume_unwind(payload);
+  }
+}
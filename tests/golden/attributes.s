@@ -0,0 +1,264 @@
+  .data
+  .globl input
+  .type input, @object
+  .align 2
+input:
+  .word 7
+  .size input, 4
+
+  .text
+  .globl fail
+  .type fail, @function
+fail:
+  addi sp, sp, -32
+  sw ra, 28(sp)
+.Lfail_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 12(sp)
+  li a0, 10
+  call putch
+  sw a0, 16(sp)
+  j .Lfail_bb_while_entry_1
+.Lfail_bb_while_body_1:
+.Lfail_bb_while_entry_1:
+  li t0, 1
+  bnez t0, .Lfail_bb_while_body_1
+.Lfail_bb_while_end_1:
+  lw ra, 28(sp)
+  addi sp, sp, 32
+  ret
+.Lfunc_end_fail:
+  .size fail, .Lfunc_end_fail-fail
+
+  .text
+  .section .text.hot,"ax",@progbits
+  .globl scale
+  .type scale, @function
+scale:
+  addi sp, sp, -48
+  sw ra, 44(sp)
+.Lscale_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  li t1, 1000
+  sgt t0, t0, t1
+  sw t0, 12(sp)
+  beqz t0, .Lscale_bb_if_end_1
+.Lscale_bb_if_true_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  add a0, zero, t0
+  call fail
+  mv a0, zero
+  lw ra, 44(sp)
+  addi sp, sp, 48
+  ret
+.Lscale_bb_if_end_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 20(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 24(sp)
+  li t0, 1
+  add t0, t1, t0
+  sw t0, 28(sp)
+  mv a0, t0
+  lw ra, 44(sp)
+  addi sp, sp, 48
+  ret
+.Lfunc_end_scale:
+  .size scale, .Lfunc_end_scale-scale
+
+  .text
+  .globl square
+  .type square, @function
+square:
+  addi sp, sp, -32
+.Lsquare_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t1, 0(t0)
+  sw t1, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 12(sp)
+  lw t1, 8(sp)
+  mul t0, t1, t0
+  sw t0, 16(sp)
+  mv a0, t0
+  addi sp, sp, 32
+  ret
+.Lfunc_end_square:
+  .size square, .Lfunc_end_square-square
+
+  .text
+  .section .text.hot,"ax",@progbits
+  .globl mix
+  .type mix, @function
+mix:
+  addi sp, sp, -96
+  sw ra, 92(sp)
+.Lmix_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 20(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 24(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 12
+  sw t0, 28(sp)
+  sw zero, 0(t0)
+  j .Lmix_bb_while_entry_1
+.Lmix_bb_while_body_1:
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 44(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  lw t1, 48(sp)
+  add t0, t1, t0
+  sw t0, 56(sp)
+  add a0, zero, t0
+  call scale
+  sw a0, 60(sp)
+  lw t0, 60(sp)
+  li t1, 97
+  rem t0, t0, t1
+  sw t0, 64(sp)
+  lw t1, 44(sp)
+  add t0, t1, t0
+  sw t0, 68(sp)
+  lw t1, 28(sp)
+  sw t0, 0(t1)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 72(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 76(sp)
+  lw t1, 24(sp)
+  sw t0, 0(t1)
+.Lmix_bb_while_entry_1:
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t1, 32(sp)
+  slt t0, t1, t0
+  sw t0, 40(sp)
+  bnez t0, .Lmix_bb_while_body_1
+.Lmix_bb_while_end_1:
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 80(sp)
+  mv a0, t0
+  lw ra, 92(sp)
+  addi sp, sp, 96
+  ret
+.Lfunc_end_mix:
+  .size mix, .Lfunc_end_mix-mix
+
+  .text
+  .globl check
+  .type check, @function
+check:
+  addi sp, sp, -32
+  sw ra, 28(sp)
+.Lcheck_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  slt t0, t0, zero
+  sw t0, 12(sp)
+  beqz t0, .Lcheck_bb_if_false_1
+.Lcheck_bb_if_true_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  add a0, zero, t0
+  call fail
+  mv a0, zero
+  lw ra, 28(sp)
+  addi sp, sp, 32
+  ret
+.Lcheck_bb_if_false_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 20(sp)
+  add a0, zero, t0
+  call square
+  sw a0, 24(sp)
+  lw a0, 24(sp)
+  lw ra, 28(sp)
+  addi sp, sp, 32
+  ret
+.Lfunc_end_check:
+  .size check, .Lfunc_end_check-check
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -48
+  sw ra, 44(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  li t1, 914
+  sw t1, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 12(sp)
+  li a0, 10
+  call putch
+  sw a0, 16(sp)
+  lui t0, %hi(input)
+  lw t0, %lo(input)(t0)
+  sw t0, 20(sp)
+  add a0, zero, t0
+  call scale
+  sw a0, 24(sp)
+  lw a0, 24(sp)
+  call putint
+  sw a0, 28(sp)
+  li a0, 10
+  call putch
+  sw a0, 32(sp)
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  li t1, 256
+  rem t0, t0, t1
+  sw t0, 40(sp)
+  mv a0, t0
+  lw ra, 44(sp)
+  addi sp, sp, 48
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
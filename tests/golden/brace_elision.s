@@ -0,0 +1,723 @@
+  .data
+  .globl a
+  .type a, @object
+  .align 2
+a:
+  .word 1, 2, 3, 4, 0, 0
+  .size a, 24
+
+  .data
+  .globl b
+  .type b, @object
+  .align 2
+b:
+  .word 1, 0, 0, 2, 3, 0
+  .size b, 24
+
+  .data
+  .globl c
+  .type c, @object
+  .align 2
+c:
+  .word 1, 2, 3, 0, 0, 0
+  .size c, 24
+
+  .data
+  .globl d
+  .type d, @object
+  .align 2
+d:
+  .word 1, 2, 3, 0, 4, 5, 6, 0
+  .size d, 32
+
+  .text
+  .globl row
+  .type row, @function
+row:
+  addi sp, sp, -80
+  sw ra, 76(sp)
+.Lrow_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 16(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 20(sp)
+  sw zero, 0(t0)
+  j .Lrow_bb_while_entry_1
+.Lrow_bb_while_body_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 44(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 52(sp)
+  li a0, 32
+  call putch
+  sw a0, 56(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 60(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 64(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lrow_bb_while_entry_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slt t0, t1, t0
+  sw t0, 32(sp)
+  bnez t0, .Lrow_bb_while_body_1
+.Lrow_bb_while_end_1:
+  li a0, 10
+  call putch
+  sw a0, 68(sp)
+  lw ra, 76(sp)
+  addi sp, sp, 80
+  ret
+.Lfunc_end_row:
+  .size row, .Lfunc_end_row-row
+
+  .text
+  .globl rows3
+  .type rows3, @function
+rows3:
+  addi sp, sp, -64
+  sw ra, 60(sp)
+.Lrows3_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 16(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 20(sp)
+  sw zero, 0(t0)
+  j .Lrows3_bb_while_entry_1
+.Lrows3_bb_while_body_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  slli t2, t0, 2
+  slli t0, t0, 3
+  add t2, t2, t0
+  add t0, t1, t2
+  sw t0, 44(sp)
+  sw t0, 48(sp)
+  add a0, zero, t0
+  li a1, 3
+  call row
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 56(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lrows3_bb_while_entry_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slt t0, t1, t0
+  sw t0, 32(sp)
+  bnez t0, .Lrows3_bb_while_body_1
+.Lrows3_bb_while_end_1:
+  lw ra, 60(sp)
+  addi sp, sp, 64
+  ret
+.Lfunc_end_rows3:
+  .size rows3, .Lfunc_end_rows3-rows3
+
+  .text
+  .globl rows2
+  .type rows2, @function
+rows2:
+  addi sp, sp, -64
+  sw ra, 60(sp)
+.Lrows2_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 16(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 20(sp)
+  sw zero, 0(t0)
+  j .Lrows2_bb_while_entry_1
+.Lrows2_bb_while_body_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  slli t0, t0, 3
+  add t0, t1, t0
+  sw t0, 44(sp)
+  sw t0, 48(sp)
+  add a0, zero, t0
+  li a1, 2
+  call row
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 56(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lrows2_bb_while_entry_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slt t0, t1, t0
+  sw t0, 32(sp)
+  bnez t0, .Lrows2_bb_while_body_1
+.Lrows2_bb_while_end_1:
+  lw ra, 60(sp)
+  addi sp, sp, 64
+  ret
+.Lfunc_end_rows2:
+  .size rows2, .Lfunc_end_rows2-rows2
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -752
+  sw ra, 748(sp)
+.Lmain_bb_entry_0:
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 180(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows3
+  lui t0, %hi(b)
+  addi t0, t0, %lo(b)
+  sw t0, 184(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows3
+  lui t0, %hi(c)
+  addi t0, t0, %lo(c)
+  sw t0, 188(sp)
+  add a0, zero, t0
+  li a1, 3
+  call rows2
+  lui t0, %hi(d)
+  addi t0, t0, %lo(d)
+  sw t0, 192(sp)
+  sw t0, 196(sp)
+  sw t0, 200(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows2
+  lui t0, %hi(d)
+  addi t0, t0, %lo(d)
+  sw t0, 204(sp)
+  li t1, 1
+  slli t1, t1, 4
+  add t0, t0, t1
+  sw t0, 208(sp)
+  sw t0, 212(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows2
+  addi t0, sp, 0
+  sw t0, 216(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  addi t0, sp, 4
+  sw t0, 228(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_2
+.Lmain_bb_while_body_2:
+  addi t0, sp, 84
+  sw t0, 464(sp)
+  sw t0, 468(sp)
+  sw t0, 472(sp)
+  sw t0, 476(sp)
+  li t1, 1
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 480(sp)
+  li t1, 2
+  sw t1, 0(t0)
+  lw t0, 468(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 484(sp)
+  sw t0, 488(sp)
+  li t1, 3
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 492(sp)
+  sw zero, 0(t0)
+  lw t0, 468(sp)
+  li t1, 2
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 496(sp)
+  sw t0, 500(sp)
+  sw zero, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 504(sp)
+  sw zero, 0(t0)
+  lw t0, 464(sp)
+  li t1, 1
+  slli t2, t1, 3
+  slli t1, t1, 4
+  add t2, t2, t1
+  add t0, t0, t2
+  sw t0, 508(sp)
+  sw t0, 512(sp)
+  sw t0, 516(sp)
+  li t1, 4
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 520(sp)
+  sw zero, 0(t0)
+  lw t0, 508(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 524(sp)
+  sw t0, 528(sp)
+  li t1, 5
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 532(sp)
+  li t1, 6
+  sw t1, 0(t0)
+  lw t0, 508(sp)
+  li t1, 2
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 536(sp)
+  sw t0, 540(sp)
+  li t1, 7
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 544(sp)
+  sw zero, 0(t0)
+  lw t0, 464(sp)
+  sw t0, 548(sp)
+  lw t0, 216(sp)
+  lw t0, 0(t0)
+  sw t0, 552(sp)
+  lw t1, 548(sp)
+  slli t2, t0, 3
+  slli t0, t0, 4
+  add t2, t2, t0
+  add t0, t1, t2
+  sw t0, 556(sp)
+  sw t0, 560(sp)
+  lw t0, 228(sp)
+  lw t0, 0(t0)
+  sw t0, 564(sp)
+  lw t1, 560(sp)
+  slli t0, t0, 3
+  add t0, t1, t0
+  sw t0, 568(sp)
+  sw t0, 572(sp)
+  sw t0, 576(sp)
+  lw t0, 0(t0)
+  sw t0, 580(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 584(sp)
+  li a0, 32
+  call putch
+  sw a0, 588(sp)
+  addi t0, sp, 132
+  sw t0, 592(sp)
+  sw t0, 596(sp)
+  sw t0, 600(sp)
+  sw t0, 604(sp)
+  li t1, 1
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 608(sp)
+  li t1, 2
+  sw t1, 0(t0)
+  lw t0, 596(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 612(sp)
+  sw t0, 616(sp)
+  li t1, 3
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 620(sp)
+  sw zero, 0(t0)
+  lw t0, 596(sp)
+  li t1, 2
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 624(sp)
+  sw t0, 628(sp)
+  sw zero, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 632(sp)
+  sw zero, 0(t0)
+  lw t0, 592(sp)
+  li t1, 1
+  slli t2, t1, 3
+  slli t1, t1, 4
+  add t2, t2, t1
+  add t0, t0, t2
+  sw t0, 636(sp)
+  sw t0, 640(sp)
+  sw t0, 644(sp)
+  li t1, 4
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 648(sp)
+  sw zero, 0(t0)
+  lw t0, 636(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 652(sp)
+  sw t0, 656(sp)
+  li t1, 5
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 660(sp)
+  li t1, 6
+  sw t1, 0(t0)
+  lw t0, 636(sp)
+  li t1, 2
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 664(sp)
+  sw t0, 668(sp)
+  li t1, 7
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 672(sp)
+  sw zero, 0(t0)
+  lw t0, 592(sp)
+  sw t0, 676(sp)
+  lw t0, 216(sp)
+  lw t0, 0(t0)
+  sw t0, 680(sp)
+  lw t1, 676(sp)
+  slli t2, t0, 3
+  slli t0, t0, 4
+  add t2, t2, t0
+  add t0, t1, t2
+  sw t0, 684(sp)
+  sw t0, 688(sp)
+  lw t0, 228(sp)
+  lw t0, 0(t0)
+  sw t0, 692(sp)
+  lw t1, 688(sp)
+  slli t0, t0, 3
+  add t0, t1, t0
+  sw t0, 696(sp)
+  sw t0, 700(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 704(sp)
+  lw t0, 0(t0)
+  sw t0, 708(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 712(sp)
+  li a0, 32
+  call putch
+  sw a0, 716(sp)
+  lw t0, 228(sp)
+  lw t0, 0(t0)
+  sw t0, 720(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 724(sp)
+  lw t1, 228(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_2:
+  lw t0, 228(sp)
+  lw t0, 0(t0)
+  sw t0, 456(sp)
+  li t1, 3
+  slt t0, t0, t1
+  sw t0, 460(sp)
+  bnez t0, .Lmain_bb_while_body_2
+.Lmain_bb_while_end_2:
+  li a0, 10
+  call putch
+  sw a0, 728(sp)
+  lw t0, 216(sp)
+  lw t0, 0(t0)
+  sw t0, 732(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 736(sp)
+  lw t1, 216(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  lw t0, 216(sp)
+  lw t0, 0(t0)
+  sw t0, 220(sp)
+  li t1, 2
+  slt t0, t0, t1
+  sw t0, 224(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  addi t0, sp, 8
+  sw t0, 232(sp)
+  li t1, 7
+  sw t1, 0(t0)
+  addi t1, sp, 12
+  sw t1, 236(sp)
+  lw t0, 0(t0)
+  sw t0, 240(sp)
+  lw t0, 236(sp)
+  sw t0, 244(sp)
+  sw t0, 248(sp)
+  sw zero, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 252(sp)
+  sw zero, 0(t0)
+  lw t0, 236(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 256(sp)
+  sw t0, 260(sp)
+  lw t1, 240(sp)
+  sw t1, 0(t0)
+  lw t0, 256(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 264(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 28
+  sw t0, 268(sp)
+  lw t0, 232(sp)
+  lw t1, 0(t0)
+  sw t1, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 276(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 280(sp)
+  lw t0, 232(sp)
+  lw t0, 0(t0)
+  sw t0, 284(sp)
+  li t1, 2
+  add t0, t0, t1
+  sw t0, 288(sp)
+  lw t0, 268(sp)
+  sw t0, 292(sp)
+  sw t0, 296(sp)
+  lw t1, 272(sp)
+  sw t1, 0(t0)
+  lw t0, 292(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 300(sp)
+  lw t1, 280(sp)
+  sw t1, 0(t0)
+  lw t0, 268(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 304(sp)
+  sw t0, 308(sp)
+  lw t1, 288(sp)
+  sw t1, 0(t0)
+  lw t0, 304(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 312(sp)
+  sw zero, 0(t0)
+  lw t0, 268(sp)
+  li t1, 2
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 316(sp)
+  sw t0, 320(sp)
+  sw zero, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 324(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 52
+  sw t0, 328(sp)
+  lw t0, 232(sp)
+  lw t1, 0(t0)
+  sw t1, 332(sp)
+  lw t0, 0(t0)
+  sw t0, 336(sp)
+  lw t0, 328(sp)
+  sw t0, 340(sp)
+  sw t0, 344(sp)
+  sw t0, 348(sp)
+  li t1, 1
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 352(sp)
+  lw t1, 332(sp)
+  sw t1, 0(t0)
+  lw t0, 340(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 356(sp)
+  sw t0, 360(sp)
+  sw zero, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 364(sp)
+  sw zero, 0(t0)
+  lw t0, 328(sp)
+  li t1, 1
+  slli t1, t1, 4
+  add t0, t0, t1
+  sw t0, 368(sp)
+  sw t0, 372(sp)
+  sw t0, 376(sp)
+  lw t1, 336(sp)
+  sw t1, 0(t0)
+  lw t0, 372(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 380(sp)
+  li t1, 3
+  sw t1, 0(t0)
+  lw t0, 368(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 384(sp)
+  sw t0, 388(sp)
+  li t1, 4
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 392(sp)
+  sw zero, 0(t0)
+  lw t0, 236(sp)
+  sw t0, 396(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows2
+  lw t0, 268(sp)
+  sw t0, 400(sp)
+  add a0, zero, t0
+  li a1, 3
+  call rows2
+  lw t0, 328(sp)
+  sw t0, 404(sp)
+  sw t0, 408(sp)
+  sw t0, 412(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows2
+  lw t0, 328(sp)
+  sw t0, 416(sp)
+  li t1, 1
+  slli t1, t1, 4
+  add t0, t0, t1
+  sw t0, 420(sp)
+  sw t0, 424(sp)
+  add a0, zero, t0
+  li a1, 2
+  call rows2
+  lw t0, 328(sp)
+  sw t0, 428(sp)
+  li t1, 1
+  slli t1, t1, 4
+  add t0, t0, t1
+  sw t0, 432(sp)
+  sw t0, 436(sp)
+  li t1, 1
+  slli t1, t1, 3
+  add t0, t0, t1
+  sw t0, 440(sp)
+  sw t0, 444(sp)
+  sw t0, 448(sp)
+  lw t0, 0(t0)
+  sw t0, 452(sp)
+  mv a0, t0
+  lw ra, 748(sp)
+  addi sp, sp, 752
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
@@ -0,0 +1,3242 @@
+  .bss
+  .globl a
+  .type a, @object
+  .align 2
+a:
+  .zero 64
+  .size a, 64
+
+  .text
+  .globl find
+  .type find, @function
+find:
+  addi sp, sp, -64
+.Lfind_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 8(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 12(sp)
+  sw zero, 0(t0)
+  j .Lfind_bb_while_entry_1
+.Lfind_bb_while_body_1:
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 24(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 32(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  xor t0, t1, t0
+  seqz t0, t0
+  sw t0, 44(sp)
+  bnez t0, .Lfind_bb_if_true_2
+.Lfind_bb_if_end_2:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 56(sp)
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+.Lfind_bb_while_entry_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  li t1, 16
+  slt t0, t0, t1
+  sw t0, 20(sp)
+  bnez t0, .Lfind_bb_while_body_1
+.Lfind_bb_while_end_1:
+  li a0, -1
+  addi sp, sp, 64
+  ret
+.Lfind_bb_if_true_2:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  mv a0, t0
+  addi sp, sp, 64
+  ret
+.Lfind_bb_unreachable_3:
+  j .Lfind_bb_if_end_2
+.Lfunc_end_find:
+  .size find, .Lfunc_end_find-find
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -2048
+  addi sp, sp, -1040
+  li t6, 3084
+  add t6, t6, sp
+  sw ra, 0(t6)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 24(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 32(sp)
+  lw t0, 12(sp)
+  lw t1, 0(t0)
+  sw t1, 36(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  mul t0, t1, t0
+  sw t0, 44(sp)
+  li t1, 11
+  rem t0, t0, t1
+  sw t0, 48(sp)
+  lw t1, 32(sp)
+  sw t0, 0(t1)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 56(sp)
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  li t1, 16
+  slt t0, t0, t1
+  sw t0, 20(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  addi t0, sp, 4
+  sw t0, 60(sp)
+  sw zero, 0(t0)
+  lw t0, 12(sp)
+  sw zero, 0(t0)
+.Lmain_bb_while_entry_2:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 64(sp)
+  li t1, 10
+  slt t0, t0, t1
+  sw t0, 68(sp)
+  bnez t0, .Lmain_bb_while_end_2_far2
+  j .Lmain_bb_while_end_2
+.Lmain_bb_while_end_2_far2:
+.Lmain_bb_while_body_2:
+  addi t0, sp, 8
+  sw t0, 72(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_3
+.Lmain_bb_while_body_3:
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 120(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 124(sp)
+  lw t1, 72(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 128(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 132(sp)
+  lw t1, 128(sp)
+  sgt t0, t1, t0
+  sw t0, 136(sp)
+  beqz t0, .Lmain_bb_if_true_4_far1
+  j .Lmain_bb_if_true_4
+.Lmain_bb_if_true_4_far1:
+.Lmain_bb_if_end_4:
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 152(sp)
+  li t1, 3
+  rem t0, t0, t1
+  sw t0, 156(sp)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 160(sp)
+  beqz t0, .Lmain_bb_if_end_6
+.Lmain_bb_if_true_6:
+  j .Lmain_bb_while_entry_3
+.Lmain_bb_if_end_6:
+  lw t0, 60(sp)
+  lw t0, 0(t0)
+  sw t0, 164(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 168(sp)
+  lw t1, 164(sp)
+  add t0, t1, t0
+  sw t0, 172(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_3:
+  li t0, 1
+  bnez t0, .Lmain_bb_while_body_3
+.Lmain_bb_while_end_3:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 140(sp)
+  li t1, 2
+  rem t0, t0, t1
+  sw t0, 144(sp)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 148(sp)
+  bnez t0, .Lmain_bb_if_false_8_far0
+  j .Lmain_bb_if_false_8
+.Lmain_bb_if_false_8_far0:
+.Lmain_bb_if_true_8:
+  lw t0, 60(sp)
+  lw t0, 0(t0)
+  sw t0, 176(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 180(sp)
+  slli t0, t0, 0
+  sw t0, 184(sp)
+  lw t1, 176(sp)
+  add t0, t1, t0
+  sw t0, 188(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 192(sp)
+  lw t1, 188(sp)
+  sub t0, t1, t0
+  sw t0, 196(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 200(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 204(sp)
+  slli t0, t0, 1
+  sw t0, 208(sp)
+  lw t1, 200(sp)
+  add t0, t1, t0
+  sw t0, 212(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 216(sp)
+  lw t1, 212(sp)
+  sub t0, t1, t0
+  sw t0, 220(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 224(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 228(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 232(sp)
+  lw t0, 224(sp)
+  add t0, t0, t1
+  sw t0, 236(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 240(sp)
+  lw t1, 236(sp)
+  sub t0, t1, t0
+  sw t0, 244(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 248(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 252(sp)
+  slli t0, t0, 2
+  sw t0, 256(sp)
+  lw t1, 248(sp)
+  add t0, t1, t0
+  sw t0, 260(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 264(sp)
+  lw t1, 260(sp)
+  sub t0, t1, t0
+  sw t0, 268(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 272(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 276(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 280(sp)
+  lw t0, 272(sp)
+  add t0, t0, t1
+  sw t0, 284(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 288(sp)
+  lw t1, 284(sp)
+  sub t0, t1, t0
+  sw t0, 292(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 296(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 300(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 304(sp)
+  lw t0, 296(sp)
+  add t0, t0, t1
+  sw t0, 308(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 312(sp)
+  lw t1, 308(sp)
+  sub t0, t1, t0
+  sw t0, 316(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 320(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 324(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 328(sp)
+  lw t1, 320(sp)
+  add t0, t1, t0
+  sw t0, 332(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 336(sp)
+  lw t1, 332(sp)
+  sub t0, t1, t0
+  sw t0, 340(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 344(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 348(sp)
+  slli t0, t0, 0
+  sw t0, 352(sp)
+  lw t1, 344(sp)
+  add t0, t1, t0
+  sw t0, 356(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 360(sp)
+  lw t1, 356(sp)
+  sub t0, t1, t0
+  sw t0, 364(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 368(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 372(sp)
+  slli t0, t0, 1
+  sw t0, 376(sp)
+  lw t1, 368(sp)
+  add t0, t1, t0
+  sw t0, 380(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 384(sp)
+  lw t1, 380(sp)
+  sub t0, t1, t0
+  sw t0, 388(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 392(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 396(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 400(sp)
+  lw t0, 392(sp)
+  add t0, t0, t1
+  sw t0, 404(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 408(sp)
+  lw t1, 404(sp)
+  sub t0, t1, t0
+  sw t0, 412(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 416(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 420(sp)
+  slli t0, t0, 2
+  sw t0, 424(sp)
+  lw t1, 416(sp)
+  add t0, t1, t0
+  sw t0, 428(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 432(sp)
+  lw t1, 428(sp)
+  sub t0, t1, t0
+  sw t0, 436(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 440(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 444(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 448(sp)
+  lw t0, 440(sp)
+  add t0, t0, t1
+  sw t0, 452(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 456(sp)
+  lw t1, 452(sp)
+  sub t0, t1, t0
+  sw t0, 460(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 464(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 468(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 472(sp)
+  lw t0, 464(sp)
+  add t0, t0, t1
+  sw t0, 476(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 480(sp)
+  lw t1, 476(sp)
+  sub t0, t1, t0
+  sw t0, 484(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 488(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 492(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 496(sp)
+  lw t1, 488(sp)
+  add t0, t1, t0
+  sw t0, 500(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 504(sp)
+  lw t1, 500(sp)
+  sub t0, t1, t0
+  sw t0, 508(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 512(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 516(sp)
+  slli t0, t0, 0
+  sw t0, 520(sp)
+  lw t1, 512(sp)
+  add t0, t1, t0
+  sw t0, 524(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 528(sp)
+  lw t1, 524(sp)
+  sub t0, t1, t0
+  sw t0, 532(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 536(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 540(sp)
+  slli t0, t0, 1
+  sw t0, 544(sp)
+  lw t1, 536(sp)
+  add t0, t1, t0
+  sw t0, 548(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 552(sp)
+  lw t1, 548(sp)
+  sub t0, t1, t0
+  sw t0, 556(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 560(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 564(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 568(sp)
+  lw t0, 560(sp)
+  add t0, t0, t1
+  sw t0, 572(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 576(sp)
+  lw t1, 572(sp)
+  sub t0, t1, t0
+  sw t0, 580(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 584(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 588(sp)
+  slli t0, t0, 2
+  sw t0, 592(sp)
+  lw t1, 584(sp)
+  add t0, t1, t0
+  sw t0, 596(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 600(sp)
+  lw t1, 596(sp)
+  sub t0, t1, t0
+  sw t0, 604(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 608(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 612(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 616(sp)
+  lw t0, 608(sp)
+  add t0, t0, t1
+  sw t0, 620(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 624(sp)
+  lw t1, 620(sp)
+  sub t0, t1, t0
+  sw t0, 628(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 632(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 636(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 640(sp)
+  lw t0, 632(sp)
+  add t0, t0, t1
+  sw t0, 644(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 648(sp)
+  lw t1, 644(sp)
+  sub t0, t1, t0
+  sw t0, 652(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 656(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 660(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 664(sp)
+  lw t1, 656(sp)
+  add t0, t1, t0
+  sw t0, 668(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 672(sp)
+  lw t1, 668(sp)
+  sub t0, t1, t0
+  sw t0, 676(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 680(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 684(sp)
+  slli t0, t0, 0
+  sw t0, 688(sp)
+  lw t1, 680(sp)
+  add t0, t1, t0
+  sw t0, 692(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 696(sp)
+  lw t1, 692(sp)
+  sub t0, t1, t0
+  sw t0, 700(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 704(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 708(sp)
+  slli t0, t0, 1
+  sw t0, 712(sp)
+  lw t1, 704(sp)
+  add t0, t1, t0
+  sw t0, 716(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 720(sp)
+  lw t1, 716(sp)
+  sub t0, t1, t0
+  sw t0, 724(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 728(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 732(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 736(sp)
+  lw t0, 728(sp)
+  add t0, t0, t1
+  sw t0, 740(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 744(sp)
+  lw t1, 740(sp)
+  sub t0, t1, t0
+  sw t0, 748(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 752(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 756(sp)
+  slli t0, t0, 2
+  sw t0, 760(sp)
+  lw t1, 752(sp)
+  add t0, t1, t0
+  sw t0, 764(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 768(sp)
+  lw t1, 764(sp)
+  sub t0, t1, t0
+  sw t0, 772(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 776(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 780(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 784(sp)
+  lw t0, 776(sp)
+  add t0, t0, t1
+  sw t0, 788(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 792(sp)
+  lw t1, 788(sp)
+  sub t0, t1, t0
+  sw t0, 796(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 800(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 804(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 808(sp)
+  lw t0, 800(sp)
+  add t0, t0, t1
+  sw t0, 812(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 816(sp)
+  lw t1, 812(sp)
+  sub t0, t1, t0
+  sw t0, 820(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 824(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 828(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 832(sp)
+  lw t1, 824(sp)
+  add t0, t1, t0
+  sw t0, 836(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 840(sp)
+  lw t1, 836(sp)
+  sub t0, t1, t0
+  sw t0, 844(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 848(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 852(sp)
+  slli t0, t0, 0
+  sw t0, 856(sp)
+  lw t1, 848(sp)
+  add t0, t1, t0
+  sw t0, 860(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 864(sp)
+  lw t1, 860(sp)
+  sub t0, t1, t0
+  sw t0, 868(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 872(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 876(sp)
+  slli t0, t0, 1
+  sw t0, 880(sp)
+  lw t1, 872(sp)
+  add t0, t1, t0
+  sw t0, 884(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 888(sp)
+  lw t1, 884(sp)
+  sub t0, t1, t0
+  sw t0, 892(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 896(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 900(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 904(sp)
+  lw t0, 896(sp)
+  add t0, t0, t1
+  sw t0, 908(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 912(sp)
+  lw t1, 908(sp)
+  sub t0, t1, t0
+  sw t0, 916(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 920(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 924(sp)
+  slli t0, t0, 2
+  sw t0, 928(sp)
+  lw t1, 920(sp)
+  add t0, t1, t0
+  sw t0, 932(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 936(sp)
+  lw t1, 932(sp)
+  sub t0, t1, t0
+  sw t0, 940(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 944(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 948(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 952(sp)
+  lw t0, 944(sp)
+  add t0, t0, t1
+  sw t0, 956(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 960(sp)
+  lw t1, 956(sp)
+  sub t0, t1, t0
+  sw t0, 964(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 968(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 972(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 976(sp)
+  lw t0, 968(sp)
+  add t0, t0, t1
+  sw t0, 980(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 984(sp)
+  lw t1, 980(sp)
+  sub t0, t1, t0
+  sw t0, 988(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 992(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 996(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1000(sp)
+  lw t1, 992(sp)
+  add t0, t1, t0
+  sw t0, 1004(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1008(sp)
+  lw t1, 1004(sp)
+  sub t0, t1, t0
+  sw t0, 1012(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1016(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1020(sp)
+  slli t0, t0, 0
+  sw t0, 1024(sp)
+  lw t1, 1016(sp)
+  add t0, t1, t0
+  sw t0, 1028(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1032(sp)
+  lw t1, 1028(sp)
+  sub t0, t1, t0
+  sw t0, 1036(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1040(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1044(sp)
+  slli t0, t0, 1
+  sw t0, 1048(sp)
+  lw t1, 1040(sp)
+  add t0, t1, t0
+  sw t0, 1052(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1056(sp)
+  lw t1, 1052(sp)
+  sub t0, t1, t0
+  sw t0, 1060(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1064(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1068(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1072(sp)
+  lw t0, 1064(sp)
+  add t0, t0, t1
+  sw t0, 1076(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1080(sp)
+  lw t1, 1076(sp)
+  sub t0, t1, t0
+  sw t0, 1084(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1088(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1092(sp)
+  slli t0, t0, 2
+  sw t0, 1096(sp)
+  lw t1, 1088(sp)
+  add t0, t1, t0
+  sw t0, 1100(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1104(sp)
+  lw t1, 1100(sp)
+  sub t0, t1, t0
+  sw t0, 1108(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1112(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1116(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1120(sp)
+  lw t0, 1112(sp)
+  add t0, t0, t1
+  sw t0, 1124(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1128(sp)
+  lw t1, 1124(sp)
+  sub t0, t1, t0
+  sw t0, 1132(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1136(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1140(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1144(sp)
+  lw t0, 1136(sp)
+  add t0, t0, t1
+  sw t0, 1148(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1152(sp)
+  lw t1, 1148(sp)
+  sub t0, t1, t0
+  sw t0, 1156(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1160(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1164(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1168(sp)
+  lw t1, 1160(sp)
+  add t0, t1, t0
+  sw t0, 1172(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1176(sp)
+  lw t1, 1172(sp)
+  sub t0, t1, t0
+  sw t0, 1180(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1184(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1188(sp)
+  slli t0, t0, 0
+  sw t0, 1192(sp)
+  lw t1, 1184(sp)
+  add t0, t1, t0
+  sw t0, 1196(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1200(sp)
+  lw t1, 1196(sp)
+  sub t0, t1, t0
+  sw t0, 1204(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1208(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1212(sp)
+  slli t0, t0, 1
+  sw t0, 1216(sp)
+  lw t1, 1208(sp)
+  add t0, t1, t0
+  sw t0, 1220(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1224(sp)
+  lw t1, 1220(sp)
+  sub t0, t1, t0
+  sw t0, 1228(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1232(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1236(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1240(sp)
+  lw t0, 1232(sp)
+  add t0, t0, t1
+  sw t0, 1244(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1248(sp)
+  lw t1, 1244(sp)
+  sub t0, t1, t0
+  sw t0, 1252(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1256(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1260(sp)
+  slli t0, t0, 2
+  sw t0, 1264(sp)
+  lw t1, 1256(sp)
+  add t0, t1, t0
+  sw t0, 1268(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1272(sp)
+  lw t1, 1268(sp)
+  sub t0, t1, t0
+  sw t0, 1276(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1280(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1284(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1288(sp)
+  lw t0, 1280(sp)
+  add t0, t0, t1
+  sw t0, 1292(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1296(sp)
+  lw t1, 1292(sp)
+  sub t0, t1, t0
+  sw t0, 1300(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1304(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1308(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1312(sp)
+  lw t0, 1304(sp)
+  add t0, t0, t1
+  sw t0, 1316(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1320(sp)
+  lw t1, 1316(sp)
+  sub t0, t1, t0
+  sw t0, 1324(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1328(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1332(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1336(sp)
+  lw t1, 1328(sp)
+  add t0, t1, t0
+  sw t0, 1340(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1344(sp)
+  lw t1, 1340(sp)
+  sub t0, t1, t0
+  sw t0, 1348(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1352(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1356(sp)
+  slli t0, t0, 0
+  sw t0, 1360(sp)
+  lw t1, 1352(sp)
+  add t0, t1, t0
+  sw t0, 1364(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1368(sp)
+  lw t1, 1364(sp)
+  sub t0, t1, t0
+  sw t0, 1372(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1376(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1380(sp)
+  slli t0, t0, 1
+  sw t0, 1384(sp)
+  lw t1, 1376(sp)
+  add t0, t1, t0
+  sw t0, 1388(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1392(sp)
+  lw t1, 1388(sp)
+  sub t0, t1, t0
+  sw t0, 1396(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1400(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1404(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1408(sp)
+  lw t0, 1400(sp)
+  add t0, t0, t1
+  sw t0, 1412(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1416(sp)
+  lw t1, 1412(sp)
+  sub t0, t1, t0
+  sw t0, 1420(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1424(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1428(sp)
+  slli t0, t0, 2
+  sw t0, 1432(sp)
+  lw t1, 1424(sp)
+  add t0, t1, t0
+  sw t0, 1436(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1440(sp)
+  lw t1, 1436(sp)
+  sub t0, t1, t0
+  sw t0, 1444(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1448(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1452(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1456(sp)
+  lw t0, 1448(sp)
+  add t0, t0, t1
+  sw t0, 1460(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1464(sp)
+  lw t1, 1460(sp)
+  sub t0, t1, t0
+  sw t0, 1468(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1472(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1476(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1480(sp)
+  lw t0, 1472(sp)
+  add t0, t0, t1
+  sw t0, 1484(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1488(sp)
+  lw t1, 1484(sp)
+  sub t0, t1, t0
+  sw t0, 1492(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1496(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1500(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1504(sp)
+  lw t1, 1496(sp)
+  add t0, t1, t0
+  sw t0, 1508(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1512(sp)
+  lw t1, 1508(sp)
+  sub t0, t1, t0
+  sw t0, 1516(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1520(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1524(sp)
+  slli t0, t0, 0
+  sw t0, 1528(sp)
+  lw t1, 1520(sp)
+  add t0, t1, t0
+  sw t0, 1532(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1536(sp)
+  lw t1, 1532(sp)
+  sub t0, t1, t0
+  sw t0, 1540(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1544(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1548(sp)
+  slli t0, t0, 1
+  sw t0, 1552(sp)
+  lw t1, 1544(sp)
+  add t0, t1, t0
+  sw t0, 1556(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1560(sp)
+  lw t1, 1556(sp)
+  sub t0, t1, t0
+  sw t0, 1564(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1568(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1572(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1576(sp)
+  lw t0, 1568(sp)
+  add t0, t0, t1
+  sw t0, 1580(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1584(sp)
+  lw t1, 1580(sp)
+  sub t0, t1, t0
+  sw t0, 1588(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1592(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1596(sp)
+  slli t0, t0, 2
+  sw t0, 1600(sp)
+  lw t1, 1592(sp)
+  add t0, t1, t0
+  sw t0, 1604(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1608(sp)
+  lw t1, 1604(sp)
+  sub t0, t1, t0
+  sw t0, 1612(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1616(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1620(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1624(sp)
+  lw t0, 1616(sp)
+  add t0, t0, t1
+  sw t0, 1628(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1632(sp)
+  lw t1, 1628(sp)
+  sub t0, t1, t0
+  sw t0, 1636(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1640(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1644(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1648(sp)
+  lw t0, 1640(sp)
+  add t0, t0, t1
+  sw t0, 1652(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1656(sp)
+  lw t1, 1652(sp)
+  sub t0, t1, t0
+  sw t0, 1660(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1664(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1668(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1672(sp)
+  lw t1, 1664(sp)
+  add t0, t1, t0
+  sw t0, 1676(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1680(sp)
+  lw t1, 1676(sp)
+  sub t0, t1, t0
+  sw t0, 1684(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1688(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1692(sp)
+  slli t0, t0, 0
+  sw t0, 1696(sp)
+  lw t1, 1688(sp)
+  add t0, t1, t0
+  sw t0, 1700(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1704(sp)
+  lw t1, 1700(sp)
+  sub t0, t1, t0
+  sw t0, 1708(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1712(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1716(sp)
+  slli t0, t0, 1
+  sw t0, 1720(sp)
+  lw t1, 1712(sp)
+  add t0, t1, t0
+  sw t0, 1724(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1728(sp)
+  lw t1, 1724(sp)
+  sub t0, t1, t0
+  sw t0, 1732(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1736(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1740(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1744(sp)
+  lw t0, 1736(sp)
+  add t0, t0, t1
+  sw t0, 1748(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1752(sp)
+  lw t1, 1748(sp)
+  sub t0, t1, t0
+  sw t0, 1756(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1760(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1764(sp)
+  slli t0, t0, 2
+  sw t0, 1768(sp)
+  lw t1, 1760(sp)
+  add t0, t1, t0
+  sw t0, 1772(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1776(sp)
+  lw t1, 1772(sp)
+  sub t0, t1, t0
+  sw t0, 1780(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1784(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1788(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1792(sp)
+  lw t0, 1784(sp)
+  add t0, t0, t1
+  sw t0, 1796(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1800(sp)
+  lw t1, 1796(sp)
+  sub t0, t1, t0
+  sw t0, 1804(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1808(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1812(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1816(sp)
+  lw t0, 1808(sp)
+  add t0, t0, t1
+  sw t0, 1820(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1824(sp)
+  lw t1, 1820(sp)
+  sub t0, t1, t0
+  sw t0, 1828(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1832(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1836(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 1840(sp)
+  lw t1, 1832(sp)
+  add t0, t1, t0
+  sw t0, 1844(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1848(sp)
+  lw t1, 1844(sp)
+  sub t0, t1, t0
+  sw t0, 1852(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1856(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1860(sp)
+  slli t0, t0, 0
+  sw t0, 1864(sp)
+  lw t1, 1856(sp)
+  add t0, t1, t0
+  sw t0, 1868(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1872(sp)
+  lw t1, 1868(sp)
+  sub t0, t1, t0
+  sw t0, 1876(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1880(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1884(sp)
+  slli t0, t0, 1
+  sw t0, 1888(sp)
+  lw t1, 1880(sp)
+  add t0, t1, t0
+  sw t0, 1892(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1896(sp)
+  lw t1, 1892(sp)
+  sub t0, t1, t0
+  sw t0, 1900(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1904(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1908(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 1912(sp)
+  lw t0, 1904(sp)
+  add t0, t0, t1
+  sw t0, 1916(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1920(sp)
+  lw t1, 1916(sp)
+  sub t0, t1, t0
+  sw t0, 1924(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1928(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1932(sp)
+  slli t0, t0, 2
+  sw t0, 1936(sp)
+  lw t1, 1928(sp)
+  add t0, t1, t0
+  sw t0, 1940(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1944(sp)
+  lw t1, 1940(sp)
+  sub t0, t1, t0
+  sw t0, 1948(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1952(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1956(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1960(sp)
+  lw t0, 1952(sp)
+  add t0, t0, t1
+  sw t0, 1964(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1968(sp)
+  lw t1, 1964(sp)
+  sub t0, t1, t0
+  sw t0, 1972(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 1976(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 1980(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 1984(sp)
+  lw t0, 1976(sp)
+  add t0, t0, t1
+  sw t0, 1988(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 1992(sp)
+  lw t1, 1988(sp)
+  sub t0, t1, t0
+  sw t0, 1996(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 2000(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 2004(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 2008(sp)
+  lw t1, 2000(sp)
+  add t0, t1, t0
+  sw t0, 2012(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 2016(sp)
+  lw t1, 2012(sp)
+  sub t0, t1, t0
+  sw t0, 2020(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 2024(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 2028(sp)
+  slli t0, t0, 0
+  sw t0, 2032(sp)
+  lw t1, 2024(sp)
+  add t0, t1, t0
+  sw t0, 2036(sp)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 2040(sp)
+  lw t1, 2036(sp)
+  sub t0, t1, t0
+  sw t0, 2044(sp)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2048
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2052
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2056
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2048
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2060
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2064
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2060
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2068
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2072
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2076
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2080
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2072
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2084
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2088
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2084
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2092
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2096
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2100
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2104
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2096
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2108
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2112
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2108
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2116
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2120
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2124
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2128
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2120
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2132
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2136
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2132
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2140
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2144
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2148
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2152
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2144
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2156
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2160
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2156
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2164
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2168
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2172
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 2176
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2168
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2180
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2184
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2180
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2188
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2192
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2196
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 2200
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2192
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2204
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2208
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2204
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2212
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2216
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2220
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2224
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2216
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2228
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2232
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2228
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2236
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2240
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2244
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2248
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2240
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2252
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2256
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2252
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2260
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2264
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2268
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2272
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2264
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2276
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2280
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2276
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2284
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2288
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2292
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2296
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2288
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2300
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2304
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2300
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2308
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2312
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2316
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2320
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2312
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2324
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2328
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2324
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2332
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2336
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2340
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 2344
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2336
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2348
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2352
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2348
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2356
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2360
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2364
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 2368
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2360
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2372
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2376
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2372
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2380
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2384
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2388
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2392
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2384
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2396
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2400
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2396
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2404
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2408
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2412
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2416
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2408
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2420
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2424
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2420
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2428
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2432
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2436
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2440
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2432
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2444
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2448
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2444
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2452
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2456
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2460
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2464
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2456
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2468
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2472
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2468
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2476
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2480
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2484
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2488
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2480
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2492
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2496
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2492
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2500
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2504
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2508
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 2512
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2504
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2516
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2520
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2516
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2524
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2528
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2532
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 2536
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2528
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2540
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2544
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2540
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2548
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2552
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2556
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2560
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2552
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2564
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2568
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2564
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2572
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2576
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2580
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2584
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2576
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2588
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2592
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2588
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2596
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2600
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2604
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2608
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2600
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2612
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2616
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2612
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2620
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2624
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2628
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2632
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2624
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2636
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2640
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2636
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2644
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2648
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2652
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2656
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2648
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2660
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2664
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2660
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2668
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2672
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2676
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 2680
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2672
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2684
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2688
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2684
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2692
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2696
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2700
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 2704
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2696
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2708
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2712
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2708
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2716
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2720
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2724
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2728
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2720
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2732
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2736
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2732
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2740
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2744
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2748
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2752
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2744
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2756
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2760
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2756
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2764
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2768
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2772
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2776
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2768
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2780
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2784
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2780
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2788
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2792
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2796
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2800
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2792
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2804
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2808
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2804
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2812
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2816
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2820
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2824
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2816
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2828
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2832
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2828
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2836
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2840
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2844
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 2848
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2840
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2852
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2856
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2852
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2860
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2864
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2868
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 2872
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2864
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2876
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2880
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2876
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2884
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2888
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2892
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 1
+  li t6, 2896
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2888
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2900
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2904
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2900
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2908
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2912
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2916
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  li t6, 2920
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2912
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2924
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2928
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2924
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2932
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2936
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2940
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 2
+  li t6, 2944
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2936
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 2948
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2952
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2948
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2956
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2960
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2964
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2968
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2960
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2972
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 2976
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2972
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 2980
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 2984
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 2988
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  li t6, 2992
+  add t6, t6, sp
+  sw t1, 0(t6)
+  li t6, 2984
+  add t6, t6, sp
+  lw t0, 0(t6)
+  add t0, t0, t1
+  li t6, 2996
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 3000
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 2996
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 3004
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 3008
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 3012
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  mul t0, t0, t1
+  li t6, 3016
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 3008
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 3020
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 3024
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 3020
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 3028
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  li t6, 3032
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 3036
+  add t6, t6, sp
+  sw t0, 0(t6)
+  slli t0, t0, 0
+  li t6, 3040
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 3032
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 3044
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  li t6, 3048
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 3044
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sub t0, t1, t0
+  li t6, 3052
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+.Lmain_bb_if_end_8:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  li t6, 3056
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  add t0, t0, t1
+  li t6, 3060
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_while_entry_2
+.Lmain_bb_if_true_4:
+  j .Lmain_bb_while_end_3
+.Lmain_bb_if_false_8:
+  lw t0, 60(sp)
+  lw t0, 0(t0)
+  li t6, 3064
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  sub t0, t0, t1
+  li t6, 3068
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 60(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_if_end_8
+.Lmain_bb_while_end_2:
+  lw t0, 60(sp)
+  lw t0, 0(t0)
+  sw t0, 76(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 80(sp)
+  li a0, 10
+  call putch
+  sw a0, 84(sp)
+  li a0, 5
+  call find
+  sw a0, 88(sp)
+  lw a0, 88(sp)
+  call putint
+  sw a0, 92(sp)
+  li a0, 32
+  call putch
+  sw a0, 96(sp)
+  li a0, 7
+  call find
+  sw a0, 100(sp)
+  lw a0, 100(sp)
+  call putint
+  sw a0, 104(sp)
+  li a0, 10
+  call putch
+  sw a0, 108(sp)
+  lw t0, 60(sp)
+  lw t0, 0(t0)
+  sw t0, 112(sp)
+  li t1, 256
+  rem t0, t0, t1
+  sw t0, 116(sp)
+  mv a0, t0
+  li t6, 3084
+  add t6, t6, sp
+  lw ra, 0(t6)
+  addi sp, sp, 2032
+  addi sp, sp, 1056
+  ret
+.Lmain_bb_unreachable_5:
+  j .Lmain_bb_if_end_4
+.Lmain_bb_unreachable_7:
+  j .Lmain_bb_if_end_6
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
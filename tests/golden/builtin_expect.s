@@ -0,0 +1,245 @@
+  .bss
+  .globl errors
+  .type errors, @object
+  .align 2
+errors:
+  .zero 4
+  .size errors, 4
+
+  .text
+  .globl check
+  .type check, @function
+check:
+  addi sp, sp, -48
+  sw ra, 44(sp)
+.Lcheck_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  slt t0, t0, zero
+  sw t0, 12(sp)
+  bnez t0, .Lcheck_bb_if_true_1
+.Lcheck_bb_if_end_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  mv a0, t0
+  lw ra, 44(sp)
+  addi sp, sp, 48
+  ret
+.Lcheck_bb_if_true_1:
+  lui t0, %hi(errors)
+  lw t0, %lo(errors)(t0)
+  sw t0, 16(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 20(sp)
+  lui t1, %hi(errors)
+  sw t0, %lo(errors)(t1)
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 28(sp)
+  li a0, 10
+  call putch
+  sw a0, 32(sp)
+  mv a0, zero
+  lw ra, 44(sp)
+  addi sp, sp, 48
+  ret
+.Lcheck_bb_unreachable_2:
+  j .Lcheck_bb_if_end_1
+.Lfunc_end_check:
+  .size check, .Lfunc_end_check-check
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -208
+  sw ra, 204(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 4
+  sw t0, 20(sp)
+  sw zero, 0(t0)
+.Lmain_bb_while_entry_1:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  li t1, 100
+  slt t0, t0, t1
+  sw t0, 28(sp)
+  beqz t0, .Lmain_bb_while_end_1
+.Lmain_bb_while_body_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  li t1, 37
+  rem t0, t0, t1
+  sw t0, 40(sp)
+  li t1, 2
+  sub t0, t0, t1
+  sw t0, 44(sp)
+  add a0, zero, t0
+  call check
+  sw a0, 48(sp)
+  lw t0, 32(sp)
+  lw t1, 48(sp)
+  add t0, t0, t1
+  sw t0, 52(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 56(sp)
+  li t1, 10
+  rem t0, t0, t1
+  sw t0, 60(sp)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 64(sp)
+  bnez t0, .Lmain_bb_if_true_2
+.Lmain_bb_if_end_2:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 108(sp)
+  li t1, 50
+  xor t0, t0, t1
+  seqz t0, t0
+  sw t0, 112(sp)
+  addi t1, sp, 8
+  sw t1, 116(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 120(sp)
+  beqz t0, .Lmain_bb_sc_if_end_3
+.Lmain_bb_sc_if_true_3:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 124(sp)
+  li t1, 70
+  xor t0, t0, t1
+  seqz t0, t0
+  sw t0, 128(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 132(sp)
+  lw t1, 116(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_3:
+  lw t0, 116(sp)
+  lw t0, 0(t0)
+  sw t0, 136(sp)
+  beqz t0, .Lmain_bb_if_end_4
+.Lmain_bb_if_true_4:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 140(sp)
+  slli t0, t0, 1
+  sw t0, 144(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lmain_bb_if_end_4:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 148(sp)
+  li t1, 3
+  sgt t0, t0, t1
+  sw t0, 152(sp)
+  addi t1, sp, 12
+  sw t1, 156(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 160(sp)
+  beqz t0, .Lmain_bb_sc_if_end_5
+.Lmain_bb_sc_if_true_5:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 164(sp)
+  li t1, 2
+  rem t0, t0, t1
+  sw t0, 168(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 172(sp)
+  lw t1, 156(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_5:
+  lw t0, 156(sp)
+  lw t0, 0(t0)
+  sw t0, 176(sp)
+  beqz t0, .Lmain_bb_if_end_6
+.Lmain_bb_if_true_6:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 180(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 184(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lmain_bb_if_end_6:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 188(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 192(sp)
+  lw t1, 16(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_if_true_2:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 100(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 104(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_if_end_2
+.Lmain_bb_while_end_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 72(sp)
+  li a0, 32
+  call putch
+  sw a0, 76(sp)
+  lui t0, %hi(errors)
+  lw t0, %lo(errors)(t0)
+  sw t0, 80(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 84(sp)
+  li a0, 10
+  call putch
+  sw a0, 88(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 92(sp)
+  li t1, 256
+  rem t0, t0, t1
+  sw t0, 96(sp)
+  mv a0, t0
+  lw ra, 204(sp)
+  addi sp, sp, 208
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
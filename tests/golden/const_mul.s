@@ -0,0 +1,322 @@
+  .bss
+  .globl a
+  .type a, @object
+  .align 2
+a:
+  .zero 140
+  .size a, 140
+
+  .bss
+  .globl g
+  .type g, @object
+  .align 2
+g:
+  .zero 4
+  .size g, 4
+
+  .data
+  .globl seed
+  .type seed, @object
+  .align 2
+seed:
+  .word 123457
+  .size seed, 4
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -368
+  sw ra, 364(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  lui t0, %hi(seed)
+  lw t0, %lo(seed)(t0)
+  sw t0, 20(sp)
+  lw t1, 16(sp)
+  sw t0, 0(t1)
+  addi t0, sp, 4
+  sw t0, 24(sp)
+  sw zero, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  slli t0, t0, 0
+  sw t0, 36(sp)
+  lw t1, 28(sp)
+  add t0, t1, t0
+  sw t0, 40(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 44(sp)
+  slli t0, t0, 1
+  sw t0, 48(sp)
+  lw t1, 40(sp)
+  add t0, t1, t0
+  sw t0, 52(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 56(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 60(sp)
+  lw t0, 52(sp)
+  add t0, t0, t1
+  sw t0, 64(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  slli t1, t0, 0
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 72(sp)
+  lw t0, 64(sp)
+  add t0, t0, t1
+  sw t0, 76(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 80(sp)
+  slli t1, t0, 1
+  slli t0, t0, 2
+  add t1, t1, t0
+  sw t1, 84(sp)
+  lw t0, 76(sp)
+  add t0, t0, t1
+  sw t0, 88(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 92(sp)
+  li t1, 7
+  mul t0, t0, t1
+  sw t0, 96(sp)
+  lw t1, 88(sp)
+  add t0, t1, t0
+  sw t0, 100(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 104(sp)
+  slli t1, t0, 1
+  slli t0, t0, 3
+  add t1, t1, t0
+  sw t1, 108(sp)
+  lw t0, 100(sp)
+  add t0, t0, t1
+  sw t0, 112(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 116(sp)
+  mv t0, zero
+  sw t0, 120(sp)
+  lw t1, 112(sp)
+  add t0, t1, t0
+  sw t0, 124(sp)
+  lw t1, 24(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 128(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 132(sp)
+  li t1, -1
+  mul t0, t0, t1
+  sw t0, 136(sp)
+  lw t1, 128(sp)
+  add t0, t1, t0
+  sw t0, 140(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 144(sp)
+  li t1, 12345
+  mul t0, t0, t1
+  sw t0, 148(sp)
+  lw t1, 140(sp)
+  add t0, t1, t0
+  sw t0, 152(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 156(sp)
+  slli t0, t0, 16
+  sw t0, 160(sp)
+  lw t1, 152(sp)
+  add t0, t1, t0
+  sw t0, 164(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 168(sp)
+  li t1, 255
+  mul t0, t0, t1
+  sw t0, 172(sp)
+  lw t1, 164(sp)
+  add t0, t1, t0
+  sw t0, 176(sp)
+  lw t1, 24(sp)
+  sw t0, 0(t1)
+  addi t0, sp, 8
+  sw t0, 180(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  addi t0, sp, 12
+  sw t0, 192(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_2
+.Lmain_bb_while_body_2:
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 296(sp)
+  lw t0, 180(sp)
+  lw t0, 0(t0)
+  sw t0, 300(sp)
+  lw t1, 296(sp)
+  slli t2, t0, 2
+  slli t0, t0, 4
+  add t2, t2, t0
+  add t0, t1, t2
+  sw t0, 304(sp)
+  sw t0, 308(sp)
+  lw t0, 192(sp)
+  lw t0, 0(t0)
+  sw t0, 312(sp)
+  lw t1, 308(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 316(sp)
+  lw t0, 180(sp)
+  lw t0, 0(t0)
+  sw t0, 320(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 324(sp)
+  lw t0, 192(sp)
+  lw t0, 0(t0)
+  sw t0, 328(sp)
+  slli t1, t0, 1
+  slli t0, t0, 3
+  add t1, t1, t0
+  sw t1, 332(sp)
+  lw t0, 324(sp)
+  add t0, t0, t1
+  sw t0, 336(sp)
+  lw t1, 316(sp)
+  sw t0, 0(t1)
+  lw t0, 192(sp)
+  lw t0, 0(t0)
+  sw t0, 340(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 344(sp)
+  lw t1, 192(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_2:
+  lw t0, 192(sp)
+  lw t0, 0(t0)
+  sw t0, 288(sp)
+  li t1, 5
+  slt t0, t0, t1
+  sw t0, 292(sp)
+  bnez t0, .Lmain_bb_while_body_2
+.Lmain_bb_while_end_2:
+  lw t0, 180(sp)
+  lw t0, 0(t0)
+  sw t0, 348(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 352(sp)
+  lw t1, 180(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  lw t0, 180(sp)
+  lw t0, 0(t0)
+  sw t0, 184(sp)
+  li t1, 7
+  slt t0, t0, t1
+  sw t0, 188(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 196(sp)
+  slli t1, t0, 3
+  slli t0, t0, 4
+  add t1, t1, t0
+  sw t1, 200(sp)
+  lui t0, %hi(g)
+  sw t1, %lo(g)(t0)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 204(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 208(sp)
+  li a0, 10
+  call putch
+  sw a0, 212(sp)
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 216(sp)
+  li t1, 6
+  slli t2, t1, 2
+  slli t1, t1, 4
+  add t2, t2, t1
+  add t0, t0, t2
+  sw t0, 220(sp)
+  sw t0, 224(sp)
+  li t1, 4
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 228(sp)
+  lw t0, 0(t0)
+  sw t0, 232(sp)
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 236(sp)
+  li t1, 3
+  slli t2, t1, 2
+  slli t1, t1, 4
+  add t2, t2, t1
+  add t0, t0, t2
+  sw t0, 240(sp)
+  sw t0, 244(sp)
+  li t1, 2
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 248(sp)
+  lw t0, 0(t0)
+  sw t0, 252(sp)
+  lw t1, 232(sp)
+  add t0, t1, t0
+  sw t0, 256(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 260(sp)
+  li a0, 10
+  call putch
+  sw a0, 264(sp)
+  lui t0, %hi(g)
+  lw t0, %lo(g)(t0)
+  sw t0, 268(sp)
+  slli t1, t0, 0
+  slli t0, t0, 3
+  add t1, t1, t0
+  sw t1, 272(sp)
+  add a0, zero, t1
+  call putint
+  sw a0, 276(sp)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 280(sp)
+  li t1, 256
+  rem t0, t0, t1
+  sw t0, 284(sp)
+  mv a0, t0
+  lw ra, 364(sp)
+  addi sp, sp, 368
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
@@ -0,0 +1,93 @@
+  .data
+  .globl a
+  .type a, @object
+  .align 2
+a:
+  .word -2, 2147483647
+  .size a, 8
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -112
+  sw ra, 108(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 8(sp)
+  sw zero, 0(t0)
+  li a0, -2147483648
+  call putint
+  sw a0, 12(sp)
+  li a0, 10
+  call putch
+  sw a0, 16(sp)
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 20(sp)
+  sw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 32(sp)
+  li a0, 32
+  call putch
+  sw a0, 36(sp)
+  lui t0, %hi(a)
+  addi t0, t0, %lo(a)
+  sw t0, 40(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 44(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 52(sp)
+  li a0, 10
+  call putch
+  sw a0, 56(sp)
+  li a0, 1
+  call putint
+  sw a0, 60(sp)
+  li a0, 10
+  call putch
+  sw a0, 64(sp)
+  lw t0, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  addi t1, sp, 4
+  sw t1, 72(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 76(sp)
+  beqz t0, .Lmain_bb_sc_if_end_1
+.Lmain_bb_sc_if_true_1:
+  li t0, 1
+  div t0, t0, zero
+  sw t0, 80(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 84(sp)
+  lw t1, 72(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_1:
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 88(sp)
+  beqz t0, .Lmain_bb_if_end_2
+.Lmain_bb_if_true_2:
+  add a0, zero, zero
+  call putint
+  sw a0, 92(sp)
+.Lmain_bb_if_end_2:
+  li a0, -2
+  lw ra, 108(sp)
+  addi sp, sp, 112
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
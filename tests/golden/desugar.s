@@ -0,0 +1,919 @@
+  .bss
+  .globl g
+  .type g, @object
+  .align 2
+g:
+  .zero 40
+  .size g, 40
+
+  .bss
+  .globl calls
+  .type calls, @object
+  .align 2
+calls:
+  .zero 4
+  .size calls, 4
+
+  .text
+  .globl next
+  .type next, @function
+next:
+  addi sp, sp, -16
+.Lnext_bb_entry_0:
+  lui t0, %hi(calls)
+  lw t0, %lo(calls)(t0)
+  sw t0, 0(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 4(sp)
+  lui t1, %hi(calls)
+  sw t0, %lo(calls)(t1)
+  lw t0, %lo(calls)(t1)
+  sw t0, 8(sp)
+  mv a0, t0
+  addi sp, sp, 16
+  ret
+.Lfunc_end_next:
+  .size next, .Lfunc_end_next-next
+
+  .text
+  .globl pick
+  .type pick, @function
+pick:
+  addi sp, sp, -32
+.Lpick_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 4(sp)
+  sw a0, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 8(sp)
+  bnez t0, .Lpick_bb_if_true_1
+.Lpick_bb_if_false_1:
+  lw t0, 4(sp)
+  lw t0, 0(t0)
+  sw t0, 12(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 16(sp)
+  beqz t0, .Lpick_bb_if_false_3
+.Lpick_bb_if_true_3:
+  li a0, 20
+  addi sp, sp, 32
+  ret
+.Lpick_bb_if_true_1:
+  li a0, 10
+  addi sp, sp, 32
+  ret
+.Lpick_bb_if_false_3:
+  li a0, 30
+  addi sp, sp, 32
+  ret
+.Lpick_bb_if_end_1:
+  mv a0, zero
+  addi sp, sp, 32
+  ret
+.Lpick_bb_unreachable_2:
+  j .Lpick_bb_if_end_1
+.Lpick_bb_if_end_3:
+  j .Lpick_bb_if_end_1
+.Lpick_bb_unreachable_4:
+  j .Lpick_bb_if_end_3
+.Lpick_bb_unreachable_5:
+  j .Lpick_bb_if_end_3
+.Lfunc_end_pick:
+  .size pick, .Lfunc_end_pick-pick
+
+  .text
+  .globl fill
+  .type fill, @function
+fill:
+  addi sp, sp, -80
+.Lfill_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 16(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 20(sp)
+  sw zero, 0(t0)
+  j .Lfill_bb_while_entry_1
+.Lfill_bb_while_body_1:
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 44(sp)
+  lw t0, 20(sp)
+  lw t1, 0(t0)
+  sw t1, 48(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  lw t1, 48(sp)
+  mul t0, t1, t0
+  sw t0, 56(sp)
+  lw t1, 44(sp)
+  sw t0, 0(t1)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 60(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 64(sp)
+  lw t1, 20(sp)
+  sw t0, 0(t1)
+.Lfill_bb_while_entry_1:
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 24(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  lw t1, 24(sp)
+  slt t0, t1, t0
+  sw t0, 32(sp)
+  bnez t0, .Lfill_bb_while_body_1
+.Lfill_bb_while_end_1:
+  addi sp, sp, 80
+  ret
+.Lfunc_end_fill:
+  .size fill, .Lfunc_end_fill-fill
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -928
+  sw ra, 924(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 108(sp)
+  addi t0, sp, 4
+  sw t0, 112(sp)
+  addi t0, sp, 8
+  sw t0, 116(sp)
+  addi t0, sp, 12
+  sw t0, 120(sp)
+  addi t0, sp, 16
+  sw t0, 124(sp)
+  addi t0, sp, 20
+  sw t0, 128(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 24
+  sw t0, 132(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 28
+  sw t0, 136(sp)
+  li t1, 1
+  sw t1, 0(t0)
+.Lmain_bb_while_entry_1:
+  lw t0, 136(sp)
+  lw t0, 0(t0)
+  sw t0, 140(sp)
+  addi t1, sp, 32
+  sw t1, 144(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 148(sp)
+  beqz t0, .Lmain_bb_sc_if_end_2
+.Lmain_bb_sc_if_true_2:
+  lw t0, 132(sp)
+  lw t0, 0(t0)
+  sw t0, 180(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 184(sp)
+  lw t1, 132(sp)
+  sw t0, 0(t1)
+  xor t0, zero, zero
+  snez t0, t0
+  sw t0, 188(sp)
+  lw t1, 144(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_2:
+  lw t0, 144(sp)
+  lw t0, 0(t0)
+  sw t0, 192(sp)
+  lw t0, 136(sp)
+  sw zero, 0(t0)
+  lw t0, 132(sp)
+  lw t0, 0(t0)
+  sw t0, 196(sp)
+  li t1, 10
+  slt t0, t0, t1
+  sw t0, 200(sp)
+  beqz t0, .Lmain_bb_while_end_1
+.Lmain_bb_while_body_1:
+  lw t0, 132(sp)
+  lw t0, 0(t0)
+  sw t0, 152(sp)
+  li t1, 3
+  rem t0, t0, t1
+  sw t0, 156(sp)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 160(sp)
+  beqz t0, .Lmain_bb_if_end_3
+.Lmain_bb_if_true_3:
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_if_end_3:
+  lw t0, 128(sp)
+  lw t0, 0(t0)
+  sw t0, 204(sp)
+  lw t0, 132(sp)
+  lw t0, 0(t0)
+  sw t0, 208(sp)
+  lw t1, 204(sp)
+  add t0, t1, t0
+  sw t0, 212(sp)
+  lw t1, 128(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_end_1:
+  lw t0, 128(sp)
+  lw t0, 0(t0)
+  sw t0, 164(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 168(sp)
+  li a0, 10
+  call putch
+  sw a0, 172(sp)
+  addi t0, sp, 36
+  sw t0, 176(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_5
+.Lmain_bb_while_body_5:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 216(sp)
+  li t1, 5
+  slt t0, t0, t1
+  seqz t0, t0
+  sw t0, 220(sp)
+  bnez t0, .Lmain_bb_if_true_6
+.Lmain_bb_if_end_6:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 236(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 240(sp)
+  lw t1, 176(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_5:
+  li t0, 1
+  bnez t0, .Lmain_bb_while_body_5
+.Lmain_bb_while_end_5:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 224(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 228(sp)
+  li a0, 10
+  call putch
+  sw a0, 232(sp)
+  lw t0, 176(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_8
+.Lmain_bb_while_body_8:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 252(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 256(sp)
+  lw t1, 176(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_8:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 244(sp)
+  li t1, 3
+  slt t0, t0, t1
+  sw t0, 248(sp)
+  bnez t0, .Lmain_bb_while_body_8
+  j .Lmain_bb_while_end_8
+.Lmain_bb_if_true_6:
+  j .Lmain_bb_while_end_5
+.Lmain_bb_while_end_8:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 260(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 264(sp)
+  li a0, 10
+  call putch
+  sw a0, 268(sp)
+  addi t0, sp, 40
+  sw t0, 272(sp)
+  li t1, 5
+  sw t1, 0(t0)
+  addi t1, sp, 44
+  sw t1, 276(sp)
+  lw t0, 0(t0)
+  sw t0, 280(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 284(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 288(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 292(sp)
+  lw t1, 276(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 296(sp)
+  lw t0, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 300(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 304(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 308(sp)
+  lw t1, 296(sp)
+  add t0, t1, t0
+  sw t0, 312(sp)
+  lw t1, 276(sp)
+  sw t0, 0(t1)
+  lw t0, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 316(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 320(sp)
+  li a0, 32
+  call putch
+  sw a0, 324(sp)
+  lw t0, 276(sp)
+  lw t0, 0(t0)
+  sw t0, 328(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 332(sp)
+  li a0, 32
+  call putch
+  sw a0, 336(sp)
+  lw t0, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 340(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 344(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 348(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 352(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 356(sp)
+  li a0, 32
+  call putch
+  sw a0, 360(sp)
+  lw t0, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 364(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 368(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 372(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 376(sp)
+  li a0, 10
+  call putch
+  sw a0, 380(sp)
+  lw t0, 272(sp)
+  lw t0, 0(t0)
+  sw t0, 384(sp)
+  slli t1, t0, 0
+  slli t0, t0, 1
+  add t1, t1, t0
+  sw t1, 388(sp)
+  lw t0, 272(sp)
+  sw t1, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 392(sp)
+  li t1, 2
+  sub t0, t0, t1
+  sw t0, 396(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 400(sp)
+  li t1, 2
+  div t0, t0, t1
+  sw t0, 404(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 408(sp)
+  li t1, 5
+  rem t0, t0, t1
+  sw t0, 412(sp)
+  lw t1, 272(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 416(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 420(sp)
+  li a0, 10
+  call putch
+  sw a0, 424(sp)
+  lui t0, %hi(g)
+  addi t0, t0, %lo(g)
+  sw t0, 428(sp)
+  add a0, zero, t0
+  li a1, 10
+  call fill
+  lui t0, %hi(g)
+  addi t0, t0, %lo(g)
+  sw t0, 432(sp)
+  call next
+  sw a0, 436(sp)
+  lw t0, 432(sp)
+  lw t1, 436(sp)
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 440(sp)
+  lw t1, 120(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 444(sp)
+  lw t0, 0(t1)
+  sw t0, 448(sp)
+  lw t0, 0(t0)
+  sw t0, 452(sp)
+  li t1, 100
+  add t0, t0, t1
+  sw t0, 456(sp)
+  lw t1, 444(sp)
+  sw t0, 0(t1)
+  lui t0, %hi(g)
+  addi t0, t0, %lo(g)
+  sw t0, 460(sp)
+  call next
+  sw a0, 464(sp)
+  lw t0, 460(sp)
+  lw t1, 464(sp)
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 468(sp)
+  lw t1, 124(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 472(sp)
+  lw t0, 0(t1)
+  sw t0, 476(sp)
+  lw t0, 0(t0)
+  sw t0, 480(sp)
+  slli t0, t0, 1
+  sw t0, 484(sp)
+  lw t1, 472(sp)
+  sw t0, 0(t1)
+  lui t0, %hi(g)
+  addi t0, t0, %lo(g)
+  sw t0, 488(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 492(sp)
+  lw t0, 0(t0)
+  sw t0, 496(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 500(sp)
+  li a0, 32
+  call putch
+  sw a0, 504(sp)
+  lui t0, %hi(g)
+  addi t0, t0, %lo(g)
+  sw t0, 508(sp)
+  li t1, 2
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 512(sp)
+  lw t0, 0(t0)
+  sw t0, 516(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 520(sp)
+  li a0, 32
+  call putch
+  sw a0, 524(sp)
+  lui t0, %hi(calls)
+  lw t0, %lo(calls)(t0)
+  sw t0, 528(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 532(sp)
+  li a0, 10
+  call putch
+  sw a0, 536(sp)
+  addi t0, sp, 48
+  sw t0, 540(sp)
+  li t1, 3
+  sw t1, 0(t0)
+  addi t1, sp, 52
+  sw t1, 544(sp)
+  lw t0, 0(t0)
+  sw t0, 548(sp)
+  li t1, 2
+  sgt t0, t0, t1
+  sw t0, 552(sp)
+  addi t1, sp, 56
+  sw t1, 556(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 560(sp)
+  beqz t0, .Lmain_bb_sc_if_end_9
+.Lmain_bb_sc_if_true_9:
+  lw t0, 540(sp)
+  lw t0, 0(t0)
+  sw t0, 564(sp)
+  slli t0, t0, 1
+  sw t0, 568(sp)
+  lw t1, 108(sp)
+  sw t0, 0(t1)
+  li t0, 1
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 572(sp)
+  lw t1, 556(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_9:
+  lw t0, 556(sp)
+  lw t0, 0(t0)
+  sw t0, 576(sp)
+  addi t1, sp, 60
+  sw t1, 580(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 584(sp)
+  beqz t0, .Lmain_bb_sc_if_end_10
+.Lmain_bb_sc_if_true_10:
+  lw t0, 540(sp)
+  lw t0, 0(t0)
+  sw t0, 588(sp)
+  lw t1, 108(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 592(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 596(sp)
+  lw t1, 580(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_10:
+  lw t0, 580(sp)
+  lw t0, 0(t0)
+  sw t0, 600(sp)
+  lw t0, 108(sp)
+  lw t0, 0(t0)
+  sw t0, 604(sp)
+  lw t1, 544(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 608(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 612(sp)
+  li a0, 32
+  call putch
+  sw a0, 616(sp)
+  lw t0, 540(sp)
+  lw t0, 0(t0)
+  sw t0, 620(sp)
+  li t1, 2
+  slt t0, t0, t1
+  sw t0, 624(sp)
+  beqz t0, .Lmain_bb_if_false_11
+.Lmain_bb_if_true_11:
+  li t0, 1
+  lw t1, 544(sp)
+  sw t0, 0(t1)
+.Lmain_bb_if_end_11:
+  lw t0, 544(sp)
+  lw t0, 0(t0)
+  sw t0, 628(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 632(sp)
+  li a0, 32
+  call putch
+  sw a0, 636(sp)
+  li a0, 30
+  call putint
+  sw a0, 640(sp)
+  li a0, 32
+  call putch
+  sw a0, 644(sp)
+  lw t0, 540(sp)
+  lw t0, 0(t0)
+  sw t0, 648(sp)
+  addi t1, sp, 64
+  sw t1, 652(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 656(sp)
+  beqz t0, .Lmain_bb_sc_if_end_12
+.Lmain_bb_sc_if_true_12:
+  lw t0, 540(sp)
+  lw t0, 0(t0)
+  sw t0, 660(sp)
+  lw t1, 112(sp)
+  sw t0, 0(t1)
+  li t0, 1
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 664(sp)
+  lw t1, 652(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_12:
+  lw t0, 652(sp)
+  lw t0, 0(t0)
+  sw t0, 668(sp)
+  addi t1, sp, 68
+  sw t1, 672(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 676(sp)
+  beqz t0, .Lmain_bb_sc_if_end_13
+.Lmain_bb_sc_if_true_13:
+  lw t0, 112(sp)
+  sw zero, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 680(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 684(sp)
+  lw t1, 672(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_13:
+  lw t0, 672(sp)
+  lw t0, 0(t0)
+  sw t0, 688(sp)
+  lw t0, 112(sp)
+  lw t0, 0(t0)
+  sw t0, 692(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 696(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 700(sp)
+  li a0, 10
+  call putch
+  sw a0, 704(sp)
+  addi t0, sp, 72
+  sw t0, 708(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 76
+  sw t0, 712(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 80
+  sw t0, 716(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 84
+  sw t0, 720(sp)
+  li t1, 1
+  sw t1, 0(t0)
+  addi t0, sp, 88
+  sw t0, 724(sp)
+  li t1, 1
+  sw t1, 0(t0)
+.Lmain_bb_while_entry_14:
+  lw t0, 724(sp)
+  lw t0, 0(t0)
+  sw t0, 728(sp)
+  addi t1, sp, 92
+  sw t1, 732(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 736(sp)
+  beqz t0, .Lmain_bb_sc_if_end_15
+.Lmain_bb_sc_if_true_15:
+  lw t0, 716(sp)
+  lw t0, 0(t0)
+  sw t0, 788(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 792(sp)
+  lw t1, 716(sp)
+  sw t0, 0(t1)
+  lw t0, 720(sp)
+  lw t0, 0(t0)
+  sw t0, 796(sp)
+  slli t0, t0, 1
+  sw t0, 800(sp)
+  lw t1, 720(sp)
+  sw t0, 0(t1)
+  xor t0, zero, zero
+  snez t0, t0
+  sw t0, 804(sp)
+  lw t1, 732(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_15:
+  lw t0, 732(sp)
+  lw t0, 0(t0)
+  sw t0, 808(sp)
+  lw t0, 724(sp)
+  sw zero, 0(t0)
+  lw t0, 716(sp)
+  lw t0, 0(t0)
+  sw t0, 812(sp)
+  li t1, 5
+  slt t0, t0, t1
+  sw t0, 816(sp)
+  beqz t0, .Lmain_bb_while_end_14
+.Lmain_bb_while_body_14:
+  lw t0, 708(sp)
+  lw t0, 0(t0)
+  sw t0, 740(sp)
+  lw t0, 720(sp)
+  lw t0, 0(t0)
+  sw t0, 744(sp)
+  lw t1, 740(sp)
+  add t0, t1, t0
+  sw t0, 748(sp)
+  lw t1, 708(sp)
+  sw t0, 0(t1)
+  lw t0, 716(sp)
+  lw t0, 0(t0)
+  sw t0, 752(sp)
+  li t1, 2
+  xor t0, t0, t1
+  seqz t0, t0
+  sw t0, 756(sp)
+  beqz t0, .Lmain_bb_if_end_16
+.Lmain_bb_if_true_16:
+  j .Lmain_bb_while_entry_14
+.Lmain_bb_if_end_16:
+  lw t0, 712(sp)
+  lw t0, 0(t0)
+  sw t0, 820(sp)
+  lw t0, 716(sp)
+  lw t0, 0(t0)
+  sw t0, 824(sp)
+  lw t1, 820(sp)
+  add t0, t1, t0
+  sw t0, 828(sp)
+  lw t1, 712(sp)
+  sw t0, 0(t1)
+  j .Lmain_bb_while_entry_14
+.Lmain_bb_if_false_11:
+  lw t0, 544(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_if_end_11
+.Lmain_bb_while_end_14:
+  lw t0, 708(sp)
+  lw t0, 0(t0)
+  sw t0, 760(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 764(sp)
+  li a0, 32
+  call putch
+  sw a0, 768(sp)
+  lw t0, 712(sp)
+  lw t0, 0(t0)
+  sw t0, 772(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 776(sp)
+  li a0, 10
+  call putch
+  sw a0, 780(sp)
+  addi t0, sp, 96
+  sw t0, 784(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_18
+.Lmain_bb_while_body_18:
+  lw t0, 784(sp)
+  lw t0, 0(t0)
+  sw t0, 840(sp)
+  li t1, 3
+  add t0, t0, t1
+  sw t0, 844(sp)
+  lw t1, 784(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_18:
+  lw t0, 784(sp)
+  lw t0, 0(t0)
+  sw t0, 832(sp)
+  li t1, 10
+  slt t0, t0, t1
+  sw t0, 836(sp)
+  bnez t0, .Lmain_bb_while_body_18
+.Lmain_bb_while_end_18:
+  lw t0, 784(sp)
+  lw t0, 0(t0)
+  sw t0, 848(sp)
+  lui t0, %hi(calls)
+  lw t0, %lo(calls)(t0)
+  sw t0, 852(sp)
+  addi t1, sp, 100
+  sw t1, 856(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 860(sp)
+  beqz t0, .Lmain_bb_sc_if_end_19
+.Lmain_bb_sc_if_true_19:
+  lui t0, %hi(calls)
+  lw t0, %lo(calls)(t0)
+  sw t0, 864(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 868(sp)
+  lui t1, %hi(calls)
+  sw t0, %lo(calls)(t1)
+  lw t0, %lo(calls)(t1)
+  sw t0, 872(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 876(sp)
+  lw t1, 116(sp)
+  sw t0, 0(t1)
+  li t0, 1
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 880(sp)
+  lw t1, 856(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_19:
+  lw t0, 856(sp)
+  lw t0, 0(t0)
+  sw t0, 884(sp)
+  addi t1, sp, 104
+  sw t1, 888(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 892(sp)
+  beqz t0, .Lmain_bb_sc_if_end_20
+.Lmain_bb_sc_if_true_20:
+  lw t0, 116(sp)
+  sw zero, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 896(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 900(sp)
+  lw t1, 888(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_20:
+  lw t0, 888(sp)
+  lw t0, 0(t0)
+  sw t0, 904(sp)
+  lw t0, 116(sp)
+  lw t0, 0(t0)
+  sw t0, 908(sp)
+  lw t1, 848(sp)
+  add t0, t1, t0
+  sw t0, 912(sp)
+  mv a0, t0
+  lw ra, 924(sp)
+  addi sp, sp, 928
+  ret
+.Lmain_bb_unreachable_4:
+  j .Lmain_bb_if_end_3
+.Lmain_bb_unreachable_7:
+  j .Lmain_bb_if_end_6
+.Lmain_bb_unreachable_17:
+  j .Lmain_bb_if_end_16
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
@@ -0,0 +1,184 @@
+  .data
+  .globl arr
+  .type arr, @object
+  .align 2
+arr:
+  .word 1, 2, 3, 4
+  .size arr, 16
+
+  .text
+  .globl add3
+  .type add3, @function
+add3:
+  addi sp, sp, -64
+.Ladd3_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 20(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 24(sp)
+  sw a2, 0(t0)
+  addi t0, sp, 12
+  sw t0, 28(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  lw t0, 32(sp)
+  lw t1, 36(sp)
+  lw t2, 40(sp)
+  add t3, t0, t1
+  add t3, t3, t2
+  sw t3, 44(sp)
+  lw t0, 28(sp)
+  sw t3, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  mv a0, t0
+  addi sp, sp, 64
+  ret
+.Lfunc_end_add3:
+  .size add3, .Lfunc_end_add3-add3
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -160
+  sw ra, 156(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 12(sp)
+  li t1, 20
+  sw t1, 0(t0)
+  addi t0, sp, 4
+  sw t0, 16(sp)
+  nop
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 20(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 24(sp)
+  slli t1, t0, 3
+  sw t1, 28(sp)
+  lw t0, 16(sp)
+  sw t1, 0(t0)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 36(sp)
+  li a0, 10
+  call putch
+  sw a0, 40(sp)
+  lui t0, %hi(arr)
+  addi t0, t0, %lo(arr)
+  sw t0, 44(sp)
+  li t1, 2
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 48(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  lui t0, %hi(arr)
+  addi t0, t0, %lo(arr)
+  sw t0, 56(sp)
+  li t1, 3
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 60(sp)
+  lw t0, 0(t0)
+  sw t0, 64(sp)
+  lw t1, 52(sp)
+  sub t2, t1, t0
+  sw t2, 68(sp)
+  lw t0, 48(sp)
+  sw t2, 0(t0)
+  lui t0, %hi(arr)
+  addi t0, t0, %lo(arr)
+  sw t0, 72(sp)
+  li t1, 2
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 76(sp)
+  lw t0, 0(t0)
+  sw t0, 80(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 84(sp)
+  li a0, 10
+  call putch
+  sw a0, 88(sp)
+  addi t0, sp, 8
+  sw t0, 92(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  lw t0, 92(sp)
+  lw t0, 0(t0)
+  sw t0, 104(sp)
+  addi t1, t0, 1
+  sw t1, 108(sp)
+  lw t0, 92(sp)
+  sw t1, 0(t0)
+.Lmain_bb_while_entry_1:
+  lw t0, 92(sp)
+  lw t0, 0(t0)
+  sw t0, 96(sp)
+  li t1, 3
+  slt t0, t0, t1
+  sw t0, 100(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  lw t0, 92(sp)
+  lw t0, 0(t0)
+  sw t0, 112(sp)
+  add a0, zero, t0
+  li a1, 100
+  li a2, 1000
+  call add3
+  sw a0, 116(sp)
+  lw a0, 116(sp)
+  call putint
+  sw a0, 120(sp)
+  li a0, 32
+  call putch
+  sw a0, 124(sp)
+  lw t0, 92(sp)
+  lw t0, 0(t0)
+  sw t0, 128(sp)
+
+  li t0, 0x7f
+  sw t0, 132(sp)
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+  lw t0, 0(t1)
+  sw t0, 136(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 140(sp)
+  li a0, 10
+  call putch
+  sw a0, 144(sp)
+  li a0, 1
+  li a1, 2
+  li a2, 3
+  call add3
+  sw a0, 148(sp)
+  lw a0, 148(sp)
+  lw ra, 156(sp)
+  addi sp, sp, 160
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
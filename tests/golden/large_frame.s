@@ -0,0 +1,645 @@
+  .text
+  .globl sum
+  .type sum, @function
+sum:
+  addi sp, sp, -80
+.Lsum_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 20(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 24(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 12
+  sw t0, 28(sp)
+  sw zero, 0(t0)
+  j .Lsum_bb_while_entry_1
+.Lsum_bb_while_body_1:
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 44(sp)
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  lw t1, 48(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 56(sp)
+  lw t0, 0(t0)
+  sw t0, 60(sp)
+  lw t1, 44(sp)
+  add t0, t1, t0
+  sw t0, 64(sp)
+  lw t1, 28(sp)
+  sw t0, 0(t1)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 72(sp)
+  lw t1, 24(sp)
+  sw t0, 0(t1)
+.Lsum_bb_while_entry_1:
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t1, 32(sp)
+  slt t0, t1, t0
+  sw t0, 40(sp)
+  bnez t0, .Lsum_bb_while_body_1
+.Lsum_bb_while_end_1:
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 76(sp)
+  mv a0, t0
+  addi sp, sp, 80
+  ret
+.Lfunc_end_sum:
+  .size sum, .Lfunc_end_sum-sum
+
+  .text
+  .globl medium
+  .type medium, @function
+medium:
+  addi sp, sp, -2048
+  addi sp, sp, -32
+  li t6, 2076
+  add t6, t6, sp
+  sw ra, 0(t6)
+.Lmedium_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 2008(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 2012(sp)
+  addi t0, sp, 2004
+  sw t0, 2016(sp)
+  sw zero, 0(t0)
+  j .Lmedium_bb_while_entry_1
+.Lmedium_bb_while_body_1:
+  lw t0, 2012(sp)
+  sw t0, 2028(sp)
+  lw t0, 2016(sp)
+  lw t0, 0(t0)
+  sw t0, 2032(sp)
+  lw t1, 2028(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 2036(sp)
+  lw t0, 2008(sp)
+  lw t0, 0(t0)
+  sw t0, 2040(sp)
+  lw t0, 2016(sp)
+  lw t0, 0(t0)
+  sw t0, 2044(sp)
+  lw t1, 2040(sp)
+  add t0, t1, t0
+  li t6, 2048
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 2036(sp)
+  sw t0, 0(t1)
+  lw t0, 2016(sp)
+  lw t0, 0(t0)
+  li t6, 2052
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  add t0, t0, t1
+  li t6, 2056
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t1, 2016(sp)
+  sw t0, 0(t1)
+.Lmedium_bb_while_entry_1:
+  lw t0, 2016(sp)
+  lw t0, 0(t0)
+  sw t0, 2020(sp)
+  li t1, 500
+  slt t0, t0, t1
+  sw t0, 2024(sp)
+  bnez t0, .Lmedium_bb_while_body_1
+.Lmedium_bb_while_end_1:
+  lw t0, 2012(sp)
+  li t6, 2060
+  add t6, t6, sp
+  sw t0, 0(t6)
+  add a0, zero, t0
+  li a1, 500
+  call sum
+  li t6, 2064
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 2064
+  add t6, t6, sp
+  lw a0, 0(t6)
+  li t6, 2076
+  add t6, t6, sp
+  lw ra, 0(t6)
+  addi sp, sp, 2032
+  addi sp, sp, 48
+  ret
+.Lfunc_end_medium:
+  .size medium, .Lfunc_end_medium-medium
+
+  .text
+  .globl large
+  .type large, @function
+large:
+  li t6, -6160
+  add sp, sp, t6
+.Llarge_bb_entry_0:
+  addi t0, sp, 0
+  li t6, 6040
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  li t6, 6044
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  li t6, 6048
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a2, 0(t0)
+  addi t0, sp, 12
+  li t6, 6052
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a3, 0(t0)
+  addi t0, sp, 16
+  li t6, 6056
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a4, 0(t0)
+  addi t0, sp, 20
+  li t6, 6060
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a5, 0(t0)
+  addi t0, sp, 24
+  li t6, 6064
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a6, 0(t0)
+  addi t0, sp, 28
+  li t6, 6068
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw a7, 0(t0)
+  addi t0, sp, 32
+  li t6, 6072
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6160
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t1, 0(t0)
+  addi t0, sp, 36
+  li t6, 6076
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6164
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t1, 0(t0)
+  addi t0, sp, 40
+  li t6, 6080
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6084
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6088
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6040
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6092
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6068
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6096
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6092
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 6100
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6088
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t0, 0(t1)
+  li t6, 6080
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 6104
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1499
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 6108
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6072
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6112
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6076
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6116
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6112
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 6120
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6108
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t0, 0(t1)
+  li t6, 6080
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 6124
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6128
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6132
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6080
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 6136
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1499
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 6140
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 6144
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 6132
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 6148
+  add t6, t6, sp
+  sw t0, 0(t6)
+  mv a0, t0
+  li t6, 6160
+  add sp, sp, t6
+  ret
+.Lfunc_end_large:
+  .size large, .Lfunc_end_large-large
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  li t6, -21824
+  add sp, sp, t6
+  li t6, 21820
+  add t6, t6, sp
+  sw ra, 0(t6)
+.Lmain_bb_entry_0:
+  addi t0, sp, 8
+  li t6, 21612
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 12008
+  add t0, sp, t6
+  li t6, 21616
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21608
+  add t0, sp, t6
+  li t6, 21620
+  add t6, t6, sp
+  sw t0, 0(t6)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  li t6, 21612
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21632
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21620
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21636
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21632
+  add t6, t6, sp
+  lw t1, 0(t6)
+  slli t0, t0, 2
+  add t0, t1, t0
+  li t6, 21640
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21620
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21644
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21640
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t0, 0(t1)
+  li t6, 21620
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21648
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  add t0, t0, t1
+  li t6, 21652
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21620
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  li t6, 21620
+  add t6, t6, sp
+  lw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21624
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 3000
+  slt t0, t0, t1
+  li t6, 21628
+  add t6, t6, sp
+  sw t0, 0(t6)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  li t6, 21616
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21656
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1199
+  slli t1, t1, 3
+  add t0, t0, t1
+  li t6, 21660
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21664
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 21668
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 7
+  sw t1, 0(t0)
+  li t6, 21616
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21672
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21676
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21680
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21684
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21612
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21688
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 2999
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 21692
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21696
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21684
+  add t6, t6, sp
+  lw t1, 0(t6)
+  sw t0, 0(t1)
+  li t6, 21612
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21700
+  add t6, t6, sp
+  sw t0, 0(t6)
+  add a0, zero, t0
+  li a1, 3000
+  call sum
+  li t6, 21704
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 21704
+  add t6, t6, sp
+  lw a0, 0(t6)
+  call putint
+  li t6, 21708
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 10
+  call putch
+  li t6, 21712
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 21616
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21716
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1199
+  slli t1, t1, 3
+  add t0, t0, t1
+  li t6, 21720
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21724
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 21728
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21732
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21616
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21736
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21740
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21744
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21748
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21752
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21732
+  add t6, t6, sp
+  lw t1, 0(t6)
+  add t0, t1, t0
+  li t6, 21756
+  add t6, t6, sp
+  sw t0, 0(t6)
+  add a0, zero, t0
+  call putint
+  li t6, 21760
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 10
+  call putch
+  li t6, 21764
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 3
+  call medium
+  li t6, 21768
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 21768
+  add t6, t6, sp
+  lw a0, 0(t6)
+  call putint
+  li t6, 21772
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 10
+  call putch
+  li t6, 21776
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 1
+  li a1, 2
+  li a2, 3
+  li a3, 4
+  li a4, 5
+  li a5, 6
+  li a6, 7
+  li a7, 8
+  li t0, 9
+  sw t0, 0(sp)
+  li t0, 10
+  sw t0, 4(sp)
+  call large
+  li t6, 21780
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 21780
+  add t6, t6, sp
+  lw a0, 0(t6)
+  call putint
+  li t6, 21784
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li a0, 10
+  call putch
+  li t6, 21788
+  add t6, t6, sp
+  sw a0, 0(t6)
+  li t6, 21616
+  add t6, t6, sp
+  lw t0, 0(t6)
+  li t6, 21792
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1199
+  slli t1, t1, 3
+  add t0, t0, t1
+  li t6, 21796
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t6, 21800
+  add t6, t6, sp
+  sw t0, 0(t6)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  li t6, 21804
+  add t6, t6, sp
+  sw t0, 0(t6)
+  lw t0, 0(t0)
+  li t6, 21808
+  add t6, t6, sp
+  sw t0, 0(t6)
+  mv a0, t0
+  li t6, 21820
+  add t6, t6, sp
+  lw ra, 0(t6)
+  li t6, 21824
+  add sp, sp, t6
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
@@ -0,0 +1,175 @@
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -208
+  sw ra, 204(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 32(sp)
+  sw zero, 0(t0)
+  addi t1, sp, 4
+  sw t1, 36(sp)
+  li t2, 5
+  sw t2, 0(t1)
+  lw t0, 0(t0)
+  sw t0, 40(sp)
+  addi t1, sp, 8
+  sw t1, 44(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 48(sp)
+  beqz t0, .Lmain_bb_sc_if_end_1
+.Lmain_bb_sc_if_true_1:
+  li t0, 20
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 52(sp)
+  lw t1, 44(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_1:
+  lw t0, 44(sp)
+  lw t0, 0(t0)
+  sw t0, 56(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 60(sp)
+  li a0, 32
+  call putch
+  sw a0, 64(sp)
+  lw t0, 36(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  addi t1, sp, 12
+  sw t1, 72(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 76(sp)
+  beqz t0, .Lmain_bb_sc_if_end_2
+.Lmain_bb_sc_if_true_2:
+  li t0, 7
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 80(sp)
+  lw t1, 72(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_2:
+  lw t0, 72(sp)
+  lw t0, 0(t0)
+  sw t0, 84(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 88(sp)
+  li a0, 32
+  call putch
+  sw a0, 92(sp)
+  lw t0, 32(sp)
+  lw t0, 0(t0)
+  sw t0, 96(sp)
+  addi t1, sp, 16
+  sw t1, 100(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 104(sp)
+  beqz t0, .Lmain_bb_sc_if_end_3
+.Lmain_bb_sc_if_true_3:
+  lw t0, 36(sp)
+  lw t0, 0(t0)
+  sw t0, 108(sp)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 112(sp)
+  lw t1, 100(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_3:
+  lw t0, 100(sp)
+  lw t0, 0(t0)
+  sw t0, 116(sp)
+  lw t0, 36(sp)
+  lw t0, 0(t0)
+  sw t0, 120(sp)
+  addi t1, sp, 20
+  sw t1, 124(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 128(sp)
+  beqz t0, .Lmain_bb_sc_if_end_4
+.Lmain_bb_sc_if_true_4:
+  li t0, -3
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 132(sp)
+  lw t1, 124(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_4:
+  lw t0, 124(sp)
+  lw t0, 0(t0)
+  sw t0, 136(sp)
+  lw t1, 116(sp)
+  add t0, t1, t0
+  sw t0, 140(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 144(sp)
+  li a0, 10
+  call putch
+  sw a0, 148(sp)
+  lw t0, 32(sp)
+  lw t0, 0(t0)
+  sw t0, 152(sp)
+  addi t1, sp, 24
+  sw t1, 156(sp)
+  li t2, 1
+  sw t2, 0(t1)
+  xor t0, t0, zero
+  seqz t0, t0
+  sw t0, 160(sp)
+  beqz t0, .Lmain_bb_sc_if_end_5
+.Lmain_bb_sc_if_true_5:
+  li t0, 300
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 164(sp)
+  lw t1, 156(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_5:
+  lw t0, 156(sp)
+  lw t0, 0(t0)
+  sw t0, 168(sp)
+  lw t0, 36(sp)
+  lw t0, 0(t0)
+  sw t0, 172(sp)
+  addi t1, sp, 28
+  sw t1, 176(sp)
+  sw zero, 0(t1)
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 180(sp)
+  beqz t0, .Lmain_bb_sc_if_end_6
+.Lmain_bb_sc_if_true_6:
+  li t0, 256
+  xor t0, t0, zero
+  snez t0, t0
+  sw t0, 184(sp)
+  lw t1, 176(sp)
+  sw t0, 0(t1)
+.Lmain_bb_sc_if_end_6:
+  lw t0, 176(sp)
+  lw t0, 0(t0)
+  sw t0, 188(sp)
+  lw t1, 168(sp)
+  add t0, t1, t0
+  sw t0, 192(sp)
+  mv a0, t0
+  lw ra, 204(sp)
+  addi sp, sp, 208
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
@@ -0,0 +1,263 @@
+  .text
+  .globl fill
+  .type fill, @function
+fill:
+  addi sp, sp, -96
+.Lfill_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 16(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 20(sp)
+  sw a1, 0(t0)
+  addi t0, sp, 8
+  sw t0, 24(sp)
+  sw a2, 0(t0)
+  addi t0, sp, 12
+  sw t0, 28(sp)
+  sw zero, 0(t0)
+  j .Lfill_bb_while_entry_1
+.Lfill_bb_while_body_1:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 44(sp)
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 48(sp)
+  lw t1, 44(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 52(sp)
+  lw t0, 24(sp)
+  lw t0, 0(t0)
+  sw t0, 56(sp)
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 60(sp)
+  lw t1, 56(sp)
+  add t0, t1, t0
+  sw t0, 64(sp)
+  lw t1, 52(sp)
+  sw t0, 0(t1)
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 72(sp)
+  lw t1, 28(sp)
+  sw t0, 0(t1)
+.Lfill_bb_while_entry_1:
+  lw t0, 28(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 36(sp)
+  lw t1, 32(sp)
+  slt t0, t1, t0
+  sw t0, 40(sp)
+  bnez t0, .Lfill_bb_while_body_1
+.Lfill_bb_while_end_1:
+  lw t0, 16(sp)
+  lw t0, 0(t0)
+  sw t0, 76(sp)
+  lw t0, 20(sp)
+  lw t0, 0(t0)
+  sw t0, 80(sp)
+  li t1, 1
+  sub t0, t0, t1
+  sw t0, 84(sp)
+  lw t1, 76(sp)
+  slli t0, t0, 2
+  add t0, t1, t0
+  sw t0, 88(sp)
+  lw t0, 0(t0)
+  sw t0, 92(sp)
+  mv a0, t0
+  addi sp, sp, 96
+  ret
+.Lfunc_end_fill:
+  .size fill, .Lfunc_end_fill-fill
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -640
+  sw ra, 636(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 448(sp)
+  addi t1, sp, 40
+  sw t1, 452(sp)
+  sw t0, 456(sp)
+  add a0, zero, t0
+  li a1, 10
+  add a2, zero, zero
+  call fill
+  sw a0, 460(sp)
+  lw t0, 460(sp)
+  lw t1, 452(sp)
+  sw t0, 0(t1)
+  addi t0, sp, 44
+  sw t0, 464(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  addi t0, sp, 48
+  sw t0, 476(sp)
+  lw t0, 452(sp)
+  lw t0, 0(t0)
+  sw t0, 480(sp)
+  lw t0, 476(sp)
+  sw t0, 484(sp)
+  lw t0, 464(sp)
+  lw t0, 0(t0)
+  sw t0, 488(sp)
+  lw a0, 484(sp)
+  li a1, 100
+  add a2, zero, t0
+  call fill
+  sw a0, 492(sp)
+  lw t0, 480(sp)
+  lw t1, 492(sp)
+  add t0, t0, t1
+  sw t0, 496(sp)
+  lw t1, 452(sp)
+  sw t0, 0(t1)
+  addi t0, sp, 48
+  sw t0, 500(sp)
+  li t1, 200
+  call __sysy_memzero
+  lw t0, 500(sp)
+  sw t0, 504(sp)
+  li t1, 1
+  sw t1, 0(t0)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 508(sp)
+  li t1, 2
+  sw t1, 0(t0)
+  addi t0, sp, 248
+  sw t0, 512(sp)
+  lw t0, 452(sp)
+  lw t0, 0(t0)
+  sw t0, 516(sp)
+  lw t0, 512(sp)
+  sw t0, 520(sp)
+  lw t0, 500(sp)
+  sw t0, 524(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 528(sp)
+  lw t0, 0(t0)
+  sw t0, 532(sp)
+  lw a0, 520(sp)
+  li a1, 10
+  add a2, zero, t0
+  call fill
+  sw a0, 536(sp)
+  lw t0, 516(sp)
+  lw t1, 536(sp)
+  add t0, t0, t1
+  sw t0, 540(sp)
+  lw t0, 500(sp)
+  sw t0, 544(sp)
+  sw t0, 548(sp)
+  lw t0, 0(t0)
+  sw t0, 552(sp)
+  lw t1, 540(sp)
+  add t0, t1, t0
+  sw t0, 556(sp)
+  lw t1, 452(sp)
+  sw t0, 0(t1)
+  addi t0, sp, 288
+  sw t0, 560(sp)
+  lw t0, 0(t1)
+  sw t0, 564(sp)
+  lw t0, 560(sp)
+  sw t0, 568(sp)
+  lw t0, 500(sp)
+  sw t0, 572(sp)
+  li t1, 49
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 576(sp)
+  lw t0, 0(t0)
+  sw t0, 580(sp)
+  lw a0, 568(sp)
+  li a1, 20
+  add a2, zero, t0
+  call fill
+  sw a0, 584(sp)
+  lw t0, 564(sp)
+  lw t1, 584(sp)
+  add t0, t0, t1
+  sw t0, 588(sp)
+  lw t1, 452(sp)
+  sw t0, 0(t1)
+  lw t0, 464(sp)
+  lw t0, 0(t0)
+  sw t0, 592(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 596(sp)
+  lw t1, 464(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  lw t0, 464(sp)
+  lw t0, 0(t0)
+  sw t0, 468(sp)
+  li t1, 3
+  slt t0, t0, t1
+  sw t0, 472(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  lw t0, 452(sp)
+  lw t0, 0(t0)
+  sw t0, 600(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 604(sp)
+  li a0, 10
+  call putch
+  sw a0, 608(sp)
+  lw t0, 448(sp)
+  sw t0, 612(sp)
+  li t1, 9
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 616(sp)
+  lw t0, 0(t0)
+  sw t0, 620(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 624(sp)
+  li a0, 10
+  call putch
+  sw a0, 628(sp)
+  mv a0, zero
+  lw ra, 636(sp)
+  addi sp, sp, 640
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
+  .text
+  .local __sysy_memzero
+  .type __sysy_memzero, @function
+__sysy_memzero:
+.L__sysy_memzero_loop:
+  beqz t1, .L__sysy_memzero_end
+  sw zero, 0(t0)
+  addi t0, t0, 4
+  addi t1, t1, -4
+  j .L__sysy_memzero_loop
+.L__sysy_memzero_end:
+  ret
+.Lfunc_end___sysy_memzero:
+  .size __sysy_memzero, .Lfunc_end___sysy_memzero-__sysy_memzero
+
@@ -0,0 +1,211 @@
+  .bss
+  .globl ticks
+  .type ticks, @object
+  .align 2
+ticks:
+  .zero 4
+  .size ticks, 4
+
+  .bss
+  .globl device
+  .type device, @object
+  .align 2
+device:
+  .zero 16
+  .size device, 16
+
+  .text
+  .globl delay
+  .type delay, @function
+delay:
+  addi sp, sp, -48
+.Ldelay_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 8(sp)
+  sw a0, 0(t0)
+  addi t0, sp, 4
+  sw t0, 12(sp)
+  # volatile
+  sw zero, 0(t0)
+  j .Ldelay_bb_while_entry_1
+.Ldelay_bb_while_body_1:
+  # volatile
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 28(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 32(sp)
+  # volatile
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+.Ldelay_bb_while_entry_1:
+  # volatile
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  lw t0, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 20(sp)
+  lw t1, 16(sp)
+  slt t0, t1, t0
+  sw t0, 24(sp)
+  bnez t0, .Ldelay_bb_while_body_1
+.Ldelay_bb_while_end_1:
+  addi sp, sp, 48
+  ret
+.Lfunc_end_delay:
+  .size delay, .Lfunc_end_delay-delay
+
+  .text
+  .globl poll
+  .type poll, @function
+poll:
+  addi sp, sp, -48
+.Lpoll_bb_entry_0:
+  lui t0, %hi(device)
+  addi t0, t0, %lo(device)
+  sw t0, 0(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 4(sp)
+  lui t0, %hi(device)
+  addi t0, t0, %lo(device)
+  sw t0, 8(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 12(sp)
+  # volatile
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 20(sp)
+  # volatile
+  lw t1, 4(sp)
+  sw t0, 0(t1)
+  lui t0, %hi(device)
+  addi t0, t0, %lo(device)
+  sw t0, 24(sp)
+  li t1, 1
+  slli t1, t1, 2
+  add t0, t0, t1
+  sw t0, 28(sp)
+  # volatile
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  mv a0, t0
+  addi sp, sp, 48
+  ret
+.Lfunc_end_poll:
+  .size poll, .Lfunc_end_poll-poll
+
+  .text
+  .globl main
+  .type main, @function
+main:
+  addi sp, sp, -128
+  sw ra, 124(sp)
+.Lmain_bb_entry_0:
+  addi t0, sp, 0
+  sw t0, 8(sp)
+  sw zero, 0(t0)
+  addi t0, sp, 4
+  sw t0, 12(sp)
+  sw zero, 0(t0)
+  j .Lmain_bb_while_entry_1
+.Lmain_bb_while_body_1:
+  # volatile
+  lui t0, %hi(ticks)
+  lw t0, %lo(ticks)(t0)
+  sw t0, 24(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 28(sp)
+  # volatile
+  lui t1, %hi(ticks)
+  sw t0, %lo(ticks)(t1)
+  li a0, 10
+  call delay
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 32(sp)
+  call poll
+  sw a0, 36(sp)
+  # volatile
+  lui t0, %hi(ticks)
+  lw t0, %lo(ticks)(t0)
+  sw t0, 40(sp)
+  lw t1, 36(sp)
+  mul t0, t1, t0
+  sw t0, 44(sp)
+  lw t1, 32(sp)
+  add t0, t1, t0
+  sw t0, 48(sp)
+  lw t1, 12(sp)
+  sw t0, 0(t1)
+  lw t0, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 52(sp)
+  li t1, 1
+  add t0, t0, t1
+  sw t0, 56(sp)
+  lw t1, 8(sp)
+  sw t0, 0(t1)
+.Lmain_bb_while_entry_1:
+  lw t0, 8(sp)
+  lw t0, 0(t0)
+  sw t0, 16(sp)
+  li t1, 5
+  slt t0, t0, t1
+  sw t0, 20(sp)
+  bnez t0, .Lmain_bb_while_body_1
+.Lmain_bb_while_end_1:
+  lui t0, %hi(device)
+  addi t0, t0, %lo(device)
+  sw t0, 60(sp)
+  sw t0, 64(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 68(sp)
+  # volatile
+  lw t1, 64(sp)
+  sw t0, 0(t1)
+  lui t0, %hi(device)
+  addi t0, t0, %lo(device)
+  sw t0, 72(sp)
+  sw t0, 76(sp)
+  # volatile
+  lw t0, 0(t0)
+  sw t0, 80(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 84(sp)
+  li a0, 10
+  call putch
+  sw a0, 88(sp)
+  # volatile
+  lui t0, %hi(ticks)
+  lw t0, %lo(ticks)(t0)
+  sw t0, 92(sp)
+  add a0, zero, t0
+  call putint
+  sw a0, 96(sp)
+  li a0, 10
+  call putch
+  sw a0, 100(sp)
+  lw t0, 12(sp)
+  lw t0, 0(t0)
+  sw t0, 104(sp)
+  li t1, 256
+  rem t0, t0, t1
+  sw t0, 108(sp)
+  mv a0, t0
+  lw ra, 124(sp)
+  addi sp, sp, 128
+  ret
+.Lfunc_end_main:
+  .size main, .Lfunc_end_main-main
+
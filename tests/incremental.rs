@@ -0,0 +1,107 @@
+//! 增量解析的测试：对 `testcases/` 下的程序依次做一系列改动，每次以上一次的语法树增量地重新
+//! 解析，结果须与整个重新解析相同。使程序出现语法错误的改动在比较之后撤销，其余的改动累积下来。
+
+use std::fs;
+use std::path::Path;
+use std::thread;
+
+use sysyc::frontend::ast::{Ast, Span};
+use sysyc::frontend::{parse, reparse};
+use sysyc::serialize::serialize_ast;
+
+/// 比较用的语法树 JSON（[`serialize_ast`]）与表达式嵌套得一样深，生成、输出与释放都是递归的，
+/// 在栈足够大的线程中进行
+const STACK_SIZE: usize = 1 << 30;
+
+/// 每个程序至多做的改动
+const EDITS: usize = 200;
+/// 较大的程序少做一些改动，使每个程序解析的源代码至多约为这么多字节
+const BUDGET: usize = 200_000;
+
+/// 插入的文本：空白、记号、使其后成为注释或结束注释的符号，以及一个完整的声明
+const INSERTIONS: &[&str] = &[
+  " ", "\n", "x", "1", ";", "}", "{", "/*", "*/", "//", "int q;",
+];
+
+/// 语法树的 JSON 与各顶层声明的范围
+fn summary(ast: &Ast) -> (String, Vec<Span>) {
+  let spans = ast.unit.iter().map(|&decl| ast.span(decl));
+  (serialize_ast(ast).to_string(), spans.collect())
+}
+
+/// 固定种子的伪随机数，使失败可以重现
+struct Random(u64);
+
+impl Random {
+  fn below(&mut self, n: usize) -> usize {
+    self.0 = self
+      .0
+      .wrapping_mul(6364136223846793005)
+      .wrapping_add(1442695040888963407);
+    (self.0 >> 33) as usize % n.max(1)
+  }
+}
+
+/// `text` 中不早于 `pos` 的最近的字符边界
+fn boundary(text: &str, mut pos: usize) -> usize {
+  while !text.is_char_boundary(pos) {
+    pos += 1;
+  }
+  pos
+}
+
+#[test]
+fn reparse_matches_full_parse() {
+  let test = thread::Builder::new()
+    .stack_size(STACK_SIZE)
+    .spawn(edit_cases);
+  test.unwrap().join().unwrap();
+}
+
+fn edit_cases() {
+  let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testcases");
+  let mut cases: Vec<_> = fs::read_dir(dir)
+    .unwrap()
+    .map(|e| e.unwrap().path())
+    .collect();
+  cases.retain(|path| path.extension().is_some_and(|ext| ext == "sy"));
+  cases.sort();
+  assert!(!cases.is_empty());
+  let mut random = Random(1);
+  for path in cases {
+    let mut text = fs::read_to_string(&path).unwrap();
+    let mut previous = Some(parse(&text).unwrap());
+    for edit in 0..EDITS.min(BUDGET / text.len()) {
+      let start = boundary(&text, random.below(text.len() + 1));
+      let mut new = text.clone();
+      match random.below(3) {
+        0 => {
+          let end = boundary(&text, (start + 1 + random.below(10)).min(text.len()));
+          new.replace_range(start..end, "");
+        }
+        _ => new.insert_str(start, INSERTIONS[random.below(INSERTIONS.len())]),
+      }
+      let expected = parse(&new);
+      let actual = match previous {
+        Some(ast) => reparse(ast, &text, &new),
+        None => parse(&new),
+      };
+      let name = path.file_name().unwrap().to_string_lossy();
+      match (&expected, &actual) {
+        (Ok(expected), Ok(actual)) => {
+          assert_eq!(summary(actual), summary(expected), "{} edit {}", name, edit);
+        }
+        (Err(expected), Err(actual)) => assert_eq!(actual, expected, "{} edit {}", name, edit),
+        _ => panic!("{} edit {}: only one of the parses failed", name, edit),
+      }
+      // 保留不出错的改动，出错时撤销
+      match actual {
+        Ok(ast) => {
+          previous = Some(ast);
+          text = new;
+        }
+        Err(_) => previous = parse(&text).ok(),
+      }
+    }
+  }
+}
@@ -0,0 +1,131 @@
+//! 即时编译的回归测试：`testcases/` 下的每个程序与若干边界情况即时编译执行，输出与返回值须同
+//! 解释执行的结果一致。
+
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use std::fs;
+use std::path::Path;
+
+use sysyc::{interpreter, jit, CompileOptions};
+
+/// 分别即时编译与解释执行，结果须一致；出错时只比较是否出错
+fn check(name: &str, source: &str, input: &[u8]) -> Result<(), String> {
+  let ir = sysyc::compile_to_koopa(source, &CompileOptions::default())
+    .map_err(|e| format!("{}: compile error: {}", name, e))?;
+  let expected = interpreter::run_captured(&ir, input.to_vec());
+  let actual = jit::run_captured(&ir, input.to_vec());
+  match (expected, actual) {
+    (Ok(expected), Ok(actual)) if expected == actual => Ok(()),
+    (Ok(expected), Ok(actual)) => Err(format!(
+      "{}: expected {:?}, got {:?}",
+      name,
+      (String::from_utf8_lossy(&expected.0), expected.1),
+      (String::from_utf8_lossy(&actual.0), actual.1),
+    )),
+    (Err(_), Err(_)) => Ok(()),
+    (Ok(_), Err(e)) => Err(format!("{}: JIT failed: {}", name, e)),
+    (Err(e), Ok(_)) => Err(format!(
+      "{}: interpreter failed ({}) but JIT did not",
+      name, e
+    )),
+  }
+}
+
+#[test]
+fn testcases() {
+  let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testcases");
+  let entries = fs::read_dir(dir).expect("cannot read testcases/");
+  let mut cases: Vec<_> = entries
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "sy"))
+    .collect();
+  cases.sort();
+  let mut failures = vec![];
+  for case in cases {
+    let stem = case.file_stem().unwrap().to_string_lossy().into_owned();
+    let source = fs::read_to_string(&case).unwrap();
+    let input = fs::read(case.with_extension("in")).unwrap_or_default();
+    if let Err(e) = check(&stem, &source, &input) {
+      failures.push(e);
+    }
+  }
+  assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn division() {
+  let source = r#"
+    int main() {
+      int a[6] = {7, -7, 2147483647, -2147483647 - 1, 0, 5};
+      int i = 0;
+      while (i < 6) {
+        int j = 0;
+        while (j < 6) {
+          putint(a[i] / a[j]);
+          putch(32);
+          putint(a[i] % a[j]);
+          putch(10);
+          j = j + 1;
+        }
+        i = i + 1;
+      }
+      return a[3] / -1 % 256;
+    }
+  "#;
+  check("division", source, b"").unwrap();
+}
+
+#[test]
+fn arrays_and_input() {
+  let source = r#"
+    int g[3][4] = {{1}, {2, 3}, 4, 5};
+    int sum(int a[][4], int n) {
+      int s = 0, i = 0;
+      while (i < n) { s = s + a[i][0] + a[i][1]; i = i + 1; }
+      return s;
+    }
+    int many(int a, int b, int c, int d, int e, int f, int g, int h, int i) {
+      return a - b + c - d + e - f + g - h + i * 100;
+    }
+    int main() {
+      int buf[16];
+      int n = getarray(buf);
+      putarray(n, buf);
+      putint(getint());
+      putch(getch());
+      putint(sum(g, 3));
+      putch(10);
+      return many(1, 2, 3, 4, 5, 6, 7, 8, 9) + buf[0];
+    }
+  "#;
+  check("arrays_and_input", source, b"4 10 20 30 40\n-17 x").unwrap();
+}
+
+#[test]
+fn deep_recursion() {
+  let source = r#"
+    int depth(int n) {
+      if (n == 0) return 0;
+      return depth(n - 1) + 1;
+    }
+    int main() {
+      putint(depth(200000));
+      return 0;
+    }
+  "#;
+  check("deep_recursion", source, b"").unwrap();
+}
+
+#[test]
+fn stack_overflow() {
+  let source = r#"
+    int f(int n) { return f(n + 1) + 1; }
+    int main() {
+      putint(1);
+      return f(0);
+    }
+  "#;
+  let ir = sysyc::compile_to_koopa(source, &CompileOptions::default()).unwrap();
+  let error = jit::run_captured(&ir, vec![]).unwrap_err();
+  assert!(error.to_string().contains("stack overflow"), "{}", error);
+}
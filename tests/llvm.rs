@@ -0,0 +1,163 @@
+//! LLVM IR 输出（`--emit=llvm`）的运行测试：`testcases/` 下的程序与若干边界情况输出为 LLVM IR，
+//! 以 `lli` 连同 `tests/llvm/sylib.ll` 中的运行时库执行，输出与退出码须同 `.out` 中的结果一致。
+//! 系统中没有 `lli` 时跳过。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::{env, fs, thread};
+
+use sysyc::{interpreter, CompileOptions};
+
+fn manifest_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn has_lli() -> bool {
+  let status = Command::new("lli")
+    .arg("--version")
+    .stdout(Stdio::null())
+    .status();
+  if status.is_err() {
+    eprintln!("lli not found, skipping");
+  }
+  status.is_ok()
+}
+
+/// 输出 LLVM IR 并以 `lli` 运行，返回评测格式的输出
+fn run(source: &str, input: &[u8]) -> Result<String, String> {
+  let ir =
+    sysyc::compile_to_koopa(source, &CompileOptions::default()).map_err(|e| e.to_string())?;
+  let mut llvm = vec![];
+  sysyc::backend::generate_llvm(&ir, &mut llvm).map_err(|e| e.to_string())?;
+  let runtime = manifest_dir().join("tests").join("llvm").join("sylib.ll");
+  // 模块写入文件，标准输入留给程序
+  let module = env::temp_dir().join(format!(
+    "sysyc-llvm-{}-{:?}.ll",
+    process::id(),
+    thread::current().id()
+  ));
+  fs::write(&module, llvm).unwrap();
+  let mut child = Command::new("lli")
+    .arg(format!("-extra-module={}", runtime.display()))
+    .arg(&module)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| e.to_string())?;
+  child.stdin.take().unwrap().write_all(input).unwrap();
+  let output = child.wait_with_output().map_err(|e| e.to_string());
+  fs::remove_file(&module).unwrap();
+  let output = output?;
+  let stderr = String::from_utf8_lossy(&output.stderr);
+  let code = output
+    .status
+    .code()
+    .ok_or(format!("killed by a signal: {}", stderr))?;
+  Ok(judge(
+    String::from_utf8_lossy(&output.stdout).into_owned(),
+    code,
+  ))
+}
+
+/// 评测格式的输出：程序的输出，随后一行是退出码
+fn judge(mut stdout: String, code: i32) -> String {
+  if !stdout.is_empty() && !stdout.ends_with('\n') {
+    stdout.push('\n');
+  }
+  format!("{}{}\n", stdout, code)
+}
+
+/// 与 `sysyc test` 一样忽略行末的空白与末尾的空行
+fn normalize(output: &str) -> Vec<&str> {
+  let mut lines: Vec<_> = output.lines().map(str::trim_end).collect();
+  while lines.last() == Some(&"") {
+    lines.pop();
+  }
+  lines
+}
+
+fn check(path: &Path) -> Result<(), String> {
+  let name = path.file_stem().unwrap().to_string_lossy();
+  let source = fs::read_to_string(path).unwrap();
+  let input = fs::read(path.with_extension("in")).unwrap_or_default();
+  let expected = fs::read_to_string(path.with_extension("out")).unwrap();
+  let actual = run(&source, &input).map_err(|e| format!("{}: {}", name, e))?;
+  match normalize(&expected) == normalize(&actual) {
+    true => Ok(()),
+    false => Err(format!("{}: expected\n{}\ngot\n{}", name, expected, actual)),
+  }
+}
+
+#[test]
+fn testcases() {
+  if !has_lli() {
+    return;
+  }
+  let dir = manifest_dir().join("testcases");
+  let entries = fs::read_dir(dir).expect("cannot read testcases/");
+  let mut cases: Vec<_> = entries
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "sy"))
+    // 内联汇编是 RISC-V 汇编，不能在本机运行
+    .filter(|path| path.file_stem().is_some_and(|stem| stem != "inline_asm"))
+    .collect();
+  cases.sort();
+  let failures: Vec<_> = cases.iter().filter_map(|case| check(case).err()).collect();
+  assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// 循环体中的 `alloc`（`&&` 的结果、块作用域的变量）只在入口分配一次，循环几百万次栈也不增长
+#[test]
+fn allocas_in_loops() {
+  let source = r#"
+    int main() {
+      int i = 0, s = 0;
+      while (i < 3000000) {
+        int t[4] = {i};
+        if (i % 2 == 0 && i % 3 == 0) s = s + 1 + t[1];
+        i = i + 1;
+      }
+      putint(s);
+      return 0;
+    }
+  "#;
+  let ir = sysyc::compile_to_koopa(source, &CompileOptions::default()).unwrap();
+  let mut llvm = vec![];
+  sysyc::backend::generate_llvm(&ir, &mut llvm).unwrap();
+  let llvm = String::from_utf8(llvm).unwrap();
+  let (entry, body) = llvm.split_once("\nbb0:\n").unwrap();
+  assert!(entry.contains("alloca"), "{}", llvm);
+  assert!(!body.contains("alloca"), "{}", llvm);
+  if has_lli() {
+    assert_eq!(run(source, b"").unwrap(), "500000\n0\n");
+  }
+}
+
+/// 除法按 RISC-V 的语义进行，除数为 0 或 -1 时不是未定义行为，结果与解释执行相同
+#[test]
+fn division() {
+  if !has_lli() {
+    return;
+  }
+  let source = r#"
+    int main() {
+      int a[6] = {7, -7, 2147483647, -2147483647 - 1, 0, 1}, i = 0;
+      while (i < 6) {
+        int j = 0;
+        while (j < 6) {
+          putint(a[i] / a[j]); putch(32); putint(a[i] % a[j]); putch(32);
+          j = j + 1;
+        }
+        putch(10);
+        i = i + 1;
+      }
+      return a[0] / getint() + a[1] % 3;
+    }
+  "#;
+  let ir = sysyc::compile_to_koopa(source, &CompileOptions::default()).unwrap();
+  let (stdout, code) = interpreter::run_captured(&ir, b"2".to_vec()).unwrap();
+  let expected = judge(String::from_utf8(stdout).unwrap(), code & 0xff);
+  assert_eq!(run(source, b"2").unwrap(), expected);
+}
@@ -0,0 +1,88 @@
+; 以 C 库实现的 SysY 运行时库，供 tests/llvm.rs 以 `lli -extra-module` 运行 `--emit=llvm` 的输出
+
+@int = private constant [3 x i8] c"%d\00"
+@count = private constant [4 x i8] c"%d:\00"
+@elem = private constant [4 x i8] c" %d\00"
+
+declare i32 @scanf(i8*, ...)
+declare i32 @printf(i8*, ...)
+declare i32 @getchar()
+declare i32 @putchar(i32)
+
+define i32 @getint() {
+  %x = alloca i32
+  store i32 0, i32* %x
+  %format = getelementptr [3 x i8], [3 x i8]* @int, i32 0, i32 0
+  call i32 (i8*, ...) @scanf(i8* %format, i32* %x)
+  %value = load i32, i32* %x
+  ret i32 %value
+}
+
+define i32 @getch() {
+  %c = call i32 @getchar()
+  ret i32 %c
+}
+
+define i32 @getarray(i32* %a) {
+entry:
+  %n = call i32 @getint()
+  br label %loop
+
+loop:
+  %i = phi i32 [0, %entry], [%next, %body]
+  %more = icmp slt i32 %i, %n
+  br i1 %more, label %body, label %done
+
+body:
+  %value = call i32 @getint()
+  %ptr = getelementptr i32, i32* %a, i32 %i
+  store i32 %value, i32* %ptr
+  %next = add i32 %i, 1
+  br label %loop
+
+done:
+  ret i32 %n
+}
+
+define i32 @putint(i32 %x) {
+  %format = getelementptr [3 x i8], [3 x i8]* @int, i32 0, i32 0
+  call i32 (i8*, ...) @printf(i8* %format, i32 %x)
+  ret i32 0
+}
+
+define i32 @putch(i32 %c) {
+  call i32 @putchar(i32 %c)
+  ret i32 0
+}
+
+define i32 @putarray(i32 %n, i32* %a) {
+entry:
+  %count = getelementptr [4 x i8], [4 x i8]* @count, i32 0, i32 0
+  call i32 (i8*, ...) @printf(i8* %count, i32 %n)
+  %format = getelementptr [4 x i8], [4 x i8]* @elem, i32 0, i32 0
+  br label %loop
+
+loop:
+  %i = phi i32 [0, %entry], [%next, %body]
+  %more = icmp slt i32 %i, %n
+  br i1 %more, label %body, label %done
+
+body:
+  %ptr = getelementptr i32, i32* %a, i32 %i
+  %value = load i32, i32* %ptr
+  call i32 (i8*, ...) @printf(i8* %format, i32 %value)
+  %next = add i32 %i, 1
+  br label %loop
+
+done:
+  call i32 @putchar(i32 10)
+  ret i32 0
+}
+
+define i32 @starttime() {
+  ret i32 0
+}
+
+define i32 @stoptime() {
+  ret i32 0
+}
@@ -0,0 +1,330 @@
+//! 语言服务器的测试：启动 `sysy-lsp`，经由标准输入输出完成一次会话，检查各个响应。
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+use sysyc::serialize::Json;
+
+const URI: &str = "file:///test.sy";
+
+struct Session {
+  child: Child,
+  output: BufReader<ChildStdout>,
+  next_id: i32,
+}
+
+impl Session {
+  /// 启动服务器，完成初始化并打开内容为 `text` 的文件，返回会话与打开时发布的诊断
+  fn open(text: &str) -> (Session, Json) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sysy-lsp"))
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .spawn()
+      .unwrap();
+    let output = BufReader::new(child.stdout.take().unwrap());
+    let mut session = Session {
+      child,
+      output,
+      next_id: 1,
+    };
+    let result = session.request("initialize", Json::object([]));
+    assert!(result.get("capabilities").is_some());
+    session.notify("initialized", Json::object([]));
+    let document = Json::object([
+      ("uri", URI.into()),
+      ("languageId", "sysy".into()),
+      ("version", 1.into()),
+      ("text", text.into()),
+    ]);
+    session.notify(
+      "textDocument/didOpen",
+      Json::object([("textDocument", document)]),
+    );
+    let published = session.receive();
+    let params = published.get("params").unwrap();
+    let diagnostics = params.get("diagnostics").unwrap().clone();
+    (session, diagnostics)
+  }
+
+  fn send(&mut self, message: Json) {
+    let body = message.to_string();
+    let stdin = self.child.stdin.as_mut().unwrap();
+    write!(stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    stdin.flush().unwrap();
+  }
+
+  fn receive(&mut self) -> Json {
+    let mut length = 0;
+    loop {
+      let mut line = String::new();
+      self.output.read_line(&mut line).unwrap();
+      match line.trim_end().split_once(": ") {
+        Some(("Content-Length", value)) => length = value.parse().unwrap(),
+        _ if line.trim_end().is_empty() => break,
+        _ => {}
+      }
+    }
+    let mut body = vec![0; length];
+    self.output.read_exact(&mut body).unwrap();
+    Json::parse(std::str::from_utf8(&body).unwrap()).unwrap()
+  }
+
+  fn notify(&mut self, method: &str, params: Json) {
+    let message = Json::object([
+      ("jsonrpc", "2.0".into()),
+      ("method", method.into()),
+      ("params", params),
+    ]);
+    self.send(message);
+  }
+
+  /// 发出请求，返回响应的 `result`
+  fn request(&mut self, method: &str, params: Json) -> Json {
+    let id = self.next_id;
+    self.next_id += 1;
+    let message = Json::object([
+      ("jsonrpc", "2.0".into()),
+      ("id", id.into()),
+      ("method", method.into()),
+      ("params", params),
+    ]);
+    self.send(message);
+    let response = self.receive();
+    assert_eq!(response.get("id"), Some(&id.into()));
+    response.get("result").unwrap().clone()
+  }
+
+  /// 对 `line` 行 `character` 列的请求
+  fn at(&mut self, method: &str, line: i32, character: i32) -> Json {
+    let position = Json::object([("line", line.into()), ("character", character.into())]);
+    let document = Json::object([("uri", URI.into())]);
+    self.request(
+      method,
+      Json::object([("textDocument", document), ("position", position)]),
+    )
+  }
+
+  /// 正常关闭服务器，返回其退出码
+  fn shutdown(mut self) -> Option<i32> {
+    assert_eq!(self.request("shutdown", Json::Null), Json::Null);
+    self.notify("exit", Json::Null);
+    self.child.wait().unwrap().code()
+  }
+}
+
+fn position(line: i32, character: i32) -> Json {
+  Json::object([("line", line.into()), ("character", character.into())])
+}
+
+fn range(line: i32, start: i32, end: i32) -> Json {
+  Json::object([
+    ("start", position(line, start)),
+    ("end", position(line, end)),
+  ])
+}
+
+#[test]
+fn diagnostics_on_open_and_change() {
+  let (mut session, diagnostics) = Session::open("int main() {\n  return coutn;\n}\n");
+  let diagnostics = diagnostics.as_array().unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  let message = diagnostics[0].get("message").unwrap().as_str().unwrap();
+  assert!(message.contains("coutn"), "{}", message);
+  assert_eq!(diagnostics[0].get("range"), Some(&range(1, 9, 14)));
+
+  // 改正之后诊断被清空
+  let document = Json::object([("uri", URI.into()), ("version", 2.into())]);
+  let change = Json::object([("text", "int main() { return 0; }".into())]);
+  let params = Json::object([
+    ("textDocument", document),
+    ("contentChanges", Json::Array(vec![change])),
+  ]);
+  session.notify("textDocument/didChange", params);
+  let published = session.receive();
+  let diagnostics = published.get("params").unwrap().get("diagnostics").unwrap();
+  assert_eq!(diagnostics, &Json::Array(vec![]));
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+/// `void` 类型的参数由语义分析报告，标出的是出错的参数
+#[test]
+fn void_parameter() {
+  let (session, diagnostics) = Session::open("int f(int a, void b) { return a; }\n");
+  let diagnostics = diagnostics.as_array().unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  let message = diagnostics[0].get("message").unwrap().as_str().unwrap();
+  assert!(message.contains("void"), "{}", message);
+  assert_eq!(diagnostics[0].get("range"), Some(&range(0, 18, 19)));
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn hover_and_definition() {
+  let source =
+    "/* 数组 */ int g[2][3];\nint f(int a[][3]) { return a[0][0]; }\nint main() { return f(g); }\n";
+  let (mut session, diagnostics) = Session::open(source);
+  assert_eq!(diagnostics, Json::Array(vec![]));
+
+  // 注释中的汉字各占一个 UTF-16 编码单元
+  let hover = session.at("textDocument/hover", 2, 22);
+  let value = hover.get("contents").unwrap().get("value").unwrap();
+  assert_eq!(value.as_str(), Some("```sysy\nint g[2][3]\n```\n全局变量"));
+  let definition = session.at("textDocument/definition", 2, 22);
+  assert_eq!(definition.get("range"), Some(&range(0, 13, 14)));
+
+  let hover = session.at("textDocument/hover", 1, 27);
+  let value = hover.get("contents").unwrap().get("value").unwrap();
+  assert_eq!(value.as_str(), Some("```sysy\nint (*a)[3]\n```\n参数"));
+  let definition = session.at("textDocument/definition", 2, 20);
+  assert_eq!(definition.get("range"), Some(&range(1, 4, 5)));
+
+  // 注释中没有名字
+  assert_eq!(session.at("textDocument/hover", 0, 3), Json::Null);
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn references() {
+  let source =
+    "int f(int n);\nint f(int n) { return n; }\nint main() {\n  f(1);\n  return f(2);\n}\n";
+  let (mut session, _) = Session::open(source);
+  let mut references = |declarations: bool| {
+    let document = Json::object([("uri", URI.into())]);
+    let context = Json::object([("includeDeclaration", declarations.into())]);
+    let params = Json::object([
+      ("textDocument", document),
+      ("position", position(3, 2)),
+      ("context", context),
+    ]);
+    let result = session.request("textDocument/references", params);
+    let locations = result.as_array().unwrap().iter();
+    locations
+      .map(|location| location.get("range").unwrap().clone())
+      .collect::<Vec<_>>()
+  };
+  // 函数的声明与定义都是声明处
+  assert_eq!(
+    references(true),
+    [
+      range(0, 4, 5),
+      range(1, 4, 5),
+      range(3, 2, 3),
+      range(4, 9, 10)
+    ]
+  );
+  assert_eq!(references(false), [range(3, 2, 3), range(4, 9, 10)]);
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn document_symbols() {
+  let (mut session, _) =
+    Session::open("const int N = 3;\nint main() {\n  int x = N;\n  return x;\n}\n");
+  let document = Json::object([("uri", URI.into())]);
+  let symbols = session.request(
+    "textDocument/documentSymbol",
+    Json::object([("textDocument", document)]),
+  );
+  let symbols = symbols.as_array().unwrap();
+  let names = symbols
+    .iter()
+    .map(|s| s.get("name").unwrap().as_str().unwrap());
+  assert_eq!(names.collect::<Vec<_>>(), ["N", "main"]);
+  assert_eq!(symbols[0].get("kind"), Some(&14.into()));
+  let children = symbols[1].get("children").unwrap().as_array().unwrap();
+  assert_eq!(children.len(), 1);
+  assert_eq!(children[0].get("detail").unwrap().as_str(), Some("int x"));
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn unknown_method_and_exit_without_shutdown() {
+  let (mut session, _) = Session::open("int main() { return 0; }");
+  session.send(Json::object([
+    ("jsonrpc", "2.0".into()),
+    ("id", 100.into()),
+    ("method", "textDocument/unknown".into()),
+  ]));
+  let error = session.receive();
+  let code = error.get("error").unwrap().get("code").unwrap();
+  assert_eq!(code, &(-32601).into());
+  session.notify("exit", Json::Null);
+  assert_eq!(session.child.wait().unwrap().code(), Some(1));
+}
+
+#[test]
+fn semantic_tokens() {
+  let (mut session, _) =
+    Session::open("const int N = 1;\nint main() {\n  /* a\n b */ return N;\n}\n");
+  let document = Json::object([("uri", URI.into())]);
+  let tokens = session.request(
+    "textDocument/semanticTokens/full",
+    Json::object([("textDocument", document)]),
+  );
+  let data = tokens.get("data").unwrap().as_array().unwrap();
+  let data = data.iter().map(|n| n.as_i64().unwrap());
+  // 各记号为（行差，列差，长度，类别，修饰）；跨行的注释在每行各是一个记号
+  #[rustfmt::skip]
+  let expected = [
+    0, 0, 5, 0, 0, 0, 6, 3, 0, 0, 0, 4, 1, 2, 1, 0, 4, 1, 4, 0,
+    1, 0, 3, 0, 0, 0, 4, 4, 1, 0,
+    1, 2, 4, 6, 0,
+    1, 0, 5, 6, 0, 0, 6, 6, 0, 0, 0, 7, 1, 2, 1,
+  ];
+  assert_eq!(data.collect::<Vec<_>>(), expected);
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn incremental_changes() {
+  let source = "int f() { return 1; }\nint main() {\n  return f();\n}\n";
+  let (mut session, diagnostics) = Session::open(source);
+  assert_eq!(diagnostics, Json::Array(vec![]));
+
+  // 依次应用的两处改动：先改名使调用出错，再在其后插入一个函数
+  let rename = Json::object([("range", range(0, 4, 5)), ("text", "g".into())]);
+  let insert = Json::object([("range", range(4, 0, 0)), ("text", "void h() {}\n".into())]);
+  let document = Json::object([("uri", URI.into()), ("version", 2.into())]);
+  let params = Json::object([
+    ("textDocument", document),
+    ("contentChanges", Json::Array(vec![rename, insert])),
+  ]);
+  session.notify("textDocument/didChange", params);
+  let published = session.receive();
+  let diagnostics = published.get("params").unwrap().get("diagnostics").unwrap();
+  let diagnostics = diagnostics.as_array().unwrap();
+  assert_eq!(diagnostics.len(), 1);
+  let message = diagnostics[0].get("message").unwrap().as_str().unwrap();
+  assert!(message.contains("'f'"), "{}", message);
+
+  // 改动之后的位置：函数 `h` 的名字在第 4 行
+  let definition = session.at("textDocument/definition", 2, 9);
+  assert_eq!(definition, Json::Null);
+  let hover = session.at("textDocument/hover", 4, 5);
+  let value = hover.get("contents").unwrap().get("value").unwrap();
+  assert_eq!(value.as_str(), Some("```sysy\nvoid h()\n```\n函数"));
+  assert_eq!(session.shutdown(), Some(0));
+}
+
+#[test]
+fn extended_syntax() {
+  let source = "int a[2];\nint main() {\n  a[getint()] += 1;\n  for (int i = 0; i < 2; i++) if (i) continue;\n  return a[0] ? 1 : 2;\n}\n";
+  let (mut session, diagnostics) = Session::open(source);
+  assert_eq!(diagnostics, Json::Array(vec![]));
+
+  // 展开时引入的临时变量不在大纲中
+  let document = Json::object([("uri", URI.into())]);
+  let symbols = session.request(
+    "textDocument/documentSymbol",
+    Json::object([("textDocument", document)]),
+  );
+  let children = symbols.as_array().unwrap()[1].get("children").unwrap();
+  let names = children.as_array().unwrap().iter();
+  let names = names.map(|s| s.get("name").unwrap().as_str().unwrap());
+  assert_eq!(names.collect::<Vec<_>>(), ["i"]);
+
+  let definition = session.at("textDocument/definition", 3, 34);
+  assert_eq!(definition.get("range"), Some(&range(3, 11, 12)));
+  assert_eq!(session.at("textDocument/hover", 1, 0), Json::Null);
+  assert_eq!(session.shutdown(), Some(0));
+}
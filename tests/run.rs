@@ -0,0 +1,361 @@
+//! RISC-V 代码的运行测试：程序与同样选项的内置运行时库（[`sysyc::compile_runtime`]）由内置汇编器
+//! 得到目标文件，在 `emulator` 中链接、从 `_start` 开始运行，检查输出与退出码。
+//!
+//! 其他后端以 RISC-V 的运行结果为准：`testcases/` 下的程序输出为 WebAssembly 后以 Node.js 连同
+//! `tests/wasm/sylib.js` 中的运行时库运行，输出与退出码须与 RISC-V 相同。系统中没有 `node` 时跳过。
+//! 解释执行（`sysyc run`）同样与 RISC-V 比较。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::{env, fs, thread};
+
+use sysyc::backend::riscv::{Extensions, Os, Target, Xlen};
+use sysyc::frontend::Sanitizers;
+use sysyc::{interpreter, CompileOptions};
+
+use emulator::Output;
+
+mod elf;
+mod emulator;
+
+/// 以 `options` 编译并运行 `source`，标准输入为 `input`
+fn run(source: &str, options: &CompileOptions, input: &[u8]) -> Output {
+  let program = sysyc::compile_to_object(source, options).unwrap();
+  let runtime = sysyc::compile_runtime(options).unwrap();
+  emulator::run(&[program, runtime], input)
+}
+
+fn manifest_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// `testcases/` 下的程序，按文件名排序；内联汇编不能由内置汇编器汇编，也没有其他后端的翻译
+fn cases() -> Vec<PathBuf> {
+  let dir = manifest_dir().join("testcases");
+  let entries = fs::read_dir(dir).expect("cannot read testcases/");
+  let mut cases: Vec<_> = entries
+    .map(|entry| entry.unwrap().path())
+    .filter(|path| path.extension().is_some_and(|ext| ext == "sy"))
+    .filter(|path| path.file_stem().is_some_and(|stem| stem != "inline_asm"))
+    .collect();
+  cases.sort();
+  cases
+}
+
+/// 评测格式的输出：程序的输出，随后一行是退出码的低 8 位
+fn judge(stdout: &[u8], code: i32) -> String {
+  let mut output = String::from_utf8_lossy(stdout).into_owned();
+  if !output.is_empty() && !output.ends_with('\n') {
+    output.push('\n');
+  }
+  format!("{}{}\n", output, code & 0xff)
+}
+
+/// 以默认选项编译为 RISC-V 并运行，返回评测格式的输出
+fn riscv(source: &str, input: &[u8]) -> String {
+  let output = run(source, &CompileOptions::default(), input);
+  assert!(output.stderr.is_empty());
+  judge(&output.stdout, output.code)
+}
+
+/// 在 `testcases/` 下的每个程序上比较 `backend` 与 RISC-V 的运行结果
+fn compare(backend: impl Fn(&str, &[u8]) -> Result<String, String>) {
+  let mut failures = vec![];
+  for case in cases() {
+    let name = case.file_stem().unwrap().to_string_lossy();
+    let source = fs::read_to_string(&case).unwrap();
+    let input = fs::read(case.with_extension("in")).unwrap_or_default();
+    let expected = riscv(&source, &input);
+    match backend(&source, &input) {
+      Ok(actual) if actual == expected => {}
+      Ok(actual) => failures.push(format!("{}: expected\n{}\ngot\n{}", name, expected, actual)),
+      Err(e) => failures.push(format!("{}: {}", name, e)),
+    }
+  }
+  assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+fn has_node() -> bool {
+  let status = Command::new("node")
+    .arg("--version")
+    .stdout(Stdio::null())
+    .status();
+  if status.is_err() {
+    eprintln!("node not found, skipping");
+  }
+  status.is_ok()
+}
+
+/// 输出 WebAssembly 模块并以 Node.js 运行，返回评测格式的输出
+fn wasm(source: &str, input: &[u8]) -> Result<String, String> {
+  let ir =
+    sysyc::compile_to_koopa(source, &CompileOptions::default()).map_err(|e| e.to_string())?;
+  let module = sysyc::backend::wasm::generate(&ir).map_err(|e| e.to_string())?;
+  let runtime = manifest_dir().join("tests").join("wasm").join("sylib.js");
+  // 模块写入文件，标准输入留给程序
+  let path = env::temp_dir().join(format!(
+    "sysyc-wasm-{}-{:?}.wasm",
+    process::id(),
+    thread::current().id()
+  ));
+  fs::write(&path, module.encode()).unwrap();
+  let output = run_node(&runtime, &path, input);
+  fs::remove_file(&path).unwrap();
+  output
+}
+
+fn run_node(runtime: &Path, module: &Path, input: &[u8]) -> Result<String, String> {
+  let mut child = Command::new("node")
+    .arg(runtime)
+    .arg(module)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .map_err(|e| e.to_string())?;
+  child.stdin.take().unwrap().write_all(input).unwrap();
+  let output = child.wait_with_output().map_err(|e| e.to_string())?;
+  match output.status.code() {
+    Some(code) if output.stderr.is_empty() => Ok(judge(&output.stdout, code)),
+    _ => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
+  }
+}
+
+/// 解释执行 Koopa IR，返回评测格式的输出
+fn interpret(source: &str, input: &[u8]) -> Result<String, String> {
+  let ir =
+    sysyc::compile_to_koopa(source, &CompileOptions::default()).map_err(|e| e.to_string())?;
+  let (stdout, code) = interpreter::run_captured(&ir, input.to_vec()).map_err(|e| e.to_string())?;
+  Ok(judge(&stdout, code))
+}
+
+/// SysY 中的整数字面量；`INT_MIN` 没有字面量，写作表达式
+fn literal(value: i32) -> String {
+  match value {
+    i32::MIN => "-2147483647 - 1".into(),
+    _ => value.to_string(),
+  }
+}
+
+/// 没有 M 扩展（`--march=rv32i`）时乘除法调用软件例程，结果与 M 扩展的指令逐一相同：操作数取遍
+/// 正负的组合（例程取绝对值后按无符号数试商），乘积回绕，`INT_MIN / -1` 回绕为 `INT_MIN`，
+/// 除以零时商为 -1、余数为被除数
+#[test]
+fn soft_muldiv() {
+  const MIN: i32 = i32::MIN;
+  const MAX: i32 = i32::MAX;
+  let cases = [
+    (7, 2),
+    (-7, 2),
+    (7, -2),
+    (-7, -2),
+    (0, 5),
+    (1, -1),
+    (123456789, 1000),
+    (-123456789, 1000),
+    (MAX, 1),
+    (MAX, MAX),
+    (MAX, -2),
+    (MIN, 1),
+    (MIN, 2),
+    (MIN, MAX),
+    (MIN, MIN),
+    (MIN, -1),
+    (46341, 46341),
+    (65536, 65536),
+    (5, 0),
+    (-5, 0),
+    (MIN, 0),
+    (0, 0),
+  ];
+  let array = |values: Vec<i32>| {
+    let literals: Vec<_> = values.into_iter().map(literal).collect();
+    literals.join(", ")
+  };
+  // 操作数放在全局数组中，运算不会在编译时求值；乘以常数时展开为移位加或调用例程
+  let source = format!(
+    "int a[{n}] = {{{a}}};
+int b[{n}] = {{{b}}};
+int print(int x) {{
+  putint(x);
+  putch(32);
+  return 0;
+}}
+int main() {{
+  int i = 0;
+  while (i < {n}) {{
+    print(a[i] * b[i]);
+    print(a[i] / b[i]);
+    print(a[i] % b[i]);
+    print(a[i] * 10);
+    print(a[i] * -1234567);
+    print(a[i] / 4);
+    putch(10);
+    i = i + 1;
+  }}
+  return a[3] / b[3];
+}}
+",
+    n = cases.len(),
+    a = array(cases.iter().map(|&(a, _)| a).collect()),
+    b = array(cases.iter().map(|&(_, b)| b).collect()),
+  );
+  let div = |a: i32, b: i32| if b == 0 { -1 } else { a.wrapping_div(b) };
+  let rem = |a: i32, b: i32| if b == 0 { a } else { a.wrapping_rem(b) };
+  let mut expected = String::new();
+  for (a, b) in cases {
+    let results = [
+      a.wrapping_mul(b),
+      div(a, b),
+      rem(a, b),
+      a.wrapping_mul(10),
+      a.wrapping_mul(-1234567),
+      a / 4,
+    ];
+    for result in results {
+      expected += &format!("{} ", result);
+    }
+    expected += "\n";
+  }
+
+  let rv32i = Extensions {
+    m: false,
+    ..Default::default()
+  };
+  for ext in [rv32i, Extensions::default()] {
+    let options = CompileOptions {
+      target: Target::new(Xlen::Rv32, ext, Os::Linux),
+      ..Default::default()
+    };
+    let asm = sysyc::compile_to_riscv(&source, &options).unwrap();
+    assert_eq!(asm.contains("call __sysy_divmod"), !ext.m, "{}", asm);
+    let output = run(&source, &options, b"");
+    assert_eq!(
+      String::from_utf8(output.stdout).unwrap(),
+      expected,
+      "-march=rv32{}",
+      ext
+    );
+    assert!(output.stderr.is_empty());
+    assert_eq!(output.code, 3);
+  }
+}
+
+#[test]
+fn wasm_testcases() {
+  if has_node() {
+    compare(wasm);
+  }
+}
+
+#[test]
+fn interpreter_testcases() {
+  compare(interpret);
+}
+
+/// 带有运行时检查 `sanitize` 编译 `source`，在 RISC-V 上运行，同时解释执行
+fn sanitized(
+  source: &str,
+  sanitize: Sanitizers,
+  input: &[u8],
+) -> (Output, sysyc::Result<(Vec<u8>, i32)>) {
+  let options = CompileOptions {
+    sanitize,
+    ..Default::default()
+  };
+  let output = run(source, &options, input);
+  let ir = sysyc::compile_to_koopa(source, &options).unwrap();
+  (output, interpreter::run_captured(&ir, input.to_vec()))
+}
+
+/// `-fsanitize=bounds`：越界的下标在访问之前报告出错的行、下标与长度，已写出的输出照常写出，
+/// 以退出码 1 终止（解释执行时是运行时错误）；下标都在范围内时照常运行，不输出到标准错误
+#[test]
+fn sanitize_bounds() {
+  let source = |n: i32| {
+    format!(
+      "int a[4][3];
+int main() {{
+  int i = 0;
+  while (i < {}) {{
+    putint(i);
+    a[i][2 - i % 3] = i;
+    i = i + 1;
+  }}
+  return a[3][2];
+}}
+",
+      n
+    )
+  };
+  let bounds = Sanitizers {
+    bounds: true,
+    ..Default::default()
+  };
+
+  let (output, interpreted) = sanitized(&source(4), bounds, b"");
+  assert_eq!(output.stdout, b"0123");
+  assert!(output.stderr.is_empty());
+  assert_eq!(output.code, 3);
+  assert_eq!(interpreted.unwrap(), (b"0123".to_vec(), 3));
+
+  let (output, interpreted) = sanitized(&source(5), bounds, b"");
+  assert_eq!(output.stdout, b"01234");
+  let stderr = String::from_utf8(output.stderr).unwrap();
+  assert_eq!(
+    stderr,
+    "sysyc: line 6: index 4 out of bounds for length 4\n"
+  );
+  assert_eq!(output.code, 1);
+  let error = interpreted.unwrap_err().to_string();
+  assert!(
+    error.contains("aborted by -fsanitize (exit code 1)"),
+    "{}",
+    error
+  );
+}
+
+/// `-fsanitize=integer`：除数为零与 `-2147483648 / -1` 在运算之前报告出错的行与原因，以退出码 1
+/// 终止（解释执行时是运行时错误）；不出错的除法与取模照常运行，不输出到标准错误
+#[test]
+fn sanitize_integer() {
+  let source = "int main() {
+  int a = getint(), b = getint();
+  putint(a / b);
+  putch(32);
+  putint(a % b);
+  return 0;
+}
+";
+  let integer = Sanitizers {
+    integer: true,
+    ..Default::default()
+  };
+
+  let (output, interpreted) = sanitized(source, integer, b"-7 2");
+  assert_eq!(output.stdout, b"-3 -1");
+  assert!(output.stderr.is_empty());
+  assert_eq!(output.code, 0);
+  assert_eq!(interpreted.unwrap(), (b"-3 -1".to_vec(), 0));
+
+  let reports = [
+    ("7 0", "sysyc: line 3: division by zero\n"),
+    (
+      "-2147483648 -1",
+      "sysyc: line 3: division of -2147483648 by -1 overflows\n",
+    ),
+  ];
+  for (input, report) in reports {
+    let (output, interpreted) = sanitized(source, integer, input.as_bytes());
+    assert!(output.stdout.is_empty());
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), report);
+    assert_eq!(output.code, 1);
+    let error = interpreted.unwrap_err().to_string();
+    assert!(
+      error.contains("aborted by -fsanitize (exit code 1)"),
+      "{}",
+      error
+    );
+  }
+}
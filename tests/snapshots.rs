@@ -0,0 +1,379 @@
+//! IR 生成的快照测试：每个测试编译一小段只含某一种结构的程序，把生成的 Koopa IR 同
+//! `tests/snapshots/` 中检入的结果逐字比较。与 `golden` 的整程序比较相比，改动 IR 生成时
+//! 每处变化都落在对应结构的短小文件中，能直接在审阅时读懂。
+//!
+//! 有意改变 IR 生成时，以 `UPDATE_GOLDEN=1 cargo test --test snapshots` 重新生成这些文件。
+
+use common::manifest_dir;
+use koopa::back::KoopaGenerator;
+use sysyc::CompileOptions;
+
+mod common;
+
+/// 运行时库函数的声明，每个程序都相同，不计入快照
+fn is_runtime_decl(line: &str) -> bool {
+  line.starts_with("decl @")
+}
+
+/// 编译 `source`，与 `tests/snapshots/<name>.koopa` 比较
+fn snapshot(name: &str, source: &str) {
+  snapshot_with(name, source, &CompileOptions::default());
+}
+
+/// 与 [`snapshot`] 相同，但以 `options` 编译
+fn snapshot_with(name: &str, source: &str, options: &CompileOptions) {
+  let ir = sysyc::compile_to_koopa(source, options)
+    .unwrap_or_else(|e| panic!("{}: compile error: {}", name, e));
+  let mut koopa = KoopaGenerator::new(Vec::new());
+  koopa.generate_on(&ir).unwrap();
+  let koopa = String::from_utf8(koopa.writer()).unwrap();
+  let mut actual = String::new();
+  for line in koopa.lines().filter(|line| !is_runtime_decl(line)) {
+    // 去掉运行时库声明之间留下的空行
+    if !(line.is_empty() && (actual.is_empty() || actual.ends_with("\n\n"))) {
+      actual.push_str(line);
+      actual.push('\n');
+    }
+  }
+  let path = manifest_dir()
+    .join("tests")
+    .join("snapshots")
+    .join(format!("{}.koopa", name));
+  if let Err(e) = common::check(&path, &actual) {
+    panic!("{} (rerun with UPDATE_GOLDEN=1 if intended)", e);
+  }
+}
+
+#[test]
+fn short_circuit_value() {
+  snapshot(
+    "short_circuit_value",
+    r"
+int main() {
+  int a = getint(), b = getint();
+  int x = a && b;
+  int y = a || b;
+  return x + y;
+}
+",
+  );
+}
+
+#[test]
+fn short_circuit_condition() {
+  snapshot(
+    "short_circuit_condition",
+    r"
+int main() {
+  int a = getint(), b = getint();
+  if (a > 0 && b > 0 || a == b) {
+    return 1;
+  }
+  return 0;
+}
+",
+  );
+}
+
+#[test]
+fn while_loop() {
+  snapshot(
+    "while_loop",
+    r"
+int main() {
+  int i = 0, sum = 0;
+  while (i < 10) {
+    i = i + 1;
+    if (i == 3) continue;
+    if (i == 8) break;
+    sum = sum + i;
+  }
+  return sum;
+}
+",
+  );
+}
+
+#[test]
+fn nested_loops() {
+  snapshot(
+    "nested_loops",
+    r"
+int main() {
+  int i = 0, n = 0;
+  while (i < 3) {
+    int j = 0;
+    while (j < i) {
+      n = n + 1;
+      j = j + 1;
+    }
+    i = i + 1;
+  }
+  return n;
+}
+",
+  );
+}
+
+#[test]
+fn local_array_init() {
+  snapshot(
+    "local_array_init",
+    r"
+int main() {
+  int x = getint();
+  int a[2][3] = {1, {x}, 4};
+  int b[4] = {};
+  return a[1][0] + b[3];
+}
+",
+  );
+}
+
+#[test]
+fn global_array_init() {
+  snapshot(
+    "global_array_init",
+    r"
+const int N = 3;
+int a[N][2] = {{1}, 2, 3};
+int b[4];
+int main() {
+  return a[1][1] + b[0];
+}
+",
+  );
+}
+
+#[test]
+fn const_decl() {
+  snapshot(
+    "const_decl",
+    r"
+const int a[3] = {1, 2, 3};
+int main() {
+  const int n = a[1] * 4 + 1;
+  int b[n];
+  return n + b[0];
+}
+",
+  );
+}
+
+#[test]
+fn calls() {
+  snapshot(
+    "calls",
+    r"
+int add(int a, int b) {
+  return a + b;
+}
+void fill(int a[], int n) {
+  int i = 0;
+  while (i < n) {
+    a[i] = add(i, 1);
+    i = i + 1;
+  }
+}
+int main() {
+  int a[4];
+  fill(a, 4);
+  putint(add(a[0], a[3]));
+  return 0;
+}
+",
+  );
+}
+
+#[test]
+fn const_calls() {
+  snapshot(
+    "const_calls",
+    r"
+int fib(int n) {
+  int a = 0, b = 1;
+  while (n > 0) {
+    int t = a + b;
+    a = b;
+    b = t;
+    n = n - 1;
+  }
+  return a;
+}
+int depth(int n) {
+  if (n == 0) return 0;
+  return depth(n - 1) + 1;
+}
+const int N = fib(10);
+int a[fib(5)];
+int main() {
+  return N + a[fib(4)] + depth(3);
+}
+",
+  );
+}
+
+#[test]
+fn multidim_array_argument() {
+  snapshot(
+    "multidim_array_argument",
+    r"
+int sum(int a[][2], int n) {
+  return a[n][0] + a[n][1];
+}
+int main() {
+  int m[3][2] = {1, 2, 3, 4, 5, 6};
+  return sum(m, 1) + putarray(2, m[2]);
+}
+",
+  );
+}
+
+#[test]
+fn for_loop() {
+  snapshot(
+    "for_loop",
+    r"
+int main() {
+  int sum = 0;
+  for (int i = 0; i < 10; i = i + 1) sum = sum + i;
+  for (int i = 0; i < 10; i = i + 1) {
+    if (i == 3) continue;
+    sum = sum + i;
+  }
+  return sum;
+}
+",
+  );
+}
+
+#[test]
+fn compound_assign() {
+  snapshot(
+    "compound_assign",
+    r"
+int a[4];
+int main() {
+  int x = 1;
+  x += 2;
+  x *= x;
+  a[getint()] -= x;
+  return x;
+}
+",
+  );
+}
+
+#[test]
+fn increment() {
+  snapshot(
+    "increment",
+    r"
+int main() {
+  int x = 0;
+  x++;
+  --x;
+  int y = x++;
+  return ++y + x;
+}
+",
+  );
+}
+
+#[test]
+fn conditional() {
+  snapshot(
+    "conditional",
+    r"
+int main() {
+  int x = getint(), y;
+  y = x > 0 ? x : -x;
+  putint(x ? y : 0);
+  return x < 0 ? 1 : 2;
+}
+",
+  );
+}
+
+#[test]
+fn noreturn_call() {
+  snapshot(
+    "noreturn_call",
+    r"
+__attribute__((noreturn)) void fail(int code);
+int check(int x) {
+  if (x < 0) {
+    fail(x);
+    x = -x;
+  }
+  return x;
+}
+int main() {
+  fail(check(getint()));
+  return 1;
+}
+",
+  );
+}
+
+#[test]
+fn overloads() {
+  let options = CompileOptions {
+    extensions: true,
+    ..Default::default()
+  };
+  snapshot_with(
+    "overloads",
+    r"
+int abs(int x) {
+  if (x < 0) return -x;
+  return x;
+}
+int abs(int a[], int n) {
+  int s = 0;
+  while (n > 0) {
+    n = n - 1;
+    s = s + abs(a[n]);
+  }
+  return s;
+}
+int dot(int a[], int b[], int n) {
+  if (n == 0) return 0;
+  return a[0] * b[0] + dot(a, b, 0);
+}
+void putint(int a[], int n);
+int main() {
+  int a[3] = {1, -2, 3};
+  putint(a, 3);
+  return abs(-4) + abs(a, 3) + dot(a, a, 1);
+}
+void putint(int a[], int n) {
+  while (n > 0) {
+    n = n - 1;
+    putint(a[n]);
+  }
+}
+",
+    &options,
+  );
+}
+
+#[test]
+fn auto_timing() {
+  let options = CompileOptions {
+    auto_timing: true,
+    ..Default::default()
+  };
+  snapshot_with(
+    "auto_timing",
+    r"
+int main() {
+  int n = getint();
+  if (n < 0) return 1;
+  putint(n);
+  return 0;
+}
+",
+    &options,
+  );
+}
@@ -0,0 +1,76 @@
+// tests/run.rs 以 Node.js 运行 WebAssembly 模块所用的运行时库：`node sylib.js 模块`。
+// 输入输出同 libsysy，标准输出在 main 返回后一并写出，退出码是 main 的返回值的低 8 位。
+
+const fs = require('fs');
+
+const input = fs.readFileSync(0);
+let pos = 0;
+const output = [];
+let memory;
+
+const isSpace = (c) => c === 32 || (c >= 9 && c <= 13);
+const isDigit = (c) => c >= 48 && c <= 57;
+
+function getch() {
+  return pos < input.length ? input[pos++] : -1;
+}
+
+function getint() {
+  while (pos < input.length && isSpace(input[pos])) {
+    pos++;
+  }
+  let sign = 1;
+  if (input[pos] === 45 || input[pos] === 43) {
+    sign = input[pos++] === 45 ? -1 : 1;
+  }
+  let value = 0;
+  while (pos < input.length && isDigit(input[pos])) {
+    value = (value * 10 + input[pos++] - 48) | 0;
+  }
+  return (sign * value) | 0;
+}
+
+function write(s) {
+  for (const c of s) {
+    output.push(c.charCodeAt(0));
+  }
+}
+
+const env = {
+  getint,
+  getch,
+  getarray(p) {
+    const n = getint();
+    const a = new Int32Array(memory.buffer, p, n);
+    for (let i = 0; i < n; i++) {
+      a[i] = getint();
+    }
+    return n;
+  },
+  putint(x) {
+    write(String(x));
+    return 0;
+  },
+  putch(c) {
+    output.push(c & 0xff);
+    return 0;
+  },
+  putarray(n, p) {
+    const a = new Int32Array(memory.buffer, p, n);
+    write(`${n}:`);
+    for (const x of a) {
+      write(` ${x}`);
+    }
+    write('\n');
+    return 0;
+  },
+  starttime: () => 0,
+  stoptime: () => 0,
+};
+
+WebAssembly.instantiate(fs.readFileSync(process.argv[2]), { env }).then(({ instance }) => {
+  memory = instance.exports.memory;
+  const code = instance.exports.main();
+  process.stdout.write(Buffer.from(output));
+  process.exitCode = code & 0xff;
+});